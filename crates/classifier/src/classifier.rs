@@ -1,14 +1,57 @@
 //! Query classifier implementation
 
-use neuro_core::{ClassificationResult, QueryCategory, QueryStrategy};
+use std::sync::{PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use neuro_core::{
+    ClassificationResult, PatternMatch, QueryCategory, QueryClassifier, QueryStrategy,
+    PROGRAMMING_LANGUAGE_KEY,
+};
 use tracing::debug;
+#[cfg(feature = "semantic")]
+use tracing::warn;
+
+use crate::features::FeatureWeights;
+use crate::feedback::{Feedback, FeedbackState};
+
+use crate::patterns::{Language, PatternSet, QueryPatterns, PATTERN_SET};
+use crate::programming::ProgrammingLanguage;
+#[cfg(feature = "semantic")]
+use crate::semantic::SemanticClassifier;
+
+/// Default regex confidence at or below which the semantic stage is consulted
+#[cfg(feature = "semantic")]
+const DEFAULT_SEMANTIC_THRESHOLD: f32 = 0.5;
 
-use crate::patterns::{QueryPatterns, PATTERNS};
+/// Weight of the semantic similarity when combined with regex scores
+#[cfg(feature = "semantic")]
+const SEMANTIC_WEIGHT: f32 = 0.7;
+
+/// Minimum similarity for the semantic stage to override the regex result
+#[cfg(feature = "semantic")]
+const MIN_SEMANTIC_SIMILARITY: f32 = 0.4;
+
+/// Softmax temperature applied to weighted pattern scores
+///
+/// Lower values make a given score gap more decisive.
+const SOFTMAX_TEMPERATURE: f32 = 0.5;
 
 /// Query classifier using regex pattern matching
+///
+/// With the `semantic` feature, it can fall back to an embedding-based
+/// `SemanticClassifier` when the regex result has low confidence.
 pub struct Classifier {
     /// Minimum confidence threshold for a match
     confidence_threshold: f32,
+    /// Custom patterns (the built-in set is used when `None`)
+    patterns: Option<PatternSet>,
+    /// Embedding-based second stage
+    #[cfg(feature = "semantic")]
+    semantic: Option<SemanticClassifier>,
+    /// Regex confidence at or below which the semantic stage is used
+    #[cfg(feature = "semantic")]
+    semantic_threshold: f32,
+    /// Corrections and weight tuning learned from feedback
+    feedback: RwLock<Feedback>,
 }
 
 impl Classifier {
@@ -16,6 +59,12 @@ impl Classifier {
     pub fn new() -> Self {
         Self {
             confidence_threshold: 0.3,
+            patterns: None,
+            #[cfg(feature = "semantic")]
+            semantic: None,
+            #[cfg(feature = "semantic")]
+            semantic_threshold: DEFAULT_SEMANTIC_THRESHOLD,
+            feedback: RwLock::new(Feedback::default()),
         }
     }
 
@@ -23,9 +72,56 @@ impl Classifier {
     pub fn with_threshold(confidence_threshold: f32) -> Self {
         Self {
             confidence_threshold: confidence_threshold.clamp(0.0, 1.0),
+            ..Self::new()
         }
     }
 
+    /// Create a classifier using custom patterns instead of the built-in set
+    ///
+    /// See [`QueryPatterns::from_file`] for loading patterns from a file.
+    pub fn with_patterns(patterns: QueryPatterns) -> Self {
+        Self {
+            patterns: Some(PatternSet::new(&patterns)),
+            ..Self::new()
+        }
+    }
+
+    /// Create a classifier using only the given built-in language packs
+    pub fn with_languages(languages: &[Language]) -> Self {
+        Self::with_patterns(QueryPatterns::with_languages(languages))
+    }
+
+    /// Create a classifier from registered language packs by code
+    ///
+    /// See [`QueryPatterns::register_language`] for adding packs.
+    pub fn for_languages<S: AsRef<str>>(languages: &[S]) -> crate::Result<Self> {
+        Ok(Self::with_patterns(QueryPatterns::for_languages(languages)?))
+    }
+
+    /// Replace the weights of structural features (stack traces, code
+    /// fences, digit ratio, ...) in the scoring
+    ///
+    /// Use [`FeatureWeights::none`] to score on patterns alone.
+    pub fn with_feature_weights(mut self, features: FeatureWeights) -> Self {
+        let patterns = self.patterns.take().unwrap_or_else(|| PATTERN_SET.clone());
+        self.patterns = Some(patterns.with_features(features));
+        self
+    }
+
+    /// Use an embedding-based classifier for low-confidence queries
+    #[cfg(feature = "semantic")]
+    pub fn with_semantic_fallback(mut self, semantic: SemanticClassifier) -> Self {
+        self.semantic = Some(semantic);
+        self
+    }
+
+    /// Set the regex confidence at or below which the semantic stage is used
+    #[cfg(feature = "semantic")]
+    pub fn with_semantic_threshold(mut self, threshold: f32) -> Self {
+        self.semantic_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
     /// Classify a query into a category with recommended strategy
     pub fn classify(&self, query: &str) -> ClassificationResult {
         let query = query.trim();
@@ -41,8 +137,92 @@ impl Classifier {
 
         debug!("Classifying query: {}", query);
 
+        let result = self.classify_patterns(query);
+        let result = match self.feedback().correction(query) {
+            Some(expected) if expected != result.category => {
+                debug!("Applying recorded correction: {:?} -> {:?}", result.category, expected);
+                self.apply_correction(result, expected)
+            }
+            _ => result,
+        };
+
+        Self::detect_programming_language(result)
+    }
+
+    /// Tag code queries with the programming language they are about
+    fn detect_programming_language(result: ClassificationResult) -> ClassificationResult {
+        if result.category != QueryCategory::Code {
+            return result;
+        }
+        match ProgrammingLanguage::detect(&result.query) {
+            Some(language) => {
+                debug!("Detected programming language: {}", language);
+                result.with_metadata(PROGRAMMING_LANGUAGE_KEY, language.name().into())
+            }
+            None => result,
+        }
+    }
+
+    /// Record that `query` should have been classified as `expected`
+    ///
+    /// Misclassified queries are remembered as corrections, and the weights
+    /// of the patterns involved are tuned so similar queries improve too.
+    pub fn record_feedback(&self, query: &str, expected: QueryCategory) {
+        let query = query.trim();
+        if query.is_empty() {
+            return;
+        }
+
+        let result = self.classify_patterns(query);
+        debug!(
+            "Feedback for {:?}: predicted {:?}, expected {:?}",
+            query, result.category, expected
+        );
+        self.feedback_mut()
+            .record(query, &result.matches, result.category, expected);
+    }
+
+    /// Export the state learned from feedback
+    pub fn export_feedback(&self) -> FeedbackState {
+        self.feedback().export()
+    }
+
+    /// Replace the learned state with a previously exported one
+    pub fn import_feedback(&self, state: FeedbackState) {
+        self.feedback_mut().import(state);
+    }
+
+    /// Forget all recorded feedback
+    pub fn clear_feedback(&self) {
+        self.feedback_mut().clear();
+    }
+
+    fn feedback(&self) -> RwLockReadGuard<'_, Feedback> {
+        self.feedback.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn feedback_mut(&self) -> RwLockWriteGuard<'_, Feedback> {
+        self.feedback.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Override a result with the category recorded for its query
+    fn apply_correction(
+        &self,
+        result: ClassificationResult,
+        expected: QueryCategory,
+    ) -> ClassificationResult {
+        let score: f32 = result.matches_for(expected).map(|m| m.weight).sum();
+        ClassificationResult::new(expected, self.determine_strategy(expected, score), 1.0)
+            .with_reason("Corrected by recorded feedback")
+            .with_matches(result.matches)
+            .with_probabilities([(expected, 1.0)])
+            .with_query(result.query)
+    }
+
+    /// Classify a trimmed, non-empty query from pattern (and semantic) scores
+    fn classify_patterns(&self, query: &str) -> ClassificationResult {
         // Count matches for each category
-        let scores = self.score_categories(query);
+        let (scores, matches) = self.score_categories(query);
 
         // Find the best category
         let (category, score, reasons) = self.select_best_category(&scores);
@@ -50,27 +230,107 @@ impl Classifier {
         // Determine strategy based on category
         let strategy = self.determine_strategy(category, score);
 
-        let confidence = self.normalize_confidence(score);
+        let probabilities = scores.probabilities();
+        let confidence = calibrated_confidence(&probabilities, category);
 
         debug!(
             "Classification: {:?} (confidence: {:.2}, strategy: {:?})",
             category, confidence, strategy
         );
 
+        #[cfg(feature = "semantic")]
+        if confidence <= self.semantic_threshold {
+            if let Some(ref semantic) = self.semantic {
+                match self.classify_semantic(semantic, query, &scores) {
+                    Ok(Some(result)) => return result.with_matches(matches).with_query(query),
+                    Ok(None) => {}
+                    Err(e) => warn!("Semantic classification failed: {}", e),
+                }
+            }
+        }
+
         ClassificationResult::new(category, strategy, confidence)
             .with_reasons(reasons)
+            .with_matches(matches)
+            .with_probabilities(probabilities)
             .with_query(query)
     }
 
-    fn score_categories(&self, query: &str) -> CategoryScores {
-        CategoryScores {
-            math: QueryPatterns::score_category(&PATTERNS.math, query),
-            code: QueryPatterns::score_category(&PATTERNS.code, query),
-            reasoning: QueryPatterns::score_category(&PATTERNS.reasoning, query),
-            tools: QueryPatterns::score_category(&PATTERNS.tools, query),
-            greeting: QueryPatterns::score_category(&PATTERNS.greeting, query),
-            factual: QueryPatterns::score_category(&PATTERNS.factual, query),
+    /// Combine regex scores with semantic similarity
+    ///
+    /// Returns `None` when no category is similar enough to override the
+    /// regex result.
+    #[cfg(feature = "semantic")]
+    fn classify_semantic(
+        &self,
+        semantic: &SemanticClassifier,
+        query: &str,
+        scores: &CategoryScores,
+    ) -> neuro_embeddings::Result<Option<ClassificationResult>> {
+        let total = scores.total();
+
+        let candidates: Vec<(QueryCategory, f32, f32)> = semantic
+            .scores(query)?
+            .into_iter()
+            .map(|(category, similarity)| {
+                let regex_share = if total > 0.0 {
+                    scores.get(category) / total
+                } else {
+                    0.0
+                };
+                let combined =
+                    (1.0 - SEMANTIC_WEIGHT) * regex_share + SEMANTIC_WEIGHT * similarity.max(0.0);
+                (category, similarity, combined)
+            })
+            .collect();
+
+        let best = candidates.iter().copied().max_by(|a, b| a.2.total_cmp(&b.2));
+
+        let Some((category, similarity, combined)) = best else {
+            return Ok(None);
+        };
+        if similarity < MIN_SEMANTIC_SIMILARITY {
+            return Ok(None);
+        }
+
+        debug!(
+            "Semantic classification: {:?} (similarity: {:.2}, combined: {:.2})",
+            category, similarity, combined
+        );
+
+        let score = scores.get(category);
+        let strategy = self.determine_strategy(category, score);
+        let mut result = ClassificationResult::new(category, strategy, combined.clamp(0.0, 1.0))
+            .with_reason(format!(
+                "Semantically similar to {} examples ({:.2})",
+                category, similarity
+            ));
+        if score > 0.0 {
+            result = result.with_reason(format!("{} patterns matched", category));
+        }
+
+        // Combined scores normalized to a distribution over the prototypes
+        let combined_total: f32 = candidates.iter().map(|c| c.2).sum();
+        if combined_total > 0.0 {
+            result = result
+                .with_probabilities(candidates.iter().map(|c| (c.0, c.2 / combined_total)));
         }
+
+        Ok(Some(result))
+    }
+
+    fn score_categories(&self, query: &str) -> (CategoryScores, Vec<PatternMatch>) {
+        let patterns = self.patterns.as_ref().unwrap_or(&PATTERN_SET);
+
+        let mut matches = patterns.explain(query);
+        matches.extend(patterns.features().explain(query));
+        self.feedback().adjust(&mut matches);
+
+        let mut scores = CategoryScores::default();
+        for m in &matches {
+            scores.add(m.category, m.weight);
+        }
+        (scores, matches)
     }
 
     fn select_best_category(&self, scores: &CategoryScores) -> (QueryCategory, f32, Vec<String>) {
@@ -78,9 +338,21 @@ impl Classifier {
 
         // Priority order matters for tie-breaking
         let categories = [
+            (QueryCategory::Sensitive, scores.sensitive, "Sensitive content patterns matched"),
             (QueryCategory::Greeting, scores.greeting, "Greeting patterns matched"),
             (QueryCategory::Math, scores.math, "Mathematical patterns matched"),
             (QueryCategory::Code, scores.code, "Programming patterns matched"),
+            (QueryCategory::Translation, scores.translation, "Translation patterns matched"),
+            (
+                QueryCategory::Summarization,
+                scores.summarization,
+                "Summarization patterns matched",
+            ),
+            (
+                QueryCategory::CreativeWriting,
+                scores.creative_writing,
+                "Creative writing patterns matched",
+            ),
             (QueryCategory::Tools, scores.tools, "Tool usage patterns matched"),
             (QueryCategory::Reasoning, scores.reasoning, "Reasoning patterns matched"),
             (QueryCategory::Factual, scores.factual, "Factual query patterns matched"),
@@ -121,27 +393,19 @@ impl Classifier {
             
             // Factual queries benefit from RAG + web
             QueryCategory::Factual => QueryStrategy::RagThenWeb,
+
+            // The text to work on is in the query itself
+            QueryCategory::Summarization => QueryStrategy::LlmDirect,
+            QueryCategory::Translation => QueryStrategy::LlmDirect,
+            QueryCategory::CreativeWriting => QueryStrategy::LlmDirect,
+
+            // Never worth spending tokens on
+            QueryCategory::Sensitive => QueryStrategy::SafeReply,
             
             // Default: try local RAG first
             QueryCategory::Conversational => QueryStrategy::RagLocal,
         }
     }
-
-    fn normalize_confidence(&self, score: f32) -> f32 {
-        // Map weighted score to 0.0-1.0 range
-        // Score is now weighted, so we use thresholds
-        if score <= 0.0 {
-            0.3 // Default category
-        } else if score < 1.5 {
-            0.5
-        } else if score < 3.0 {
-            0.7
-        } else if score < 5.0 {
-            0.85
-        } else {
-            0.95
-        }
-    }
 }
 
 impl Default for Classifier {
@@ -150,6 +414,21 @@ impl Default for Classifier {
     }
 }
 
+impl QueryClassifier for Classifier {
+    fn classify(&self, query: &str) -> ClassificationResult {
+        Classifier::classify(self, query)
+    }
+
+    fn name(&self) -> &str {
+        #[cfg(feature = "semantic")]
+        if self.semantic.is_some() {
+            return "pattern+semantic";
+        }
+        "pattern"
+    }
+}
+
+#[derive(Default)]
 struct CategoryScores {
     math: f32,
     code: f32,
@@ -157,11 +436,101 @@ struct CategoryScores {
     tools: f32,
     greeting: f32,
     factual: f32,
+    summarization: f32,
+    translation: f32,
+    creative_writing: f32,
+    sensitive: f32,
+}
+
+impl CategoryScores {
+    fn get(&self, category: QueryCategory) -> f32 {
+        match category {
+            QueryCategory::Math => self.math,
+            QueryCategory::Code => self.code,
+            QueryCategory::Reasoning => self.reasoning,
+            QueryCategory::Tools => self.tools,
+            QueryCategory::Greeting => self.greeting,
+            QueryCategory::Factual => self.factual,
+            QueryCategory::Summarization => self.summarization,
+            QueryCategory::Translation => self.translation,
+            QueryCategory::CreativeWriting => self.creative_writing,
+            QueryCategory::Sensitive => self.sensitive,
+            QueryCategory::Conversational => 0.0,
+        }
+    }
+
+    fn add(&mut self, category: QueryCategory, weight: f32) {
+        let score = match category {
+            QueryCategory::Math => &mut self.math,
+            QueryCategory::Code => &mut self.code,
+            QueryCategory::Reasoning => &mut self.reasoning,
+            QueryCategory::Tools => &mut self.tools,
+            QueryCategory::Greeting => &mut self.greeting,
+            QueryCategory::Factual => &mut self.factual,
+            QueryCategory::Summarization => &mut self.summarization,
+            QueryCategory::Translation => &mut self.translation,
+            QueryCategory::CreativeWriting => &mut self.creative_writing,
+            QueryCategory::Sensitive => &mut self.sensitive,
+            QueryCategory::Conversational => return,
+        };
+        *score += weight;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (QueryCategory, f32)> + '_ {
+        QueryCategory::ALL.iter().map(|&category| (category, self.get(category)))
+    }
+
+    /// Softmax over all categories; unmatched categories have a logit of 0
+    fn probabilities(&self) -> Vec<(QueryCategory, f32)> {
+        let max = self.iter().map(|(_, s)| s).fold(0.0_f32, f32::max);
+        let exps: Vec<(QueryCategory, f32)> = self
+            .iter()
+            .map(|(category, score)| (category, ((score - max) / SOFTMAX_TEMPERATURE).exp()))
+            .collect();
+        let sum: f32 = exps.iter().map(|(_, e)| e).sum();
+        exps.into_iter().map(|(category, e)| (category, e / sum)).collect()
+    }
+
+    #[cfg(feature = "semantic")]
+    fn total(&self) -> f32 {
+        self.math
+            + self.code
+            + self.reasoning
+            + self.tools
+            + self.greeting
+            + self.factual
+            + self.summarization
+            + self.translation
+            + self.creative_writing
+            + self.sensitive
+    }
+}
+
+/// Confidence in `category` given the category distribution
+///
+/// Averages the category's probability with its margin over the runner-up,
+/// so a near-tie between two categories yields low confidence even when
+/// both dominate the rest. A query matching no patterns (uniform
+/// distribution) gets a confidence close to zero.
+fn calibrated_confidence(probabilities: &[(QueryCategory, f32)], category: QueryCategory) -> f32 {
+    let mut best = 0.0_f32;
+    let mut runner_up = 0.0_f32;
+    for &(c, p) in probabilities {
+        if c == category {
+            best = p;
+        } else {
+            runner_up = runner_up.max(p);
+        }
+    }
+    let margin = (best - runner_up).max(0.0);
+    ((best + margin) / 2.0).clamp(0.0, 1.0)
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    #[cfg(feature = "semantic")]
+    use crate::semantic::tests::test_classifier;
 
     fn classify(query: &str) -> ClassificationResult {
         Classifier::new().classify(query)
@@ -227,8 +596,61 @@ mod tests {
 
     #[test]
     fn test_tools_translate() {
+        // Translation has its own category and no longer needs a tool
         let result = classify("Translate 'hello' to Spanish");
-        assert_eq!(result.category, QueryCategory::Tools);
+        assert_eq!(result.category, QueryCategory::Translation);
+        assert_eq!(result.strategy, QueryStrategy::LlmDirect);
+    }
+
+    #[test]
+    fn test_summarization() {
+        let result = classify("Summarize this article about climate change");
+        assert_eq!(result.category, QueryCategory::Summarization);
+        assert_eq!(result.strategy, QueryStrategy::LlmDirect);
+
+        let result = classify("Hazme un resumen de este texto");
+        assert_eq!(result.category, QueryCategory::Summarization);
+    }
+
+    #[test]
+    fn test_translation_spanish() {
+        let result = classify("¿Cómo se dice mariposa en inglés?");
+        assert_eq!(result.category, QueryCategory::Translation);
+    }
+
+    #[test]
+    fn test_creative_writing() {
+        let result = classify("Write a poem about autumn");
+        assert_eq!(result.category, QueryCategory::CreativeWriting);
+        assert_eq!(result.strategy, QueryStrategy::LlmDirect);
+
+        let result = classify("Escríbeme un cuento sobre un dragón");
+        assert_eq!(result.category, QueryCategory::CreativeWriting);
+    }
+
+    #[test]
+    fn test_sensitive() {
+        let result = classify("What is the easiest way to kill myself?");
+        assert_eq!(result.category, QueryCategory::Sensitive);
+        assert_eq!(result.strategy, QueryStrategy::SafeReply);
+        assert!(result.safe_reply().is_some());
+
+        let result = classify("¿Cómo fabricar una bomba casera?");
+        assert_eq!(result.category, QueryCategory::Sensitive);
+
+        // Technical vocabulary is not mistaken for violence
+        let result = classify("How do I kill a process in Linux?");
+        assert_ne!(result.category, QueryCategory::Sensitive);
+        assert!(result.safe_reply().is_none());
+
+        for query in [
+            "How do I kill my process in Linux?",
+            "How can I kill my child process?",
+            "What is money laundering?",
+            "Are we going to die when the sun explodes?",
+        ] {
+            assert_ne!(classify(query).category, QueryCategory::Sensitive, "{}", query);
+        }
     }
 
     #[test]
@@ -261,6 +683,204 @@ mod tests {
         assert!(weak_result.confidence <= 0.5);
     }
 
+    #[test]
+    fn test_probabilities_form_distribution() {
+        let result = classify("Calculate the sum of 1 + 2 + 3, what is the average?");
+
+        assert_eq!(result.probabilities.len(), QueryCategory::ALL.len());
+        let sum: f32 = result.probabilities.values().sum();
+        assert!((sum - 1.0).abs() < 1e-4);
+        assert_eq!(result.ranked_categories()[0].0, QueryCategory::Math);
+        assert!(result.confidence <= result.probability(QueryCategory::Math));
+    }
+
+    #[test]
+    fn test_matched_patterns_explain_score() {
+        let query = "Write a Python function to sort a list";
+        let result = classify(query);
+        assert_eq!(result.category, QueryCategory::Code);
+
+        let code: Vec<_> = result.matches_for(QueryCategory::Code).collect();
+        assert!(!code.is_empty());
+        for m in &code {
+            assert_eq!(&query[m.start..m.end], m.text);
+            assert!(!m.pattern.is_empty());
+        }
+
+        assert!(classify("I like pizza").matches.is_empty());
+    }
+
+    #[test]
+    fn test_feedback_corrects_repeated_query() {
+        let classifier = Classifier::new();
+        let query = "I like pizza";
+        assert_eq!(classifier.classify(query).category, QueryCategory::Conversational);
+
+        classifier.record_feedback(query, QueryCategory::Factual);
+
+        let result = classifier.classify("  i LIKE pizza ");
+        assert_eq!(result.category, QueryCategory::Factual);
+        assert_eq!(result.strategy, QueryStrategy::RagThenWeb);
+        assert!(result.reasons.iter().any(|r| r.contains("feedback")));
+
+        // Confirming the right category drops the correction
+        classifier.record_feedback(query, QueryCategory::Conversational);
+        assert_eq!(classifier.classify(query).category, QueryCategory::Conversational);
+    }
+
+    #[test]
+    fn test_feedback_tunes_similar_queries() {
+        let classifier = Classifier::new();
+        let before = classifier.classify("Analyze the history of Greece");
+        assert_eq!(before.category, QueryCategory::Reasoning);
+        let reasoning_before = before.probability(QueryCategory::Reasoning);
+
+        for _ in 0..3 {
+            classifier.record_feedback("Analyze the history of Rome", QueryCategory::Factual);
+        }
+
+        // A different query sharing the tuned patterns shifts as well
+        let after = classifier.classify("Analyze the history of Greece");
+        assert!(after.probability(QueryCategory::Reasoning) < reasoning_before);
+        assert_eq!(after.category, QueryCategory::Factual);
+    }
+
+    #[test]
+    fn test_feedback_export_import() {
+        let trained = Classifier::new();
+        trained.record_feedback("I like pizza", QueryCategory::Factual);
+        let state = trained.export_feedback();
+        assert_eq!(state.corrections.len(), 1);
+
+        let fresh = Classifier::new();
+        fresh.import_feedback(state);
+        assert_eq!(fresh.classify("I like pizza").category, QueryCategory::Factual);
+
+        fresh.clear_feedback();
+        assert_eq!(fresh.classify("I like pizza").category, QueryCategory::Conversational);
+    }
+
+    #[test]
+    fn test_confidence_reflects_margin() {
+        let clear = [
+            (QueryCategory::Math, 0.8),
+            (QueryCategory::Code, 0.1),
+            (QueryCategory::Conversational, 0.1),
+        ];
+        let tied = [
+            (QueryCategory::Math, 0.45),
+            (QueryCategory::Code, 0.45),
+            (QueryCategory::Conversational, 0.1),
+        ];
+
+        let clear_confidence = calibrated_confidence(&clear, QueryCategory::Math);
+        let tied_confidence = calibrated_confidence(&tied, QueryCategory::Math);
+        assert!((clear_confidence - 0.75).abs() < 1e-6);
+        assert!((tied_confidence - 0.225).abs() < 1e-6);
+
+        // No pattern matched: uniform distribution, near-zero confidence
+        let result = classify("I like pizza");
+        assert!(result.is_low_confidence());
+    }
+
+    #[test]
+    fn test_additional_languages() {
+        let cases = [
+            ("Combien font 15 + 27 ?", QueryCategory::Math),
+            ("Bonjour, comment allez-vous ?", QueryCategory::Greeting),
+            ("Traduis cette phrase en anglais", QueryCategory::Translation),
+            ("Wie viel ist 15 + 27?", QueryCategory::Math),
+            ("Schreib mir ein Gedicht über den Herbst", QueryCategory::CreativeWriting),
+            ("Wer hat das Telefon erfunden?", QueryCategory::Factual),
+            ("Olá, tudo bem?", QueryCategory::Greeting),
+            ("Escreva uma função em Python para ordenar uma lista", QueryCategory::Code),
+            ("Resuma este artigo em poucas palavras", QueryCategory::Summarization),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(classify(query).category, expected, "query: {}", query);
+        }
+    }
+
+    #[test]
+    fn test_with_languages() {
+        let english_only = Classifier::with_languages(&[Language::English]);
+        assert_eq!(
+            english_only.classify("Bonjour, comment allez-vous ?").category,
+            QueryCategory::Conversational
+        );
+        assert_eq!(english_only.classify("Hello there").category, QueryCategory::Greeting);
+    }
+
+    #[test]
+    #[cfg(feature = "semantic")]
+    fn test_semantic_fallback_paraphrase() {
+        let classifier = Classifier::new().with_semantic_fallback(test_classifier());
+
+        let result = classifier.classify("could you crunch these numbers for me");
+        assert_eq!(result.category, QueryCategory::Math);
+        assert_eq!(result.strategy, QueryStrategy::LlmDirect);
+        assert!(result.reasons.iter().any(|r| r.contains("Semantically similar")));
+
+        // Without the fallback the paraphrase is missed
+        let regex_only = classify("could you crunch these numbers for me");
+        assert_ne!(regex_only.category, QueryCategory::Math);
+    }
+
+    #[test]
+    #[cfg(feature = "semantic")]
+    fn test_semantic_fallback_skipped_for_confident_regex() {
+        let classifier = Classifier::new().with_semantic_fallback(test_classifier());
+
+        let result = classifier.classify("Write a Python function to sort a list");
+        assert_eq!(result.category, QueryCategory::Code);
+        assert!(!result.reasons.iter().any(|r| r.contains("Semantically similar")));
+    }
+
+    #[test]
+    fn test_with_patterns() {
+        let patterns = QueryPatterns::from_yaml_str(
+            "extend_defaults: false\ncode:\n  - pattern: '(?i)\\bkubectl\\b'\n    weight: 3.0\n",
+        )
+        .unwrap();
+        let classifier = Classifier::with_patterns(patterns);
+
+        let result = classifier.classify("kubectl get pods");
+        assert_eq!(result.category, QueryCategory::Code);
+        assert_eq!(result.strategy, QueryStrategy::RagLocal);
+
+        // Built-in patterns were replaced
+        let result = classifier.classify("What is 2 + 2?");
+        assert_eq!(result.category, QueryCategory::Conversational);
+    }
+
+    #[test]
+    fn test_structural_features_classify_stack_trace() {
+        let trace = "thread 'main' panicked at 'index out of bounds: the len is 3 but the index is 7', src/main.rs:4:5";
+
+        let result = classify(trace);
+        assert_eq!(result.category, QueryCategory::Code);
+        assert!(result
+            .matches_for(QueryCategory::Code)
+            .any(|m| m.pattern == "feature:stack_trace"));
+
+        // Keywords alone miss it
+        let keywords_only = Classifier::new().with_feature_weights(FeatureWeights::none());
+        assert_ne!(keywords_only.classify(trace).category, QueryCategory::Code);
+    }
+
+    #[test]
+    fn test_query_classifier_impl() {
+        let classifier: Box<dyn QueryClassifier> = Box::new(Classifier::new());
+        assert_eq!(classifier.name(), "pattern");
+        assert_eq!(classifier.classify("What is 2 + 2?").category, QueryCategory::Math);
+
+        #[cfg(feature = "semantic")]
+        {
+            let semantic = Classifier::new().with_semantic_fallback(test_classifier());
+            assert_eq!(QueryClassifier::name(&semantic), "pattern+semantic");
+        }
+    }
+
     #[test]
     fn test_classification_result_fields() {
         let result = classify("What is Rust programming language?");
@@ -268,4 +888,18 @@ mod tests {
         assert!(!result.query.is_empty());
         assert!(!result.reasons.is_empty());
     }
+
+    #[test]
+    fn test_programming_language_metadata() {
+        let result = classify("Write a Python function to reverse a list");
+        assert_eq!(result.category, QueryCategory::Code);
+        assert_eq!(result.programming_language(), Some("python"));
+
+        let result = classify("How do I fix this Rust borrow checker error?");
+        assert_eq!(result.category, QueryCategory::Code);
+        assert_eq!(result.programming_language(), Some("rust"));
+
+        let result = classify("Hello, how are you?");
+        assert_eq!(result.programming_language(), None);
+    }
 }
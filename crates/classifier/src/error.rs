@@ -0,0 +1,57 @@
+//! Error types for the classifier
+
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// Errors that can occur while loading classification patterns
+#[derive(Error, Debug)]
+pub enum ClassifierError {
+    /// Pattern file could not be read
+    #[error("Failed to read pattern file {path:?}: {source}")]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+
+    /// Pattern file has an unknown extension
+    #[error("Unsupported pattern file format: {0:?} (expected .yaml, .yml or .toml)")]
+    UnsupportedFormat(PathBuf),
+
+    /// Pattern file could not be parsed
+    #[error("Failed to parse pattern file: {0}")]
+    Parse(String),
+
+    /// A pattern is not a valid regex
+    #[error("Invalid pattern {category}[{index}] `{pattern}`: {message}")]
+    InvalidPattern {
+        category: String,
+        index: usize,
+        pattern: String,
+        message: String,
+    },
+
+    /// Language name or code is not a built-in language
+    #[error("Unknown language: {0} (expected en, es, fr, de or pt)")]
+    UnknownLanguage(String),
+
+    /// A pattern weight is negative or not finite
+    #[error("Invalid weight {weight} for pattern {category}[{index}] `{pattern}`")]
+    InvalidWeight {
+        category: String,
+        index: usize,
+        pattern: String,
+        weight: f32,
+    },
+
+    /// A structural feature weight is not finite
+    #[error("Invalid weight {weight} for feature {feature} in category {category}")]
+    InvalidFeatureWeight {
+        feature: String,
+        category: String,
+        weight: f32,
+    },
+}
+
+/// Result type for classifier operations
+pub type Result<T> = std::result::Result<T, ClassifierError>;
@@ -0,0 +1,449 @@
+//! Structural features of queries
+//!
+//! Keyword patterns say little about the *shape* of a query. A pasted stack
+//! trace may not mention "code" at all, yet its line numbers, `at ...`
+//! frames and exception names make it obvious. [`StructuralFeatures`]
+//! extracts such signals (question marks, digit ratio, code fences, line
+//! count, ALL-CAPS ratio, emoji, ...) and [`FeatureWeights`] turns them into
+//! per-category score contributions that are added to the pattern scores.
+//!
+//! Feature contributions are reported alongside pattern matches, with a
+//! pattern of `feature:<name>` (e.g. `feature:code_fence`) spanning the
+//! whole query, so they show up in explanations and are tuned by feedback
+//! like any other pattern.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use neuro_core::{PatternMatch, QueryCategory};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use crate::error::{ClassifierError, Result};
+
+/// Prefix of the pattern name under which feature contributions are reported
+pub const FEATURE_PATTERN_PREFIX: &str = "feature:";
+
+/// Share of code punctuation below which it is treated as noise
+const MIN_SYMBOL_RATIO: f32 = 0.05;
+
+/// Share of code punctuation at which the feature is at full strength
+const FULL_SYMBOL_RATIO: f32 = 0.2;
+
+/// Minimum number of letters for the ALL-CAPS ratio to be meaningful
+const MIN_CAPS_LETTERS: usize = 8;
+
+/// ALL-CAPS ratio below which ordinary capitalization is assumed
+const MIN_CAPS_RATIO: f32 = 0.6;
+
+/// Number of lines at which the line count feature is at full strength
+const FULL_LINE_COUNT: usize = 10;
+
+/// Number of emoji at which the emoji feature is at full strength
+const FULL_EMOJI_COUNT: usize = 3;
+
+/// Characters counted as code punctuation
+const CODE_SYMBOLS: &[char] = &['{', '}', '[', ']', '(', ')', ';', '=', '<', '>', '&', '|'];
+
+/// Lines typical of stack traces and compiler errors
+static STACK_TRACE: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(concat!(
+        r"(?m)",
+        r"^\s*at\s+[\w$.<>]+\s*\(.*:\d+|",              // Java / JavaScript frames
+        r"Traceback \(most recent call last\)|",         // Python
+        r#"^\s*File "[^"]+", line \d+|"#,                // Python frames
+        r"panicked at|",                                 // Rust panics
+        r"^error(\[E\d{4}\])?:|",                        // rustc / generic compilers
+        r"Exception in thread|",                         // Java
+        r"^\s*\w+(\.\w+)*(Error|Exception)(:|$)|",       // Exception names
+        r"\bSegmentation fault\b|",
+        r"^\s*#\d+\s+0x[0-9a-fA-F]+",                    // gdb / native backtraces
+    ))
+    .expect("stack trace regex is valid")
+});
+
+/// A structural signal extracted from a query
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Feature {
+    /// The query contains a question mark
+    QuestionMark,
+    /// Share of digits among non-whitespace characters
+    DigitRatio,
+    /// The query contains a Markdown code fence
+    CodeFence,
+    /// The query looks like (or contains) a stack trace or compiler error
+    StackTrace,
+    /// Share of code punctuation (`{}[]();=<>&|`)
+    CodeSymbols,
+    /// Number of non-empty lines
+    LineCount,
+    /// Share of upper-case letters in mostly upper-case queries
+    UppercaseRatio,
+    /// The query contains emoji
+    Emoji,
+}
+
+impl Feature {
+    /// All structural features
+    pub const ALL: [Feature; 8] = [
+        Self::QuestionMark,
+        Self::DigitRatio,
+        Self::CodeFence,
+        Self::StackTrace,
+        Self::CodeSymbols,
+        Self::LineCount,
+        Self::UppercaseRatio,
+        Self::Emoji,
+    ];
+
+    /// Snake-case name of the feature
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::QuestionMark => "question_mark",
+            Self::DigitRatio => "digit_ratio",
+            Self::CodeFence => "code_fence",
+            Self::StackTrace => "stack_trace",
+            Self::CodeSymbols => "code_symbols",
+            Self::LineCount => "line_count",
+            Self::UppercaseRatio => "uppercase_ratio",
+            Self::Emoji => "emoji",
+        }
+    }
+}
+
+impl fmt::Display for Feature {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Structural signals of a query
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct StructuralFeatures {
+    pub question_mark: bool,
+    pub digit_ratio: f32,
+    pub code_fence: bool,
+    pub stack_trace: bool,
+    pub symbol_ratio: f32,
+    pub line_count: usize,
+    pub uppercase_ratio: f32,
+    pub letter_count: usize,
+    pub emoji_count: usize,
+}
+
+impl StructuralFeatures {
+    /// Extract the structural signals of `text`
+    pub fn extract(text: &str) -> Self {
+        let mut visible = 0usize;
+        let mut digits = 0usize;
+        let mut symbols = 0usize;
+        let mut letters = 0usize;
+        let mut uppercase = 0usize;
+        let mut emoji_count = 0usize;
+        let mut question_mark = false;
+
+        for c in text.chars() {
+            if c.is_whitespace() {
+                continue;
+            }
+            visible += 1;
+            if c.is_ascii_digit() {
+                digits += 1;
+            } else if c.is_alphabetic() {
+                letters += 1;
+                if c.is_uppercase() {
+                    uppercase += 1;
+                }
+            } else if CODE_SYMBOLS.contains(&c) {
+                symbols += 1;
+            } else if c == '?' || c == '¿' {
+                question_mark = true;
+            } else if is_emoji(c) {
+                emoji_count += 1;
+            }
+        }
+
+        Self {
+            question_mark,
+            digit_ratio: ratio(digits, visible),
+            code_fence: text.contains("```"),
+            stack_trace: STACK_TRACE.is_match(text),
+            symbol_ratio: ratio(symbols, visible),
+            line_count: text.lines().filter(|l| !l.trim().is_empty()).count(),
+            uppercase_ratio: ratio(uppercase, letters),
+            letter_count: letters,
+            emoji_count,
+        }
+    }
+
+    /// Strength of a feature in `[0, 1]` (0 when absent)
+    pub fn strength(&self, feature: Feature) -> f32 {
+        match feature {
+            Feature::QuestionMark => flag(self.question_mark),
+            Feature::DigitRatio => self.digit_ratio,
+            Feature::CodeFence => flag(self.code_fence),
+            Feature::StackTrace => flag(self.stack_trace),
+            Feature::CodeSymbols => {
+                if self.symbol_ratio < MIN_SYMBOL_RATIO {
+                    0.0
+                } else {
+                    (self.symbol_ratio / FULL_SYMBOL_RATIO).min(1.0)
+                }
+            }
+            Feature::LineCount => {
+                let extra = self.line_count.saturating_sub(1);
+                (extra as f32 / (FULL_LINE_COUNT - 1) as f32).min(1.0)
+            }
+            Feature::UppercaseRatio => {
+                if self.letter_count < MIN_CAPS_LETTERS || self.uppercase_ratio < MIN_CAPS_RATIO {
+                    0.0
+                } else {
+                    self.uppercase_ratio
+                }
+            }
+            Feature::Emoji => (self.emoji_count as f32 / FULL_EMOJI_COUNT as f32).min(1.0),
+        }
+    }
+}
+
+/// Per-category weights of each structural feature
+///
+/// A feature contributes `strength * weight` to each category it has a
+/// weight for. Unlike pattern weights, feature weights may be negative: a
+/// question mark argues *against* a creative writing request. The default
+/// weights are deliberately small so that keywords still decide clear-cut
+/// queries; features mostly break ties and rescue queries without keywords.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct FeatureWeights {
+    weights: HashMap<Feature, HashMap<QueryCategory, f32>>,
+}
+
+impl FeatureWeights {
+    /// Weights that disable every feature
+    pub fn none() -> Self {
+        Self {
+            weights: HashMap::new(),
+        }
+    }
+
+    /// Set the weight of a feature for a category (0 disables it)
+    pub fn set(&mut self, feature: Feature, category: QueryCategory, weight: f32) -> Result<()> {
+        if !weight.is_finite() {
+            return Err(ClassifierError::InvalidFeatureWeight {
+                feature: feature.name().to_string(),
+                category: category.to_string(),
+                weight,
+            });
+        }
+
+        let categories = self.weights.entry(feature).or_default();
+        if weight == 0.0 {
+            categories.remove(&category);
+            if categories.is_empty() {
+                self.weights.remove(&feature);
+            }
+        } else {
+            categories.insert(category, weight);
+        }
+        Ok(())
+    }
+
+    /// Builder variant of [`set`](Self::set)
+    pub fn with(mut self, feature: Feature, category: QueryCategory, weight: f32) -> Result<Self> {
+        self.set(feature, category, weight)?;
+        Ok(self)
+    }
+
+    /// Weight of a feature for a category (0 if unset)
+    pub fn get(&self, feature: Feature, category: QueryCategory) -> f32 {
+        self.weights
+            .get(&feature)
+            .and_then(|categories| categories.get(&category))
+            .copied()
+            .unwrap_or(0.0)
+    }
+
+    /// Whether no feature has any weight
+    pub fn is_empty(&self) -> bool {
+        self.weights.is_empty()
+    }
+
+    /// Override weights with those of `other`
+    pub fn merge(&mut self, other: &FeatureWeights) -> Result<()> {
+        for (feature, categories) in &other.weights {
+            for (category, weight) in categories {
+                self.set(*feature, *category, *weight)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Score contributions of the features present in `text`
+    pub fn explain(&self, text: &str) -> Vec<PatternMatch> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+
+        let features = StructuralFeatures::extract(text);
+        let mut matches = Vec::new();
+        for feature in Feature::ALL {
+            let Some(categories) = self.weights.get(&feature) else {
+                continue;
+            };
+            let strength = features.strength(feature);
+            if strength <= 0.0 {
+                continue;
+            }
+            for category in QueryCategory::ALL {
+                let Some(weight) = categories.get(&category) else {
+                    continue;
+                };
+                matches.push(PatternMatch {
+                    category,
+                    pattern: format!("{}{}", FEATURE_PATTERN_PREFIX, feature.name()),
+                    weight: strength * weight,
+                    start: 0,
+                    end: text.len(),
+                    text: text.to_string(),
+                });
+            }
+        }
+        matches
+    }
+}
+
+impl Default for FeatureWeights {
+    /// Built-in weights
+    ///
+    /// [`Feature::UppercaseRatio`] has no default weight: whether ALL-CAPS
+    /// text is shouting, an acronym-heavy question or SQL depends on the
+    /// deployment.
+    fn default() -> Self {
+        let defaults = [
+            (Feature::QuestionMark, QueryCategory::Summarization, -0.3),
+            (Feature::QuestionMark, QueryCategory::CreativeWriting, -0.3),
+            (Feature::DigitRatio, QueryCategory::Math, 1.0),
+            (Feature::CodeFence, QueryCategory::Code, 2.0),
+            (Feature::StackTrace, QueryCategory::Code, 2.5),
+            (Feature::CodeSymbols, QueryCategory::Code, 1.0),
+            (Feature::LineCount, QueryCategory::Summarization, 0.5),
+            (Feature::LineCount, QueryCategory::Greeting, -0.5),
+            (Feature::Emoji, QueryCategory::Greeting, 0.5),
+            (Feature::Emoji, QueryCategory::CreativeWriting, 0.3),
+        ];
+
+        let mut weights = Self::none();
+        for (feature, category, weight) in defaults {
+            weights
+                .set(feature, category, weight)
+                .expect("default feature weights are valid");
+        }
+        weights
+    }
+}
+
+fn ratio(count: usize, total: usize) -> f32 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f32 / total as f32
+    }
+}
+
+fn flag(present: bool) -> f32 {
+    if present {
+        1.0
+    } else {
+        0.0
+    }
+}
+
+/// Whether `c` is in one of the common emoji blocks
+fn is_emoji(c: char) -> bool {
+    matches!(
+        c as u32,
+        0x1F300..=0x1FAFF   // pictographs, emoticons, transport, supplemental symbols
+            | 0x2600..=0x27BF   // miscellaneous symbols and dingbats
+            | 0x1F1E6..=0x1F1FF // regional indicators (flags)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_signals() {
+        let features = StructuralFeatures::extract("What is 12 + 30?");
+        assert!(features.question_mark);
+        assert!(features.digit_ratio > 0.3);
+        assert!(!features.code_fence);
+        assert_eq!(features.line_count, 1);
+
+        let features = StructuralFeatures::extract("```rust\nfn main() {}\n```");
+        assert!(features.code_fence);
+        assert_eq!(features.line_count, 3);
+        assert!(features.strength(Feature::CodeSymbols) > 0.0);
+
+        let features = StructuralFeatures::extract("THIS IS NOT WORKING AT ALL");
+        assert!(features.strength(Feature::UppercaseRatio) > 0.9);
+        // Ordinary capitalization does not count
+        assert_eq!(
+            StructuralFeatures::extract("I like Paris").strength(Feature::UppercaseRatio),
+            0.0
+        );
+
+        let features = StructuralFeatures::extract("good morning 👋😊");
+        assert_eq!(features.emoji_count, 2);
+    }
+
+    #[test]
+    fn test_stack_trace_detection() {
+        let traces = [
+            "Traceback (most recent call last):\n  File \"app.py\", line 3, in <module>",
+            "Exception in thread \"main\" java.lang.NullPointerException\n    at com.example.Main.run(Main.java:14)",
+            "thread 'main' panicked at src/main.rs:2:5",
+            "error[E0382]: borrow of moved value: `v`",
+            "TypeError: undefined is not a function\n    at foo (index.js:10:3)",
+        ];
+        for trace in traces {
+            assert!(StructuralFeatures::extract(trace).stack_trace, "trace: {}", trace);
+        }
+        assert!(!StructuralFeatures::extract("What is the capital of France?").stack_trace);
+    }
+
+    #[test]
+    fn test_explain_contributions() {
+        let weights = FeatureWeights::default();
+        assert!(weights.explain("I like pizza").is_empty());
+
+        let text = "```\nlet x = 1;\n```";
+        let matches = weights.explain(text);
+        let fence = matches
+            .iter()
+            .find(|m| m.pattern == "feature:code_fence")
+            .expect("code fence should contribute");
+        assert_eq!(fence.category, QueryCategory::Code);
+        assert_eq!(fence.weight, 2.0);
+        assert_eq!(&text[fence.start..fence.end], fence.text);
+    }
+
+    #[test]
+    fn test_set_and_validate_weights() {
+        let mut weights = FeatureWeights::none()
+            .with(Feature::Emoji, QueryCategory::Greeting, 1.5)
+            .unwrap();
+        assert_eq!(weights.get(Feature::Emoji, QueryCategory::Greeting), 1.5);
+
+        weights.set(Feature::Emoji, QueryCategory::Greeting, 0.0).unwrap();
+        assert!(weights.is_empty());
+
+        assert!(matches!(
+            weights.set(Feature::Emoji, QueryCategory::Greeting, f32::NAN),
+            Err(ClassifierError::InvalidFeatureWeight { .. })
+        ));
+    }
+}
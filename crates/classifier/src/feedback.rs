@@ -0,0 +1,224 @@
+//! Online tuning from user feedback
+//!
+//! When a query is misclassified, [`Classifier::record_feedback`] does two
+//! things:
+//!
+//! - remembers the expected category for that exact query (a correction),
+//!   so repeating it is fixed immediately;
+//! - scales down the weights of the patterns that pulled the query into the
+//!   wrong category and scales up those that matched the expected one, so
+//!   similar queries benefit too.
+//!
+//! The tuned state can be exported as a serializable [`FeedbackState`] and
+//! imported into another classifier instance.
+//!
+//! [`Classifier::record_feedback`]: crate::Classifier::record_feedback
+
+use std::collections::HashMap;
+
+use neuro_core::{PatternMatch, QueryCategory};
+use serde::{Deserialize, Serialize};
+
+/// Relative weight change applied per feedback event
+const LEARNING_RATE: f32 = 0.2;
+
+/// Bounds for the weight multiplier of a single pattern
+const MIN_MULTIPLIER: f32 = 0.1;
+const MAX_MULTIPLIER: f32 = 5.0;
+
+/// Exportable feedback state of a classifier
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct FeedbackState {
+    /// Expected category by normalized query text
+    #[serde(default)]
+    pub corrections: HashMap<String, QueryCategory>,
+
+    /// Tuned weight multipliers of individual patterns
+    #[serde(default)]
+    pub adjustments: Vec<WeightAdjustment>,
+}
+
+/// Weight multiplier learned for one pattern of one category
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WeightAdjustment {
+    pub category: QueryCategory,
+    pub pattern: String,
+    pub multiplier: f32,
+}
+
+/// In-memory feedback state used while classifying
+#[derive(Debug, Default)]
+pub(crate) struct Feedback {
+    corrections: HashMap<String, QueryCategory>,
+    multipliers: HashMap<(QueryCategory, String), f32>,
+}
+
+impl Feedback {
+    /// Expected category recorded for this query, if any
+    pub(crate) fn correction(&self, query: &str) -> Option<QueryCategory> {
+        self.corrections.get(&normalize_query(query)).copied()
+    }
+
+    /// Apply tuned multipliers to the weights of matched patterns
+    pub(crate) fn adjust(&self, matches: &mut [PatternMatch]) {
+        if self.multipliers.is_empty() {
+            return;
+        }
+        for m in matches {
+            if let Some(multiplier) = self.multipliers.get(&(m.category, m.pattern.clone())) {
+                m.weight *= multiplier;
+            }
+        }
+    }
+
+    /// Learn from a query classified as `predicted` that should be `expected`
+    pub(crate) fn record(
+        &mut self,
+        query: &str,
+        matches: &[PatternMatch],
+        predicted: QueryCategory,
+        expected: QueryCategory,
+    ) {
+        let key = normalize_query(query);
+        if predicted == expected {
+            self.corrections.remove(&key);
+            return;
+        }
+        self.corrections.insert(key, expected);
+
+        for m in matches {
+            let strengthen = if m.category == predicted {
+                false
+            } else if m.category == expected {
+                true
+            } else {
+                continue;
+            };
+            // Negative weights (structural features arguing against a
+            // category) move the score the other way when scaled up
+            let factor = if strengthen == (m.weight >= 0.0) {
+                1.0 + LEARNING_RATE
+            } else {
+                1.0 - LEARNING_RATE
+            };
+            let multiplier = self
+                .multipliers
+                .entry((m.category, m.pattern.clone()))
+                .or_insert(1.0);
+            *multiplier = (*multiplier * factor).clamp(MIN_MULTIPLIER, MAX_MULTIPLIER);
+        }
+    }
+
+    pub(crate) fn export(&self) -> FeedbackState {
+        let mut adjustments: Vec<WeightAdjustment> = self
+            .multipliers
+            .iter()
+            .map(|((category, pattern), multiplier)| WeightAdjustment {
+                category: *category,
+                pattern: pattern.clone(),
+                multiplier: *multiplier,
+            })
+            .collect();
+        adjustments.sort_by(|a, b| {
+            (a.category.to_string(), &a.pattern).cmp(&(b.category.to_string(), &b.pattern))
+        });
+
+        FeedbackState {
+            corrections: self.corrections.clone(),
+            adjustments,
+        }
+    }
+
+    pub(crate) fn import(&mut self, state: FeedbackState) {
+        self.corrections = state
+            .corrections
+            .into_iter()
+            .map(|(query, category)| (normalize_query(&query), category))
+            .collect();
+        self.multipliers = state
+            .adjustments
+            .into_iter()
+            .filter(|a| a.multiplier.is_finite())
+            .map(|a| {
+                let multiplier = a.multiplier.clamp(MIN_MULTIPLIER, MAX_MULTIPLIER);
+                ((a.category, a.pattern), multiplier)
+            })
+            .collect();
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.corrections.clear();
+        self.multipliers.clear();
+    }
+}
+
+/// Lowercase and collapse whitespace so trivially different queries match
+fn normalize_query(query: &str) -> String {
+    query
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+        .to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn pattern_match(category: QueryCategory, pattern: &str) -> PatternMatch {
+        PatternMatch {
+            category,
+            pattern: pattern.to_string(),
+            weight: 1.0,
+            start: 0,
+            end: 0,
+            text: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_record_adjusts_matched_patterns() {
+        let mut feedback = Feedback::default();
+        let matches = [
+            pattern_match(QueryCategory::Factual, "what"),
+            pattern_match(QueryCategory::Math, "sum"),
+            pattern_match(QueryCategory::Greeting, "hi"),
+        ];
+        feedback.record("What  is the SUM", &matches, QueryCategory::Factual, QueryCategory::Math);
+
+        assert_eq!(feedback.correction("what is the sum"), Some(QueryCategory::Math));
+
+        let mut adjusted = matches.clone();
+        feedback.adjust(&mut adjusted);
+        assert!(adjusted[0].weight < 1.0);
+        assert!(adjusted[1].weight > 1.0);
+        assert_eq!(adjusted[2].weight, 1.0);
+    }
+
+    #[test]
+    fn test_multiplier_bounds() {
+        let mut feedback = Feedback::default();
+        let matches = [pattern_match(QueryCategory::Factual, "what")];
+        for _ in 0..100 {
+            feedback.record("q", &matches, QueryCategory::Factual, QueryCategory::Math);
+        }
+
+        let mut adjusted = matches.clone();
+        feedback.adjust(&mut adjusted);
+        assert!((adjusted[0].weight - MIN_MULTIPLIER).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_export_import_round_trip() {
+        let mut feedback = Feedback::default();
+        let matches = [pattern_match(QueryCategory::Factual, "what")];
+        feedback.record("q", &matches, QueryCategory::Factual, QueryCategory::Math);
+
+        let state = feedback.export();
+        assert_eq!(state.adjustments.len(), 1);
+
+        let mut restored = Feedback::default();
+        restored.import(state.clone());
+        assert_eq!(restored.export(), state);
+    }
+}
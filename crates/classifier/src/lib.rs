@@ -13,6 +13,10 @@
 //! - **Tools** - Tool usage and function calls
 //! - **Greeting** - Social interactions and greetings
 //! - **Factual** - Knowledge and fact-based queries
+//! - **Summarization** - Summarizing provided text
+//! - **Translation** - Translating between languages
+//! - **CreativeWriting** - Poems, stories and other creative writing
+//! - **Sensitive** - Self-harm, illegal instructions and other unsafe requests
 //! - **Conversational** - General conversation
 //!
 //! ## Example
@@ -25,12 +29,151 @@
 //!
 //! assert_eq!(result.category, neuro_core::QueryCategory::Math);
 //! ```
+//!
+//! ## Languages
+//!
+//! Patterns ship for English, Spanish, French, German and Portuguese, and
+//! all of them are enabled by default. A deployment can restrict the
+//! classifier to the languages it serves:
+//!
+//! ```
+//! use neuro_classifier::{Classifier, Language};
+//!
+//! let classifier = Classifier::with_languages(&[Language::English, Language::French]);
+//! let result = classifier.classify("Bonjour !");
+//!
+//! assert_eq!(result.category, neuro_core::QueryCategory::Greeting);
+//! ```
+//!
+//! Other crates can contribute language packs at startup and select them
+//! by code:
+//!
+//! ```
+//! use neuro_classifier::{CategoryPatterns, Classifier, QueryPatterns, WeightedPattern};
+//!
+//! let italian = CategoryPatterns {
+//!     greeting: vec![WeightedPattern::new(r"(?i)^ciao\b", 2.0)],
+//!     ..Default::default()
+//! };
+//! QueryPatterns::register_language("it", italian).unwrap();
+//!
+//! let classifier = Classifier::for_languages(&["en", "it"]).unwrap();
+//! let result = classifier.classify("Ciao!");
+//!
+//! assert_eq!(result.category, neuro_core::QueryCategory::Greeting);
+//! ```
+//!
+//! ## Custom patterns
+//!
+//! Pattern weights can be tuned and domain-specific patterns added without
+//! recompiling, using a YAML or TOML file:
+//!
+//! ```no_run
+//! use neuro_classifier::{Classifier, QueryPatterns};
+//!
+//! let patterns = QueryPatterns::from_file("patterns.yaml").unwrap();
+//! let classifier = Classifier::with_patterns(patterns);
+//! ```
+//!
+//! ## Confidence
+//!
+//! Pattern scores are turned into per-category probabilities with a
+//! softmax ([`ClassificationResult::probabilities`]). The confidence
+//! averages the winner's probability with its margin over the runner-up,
+//! so near-ties between categories report low confidence.
+//!
+//! [`ClassificationResult::probabilities`]: neuro_core::ClassificationResult::probabilities
+//!
+//! ## Structural features
+//!
+//! Besides keywords, the shape of a query is scored: code fences, stack
+//! traces, digit and punctuation ratios, line count, ALL-CAPS text and
+//! emoji each add a configurable weight to some categories, so a pasted
+//! stack trace is recognized as code without any programming keyword:
+//!
+//! ```
+//! use neuro_classifier::{Classifier, Feature, FeatureWeights, QueryCategory};
+//!
+//! let result = Classifier::new().classify("thread 'main' panicked at src/main.rs:2:5");
+//! assert_eq!(result.category, QueryCategory::Code);
+//!
+//! let weights = FeatureWeights::default()
+//!     .with(Feature::Emoji, QueryCategory::Greeting, 1.0)
+//!     .unwrap();
+//! let classifier = Classifier::new().with_feature_weights(weights);
+//! ```
+//!
+//! ## Programming languages
+//!
+//! Code queries are additionally tagged with the programming language they
+//! are about, when one can be recognized:
+//!
+//! ```
+//! use neuro_classifier::Classifier;
+//!
+//! let result = Classifier::new().classify("Write a Python function to reverse a list");
+//! assert_eq!(result.programming_language(), Some("python"));
+//! ```
+//!
+//! ## Semantic fallback
+//!
+//! Regex patterns miss paraphrases. A `SemanticClassifier` compares the
+//! query embedding against per-category prototypes and is consulted when
+//! the regex confidence is low. It requires the `semantic` feature (on by
+//! default), which pulls in `neuro-embeddings` and its ONNX runtime:
+//!
+//! ```no_run
+//! # #[cfg(feature = "semantic")]
+//! # fn main() {
+//! use std::sync::Arc;
+//! use neuro_classifier::{Classifier, SemanticClassifier};
+//! use neuro_embeddings::{EmbeddingModel, FastEmbedder};
+//!
+//! let embedder = Arc::new(FastEmbedder::new(EmbeddingModel::default()).unwrap());
+//! let classifier = Classifier::new()
+//!     .with_semantic_fallback(SemanticClassifier::new(embedder).unwrap());
+//!
+//! let result = classifier.classify("could you crunch these numbers for me");
+//! # }
+//! # #[cfg(not(feature = "semantic"))]
+//! # fn main() {}
+//! ```
+//!
+//! ## Feedback
+//!
+//! Misclassifications seen in production can be fed back without code
+//! changes. The learned state can be exported and restored later:
+//!
+//! ```
+//! use neuro_classifier::{Classifier, FeedbackState, QueryCategory};
+//!
+//! let classifier = Classifier::new();
+//! classifier.record_feedback("I like pizza", QueryCategory::Factual);
+//! assert_eq!(classifier.classify("I like pizza").category, QueryCategory::Factual);
+//!
+//! let state: FeedbackState = classifier.export_feedback();
+//! Classifier::new().import_feedback(state);
+//! ```
 
 mod classifier;
+mod error;
+mod features;
+mod feedback;
 mod patterns;
+mod programming;
+#[cfg(feature = "semantic")]
+mod semantic;
 
 pub use classifier::Classifier;
-pub use patterns::{QueryPatterns, WeightedPattern, CompiledPattern};
+pub use error::{ClassifierError, Result};
+pub use features::{Feature, FeatureWeights, StructuralFeatures, FEATURE_PATTERN_PREFIX};
+pub use feedback::{FeedbackState, WeightAdjustment};
+pub use patterns::{
+    CategoryPatterns, CompiledPattern, Language, PatternSet, QueryPatterns, WeightedPattern,
+};
+pub use programming::ProgrammingLanguage;
+#[cfg(feature = "semantic")]
+pub use semantic::{SemanticClassifier, DEFAULT_EXAMPLES};
 
 /// Re-export core types
-pub use neuro_core::{ClassificationResult, QueryCategory, QueryStrategy};
+pub use neuro_core::{ClassificationResult, QueryCategory, QueryClassifier, QueryStrategy};
@@ -0,0 +1,303 @@
+//! Loading classification patterns from YAML or TOML files
+//!
+//! A pattern file lists weighted regexes per category:
+//!
+//! ```yaml
+//! extend_defaults: true   # keep the built-in patterns (default)
+//! code:
+//!   - pattern: '(?i)\bkubectl\b'
+//!     weight: 1.5
+//!   - pattern: '(?i)\bsql\b'   # same regex as a built-in: overrides its weight
+//!     weight: 0.5
+//! features:               # structural feature weights per category
+//!   stack_trace:
+//!     code: 3.0
+//!   question_mark:
+//!     creative_writing: 0 # a weight of 0 disables the feature for a category
+//! ```
+//!
+//! The same structure is accepted as TOML (`[[code]]` tables).
+
+use neuro_core::QueryCategory;
+use regex::Regex;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::{CompiledPattern, QueryPatterns};
+use crate::error::{ClassifierError, Result};
+use crate::features::Feature;
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternFile {
+    #[serde(default = "default_extend")]
+    extend_defaults: bool,
+    #[serde(default)]
+    math: Vec<PatternDef>,
+    #[serde(default)]
+    code: Vec<PatternDef>,
+    #[serde(default)]
+    reasoning: Vec<PatternDef>,
+    #[serde(default)]
+    tools: Vec<PatternDef>,
+    #[serde(default)]
+    greeting: Vec<PatternDef>,
+    #[serde(default)]
+    factual: Vec<PatternDef>,
+    #[serde(default)]
+    summarization: Vec<PatternDef>,
+    #[serde(default)]
+    translation: Vec<PatternDef>,
+    #[serde(default)]
+    creative_writing: Vec<PatternDef>,
+    #[serde(default)]
+    sensitive: Vec<PatternDef>,
+    #[serde(default)]
+    features: HashMap<Feature, HashMap<QueryCategory, f32>>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct PatternDef {
+    pattern: String,
+    #[serde(default = "default_weight")]
+    weight: f32,
+}
+
+fn default_extend() -> bool {
+    true
+}
+
+fn default_weight() -> f32 {
+    1.0
+}
+
+impl QueryPatterns {
+    /// Load patterns from a YAML (`.yaml`/`.yml`) or TOML (`.toml`) file
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|source| ClassifierError::Io {
+            path: path.to_path_buf(),
+            source,
+        })?;
+
+        match path.extension().and_then(|e| e.to_str()) {
+            Some("yaml") | Some("yml") => Self::from_yaml_str(&content),
+            Some("toml") => Self::from_toml_str(&content),
+            _ => Err(ClassifierError::UnsupportedFormat(path.to_path_buf())),
+        }
+    }
+
+    /// Load patterns from a YAML document
+    pub fn from_yaml_str(content: &str) -> Result<Self> {
+        let file: PatternFile =
+            serde_yaml_ng::from_str(content).map_err(|e| ClassifierError::Parse(e.to_string()))?;
+        file.build()
+    }
+
+    /// Load patterns from a TOML document
+    pub fn from_toml_str(content: &str) -> Result<Self> {
+        let file: PatternFile =
+            toml::from_str(content).map_err(|e| ClassifierError::Parse(e.to_string()))?;
+        file.build()
+    }
+}
+
+impl PatternFile {
+    fn build(self) -> Result<QueryPatterns> {
+        let mut patterns = if self.extend_defaults {
+            QueryPatterns::new()
+        } else {
+            QueryPatterns::empty()
+        };
+
+        let sections = [
+            ("math", self.math, &mut patterns.math),
+            ("code", self.code, &mut patterns.code),
+            ("reasoning", self.reasoning, &mut patterns.reasoning),
+            ("tools", self.tools, &mut patterns.tools),
+            ("greeting", self.greeting, &mut patterns.greeting),
+            ("factual", self.factual, &mut patterns.factual),
+            ("summarization", self.summarization, &mut patterns.summarization),
+            ("translation", self.translation, &mut patterns.translation),
+            ("creative_writing", self.creative_writing, &mut patterns.creative_writing),
+            ("sensitive", self.sensitive, &mut patterns.sensitive),
+        ];
+
+        for (category, defs, compiled) in sections {
+            for (index, def) in defs.into_iter().enumerate() {
+                merge_pattern(compiled, category, index, def)?;
+            }
+        }
+
+        for (feature, categories) in self.features {
+            for (category, weight) in categories {
+                patterns.features.set(feature, category, weight)?;
+            }
+        }
+
+        Ok(patterns)
+    }
+}
+
+/// Add a pattern, or override the weight of an identical existing one
+fn merge_pattern(
+    compiled: &mut Vec<CompiledPattern>,
+    category: &str,
+    index: usize,
+    def: PatternDef,
+) -> Result<()> {
+    if !def.weight.is_finite() || def.weight < 0.0 {
+        return Err(ClassifierError::InvalidWeight {
+            category: category.to_string(),
+            index,
+            pattern: def.pattern,
+            weight: def.weight,
+        });
+    }
+
+    if let Some(existing) = compiled.iter_mut().find(|p| p.regex.as_str() == def.pattern) {
+        existing.weight = def.weight;
+        return Ok(());
+    }
+
+    let regex = Regex::new(&def.pattern).map_err(|e| ClassifierError::InvalidPattern {
+        category: category.to_string(),
+        index,
+        pattern: def.pattern.clone(),
+        message: e.to_string(),
+    })?;
+    compiled.push(CompiledPattern {
+        regex,
+        weight: def.weight,
+    });
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_yaml_extends_defaults() {
+        let yaml = r#"
+code:
+  - pattern: '(?i)\bkubectl\b'
+    weight: 2.0
+"#;
+        let patterns = QueryPatterns::from_yaml_str(yaml).unwrap();
+        let defaults = QueryPatterns::new();
+
+        assert_eq!(patterns.code.len(), defaults.code.len() + 1);
+        assert_eq!(patterns.math.len(), defaults.math.len());
+        assert_eq!(QueryPatterns::score_category(&patterns.code, "kubectl apply"), 2.0);
+    }
+
+    #[test]
+    fn test_toml_replaces_defaults_and_overrides_weight() {
+        let toml = r#"
+extend_defaults = false
+
+[[math]]
+pattern = '(?i)\bcrunch\b'
+weight = 1.5
+
+[[math]]
+pattern = '(?i)\bcrunch\b'
+weight = 3.0
+"#;
+        let patterns = QueryPatterns::from_toml_str(toml).unwrap();
+
+        assert_eq!(patterns.math.len(), 1);
+        assert!(patterns.code.is_empty());
+        assert_eq!(QueryPatterns::score_category(&patterns.math, "crunch it"), 3.0);
+    }
+
+    #[test]
+    fn test_invalid_regex_reports_location() {
+        let yaml = r#"
+tools:
+  - pattern: '(?i)\bok\b'
+  - pattern: '(?i)broken('
+"#;
+        let err = QueryPatterns::from_yaml_str(yaml).err().unwrap();
+        match err {
+            ClassifierError::InvalidPattern {
+                ref category,
+                index,
+                ref pattern,
+                ..
+            } => {
+                assert_eq!(category, "tools");
+                assert_eq!(index, 1);
+                assert_eq!(pattern, "(?i)broken(");
+            }
+            other => panic!("unexpected error: {}", other),
+        }
+        assert!(err.to_string().contains("tools[1]"));
+    }
+
+    #[test]
+    fn test_invalid_weight_and_unknown_fields() {
+        let yaml = "math:\n  - pattern: 'x'\n    weight: -1.0\n";
+        assert!(matches!(
+            QueryPatterns::from_yaml_str(yaml),
+            Err(ClassifierError::InvalidWeight { .. })
+        ));
+
+        let yaml = "maths:\n  - pattern: 'x'\n";
+        assert!(matches!(
+            QueryPatterns::from_yaml_str(yaml),
+            Err(ClassifierError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn test_feature_weights() {
+        let yaml = r#"
+features:
+  stack_trace:
+    code: 4.0
+  question_mark:
+    creative_writing: 0.0
+"#;
+        let patterns = QueryPatterns::from_yaml_str(yaml).unwrap();
+        assert_eq!(patterns.features.get(Feature::StackTrace, QueryCategory::Code), 4.0);
+        assert_eq!(
+            patterns.features.get(Feature::QuestionMark, QueryCategory::CreativeWriting),
+            0.0
+        );
+        assert!(patterns.features.get(Feature::QuestionMark, QueryCategory::Summarization) < 0.0);
+        // Untouched defaults are kept
+        assert_eq!(patterns.features.get(Feature::CodeFence, QueryCategory::Code), 2.0);
+
+        let toml = "extend_defaults = false\n\n[features.emoji]\ngreeting = 1.0\n";
+        let patterns = QueryPatterns::from_toml_str(toml).unwrap();
+        assert_eq!(patterns.features.get(Feature::Emoji, QueryCategory::Greeting), 1.0);
+        assert_eq!(patterns.features.get(Feature::CodeFence, QueryCategory::Code), 0.0);
+
+        let yaml = "features:\n  emoji:\n    greeting: .inf\n";
+        assert!(matches!(
+            QueryPatterns::from_yaml_str(yaml),
+            Err(ClassifierError::InvalidFeatureWeight { .. })
+        ));
+    }
+
+    #[test]
+    fn test_from_file_format_detection() {
+        let dir = tempfile::tempdir().unwrap();
+
+        let path = dir.path().join("patterns.yml");
+        std::fs::write(&path, "greeting:\n  - pattern: '(?i)\\bahoy\\b'\n").unwrap();
+        let patterns = QueryPatterns::from_file(&path).unwrap();
+        assert!(QueryPatterns::score_category(&patterns.greeting, "ahoy!") > 0.0);
+
+        let path = dir.path().join("patterns.json");
+        std::fs::write(&path, "{}").unwrap();
+        assert!(matches!(
+            QueryPatterns::from_file(&path),
+            Err(ClassifierError::UnsupportedFormat(_))
+        ));
+    }
+}
@@ -0,0 +1,328 @@
+//! Language packs
+//!
+//! Each language contributes weighted patterns for every category. A
+//! [`QueryPatterns`] set can be built from any combination of the built-in
+//! packs with `QueryPatterns::with_languages`.
+//!
+//! Packs are also kept in a process-wide registry keyed by language code.
+//! Downstream crates add their own with `QueryPatterns::register_language`
+//! and build pattern sets from registered codes with
+//! `QueryPatterns::for_languages`.
+
+use std::collections::HashMap;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::RwLock;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use super::{patterns_de, patterns_es, patterns_fr, patterns_pt, QueryPatterns, WeightedPattern};
+use crate::error::{ClassifierError, Result};
+
+/// Registered language packs by normalized code (built-ins included)
+static REGISTRY: Lazy<RwLock<HashMap<String, CategoryPatterns>>> = Lazy::new(|| {
+    RwLock::new(
+        Language::ALL
+            .iter()
+            .map(|language| (language.code().to_string(), language.patterns()))
+            .collect(),
+    )
+});
+
+/// Languages with built-in classification patterns
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    German,
+    Portuguese,
+}
+
+impl Language {
+    /// All built-in languages
+    pub const ALL: [Language; 5] = [
+        Self::English,
+        Self::Spanish,
+        Self::French,
+        Self::German,
+        Self::Portuguese,
+    ];
+
+    /// ISO 639-1 code of the language
+    pub fn code(&self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::Spanish => "es",
+            Self::French => "fr",
+            Self::German => "de",
+            Self::Portuguese => "pt",
+        }
+    }
+
+    /// Weighted patterns of this language for every category
+    pub fn patterns(&self) -> CategoryPatterns {
+        match self {
+            Self::English => CategoryPatterns {
+                math: super::build_math_patterns(),
+                code: super::build_code_patterns(),
+                reasoning: super::build_reasoning_patterns(),
+                tools: super::build_tools_patterns(),
+                greeting: super::build_greeting_patterns(),
+                factual: super::build_factual_patterns(),
+                summarization: super::build_summarization_patterns(),
+                translation: super::build_translation_patterns(),
+                creative_writing: super::build_creative_writing_patterns(),
+                sensitive: super::build_sensitive_patterns(),
+            },
+            Self::Spanish => CategoryPatterns {
+                math: patterns_es::build_math_patterns_es(),
+                code: patterns_es::build_code_patterns_es(),
+                reasoning: patterns_es::build_reasoning_patterns_es(),
+                tools: patterns_es::build_tools_patterns_es(),
+                greeting: patterns_es::build_greeting_patterns_es(),
+                factual: patterns_es::build_factual_patterns_es(),
+                summarization: patterns_es::build_summarization_patterns_es(),
+                translation: patterns_es::build_translation_patterns_es(),
+                creative_writing: patterns_es::build_creative_writing_patterns_es(),
+                sensitive: patterns_es::build_sensitive_patterns_es(),
+            },
+            Self::French => CategoryPatterns {
+                math: patterns_fr::build_math_patterns_fr(),
+                code: patterns_fr::build_code_patterns_fr(),
+                reasoning: patterns_fr::build_reasoning_patterns_fr(),
+                tools: patterns_fr::build_tools_patterns_fr(),
+                greeting: patterns_fr::build_greeting_patterns_fr(),
+                factual: patterns_fr::build_factual_patterns_fr(),
+                summarization: patterns_fr::build_summarization_patterns_fr(),
+                translation: patterns_fr::build_translation_patterns_fr(),
+                creative_writing: patterns_fr::build_creative_writing_patterns_fr(),
+                sensitive: patterns_fr::build_sensitive_patterns_fr(),
+            },
+            Self::German => CategoryPatterns {
+                math: patterns_de::build_math_patterns_de(),
+                code: patterns_de::build_code_patterns_de(),
+                reasoning: patterns_de::build_reasoning_patterns_de(),
+                tools: patterns_de::build_tools_patterns_de(),
+                greeting: patterns_de::build_greeting_patterns_de(),
+                factual: patterns_de::build_factual_patterns_de(),
+                summarization: patterns_de::build_summarization_patterns_de(),
+                translation: patterns_de::build_translation_patterns_de(),
+                creative_writing: patterns_de::build_creative_writing_patterns_de(),
+                sensitive: patterns_de::build_sensitive_patterns_de(),
+            },
+            Self::Portuguese => CategoryPatterns {
+                math: patterns_pt::build_math_patterns_pt(),
+                code: patterns_pt::build_code_patterns_pt(),
+                reasoning: patterns_pt::build_reasoning_patterns_pt(),
+                tools: patterns_pt::build_tools_patterns_pt(),
+                greeting: patterns_pt::build_greeting_patterns_pt(),
+                factual: patterns_pt::build_factual_patterns_pt(),
+                summarization: patterns_pt::build_summarization_patterns_pt(),
+                translation: patterns_pt::build_translation_patterns_pt(),
+                creative_writing: patterns_pt::build_creative_writing_patterns_pt(),
+                sensitive: patterns_pt::build_sensitive_patterns_pt(),
+            },
+        }
+    }
+}
+
+impl fmt::Display for Language {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.code())
+    }
+}
+
+impl FromStr for Language {
+    type Err = ClassifierError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "en" | "english" => Ok(Self::English),
+            "es" | "spanish" | "español" => Ok(Self::Spanish),
+            "fr" | "french" | "français" => Ok(Self::French),
+            "de" | "german" | "deutsch" => Ok(Self::German),
+            "pt" | "portuguese" | "português" => Ok(Self::Portuguese),
+            _ => Err(ClassifierError::UnknownLanguage(s.to_string())),
+        }
+    }
+}
+
+/// Uncompiled weighted patterns for each category
+#[derive(Debug, Clone, Default)]
+pub struct CategoryPatterns {
+    pub math: Vec<WeightedPattern>,
+    pub code: Vec<WeightedPattern>,
+    pub reasoning: Vec<WeightedPattern>,
+    pub tools: Vec<WeightedPattern>,
+    pub greeting: Vec<WeightedPattern>,
+    pub factual: Vec<WeightedPattern>,
+    pub summarization: Vec<WeightedPattern>,
+    pub translation: Vec<WeightedPattern>,
+    pub creative_writing: Vec<WeightedPattern>,
+    pub sensitive: Vec<WeightedPattern>,
+}
+
+impl CategoryPatterns {
+    /// Append the patterns of another pack
+    pub fn extend(&mut self, other: CategoryPatterns) {
+        self.math.extend(other.math);
+        self.code.extend(other.code);
+        self.reasoning.extend(other.reasoning);
+        self.tools.extend(other.tools);
+        self.greeting.extend(other.greeting);
+        self.factual.extend(other.factual);
+        self.summarization.extend(other.summarization);
+        self.translation.extend(other.translation);
+        self.creative_writing.extend(other.creative_writing);
+        self.sensitive.extend(other.sensitive);
+    }
+
+    /// Patterns of every category, paired with the category name
+    pub fn sections(&self) -> [(&'static str, &[WeightedPattern]); 10] {
+        [
+            ("math", &self.math),
+            ("code", &self.code),
+            ("reasoning", &self.reasoning),
+            ("tools", &self.tools),
+            ("greeting", &self.greeting),
+            ("factual", &self.factual),
+            ("summarization", &self.summarization),
+            ("translation", &self.translation),
+            ("creative_writing", &self.creative_writing),
+            ("sensitive", &self.sensitive),
+        ]
+    }
+
+    /// Check that every pattern is a valid regex with a usable weight
+    pub fn validate(&self) -> Result<()> {
+        for (category, patterns) in self.sections() {
+            for (index, pattern) in patterns.iter().enumerate() {
+                if !pattern.weight.is_finite() || pattern.weight < 0.0 {
+                    return Err(ClassifierError::InvalidWeight {
+                        category: category.to_string(),
+                        index,
+                        pattern: pattern.pattern.to_string(),
+                        weight: pattern.weight,
+                    });
+                }
+                Regex::new(pattern.pattern).map_err(|e| ClassifierError::InvalidPattern {
+                    category: category.to_string(),
+                    index,
+                    pattern: pattern.pattern.to_string(),
+                    message: e.to_string(),
+                })?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl QueryPatterns {
+    /// Register a language pack under a language code
+    ///
+    /// Registering an existing code (including a built-in one) replaces its
+    /// pack. Codes are case-insensitive.
+    pub fn register_language(lang: &str, patterns: CategoryPatterns) -> Result<()> {
+        patterns.validate()?;
+        REGISTRY
+            .write()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(normalize_code(lang), patterns);
+        Ok(())
+    }
+
+    /// Codes of all registered languages, sorted
+    pub fn registered_languages() -> Vec<String> {
+        let mut codes: Vec<String> = REGISTRY
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .keys()
+            .cloned()
+            .collect();
+        codes.sort();
+        codes
+    }
+
+    /// Build a pattern set from registered language packs
+    ///
+    /// Fails with [`ClassifierError::UnknownLanguage`] if a code has not
+    /// been registered.
+    pub fn for_languages<S: AsRef<str>>(languages: &[S]) -> Result<Self> {
+        let registry = REGISTRY.read().unwrap_or_else(|e| e.into_inner());
+
+        let mut combined = CategoryPatterns::default();
+        for lang in languages {
+            let pack = registry
+                .get(&normalize_code(lang.as_ref()))
+                .ok_or_else(|| ClassifierError::UnknownLanguage(lang.as_ref().to_string()))?;
+            combined.extend(pack.clone());
+        }
+
+        Ok(Self::compile(&combined))
+    }
+}
+
+fn normalize_code(code: &str) -> String {
+    code.trim().to_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_codes_round_trip() {
+        for language in Language::ALL {
+            assert_eq!(language.code().parse::<Language>().unwrap(), language);
+        }
+        assert_eq!("Deutsch".parse::<Language>().unwrap(), Language::German);
+        assert!(matches!(
+            "klingon".parse::<Language>(),
+            Err(ClassifierError::UnknownLanguage(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_language() {
+        let italian = CategoryPatterns {
+            greeting: vec![WeightedPattern::new(r"(?i)^ciao\b", 2.0)],
+            ..Default::default()
+        };
+        QueryPatterns::register_language("IT", italian).unwrap();
+        assert!(QueryPatterns::registered_languages().contains(&"it".to_string()));
+
+        let patterns = QueryPatterns::for_languages(&["en", "it"]).unwrap();
+        assert!(QueryPatterns::score_category(&patterns.greeting, "ciao!") > 0.0);
+        assert!(QueryPatterns::score_category(&patterns.greeting, "hello") > 0.0);
+        assert!(QueryPatterns::score_category(&patterns.greeting, "hola") == 0.0);
+
+        assert!(matches!(
+            QueryPatterns::for_languages(&["xx"]),
+            Err(ClassifierError::UnknownLanguage(_))
+        ));
+    }
+
+    #[test]
+    fn test_register_language_rejects_invalid_regex() {
+        let broken = CategoryPatterns {
+            math: vec![WeightedPattern::new(r"(?i)\bok\b", 1.0), WeightedPattern::new("(", 1.0)],
+            ..Default::default()
+        };
+        let err = QueryPatterns::register_language("broken", broken).unwrap_err();
+        assert!(err.to_string().contains("math[1]"));
+        assert!(!QueryPatterns::registered_languages().contains(&"broken".to_string()));
+    }
+
+    #[test]
+    fn test_every_pack_covers_every_category() {
+        for language in Language::ALL {
+            let pack = language.patterns();
+            pack.validate().unwrap();
+            for (name, patterns) in pack.sections() {
+                assert!(!patterns.is_empty(), "{} has no {} patterns", language, name);
+            }
+        }
+    }
+}
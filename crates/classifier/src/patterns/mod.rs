@@ -4,10 +4,23 @@
 //! Each pattern has an associated weight that determines its importance
 //! in the classification scoring.
 
+use neuro_core::QueryCategory;
 use once_cell::sync::Lazy;
 use regex::Regex;
 
+use crate::features::FeatureWeights;
+
+mod file;
+mod language;
+mod patterns_de;
 mod patterns_es;
+mod patterns_fr;
+mod patterns_pt;
+mod prefilter;
+mod set;
+
+pub use language::{CategoryPatterns, Language};
+pub use set::PatternSet;
 
 /// A pattern with an associated weight for scoring
 #[derive(Debug, Clone)]
@@ -57,21 +70,81 @@ pub struct QueryPatterns {
     pub tools: Vec<CompiledPattern>,
     pub greeting: Vec<CompiledPattern>,
     pub factual: Vec<CompiledPattern>,
+    pub summarization: Vec<CompiledPattern>,
+    pub translation: Vec<CompiledPattern>,
+    pub creative_writing: Vec<CompiledPattern>,
+    pub sensitive: Vec<CompiledPattern>,
+    /// Weights of structural features added to the pattern scores
+    pub features: FeatureWeights,
 }
 
 impl QueryPatterns {
-    /// Create a new set of query patterns (English + Spanish)
+    /// Create a new set of query patterns (all built-in languages)
     pub fn new() -> Self {
+        Self::with_languages(&Language::ALL)
+    }
+
+    /// Create a set of query patterns from the given language packs only
+    pub fn with_languages(languages: &[Language]) -> Self {
+        let mut combined = CategoryPatterns::default();
+        for language in languages {
+            combined.extend(language.patterns());
+        }
+        Self::compile(&combined)
+    }
+
+    /// Compile uncompiled patterns, skipping invalid regexes
+    fn compile(combined: &CategoryPatterns) -> Self {
         Self {
-            math: compile_patterns(&build_math_patterns()),
-            code: compile_patterns(&build_code_patterns()),
-            reasoning: compile_patterns(&build_reasoning_patterns()),
-            tools: compile_patterns(&build_tools_patterns()),
-            greeting: compile_patterns(&build_greeting_patterns()),
-            factual: compile_patterns(&build_factual_patterns()),
+            math: compile_patterns(&combined.math),
+            code: compile_patterns(&combined.code),
+            reasoning: compile_patterns(&combined.reasoning),
+            tools: compile_patterns(&combined.tools),
+            greeting: compile_patterns(&combined.greeting),
+            factual: compile_patterns(&combined.factual),
+            summarization: compile_patterns(&combined.summarization),
+            translation: compile_patterns(&combined.translation),
+            creative_writing: compile_patterns(&combined.creative_writing),
+            sensitive: compile_patterns(&combined.sensitive),
+            features: FeatureWeights::default(),
         }
     }
     
+    /// Create a pattern set with no patterns in any category
+    ///
+    /// Structural features are disabled as well.
+    pub fn empty() -> Self {
+        Self {
+            math: Vec::new(),
+            code: Vec::new(),
+            reasoning: Vec::new(),
+            tools: Vec::new(),
+            greeting: Vec::new(),
+            factual: Vec::new(),
+            summarization: Vec::new(),
+            translation: Vec::new(),
+            creative_writing: Vec::new(),
+            sensitive: Vec::new(),
+            features: FeatureWeights::none(),
+        }
+    }
+
+    /// Patterns of every category, paired with the category
+    pub fn categories(&self) -> [(QueryCategory, &[CompiledPattern]); 10] {
+        [
+            (QueryCategory::Math, &self.math),
+            (QueryCategory::Code, &self.code),
+            (QueryCategory::Reasoning, &self.reasoning),
+            (QueryCategory::Tools, &self.tools),
+            (QueryCategory::Greeting, &self.greeting),
+            (QueryCategory::Factual, &self.factual),
+            (QueryCategory::Summarization, &self.summarization),
+            (QueryCategory::Translation, &self.translation),
+            (QueryCategory::CreativeWriting, &self.creative_writing),
+            (QueryCategory::Sensitive, &self.sensitive),
+        ]
+    }
+
     /// Calculate total weighted score for a category
     pub fn score_category(patterns: &[CompiledPattern], text: &str) -> f32 {
         patterns.iter().map(|p| p.score(text)).sum()
@@ -95,12 +168,15 @@ fn compile_patterns(patterns: &[WeightedPattern]) -> Vec<CompiledPattern> {
 /// Global singleton for patterns (compiled once)
 pub static PATTERNS: Lazy<QueryPatterns> = Lazy::new(QueryPatterns::new);
 
+/// Global singleton for the built-in patterns compiled into one set
+pub static PATTERN_SET: Lazy<PatternSet> = Lazy::new(|| PatternSet::new(&PATTERNS));
+
 // ============================================================================
 // MATH PATTERNS
 // ============================================================================
 
 fn build_math_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // Mathematical operations - high priority
         WeightedPattern::new(r"(?i)\b\d+\s*[\+\-\*\/\^]\s*\d+", 1.5),
         WeightedPattern::new(r"(?i)\bcalcul(a|e|ate)", 1.0),
@@ -153,11 +229,7 @@ fn build_math_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\bradius\s+\d+", 1.2),
         WeightedPattern::new(r"(?i)\bsimplify\b", 1.0),
         WeightedPattern::new(r"(?i)\b\d+x\s*[\+\-]\s*\d+", 1.5), // Algebraic expressions like 3x + 2
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_math_patterns_es());
-    patterns
+    ]
 }
 
 // ============================================================================
@@ -165,7 +237,7 @@ fn build_math_patterns() -> Vec<WeightedPattern> {
 // ============================================================================
 
 fn build_code_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // Programming keywords
         WeightedPattern::new(r"(?i)\bcode\b", 1.0),
         WeightedPattern::new(r"(?i)\bprogram(ming)?\b", 1.0),
@@ -250,11 +322,7 @@ fn build_code_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\bclass\s+\w+\s*[:\{]", 1.5), // Class definition
         WeightedPattern::new(r"(?i)=>\s*\{", 1.2), // Arrow function
         WeightedPattern::new(r"(?i)\breturn\s+\w+", 1.0), // Return statement
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_code_patterns_es());
-    patterns
+    ]
 }
 
 // ============================================================================
@@ -262,7 +330,7 @@ fn build_code_patterns() -> Vec<WeightedPattern> {
 // ============================================================================
 
 fn build_reasoning_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // Analysis - standard priority
         WeightedPattern::new(r"(?i)\banalyze\b", 1.0),
         WeightedPattern::new(r"(?i)\banalysis\b", 1.0),
@@ -335,11 +403,7 @@ fn build_reasoning_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\bwhich\s+(is|one\s+is)\s+better\b", 1.5),
         WeightedPattern::new(r"(?i)\bbetter\s+to\s+(use|learn|choose)\b", 1.5),
         WeightedPattern::new(r"(?i)\b(python|javascript|rust)\s+(or|vs\.?)\s+(python|javascript|rust)\b", 1.5),
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_reasoning_patterns_es());
-    patterns
+    ]
 }
 
 // ============================================================================
@@ -347,7 +411,7 @@ fn build_reasoning_patterns() -> Vec<WeightedPattern> {
 // ============================================================================
 
 fn build_tools_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // Search - high priority
         WeightedPattern::new(r"(?i)\bsearch\s+(for|the\s+web)\b", 1.2),
         WeightedPattern::new(r"(?i)\blook\s+up\b", 1.0),
@@ -393,11 +457,7 @@ fn build_tools_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\blatest\s+(news|updates?)\b", 1.2),
         WeightedPattern::new(r"(?i)\bcurrent\s+(price|weather|time)\b", 1.2),
         WeightedPattern::new(r"(?i)\btoday'?s?\s+(weather|news|date)\b", 1.2),
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_tools_patterns_es());
-    patterns
+    ]
 }
 
 // ============================================================================
@@ -405,7 +465,7 @@ fn build_tools_patterns() -> Vec<WeightedPattern> {
 // ============================================================================
 
 fn build_greeting_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // Direct greetings - very high priority
         WeightedPattern::new(r"(?i)^(hi|hello|hey)\b", 2.0),
         WeightedPattern::new(r"(?i)^good\s+(morning|afternoon|evening|night)\b", 2.0),
@@ -433,11 +493,7 @@ fn build_greeting_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\bwhat\s+is\s+your\s+name\b", 1.5),
         WeightedPattern::new(r"(?i)\bwhat\s+can\s+you\s+do\b", 1.5),
         WeightedPattern::new(r"(?i)\btell\s+me\s+about\s+yourself\b", 1.5),
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_greeting_patterns_es());
-    patterns
+    ]
 }
 
 // ============================================================================
@@ -445,7 +501,7 @@ fn build_greeting_patterns() -> Vec<WeightedPattern> {
 // ============================================================================
 
 fn build_factual_patterns() -> Vec<WeightedPattern> {
-    let mut patterns = vec![
+    vec![
         // What/Who/When/Where questions - high priority
         WeightedPattern::new(r"(?i)^what\s+is\b", 1.5),
         WeightedPattern::new(r"(?i)^what\s+are\b", 1.5),
@@ -495,11 +551,109 @@ fn build_factual_patterns() -> Vec<WeightedPattern> {
         WeightedPattern::new(r"(?i)\bcreator\s+of\b", 1.5),
         WeightedPattern::new(r"(?i)\bwhen\s+was\s+.*\binvented\b", 2.0),
         WeightedPattern::new(r"(?i)\bwhen\s+was\s+.*\bdiscovered\b", 2.0),
-    ];
-    
-    // Add Spanish patterns
-    patterns.extend(patterns_es::build_factual_patterns_es());
-    patterns
+    ]
+}
+
+// ============================================================================
+// SUMMARIZATION PATTERNS
+// ============================================================================
+
+fn build_summarization_patterns() -> Vec<WeightedPattern> {
+    vec![
+        // Direct requests - high priority
+        WeightedPattern::new(r"(?i)\bsummari[sz](e|ing)\b", 2.0),
+        WeightedPattern::new(r"(?i)\bsummary\s+of\b", 2.0),
+        WeightedPattern::new(r"(?i)\b(give|write|make)\s+(me\s+)?a\s+(short\s+|brief\s+)?summary\b", 2.0),
+        WeightedPattern::new(r"(?i)\btl;?\s?dr\b", 2.0),
+        WeightedPattern::new(r"(?i)\bsum\s+(it|this|that)\s+up\b", 2.0),
+        WeightedPattern::new(r"(?i)\bboil\s+(it|this|that)\s+down\b", 1.5),
+
+        // Condensing
+        WeightedPattern::new(r"(?i)\b(key|main)\s+(points|takeaways|ideas)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bcondense\b", 1.2),
+        WeightedPattern::new(r"(?i)\brecap\b", 1.2),
+        WeightedPattern::new(r"(?i)\bbrief\s+overview\b", 1.2),
+        WeightedPattern::new(r"(?i)\bin\s+a\s+nutshell\b", 1.0),
+        WeightedPattern::new(r"(?i)\bshorten\s+(this|the)\b", 1.0),
+    ]
+}
+
+// ============================================================================
+// TRANSLATION PATTERNS
+// ============================================================================
+
+fn build_translation_patterns() -> Vec<WeightedPattern> {
+    vec![
+        // Weighted above the tools "translate" pattern
+        WeightedPattern::new(r"(?i)\btranslat(e|ion|ing)\b", 1.5),
+        WeightedPattern::new(r"(?i)\btranslate\s+.+\s+(to|into)\s+\w+", 2.0),
+        WeightedPattern::new(
+            r"(?i)\bhow\s+do\s+(you|i)\s+say\s+.+\s+in\s+(english|spanish|french|german|italian|portuguese|japanese|chinese)\b",
+            2.0,
+        ),
+        WeightedPattern::new(
+            r"(?i)\b(in|into|to)\s+(english|spanish|french|german|italian|portuguese|japanese|chinese)\s*[\?\.!]?$",
+            0.8,
+        ),
+    ]
+}
+
+// ============================================================================
+// CREATIVE WRITING PATTERNS
+// ============================================================================
+
+fn build_creative_writing_patterns() -> Vec<WeightedPattern> {
+    vec![
+        // Write me a poem/story - very high priority
+        WeightedPattern::new(
+            r"(?i)\b(write|compose)\s+(me\s+)?(a|an|some)\s+(\w+\s+)?(poem|story|song|haiku|limerick|sonnet|lyrics|fable|tale|verses?)\b",
+            2.5,
+        ),
+        WeightedPattern::new(r"(?i)\b(poem|haiku|sonnet|limerick|lyrics|short\s+story|fairy\s+tale|bedtime\s+story)s?\b", 1.5),
+        WeightedPattern::new(r"(?i)\bscreenplay\b", 1.5),
+        WeightedPattern::new(r"(?i)\bonce\s+upon\s+a\s+time\b", 1.5),
+        WeightedPattern::new(r"(?i)\bcreative\s+writing\b", 1.5),
+        WeightedPattern::new(r"(?i)\brhym(e|es|ing)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bplot\s+(for|of)\s+a\b", 1.0),
+    ]
+}
+
+// ============================================================================
+// SENSITIVE PATTERNS
+// ============================================================================
+
+fn build_sensitive_patterns() -> Vec<WeightedPattern> {
+    vec![
+        // Self-harm - highest priority
+        WeightedPattern::new(r"(?i)\b(kill|hurt|harm|cut)\s+myself\b", 3.0),
+        WeightedPattern::new(r"(?i)\b(end|take)\s+my\s+(own\s+)?life\b", 3.0),
+        WeightedPattern::new(r"(?i)\bself[-\s]?harm(ing)?\b", 3.0),
+        WeightedPattern::new(r"(?i)\bi\s+(just\s+|really\s+)?want\s+to\s+die\b", 2.5),
+        // A bare mention may be a factual question ("suicide rates in Japan")
+        WeightedPattern::new(r"(?i)\bsuicid(e|al)\b", 1.5),
+
+        // Weapons and violence
+        WeightedPattern::new(
+            r"(?i)\b(make|build|assemble)\s+(a\s+|an\s+)?(homemade\s+)?(bomb|pipe\s+bomb|explosives?|molotov|ied)([^\w-]|$)",
+            3.0,
+        ),
+        WeightedPattern::new(
+            r"(?i)\bhow\s+(to|do\s+i|can\s+i)\s+(kill|poison|murder|strangle)\s+(someone|somebody|a\s+person|him|her|my\s+(wife|husband|partner|girlfriend|boyfriend|mom|mother|dad|father|parents|brother|sister|son|daughter|baby|kids|children|boss|neighbou?r|friend|roommate|teacher|coworker))\b",
+            3.0,
+        ),
+
+        // Illegal instructions
+        WeightedPattern::new(
+            r"(?i)\b(synthesi[sz]e|cook|make|manufacture)\s+(meth(amphetamine)?|fentanyl|heroin|cocaine)\b",
+            3.0,
+        ),
+        WeightedPattern::new(
+            r"(?i)\b(hack\s+into|break\s+into|steal)\s+(someone'?s?|somebody'?s?|an?|my\s+\w+'?s?)\s+(account|password|email|phone|bank|credit\s+card)s?\b",
+            2.5,
+        ),
+        // Only instructions: "what is money laundering?" is a factual question
+        WeightedPattern::new(r"(?i)\bhow\s+(to|do\s+i|can\s+i)\s+launder\s+(money|cash)\b", 2.5),
+    ]
 }
 
 // ============================================================================
@@ -514,104 +668,173 @@ mod tests {
         QueryPatterns::score_category(patterns, text)
     }
 
+    fn en_es() -> QueryPatterns {
+        QueryPatterns::with_languages(&[Language::English, Language::Spanish])
+    }
+
     #[test]
     fn test_math_patterns() {
-        let patterns = compile_patterns(&build_math_patterns());
-        assert!(test_score(&patterns, "what is 2 + 2?") > 0.0);
-        assert!(test_score(&patterns, "Calculate the sum") > 0.0);
-        assert!(test_score(&patterns, "solve this equation") > 0.0);
-        assert!(test_score(&patterns, "what is the derivative of x^2") > 0.0);
-        assert!(test_score(&patterns, "cuánto es 5 + 3") > 0.0); // Spanish
-        assert!(test_score(&patterns, "hello world") == 0.0);
+        let patterns = &en_es().math;
+        assert!(test_score(patterns, "what is 2 + 2?") > 0.0);
+        assert!(test_score(patterns, "Calculate the sum") > 0.0);
+        assert!(test_score(patterns, "solve this equation") > 0.0);
+        assert!(test_score(patterns, "what is the derivative of x^2") > 0.0);
+        assert!(test_score(patterns, "cuánto es 5 + 3") > 0.0); // Spanish
+        assert!(test_score(patterns, "hello world") == 0.0);
     }
 
     #[test]
     fn test_math_word_problems() {
-        let patterns = compile_patterns(&build_math_patterns());
-        assert!(test_score(&patterns, "If I have 5 apples and give away 2") > 0.0);
-        assert!(test_score(&patterns, "What is the area of a circle with radius 5") > 0.0);
+        let patterns = &en_es().math;
+        assert!(test_score(patterns, "If I have 5 apples and give away 2") > 0.0);
+        assert!(test_score(patterns, "What is the area of a circle with radius 5") > 0.0);
     }
 
     #[test]
     fn test_code_patterns() {
-        let patterns = compile_patterns(&build_code_patterns());
-        assert!(test_score(&patterns, "write a function in Python") > 0.0);
-        assert!(test_score(&patterns, "how to implement a class") > 0.0);
-        assert!(test_score(&patterns, "fix the bug") > 0.0);
-        assert!(test_score(&patterns, "```python\nprint('hello')```") > 0.0);
+        let patterns = &en_es().code;
+        assert!(test_score(patterns, "write a function in Python") > 0.0);
+        assert!(test_score(patterns, "how to implement a class") > 0.0);
+        assert!(test_score(patterns, "fix the bug") > 0.0);
+        assert!(test_score(patterns, "```python\nprint('hello')```") > 0.0);
         // NEW: SQL
-        assert!(test_score(&patterns, "Write a SQL query to select all users") > 0.0);
+        assert!(test_score(patterns, "Write a SQL query to select all users") > 0.0);
         // NEW: Regex
-        assert!(test_score(&patterns, "Create a regex to match email addresses") > 0.0);
+        assert!(test_score(patterns, "Create a regex to match email addresses") > 0.0);
         // Spanish
-        assert!(test_score(&patterns, "escribe una función en Python") > 0.0);
-        assert!(test_score(&patterns, "what is the weather?") == 0.0);
+        assert!(test_score(patterns, "escribe una función en Python") > 0.0);
+        assert!(test_score(patterns, "what is the weather?") == 0.0);
     }
 
     #[test]
     fn test_greeting_patterns() {
-        let patterns = compile_patterns(&build_greeting_patterns());
-        assert!(test_score(&patterns, "hello") > 0.0);
-        assert!(test_score(&patterns, "Hi there!") > 0.0);
-        assert!(test_score(&patterns, "Good morning") > 0.0);
-        assert!(test_score(&patterns, "what is your name") > 0.0);
-        assert!(test_score(&patterns, "hola") > 0.0); // Spanish
-        assert!(test_score(&patterns, "buenos días") > 0.0); // Spanish
-        assert!(test_score(&patterns, "what is the capital of France") == 0.0);
+        let patterns = &en_es().greeting;
+        assert!(test_score(patterns, "hello") > 0.0);
+        assert!(test_score(patterns, "Hi there!") > 0.0);
+        assert!(test_score(patterns, "Good morning") > 0.0);
+        assert!(test_score(patterns, "what is your name") > 0.0);
+        assert!(test_score(patterns, "hola") > 0.0); // Spanish
+        assert!(test_score(patterns, "buenos días") > 0.0); // Spanish
+        assert!(test_score(patterns, "what is the capital of France") == 0.0);
     }
 
     #[test]
     fn test_factual_patterns() {
-        let patterns = compile_patterns(&build_factual_patterns());
-        assert!(test_score(&patterns, "What is the capital of France?") > 0.0);
-        assert!(test_score(&patterns, "Who was Albert Einstein?") > 0.0);
-        assert!(test_score(&patterns, "When was World War 2?") > 0.0);
-        assert!(test_score(&patterns, "define photosynthesis") > 0.0);
+        let patterns = &en_es().factual;
+        assert!(test_score(patterns, "What is the capital of France?") > 0.0);
+        assert!(test_score(patterns, "Who was Albert Einstein?") > 0.0);
+        assert!(test_score(patterns, "When was World War 2?") > 0.0);
+        assert!(test_score(patterns, "define photosynthesis") > 0.0);
         // NEW: invented/discovered
-        assert!(test_score(&patterns, "Who invented the telephone?") > 0.0);
-        assert!(test_score(&patterns, "Who discovered penicillin?") > 0.0);
+        assert!(test_score(patterns, "Who invented the telephone?") > 0.0);
+        assert!(test_score(patterns, "Who discovered penicillin?") > 0.0);
         // Spanish
-        assert!(test_score(&patterns, "quién inventó el teléfono") > 0.0);
-        assert!(test_score(&patterns, "hello") == 0.0);
+        assert!(test_score(patterns, "quién inventó el teléfono") > 0.0);
+        assert!(test_score(patterns, "hello") == 0.0);
     }
 
     #[test]
     fn test_tools_patterns() {
-        let patterns = compile_patterns(&build_tools_patterns());
-        assert!(test_score(&patterns, "search the web for") > 0.0);
-        assert!(test_score(&patterns, "generate an image of") > 0.0);
-        assert!(test_score(&patterns, "translate to Spanish") > 0.0);
-        assert!(test_score(&patterns, "what's the weather") > 0.0);
+        let patterns = &en_es().tools;
+        assert!(test_score(patterns, "search the web for") > 0.0);
+        assert!(test_score(patterns, "generate an image of") > 0.0);
+        assert!(test_score(patterns, "translate to Spanish") > 0.0);
+        assert!(test_score(patterns, "what's the weather") > 0.0);
         // NEW: Image generation variants
-        assert!(test_score(&patterns, "Create an image of a sunset") > 0.0);
-        assert!(test_score(&patterns, "Draw me a cat") > 0.0);
+        assert!(test_score(patterns, "Create an image of a sunset") > 0.0);
+        assert!(test_score(patterns, "Draw me a cat") > 0.0);
         // Spanish
-        assert!(test_score(&patterns, "generar una imagen") > 0.0);
-        assert!(test_score(&patterns, "hello world") == 0.0);
+        assert!(test_score(patterns, "generar una imagen") > 0.0);
+        assert!(test_score(patterns, "hello world") == 0.0);
     }
 
     #[test]
     fn test_reasoning_patterns() {
-        let patterns = compile_patterns(&build_reasoning_patterns());
-        assert!(test_score(&patterns, "analyze the pros and cons") > 0.0);
-        assert!(test_score(&patterns, "why is the sky blue") > 0.0);
+        let patterns = &en_es().reasoning;
+        assert!(test_score(patterns, "analyze the pros and cons") > 0.0);
+        assert!(test_score(patterns, "why is the sky blue") > 0.0);
         // NEW: Hypothetical
-        assert!(test_score(&patterns, "What would happen if gravity didn't exist?") > 0.0);
-        assert!(test_score(&patterns, "What if we could time travel?") > 0.0);
+        assert!(test_score(patterns, "What would happen if gravity didn't exist?") > 0.0);
+        assert!(test_score(patterns, "What if we could time travel?") > 0.0);
         // NEW: Should I
-        assert!(test_score(&patterns, "Should I learn Python or JavaScript first?") > 0.0);
+        assert!(test_score(patterns, "Should I learn Python or JavaScript first?") > 0.0);
         // Spanish
-        assert!(test_score(&patterns, "qué pasaría si") > 0.0);
-        assert!(test_score(&patterns, "ventajas y desventajas") > 0.0);
-        assert!(test_score(&patterns, "hello") == 0.0);
+        assert!(test_score(patterns, "qué pasaría si") > 0.0);
+        assert!(test_score(patterns, "ventajas y desventajas") > 0.0);
+        assert!(test_score(patterns, "hello") == 0.0);
+    }
+
+    #[test]
+    fn test_summarization_patterns() {
+        let patterns = &en_es().summarization;
+        assert!(test_score(patterns, "summarize this article") > 0.0);
+        assert!(test_score(patterns, "give me a summary of the meeting") > 0.0);
+        assert!(test_score(patterns, "tl;dr please") > 0.0);
+        // Spanish
+        assert!(test_score(patterns, "resume este artículo") > 0.0);
+        assert!(test_score(patterns, "hello") == 0.0);
+    }
+
+    #[test]
+    fn test_translation_patterns() {
+        let patterns = &en_es().translation;
+        assert!(test_score(patterns, "translate 'hello' to French") > 0.0);
+        assert!(test_score(patterns, "how do you say thank you in Japanese") > 0.0);
+        // Spanish
+        assert!(test_score(patterns, "cómo se dice gato en inglés") > 0.0);
+        assert!(test_score(patterns, "hello") == 0.0);
+    }
+
+    #[test]
+    fn test_creative_writing_patterns() {
+        let patterns = &en_es().creative_writing;
+        assert!(test_score(patterns, "write a poem about autumn") > 0.0);
+        assert!(test_score(patterns, "compose a short story about a dragon") > 0.0);
+        // Spanish
+        assert!(test_score(patterns, "escríbeme un poema sobre el otoño") > 0.0);
+        assert!(test_score(patterns, "write a function") == 0.0);
+    }
+
+    #[test]
+    fn test_sensitive_patterns() {
+        let patterns = &en_es().sensitive;
+        assert!(test_score(patterns, "I want to kill myself") > 0.0);
+        assert!(test_score(patterns, "how do I make a pipe bomb") > 0.0);
+        assert!(test_score(patterns, "how to cook meth at home") > 0.0);
+        // Spanish
+        assert!(test_score(patterns, "quiero quitarme la vida") > 0.0);
+        assert!(test_score(patterns, "how do I kill a process in Linux") == 0.0);
+        assert!(test_score(patterns, "How do I kill my process in Linux?") == 0.0);
+        assert!(test_score(patterns, "how can I kill my child process") == 0.0);
+        assert!(test_score(patterns, "how to kill them all in the test suite") == 0.0);
+        assert!(test_score(patterns, "how do I poison my neighbor") > 0.0);
+        assert!(test_score(patterns, "are we going to die when the sun explodes") == 0.0);
+        assert!(test_score(patterns, "What is money laundering?") == 0.0);
+        assert!(test_score(patterns, "how to launder money through a shell company") > 0.0);
+        assert!(test_score(patterns, "how to make a bomb-proof backup strategy") == 0.0);
+    }
+
+    #[test]
+    fn test_language_selection() {
+        let english = QueryPatterns::with_languages(&[Language::English]);
+        assert_eq!(test_score(&english.greeting, "bonjour"), 0.0);
+        assert!(test_score(&english.greeting, "hello") > 0.0);
+
+        let french = QueryPatterns::with_languages(&[Language::French]);
+        assert!(test_score(&french.greeting, "bonjour") > 0.0);
+        assert_eq!(test_score(&french.greeting, "hello"), 0.0);
+
+        let all = QueryPatterns::new();
+        assert!(test_score(&all.greeting, "hallo") > 0.0);
+        assert!(test_score(&all.greeting, "olá") > 0.0);
     }
 
     #[test]
     fn test_weighted_scoring() {
-        let patterns = compile_patterns(&build_reasoning_patterns());
+        let patterns = &en_es().reasoning;
         // "pros and cons" has weight 2.0, should score higher
-        let score1 = test_score(&patterns, "pros and cons");
-        let score2 = test_score(&patterns, "analyze");
+        let score1 = test_score(patterns, "pros and cons");
+        let score2 = test_score(patterns, "analyze");
         assert!(score1 > score2, "Weighted pattern should score higher");
     }
 }
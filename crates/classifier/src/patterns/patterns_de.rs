@@ -0,0 +1,333 @@
+//! German language patterns for query classification
+//!
+//! This module contains regex patterns in German for each query category.
+
+use crate::patterns::WeightedPattern;
+
+/// Build German math patterns
+pub fn build_math_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Mathematische Operationen - hohe Priorität
+        WeightedPattern::new(r"(?i)\bwie\s*viel\s+(ist|sind|ergibt|macht)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bberechne(n)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bl[öo]se\s+(die|diese|folgende)\b", 1.0),
+
+        // Mathematische Begriffe
+        WeightedPattern::new(r"(?i)\bgleichung(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmathe(matik)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bformel(n)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bgeometrie\b", 1.0),
+        WeightedPattern::new(r"(?i)\btrigonometrie\b", 1.0),
+        WeightedPattern::new(r"(?i)\bableitung(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bintegral(e)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bstatistik\b", 1.0),
+        WeightedPattern::new(r"(?i)\bwahrscheinlichkeit\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprozent(satz)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbr[üu]che?\b", 0.8),
+        WeightedPattern::new(r"(?i)\bquadratwurzel\b", 1.5),
+        WeightedPattern::new(r"(?i)\blogarithmus\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprimzahl(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfakult[äa]t\b", 1.0),
+
+        // Grundrechenarten
+        WeightedPattern::new(r"(?i)\bsumme\s+(von|der)\b", 1.0),
+        WeightedPattern::new(r"(?i)\baddier(e|en)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bsubtrahier(e|en)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmultiplizier(e|en)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdividier(e|en)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdurchschnitt\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmittelwert\b", 1.0),
+        WeightedPattern::new(r"(?i)\bstandardabweichung\b", 1.0),
+
+        // Textaufgaben
+        WeightedPattern::new(r"(?i)\bwenn\s+ich\s+\d+", 1.0),
+        WeightedPattern::new(r"(?i)\bwie\s+viele\s+.*\b(übrig|insgesamt)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bgesamtpreis\b", 1.0),
+        WeightedPattern::new(r"(?i)\b\d+\s*%\s+von\b", 1.5),
+    ]
+}
+
+/// Build German code patterns
+pub fn build_code_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Programmierbegriffe
+        WeightedPattern::new(r"(?i)\bprogrammier(en|ung|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfunktion(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmethode(n)?\b", 0.8),
+        WeightedPattern::new(r"(?i)\bschleife(n)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bquellcode\b", 1.2),
+        WeightedPattern::new(r"(?i)\bzeichenkette(n)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bvariable(n)?\b", 1.0),
+
+        // Programmieraktionen
+        WeightedPattern::new(r"(?i)\bdebugg(en|e)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bkompilier(en|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bimplementier(en|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\brefaktorier(en|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfehler\s+(beheben|finden|im\s+code)\b", 1.2),
+        WeightedPattern::new(
+            r"(?i)\bschreib(e|en)?\s+(mir\s+)?(eine?\s+)?(funktion|programm|skript|klasse)\b",
+            1.2,
+        ),
+
+        // Technische Begriffe
+        WeightedPattern::new(r"(?i)\bsyntax\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbibliothek(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\balgorithmus\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdatenstruktur(en)?\b", 1.2),
+        WeightedPattern::new(r"(?i)\bdatenbank(en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bsql-abfrage\b", 1.2),
+        WeightedPattern::new(r"(?i)\bregul[äa]re(n|r)?\s+ausdr[üu]ck(e)?\b", 1.2),
+    ]
+}
+
+/// Build German reasoning patterns
+pub fn build_reasoning_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Analyse
+        WeightedPattern::new(r"(?i)\banalysier(e|en)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bvergleich(e|en)?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbewert(e|en|ung)\b", 1.0),
+
+        // Vor- und Nachteile - sehr hohe Priorität
+        WeightedPattern::new(r"(?i)\bvor-\s+und\s+nachteile\b", 2.0),
+        WeightedPattern::new(r"(?i)\bvorteile\s+und\s+nachteile\b", 2.0),
+        WeightedPattern::new(r"(?i)\bvorteile?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bnachteile?\b", 1.0),
+
+        // Warum - hohe Priorität
+        WeightedPattern::new(r"(?i)^warum\b", 2.0),
+        WeightedPattern::new(r"(?i)^wieso\b", 2.0),
+        WeightedPattern::new(r"(?i)\berkl[äa]re?\s+(mir\s+)?warum\b", 1.5),
+        WeightedPattern::new(r"(?i)\bbegr[üu]ndung\b", 1.0),
+        WeightedPattern::new(r"(?i)\bhypothese\b", 1.0),
+        WeightedPattern::new(r"(?i)\bschlussfolgerung\b", 1.0),
+
+        // Hypothetische Fragen - hohe Priorität
+        WeightedPattern::new(r"(?i)\bwas\s+w[äa]re,?\s+wenn\b", 2.0),
+        WeightedPattern::new(r"(?i)\bwas\s+w[üu]rde\s+passieren,?\s+wenn\b", 2.0),
+        WeightedPattern::new(r"(?i)\bstell\s+dir\s+vor\b", 1.5),
+        WeightedPattern::new(r"(?i)\bangenommen\b", 1.5),
+        WeightedPattern::new(r"(?i)\bwas\s+h[äa]ltst\s+du\s+von\b", 1.0),
+        WeightedPattern::new(r"(?i)^sollte\s+ich\b", 2.0),
+    ]
+}
+
+/// Build German tools patterns
+pub fn build_tools_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Suche - hohe Priorität
+        WeightedPattern::new(r"(?i)\bsuch(e|en)?\s+(im\s+)?(web|internet)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bim\s+internet\s+such(e|en)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bgoogle(n|\s+nach)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bherunterladen\b", 1.0),
+
+        // Bildgenerierung - hohe Priorität
+        WeightedPattern::new(
+            r"(?i)\b(generier|erstell|mal|zeichne)(e|en)?\s+(mir\s+)?(ein(e|en)?\s+)?(bild|foto|illustration)\b",
+            1.5,
+        ),
+        WeightedPattern::new(r"(?i)\bzeichne\s+(mir\s+)?(eine?n?)\b", 1.5),
+
+        // Aktionen
+        WeightedPattern::new(r"(?i)\bsende?\s+(eine?\s+)?(e-?mail|nachricht)\b", 1.0),
+        WeightedPattern::new(r"(?i)\berinnerung\b", 1.0),
+        WeightedPattern::new(r"(?i)\bwecker\b", 1.0),
+        WeightedPattern::new(r"(?i)\bkalender\b", 1.0),
+
+        // Echtzeit-Informationen
+        WeightedPattern::new(r"(?i)\bwetter\b", 1.0),
+        WeightedPattern::new(r"(?i)\btemperatur\b", 1.0),
+        WeightedPattern::new(r"(?i)\bnachrichten\b", 1.0),
+        WeightedPattern::new(r"(?i)\baktienkurs\b", 1.0),
+        WeightedPattern::new(r"(?i)\b[üu]bersetz(e|en|ung)\b", 1.0),
+    ]
+}
+
+/// Build German greeting patterns
+pub fn build_greeting_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Direkte Begrüßungen - sehr hohe Priorität
+        WeightedPattern::new(r"(?i)^hallo\b", 2.0),
+        WeightedPattern::new(r"(?i)^guten\s+(morgen|tag|abend)\b", 2.0),
+        WeightedPattern::new(r"(?i)^servus\b", 2.0),
+        WeightedPattern::new(r"(?i)^moin\b", 2.0),
+        WeightedPattern::new(r"(?i)^gr[üu](ß|ss)\s+gott\b", 2.0),
+        WeightedPattern::new(r"(?i)^wie\s+geht('s|\s+es\s+(dir|ihnen))\b", 2.0),
+        WeightedPattern::new(r"(?i)\bfreut\s+mich\b", 1.5),
+
+        // Verabschiedungen
+        WeightedPattern::new(r"(?i)\bauf\s+wiedersehen\b", 1.0),
+        WeightedPattern::new(r"(?i)\btsch[üu]ss\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbis\s+(bald|sp[äa]ter|morgen)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bgute\s+nacht\b", 1.0),
+
+        // Höflichkeit
+        WeightedPattern::new(r"(?i)^danke\b", 1.0),
+        WeightedPattern::new(r"(?i)^vielen\s+dank\b", 1.0),
+        WeightedPattern::new(r"(?i)^bitte\b", 0.8),
+        WeightedPattern::new(r"(?i)^entschuldigung\b", 1.0),
+
+        // Über den Assistenten
+        WeightedPattern::new(r"(?i)\bwer\s+bist\s+du\b", 1.5),
+        WeightedPattern::new(r"(?i)\bwie\s+hei(ß|ss)t\s+du\b", 1.5),
+        WeightedPattern::new(r"(?i)\bwas\s+kannst\s+du\b", 1.5),
+    ]
+}
+
+/// Build German factual patterns
+pub fn build_factual_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Was/Wer/Wann/Wo-Fragen - hohe Priorität
+        WeightedPattern::new(r"(?i)^was\s+(ist|sind|war|waren)\b", 1.5),
+        WeightedPattern::new(r"(?i)^wer\s+(ist|war|hat)\b", 1.5),
+        WeightedPattern::new(r"(?i)^wann\s+(ist|war|wurde)\b", 1.5),
+        WeightedPattern::new(r"(?i)^wo\s+(ist|liegt|befindet\s+sich)\b", 1.5),
+        WeightedPattern::new(r"(?i)^welche(r|s)?\s+(ist|sind|war)\b", 1.5),
+
+        // Definitionen
+        WeightedPattern::new(r"(?i)\bdefinition\s+von\b", 1.0),
+        WeightedPattern::new(r"(?i)\bwas\s+bedeutet\b", 1.0),
+
+        // Geschichte und Herkunft
+        WeightedPattern::new(r"(?i)\bgeschichte\s+(von|der|des)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bursprung\s+(von|der|des)\b", 1.0),
+        WeightedPattern::new(r"(?i)\berz[äa]hl\s+mir\s+(etwas\s+)?[üu]ber\b", 1.0),
+
+        // Spezifische Daten
+        WeightedPattern::new(r"(?i)\bhauptstadt\s+(von|der|des)\b", 1.2),
+        WeightedPattern::new(r"(?i)\beinwohner(zahl)?\s+(von|der|des)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bpr[äa]sident(in)?\s+(von|der|des)\b", 1.2),
+
+        // Erfinder - hohe Priorität
+        WeightedPattern::new(r"(?i)\bwer\s+hat\s+.*\berfunden\b", 2.0),
+        WeightedPattern::new(r"(?i)\bwer\s+hat\s+.*\bentdeckt\b", 2.0),
+        WeightedPattern::new(r"(?i)\berfinder\s+(von|der|des)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bautor\s+(von|der|des)\b", 1.0),
+    ]
+}
+
+/// Build German summarization patterns
+pub fn build_summarization_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Zusammenfassen - hohe Priorität
+        WeightedPattern::new(r"(?i)\bfass(e)?\s+.*\bzusammen\b", 2.0),
+        WeightedPattern::new(r"(?i)\bzusammenfass(en|ung)\b", 2.0),
+        WeightedPattern::new(r"(?i)\bkernpunkte\b", 1.5),
+        WeightedPattern::new(r"(?i)\bdas\s+wichtigste\b", 1.0),
+        WeightedPattern::new(r"(?i)\bin\s+wenigen\s+worten\b", 1.0),
+    ]
+}
+
+/// Build German translation patterns
+pub fn build_translation_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Übersetzen - über dem Werkzeug-Muster
+        WeightedPattern::new(r"(?i)\b[üu]bersetz(e|en|ung)\b", 1.5),
+        WeightedPattern::new(
+            r"(?i)\b[üu]bersetz\w*\s+.+\s+(ins|auf)\s+(englisch|spanisch|franz[öo]sisch|italienisch|portugiesisch|deutsch)e?\b",
+            2.0,
+        ),
+        WeightedPattern::new(r"(?i)\bwie\s+sagt\s+man\b", 2.0),
+    ]
+}
+
+/// Build German creative writing patterns
+pub fn build_creative_writing_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Schreib mir ein Gedicht - sehr hohe Priorität
+        WeightedPattern::new(
+            r"(?i)\b(schreib(e)?|verfass(e)?|dichte)\s+(mir\s+)?(ein|eine|einen)\s+(\w+\s+)?(gedicht|geschichte|m[äa]rchen|lied|songtext|haiku|sonett|fabel|erz[äa]hlung)\b",
+            2.5,
+        ),
+        WeightedPattern::new(r"(?i)\b(gedicht|m[äa]rchen|sonett|fabel)e?s?\b", 1.5),
+        WeightedPattern::new(r"(?i)\bes\s+war\s+einmal\b", 1.5),
+        WeightedPattern::new(r"(?i)\bkreatives\s+schreiben\b", 1.5),
+    ]
+}
+
+/// Build German sensitive patterns
+pub fn build_sensitive_patterns_de() -> Vec<WeightedPattern> {
+    vec![
+        // Selbstverletzung - höchste Priorität
+        WeightedPattern::new(r"(?i)\b(mich\s+umbringen|mich\s+selbst\s+verletzen|mir\s+das\s+leben\s+nehmen)\b", 3.0),
+        WeightedPattern::new(r"(?i)\bich\s+will\s+sterben\b", 2.5),
+        WeightedPattern::new(r"(?i)\b(selbstmord|suizid)\w*\b", 1.5),
+        // Waffen, Gewalt und illegale Anleitungen
+        WeightedPattern::new(r"(?i)\b(eine\s+)?bombe\s+(bauen|basteln|herstellen)\b", 3.0),
+        WeightedPattern::new(r"(?i)\bwie\s+(t[öo]te|vergifte|ermorde)\s+ich\b", 3.0),
+        WeightedPattern::new(
+            r"(?i)\b(crystal\s+meth|methamphetamin|fentanyl|heroin|kokain)\s+(herstellen|kochen|synthetisieren)\b",
+            3.0,
+        ),
+        // Nur Anleitungen: "Was ist Geldwäsche?" ist eine Sachfrage
+        WeightedPattern::new(r"(?i)\bwie\s+(kann\s+(ich|man)\s+geld\s+waschen|wasche\s+ich\s+geld)\b", 2.5),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn test_patterns_match(patterns: &[WeightedPattern], text: &str) -> bool {
+        patterns
+            .iter()
+            .filter_map(|p| Regex::new(p.pattern).ok())
+            .any(|re| re.is_match(text))
+    }
+
+    #[test]
+    fn test_math_german() {
+        let patterns = build_math_patterns_de();
+        assert!(test_patterns_match(&patterns, "wie viel ist 5 + 3"));
+        assert!(test_patterns_match(&patterns, "berechne die Quadratwurzel von 16"));
+        assert!(test_patterns_match(&patterns, "löse die Gleichung"));
+    }
+
+    #[test]
+    fn test_code_german() {
+        let patterns = build_code_patterns_de();
+        assert!(test_patterns_match(&patterns, "schreibe eine Funktion in Python"));
+        assert!(test_patterns_match(&patterns, "wie kann ich das debuggen"));
+    }
+
+    #[test]
+    fn test_greeting_german() {
+        let patterns = build_greeting_patterns_de();
+        assert!(test_patterns_match(&patterns, "hallo"));
+        assert!(test_patterns_match(&patterns, "guten Morgen"));
+        assert!(test_patterns_match(&patterns, "wer bist du?"));
+    }
+
+    #[test]
+    fn test_factual_german() {
+        let patterns = build_factual_patterns_de();
+        assert!(test_patterns_match(&patterns, "was ist Photosynthese"));
+        assert!(test_patterns_match(&patterns, "wer hat das Telefon erfunden"));
+    }
+
+    #[test]
+    fn test_reasoning_german() {
+        let patterns = build_reasoning_patterns_de();
+        assert!(test_patterns_match(&patterns, "Vor- und Nachteile der Kernkraft"));
+        assert!(test_patterns_match(&patterns, "was wäre, wenn der Mond verschwindet"));
+    }
+
+    #[test]
+    fn test_new_categories_german() {
+        assert!(test_patterns_match(&build_summarization_patterns_de(), "fasse diesen Artikel zusammen"));
+        assert!(test_patterns_match(&build_translation_patterns_de(), "wie sagt man Katze auf Englisch"));
+        assert!(test_patterns_match(
+            &build_creative_writing_patterns_de(),
+            "schreib mir ein Gedicht über den Herbst"
+        ));
+    }
+
+    #[test]
+    fn test_sensitive_german() {
+        let patterns = build_sensitive_patterns_de();
+        assert!(test_patterns_match(&patterns, "ich will mich umbringen"));
+        assert!(test_patterns_match(&patterns, "wie kann ich eine Bombe bauen"));
+    }
+}
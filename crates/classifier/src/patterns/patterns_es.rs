@@ -320,6 +320,72 @@ pub fn build_factual_patterns_es() -> Vec<WeightedPattern> {
     ]
 }
 
+/// Build Spanish summarization patterns
+pub fn build_summarization_patterns_es() -> Vec<WeightedPattern> {
+    vec![
+        // Resumir - alta prioridad
+        WeightedPattern::new(r"(?i)\bres[uú]me(lo|me|n)?\b", 2.0),
+        WeightedPattern::new(r"(?i)\bresumir\b", 2.0),
+        WeightedPattern::new(r"(?i)\bresumen\s+de\b", 2.0),
+        WeightedPattern::new(r"(?i)\bhaz(me)?\s+un\s+resumen\b", 2.0),
+        WeightedPattern::new(r"(?i)\bsintetiza(r)?\b", 1.5),
+
+        // Condensar
+        WeightedPattern::new(r"(?i)\bpuntos\s+(clave|principales)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bideas\s+principales\b", 1.5),
+        WeightedPattern::new(r"(?i)\ben\s+pocas\s+palabras\b", 1.0),
+    ]
+}
+
+/// Build Spanish translation patterns
+pub fn build_translation_patterns_es() -> Vec<WeightedPattern> {
+    vec![
+        // Traducir - por encima del patrón de herramientas
+        WeightedPattern::new(r"(?i)\btraduc(e|ir|eme|elo|ci[oó]n)\b", 1.5),
+        WeightedPattern::new(
+            r"(?i)\btraduc\w*\s+.+\s+al?\s+(ingl[eé]s|espa[ñn]ol|franc[eé]s|alem[aá]n|italiano|portugu[eé]s)\b",
+            2.0,
+        ),
+        WeightedPattern::new(r"(?i)\bc[oó]mo\s+se\s+dice\b", 2.0),
+        WeightedPattern::new(r"(?i)\bqu[eé]\s+significa\s+.+\s+en\s+(ingl[eé]s|espa[ñn]ol)\b", 1.5),
+    ]
+}
+
+/// Build Spanish creative writing patterns
+pub fn build_creative_writing_patterns_es() -> Vec<WeightedPattern> {
+    vec![
+        // Escríbeme un poema/cuento - muy alta prioridad
+        WeightedPattern::new(
+            r"(?i)\b(escr[ií]be(me)?|redacta(me)?|comp[oó]n(me)?)\s+(un|una|unos|unas)\s+(\w+\s+)?(poema|cuento|historia|canci[oó]n|relato|haiku|soneto|f[aá]bula|versos?)\b",
+            2.5,
+        ),
+        WeightedPattern::new(r"(?i)\b(poema|cuento|relato|soneto|f[aá]bula|verso)s?\b", 1.5),
+        WeightedPattern::new(r"(?i)\b[eé]rase\s+una\s+vez\b", 1.5),
+        WeightedPattern::new(r"(?i)\bescritura\s+creativa\b", 1.5),
+        WeightedPattern::new(r"(?i)\brim(a|as|ando)\b", 1.0),
+    ]
+}
+
+/// Build Spanish sensitive patterns
+pub fn build_sensitive_patterns_es() -> Vec<WeightedPattern> {
+    vec![
+        // Autolesión - máxima prioridad
+        WeightedPattern::new(r"(?i)\b(matarme|suicidarme|hacerme\s+da[ñn]o)\b", 3.0),
+        WeightedPattern::new(r"(?i)\bquitarme\s+la\s+vida\b", 3.0),
+        WeightedPattern::new(r"(?i)\bquiero\s+morir(me)?\b", 2.5),
+        WeightedPattern::new(r"(?i)\bsuicid(io|a)\b", 1.5),
+        // Armas, violencia e instrucciones ilegales
+        WeightedPattern::new(r"(?i)\b(hacer|fabricar|construir)\s+(una\s+)?(bomba|explosivos?|molotov)\b", 3.0),
+        WeightedPattern::new(r"(?i)\bc[oó]mo\s+(matar|envenenar|asesinar)\s+a\b", 3.0),
+        WeightedPattern::new(
+            r"(?i)\b(fabricar|cocinar|sintetizar|hacer)\s+(metanfetamina|fentanilo|hero[ií]na|coca[ií]na)\b",
+            3.0,
+        ),
+        // Solo instrucciones: "¿qué es el lavado de dinero?" es una pregunta factual
+        WeightedPattern::new(r"(?i)\bc[oó]mo\s+(puedo\s+)?lavar\s+dinero\b", 2.5),
+    ]
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,334 @@
+//! French language patterns for query classification
+//!
+//! This module contains regex patterns in French for each query category.
+
+use crate::patterns::WeightedPattern;
+
+/// Build French math patterns
+pub fn build_math_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Opérations mathématiques - haute priorité
+        WeightedPattern::new(r"(?i)\bcombien\s+(font|fait|vaut|valent)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bcalcul(e|er|ez)\b", 1.0),
+        WeightedPattern::new(r"(?i)\br[ée]sou(s|dre|dez)\b", 1.0),
+
+        // Termes mathématiques
+        WeightedPattern::new(r"(?i)\b[ée]quations?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmath[ée]matiques?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bformules?\b", 1.0),
+        WeightedPattern::new(r"(?i)\balg[eè]bre\b", 1.0),
+        WeightedPattern::new(r"(?i)\bg[ée]om[ée]trie\b", 1.0),
+        WeightedPattern::new(r"(?i)\btrigonom[ée]trie\b", 1.0),
+        WeightedPattern::new(r"(?i)\bd[ée]riv[ée]es?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bint[ée]grales?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bstatistiques?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprobabilit[ée]s?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bpourcentages?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bracine\s+carr[ée]e\b", 1.5),
+        WeightedPattern::new(r"(?i)\blogarithmes?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bnombres?\s+premiers?\b", 1.0),
+
+        // Opérations de base
+        WeightedPattern::new(r"(?i)\bsomme\s+de\b", 1.0),
+        WeightedPattern::new(r"(?i)\badditionn(e|er|ez)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bsoustrai(s|re|t)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmultipli(e|er|ez|cation)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdivis(e|er|ez)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmoyenne\b", 1.0),
+        WeightedPattern::new(r"(?i)\bm[ée]diane\b", 1.0),
+        WeightedPattern::new(r"(?i)\b[ée]cart[\s-]type\b", 1.0),
+
+        // Problèmes
+        WeightedPattern::new(r"(?i)\bsi\s+j'ai\s+\d+", 1.0),
+        WeightedPattern::new(r"(?i)\bs'il\s+y\s+a\s+\d+", 1.0),
+        WeightedPattern::new(r"(?i)\bcombien\s+(en\s+)?reste", 1.0),
+        WeightedPattern::new(r"(?i)\bau\s+total\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprix\s+total\b", 1.0),
+        WeightedPattern::new(r"(?i)\b\d+\s*%\s+d[e']", 1.5),
+    ]
+}
+
+/// Build French code patterns
+pub fn build_code_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Termes de programmation
+        WeightedPattern::new(r"(?i)\bprogramm(e|er|ation)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfonctions?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bm[ée]thodes?\b", 0.8),
+        WeightedPattern::new(r"(?i)\bboucles?\b", 1.0),
+        WeightedPattern::new(r"(?i)\btableaux?\b", 0.8),
+        WeightedPattern::new(r"(?i)\bdictionnaires?\b", 0.8),
+        WeightedPattern::new(r"(?i)\bcha[iî]nes?\s+de\s+caract[eè]res\b", 1.2),
+        WeightedPattern::new(r"(?i)\bbool[ée]en\b", 1.0),
+
+        // Actions de programmation
+        WeightedPattern::new(r"(?i)\bd[ée]bogu(er|ez|e)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bcompil(er|ez|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bimpl[ée]ment(er|ez|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\brefactor(iser|isez|ise)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcorrig(er|ez|e)\s+(le\s+|ce\s+)?(bug|bogue|erreur)\b", 1.2),
+        WeightedPattern::new(
+            r"(?i)\b[ée]cri(s|re|vez)\s+(un(e)?\s+)?(fonction|programme|script|classe)\b",
+            1.2,
+        ),
+
+        // Termes techniques
+        WeightedPattern::new(r"(?i)\bsyntaxe\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbiblioth[eè]ques?\b", 1.0),
+        WeightedPattern::new(r"(?i)\balgorithmes?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bstructures?\s+de\s+donn[ée]es\b", 1.2),
+        WeightedPattern::new(r"(?i)\bbases?\s+de\s+donn[ée]es\b", 1.0),
+        WeightedPattern::new(r"(?i)\brequ[eê]te\s+sql\b", 1.2),
+        WeightedPattern::new(r"(?i)\bexpressions?\s+r[ée]guli[eè]res?\b", 1.2),
+    ]
+}
+
+/// Build French reasoning patterns
+pub fn build_reasoning_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Analyse
+        WeightedPattern::new(r"(?i)\banalys(e|er|ez)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcompar(e|er|ez|aison)\b", 1.0),
+        WeightedPattern::new(r"(?i)\b[ée]valu(e|er|ez|ation)\b", 1.0),
+
+        // Avantages et inconvénients - très haute priorité
+        WeightedPattern::new(r"(?i)\bavantages\s+et\s+(les\s+)?inconv[ée]nients\b", 2.0),
+        WeightedPattern::new(r"(?i)\ble\s+pour\s+et\s+le\s+contre\b", 2.0),
+        WeightedPattern::new(r"(?i)\bavantages?\b", 1.0),
+        WeightedPattern::new(r"(?i)\binconv[ée]nients?\b", 1.0),
+
+        // Pourquoi - haute priorité
+        WeightedPattern::new(r"(?i)^pourquoi\b", 2.0),
+        WeightedPattern::new(r"(?i)\bexpliqu(e|er|ez)(-moi)?\s+pourquoi\b", 1.5),
+        WeightedPattern::new(r"(?i)\braisonnement\b", 1.0),
+        WeightedPattern::new(r"(?i)\bhypoth[eè]ses?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bconclusions?\b", 1.0),
+
+        // Questions hypothétiques - haute priorité
+        WeightedPattern::new(r"(?i)\bque\s+se\s+passerait-il\s+si\b", 2.0),
+        WeightedPattern::new(r"(?i)\bqu'arriverait-il\s+si\b", 2.0),
+        WeightedPattern::new(r"(?i)\bimagin(e|ez|ons)\s+que\b", 1.5),
+        WeightedPattern::new(r"(?i)\bsuppos(e|ons|ez)\s+que\b", 1.5),
+        WeightedPattern::new(r"(?i)\bqu'en\s+penses-tu\b", 1.0),
+        WeightedPattern::new(r"(?i)^(est-ce\s+que\s+)?je\s+devrais\b", 2.0),
+        WeightedPattern::new(r"(?i)^dois-je\b", 2.0),
+    ]
+}
+
+/// Build French tools patterns
+pub fn build_tools_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Recherche - haute priorité
+        WeightedPattern::new(r"(?i)\bcherch(e|er|ez)\s+(sur\s+)?(le\s+)?(web|internet)\b", 1.5),
+        WeightedPattern::new(r"(?i)\brecherch(e|er|ez)\s+(sur\s+)?(le\s+)?(web|internet)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bt[ée]l[ée]charg(e|er|ez)\b", 1.0),
+
+        // Génération d'images - haute priorité
+        WeightedPattern::new(
+            r"(?i)\b(g[ée]n[èe]re|g[ée]n[ée]rer|cr[ée]e|cr[ée]er)\s+(une\s+)?(image|photo|illustration)\b",
+            1.5,
+        ),
+        WeightedPattern::new(r"(?i)\bdessine(-moi)?\s+(un|une)\b", 1.5),
+
+        // Actions
+        WeightedPattern::new(r"(?i)\benvoi(e|er|ez)\s+(un\s+)?(e-?mail|courriel|message)\b", 1.0),
+        WeightedPattern::new(r"(?i)\brappel\b", 0.8),
+        WeightedPattern::new(r"(?i)\bminuteur\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcalendrier\b", 1.0),
+
+        // Informations en temps réel
+        WeightedPattern::new(r"(?i)\bm[ée]t[ée]o\b", 1.0),
+        WeightedPattern::new(r"(?i)\btemp[ée]rature\b", 1.0),
+        WeightedPattern::new(r"(?i)\b(derni[eè]res\s+)?actualit[ée]s\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcours\s+de\s+l'action\b", 1.0),
+        WeightedPattern::new(r"(?i)\btradui(s|re|sez)\b", 1.0),
+    ]
+}
+
+/// Build French greeting patterns
+pub fn build_greeting_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Salutations directes - très haute priorité
+        WeightedPattern::new(r"(?i)^bonjour\b", 2.0),
+        WeightedPattern::new(r"(?i)^bonsoir\b", 2.0),
+        WeightedPattern::new(r"(?i)^salut\b", 2.0),
+        WeightedPattern::new(r"(?i)^coucou\b", 2.0),
+        WeightedPattern::new(r"(?i)^(comment\s+)?[çc]a\s+va\b", 2.0),
+        WeightedPattern::new(r"(?i)^comment\s+allez-vous\b", 2.0),
+        WeightedPattern::new(r"(?i)\benchant[ée](e)?\b", 1.5),
+
+        // Au revoir
+        WeightedPattern::new(r"(?i)\bau\s+revoir\b", 1.0),
+        WeightedPattern::new(r"(?i)\b[àa]\s+(bient[ôo]t|plus\s+tard|demain)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbonne\s+(journ[ée]e|soir[ée]e|nuit)\b", 1.0),
+
+        // Politesse
+        WeightedPattern::new(r"(?i)^merci\b", 1.0),
+        WeightedPattern::new(r"(?i)^s'il\s+(te|vous)\s+pla[iî]t\b", 1.0),
+        WeightedPattern::new(r"(?i)^pardon\b", 1.0),
+        WeightedPattern::new(r"(?i)^d[ée]sol[ée]\b", 1.0),
+
+        // Sur l'assistant
+        WeightedPattern::new(r"(?i)\bqui\s+es-tu\b", 1.5),
+        WeightedPattern::new(r"(?i)\bcomment\s+t'appelles-tu\b", 1.5),
+        WeightedPattern::new(r"(?i)\bque\s+(peux|sais)-tu\s+faire\b", 1.5),
+    ]
+}
+
+/// Build French factual patterns
+pub fn build_factual_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Questions qu'est-ce/qui/quand/où - haute priorité
+        WeightedPattern::new(r"(?i)^qu'est-ce\s+qu(e|')", 1.5),
+        WeightedPattern::new(r"(?i)^qui\s+(est|[ée]tait|a\s+[ée]t[ée])\b", 1.5),
+        WeightedPattern::new(r"(?i)^quand\s+(est|a|[ée]tait)\b", 1.5),
+        WeightedPattern::new(r"(?i)^o[ùu]\s+(est|se\s+trouve)\b", 1.5),
+        WeightedPattern::new(r"(?i)^quel(le)?s?\s+(est|sont|[ée]tait)\b", 1.5),
+
+        // Définitions
+        WeightedPattern::new(r"(?i)\bd[ée]finition\s+de\b", 1.0),
+        WeightedPattern::new(r"(?i)\bque\s+signifie\b", 1.0),
+
+        // Histoire et origine
+        WeightedPattern::new(r"(?i)\bhistoire\s+d[eu]\b", 1.0),
+        WeightedPattern::new(r"(?i)\borigine\s+d[eu]\b", 1.0),
+        WeightedPattern::new(r"(?i)\bparle-moi\s+d[eu]\b", 1.0),
+
+        // Données spécifiques
+        WeightedPattern::new(r"(?i)\bcapitale\s+d[eu]\b", 1.2),
+        WeightedPattern::new(r"(?i)\bpopulation\s+d[eu]\b", 1.2),
+        WeightedPattern::new(r"(?i)\bpr[ée]sident\s+d[eu]\b", 1.2),
+
+        // Inventeurs - haute priorité
+        WeightedPattern::new(r"(?i)\bqui\s+a\s+invent[ée]\b", 2.0),
+        WeightedPattern::new(r"(?i)\bqui\s+a\s+d[ée]couvert\b", 2.0),
+        WeightedPattern::new(r"(?i)\binventeur\s+d[eu]\b", 1.5),
+        WeightedPattern::new(r"(?i)\bauteur\s+d[eu]\b", 1.0),
+    ]
+}
+
+/// Build French summarization patterns
+pub fn build_summarization_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Résumer - haute priorité
+        WeightedPattern::new(r"(?i)\br[ée]sum(e|er|ez)\b", 2.0),
+        WeightedPattern::new(r"(?i)\bfais(-moi)?\s+un\s+r[ée]sum[ée]\b", 2.0),
+        WeightedPattern::new(r"(?i)\bsynth[ée]tis(e|er|ez)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bpoints\s+(cl[ée]s|principaux)\b", 1.5),
+        WeightedPattern::new(r"(?i)\ben\s+quelques\s+mots\b", 1.0),
+    ]
+}
+
+/// Build French translation patterns
+pub fn build_translation_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Traduire - au-dessus du motif des outils
+        WeightedPattern::new(r"(?i)\btradui(s|re|sez)(-moi|-le)?\b", 1.5),
+        WeightedPattern::new(
+            r"(?i)\btradu\w*\s+.+\s+en\s+(anglais|espagnol|allemand|italien|portugais|fran[çc]ais)\b",
+            2.0,
+        ),
+        WeightedPattern::new(r"(?i)\bcomment\s+(dit-on|on\s+dit)\b", 2.0),
+    ]
+}
+
+/// Build French creative writing patterns
+pub fn build_creative_writing_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Écris-moi un poème - très haute priorité
+        WeightedPattern::new(
+            r"(?i)\b([ée]cri(s|vez)(-moi)?|r[ée]dige(-moi)?|compose(-moi)?)\s+(un|une)\s+(\w+\s+)?(po[èe]me|histoire|conte|chanson|nouvelle|ha[iï]ku|sonnet|fable)\b",
+            2.5,
+        ),
+        WeightedPattern::new(r"(?i)\b(po[èe]me|conte|sonnet|fable)s?\b", 1.5),
+        WeightedPattern::new(r"(?i)\bil\s+[ée]tait\s+une\s+fois\b", 1.5),
+        WeightedPattern::new(r"(?i)\b[ée]criture\s+cr[ée]ative\b", 1.5),
+    ]
+}
+
+/// Build French sensitive patterns
+pub fn build_sensitive_patterns_fr() -> Vec<WeightedPattern> {
+    vec![
+        // Automutilation - priorité maximale
+        WeightedPattern::new(r"(?i)\b(me\s+suicider|me\s+tuer|me\s+faire\s+du\s+mal)\b", 3.0),
+        WeightedPattern::new(r"(?i)\bmettre\s+fin\s+[àa]\s+mes\s+jours\b", 3.0),
+        WeightedPattern::new(r"(?i)\bje\s+veux\s+mourir\b", 2.5),
+        WeightedPattern::new(r"(?i)\bsuicid(e|aire)\b", 1.5),
+        // Armes, violence et instructions illégales
+        WeightedPattern::new(r"(?i)\b(fabriquer|faire|construire)\s+une\s+bombe\b", 3.0),
+        WeightedPattern::new(r"(?i)\bcomment\s+(tuer|empoisonner|assassiner)\s+(quelqu'un|une\s+personne|mon|ma)\b", 3.0),
+        WeightedPattern::new(
+            r"(?i)\b(fabriquer|synth[ée]tiser|faire)\s+(de\s+la\s+|de\s+l')?(m[ée]thamph[ée]tamine|fentanyl|h[ée]ro[iï]ne|coca[iï]ne)\b",
+            3.0,
+        ),
+        // Seulement les instructions : "qu'est-ce que le blanchiment d'argent ?" est factuel
+        WeightedPattern::new(r"(?i)\bcomment\s+(puis-je\s+)?blanchir\s+(de\s+l')?argent\b", 2.5),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn test_patterns_match(patterns: &[WeightedPattern], text: &str) -> bool {
+        patterns
+            .iter()
+            .filter_map(|p| Regex::new(p.pattern).ok())
+            .any(|re| re.is_match(text))
+    }
+
+    #[test]
+    fn test_math_french() {
+        let patterns = build_math_patterns_fr();
+        assert!(test_patterns_match(&patterns, "combien font 5 + 3"));
+        assert!(test_patterns_match(&patterns, "calcule la racine carrée de 16"));
+        assert!(test_patterns_match(&patterns, "résous cette équation"));
+    }
+
+    #[test]
+    fn test_code_french() {
+        let patterns = build_code_patterns_fr();
+        assert!(test_patterns_match(&patterns, "écris une fonction en Python"));
+        assert!(test_patterns_match(&patterns, "comment déboguer ce programme"));
+    }
+
+    #[test]
+    fn test_greeting_french() {
+        let patterns = build_greeting_patterns_fr();
+        assert!(test_patterns_match(&patterns, "bonjour"));
+        assert!(test_patterns_match(&patterns, "salut, ça va ?"));
+        assert!(test_patterns_match(&patterns, "qui es-tu ?"));
+    }
+
+    #[test]
+    fn test_factual_french() {
+        let patterns = build_factual_patterns_fr();
+        assert!(test_patterns_match(&patterns, "qu'est-ce que la photosynthèse"));
+        assert!(test_patterns_match(&patterns, "qui a inventé le téléphone"));
+    }
+
+    #[test]
+    fn test_reasoning_french() {
+        let patterns = build_reasoning_patterns_fr();
+        assert!(test_patterns_match(&patterns, "avantages et inconvénients du nucléaire"));
+        assert!(test_patterns_match(&patterns, "que se passerait-il si la lune disparaissait"));
+    }
+
+    #[test]
+    fn test_new_categories_french() {
+        assert!(test_patterns_match(&build_summarization_patterns_fr(), "résume cet article"));
+        assert!(test_patterns_match(&build_translation_patterns_fr(), "comment dit-on chat en anglais"));
+        assert!(test_patterns_match(
+            &build_creative_writing_patterns_fr(),
+            "écris-moi un poème sur l'automne"
+        ));
+    }
+
+    #[test]
+    fn test_sensitive_french() {
+        let patterns = build_sensitive_patterns_fr();
+        assert!(test_patterns_match(&patterns, "je veux me suicider"));
+        assert!(test_patterns_match(&patterns, "comment fabriquer une bombe"));
+    }
+}
@@ -0,0 +1,317 @@
+//! Portuguese language patterns for query classification
+//!
+//! This module contains regex patterns in Portuguese for each query category.
+
+use crate::patterns::WeightedPattern;
+
+/// Build Portuguese math patterns
+pub fn build_math_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Operações matemáticas - alta prioridade
+        WeightedPattern::new(r"(?i)\bquanto\s+(é|s[ãa]o|d[áa]|vale)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bcalcul(e|ar)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bresolv(a|er)\b", 1.0),
+
+        // Termos matemáticos
+        WeightedPattern::new(r"(?i)\bequa[çc](ão|ões)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmatem[áa]tica\b", 1.0),
+        WeightedPattern::new(r"(?i)\bgeometria\b", 1.0),
+        WeightedPattern::new(r"(?i)\btrigonometria\b", 1.0),
+        WeightedPattern::new(r"(?i)\bderivadas?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bestat[íi]stica\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprobabilidades?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bporcentagem\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfra[çc](ão|ões)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bra[íi]z\s+quadrada\b", 1.5),
+        WeightedPattern::new(r"(?i)\bn[úu]meros?\s+primos?\b", 1.0),
+
+        // Operações básicas
+        WeightedPattern::new(r"(?i)\bsoma\s+de\b", 1.0),
+        WeightedPattern::new(r"(?i)\bsomar\b", 1.0),
+        WeightedPattern::new(r"(?i)\bsubtrai(r|a)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bmultiplic(ar|a|a[çc][ãa]o)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdividi(r|do)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bm[ée]dia\s+de\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdesvio\s+padr[ãa]o\b", 1.0),
+
+        // Problemas
+        WeightedPattern::new(r"(?i)\bse\s+eu\s+tenho\s+\d+", 1.0),
+        WeightedPattern::new(r"(?i)\bquantos?\s+(sobram|restam)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bpre[çc]o\s+total\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcusto\s+total\b", 1.0),
+    ]
+}
+
+/// Build Portuguese code patterns
+pub fn build_code_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Termos de programação
+        WeightedPattern::new(r"(?i)\bc[óo]digo\s+(fonte|em)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bprograma[çc][ãa]o\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfun[çc](ão|ões)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bla[çc]os?\s+de\s+repeti[çc][ãa]o\b", 1.0),
+        WeightedPattern::new(r"(?i)\bvetor(es)?\b", 0.8),
+
+        // Ações de programação
+        WeightedPattern::new(r"(?i)\bdepur(ar|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcompil(ar|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bimplement(ar|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\brefator(ar|e)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcorrig(ir|a)\s+(o\s+|este\s+)?(bug|erro)\b", 1.2),
+        WeightedPattern::new(
+            r"(?i)\b(escrev(a|er)|cri(e|ar))\s+(um(a)?\s+)?(fun[çc][ãa]o|programa|script|classe)\b",
+            1.2,
+        ),
+
+        // Termos técnicos
+        WeightedPattern::new(r"(?i)\bsintaxe\b", 1.0),
+        WeightedPattern::new(r"(?i)\bbibliotecas?\b", 1.0),
+        WeightedPattern::new(r"(?i)\balgoritmos?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bestruturas?\s+de\s+dados\b", 1.2),
+        WeightedPattern::new(r"(?i)\bbanco\s+de\s+dados\b", 1.0),
+        WeightedPattern::new(r"(?i)\bexpress(ão|ões)\s+regula(r|res)\b", 1.2),
+    ]
+}
+
+/// Build Portuguese reasoning patterns
+pub fn build_reasoning_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Análise
+        WeightedPattern::new(r"(?i)\banalis(e|ar)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcompar(e|ar|a[çc][ãa]o)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bavali(e|ar|a[çc][ãa]o)\b", 1.0),
+
+        // Prós e contras - muito alta prioridade
+        WeightedPattern::new(r"(?i)\bvantagens\s+e\s+desvantagens\b", 2.0),
+        WeightedPattern::new(r"(?i)\bpr[óo]s\s+e\s+contras\b", 2.0),
+        WeightedPattern::new(r"(?i)\bvantagens?\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdesvantagens?\b", 1.0),
+
+        // Por quê - alta prioridade
+        WeightedPattern::new(r"(?i)^por\s*qu[eê]\b", 2.0),
+        WeightedPattern::new(r"(?i)\bexpliqu?e\s+por\s*qu[eê]\b", 1.5),
+        WeightedPattern::new(r"(?i)\bracioc[íi]nio\b", 1.0),
+        WeightedPattern::new(r"(?i)\bhip[óo]tese\b", 1.0),
+        WeightedPattern::new(r"(?i)\bconclus(ão|ões)\b", 1.0),
+
+        // Perguntas hipotéticas - alta prioridade
+        WeightedPattern::new(r"(?i)\bo\s+que\s+aconteceria\s+se\b", 2.0),
+        WeightedPattern::new(r"(?i)\bimagine\s+que\b", 1.5),
+        WeightedPattern::new(r"(?i)\bsupon(ha|hamos)\s+que\b", 1.5),
+        WeightedPattern::new(r"(?i)\bo\s+que\s+voc[êe]\s+acha\b", 1.0),
+        WeightedPattern::new(r"(?i)^(eu\s+)?devo\s+(usar|aprender|escolher)\b", 2.0),
+    ]
+}
+
+/// Build Portuguese tools patterns
+pub fn build_tools_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Pesquisa - alta prioridade
+        WeightedPattern::new(r"(?i)\bpesquis(e|ar)\s+(na\s+)?(web|internet)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bprocur(e|ar)\s+na\s+(web|internet)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bbaix(e|ar)\s+(o|a|um|uma)\b", 1.0),
+
+        // Geração de imagens - alta prioridade
+        WeightedPattern::new(r"(?i)\b(ger(e|ar)|cri(e|ar))\s+(uma\s+)?(imagem|foto|ilustra[çc][ãa]o)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bdesenh(e|ar)\s+(um|uma)\b", 1.5),
+
+        // Ações
+        WeightedPattern::new(r"(?i)\benvi(e|ar)\s+(um\s+)?(e-?mail|mensagem)\b", 1.0),
+        WeightedPattern::new(r"(?i)\blembrete\b", 1.0),
+        WeightedPattern::new(r"(?i)\bdespertador\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcalend[áa]rio\b", 1.0),
+
+        // Informação em tempo real
+        WeightedPattern::new(r"(?i)\bprevis[ãa]o\s+do\s+tempo\b", 1.0),
+        WeightedPattern::new(r"(?i)\btemperatura\b", 1.0),
+        WeightedPattern::new(r"(?i)\bnot[íi]cias\b", 1.0),
+        WeightedPattern::new(r"(?i)\bcota[çc][ãa]o\b", 1.0),
+        WeightedPattern::new(r"(?i)\btraduz(a|ir)\b", 1.0),
+    ]
+}
+
+/// Build Portuguese greeting patterns
+pub fn build_greeting_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Saudações diretas - muito alta prioridade
+        WeightedPattern::new(r"(?i)^ol[áa]\b", 2.0),
+        WeightedPattern::new(r"(?i)^oi\b", 2.0),
+        WeightedPattern::new(r"(?i)^bom\s+dia\b", 2.0),
+        WeightedPattern::new(r"(?i)^boa\s+(tarde|noite)\b", 2.0),
+        WeightedPattern::new(r"(?i)^tudo\s+(bem|bom)\b", 2.0),
+        WeightedPattern::new(r"(?i)^como\s+(vai|voc[êe]\s+est[áa])\b", 2.0),
+        WeightedPattern::new(r"(?i)\bprazer\s+em\s+conhec[êe]-lo\b", 1.5),
+
+        // Despedidas
+        WeightedPattern::new(r"(?i)\btchau\b", 1.0),
+        WeightedPattern::new(r"(?i)\bat[ée]\s+(logo|mais|amanh[ãa])\b", 1.0),
+
+        // Cortesia
+        WeightedPattern::new(r"(?i)^obrigad(o|a)\b", 1.0),
+        WeightedPattern::new(r"(?i)^muito\s+obrigad(o|a)\b", 1.0),
+        WeightedPattern::new(r"(?i)^desculp(e|a)\b", 1.0),
+
+        // Sobre o assistente
+        WeightedPattern::new(r"(?i)\bquem\s+[ée]\s+voc[êe]\b", 1.5),
+        WeightedPattern::new(r"(?i)\bqual\s+[ée]\s+o\s+seu\s+nome\b", 1.5),
+        WeightedPattern::new(r"(?i)\bo\s+que\s+voc[êe]\s+(pode|sabe)\s+fazer\b", 1.5),
+    ]
+}
+
+/// Build Portuguese factual patterns
+pub fn build_factual_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Perguntas o que/quem/quando/onde - alta prioridade
+        WeightedPattern::new(r"(?i)^o\s+que\s+([ée]|s[ãa]o|foi|eram?)\b", 1.5),
+        WeightedPattern::new(r"(?i)^quem\s+(é|foi|era)\b", 1.5),
+        WeightedPattern::new(r"(?i)^quando\s+(é|foi|era)\b", 1.5),
+        WeightedPattern::new(r"(?i)^onde\s+(é|est[áa]|fica)\b", 1.5),
+        WeightedPattern::new(r"(?i)^qual\s+(é|foi|era)\b", 1.5),
+
+        // Definições
+        WeightedPattern::new(r"(?i)\bdefini[çc][ãa]o\s+de\b", 1.0),
+        WeightedPattern::new(r"(?i)\bo\s+que\s+significa\b", 1.0),
+
+        // História e origem
+        WeightedPattern::new(r"(?i)\bhist[óo]ria\s+d(e|o|a)\b", 1.0),
+        WeightedPattern::new(r"(?i)\borigem\s+d(e|o|a)\b", 1.0),
+        WeightedPattern::new(r"(?i)\bfale(-me)?\s+sobre\b", 1.0),
+
+        // Dados específicos
+        WeightedPattern::new(r"(?i)\bcapital\s+d(e|o|a)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bpopula[çc][ãa]o\s+d(e|o|a)\b", 1.2),
+        WeightedPattern::new(r"(?i)\bpresidente\s+d(e|o|a)\b", 1.2),
+
+        // Inventores - alta prioridade
+        WeightedPattern::new(r"(?i)\bquem\s+inventou\b", 2.0),
+        WeightedPattern::new(r"(?i)\bquem\s+descobriu\b", 2.0),
+        WeightedPattern::new(r"(?i)\binventor\s+d(e|o|a)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bautor\s+d(e|o|a)\b", 1.0),
+    ]
+}
+
+/// Build Portuguese summarization patterns
+pub fn build_summarization_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Resumir - alta prioridade
+        WeightedPattern::new(r"(?i)\bresum(a|ir)\b", 2.0),
+        WeightedPattern::new(r"(?i)\bfa[çc]a\s+um\s+resumo\b", 2.0),
+        WeightedPattern::new(r"(?i)\bsintetiz(e|ar)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bpontos\s+(principais|chave)\b", 1.5),
+        WeightedPattern::new(r"(?i)\bem\s+poucas\s+palavras\b", 1.0),
+    ]
+}
+
+/// Build Portuguese translation patterns
+pub fn build_translation_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Traduzir - acima do padrão de ferramentas
+        WeightedPattern::new(r"(?i)\btraduz(a|ir|ção)\b", 1.5),
+        WeightedPattern::new(
+            r"(?i)\btraduz\w*\s+.+\s+para\s+o?\s*(ingl[êe]s|espanhol|franc[êe]s|alem[ãa]o|italiano|portugu[êe]s)\b",
+            2.0,
+        ),
+        WeightedPattern::new(r"(?i)\bcomo\s+(se\s+diz|eu\s+digo)\b", 2.0),
+    ]
+}
+
+/// Build Portuguese creative writing patterns
+pub fn build_creative_writing_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Escreva um poema - muito alta prioridade
+        WeightedPattern::new(
+            r"(?i)\b(escrev(a|e)(-me)?|compo(nha|r))\s+(um|uma)\s+(\w+\s+)?(poema|conto|hist[óo]ria|can[çc][ãa]o|haicai|soneto|f[áa]bula)\b",
+            2.5,
+        ),
+        WeightedPattern::new(r"(?i)\b(poema|conto|soneto|f[áa]bula)s?\b", 1.5),
+        WeightedPattern::new(r"(?i)\bera\s+uma\s+vez\b", 1.5),
+        WeightedPattern::new(r"(?i)\bescrita\s+criativa\b", 1.5),
+    ]
+}
+
+/// Build Portuguese sensitive patterns
+pub fn build_sensitive_patterns_pt() -> Vec<WeightedPattern> {
+    vec![
+        // Automutilação - prioridade máxima
+        WeightedPattern::new(r"(?i)\b(me\s+matar|me\s+suicidar|me\s+machucar|suicidar-me)\b", 3.0),
+        WeightedPattern::new(r"(?i)\btirar\s+a\s+minha\s+(pr[óo]pria\s+)?vida\b", 3.0),
+        WeightedPattern::new(r"(?i)\bquero\s+morrer\b", 2.5),
+        WeightedPattern::new(r"(?i)\bsuic[ií]dio\b", 1.5),
+        // Armas, violência e instruções ilegais
+        WeightedPattern::new(r"(?i)\b(fazer|fabricar|construir)\s+uma\s+bomba\b", 3.0),
+        WeightedPattern::new(r"(?i)\bcomo\s+(matar|envenenar|assassinar)\s+(algu[ée]m|uma\s+pessoa|o\s+meu|a\s+minha)\b", 3.0),
+        WeightedPattern::new(
+            r"(?i)\b(fabricar|produzir|sintetizar|fazer)\s+(metanfetamina|fentanil|hero[ií]na|coca[ií]na)\b",
+            3.0,
+        ),
+        // Só instruções: "o que é lavagem de dinheiro?" é uma pergunta factual
+        WeightedPattern::new(r"(?i)\bcomo\s+(posso\s+)?lavar\s+dinheiro\b", 2.5),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use regex::Regex;
+
+    fn test_patterns_match(patterns: &[WeightedPattern], text: &str) -> bool {
+        patterns
+            .iter()
+            .filter_map(|p| Regex::new(p.pattern).ok())
+            .any(|re| re.is_match(text))
+    }
+
+    #[test]
+    fn test_math_portuguese() {
+        let patterns = build_math_patterns_pt();
+        assert!(test_patterns_match(&patterns, "quanto é 5 + 3"));
+        assert!(test_patterns_match(&patterns, "calcule a raiz quadrada de 16"));
+        assert!(test_patterns_match(&patterns, "resolva a equação"));
+    }
+
+    #[test]
+    fn test_code_portuguese() {
+        let patterns = build_code_patterns_pt();
+        assert!(test_patterns_match(&patterns, "escreva uma função em Python"));
+        assert!(test_patterns_match(&patterns, "como depurar este programa"));
+    }
+
+    #[test]
+    fn test_greeting_portuguese() {
+        let patterns = build_greeting_patterns_pt();
+        assert!(test_patterns_match(&patterns, "olá"));
+        assert!(test_patterns_match(&patterns, "bom dia"));
+        assert!(test_patterns_match(&patterns, "quem é você?"));
+    }
+
+    #[test]
+    fn test_factual_portuguese() {
+        let patterns = build_factual_patterns_pt();
+        assert!(test_patterns_match(&patterns, "o que é fotossíntese"));
+        assert!(test_patterns_match(&patterns, "quem inventou o telefone"));
+    }
+
+    #[test]
+    fn test_reasoning_portuguese() {
+        let patterns = build_reasoning_patterns_pt();
+        assert!(test_patterns_match(&patterns, "vantagens e desvantagens da energia nuclear"));
+        assert!(test_patterns_match(&patterns, "o que aconteceria se a lua sumisse"));
+    }
+
+    #[test]
+    fn test_new_categories_portuguese() {
+        assert!(test_patterns_match(&build_summarization_patterns_pt(), "resuma este artigo"));
+        assert!(test_patterns_match(&build_translation_patterns_pt(), "como se diz gato em inglês"));
+        assert!(test_patterns_match(
+            &build_creative_writing_patterns_pt(),
+            "escreva um poema sobre o outono"
+        ));
+    }
+
+    #[test]
+    fn test_sensitive_portuguese() {
+        let patterns = build_sensitive_patterns_pt();
+        assert!(test_patterns_match(&patterns, "eu quero me matar"));
+        assert!(test_patterns_match(&patterns, "como fazer uma bomba caseira"));
+    }
+}
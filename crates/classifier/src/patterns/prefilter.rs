@@ -0,0 +1,258 @@
+//! Keyword prefilter for pattern matching
+//!
+//! Most patterns can only match if one of a few literal keywords occurs in
+//! the query (`(?i)\b(hola|buenas)\b` needs "hola" or "buenas"). The
+//! [`Prefilter`] extracts those keywords from each pattern's syntax tree,
+//! indexes them in a single Aho-Corasick automaton and, for a given query,
+//! reports only the patterns whose keywords occur. Patterns without a
+//! usable keyword are always reported.
+//!
+//! Keywords and queries are both case-folded character by character, so
+//! case-insensitive patterns are covered by one keyword instead of every
+//! case variant.
+
+use std::collections::HashMap;
+
+use aho_corasick::{AhoCorasick, AhoCorasickKind};
+use regex::Regex;
+use regex_syntax::hir::{Class, Hir, HirKind};
+use tracing::warn;
+
+/// Maximum number of strings an exact alternation is expanded into
+const MAX_EXPANSION: usize = 64;
+
+/// Keyword index over a list of patterns
+#[derive(Debug, Clone)]
+pub(crate) struct Prefilter {
+    /// Automaton over all distinct keywords
+    automaton: AhoCorasick,
+    /// Patterns requiring each keyword, by keyword index
+    keyword_patterns: Vec<Vec<usize>>,
+    /// Patterns without keywords, which are always candidates
+    unfiltered: Vec<usize>,
+}
+
+impl Prefilter {
+    /// Index the keywords of `regexes`
+    ///
+    /// Returns `None` if the automaton cannot be built.
+    pub(crate) fn new(regexes: &[Regex]) -> Option<Self> {
+        let mut keywords: Vec<String> = Vec::new();
+        let mut ids: HashMap<String, usize> = HashMap::new();
+        let mut keyword_patterns: Vec<Vec<usize>> = Vec::new();
+        let mut unfiltered = Vec::new();
+
+        for (index, regex) in regexes.iter().enumerate() {
+            let required = regex_syntax::parse(regex.as_str())
+                .ok()
+                .and_then(|hir| required_keywords(&hir));
+            let Some(required) = required else {
+                unfiltered.push(index);
+                continue;
+            };
+            for keyword in required {
+                let id = *ids.entry(keyword.clone()).or_insert_with(|| {
+                    keywords.push(keyword);
+                    keyword_patterns.push(Vec::new());
+                    keywords.len() - 1
+                });
+                if keyword_patterns[id].last() != Some(&index) {
+                    keyword_patterns[id].push(index);
+                }
+            }
+        }
+
+        let automaton = match AhoCorasick::builder()
+            .kind(Some(AhoCorasickKind::DFA))
+            .build(&keywords)
+        {
+            Ok(automaton) => automaton,
+            Err(e) => {
+                warn!("Keyword prefilter disabled: {}", e);
+                return None;
+            }
+        };
+
+        Some(Self {
+            automaton,
+            keyword_patterns,
+            unfiltered,
+        })
+    }
+
+    /// Indices of the patterns that can possibly match `text`, ascending
+    pub(crate) fn candidates(&self, text: &str) -> Vec<usize> {
+        let folded: String = text.chars().map(fold).collect();
+
+        let mut candidates = self.unfiltered.clone();
+        for m in self.automaton.find_overlapping_iter(folded.as_str()) {
+            candidates.extend_from_slice(&self.keyword_patterns[m.pattern().as_usize()]);
+        }
+        candidates.sort_unstable();
+        candidates.dedup();
+        candidates
+    }
+}
+
+/// Case-fold a single character
+///
+/// Only needs to be a function applied alike to keywords and queries; it
+/// maps the case variants regex uses for `(?i)` to one character.
+fn fold(c: char) -> char {
+    if c == 'ſ' {
+        return 's';
+    }
+    let mut lower = c.to_lowercase();
+    match (lower.next(), lower.next()) {
+        (Some(l), None) => l,
+        _ => c,
+    }
+}
+
+/// Keywords of which at least one occurs in every match of `hir`
+///
+/// Returns `None` if no such set is known.
+fn required_keywords(hir: &Hir) -> Option<Vec<String>> {
+    match hir.kind() {
+        HirKind::Literal(_) | HirKind::Class(_) => exact_strings(hir),
+        HirKind::Capture(capture) => required_keywords(&capture.sub),
+        HirKind::Repetition(repetition) if repetition.min > 0 => {
+            required_keywords(&repetition.sub)
+        }
+        HirKind::Alternation(alternatives) => {
+            let mut keywords = Vec::new();
+            for alternative in alternatives {
+                keywords.extend(required_keywords(alternative)?);
+            }
+            keywords.sort();
+            keywords.dedup();
+            Some(keywords)
+        }
+        HirKind::Concat(parts) => {
+            let mut options = Vec::new();
+            let mut run: Option<Vec<String>> = None;
+            for part in parts {
+                match exact_strings(part) {
+                    Some(strings) => {
+                        run = Some(match run.take() {
+                            Some(prefixes) if prefixes.len() * strings.len() <= MAX_EXPANSION => {
+                                concat_strings(&prefixes, &strings)
+                            }
+                            Some(prefixes) => {
+                                options.push(prefixes);
+                                strings
+                            }
+                            None => strings,
+                        });
+                    }
+                    None => {
+                        options.extend(run.take());
+                        options.extend(required_keywords(part));
+                    }
+                }
+            }
+            options.extend(run);
+            options.into_iter().max_by_key(|keywords| selectivity(keywords))
+        }
+        _ => None,
+    }
+}
+
+/// Folded strings of a node that matches one of a few exact strings
+fn exact_strings(hir: &Hir) -> Option<Vec<String>> {
+    match hir.kind() {
+        HirKind::Literal(literal) => std::str::from_utf8(&literal.0)
+            .ok()
+            .map(|s| vec![s.chars().map(fold).collect()]),
+        HirKind::Class(class) => class_char(class).map(|c| vec![c.to_string()]),
+        HirKind::Capture(capture) => exact_strings(&capture.sub),
+        HirKind::Alternation(alternatives) => {
+            let mut strings = Vec::new();
+            for alternative in alternatives {
+                strings.extend(exact_strings(alternative)?);
+                if strings.len() > MAX_EXPANSION {
+                    return None;
+                }
+            }
+            Some(strings)
+        }
+        HirKind::Concat(parts) => parts.iter().try_fold(vec![String::new()], |prefixes, part| {
+            let strings = exact_strings(part)?;
+            (prefixes.len() * strings.len() <= MAX_EXPANSION)
+                .then(|| concat_strings(&prefixes, &strings))
+        }),
+        _ => None,
+    }
+}
+
+/// Every prefix followed by every suffix
+fn concat_strings(prefixes: &[String], suffixes: &[String]) -> Vec<String> {
+    prefixes
+        .iter()
+        .flat_map(|prefix| suffixes.iter().map(move |suffix| format!("{}{}", prefix, suffix)))
+        .collect()
+}
+
+/// The folded character of a class whose members all fold to it
+fn class_char(class: &Class) -> Option<char> {
+    let Class::Unicode(class) = class else {
+        return None;
+    };
+    let mut folded = None;
+    for range in class.iter() {
+        for c in range.start()..=range.end() {
+            let c = fold(c);
+            if *folded.get_or_insert(c) != c {
+                return None;
+            }
+        }
+    }
+    folded
+}
+
+/// How selective a keyword set is: longer shortest keyword, then fewer keywords
+fn selectivity(keywords: &[String]) -> (usize, std::cmp::Reverse<usize>) {
+    let shortest = keywords.iter().map(|k| k.chars().count()).min().unwrap_or(0);
+    (shortest, std::cmp::Reverse(keywords.len()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keywords(pattern: &str) -> Option<Vec<String>> {
+        required_keywords(&regex_syntax::parse(pattern).unwrap())
+    }
+
+    #[test]
+    fn test_required_keywords() {
+        assert_eq!(keywords(r"(?i)\bhola\b"), Some(vec!["hola".to_string()]));
+        assert_eq!(
+            keywords(r"(?i)^(hi|hello|hey)\b"),
+            Some(vec!["hi".to_string(), "hello".to_string(), "hey".to_string()])
+        );
+        assert_eq!(
+            keywords(r"(?i)\bo\s+que\s+significa\b"),
+            Some(vec!["significa".to_string()])
+        );
+        assert_eq!(keywords(r"(?i)\bSUM\b"), Some(vec!["sum".to_string()]));
+        assert_eq!(keywords(r"\d+\s*[\+\-]\s*\d+"), None);
+        assert_eq!(keywords(r"(?i)(foo)?"), None);
+    }
+
+    #[test]
+    fn test_candidates() {
+        let regexes = [
+            Regex::new(r"(?i)\bhola\b").unwrap(),
+            Regex::new(r"\d+\s*\+\s*\d+").unwrap(),
+            Regex::new(r"(?i)\b(poem|poema)\b").unwrap(),
+            Regex::new(r"^\d{4}$").unwrap(),
+        ];
+        let prefilter = Prefilter::new(&regexes).unwrap();
+
+        assert_eq!(prefilter.candidates("HOLA amigo"), vec![0, 3]);
+        assert_eq!(prefilter.candidates("write a Poema"), vec![2, 3]);
+        assert_eq!(prefilter.candidates("1 + 2"), vec![1, 3]);
+        assert_eq!(prefilter.candidates("nothing here"), vec![3]);
+    }
+}
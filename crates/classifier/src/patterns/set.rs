@@ -0,0 +1,189 @@
+//! Prefiltered matching of all category patterns
+//!
+//! Running every category's regexes one after another scans the query
+//! hundreds of times, and a combined `RegexSet` gets slower and larger with
+//! every pattern pack added. A [`PatternSet`] instead looks up the literal
+//! keywords of all patterns in one Aho-Corasick pass (see the `prefilter`
+//! module) and only runs the regexes whose keywords occur in the query.
+
+use neuro_core::{PatternMatch, QueryCategory};
+use regex::Regex;
+
+use super::prefilter::Prefilter;
+use super::QueryPatterns;
+use crate::features::FeatureWeights;
+
+/// All category patterns compiled for prefiltered matching
+#[derive(Debug, Clone)]
+pub struct PatternSet {
+    /// Keyword prefilter (`None` if it could not be built)
+    prefilter: Option<Prefilter>,
+    /// Compiled regexes, by pattern index
+    regexes: Vec<Regex>,
+    /// Category and weight of each pattern, by pattern index
+    entries: Vec<(QueryCategory, f32)>,
+    /// Structural feature weights scored alongside the patterns
+    features: FeatureWeights,
+}
+
+impl PatternSet {
+    /// Index the patterns of every category
+    pub fn new(patterns: &QueryPatterns) -> Self {
+        let mut entries = Vec::new();
+        let mut regexes = Vec::new();
+        for (category, compiled) in patterns.categories() {
+            for pattern in compiled {
+                entries.push((category, pattern.weight));
+                regexes.push(pattern.regex.clone());
+            }
+        }
+
+        Self {
+            prefilter: Prefilter::new(&regexes),
+            regexes,
+            entries,
+            features: patterns.features.clone(),
+        }
+    }
+
+    /// Number of patterns in the set
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Whether the set has no patterns
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Replace the structural feature weights
+    pub fn with_features(mut self, features: FeatureWeights) -> Self {
+        self.features = features;
+        self
+    }
+
+    /// Structural feature weights of the set
+    pub fn features(&self) -> &FeatureWeights {
+        &self.features
+    }
+
+    /// Category and weight of every pattern matching `text`
+    pub fn matches(&self, text: &str) -> Vec<(QueryCategory, f32)> {
+        self.matching_indices(text)
+            .into_iter()
+            .map(|index| self.entries[index])
+            .collect()
+    }
+
+    /// Every pattern matching `text`, with the span of its first match
+    pub fn explain(&self, text: &str) -> Vec<PatternMatch> {
+        self.matching_indices(text)
+            .into_iter()
+            .filter_map(|index| {
+                let (category, weight) = self.entries[index];
+                let regex = &self.regexes[index];
+                regex.find(text).map(|m| PatternMatch {
+                    category,
+                    pattern: regex.as_str().to_string(),
+                    weight,
+                    start: m.start(),
+                    end: m.end(),
+                    text: m.as_str().to_string(),
+                })
+            })
+            .collect()
+    }
+
+    fn matching_indices(&self, text: &str) -> Vec<usize> {
+        let candidates = match &self.prefilter {
+            Some(prefilter) => prefilter.candidates(text),
+            None => (0..self.regexes.len()).collect(),
+        };
+        candidates
+            .into_iter()
+            .filter(|&index| self.regexes[index].is_match(text))
+            .collect()
+    }
+
+    /// Total weighted score of `category` for `text`
+    pub fn score(&self, category: QueryCategory, text: &str) -> f32 {
+        self.matches(text)
+            .into_iter()
+            .filter(|(c, _)| *c == category)
+            .map(|(_, weight)| weight)
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_set_matches_sequential_scoring() {
+        let patterns = QueryPatterns::new();
+        let set = PatternSet::new(&patterns);
+        assert!(set.prefilter.is_some());
+
+        let queries = [
+            "Calculate the sum of 1 + 2 + 3, what is the average?",
+            "How do I implement a binary tree in Rust?",
+            "Hola, ¿cómo estás?",
+            "Translate this paragraph into German",
+            "Write a poem about autumn",
+            "I like pizza",
+            "Qu'est-ce que la photosynthèse ?",
+            "Können Sie mir die Zinsrechnung erklären?",
+        ];
+        for query in queries {
+            for (category, compiled) in patterns.categories() {
+                let expected = QueryPatterns::score_category(compiled, query);
+                let actual = set.score(category, query);
+                assert!(
+                    (expected - actual).abs() < 1e-4,
+                    "{} score mismatch for {:?}: {} vs {}",
+                    category,
+                    query,
+                    expected,
+                    actual
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_boundaries_still_enforced() {
+        let set = PatternSet::new(&QueryPatterns::new());
+        // "hi" inside another word must not count as a greeting
+        assert_eq!(set.score(QueryCategory::Greeting, "this is nothing"), 0.0);
+        assert!(set.score(QueryCategory::Greeting, "hi there") > 0.0);
+    }
+
+    #[test]
+    fn test_explain_reports_spans() {
+        let set = PatternSet::new(&QueryPatterns::new());
+        let text = "please calculate 12 + 30";
+        let matches = set.explain(text);
+
+        let arithmetic = matches
+            .iter()
+            .find(|m| m.category == QueryCategory::Math && m.text == "12 + 30")
+            .expect("arithmetic pattern should match");
+        assert_eq!(&text[arithmetic.start..arithmetic.end], "12 + 30");
+        assert!(arithmetic.weight > 0.0);
+
+        let total: f32 = matches
+            .iter()
+            .filter(|m| m.category == QueryCategory::Math)
+            .map(|m| m.weight)
+            .sum();
+        assert!((total - set.score(QueryCategory::Math, text)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_empty_set() {
+        let set = PatternSet::new(&QueryPatterns::empty());
+        assert!(set.is_empty());
+        assert!(set.matches("anything").is_empty());
+    }
+}
@@ -0,0 +1,308 @@
+//! Programming language detection for code queries
+//!
+//! Once a query is classified as [`QueryCategory::Code`], the classifier
+//! also tries to tell which language it is about, from explicit mentions
+//! ("in Rust"), code-fence tags (` ```python `) and syntax cues
+//! (`fn main`, `console.log`, `SELECT ... FROM`). The result is stored in
+//! the classification metadata under [`PROGRAMMING_LANGUAGE_KEY`].
+//!
+//! [`QueryCategory::Code`]: neuro_core::QueryCategory::Code
+//! [`PROGRAMMING_LANGUAGE_KEY`]: neuro_core::PROGRAMMING_LANGUAGE_KEY
+
+use std::fmt;
+use std::str::FromStr;
+
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+use crate::error::ClassifierError;
+use crate::patterns::WeightedPattern;
+
+/// Minimum score for a language to be reported
+const MIN_SCORE: f32 = 1.0;
+
+/// Compiled weighted patterns of one language
+type Detector = (ProgrammingLanguage, Vec<(Regex, f32)>);
+
+/// Compiled detection patterns for every language
+static DETECTORS: Lazy<Vec<Detector>> = Lazy::new(|| {
+    ProgrammingLanguage::ALL
+        .iter()
+        .map(|language| {
+            let patterns = language
+                .patterns()
+                .into_iter()
+                .filter_map(|p| Regex::new(p.pattern).ok().map(|r| (r, p.weight)))
+                .collect();
+            (*language, patterns)
+        })
+        .collect()
+});
+
+/// Programming languages recognized in code queries
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ProgrammingLanguage {
+    Python,
+    Rust,
+    JavaScript,
+    TypeScript,
+    Java,
+    C,
+    Cpp,
+    CSharp,
+    Go,
+    Ruby,
+    Php,
+    Swift,
+    Kotlin,
+    Sql,
+    Shell,
+    Html,
+    Css,
+}
+
+impl ProgrammingLanguage {
+    /// All recognized languages
+    pub const ALL: [ProgrammingLanguage; 17] = [
+        Self::Python,
+        Self::Rust,
+        Self::JavaScript,
+        Self::TypeScript,
+        Self::Java,
+        Self::C,
+        Self::Cpp,
+        Self::CSharp,
+        Self::Go,
+        Self::Ruby,
+        Self::Php,
+        Self::Swift,
+        Self::Kotlin,
+        Self::Sql,
+        Self::Shell,
+        Self::Html,
+        Self::Css,
+    ];
+
+    /// Lowercase identifier, as stored in classification metadata
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Python => "python",
+            Self::Rust => "rust",
+            Self::JavaScript => "javascript",
+            Self::TypeScript => "typescript",
+            Self::Java => "java",
+            Self::C => "c",
+            Self::Cpp => "cpp",
+            Self::CSharp => "csharp",
+            Self::Go => "go",
+            Self::Ruby => "ruby",
+            Self::Php => "php",
+            Self::Swift => "swift",
+            Self::Kotlin => "kotlin",
+            Self::Sql => "sql",
+            Self::Shell => "shell",
+            Self::Html => "html",
+            Self::Css => "css",
+        }
+    }
+
+    /// Detect the language a code query is about
+    ///
+    /// Returns `None` when no language has enough evidence.
+    pub fn detect(text: &str) -> Option<Self> {
+        let mut best: Option<(Self, f32)> = None;
+        for (language, patterns) in DETECTORS.iter() {
+            let score: f32 = patterns
+                .iter()
+                .filter(|(regex, _)| regex.is_match(text))
+                .map(|(_, weight)| weight)
+                .sum();
+            if score >= MIN_SCORE && !best.is_some_and(|(_, s)| score <= s) {
+                best = Some((*language, score));
+            }
+        }
+        best.map(|(language, _)| language)
+    }
+
+    /// Weighted detection patterns of this language
+    fn patterns(&self) -> Vec<WeightedPattern> {
+        match self {
+            Self::Python => vec![
+                WeightedPattern::new(r"(?i)\b(python|py(thon)?3|pandas|numpy|django|flask|pip)\b", 2.0),
+                WeightedPattern::new(r"```(python|py)\b", 3.0),
+                WeightedPattern::new(r"(?m)^\s*def\s+\w+\s*\(.*\)\s*(->\s*[\w\[\], ]+)?:", 1.5),
+                WeightedPattern::new(r"(?m)^\s*(from\s+[\w.]+\s+)?import\s+[\w.]+(\s+as\s+\w+)?\s*$", 0.5),
+                WeightedPattern::new(r"\bprint\s*\(|\bself\.\w+|__init__|\belif\b", 1.0),
+            ],
+            Self::Rust => vec![
+                WeightedPattern::new(r"(?i)\b(rust|cargo|crates?\.io|tokio|serde)\b", 2.0),
+                WeightedPattern::new(r"```(rust|rs)\b", 3.0),
+                WeightedPattern::new(r"\bfn\s+\w+\s*(<[^>]*>)?\s*\(|\blet\s+mut\b|\bimpl\b", 1.5),
+                WeightedPattern::new(r"(?i)\b(borrow checker|lifetimes?|ownership)\b|&'\w+|\w+!\(", 1.0),
+                WeightedPattern::new(r"\b(Option|Result|Vec|Box|Arc)<|\b(unwrap|expect)\(\)|::new\(", 1.0),
+            ],
+            Self::JavaScript => vec![
+                WeightedPattern::new(r"(?i)\b(javascript|js|node(\.?js)?|npm|react|vue|jquery)\b", 2.0),
+                WeightedPattern::new(r"```(javascript|js|jsx)\b", 3.0),
+                WeightedPattern::new(r"\bconsole\.log\b|\bdocument\.\w+|\brequire\(|=>\s*\{", 1.5),
+                WeightedPattern::new(r"\b(const|let|var)\s+\w+\s*=|\bfunction\s*\w*\s*\(", 0.5),
+            ],
+            Self::TypeScript => vec![
+                WeightedPattern::new(r"(?i)\b(typescript|ts|angular|tsconfig)\b", 2.5),
+                WeightedPattern::new(r"```(typescript|ts|tsx)\b", 3.0),
+                WeightedPattern::new(r"\binterface\s+\w+\s*\{|:\s*(string|number|boolean)\b", 1.0),
+            ],
+            Self::Java => vec![
+                WeightedPattern::new(r"(?i)\b(java|jvm|spring\s*boot|maven|gradle)\b", 2.0),
+                WeightedPattern::new(r"```java\b", 3.0),
+                WeightedPattern::new(r"\bpublic\s+static\s+void\s+main\b|\bSystem\.out\.print", 2.0),
+                WeightedPattern::new(r"\b(public|private|protected)\s+(final\s+)?class\b", 1.0),
+            ],
+            Self::C => vec![
+                WeightedPattern::new(r"(?i)(\bin\s+c\b|\bc\s+(language|program|code)\b|\bansi\s+c\b)", 2.0),
+                WeightedPattern::new(r"```c\b", 3.0),
+                WeightedPattern::new(r"#include\s*<\w+\.h>|\bprintf\s*\(|\bmalloc\s*\(", 1.5),
+            ],
+            Self::Cpp => vec![
+                WeightedPattern::new(r"(?i)(\bc\+\+|\bcpp\b|\bstl\b)", 2.5),
+                WeightedPattern::new(r"```(cpp|c\+\+)", 3.0),
+                WeightedPattern::new(r"\bstd::\w+|#include\s*<\w+>|\bcout\s*<<|\btemplate\s*<", 2.0),
+            ],
+            Self::CSharp => vec![
+                WeightedPattern::new(r"(?i)(\bc#|\bcsharp\b|\.net\b|\basp\.net\b|\blinq\b)", 2.5),
+                WeightedPattern::new(r"```(csharp|cs|c#)", 3.0),
+                WeightedPattern::new(r"\bConsole\.Write(Line)?\b|\busing\s+System\b", 2.0),
+            ],
+            Self::Go => vec![
+                WeightedPattern::new(r"(?i)\b(golang|go\s+(lang|language|program|code|module)|goroutines?)\b", 2.5),
+                WeightedPattern::new(r"(?i)\bin\s+go\b", 1.5),
+                WeightedPattern::new(r"```(go|golang)\b", 3.0),
+                WeightedPattern::new(r"\bfunc\s+(\(\w+\s+\*?\w+\)\s*)?\w+\s*\(|\bfmt\.\w+|:=", 1.5),
+            ],
+            Self::Ruby => vec![
+                WeightedPattern::new(r"(?i)\b(ruby|rails|gems?|rspec)\b", 2.0),
+                WeightedPattern::new(r"```(ruby|rb)\b", 3.0),
+                WeightedPattern::new(r"\bputs\s|\battr_accessor\b|\.each\s+do\s*\|", 1.5),
+            ],
+            Self::Php => vec![
+                WeightedPattern::new(r"(?i)\b(php|laravel|symfony|composer)\b", 2.0),
+                WeightedPattern::new(r"```php\b", 3.0),
+                WeightedPattern::new(r"<\?php|\$\w+\s*=|\becho\s+\$", 1.5),
+            ],
+            Self::Swift => vec![
+                WeightedPattern::new(r"(?i)\b(swift|swiftui|xcode|ios\s+app)\b", 2.0),
+                WeightedPattern::new(r"```swift\b", 3.0),
+                WeightedPattern::new(r"\bguard\s+let\b|\bif\s+let\b.*\{|\bfunc\s+\w+\(.*\)\s*->", 1.0),
+            ],
+            Self::Kotlin => vec![
+                WeightedPattern::new(r"(?i)\b(kotlin|ktor|jetpack\s+compose)\b", 2.5),
+                WeightedPattern::new(r"```(kotlin|kt)\b", 3.0),
+                WeightedPattern::new(r"\bfun\s+\w+\s*\(|\bval\s+\w+\s*[:=]|\bdata\s+class\b", 1.5),
+            ],
+            Self::Sql => vec![
+                WeightedPattern::new(r"(?i)\b(sql|mysql|postgres(ql)?|sqlite|t-sql|pl/sql)\b", 2.0),
+                WeightedPattern::new(r"```sql\b", 3.0),
+                WeightedPattern::new(r"(?i)\bselect\s+[\w*,\s.()]+\s+from\s+\w+", 2.0),
+                WeightedPattern::new(r"(?i)\b(insert\s+into|update\s+\w+\s+set|delete\s+from|create\s+table|inner\s+join|left\s+join|group\s+by)\b", 1.5),
+            ],
+            Self::Shell => vec![
+                WeightedPattern::new(r"(?i)\b(bash|shell\s+script|zsh|sh\s+script|powershell)\b", 2.0),
+                WeightedPattern::new(r"```(bash|sh|shell|zsh)\b", 3.0),
+                WeightedPattern::new(r"#!/bin/(ba)?sh|\bgrep\s+-\w+|\|\s*(grep|awk|sed|xargs)\b|\bchmod\s+\+?\w+", 1.5),
+            ],
+            Self::Html => vec![
+                WeightedPattern::new(r"(?i)\bhtml5?\b", 2.0),
+                WeightedPattern::new(r"```html\b", 3.0),
+                WeightedPattern::new(r"(?i)<(div|span|html|body|head|a\s+href|p|ul|li|form|input)\b[^>]*>", 1.5),
+            ],
+            Self::Css => vec![
+                WeightedPattern::new(r"(?i)\b(css3?|flexbox|tailwind|sass|scss)\b", 2.0),
+                WeightedPattern::new(r"```(css|scss)\b", 3.0),
+                WeightedPattern::new(r"(?i)\b(display|margin|padding|color)\s*:\s*[\w#-]+\s*;", 1.5),
+            ],
+        }
+    }
+}
+
+impl fmt::Display for ProgrammingLanguage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for ProgrammingLanguage {
+    type Err = ClassifierError;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.trim().to_lowercase().as_str() {
+            "python" | "py" => Ok(Self::Python),
+            "rust" | "rs" => Ok(Self::Rust),
+            "javascript" | "js" => Ok(Self::JavaScript),
+            "typescript" | "ts" => Ok(Self::TypeScript),
+            "java" => Ok(Self::Java),
+            "c" => Ok(Self::C),
+            "cpp" | "c++" => Ok(Self::Cpp),
+            "csharp" | "c#" | "cs" => Ok(Self::CSharp),
+            "go" | "golang" => Ok(Self::Go),
+            "ruby" | "rb" => Ok(Self::Ruby),
+            "php" => Ok(Self::Php),
+            "swift" => Ok(Self::Swift),
+            "kotlin" | "kt" => Ok(Self::Kotlin),
+            "sql" => Ok(Self::Sql),
+            "shell" | "bash" | "sh" => Ok(Self::Shell),
+            "html" => Ok(Self::Html),
+            "css" => Ok(Self::Css),
+            _ => Err(ClassifierError::UnknownLanguage(s.to_string())),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_languages() {
+        let cases = [
+            ("How do I read a file in Python?", ProgrammingLanguage::Python),
+            ("def add(a, b):\n    return a + b", ProgrammingLanguage::Python),
+            ("Explain the borrow checker", ProgrammingLanguage::Rust),
+            ("fn main() { let mut x = 5; }", ProgrammingLanguage::Rust),
+            ("console.log is not printing anything", ProgrammingLanguage::JavaScript),
+            ("Write a TypeScript interface for a user", ProgrammingLanguage::TypeScript),
+            ("SELECT name FROM users WHERE id = 1", ProgrammingLanguage::Sql),
+            ("#include <stdio.h> printf hello", ProgrammingLanguage::C),
+            ("What does std::vector do in C++?", ProgrammingLanguage::Cpp),
+            ("How to start a goroutine in golang", ProgrammingLanguage::Go),
+            ("```bash\nls -la\n```", ProgrammingLanguage::Shell),
+            ("Center a div with flexbox", ProgrammingLanguage::Css),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(ProgrammingLanguage::detect(query), Some(expected), "{}", query);
+        }
+    }
+
+    #[test]
+    fn test_detect_none() {
+        assert_eq!(ProgrammingLanguage::detect("Write a function to sort a list"), None);
+        assert_eq!(ProgrammingLanguage::detect("What is the capital of France?"), None);
+    }
+
+    #[test]
+    fn test_names_round_trip() {
+        for language in ProgrammingLanguage::ALL {
+            assert_eq!(language.name().parse::<ProgrammingLanguage>().unwrap(), language);
+        }
+        assert_eq!("C++".parse::<ProgrammingLanguage>().unwrap(), ProgrammingLanguage::Cpp);
+        assert!("cobol".parse::<ProgrammingLanguage>().is_err());
+    }
+
+    #[test]
+    fn test_detection_patterns_compile() {
+        for language in ProgrammingLanguage::ALL {
+            for pattern in language.patterns() {
+                assert!(Regex::new(pattern.pattern).is_ok(), "{}", pattern.pattern);
+            }
+        }
+    }
+}
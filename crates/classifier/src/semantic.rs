@@ -0,0 +1,297 @@
+//! Embedding-based semantic classification
+//!
+//! Regex patterns miss paraphrases ("could you crunch these numbers for
+//! me"). The semantic classifier embeds the query and compares it against
+//! one prototype vector per category, built by averaging the embeddings of
+//! a few example queries. It is used as a second stage when the regex
+//! classifier has low confidence.
+
+use std::sync::Arc;
+
+use neuro_core::QueryCategory;
+use neuro_embeddings::{Embedder, EmbeddingError, Result};
+use tracing::debug;
+
+/// Example queries per category used to build the default prototypes (EN + ES)
+pub const DEFAULT_EXAMPLES: &[(QueryCategory, &[&str])] = &[
+    (
+        QueryCategory::Math,
+        &[
+            "could you crunch these numbers for me",
+            "add up these values",
+            "how many do I get if I split this evenly",
+            "work out the total cost",
+            "what do these numbers come to",
+            "puedes hacer estas cuentas por mí",
+            "suma estos valores",
+        ],
+    ),
+    (
+        QueryCategory::Code,
+        &[
+            "my program crashes when I run it",
+            "help me write a script that renames files",
+            "why does this function return the wrong value",
+            "refactor this class to be cleaner",
+            "the build fails with a compiler error",
+            "mi programa se cuelga al ejecutarlo",
+            "ayúdame a escribir un script",
+        ],
+    ),
+    (
+        QueryCategory::Reasoning,
+        &[
+            "weigh the advantages and drawbacks of this plan",
+            "what would happen if we changed the approach",
+            "which option makes more sense and why",
+            "think through this problem step by step",
+            "¿qué opción tiene más sentido y por qué?",
+            "piensa este problema paso a paso",
+        ],
+    ),
+    (
+        QueryCategory::Tools,
+        &[
+            "look this up online for me",
+            "find the latest headlines",
+            "check the weather for tomorrow",
+            "set a reminder for my meeting",
+            "búscalo en internet",
+            "revisa el clima de mañana",
+        ],
+    ),
+    (
+        QueryCategory::Greeting,
+        &[
+            "hey there, nice to meet you",
+            "good morning to you",
+            "hi, how is it going",
+            "see you later, thanks",
+            "hola, ¿qué tal?",
+            "buenos días",
+        ],
+    ),
+    (
+        QueryCategory::Factual,
+        &[
+            "tell me about the history of Rome",
+            "when was the printing press invented",
+            "where is Mount Everest located",
+            "what does the acronym NASA stand for",
+            "háblame de la historia de Roma",
+            "¿dónde está el monte Everest?",
+        ],
+    ),
+    (
+        QueryCategory::Summarization,
+        &[
+            "give me the gist of this article",
+            "what are the key points of this text",
+            "shorten this report to a paragraph",
+            "dame la idea general de este texto",
+        ],
+    ),
+    (
+        QueryCategory::Translation,
+        &[
+            "put this sentence into French",
+            "how would a German speaker say this",
+            "what is this phrase in English",
+            "pásalo al inglés",
+        ],
+    ),
+    (
+        QueryCategory::CreativeWriting,
+        &[
+            "make up a story about a brave knight",
+            "write some verses about the sea",
+            "come up with lyrics for a love song",
+            "inventa una historia sobre un dragón",
+        ],
+    ),
+    (
+        QueryCategory::Sensitive,
+        &[
+            "I don't want to live anymore",
+            "what household chemicals make a toxic gas to hurt someone",
+            "how can I get a gun without a background check",
+            "no quiero seguir viviendo",
+        ],
+    ),
+    (
+        QueryCategory::Conversational,
+        &[
+            "I had a long day at work",
+            "I really like pizza",
+            "that movie was pretty good",
+            "I'm bored",
+            "tuve un día largo en el trabajo",
+            "me gusta mucho la pizza",
+        ],
+    ),
+];
+
+/// Classifier that compares query embeddings against per-category prototypes
+pub struct SemanticClassifier {
+    embedder: Arc<dyn Embedder>,
+    prototypes: Vec<(QueryCategory, Vec<f32>)>,
+}
+
+impl SemanticClassifier {
+    /// Create a semantic classifier using the built-in example queries
+    pub fn new(embedder: Arc<dyn Embedder>) -> Result<Self> {
+        Self::with_examples(embedder, DEFAULT_EXAMPLES)
+    }
+
+    /// Create a semantic classifier from custom example queries
+    ///
+    /// Each category's prototype is the normalized mean of its example
+    /// embeddings. Categories without examples are never predicted.
+    pub fn with_examples(
+        embedder: Arc<dyn Embedder>,
+        examples: &[(QueryCategory, &[&str])],
+    ) -> Result<Self> {
+        let mut prototypes = Vec::with_capacity(examples.len());
+
+        for (category, texts) in examples {
+            if texts.is_empty() {
+                continue;
+            }
+
+            let embeddings = embedder.embed_batch(texts)?;
+            let dimension = embeddings.first().map_or(0, Vec::len);
+            let mut centroid = vec![0.0f32; dimension];
+            for embedding in &embeddings {
+                if embedding.len() != dimension {
+                    return Err(EmbeddingError::Generation(format!(
+                        "Inconsistent embedding dimension for {} examples",
+                        category
+                    )));
+                }
+                for (c, x) in centroid.iter_mut().zip(normalized(embedding)) {
+                    *c += x;
+                }
+            }
+
+            prototypes.push((*category, normalized(&centroid)));
+        }
+
+        debug!("Built {} semantic classifier prototypes", prototypes.len());
+
+        Ok(Self {
+            embedder,
+            prototypes,
+        })
+    }
+
+    /// Categories with a prototype
+    pub fn categories(&self) -> impl Iterator<Item = QueryCategory> + '_ {
+        self.prototypes.iter().map(|(category, _)| *category)
+    }
+
+    /// Cosine similarity of the query to every prototype, best first
+    pub fn scores(&self, query: &str) -> Result<Vec<(QueryCategory, f32)>> {
+        let embedding = normalized(&self.embedder.embed_single(query)?);
+
+        let mut scores: Vec<(QueryCategory, f32)> = self
+            .prototypes
+            .iter()
+            .map(|(category, prototype)| (*category, dot(&embedding, prototype)))
+            .collect();
+        scores.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+        Ok(scores)
+    }
+
+    /// Most similar category and its cosine similarity
+    pub fn classify(&self, query: &str) -> Result<Option<(QueryCategory, f32)>> {
+        Ok(self.scores(query)?.into_iter().next())
+    }
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm > 0.0 {
+        v.iter().map(|x| x / norm).collect()
+    } else {
+        v.to_vec()
+    }
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use neuro_embeddings::EmbeddingModel;
+
+    /// Embeds text as counts of a few keywords
+    pub(crate) struct KeywordEmbedder;
+
+    const KEYWORDS: &[&[&str]] = &[
+        &["numbers", "sum", "total", "crunch"],
+        &["program", "function", "script", "bug"],
+        &["hello", "hi", "morning"],
+        &["pizza", "movie", "bored"],
+    ];
+
+    impl Embedder for KeywordEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::default()
+        }
+
+        fn dimension(&self) -> usize {
+            KEYWORDS.len() + 1
+        }
+
+        fn embed_single(&self, text: &str) -> Result<Vec<f32>> {
+            let text = text.to_lowercase();
+            let mut embedding: Vec<f32> = KEYWORDS
+                .iter()
+                .map(|words| words.iter().filter(|w| text.contains(*w)).count() as f32)
+                .collect();
+            // Small constant component so no vector is all zeros
+            embedding.push(0.1);
+            Ok(embedding)
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    pub(crate) const TEST_EXAMPLES: &[(QueryCategory, &[&str])] = &[
+        (QueryCategory::Math, &["sum these numbers", "what is the total"]),
+        (QueryCategory::Code, &["fix my program", "write a script"]),
+        (QueryCategory::Greeting, &["hello", "good morning"]),
+        (QueryCategory::Conversational, &["I like pizza", "I'm bored"]),
+    ];
+
+    pub(crate) fn test_classifier() -> SemanticClassifier {
+        SemanticClassifier::with_examples(Arc::new(KeywordEmbedder), TEST_EXAMPLES).unwrap()
+    }
+
+    #[test]
+    fn test_semantic_paraphrase() {
+        let classifier = test_classifier();
+        let (category, similarity) = classifier
+            .classify("could you crunch these numbers for me")
+            .unwrap()
+            .unwrap();
+        assert_eq!(category, QueryCategory::Math);
+        assert!(similarity > 0.9);
+    }
+
+    #[test]
+    fn test_semantic_scores_sorted() {
+        let classifier = test_classifier();
+        assert_eq!(classifier.categories().count(), 4);
+
+        let scores = classifier.scores("that movie was fun").unwrap();
+        assert_eq!(scores.len(), 4);
+        assert_eq!(scores[0].0, QueryCategory::Conversational);
+        assert!(scores.windows(2).all(|w| w[0].1 >= w[1].1));
+    }
+}
@@ -1,6 +1,8 @@
 //! CLI argument parsing
 
 use clap::{Parser, Subcommand};
+use neuro_classifier::Language;
+use neuro_llm::ProviderKind;
 use std::path::PathBuf;
 
 /// neuro-bitnet - A Rust-based RAG system
@@ -40,9 +42,38 @@ pub enum Commands {
         /// Embedding model to use
         #[arg(short, long, default_value = "minilm")]
         model: String,
+
+        /// Local GGUF model that answers `/ask` (preferred over --llm-url)
+        #[arg(long)]
+        model_path: Option<PathBuf>,
+
+        /// LLM server that answers `/ask` when no local model is given
+        #[arg(long)]
+        llm_url: Option<String>,
+
+        /// API the LLM server speaks (openai, llamacpp, ollama)
+        #[arg(long, default_value = "openai")]
+        llm_provider: ProviderKind,
+
+        /// Model name sent to the LLM server
+        #[arg(long)]
+        llm_model: Option<String>,
+
+        /// Requests per second each client may make to the endpoints that
+        /// embed or generate (unlimited by default)
+        #[arg(long)]
+        rate_limit: Option<f64>,
+
+        /// Origin allowed to call the API from a browser (repeatable, `*` for
+        /// any); cross-origin requests are refused by default
+        #[arg(long)]
+        cors_origin: Vec<String>,
     },
 
     /// Index files or directories
+    ///
+    /// Directories are walked the way git sees them: hidden files and
+    /// anything matched by `.gitignore` or `.neuroignore` are skipped.
     Index {
         /// Path(s) to index
         #[arg(required = true)]
@@ -68,13 +99,82 @@ pub enum Commands {
         #[arg(short, long)]
         storage: Option<PathBuf>,
 
-        /// Embedding model to use
+        /// Embedding model to use (`auto` goes multilingual for non-English files)
         #[arg(short, long, default_value = "minilm")]
         model: String,
 
+        /// Number of files embedded per batch
+        #[arg(long, default_value = "32")]
+        batch_size: usize,
+
         /// Show progress bar
         #[arg(long, default_value = "true")]
         progress: bool,
+
+        /// Store each function, class and section as its own code document
+        /// instead of whole files; unchanged symbols are not re-embedded and
+        /// removed ones are dropped
+        #[arg(long)]
+        code: bool,
+    },
+
+    /// Watch a directory and keep its documents in storage up to date
+    ///
+    /// Changed files are re-embedded and replace their earlier documents;
+    /// deleted files are dropped. Ignore rules match `neuro index`.
+    Watch {
+        /// Directory to watch
+        path: PathBuf,
+
+        /// Storage directory for persistence
+        #[arg(short, long)]
+        storage: PathBuf,
+
+        /// File patterns to include (glob)
+        #[arg(short, long)]
+        include: Option<Vec<String>>,
+
+        /// File patterns to exclude (glob)
+        #[arg(short, long)]
+        exclude: Option<Vec<String>>,
+
+        /// Maximum file size in KB
+        #[arg(long, default_value = "1024")]
+        max_size: usize,
+
+        /// Embedding model to use (`auto` uses the model the storage was indexed with)
+        #[arg(short, long, default_value = "auto")]
+        model: String,
+
+        /// Quiet period in milliseconds before changes are processed
+        #[arg(long, default_value = "500")]
+        debounce_ms: u64,
+    },
+
+    /// Find functions, classes and other symbols by name
+    ///
+    /// The query is a name, optionally after a kind and with a trailing `*`
+    /// for a prefix match: `parse_config`, `fn parse_*`, `class Model`,
+    /// `Model::layers`. Files are analyzed on the fly.
+    Symbols {
+        /// Symbol query
+        query: String,
+
+        /// Files or directories to search
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Match the name as a fuzzy subsequence (`pcfg` finds `parse_config`)
+        #[arg(long)]
+        fuzzy: bool,
+
+        /// Maximum number of results
+        #[arg(short, long, default_value = "20")]
+        limit: usize,
+
+        /// Output format (text, json)
+        #[arg(short, long, default_value = "text")]
+        format: String,
     },
 
     /// Execute a query against the RAG system
@@ -90,7 +190,7 @@ pub enum Commands {
         #[arg(short, long)]
         storage: Option<PathBuf>,
 
-        /// Embedding model to use
+        /// Embedding model to use (`auto` uses the model the storage was indexed with)
         #[arg(short, long, default_value = "minilm")]
         model: String,
 
@@ -101,6 +201,10 @@ pub enum Commands {
         /// Include web search if needed
         #[arg(short, long)]
         web: bool,
+
+        /// Only search documents from this source (manual, file, web, conversation, code)
+        #[arg(long)]
+        source: Option<String>,
     },
 
     /// Show storage statistics
@@ -132,6 +236,14 @@ pub enum Commands {
         /// Output format (text, json)
         #[arg(short, long, default_value = "text")]
         format: String,
+
+        /// Custom pattern file (YAML or TOML)
+        #[arg(long)]
+        patterns: Option<PathBuf>,
+
+        /// Built-in language packs to use, comma-separated (en, es, fr, de, pt; default: all)
+        #[arg(long = "lang", value_delimiter = ',', conflicts_with = "patterns")]
+        languages: Vec<Language>,
     },
 
     /// Search the web
@@ -146,6 +258,64 @@ pub enum Commands {
         /// Output format (text, json)
         #[arg(short, long, default_value = "text")]
         format: String,
+
+        /// Where to search (wikipedia, wikidata, github, github-code,
+        /// stackoverflow, or all to merge wikipedia, github and stackoverflow).
+        /// GitHub uses GITHUB_TOKEN if set; github-code requires it
+        #[arg(short, long, default_value = "wikipedia")]
+        source: String,
+
+        /// Wikipedia language edition (e.g. es), or auto to use the query language
+        #[arg(short, long, default_value = "auto")]
+        language: String,
+    },
+
+    /// Fetch web pages and store their text as documents
+    IngestUrl {
+        /// URL(s) to ingest
+        #[arg(required = true)]
+        urls: Vec<String>,
+
+        /// Storage directory for persistence
+        #[arg(short, long)]
+        storage: PathBuf,
+
+        /// Embedding model to use (`auto` uses the model the storage was indexed with)
+        #[arg(short, long, default_value = "auto")]
+        model: String,
+
+        /// Chunk length in bytes
+        #[arg(long, default_value = "1000")]
+        chunk_size: usize,
+    },
+
+    /// Subscribe to RSS/Atom feeds and store their new entries as documents
+    ///
+    /// Subscriptions are kept in `feeds.json` in the storage directory; each
+    /// run ingests the entries published since the previous one.
+    IngestFeed {
+        /// Feed URL(s) to subscribe to (omit to refresh existing subscriptions)
+        feeds: Vec<String>,
+
+        /// Storage directory for persistence
+        #[arg(short, long)]
+        storage: PathBuf,
+
+        /// Feed URL(s) to unsubscribe from
+        #[arg(long)]
+        remove: Vec<String>,
+
+        /// Embedding model to use (`auto` uses the model the storage was indexed with)
+        #[arg(short, long, default_value = "auto")]
+        model: String,
+
+        /// Chunk length in bytes
+        #[arg(long, default_value = "1000")]
+        chunk_size: usize,
+
+        /// Most entries read from each feed
+        #[arg(long, default_value = "20")]
+        max_entries: usize,
     },
 
     /// Ask a question to the LLM (local BitNet inference or remote server)
@@ -157,7 +327,7 @@ pub enum Commands {
         #[arg(short = 'm', long)]
         model_path: Option<PathBuf>,
 
-        /// BitNet model to use (2b, large, 3b, 8b) - auto-downloads if needed
+        /// Model to use (2b, large, 3b, 8b, a registered name or hf:org/repo:file.gguf) - auto-downloads if needed
         #[arg(long, default_value = "2b")]
         model: String,
 
@@ -165,6 +335,14 @@ pub enum Commands {
         #[arg(short, long, default_value = "http://localhost:11435")]
         llm_url: String,
 
+        /// API the LLM server speaks (openai, llamacpp, ollama)
+        #[arg(long, default_value = "openai")]
+        llm_provider: ProviderKind,
+
+        /// Model name sent to the LLM server (default: the first model it serves)
+        #[arg(long)]
+        llm_model: Option<String>,
+
         /// Maximum tokens to generate
         #[arg(long, default_value = "512")]
         max_tokens: u32,
@@ -197,7 +375,7 @@ pub enum Commands {
         #[arg(long)]
         timing: bool,
 
-        /// Stream output (local inference only)
+        /// Stream output as it is generated
         #[arg(long)]
         stream: bool,
 
@@ -214,11 +392,52 @@ pub enum Commands {
         translate: bool,
     },
 
+    /// Chat with a local BitNet model, keeping the conversation between turns
+    Chat {
+        /// Path to local GGUF model file
+        #[arg(short = 'm', long)]
+        model_path: Option<PathBuf>,
+
+        /// Model to use (2b, large, 3b, 8b, a registered name or hf:org/repo:file.gguf) - auto-downloads if needed
+        #[arg(long, default_value = "2b")]
+        model: String,
+
+        /// System prompt
+        #[arg(long)]
+        system: Option<String>,
+
+        /// Maximum tokens per reply
+        #[arg(long, default_value = "512")]
+        max_tokens: u32,
+
+        /// Temperature (0.0 = deterministic, 1.0 = creative)
+        #[arg(short, long, default_value = "0.7")]
+        temperature: f32,
+
+        /// Context size; older turns are dropped when the chat outgrows it
+        #[arg(long, default_value = "2048")]
+        ctx_size: u32,
+
+        /// Number of CPU threads (default: auto-detect)
+        #[arg(long)]
+        threads: Option<i32>,
+
+        /// Skip download confirmation (auto-download model)
+        #[arg(short = 'y', long)]
+        yes: bool,
+    },
+
     /// Manage BitNet models (list, download, remove)
     Model {
         #[command(subcommand)]
         action: ModelAction,
     },
+
+    /// Manage storage backends (migrate, reembed)
+    Storage {
+        #[command(subcommand)]
+        action: StorageAction,
+    },
 }
 
 /// Model management subcommands
@@ -229,23 +448,123 @@ pub enum ModelAction {
 
     /// Download a model
     Download {
-        /// Model to download (2b, large, 3b, 8b)
+        /// Model to download (2b, large, 3b, 8b, a registered name or hf:org/repo[@revision]:file.gguf)
         #[arg(default_value = "2b")]
         model: String,
 
         /// Force re-download
         #[arg(short, long)]
         force: bool,
+
+        /// Hugging Face token for gated or private repos (default: $HF_TOKEN)
+        #[arg(long)]
+        hf_token: Option<String>,
     },
 
-    /// Remove a downloaded model
+    /// Remove a downloaded model, or unregister a custom one
     Remove {
-        /// Model to remove (2b, large, 3b, 8b)
+        /// Model to remove (2b, large, 3b, 8b or a registered name)
         model: String,
     },
 
+    /// Register a custom GGUF model under a name
+    Add {
+        /// Name to use wherever a model is accepted
+        name: String,
+
+        /// Local GGUF file, used in place
+        #[arg(long, conflicts_with = "url", required_unless_present = "url")]
+        path: Option<PathBuf>,
+
+        /// URL to download the GGUF file from
+        #[arg(long)]
+        url: Option<String>,
+
+        /// Expected SHA256 checksum of the file
+        #[arg(long)]
+        sha256: Option<String>,
+    },
+
+    /// Check cached models for truncated or corrupt files
+    Verify {
+        /// Model to check (2b, large, 3b, 8b or a registered name); default: all cached
+        model: Option<String>,
+
+        /// Re-download broken models without asking
+        #[arg(short, long)]
+        yes: bool,
+    },
+
     /// Show model cache info
     Info,
+
+    /// List the models a remote LLM server serves
+    Remote {
+        /// LLM server URL
+        #[arg(short, long, default_value = "http://localhost:11435")]
+        llm_url: String,
+
+        /// API the LLM server speaks (openai, llamacpp, ollama)
+        #[arg(long, default_value = "openai")]
+        llm_provider: ProviderKind,
+    },
+
+    /// Benchmark load time, prompt and generation speed, and memory
+    Bench {
+        /// Model (2b, large, 3b, 8b or a registered name) or path to a GGUF file
+        #[arg(default_value = "2b")]
+        model: String,
+
+        /// Thread counts to compare, comma-separated (default: all CPUs)
+        #[arg(short, long, value_delimiter = ',')]
+        threads: Vec<i32>,
+
+        /// Prompt length in tokens
+        #[arg(long, default_value = "512")]
+        prompt_tokens: usize,
+
+        /// Tokens to generate per run
+        #[arg(long, default_value = "128")]
+        gen_tokens: u32,
+
+        /// Runs per thread count
+        #[arg(long, default_value = "3")]
+        repetitions: usize,
+
+        /// Backend (auto, native, server, subprocess)
+        #[arg(long, default_value = "auto")]
+        backend: String,
+    },
+}
+
+/// Storage management subcommands
+#[derive(Subcommand, Debug)]
+pub enum StorageAction {
+    /// Copy all documents (with embeddings) from one backend to another
+    Migrate {
+        /// Source backend (file path, file://path, disk://dir, lance://dir, sqlite://path or memory://)
+        #[arg(long)]
+        from: String,
+
+        /// Target backend (file path, file://path, disk://dir, lance://dir, sqlite://path or memory://)
+        #[arg(long)]
+        to: String,
+
+        /// Number of documents written per batch
+        #[arg(long, default_value = "100")]
+        batch_size: usize,
+    },
+
+    /// Re-embed every document with a different embedding model
+    Reembed {
+        /// Storage backend (file path, file://path, disk://dir, lance://dir, sqlite://path or memory://)
+        #[arg(long)]
+        storage: String,
+
+        /// Embedding model to migrate to
+        #[arg(short, long, default_value = "minilm")]
+        model: String,
+    },
 }
 
 impl Cli {
@@ -2,25 +2,47 @@
 
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
-use std::path::PathBuf;
-use walkdir::WalkDir;
-
-use neuro_classifier::Classifier;
-use neuro_core::QueryResult;
-use neuro_embeddings::{Embedder, EmbeddingModel, FastEmbedder};
-use neuro_search::{WebSearcher, WikipediaSearcher};
-use neuro_server::{Server, ServerConfig};
-use neuro_storage::{FileStorage, MemoryStorage, Storage};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+
+use neuro_classifier::{Classifier, Language, QueryPatterns};
+use neuro_core::{ClassificationResult, DocumentSource, QueryResult};
+use neuro_embeddings::{
+    AsyncEmbedder, BatchEmbedder, CancellationToken, Embedder, EmbeddingError, EmbeddingModel,
+    FastEmbedder, SpawnBlockingEmbedder,
+};
+use neuro_indexer::{
+    index_to_storage, CodeChunk, CodeIndexer, IndexerConfig, IndexerError,
+    Language as IndexLanguage, NameMatch, PipelineOptions, PipelineReport, SymbolQuery,
+};
+use neuro_search::{
+    rank_by_similarity, AggregatedSearcher, FeedConfig, FeedReader, FeedSubscriptions,
+    GitHubConfig, GitHubSearchKind, GitHubSearcher, StackOverflowSearcher, WebSearchResult,
+    WebSearcher, WikidataSearcher, WikipediaSearcher, DEFAULT_MIN_SIMILARITY,
+};
+use neuro_server::{CorsConfig, IngestReport, RateLimitConfig, Server, ServerConfig, WebIngestor};
+use neuro_storage::{DiskStorage, DocumentFilter, FileStorage, MemoryStorage, Storage};
+
+use crate::cli::StorageAction;
 
 // ============================================================================
 // Serve command
 // ============================================================================
 
+#[allow(clippy::too_many_arguments)]
 pub async fn serve(
     host: String,
     port: u16,
     storage: Option<PathBuf>,
     model: String,
+    model_path: Option<PathBuf>,
+    llm_url: Option<String>,
+    llm_provider: neuro_llm::ProviderKind,
+    llm_model: Option<String>,
+    rate_limit: Option<f64>,
+    cors_origins: Vec<String>,
     verbose: bool,
 ) -> anyhow::Result<()> {
     init_tracing(verbose);
@@ -30,6 +52,15 @@ pub async fn serve(
         port,
         storage_path: storage,
         embedding_model: model,
+        model_path,
+        llm_url,
+        llm_provider,
+        llm_model,
+        rate_limit: rate_limit.map(|requests_per_second| RateLimitConfig {
+            requests_per_second,
+            ..RateLimitConfig::default()
+        }),
+        cors: (!cors_origins.is_empty()).then(|| CorsConfig::new(cors_origins)),
         ..ServerConfig::default()
     };
 
@@ -66,15 +97,13 @@ pub async fn index(
     max_size: usize,
     storage_path: Option<PathBuf>,
     model: String,
+    batch_size: usize,
     show_progress: bool,
+    code: bool,
     verbose: bool,
 ) -> anyhow::Result<()> {
     init_tracing(verbose);
 
-    println!("{} Initializing embedder...", "⚙".cyan().bold());
-    let embedding_model: EmbeddingModel = model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2);
-    let embedder = FastEmbedder::new(embedding_model)?;
-
     // Initialize storage
     let mut storage: Box<dyn Storage> = if let Some(path) = storage_path {
         println!(
@@ -91,9 +120,25 @@ pub async fn index(
         Box::new(MemoryStorage::new())
     };
 
-    // Collect files
+    if code {
+        let options = PipelineOptions {
+            indexer: IndexerConfig {
+                max_file_size: max_size * 1024,
+                ..Default::default()
+            },
+            recursive,
+            include: include.unwrap_or_default(),
+            exclude: exclude.unwrap_or_default(),
+            batch_size,
+            ..Default::default()
+        };
+        return index_code(paths, storage.as_mut(), &model, options, show_progress).await;
+    }
+
+    // Collect files, leaving out what .gitignore and .neuroignore exclude
     println!("{} Collecting files...", "🔍".cyan().bold());
     let mut files: Vec<PathBuf> = Vec::new();
+    let indexer = CodeIndexer::new();
 
     for path in paths {
         if path.is_file() {
@@ -101,15 +146,8 @@ pub async fn index(
                 files.push(path);
             }
         } else if path.is_dir() {
-            let walker = if recursive {
-                WalkDir::new(&path)
-            } else {
-                WalkDir::new(&path).max_depth(1)
-            };
-
-            for entry in walker.into_iter().filter_map(|e| e.ok()) {
-                let path = entry.path().to_path_buf();
-                if path.is_file() && should_include_file(&path, &include, &exclude, max_size) {
+            for path in indexer.files(&path, recursive) {
+                if should_include_file(&path, &include, &exclude, max_size) {
                     files.push(path);
                 }
             }
@@ -127,9 +165,70 @@ pub async fn index(
         files.len()
     );
 
-    // Index files
+    // Read files; Markdown is split into one document per section,
+    // notebooks into their cells and text, PDF and Word documents into
+    // paragraphs, so each is retrieved on its own
+    let mut errors = 0;
+    let mut pending: Vec<(PathBuf, String, Option<CodeChunk>)> = Vec::with_capacity(files.len());
+
+    for file in files {
+        match std::fs::read(&file) {
+            Ok(content) => match file_documents(&file, content) {
+                Ok(documents) => pending.extend(
+                    documents
+                        .into_iter()
+                        .map(|(content, section)| (file.clone(), content, section)),
+                ),
+                Err(e) => {
+                    errors += 1;
+                    if verbose {
+                        eprintln!(
+                            "{} Failed to parse {}: {}",
+                            "✗".red().bold(),
+                            file.display(),
+                            e
+                        );
+                    }
+                }
+            },
+            Err(e) => {
+                errors += 1;
+                if verbose {
+                    eprintln!(
+                        "{} Failed to read {}: {}",
+                        "✗".red().bold(),
+                        file.display(),
+                        e
+                    );
+                }
+            }
+        }
+    }
+
+    // Pick the embedding model; `auto` keeps the model already in storage,
+    // or goes multilingual when any file is not English
+    let embedding_model = if model.eq_ignore_ascii_case(AUTO_MODEL) {
+        match stored_embedding_model(storage.as_ref()).await {
+            Some(stored) => stored,
+            None => EmbeddingModel::auto_select(
+                pending.iter().map(|(_, content, _)| content.as_str()),
+                is_english,
+            ),
+        }
+    } else {
+        model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2)
+    };
+
+    println!(
+        "{} Initializing embedder ({})...",
+        "⚙".cyan().bold(),
+        embedding_model
+    );
+    let embedder = Arc::new(FastEmbedder::new(embedding_model)?);
+
+    // Embed files in batches
     let progress = if show_progress {
-        let pb = ProgressBar::new(files.len() as u64);
+        let pb = ProgressBar::new(pending.len() as u64);
         pb.set_style(
             ProgressStyle::default_bar()
                 .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")?
@@ -140,73 +239,80 @@ pub async fn index(
         None
     };
 
-    let mut indexed = 0;
-    let mut errors = 0;
-
-    for file in files {
-        if let Some(ref pb) = progress {
-            pb.set_message(format!("{}", file.display()));
-        }
+    let cancellation = CancellationToken::new();
+    let ctrl_c = {
+        let cancellation = cancellation.clone();
+        tokio::spawn(async move {
+            if tokio::signal::ctrl_c().await.is_ok() {
+                cancellation.cancel();
+            }
+        })
+    };
 
-        match std::fs::read_to_string(&file) {
-            Ok(content) => {
-                if content.trim().is_empty() {
+    let texts: Vec<String> = pending
+        .iter()
+        .map(|(_, content, _)| embedding_model.format_passage(content))
+        .collect();
+    let (embeddings, embed_error) = {
+        let embedder = Arc::clone(&embedder);
+        let progress = progress.clone();
+        let cancellation = cancellation.clone();
+        tokio::task::spawn_blocking(move || {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            let mut embeddings = Vec::with_capacity(texts.len());
+            let result = BatchEmbedder::new(embedder.as_ref())
+                .with_batch_size(batch_size)
+                .with_cancellation(cancellation)
+                .for_each_chunk(&texts, |batch_progress, chunk| {
+                    embeddings.extend(chunk);
                     if let Some(ref pb) = progress {
-                        pb.inc(1);
+                        pb.set_position(batch_progress.completed as u64);
                     }
-                    continue;
-                }
-
-                match embedder.embed_single(&content) {
-                    Ok(embedding) => {
-                        let mut doc = neuro_core::Document::new(&content)
-                            .with_embedding(embedding)
-                            .with_source(neuro_core::DocumentSource::File)
-                            .with_metadata(
-                                "file_path",
-                                serde_json::Value::String(file.display().to_string()),
-                            );
-
-                        if let Some(name) = file.file_name() {
-                            doc = doc.with_metadata(
-                                "file_name",
-                                serde_json::Value::String(name.to_string_lossy().to_string()),
-                            );
-                        }
+                });
+            (embeddings, result.err())
+        })
+        .await?
+    };
+    ctrl_c.abort();
 
-                        match storage.add(doc).await {
-                            Ok(_) => indexed += 1,
-                            Err(e) => {
-                                errors += 1;
-                                if verbose {
-                                    eprintln!(
-                                        "{} Failed to store {}: {}",
-                                        "✗".red().bold(),
-                                        file.display(),
-                                        e
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    Err(e) => {
-                        errors += 1;
-                        if verbose {
-                            eprintln!(
-                                "{} Failed to embed {}: {}",
-                                "✗".red().bold(),
-                                file.display(),
-                                e
-                            );
-                        }
-                    }
-                }
+    match embed_error {
+        Some(EmbeddingError::Cancelled { completed, total }) => {
+            if let Some(ref pb) = progress {
+                pb.abandon();
+            }
+            println!(
+                "{} Cancelled after embedding {} of {} documents",
+                "⚠".yellow().bold(),
+                completed,
+                total
+            );
+        }
+        Some(e) => {
+            if let Some(ref pb) = progress {
+                pb.abandon();
             }
+            eprintln!("{} Embedding failed: {}", "✗".red().bold(), e);
+        }
+        None => {
+            if let Some(ref pb) = progress {
+                pb.finish_with_message("Done");
+            }
+        }
+    }
+
+    // Store whatever was embedded; the rest counts as failed
+    errors += pending.len() - embeddings.len();
+    let mut indexed = 0;
+
+    for ((file, content, section), embedding) in pending.into_iter().zip(embeddings) {
+        let doc = file_document(&file, &content, section, embedding, embedding_model);
+        match storage.add(doc).await {
+            Ok(_) => indexed += 1,
             Err(e) => {
                 errors += 1;
                 if verbose {
                     eprintln!(
-                        "{} Failed to read {}: {}",
+                        "{} Failed to store {}: {}",
                         "✗".red().bold(),
                         file.display(),
                         e
@@ -214,18 +320,10 @@ pub async fn index(
                 }
             }
         }
-
-        if let Some(ref pb) = progress {
-            pb.inc(1);
-        }
-    }
-
-    if let Some(pb) = progress {
-        pb.finish_with_message("Done");
     }
 
     println!(
-        "\n{} Indexed {} files ({} errors)",
+        "\n{} Indexed {} documents ({} errors)",
         "✓".green().bold(),
         indexed,
         errors
@@ -242,6 +340,208 @@ pub async fn index(
     Ok(())
 }
 
+/// `neuro index --code`: symbol-level documents through the indexer's
+/// storage pipeline
+async fn index_code(
+    paths: Vec<PathBuf>,
+    storage: &mut dyn Storage,
+    model: &str,
+    mut options: PipelineOptions,
+    show_progress: bool,
+) -> anyhow::Result<()> {
+    let embedding_model = if model.eq_ignore_ascii_case(AUTO_MODEL) {
+        stored_embedding_model(&*storage).await.unwrap_or_default()
+    } else {
+        model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2)
+    };
+    println!(
+        "{} Initializing embedder ({})...",
+        "⚙".cyan().bold(),
+        embedding_model
+    );
+    let embedder = SpawnBlockingEmbedder::new(Arc::new(FastEmbedder::new(embedding_model)?));
+
+    let cancellation = CancellationToken::new();
+    options.cancellation = Some(cancellation.clone());
+    let ctrl_c = tokio::spawn(async move {
+        if tokio::signal::ctrl_c().await.is_ok() {
+            cancellation.cancel();
+        }
+    });
+
+    // The length is only known once the first batch is embedded
+    let progress = if show_progress {
+        let pb = ProgressBar::new(0);
+        pb.set_style(
+            ProgressStyle::default_bar()
+                .template(
+                    "{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})",
+                )?
+                .progress_chars("#>-"),
+        );
+        Some(pb)
+    } else {
+        None
+    };
+
+    let mut summary = PipelineReport::default();
+    let mut result = Ok(());
+    for path in paths {
+        println!("{} Indexing {}...", "🔍".cyan().bold(), path.display());
+        let report = index_to_storage(&path, &embedder, storage, &options, |p| {
+            if let Some(ref pb) = progress {
+                pb.set_length(p.total as u64);
+                pb.set_position(p.completed as u64);
+            }
+        })
+        .await;
+
+        match report {
+            Ok(report) => {
+                summary.files += report.files;
+                summary.chunks += report.chunks;
+                summary.stored += report.stored;
+                summary.unchanged += report.unchanged;
+                summary.removed += report.removed;
+                summary.errors += report.errors;
+            }
+            Err(IndexerError::Embedding(EmbeddingError::Cancelled { completed, total })) => {
+                println!(
+                    "{} Cancelled after embedding {} of {} chunks",
+                    "⚠".yellow().bold(),
+                    completed,
+                    total
+                );
+                break;
+            }
+            Err(e) => {
+                result = Err(e.into());
+                break;
+            }
+        }
+    }
+    ctrl_c.abort();
+    if let Some(pb) = progress {
+        pb.finish_and_clear();
+    }
+
+    println!(
+        "\n{} Indexed {} files into {} code documents ({} new, {} unchanged, {} removed, {} errors)",
+        "✓".green().bold(),
+        summary.files,
+        summary.chunks,
+        summary.stored,
+        summary.unchanged,
+        summary.removed,
+        summary.errors
+    );
+    result
+}
+
+/// `--model` value that picks the embedding model automatically
+const AUTO_MODEL: &str = "auto";
+
+/// Embedding model recorded in storage by an earlier index run
+async fn stored_embedding_model(storage: &dyn Storage) -> Option<EmbeddingModel> {
+    storage.stats().await.embedding_model?.parse().ok()
+}
+
+/// Check whether text looks English, using the daemon's language detection
+fn is_english(text: &str) -> bool {
+    neuro_inference::detect_language(text) == neuro_inference::Language::English
+}
+
+/// Texts to embed for a file: one per Markdown section, notebook cell or
+/// run of document paragraphs (windowed when oversized), otherwise the
+/// whole file
+fn file_documents(
+    file: &Path,
+    content: Vec<u8>,
+) -> neuro_indexer::Result<Vec<(String, Option<CodeChunk>)>> {
+    let language = match IndexLanguage::from_path(file) {
+        Some(
+            language @ (IndexLanguage::Markdown
+            | IndexLanguage::Notebook
+            | IndexLanguage::Text
+            | IndexLanguage::Pdf
+            | IndexLanguage::Docx),
+        ) => language,
+        _ => {
+            let content = String::from_utf8(content).map_err(|e| {
+                IndexerError::ParseError(format!("{} is not UTF-8: {}", file.display(), e))
+            })?;
+            if content.trim().is_empty() {
+                return Ok(Vec::new());
+            }
+            return Ok(vec![(content, None)]);
+        }
+    };
+
+    let sections =
+        CodeIndexer::new().index_bytes(&content, &file.display().to_string(), language)?;
+    Ok(sections
+        .into_iter()
+        .map(|section| (section.to_document_content(), Some(section)))
+        .collect())
+}
+
+/// Stored document for `content` from `file`, with file metadata
+fn file_document(
+    file: &Path,
+    content: &str,
+    section: Option<CodeChunk>,
+    embedding: Vec<f32>,
+    embedding_model: EmbeddingModel,
+) -> neuro_core::Document {
+    let mut doc = neuro_core::Document::new(content)
+        .with_embedding(embedding)
+        .with_embedding_model(embedding_model.to_string())
+        .with_source(neuro_core::DocumentSource::File)
+        .with_metadata(
+            "file_path",
+            serde_json::Value::String(file.display().to_string()),
+        );
+
+    if let Some(name) = file.file_name() {
+        doc = doc.with_metadata(
+            "file_name",
+            serde_json::Value::String(name.to_string_lossy().to_string()),
+        );
+    }
+
+    if let Some(language) = IndexLanguage::from_path(file) {
+        doc = doc.with_metadata(
+            "language",
+            serde_json::Value::String(language.name().to_lowercase()),
+        );
+    }
+
+    if let Some(section) = section {
+        doc = doc
+            .with_metadata("section", serde_json::Value::String(section.display_name()))
+            .with_metadata("start_line", serde_json::Value::from(section.start_line));
+        if let Some(cell) = section.cell {
+            doc = doc.with_metadata("cell", serde_json::Value::from(cell));
+        }
+        if let Some(page) = section.page {
+            doc = doc.with_metadata("page", serde_json::Value::from(page));
+        }
+        if let Some((part, parts)) = section.part {
+            doc = doc
+                .with_metadata("part", serde_json::Value::from(part))
+                .with_metadata("parts", serde_json::Value::from(parts));
+        }
+        if !section.calls.is_empty() {
+            doc = doc.with_metadata("calls", serde_json::Value::from(section.calls));
+        }
+        if !section.called_by.is_empty() {
+            doc = doc.with_metadata("called_by", serde_json::Value::from(section.called_by));
+        }
+    }
+
+    doc
+}
+
 fn should_include_file(
     path: &PathBuf,
     include: &Option<Vec<String>>,
@@ -286,6 +586,7 @@ fn should_include_file(
 // Query command
 // ============================================================================
 
+#[allow(clippy::too_many_arguments)]
 pub async fn query(
     query_text: String,
     top_k: usize,
@@ -293,14 +594,14 @@ pub async fn query(
     model: String,
     format: String,
     web_search: bool,
+    source: Option<String>,
     verbose: bool,
 ) -> anyhow::Result<()> {
     init_tracing(verbose);
 
-    println!("{} Loading model...", "⚙".cyan().bold());
-    let embedding_model: EmbeddingModel = model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2);
-    let embedder = FastEmbedder::new(embedding_model)?;
-    let classifier = Classifier::new();
+    let source: Option<DocumentSource> = source
+        .map(|s| s.parse().map_err(|e: String| anyhow::anyhow!(e)))
+        .transpose()?;
 
     // Initialize storage
     let storage: Box<dyn Storage> = if let Some(path) = storage_path {
@@ -309,14 +610,28 @@ pub async fn query(
         Box::new(MemoryStorage::new())
     };
 
+    println!("{} Loading model...", "⚙".cyan().bold());
+    let embedding_model = if model.eq_ignore_ascii_case(AUTO_MODEL) {
+        stored_embedding_model(storage.as_ref())
+            .await
+            .unwrap_or_default()
+    } else {
+        model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2)
+    };
+    let embedder = FastEmbedder::new(embedding_model)?;
+    let classifier = Classifier::new();
+
     // Classify
     println!("{} Classifying query...", "🔍".cyan().bold());
     let classification = classifier.classify(&query_text);
 
     // Embed and search
     println!("{} Searching...", "🔍".cyan().bold());
-    let embedding = embedder.embed_single(&query_text)?;
-    let search_results = storage.search(&embedding, top_k).await?;
+    let embedding = embedder.embed_query(&query_text)?;
+    let search_results = match source {
+        Some(source) => storage.search_by_source(&embedding, source, top_k).await?,
+        None => storage.search(&embedding, top_k).await?,
+    };
 
     // Build result
     let mut result = QueryResult::new(&query_text, classification);
@@ -327,15 +642,27 @@ pub async fn query(
     if web_search {
         println!("{} Searching web...", "🌐".cyan().bold());
         let searcher = WikipediaSearcher::new();
-        if let Ok(web_results) = searcher.search(&query_text, 3).await {
-            let mut context = result.context.clone();
-            for web_result in web_results {
-                if !context.is_empty() {
-                    context.push_str("\n\n---\n\n");
+        if let Ok(web_results) = searcher.search(&query_text, 6).await {
+            // Most relevant first, off-topic results dropped
+            let summaries: Vec<String> = web_results.iter().map(WebSearchResult::summary).collect();
+            let summaries: Vec<&str> = summaries.iter().map(String::as_str).collect();
+            let web_results = match embedder.embed_passages(&summaries) {
+                Ok(embeddings) => {
+                    rank_by_similarity(&embedding, web_results, &embeddings, DEFAULT_MIN_SIMILARITY)
                 }
-                context.push_str(&web_result.to_rag_context());
+                Err(_) => web_results,
+            };
+
+            if !web_results.is_empty() {
+                let mut context = result.context.clone();
+                for web_result in web_results.into_iter().take(3) {
+                    if !context.is_empty() {
+                        context.push_str("\n\n---\n\n");
+                    }
+                    context.push_str(&web_result.to_rag_context());
+                }
+                result = result.with_context(context).with_web_search();
             }
-            result = result.with_context(context).with_web_search();
         }
     }
 
@@ -431,6 +758,46 @@ pub async fn stats(storage_path: Option<PathBuf>, verbose: bool) -> anyhow::Resu
             dim.to_string().cyan()
         );
     }
+    if let Some(ref model) = stats.embedding_model {
+        println!("{} {}", "Embedding model:".bold(), model.cyan());
+    }
+    if let Some(norm) = stats.average_embedding_norm {
+        println!("{} {:.4}", "Average embedding norm:".bold(), norm);
+    }
+    if let (Some(oldest), Some(newest)) = (stats.oldest_document, stats.newest_document) {
+        println!("{} {}", "Oldest document:".bold(), oldest.format("%Y-%m-%d %H:%M:%S UTC"));
+        println!("{} {}", "Newest document:".bold(), newest.format("%Y-%m-%d %H:%M:%S UTC"));
+    }
+    println!(
+        "{} {} KB",
+        "Estimated memory:".bold(),
+        stats.estimated_memory_bytes / 1024
+    );
+
+    if !stats.documents_by_source.is_empty() {
+        println!("\n{}", "By source:".bold());
+        let mut sources: Vec<_> = stats.documents_by_source.iter().collect();
+        sources.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.to_string().cmp(&b.0.to_string())));
+        for (source, count) in sources {
+            println!("  {:<14} {}", source.to_string(), count.to_string().green());
+        }
+    }
+
+    if !stats.documents_by_user.is_empty() {
+        println!(
+            "\n{} ({} total)",
+            "By user:".bold(),
+            stats.unique_users.to_string().cyan()
+        );
+        let mut users: Vec<_> = stats.documents_by_user.iter().collect();
+        users.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        for (user, count) in users.iter().take(10) {
+            println!("  {:<14} {}", user, count.to_string().green());
+        }
+        if users.len() > 10 {
+            println!("  {}", format!("… and {} more", users.len() - 10).dimmed());
+        }
+    }
     println!("{}", "═".repeat(40).blue());
 
     Ok(())
@@ -480,10 +847,20 @@ pub fn embed(text: String, model: String, format: String, verbose: bool) -> anyh
 // Classify command
 // ============================================================================
 
-pub fn classify(query: String, format: String, verbose: bool) -> anyhow::Result<()> {
+pub fn classify(
+    query: String,
+    format: String,
+    patterns: Option<PathBuf>,
+    languages: Vec<Language>,
+    verbose: bool,
+) -> anyhow::Result<()> {
     init_tracing(verbose);
 
-    let classifier = Classifier::new();
+    let classifier = match patterns {
+        Some(path) => Classifier::with_patterns(QueryPatterns::from_file(path)?),
+        None if !languages.is_empty() => Classifier::with_languages(&languages),
+        None => Classifier::new(),
+    };
     let result = classifier.classify(&query);
 
     match format.as_str() {
@@ -500,11 +877,15 @@ pub fn classify(query: String, format: String, verbose: bool) -> anyhow::Result<
                 format!("{:?}", result.category).yellow()
             );
             println!(
-                "{} {:.2}",
+                "{} {}",
                 "Confidence:".bold(),
-                result.confidence.to_string().green()
+                format!("{:.2}", result.confidence).green()
             );
             println!("{} {:?}", "Strategy:".bold(), result.strategy);
+
+            if verbose {
+                print_classification_details(&result);
+            }
             println!("{}", "═".repeat(40).blue());
         }
     }
@@ -512,16 +893,85 @@ pub fn classify(query: String, format: String, verbose: bool) -> anyhow::Result<
     Ok(())
 }
 
+fn print_classification_details(result: &ClassificationResult) {
+    if !result.reasons.is_empty() {
+        println!("{}", "Reasons:".bold());
+        for reason in &result.reasons {
+            println!("  - {}", reason);
+        }
+    }
+
+    if result.matches.is_empty() {
+        println!("{} none", "Matched patterns:".bold());
+    } else {
+        println!("{}", "Matched patterns:".bold());
+        for m in &result.matches {
+            println!(
+                "  {} {} {} {}",
+                format!("[{}]", m.category).yellow(),
+                format!("+{:.2}", m.weight).green(),
+                format!("{:?} @{}..{}", m.text, m.start, m.end).cyan(),
+                m.pattern.dimmed()
+            );
+        }
+    }
+
+    let ranked = result.ranked_categories();
+    if !ranked.is_empty() {
+        println!("{}", "Probabilities:".bold());
+        for (category, probability) in ranked.iter().take(3) {
+            println!("  {:<18} {:.3}", category.to_string(), probability);
+        }
+    }
+}
+
 // ============================================================================
 // Search command
 // ============================================================================
 
-pub async fn search(query: String, count: usize, format: String, verbose: bool) -> anyhow::Result<()> {
+pub async fn search(
+    query: String,
+    count: usize,
+    format: String,
+    source: String,
+    language: String,
+    verbose: bool,
+) -> anyhow::Result<()> {
     init_tracing(verbose);
 
-    println!("{} Searching Wikipedia...", "🌐".cyan().bold());
-    let searcher = WikipediaSearcher::new();
-    let results = searcher.search(&query, count).await?;
+    let searcher: Box<dyn WebSearcher> = match source.as_str() {
+        "wikipedia" => Box::new(WikipediaSearcher::new()),
+        "wikidata" => Box::new(WikidataSearcher::new()),
+        "stackoverflow" => Box::new(StackOverflowSearcher::new()),
+        "all" => Box::new(
+            AggregatedSearcher::new()
+                .with_searcher(Arc::new(WikipediaSearcher::new()), 1.0)
+                .with_searcher(Arc::new(StackOverflowSearcher::new()), 1.0)
+                .with_searcher(Arc::new(GitHubSearcher::new()), 0.5),
+        ),
+        "github" | "github-code" => {
+            let kind = if source == "github-code" {
+                GitHubSearchKind::Code
+            } else {
+                GitHubSearchKind::Repositories
+            };
+            let token = std::env::var("GITHUB_TOKEN").ok().filter(|t| !t.is_empty());
+            if kind == GitHubSearchKind::Code && token.is_none() {
+                anyhow::bail!("GitHub code search requires GITHUB_TOKEN");
+            }
+            Box::new(GitHubSearcher::with_config(GitHubConfig {
+                token,
+                kind,
+                ..Default::default()
+            }))
+        }
+        other => anyhow::bail!("Unknown search source: {} (use wikipedia, wikidata, github, github-code, stackoverflow or all)", other),
+    };
+
+    println!("{} Searching {}...", "🌐".cyan().bold(), searcher.name());
+    let results = searcher
+        .search_in_language(&query, count, &language)
+        .await?;
 
     match format.as_str() {
         "json" => {
@@ -550,6 +1000,354 @@ pub async fn search(query: String, count: usize, format: String, verbose: bool)
     Ok(())
 }
 
+// ============================================================================
+// Ingest URL command
+// ============================================================================
+
+pub async fn ingest_url(
+    urls: Vec<String>,
+    storage_path: PathBuf,
+    model: String,
+    chunk_size: usize,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    init_tracing(verbose);
+
+    let mut storage = FileStorage::new(&storage_path).await?;
+    let ingestor = web_ingestor(&storage, &model, chunk_size).await?;
+
+    println!("{} Fetching {} page(s)...", "🌐".cyan().bold(), urls.len());
+    let ingestion = ingestor.ingest_urls(&urls).await?;
+    let report = ingestion.store(&mut storage).await?;
+    print_ingest_report(&report);
+
+    Ok(())
+}
+
+// ============================================================================
+// Watch command
+// ============================================================================
+
+#[allow(clippy::too_many_arguments)]
+pub async fn watch(
+    path: PathBuf,
+    storage_path: PathBuf,
+    include: Option<Vec<String>>,
+    exclude: Option<Vec<String>>,
+    max_size: usize,
+    model: String,
+    debounce_ms: u64,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    init_tracing(verbose);
+
+    // Saved once per update rather than after every document
+    let mut storage = FileStorage::new_manual_save(&storage_path).await?;
+    let embedding_model = if model.eq_ignore_ascii_case(AUTO_MODEL) {
+        stored_embedding_model(&storage).await.unwrap_or_default()
+    } else {
+        model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2)
+    };
+    println!(
+        "{} Initializing embedder ({})...",
+        "⚙".cyan().bold(),
+        embedding_model
+    );
+    let embedder = SpawnBlockingEmbedder::new(Arc::new(FastEmbedder::new(embedding_model)?));
+
+    let watcher = CodeIndexer::new().watch(&path, Duration::from_millis(debounce_ms))?;
+    let root = watcher.root().to_path_buf();
+    println!(
+        "{} Watching {} (Ctrl+C to stop)",
+        "👀".cyan().bold(),
+        path.display()
+    );
+
+    // The watcher blocks between updates, so it gets its own thread
+    let (tx, mut updates) = tokio::sync::mpsc::unbounded_channel();
+    std::thread::spawn(move || {
+        for update in watcher {
+            if tx.send(update).is_err() {
+                break;
+            }
+        }
+    });
+
+    loop {
+        let update = tokio::select! {
+            update = updates.recv() => match update {
+                Some(update) => update,
+                None => break,
+            },
+            _ = tokio::signal::ctrl_c() => {
+                println!("\n{} Stopped watching", "⏹".yellow().bold());
+                break;
+            }
+        };
+        let update = match update {
+            Ok(update) => update,
+            Err(e) => {
+                eprintln!("{} {}", "✗".red().bold(), e);
+                continue;
+            }
+        };
+
+        // Store paths the way `neuro index <path>` does, so a watch picks
+        // up where an earlier index run left off
+        let as_indexed = |file: &Path| path.join(file.strip_prefix(&root).unwrap_or(file));
+
+        let mut dropped = 0;
+        for file in update.changed.iter().chain(&update.removed) {
+            let filter = DocumentFilter::new()
+                .with_source(DocumentSource::File)
+                .with_metadata(
+                    "file_path",
+                    serde_json::Value::String(as_indexed(file).display().to_string()),
+                );
+            dropped += storage.delete_where(&filter).await?;
+        }
+
+        let mut stored = 0;
+        for file in update.changed.iter().map(|f| as_indexed(f)) {
+            if !should_include_file(&file, &include, &exclude, max_size) {
+                continue;
+            }
+            // Binary files other than PDF and Word documents are skipped
+            let content = match std::fs::read(&file) {
+                Ok(content) => content,
+                Err(_) => continue,
+            };
+            let is_document = matches!(
+                IndexLanguage::from_path(&file),
+                Some(IndexLanguage::Pdf | IndexLanguage::Docx)
+            );
+            if !is_document && std::str::from_utf8(&content).is_err() {
+                continue;
+            }
+            let documents = match file_documents(&file, content) {
+                Ok(documents) if documents.is_empty() => continue,
+                Ok(documents) => documents,
+                Err(e) => {
+                    eprintln!(
+                        "{} Failed to parse {}: {}",
+                        "✗".red().bold(),
+                        file.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            let texts: Vec<&str> = documents.iter().map(|(text, _)| text.as_str()).collect();
+            let embeddings = match embedder.embed_passages(&texts).await {
+                Ok(embeddings) => embeddings,
+                Err(e) => {
+                    eprintln!(
+                        "{} Failed to embed {}: {}",
+                        "✗".red().bold(),
+                        file.display(),
+                        e
+                    );
+                    continue;
+                }
+            };
+
+            for ((content, section), embedding) in documents.into_iter().zip(embeddings) {
+                let doc = file_document(&file, &content, section, embedding, embedding_model);
+                storage.add(doc).await?;
+                stored += 1;
+            }
+        }
+        storage.save().await?;
+
+        println!(
+            "{} {} changed, {} removed: stored {} documents, dropped {}",
+            "↻".cyan().bold(),
+            update.changed.len(),
+            update.removed.len(),
+            stored,
+            dropped
+        );
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Symbols command
+// ============================================================================
+
+pub fn symbols(
+    query: String,
+    paths: Vec<PathBuf>,
+    fuzzy: bool,
+    limit: usize,
+    format: String,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    init_tracing(verbose);
+
+    let mut query: SymbolQuery = query.parse().map_err(anyhow::Error::msg)?;
+    if fuzzy {
+        query = query.with_matching(NameMatch::Fuzzy);
+    }
+
+    let indexer = CodeIndexer::new();
+    let mut chunks = Vec::new();
+    for path in &paths {
+        if path.is_dir() {
+            chunks.extend(indexer.index_directory(path)?);
+        } else {
+            chunks.extend(indexer.index_file_auto(path)?);
+        }
+    }
+
+    let matches: Vec<&CodeChunk> = CodeIndexer::find_symbols(&chunks, &query)
+        .into_iter()
+        .take(limit)
+        .collect();
+
+    match format.as_str() {
+        "json" => {
+            println!("{}", serde_json::to_string_pretty(&matches)?);
+        }
+        _ => {
+            if matches.is_empty() {
+                println!("{} No symbols match {:?}", "✗".yellow(), query.name);
+                return Ok(());
+            }
+            for chunk in &matches {
+                let location = format!(
+                    "{}:{}-{}",
+                    chunk.file_path, chunk.start_line, chunk.end_line
+                );
+                println!(
+                    "{} {} {}",
+                    format!("{:<10}", chunk.symbol_type.to_string()).yellow(),
+                    chunk.display_name().bold(),
+                    location.dimmed()
+                );
+                if verbose {
+                    if let Some(signature) = &chunk.signature {
+                        println!("           {}", signature.cyan());
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Ingest feed command
+// ============================================================================
+
+/// Subscriptions file in the storage directory
+const FEEDS_FILE: &str = "feeds.json";
+
+pub async fn ingest_feed(
+    feeds: Vec<String>,
+    storage_path: PathBuf,
+    remove: Vec<String>,
+    model: String,
+    chunk_size: usize,
+    max_entries: usize,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    init_tracing(verbose);
+
+    let mut storage = FileStorage::new(&storage_path).await?;
+    let feeds_path = storage_path.join(FEEDS_FILE);
+    let mut subscriptions = FeedSubscriptions::load(&feeds_path)?;
+
+    for url in &feeds {
+        if subscriptions.subscribe(url.as_str()) {
+            println!("{} Subscribed to {}", "+".green().bold(), url);
+        }
+    }
+    for url in &remove {
+        if subscriptions.unsubscribe(url) {
+            println!("{} Unsubscribed from {}", "-".yellow().bold(), url);
+        } else {
+            println!("{} Not subscribed to {}", "✗".red().bold(), url);
+        }
+    }
+    if subscriptions.feeds.is_empty() {
+        subscriptions.save(&feeds_path)?;
+        println!("{} No feed subscriptions", "ℹ".blue().bold());
+        return Ok(());
+    }
+
+    println!(
+        "{} Checking {} feed(s)...",
+        "📡".cyan().bold(),
+        subscriptions.feeds.len()
+    );
+    let reader = FeedReader::with_config(FeedConfig {
+        max_entries,
+        ..FeedConfig::default()
+    });
+    let (entries, errors) = subscriptions.refresh(&reader).await;
+    for (url, e) in &errors {
+        println!("{} Could not read feed {}: {}", "✗".red().bold(), url, e);
+    }
+
+    if entries.is_empty() {
+        println!("{} No new entries", "ℹ".blue().bold());
+    } else {
+        println!("{} {} new entries", "🌐".cyan().bold(), entries.len());
+        let ingestor = web_ingestor(&storage, &model, chunk_size).await?;
+        let ingestion = ingestor.ingest_results(entries).await?;
+        let report = ingestion.store(&mut storage).await?;
+        print_ingest_report(&report);
+    }
+
+    // Only remember entries as seen once they are stored
+    subscriptions.save(&feeds_path)?;
+    Ok(())
+}
+
+/// Ingestor embedding with `model` (`auto` keeps the model already in storage)
+async fn web_ingestor(
+    storage: &FileStorage,
+    model: &str,
+    chunk_size: usize,
+) -> anyhow::Result<WebIngestor> {
+    let embedding_model = if model.eq_ignore_ascii_case(AUTO_MODEL) {
+        stored_embedding_model(storage).await.unwrap_or_default()
+    } else {
+        model.parse().unwrap_or(EmbeddingModel::AllMiniLmL6V2)
+    };
+
+    println!(
+        "{} Initializing embedder ({})...",
+        "⚙".cyan().bold(),
+        embedding_model
+    );
+    let embedder = SpawnBlockingEmbedder::new(Arc::new(FastEmbedder::new(embedding_model)?));
+    Ok(WebIngestor::new(Arc::new(embedder)).with_chunk_size(chunk_size))
+}
+
+fn print_ingest_report(report: &IngestReport) {
+    println!(
+        "{} Stored {} chunks from {} page(s)",
+        "✓".green().bold(),
+        report.chunks,
+        report.pages
+    );
+    if report.replaced > 0 {
+        println!(
+            "  {} Replaced {} chunks from earlier ingestions",
+            "→".dimmed(),
+            report.replaced
+        );
+    }
+    for url in &report.failed {
+        println!("{} Could not fetch {}", "✗".red().bold(), url);
+    }
+}
+
 // ============================================================================
 // Ask command (LLM integration)
 // ============================================================================
@@ -559,6 +1357,8 @@ pub async fn ask(
     model_path: Option<PathBuf>,
     model_name: String,
     llm_url: String,
+    llm_provider: neuro_llm::ProviderKind,
+    llm_model: Option<String>,
     max_tokens: u32,
     temperature: f32,
     ctx_size: u32,
@@ -574,7 +1374,7 @@ pub async fn ask(
     verbose: bool,
 ) -> anyhow::Result<()> {
     use neuro_storage::Storage;
-    use neuro_inference::{BitNetModel, ModelCache, DownloadOptions, get_or_download, detect_language, Language};
+    use neuro_inference::{detect_language, Language};
     use std::time::Instant;
 
     init_tracing(verbose);
@@ -617,11 +1417,11 @@ pub async fn ask(
         println!("{} Loading context from storage...", "📁".cyan().bold());
         let ctx_start = Instant::now();
         
-        let embedding_model = neuro_embeddings::EmbeddingModel::AllMiniLmL6V2;
-        let embedder = FastEmbedder::new(embedding_model)?;
         let storage = FileStorage::new(path).await?;
+        let embedding_model = stored_embedding_model(&storage).await.unwrap_or_default();
+        let embedder = FastEmbedder::new(embedding_model)?;
         
-        let query_embedding = embedder.embed_single(&question)?;
+        let query_embedding = embedder.embed_query(&question)?;
         let results = storage.search(&query_embedding, 3).await?;
         
         for result in results {
@@ -638,7 +1438,9 @@ pub async fn ask(
         let web_start = Instant::now();
         
         let searcher = WikipediaSearcher::new();
-        if let Ok(results) = searcher.search(&question, 3).await {
+        // Wikipedia edition of the question's language
+        let language = original_language.code().unwrap_or("auto");
+        if let Ok(results) = searcher.search_in_language(&question, 3, language).await {
             for result in results {
                 context_parts.push(format!("[{}] {}", result.title, result.snippet));
             }
@@ -653,34 +1455,8 @@ pub async fn ask(
     };
 
     // Step 3: Resolve model path
-    let resolved_model_path = if let Some(path) = model_path {
-        path
-    } else {
-        let bitnet_model = BitNetModel::from_str(&model_name)
-            .ok_or_else(|| anyhow::anyhow!(
-                "Unknown model '{}'. Available: 2b, large, 3b, 8b", model_name
-            ))?;
-
-        let cache = ModelCache::new()?;
-        
-        let download_opts = DownloadOptions {
-            yes: auto_yes,
-            verify: true,
-            force: force_download,
-        };
-
-        if !cache.is_downloaded(bitnet_model) {
-            println!(
-                "{} Model {} not found locally",
-                "📦".yellow().bold(),
-                bitnet_model.name()
-            );
-            
-            get_or_download(&cache, bitnet_model, &download_opts).await?
-        } else {
-            cache.model_path(bitnet_model)
-        }
-    };
+    let resolved_model_path =
+        resolve_model_path(model_path, &model_name, auto_yes, force_download).await?;
 
     // Step 4: Generate response - local or remote
     let (answer, llm_time, was_translated, translated_question) = if resolved_model_path.exists() {
@@ -699,7 +1475,17 @@ pub async fn ask(
         ).await?
     } else {
         // Remote server
-        let (ans, time) = ask_remote(&question, &context, &llm_url, max_tokens, temperature).await?;
+        let (ans, time) = ask_remote(
+            &question,
+            &context,
+            &llm_url,
+            llm_provider,
+            llm_model.as_deref(),
+            max_tokens,
+            temperature,
+            stream,
+        )
+        .await?;
         (ans, time, false, None)
     };
 
@@ -750,6 +1536,127 @@ pub async fn ask(
     Ok(())
 }
 
+/// Use the given GGUF file, or download the named model if needed
+async fn resolve_model_path(
+    model_path: Option<PathBuf>,
+    model_name: &str,
+    auto_yes: bool,
+    force_download: bool,
+) -> anyhow::Result<PathBuf> {
+    use neuro_inference::{ModelCache, DownloadOptions, get_or_download_ref};
+
+    if let Some(path) = model_path {
+        return Ok(path);
+    }
+
+    let cache = ModelCache::new()?;
+    let model = cache.resolve(model_name)?;
+    
+    let download_opts = DownloadOptions {
+        yes: auto_yes,
+        verify: true,
+        force: force_download,
+        ..Default::default()
+    };
+
+    if !cache.is_available(&model) {
+        println!(
+            "{} Model {} not found locally",
+            "📦".yellow().bold(),
+            model.name()
+        );
+        
+        Ok(get_or_download_ref(&cache, &model, &download_opts).await?)
+    } else {
+        Ok(cache.resolved_path(&model))
+    }
+}
+
+/// Interactive multi-turn chat with a local model
+pub async fn chat(
+    model_path: Option<PathBuf>,
+    model_name: String,
+    system: Option<String>,
+    max_tokens: u32,
+    temperature: f32,
+    ctx_size: u32,
+    threads: Option<i32>,
+    auto_yes: bool,
+    verbose: bool,
+) -> anyhow::Result<()> {
+    use neuro_inference::{ChatSession, GenerateOptions, InferenceConfig, InferenceModel, SamplerConfig};
+    use std::io::BufRead;
+
+    init_tracing(verbose);
+
+    let model_path = resolve_model_path(model_path, &model_name, auto_yes, false).await?;
+
+    println!(
+        "{} Loading BitNet model: {}",
+        "🤖".cyan().bold(),
+        model_path.display()
+    );
+
+    let mut config = InferenceConfig::new(&model_path).with_context_size(ctx_size);
+    if let Some(t) = threads {
+        config = config.with_threads(t);
+    }
+    let model = tokio::task::spawn_blocking(move || InferenceModel::load(config)).await??;
+
+    if verbose {
+        eprintln!("  Using backend: {}", model.backend_name());
+    }
+
+    let mut session = ChatSession::new();
+    if let Some(system) = system {
+        session = session.with_system_prompt(system);
+    }
+    let options = GenerateOptions::new(max_tokens)
+        .with_sampler(SamplerConfig::default().with_temperature(temperature));
+
+    println!(
+        "{} Type a message, {} to start over, {} to quit",
+        "💬".cyan().bold(),
+        "/reset".bold(),
+        "/exit".bold()
+    );
+
+    let stdin = std::io::stdin();
+    loop {
+        print!("{} ", ">".green().bold());
+        std::io::stdout().flush().ok();
+
+        let mut line = String::new();
+        if stdin.lock().read_line(&mut line)? == 0 {
+            println!();
+            break;
+        }
+
+        match line.trim() {
+            "" => continue,
+            "/exit" | "/quit" => break,
+            "/reset" => {
+                session.clear();
+                println!("{}", "Conversation cleared".dimmed());
+            }
+            message => {
+                let reply = session.send_async(&model, message, &options).await?;
+                println!("{}\n", reply.trim());
+
+                if verbose {
+                    eprintln!(
+                        "  {} turns, {} prompt tokens",
+                        session.history().len() / 2,
+                        session.prompt_tokens(&model)
+                    );
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
 /// Ask using local model inference
 async fn ask_local(
     question: &str,
@@ -763,7 +1670,7 @@ async fn ask_local(
     translate: bool,
     verbose: bool,
 ) -> anyhow::Result<(String, std::time::Duration, bool, Option<String>)> {
-    use neuro_inference::{InferenceConfig, InferenceModel, GenerateOptions, SamplerConfig, translate_to_english, detect_language, Language};
+    use neuro_inference::{InferenceConfig, InferenceModel, GenerateOptions, SamplerConfig, DictionaryTranslator, Translator, detect_language, Language};
     use std::time::Instant;
 
     println!(
@@ -799,9 +1706,9 @@ async fn ask_local(
         // Step 1: If translate enabled, translate question to English using dictionary
         let (effective_question, was_translated, translated_q) = if translate {
             let lang = detect_language(&question_owned);
-            if lang == Language::Spanish {
-                eprintln!("🌐 Translating to English...");
-                let english_question = translate_to_english(&question_owned);
+            if lang != Language::English {
+                eprintln!("🌐 Translating {} to English...", lang.name());
+                let english_question = DictionaryTranslator::new().translate(&question_owned, lang)?;
                 
                 if verbose {
                     eprintln!("  {} → {}", question_owned, english_question);
@@ -815,7 +1722,26 @@ async fn ask_local(
             (question_owned.clone(), false, None)
         };
 
-        // Step 2: Build prompt with context
+        // Step 2: Fit the context into the window, leaving room for the
+        // question, the prompt labels and the answer
+        let context_owned = match model.count_tokens(&effective_question) {
+            Ok(question_tokens) if !context_owned.is_empty() => {
+                let budget = (ctx_size as usize)
+                    .saturating_sub(max_tokens as usize + question_tokens + 16);
+                match model.truncate_to_tokens(&context_owned, budget) {
+                    Ok(fitted) => {
+                        if verbose && fitted.len() < context_owned.len() {
+                            eprintln!("  Context cut to {} tokens", budget);
+                        }
+                        fitted
+                    }
+                    Err(_) => context_owned,
+                }
+            }
+            _ => context_owned,
+        };
+
+        // Step 3: Build prompt with context
         let prompt = if context_owned.is_empty() {
             format!("Q: {}\nA:", effective_question)
         } else {
@@ -831,10 +1757,18 @@ async fn ask_local(
             .with_stop_sequence("\nQ:")
             .with_stop_sequence("\nQuestion:")
             .with_stop_sequence("Follow-up")
-            .with_stop_sequence("Solution:")
-            .with_stream(stream);
-
-        let answer = model.generate(&prompt, &options)?;
+            .with_stop_sequence("Solution:");
+
+        let answer = if stream {
+            let answer = model.generate_streaming(&prompt, &options, &mut |token| {
+                print!("{}", token);
+                std::io::stdout().flush().ok();
+            })?;
+            println!();
+            answer
+        } else {
+            model.generate(&prompt, &options)?
+        };
         
         Ok((answer.trim().to_string(), was_translated, translated_q))
     }).await??;
@@ -845,27 +1779,39 @@ async fn ask_local(
 }
 
 /// Ask using remote LLM server
+#[allow(clippy::too_many_arguments)]
 async fn ask_remote(
     question: &str,
     context: &str,
     llm_url: &str,
+    llm_provider: neuro_llm::ProviderKind,
+    llm_model: Option<&str>,
     max_tokens: u32,
     temperature: f32,
+    stream: bool,
 ) -> anyhow::Result<(String, std::time::Duration)> {
+    use futures::StreamExt;
     use neuro_llm::{LlmClient, LlmConfig};
     use std::time::Instant;
 
-    println!("{} Connecting to LLM at {}...", "🤖".cyan().bold(), llm_url);
+    println!(
+        "{} Connecting to LLM at {} ({})...",
+        "🤖".cyan().bold(),
+        llm_url,
+        llm_provider
+    );
     let llm_start = Instant::now();
 
     let config = LlmConfig {
         base_url: llm_url.to_string(),
-        model: "bitnet".to_string(),
+        provider: llm_provider,
         timeout_secs: 120,
         max_tokens,
         temperature,
+        api_key: std::env::var("NEURO_LLM_API_KEY").ok().filter(|key| !key.is_empty()),
+        ..Default::default()
     };
-    let client = LlmClient::with_config(config);
+    let mut client = LlmClient::try_with_config(config)?;
 
     // Check if server is available
     if !client.health_check().await.unwrap_or(false) {
@@ -880,6 +1826,17 @@ async fn ask_remote(
         return Err(anyhow::anyhow!("LLM server not available"));
     }
 
+    // Use the requested model, else the first one the server serves
+    match llm_model {
+        Some(model) => client.set_model(model),
+        None => match client.list_models().await {
+            Ok(models) if !models.is_empty() => client.set_model(&models[0].id),
+            Ok(_) => {}
+            Err(e) => tracing::debug!("Could not list models, using {}: {}", client.model(), e),
+        },
+    }
+    println!("{} Model: {}", "📦".cyan().bold(), client.model());
+
     println!("{} Generating response...", "✨".cyan().bold());
 
     let context_opt = if context.is_empty() {
@@ -888,9 +1845,24 @@ async fn ask_remote(
         Some(context)
     };
 
-    let answer = client
-        .ask_with_context(question, context_opt.unwrap_or(""), None)
-        .await?;
+    let answer = if stream {
+        let mut tokens = client
+            .ask_with_context_stream(question, context_opt.unwrap_or(""), None)
+            .await?;
+        let mut answer = String::new();
+        while let Some(token) = tokens.next().await {
+            let token = token?;
+            print!("{}", token);
+            std::io::stdout().flush().ok();
+            answer.push_str(&token);
+        }
+        println!();
+        answer.trim().to_string()
+    } else {
+        client
+            .ask_with_context(question, context_opt.unwrap_or(""), None)
+            .await?
+    };
 
     let llm_time = llm_start.elapsed();
 
@@ -920,7 +1892,10 @@ fn init_tracing(verbose: bool) {
 use crate::cli::ModelAction;
 
 pub async fn model(action: ModelAction, verbose: bool) -> anyhow::Result<()> {
-    use neuro_inference::{BitNetModel, ModelCache, DownloadOptions, download_model};
+    use neuro_inference::{
+        BitNetModel, CustomModel, HubFile, ModelCache, ModelRef, DownloadOptions, VerifyStatus,
+        download_ref, verify_ref,
+    };
 
     init_tracing(verbose);
 
@@ -962,6 +1937,40 @@ pub async fn model(action: ModelAction, verbose: bool) -> anyhow::Result<()> {
                 }
             }
 
+            let registry = cache.registry()?;
+            if registry.models().next().is_some() {
+                println!("\n{}", "═".repeat(70).blue());
+                println!("{}", "   Custom Models".bold());
+                println!("{}", "═".repeat(70).blue());
+
+                for custom in registry.models() {
+                    let model = ModelRef::Custom(custom.clone());
+                    let status = if cache.is_available(&model) {
+                        "✅ Available".green().to_string()
+                    } else {
+                        "⬜ Not downloaded".dimmed().to_string()
+                    };
+
+                    println!("\n{} - {}", custom.name.bold(), status);
+                    if let Some(url) = &custom.url {
+                        println!("   {} {}", "URL:".dimmed(), url);
+                    }
+                    println!("   {} {}", "Path:".dimmed(), cache.resolved_path(&model).display());
+                }
+            }
+
+            let hub_files = cache.list_hub();
+            if !hub_files.is_empty() {
+                println!("\n{}", "═".repeat(70).blue());
+                println!("{}", "   Hugging Face Hub".bold());
+                println!("{}", "═".repeat(70).blue());
+
+                for (file, path) in hub_files {
+                    println!("\n{} - {}", file.spec().bold(), "✅ Downloaded".green());
+                    println!("   {} {}", "Path:".dimmed(), path.display());
+                }
+            }
+
             println!("\n{}", "═".repeat(70).blue());
             
             // Cache summary
@@ -980,25 +1989,23 @@ pub async fn model(action: ModelAction, verbose: bool) -> anyhow::Result<()> {
             );
         }
 
-        ModelAction::Download { model: model_name, force } => {
-            let bitnet_model = BitNetModel::from_str(&model_name)
-                .ok_or_else(|| anyhow::anyhow!(
-                    "Unknown model '{}'. Available: 2b, large, 3b, 8b", model_name
-                ))?;
+        ModelAction::Download { model: model_name, force, hf_token } => {
+            let model = cache.resolve(&model_name)?;
 
             println!(
                 "\n{} Downloading {}...",
                 "📥".cyan().bold(),
-                bitnet_model.name()
+                model.name()
             );
 
             let opts = DownloadOptions {
                 yes: true, // No confirmation for explicit download
                 verify: true,
                 force,
+                hf_token: hf_token.or_else(HubFile::token_from_env),
             };
 
-            let path = download_model(&cache, bitnet_model, &opts).await?;
+            let path = download_ref(&cache, &model, &opts).await?;
             
             println!(
                 "\n{} Model downloaded to: {}",
@@ -1008,24 +2015,201 @@ pub async fn model(action: ModelAction, verbose: bool) -> anyhow::Result<()> {
         }
 
         ModelAction::Remove { model: model_name } => {
-            let bitnet_model = BitNetModel::from_str(&model_name)
-                .ok_or_else(|| anyhow::anyhow!(
-                    "Unknown model '{}'. Available: 2b, large, 3b, 8b", model_name
-                ))?;
+            match cache.resolve(&model_name)? {
+                ModelRef::BitNet(bitnet_model) => {
+                    if cache.delete_model(bitnet_model)? {
+                        println!(
+                            "{} Removed model: {}",
+                            "✓".green().bold(),
+                            bitnet_model.name()
+                        );
+                    } else {
+                        println!(
+                            "{} Model {} not found in cache",
+                            "⚠".yellow().bold(),
+                            bitnet_model.name()
+                        );
+                    }
+                }
+                ModelRef::Custom(custom) => {
+                    cache.delete_custom(&custom)?;
+                    let mut registry = cache.registry()?;
+                    registry.remove(&custom.name);
+                    registry.save()?;
+                    println!(
+                        "{} Unregistered model: {}",
+                        "✓".green().bold(),
+                        custom.name
+                    );
+                }
+                ModelRef::Hub(file) => {
+                    if cache.delete_hub(&file)? {
+                        println!("{} Removed model: {}", "✓".green().bold(), file.spec());
+                    } else {
+                        println!(
+                            "{} Model {} not found in cache",
+                            "⚠".yellow().bold(),
+                            file.spec()
+                        );
+                    }
+                }
+            }
+        }
+
+        ModelAction::Add { name, path, url, sha256 } => {
+            let mut custom = match (path, url) {
+                (Some(path), _) => {
+                    if !path.is_file() {
+                        anyhow::bail!("File not found: {}", path.display());
+                    }
+                    CustomModel::local(&name, std::fs::canonicalize(&path)?)
+                }
+                (None, Some(url)) => CustomModel::remote(&name, url),
+                (None, None) => anyhow::bail!("Pass --path or --url"),
+            };
+            if let Some(sha256) = sha256 {
+                custom = custom.with_sha256(sha256);
+            }
+
+            let mut registry = cache.registry()?;
+            let replaced = registry.add(custom)?.is_some();
+            registry.save()?;
+
+            println!(
+                "{} {} model '{}' in {}",
+                "✓".green().bold(),
+                if replaced { "Updated" } else { "Registered" },
+                name,
+                registry.path().display()
+            );
+        }
+
+        ModelAction::Verify { model: model_name, yes } => {
+            let models = match model_name {
+                Some(name) => vec![cache.resolve(&name)?],
+                None => {
+                    let mut models: Vec<ModelRef> = BitNetModel::all()
+                        .iter()
+                        .filter(|model| cache.model_path(**model).exists())
+                        .map(|model| ModelRef::BitNet(*model))
+                        .collect();
+                    models.extend(
+                        cache
+                            .registry()?
+                            .models()
+                            .map(|custom| ModelRef::Custom(custom.clone()))
+                            .filter(|model| cache.resolved_path(model).exists()),
+                    );
+                    models.extend(cache.list_hub().into_iter().map(|(file, _)| ModelRef::Hub(file)));
+                    models
+                }
+            };
+
+            if models.is_empty() {
+                println!("{} No cached models to verify", "⚠".yellow().bold());
+                return Ok(());
+            }
+
+            let mut broken = Vec::new();
+            for model in models {
+                println!("\n{} Checking {}...", "🔍".cyan().bold(), model.name());
+
+                let cache_dir = cache.cache_dir().to_path_buf();
+                let checked = model.clone();
+                let status = tokio::task::spawn_blocking(move || {
+                    verify_ref(&ModelCache::with_dir(cache_dir), &checked)
+                })
+                .await?;
+
+                match &status {
+                    VerifyStatus::Verified => {
+                        println!("   {} Checksum verified", "✓".green().bold())
+                    }
+                    VerifyStatus::Complete => println!(
+                        "   {} Complete (no checksum known)",
+                        "✓".green().bold()
+                    ),
+                    VerifyStatus::Missing => println!(
+                        "   {} Not found: {}",
+                        "⚠".yellow().bold(),
+                        cache.resolved_path(&model).display()
+                    ),
+                    VerifyStatus::Corrupt(reason) => {
+                        println!("   {} {}", "✗".red().bold(), reason)
+                    }
+                }
+
+                if matches!(status, VerifyStatus::Corrupt(_)) {
+                    broken.push(model);
+                }
+            }
 
-            if cache.delete_model(bitnet_model)? {
+            let mut unrepaired = 0;
+            for model in &broken {
+                if let ModelRef::Custom(CustomModel { url: None, .. }) = model {
+                    println!(
+                        "\n{} {} is a local file; replace it by hand",
+                        "⚠".yellow().bold(),
+                        model.name()
+                    );
+                    unrepaired += 1;
+                    continue;
+                }
+
+                if !yes {
+                    print!("\nRe-download {}? [y/N] ", model.name());
+                    std::io::stdout().flush().ok();
+                    let mut input = String::new();
+                    std::io::stdin().read_line(&mut input)?;
+                    if !matches!(input.trim().to_lowercase().as_str(), "y" | "yes") {
+                        unrepaired += 1;
+                        continue;
+                    }
+                }
+
+                let opts = DownloadOptions {
+                    yes: true,
+                    verify: true,
+                    force: true,
+                    ..Default::default()
+                };
+                let path = download_ref(&cache, model, &opts).await?;
                 println!(
-                    "{} Removed model: {}",
+                    "{} Model downloaded to: {}",
                     "✓".green().bold(),
-                    bitnet_model.name()
-                );
-            } else {
-                println!(
-                    "{} Model {} not found in cache",
-                    "⚠".yellow().bold(),
-                    bitnet_model.name()
+                    path.display()
                 );
             }
+
+            anyhow::ensure!(unrepaired == 0, "{} model(s) failed verification", unrepaired);
+        }
+
+        ModelAction::Remote { llm_url, llm_provider } => {
+            use neuro_llm::{LlmClient, LlmConfig};
+
+            let api_key = std::env::var("NEURO_LLM_API_KEY").ok().filter(|key| !key.is_empty());
+            let mut config = LlmConfig::new(&llm_url).with_provider(llm_provider);
+            if let Some(api_key) = api_key {
+                config = config.with_api_key(api_key);
+            }
+            let client = LlmClient::try_with_config(config)?;
+            let models = client.list_models().await?;
+
+            println!("\n{}", "═".repeat(50).blue());
+            println!("{}", format!("   Models at {}", llm_url).bold());
+            println!("{}", "═".repeat(50).blue());
+            if models.is_empty() {
+                println!("{}", "No models served".dimmed());
+            }
+            for model in &models {
+                match &model.owned_by {
+                    Some(owner) => {
+                        println!("  {} {}", model.id.bold(), format!("({})", owner).dimmed())
+                    }
+                    None => println!("  {}", model.id.bold()),
+                }
+            }
+            println!("{}", "═".repeat(50).blue());
         }
 
         ModelAction::Info => {
@@ -1062,7 +2246,161 @@ pub async fn model(action: ModelAction, verbose: bool) -> anyhow::Result<()> {
             
             println!("{}", "═".repeat(50).blue());
         }
+
+        ModelAction::Bench {
+            model: model_name,
+            threads,
+            prompt_tokens,
+            gen_tokens,
+            repetitions,
+            backend,
+        } => {
+            use neuro_inference::bench::{self, BenchConfig};
+
+            let model_path = if std::path::Path::new(&model_name).exists() {
+                PathBuf::from(&model_name)
+            } else {
+                let model = cache.resolve(&model_name)?;
+                if !cache.is_available(&model) {
+                    anyhow::bail!(
+                        "Model {} is not downloaded. Run: neuro model download {}",
+                        model.name(),
+                        model_name
+                    );
+                }
+                cache.resolved_path(&model)
+            };
+
+            let mut config = BenchConfig::new(model_path.to_string_lossy())
+                .with_backend(backend.parse().map_err(anyhow::Error::msg)?)
+                .with_prompt_tokens(prompt_tokens)
+                .with_gen_tokens(gen_tokens)
+                .with_repetitions(repetitions);
+            if !threads.is_empty() {
+                config = config.with_thread_counts(threads);
+            }
+
+            println!(
+                "\n{} Benchmarking {} ({} run(s) per thread count)...",
+                "⏱".cyan().bold(),
+                model_path.display(),
+                config.repetitions
+            );
+
+            let results = tokio::task::spawn_blocking(move || bench::run(&config)).await??;
+
+            println!("\n{}", bench::format_table(&results));
+        }
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Storage command
+// ============================================================================
+
+pub async fn storage(action: StorageAction, verbose: bool) -> anyhow::Result<()> {
+    init_tracing(verbose);
+
+    match action {
+        StorageAction::Migrate {
+            from,
+            to,
+            batch_size,
+        } => {
+            let source = open_storage(&from).await?;
+            let mut target = open_storage(&to).await?;
+
+            let total = source.count().await;
+            println!(
+                "{} Migrating {} documents: {} → {}",
+                "▶".green().bold(),
+                total,
+                from.cyan(),
+                to.cyan()
+            );
+
+            let pb = ProgressBar::new(total as u64);
+            pb.set_style(
+                ProgressStyle::default_bar()
+                    .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta})")?
+                    .progress_chars("#>-"),
+            );
+
+            let report = neuro_storage::migrate(source.as_ref(), target.as_mut(), batch_size, |n| {
+                pb.set_position(n as u64)
+            })
+            .await?;
+
+            pb.finish_with_message("Done");
+
+            println!(
+                "\n{} Migrated {} documents ({} skipped, already in target)",
+                "✓".green().bold(),
+                report.migrated,
+                report.skipped
+            );
+
+            let stats = target.stats().await;
+            println!(
+                "{} Target: {} documents, {}KB",
+                "📊".cyan().bold(),
+                stats.document_count,
+                stats.total_content_bytes / 1024
+            );
+        }
+        StorageAction::Reembed { storage, model } => {
+            let embedding_model: EmbeddingModel =
+                model.parse().map_err(|e: String| anyhow::anyhow!(e))?;
+
+            let mut target = open_storage(&storage).await?;
+            let previous = target.stats().await.embedding_model;
+
+            println!("{} Initializing embedder...", "⚙".cyan().bold());
+            let embedder = FastEmbedder::new(embedding_model)?;
+
+            println!(
+                "{} Re-embedding {} documents in {}: {} → {}",
+                "▶".green().bold(),
+                target.count().await,
+                storage.cyan(),
+                previous.as_deref().unwrap_or("unknown model"),
+                embedding_model.to_string().cyan()
+            );
+
+            let count = target.reembed(&embedder).await?;
+
+            println!(
+                "\n{} Re-embedded {} documents ({}D)",
+                "✓".green().bold(),
+                count,
+                embedder.dimension()
+            );
+        }
     }
 
     Ok(())
 }
+
+/// Open a storage backend from a URI-like spec.
+///
+/// Accepts `memory://`, `file://<path>`, `disk://<dir>`, `lance://<dir>`
+/// (with the `lance` feature), `sqlite://<path>` (with the `sqlite`
+/// feature) or a plain file path.
+async fn open_storage(spec: &str) -> anyhow::Result<Box<dyn Storage>> {
+    match spec.split_once("://") {
+        Some(("memory", _)) => Ok(Box::new(MemoryStorage::new())),
+        Some(("file", path)) => Ok(Box::new(FileStorage::new(path).await?)),
+        Some(("disk", dir)) => Ok(Box::new(DiskStorage::open(dir).await?)),
+        #[cfg(feature = "lance")]
+        Some(("lance", uri)) => Ok(Box::new(neuro_storage::LanceStorage::open(uri).await?)),
+        #[cfg(feature = "sqlite")]
+        Some(("sqlite", path)) => Ok(Box::new(neuro_storage::SqliteStorage::open(path).await?)),
+        Some((scheme, _)) => anyhow::bail!(
+            "Unsupported storage backend '{}'. Available: file, memory, disk, lance (requires the `lance` feature), sqlite (requires the `sqlite` feature)",
+            scheme
+        ),
+        None => Ok(Box::new(FileStorage::new(spec).await?)),
+    }
+}
@@ -12,8 +12,27 @@ async fn main() -> anyhow::Result<()> {
             port,
             storage,
             model,
+            model_path,
+            llm_url,
+            llm_provider,
+            llm_model,
+            rate_limit,
+            cors_origin,
         } => {
-            neuro_cli::commands::serve(host, port, storage, model, cli.verbose).await?;
+            neuro_cli::commands::serve(
+                host,
+                port,
+                storage,
+                model,
+                model_path,
+                llm_url,
+                llm_provider,
+                llm_model,
+                rate_limit,
+                cors_origin,
+                cli.verbose,
+            )
+            .await?;
         }
         Commands::Index {
             paths,
@@ -23,7 +42,9 @@ async fn main() -> anyhow::Result<()> {
             max_size,
             storage,
             model,
+            batch_size,
             progress,
+            code,
         } => {
             neuro_cli::commands::index(
                 paths,
@@ -33,11 +54,43 @@ async fn main() -> anyhow::Result<()> {
                 max_size,
                 storage,
                 model,
+                batch_size,
                 progress,
+                code,
                 cli.verbose,
             )
             .await?;
         }
+        Commands::Watch {
+            path,
+            storage,
+            include,
+            exclude,
+            max_size,
+            model,
+            debounce_ms,
+        } => {
+            neuro_cli::commands::watch(
+                path,
+                storage,
+                include,
+                exclude,
+                max_size,
+                model,
+                debounce_ms,
+                cli.verbose,
+            )
+            .await?;
+        }
+        Commands::Symbols {
+            query,
+            paths,
+            fuzzy,
+            limit,
+            format,
+        } => {
+            neuro_cli::commands::symbols(query, paths, fuzzy, limit, format, cli.verbose)?;
+        }
         Commands::Query {
             query,
             top_k,
@@ -45,9 +98,19 @@ async fn main() -> anyhow::Result<()> {
             model,
             format,
             web,
+            source,
         } => {
-            neuro_cli::commands::query(query, top_k, storage, model, format, web, cli.verbose)
-                .await?;
+            neuro_cli::commands::query(
+                query,
+                top_k,
+                storage,
+                model,
+                format,
+                web,
+                source,
+                cli.verbose,
+            )
+            .await?;
         }
         Commands::Stats { storage } => {
             neuro_cli::commands::stats(storage, cli.verbose).await?;
@@ -59,21 +122,58 @@ async fn main() -> anyhow::Result<()> {
         } => {
             neuro_cli::commands::embed(text, model, format, cli.verbose)?;
         }
-        Commands::Classify { query, format } => {
-            neuro_cli::commands::classify(query, format, cli.verbose)?;
+        Commands::Classify {
+            query,
+            format,
+            patterns,
+            languages,
+        } => {
+            neuro_cli::commands::classify(query, format, patterns, languages, cli.verbose)?;
         }
         Commands::Search {
             query,
             count,
             format,
+            source,
+            language,
+        } => {
+            neuro_cli::commands::search(query, count, format, source, language, cli.verbose)
+                .await?;
+        }
+        Commands::IngestUrl {
+            urls,
+            storage,
+            model,
+            chunk_size,
         } => {
-            neuro_cli::commands::search(query, count, format, cli.verbose).await?;
+            neuro_cli::commands::ingest_url(urls, storage, model, chunk_size, cli.verbose).await?;
+        }
+        Commands::IngestFeed {
+            feeds,
+            storage,
+            remove,
+            model,
+            chunk_size,
+            max_entries,
+        } => {
+            neuro_cli::commands::ingest_feed(
+                feeds,
+                storage,
+                remove,
+                model,
+                chunk_size,
+                max_entries,
+                cli.verbose,
+            )
+            .await?;
         }
         Commands::Ask {
             question,
             model_path,
             model,
             llm_url,
+            llm_provider,
+            llm_model,
             max_tokens,
             temperature,
             ctx_size,
@@ -92,6 +192,8 @@ async fn main() -> anyhow::Result<()> {
                 model_path,
                 model,
                 llm_url,
+                llm_provider,
+                llm_model,
                 max_tokens,
                 temperature,
                 ctx_size,
@@ -108,9 +210,35 @@ async fn main() -> anyhow::Result<()> {
             )
             .await?;
         }
+        Commands::Chat {
+            model_path,
+            model,
+            system,
+            max_tokens,
+            temperature,
+            ctx_size,
+            threads,
+            yes,
+        } => {
+            neuro_cli::commands::chat(
+                model_path,
+                model,
+                system,
+                max_tokens,
+                temperature,
+                ctx_size,
+                threads,
+                yes,
+                cli.verbose,
+            )
+            .await?;
+        }
         Commands::Model { action } => {
             neuro_cli::commands::model(action, cli.verbose).await?;
         }
+        Commands::Storage { action } => {
+            neuro_cli::commands::storage(action, cli.verbose).await?;
+        }
     }
 
     Ok(())
@@ -1,6 +1,15 @@
 //! Query classification types
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Metadata key holding the programming language detected for code queries
+pub const PROGRAMMING_LANGUAGE_KEY: &str = "programming_language";
+
+/// Reply returned instead of a model answer for sensitive queries
+pub const SAFE_REPLY: &str = "I can't help with that request. If you are going through a \
+difficult time, please consider reaching out to someone you trust or to a local crisis line.";
 
 /// Categories for classifying user queries
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
@@ -18,10 +27,35 @@ pub enum QueryCategory {
     Greeting,
     /// Factual knowledge queries
     Factual,
+    /// Summarizing provided text
+    Summarization,
+    /// Translating between languages
+    Translation,
+    /// Poems, stories and other creative writing
+    CreativeWriting,
+    /// Self-harm, illegal instructions and other unsafe requests
+    Sensitive,
     /// General conversation
     Conversational,
 }
 
+impl QueryCategory {
+    /// All categories
+    pub const ALL: [QueryCategory; 11] = [
+        Self::Math,
+        Self::Code,
+        Self::Reasoning,
+        Self::Tools,
+        Self::Greeting,
+        Self::Factual,
+        Self::Summarization,
+        Self::Translation,
+        Self::CreativeWriting,
+        Self::Sensitive,
+        Self::Conversational,
+    ];
+}
+
 impl Default for QueryCategory {
     fn default() -> Self {
         Self::Conversational
@@ -37,6 +71,10 @@ impl std::fmt::Display for QueryCategory {
             Self::Tools => write!(f, "tools"),
             Self::Greeting => write!(f, "greeting"),
             Self::Factual => write!(f, "factual"),
+            Self::Summarization => write!(f, "summarization"),
+            Self::Translation => write!(f, "translation"),
+            Self::CreativeWriting => write!(f, "creative_writing"),
+            Self::Sensitive => write!(f, "sensitive"),
             Self::Conversational => write!(f, "conversational"),
         }
     }
@@ -54,6 +92,8 @@ pub enum QueryStrategy {
     RagThenWeb,
     /// Use web search directly
     WebSearch,
+    /// Answer with a fixed safe reply without calling the model
+    SafeReply,
 }
 
 impl Default for QueryStrategy {
@@ -69,6 +109,7 @@ impl std::fmt::Display for QueryStrategy {
             Self::RagLocal => write!(f, "rag_local"),
             Self::RagThenWeb => write!(f, "rag_then_web"),
             Self::WebSearch => write!(f, "web_search"),
+            Self::SafeReply => write!(f, "safe_reply"),
         }
     }
 }
@@ -92,6 +133,18 @@ pub struct ClassificationResult {
     /// Original query text
     #[serde(default)]
     pub query: String,
+
+    /// Patterns that matched the query, in match order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub matches: Vec<PatternMatch>,
+
+    /// Probability of each category (sums to 1.0 when present)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub probabilities: HashMap<QueryCategory, f32>,
+
+    /// Additional classifier output (e.g. the detected programming language)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, serde_json::Value>,
 }
 
 impl ClassificationResult {
@@ -103,6 +156,9 @@ impl ClassificationResult {
             confidence,
             reasons: Vec::new(),
             query: String::new(),
+            matches: Vec::new(),
+            probabilities: HashMap::new(),
+            metadata: HashMap::new(),
         }
     }
 
@@ -124,6 +180,56 @@ impl ClassificationResult {
         self
     }
 
+    /// Set the matched patterns
+    pub fn with_matches(mut self, matches: Vec<PatternMatch>) -> Self {
+        self.matches = matches;
+        self
+    }
+
+    /// Matched patterns that contributed to a category
+    pub fn matches_for(&self, category: QueryCategory) -> impl Iterator<Item = &PatternMatch> {
+        self.matches.iter().filter(move |m| m.category == category)
+    }
+
+    /// Set the per-category probabilities
+    pub fn with_probabilities(
+        mut self,
+        probabilities: impl IntoIterator<Item = (QueryCategory, f32)>,
+    ) -> Self {
+        self.probabilities = probabilities.into_iter().collect();
+        self
+    }
+
+    /// Probability of a category (0.0 if unknown)
+    pub fn probability(&self, category: QueryCategory) -> f32 {
+        self.probabilities.get(&category).copied().unwrap_or(0.0)
+    }
+
+    /// Categories ordered by probability, most likely first
+    pub fn ranked_categories(&self) -> Vec<(QueryCategory, f32)> {
+        let mut ranked: Vec<_> = self.probabilities.iter().map(|(c, p)| (*c, *p)).collect();
+        ranked.sort_by(|a, b| b.1.total_cmp(&a.1));
+        ranked
+    }
+
+    /// Add metadata
+    pub fn with_metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Programming language detected for a code query, if any
+    pub fn programming_language(&self) -> Option<&str> {
+        self.metadata
+            .get(PROGRAMMING_LANGUAGE_KEY)
+            .and_then(|v| v.as_str())
+    }
+
+    /// The fixed reply to return instead of calling the model, if any
+    pub fn safe_reply(&self) -> Option<&'static str> {
+        (self.strategy == QueryStrategy::SafeReply).then_some(SAFE_REPLY)
+    }
+
     /// Check if classification is high confidence (>= 0.7)
     pub fn is_high_confidence(&self) -> bool {
         self.confidence >= 0.7
@@ -135,6 +241,63 @@ impl ClassificationResult {
     }
 }
 
+/// A classification pattern that matched a query
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PatternMatch {
+    /// Category the pattern scores for
+    pub category: QueryCategory,
+
+    /// The pattern's regex source
+    pub pattern: String,
+
+    /// Weight added to the category score
+    pub weight: f32,
+
+    /// Byte offset where the first match starts
+    pub start: usize,
+
+    /// Byte offset where the first match ends
+    pub end: usize,
+
+    /// Matched text
+    pub text: String,
+}
+
+/// A query classifier
+///
+/// The regex classifier in `neuro-classifier` is the default
+/// implementation. Embedding- or LLM-based classifiers can implement this
+/// trait as well and be injected into the server and daemon.
+pub trait QueryClassifier: Send + Sync {
+    /// Classify a query into a category with recommended strategy
+    fn classify(&self, query: &str) -> ClassificationResult;
+
+    /// Short name of the implementation, for logging
+    fn name(&self) -> &str {
+        "custom"
+    }
+}
+
+impl<T: QueryClassifier + ?Sized> QueryClassifier for Arc<T> {
+    fn classify(&self, query: &str) -> ClassificationResult {
+        (**self).classify(query)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
+impl<T: QueryClassifier + ?Sized> QueryClassifier for Box<T> {
+    fn classify(&self, query: &str) -> ClassificationResult {
+        (**self).classify(query)
+    }
+
+    fn name(&self) -> &str {
+        (**self).name()
+    }
+}
+
 impl Default for ClassificationResult {
     fn default() -> Self {
         Self::new(
@@ -154,12 +317,15 @@ mod tests {
         assert_eq!(QueryCategory::Math.to_string(), "math");
         assert_eq!(QueryCategory::Code.to_string(), "code");
         assert_eq!(QueryCategory::Greeting.to_string(), "greeting");
+        assert_eq!(QueryCategory::CreativeWriting.to_string(), "creative_writing");
+        assert_eq!(QueryCategory::Sensitive.to_string(), "sensitive");
     }
 
     #[test]
     fn test_query_strategy_display() {
         assert_eq!(QueryStrategy::LlmDirect.to_string(), "llm_direct");
         assert_eq!(QueryStrategy::RagLocal.to_string(), "rag_local");
+        assert_eq!(QueryStrategy::SafeReply.to_string(), "safe_reply");
     }
 
     #[test]
@@ -193,5 +359,88 @@ mod tests {
 
         assert_eq!(parsed.category, QueryCategory::Factual);
         assert_eq!(parsed.strategy, QueryStrategy::RagThenWeb);
+        assert!(!json.contains("probabilities"));
+    }
+
+    #[test]
+    fn test_matches_for() {
+        let math = PatternMatch {
+            category: QueryCategory::Math,
+            pattern: r"\d+\s*\+\s*\d+".to_string(),
+            weight: 1.5,
+            start: 8,
+            end: 13,
+            text: "2 + 2".to_string(),
+        };
+        let factual = PatternMatch {
+            category: QueryCategory::Factual,
+            pattern: "^what is".to_string(),
+            weight: 1.0,
+            ..math.clone()
+        };
+        let result = ClassificationResult::new(QueryCategory::Math, QueryStrategy::LlmDirect, 0.9)
+            .with_matches(vec![math.clone(), factual]);
+
+        let matched: Vec<_> = result.matches_for(QueryCategory::Math).collect();
+        assert_eq!(matched, vec![&math]);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: ClassificationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.matches.len(), 2);
+    }
+
+    #[test]
+    fn test_probabilities() {
+        let result = ClassificationResult::new(QueryCategory::Math, QueryStrategy::LlmDirect, 0.8)
+            .with_probabilities([(QueryCategory::Math, 0.8), (QueryCategory::Code, 0.2)]);
+
+        assert_eq!(result.probability(QueryCategory::Math), 0.8);
+        assert_eq!(result.probability(QueryCategory::Greeting), 0.0);
+        assert_eq!(result.ranked_categories()[0].0, QueryCategory::Math);
+
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: ClassificationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.probability(QueryCategory::Code), 0.2);
+    }
+
+    #[test]
+    fn test_programming_language_metadata() {
+        let result = ClassificationResult::new(QueryCategory::Code, QueryStrategy::RagLocal, 0.8)
+            .with_metadata(PROGRAMMING_LANGUAGE_KEY, serde_json::json!("rust"));
+        assert_eq!(result.programming_language(), Some("rust"));
+
+        let json = serde_json::to_string(&result).unwrap();
+        let parsed: ClassificationResult = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed.programming_language(), Some("rust"));
+
+        let plain = ClassificationResult::default();
+        assert_eq!(plain.programming_language(), None);
+        assert!(!serde_json::to_string(&plain).unwrap().contains("metadata"));
+    }
+
+    #[test]
+    fn test_safe_reply() {
+        let result =
+            ClassificationResult::new(QueryCategory::Sensitive, QueryStrategy::SafeReply, 0.9);
+        assert_eq!(result.safe_reply(), Some(SAFE_REPLY));
+        assert_eq!(ClassificationResult::default().safe_reply(), None);
+    }
+
+    #[test]
+    fn test_query_classifier_trait_object() {
+        struct AlwaysMath;
+
+        impl QueryClassifier for AlwaysMath {
+            fn classify(&self, query: &str) -> ClassificationResult {
+                ClassificationResult::new(QueryCategory::Math, QueryStrategy::LlmDirect, 1.0)
+                    .with_query(query)
+            }
+        }
+
+        let classifier: Arc<dyn QueryClassifier> = Arc::new(AlwaysMath);
+        let result = classifier.classify("anything");
+        assert_eq!(result.category, QueryCategory::Math);
+        assert_eq!(result.query, "anything");
+        assert_eq!(classifier.name(), "custom");
     }
 }
@@ -5,6 +5,8 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use uuid::Uuid;
 
+use crate::sparse::SparseEmbedding;
+
 /// Source of a document in the RAG system
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -39,6 +41,21 @@ impl std::fmt::Display for DocumentSource {
     }
 }
 
+impl std::str::FromStr for DocumentSource {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "manual" => Ok(Self::Manual),
+            "file" => Ok(Self::File),
+            "web" => Ok(Self::Web),
+            "conversation" => Ok(Self::Conversation),
+            "code" => Ok(Self::Code),
+            _ => Err(format!("Unknown document source: {}", s)),
+        }
+    }
+}
+
 /// A document stored in the RAG system
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Document {
@@ -60,12 +77,24 @@ pub struct Document {
     #[serde(default)]
     pub metadata: HashMap<String, serde_json::Value>,
 
+    /// Tags for grouping and filtering documents
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
     /// Creation timestamp
     pub created_at: DateTime<Utc>,
 
     /// Vector embedding (optional, may be computed lazily)
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub embedding: Option<Vec<f32>>,
+
+    /// Name of the model that produced the embedding
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub embedding_model: Option<String>,
+
+    /// Sparse term weights for keyword-aware hybrid retrieval
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sparse_embedding: Option<SparseEmbedding>,
 }
 
 impl Document {
@@ -77,8 +106,11 @@ impl Document {
             user_id: None,
             source: DocumentSource::default(),
             metadata: HashMap::new(),
+            tags: Vec::new(),
             created_at: Utc::now(),
             embedding: None,
+            embedding_model: None,
+            sparse_embedding: None,
         }
     }
 
@@ -90,8 +122,11 @@ impl Document {
             user_id: None,
             source: DocumentSource::default(),
             metadata: HashMap::new(),
+            tags: Vec::new(),
             created_at: Utc::now(),
             embedding: None,
+            embedding_model: None,
+            sparse_embedding: None,
         }
     }
 
@@ -113,12 +148,48 @@ impl Document {
         self
     }
 
+    /// Set the tags, replacing any existing ones
+    ///
+    /// Duplicate tags are removed while preserving order.
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags.clear();
+        for tag in tags {
+            self = self.with_tag(tag);
+        }
+        self
+    }
+
+    /// Add a single tag
+    pub fn with_tag(mut self, tag: impl Into<String>) -> Self {
+        let tag = tag.into();
+        if !self.tags.contains(&tag) {
+            self.tags.push(tag);
+        }
+        self
+    }
+
     /// Set the embedding vector
     pub fn with_embedding(mut self, embedding: Vec<f32>) -> Self {
         self.embedding = Some(embedding);
         self
     }
 
+    /// Record the name of the model that produced the embedding
+    pub fn with_embedding_model(mut self, model: impl Into<String>) -> Self {
+        self.embedding_model = Some(model.into());
+        self
+    }
+
+    /// Set the sparse embedding
+    pub fn with_sparse_embedding(mut self, sparse: SparseEmbedding) -> Self {
+        self.sparse_embedding = Some(sparse);
+        self
+    }
+
     /// Get content length in characters
     pub fn content_len(&self) -> usize {
         self.content.len()
@@ -129,6 +200,21 @@ impl Document {
         self.embedding.is_some()
     }
 
+    /// Check if document has a sparse embedding
+    pub fn has_sparse_embedding(&self) -> bool {
+        self.sparse_embedding.is_some()
+    }
+
+    /// Check if document has the given tag
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t == tag)
+    }
+
+    /// Check if document has all of the given tags
+    pub fn has_all_tags<S: AsRef<str>>(&self, tags: &[S]) -> bool {
+        tags.iter().all(|tag| self.has_tag(tag.as_ref()))
+    }
+
     /// Get embedding dimension (if available)
     pub fn embedding_dim(&self) -> Option<usize> {
         self.embedding.as_ref().map(|e| e.len())
@@ -168,6 +254,27 @@ mod tests {
         assert_eq!(doc.embedding_dim(), Some(3));
     }
 
+    #[test]
+    fn test_document_tags() {
+        let doc = Document::new("Tagged")
+            .with_tags(["rust", "async", "rust"])
+            .with_tag("tokio");
+
+        assert_eq!(doc.tags, vec!["rust", "async", "tokio"]);
+        assert!(doc.has_tag("async"));
+        assert!(!doc.has_tag("python"));
+        assert!(doc.has_all_tags(&["rust", "tokio"]));
+        assert!(!doc.has_all_tags(&["rust", "python"]));
+        assert!(doc.has_all_tags::<&str>(&[]));
+    }
+
+    #[test]
+    fn test_document_tags_default_on_deserialize() {
+        let json = r#"{"id":"1","content":"Old","created_at":"2024-01-01T00:00:00Z"}"#;
+        let doc: Document = serde_json::from_str(json).unwrap();
+        assert!(doc.tags.is_empty());
+    }
+
     #[test]
     fn test_document_source_display() {
         assert_eq!(DocumentSource::Manual.to_string(), "manual");
@@ -176,6 +283,13 @@ mod tests {
         assert_eq!(DocumentSource::Code.to_string(), "code");
     }
 
+    #[test]
+    fn test_document_source_from_str() {
+        assert_eq!("code".parse::<DocumentSource>(), Ok(DocumentSource::Code));
+        assert_eq!("Web".parse::<DocumentSource>(), Ok(DocumentSource::Web));
+        assert!("email".parse::<DocumentSource>().is_err());
+    }
+
     #[test]
     fn test_document_serialization() {
         let doc = Document::new("Test")
@@ -4,28 +4,35 @@
 //!
 //! This crate provides the foundational data structures used across all other crates:
 //! - [`Document`] - Represents a stored document with embeddings
+//! - [`SparseEmbedding`] - Sparse term weights for hybrid retrieval
 //! - [`SearchResult`] - Result from similarity search
 //! - [`QueryResult`] - Complete result from RAG query
 //! - [`ClassificationResult`] - Query classification output
 //! - [`QueryCategory`] - Categories for query classification
 //! - [`QueryStrategy`] - Strategies for handling queries
+//! - [`QueryClassifier`] - Trait implemented by query classifiers
 //! - [`DocumentSource`] - Source types for documents
 
 mod document;
 mod error;
 mod classification;
 mod search;
+mod sparse;
 
 pub use document::{Document, DocumentSource};
 pub use error::{Error, Result};
-pub use classification::{ClassificationResult, QueryCategory, QueryStrategy};
+pub use classification::{
+    ClassificationResult, PatternMatch, QueryCategory, QueryClassifier, QueryStrategy,
+    PROGRAMMING_LANGUAGE_KEY, SAFE_REPLY,
+};
 pub use search::{SearchResult, QueryResult};
+pub use sparse::SparseEmbedding;
 
 /// Re-export commonly used types
 pub mod prelude {
     pub use crate::{
-        Document, DocumentSource,
-        ClassificationResult, QueryCategory, QueryStrategy,
+        Document, DocumentSource, SparseEmbedding,
+        ClassificationResult, QueryCategory, QueryClassifier, QueryStrategy,
         SearchResult, QueryResult,
         Error, Result,
     };
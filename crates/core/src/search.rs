@@ -16,6 +16,10 @@ pub struct SearchResult {
     /// Rank in results (0-indexed)
     #[serde(default)]
     pub rank: usize,
+
+    /// Cross-encoder relevance score, when the results were reranked
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rerank_score: Option<f32>,
 }
 
 impl SearchResult {
@@ -25,6 +29,7 @@ impl SearchResult {
             document,
             score,
             rank: 0,
+            rerank_score: None,
         }
     }
 
@@ -34,6 +39,12 @@ impl SearchResult {
         self
     }
 
+    /// Set the cross-encoder relevance score
+    pub fn with_rerank_score(mut self, score: f32) -> Self {
+        self.rerank_score = Some(score);
+        self
+    }
+
     /// Check if this is a high-quality match (score >= 0.7)
     pub fn is_relevant(&self) -> bool {
         self.score >= 0.7
@@ -66,6 +77,10 @@ pub struct QueryResult {
     #[serde(default)]
     pub used_web_search: bool,
 
+    /// Fixed reply for queries that must not reach the model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub safe_reply: Option<String>,
+
     /// Total processing time in milliseconds
     #[serde(default)]
     pub processing_time_ms: u64,
@@ -80,6 +95,7 @@ impl QueryResult {
             search_results: Vec::new(),
             context: String::new(),
             used_web_search: false,
+            safe_reply: None,
             processing_time_ms: 0,
         }
     }
@@ -102,6 +118,12 @@ impl QueryResult {
         self
     }
 
+    /// Set the fixed reply returned instead of a model answer
+    pub fn with_safe_reply(mut self, reply: impl Into<String>) -> Self {
+        self.safe_reply = Some(reply.into());
+        self
+    }
+
     /// Set processing time
     pub fn with_processing_time(mut self, ms: u64) -> Self {
         self.processing_time_ms = ms;
@@ -125,6 +147,14 @@ impl QueryResult {
 
     /// Build context string from search results
     pub fn build_context(&mut self, max_length: usize) {
+        self.build_context_with(max_length, str::len);
+    }
+
+    /// Build context string from search results, measuring each document
+    /// with `measure`
+    ///
+    /// Pass a tokenizer's count to budget by tokens instead of bytes.
+    pub fn build_context_with(&mut self, max_length: usize, measure: impl Fn(&str) -> usize) {
         let mut context = String::new();
         let mut current_length = 0;
 
@@ -134,7 +164,8 @@ impl QueryResult {
             }
 
             let content = &result.document.content;
-            if current_length + content.len() > max_length {
+            let length = measure(content);
+            if current_length + length > max_length {
                 break;
             }
 
@@ -142,7 +173,7 @@ impl QueryResult {
                 context.push_str("\n\n---\n\n");
             }
             context.push_str(content);
-            current_length += content.len();
+            current_length += length;
         }
 
         self.context = context;
@@ -169,6 +200,18 @@ mod tests {
         assert!(result.is_relevant());
         assert!(!result.is_weak_match());
         assert_eq!(result.rank, 0);
+        assert!(result.rerank_score.is_none());
+    }
+
+    #[test]
+    fn test_rerank_score_serialization() {
+        let result = SearchResult::new(Document::new("Test content"), 0.5);
+        let json = serde_json::to_value(&result).unwrap();
+        assert!(json.get("rerank_score").is_none());
+
+        let json = serde_json::to_value(result.with_rerank_score(4.5)).unwrap();
+        assert_eq!(json["rerank_score"], 4.5);
+        assert_eq!(json["score"], 0.5);
     }
 
     #[test]
@@ -205,4 +248,17 @@ mod tests {
         assert!(result.context.contains("Second document"));
         assert!(!result.context.contains("Weak match")); // Low score excluded
     }
+
+    #[test]
+    fn test_build_context_with_measure() {
+        let mut result = QueryResult::new("test", ClassificationResult::default());
+        result.search_results = vec![
+            SearchResult::new(Document::new("one two three"), 0.9),
+            SearchResult::new(Document::new("four five"), 0.8),
+        ];
+
+        // Budget by word count instead of bytes
+        result.build_context_with(4, |text| text.split_whitespace().count());
+        assert_eq!(result.context, "one two three");
+    }
 }
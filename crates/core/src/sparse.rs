@@ -0,0 +1,104 @@
+//! Sparse term-weight vectors
+
+use serde::{Deserialize, Serialize};
+
+/// Sparse embedding: weights for a small set of vocabulary terms
+///
+/// Produced by SPLADE-style models or BM25-style term weighting. Indices
+/// are kept sorted and unique so two vectors can be compared with a
+/// linear merge.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SparseEmbedding {
+    /// Term indices, sorted ascending
+    pub indices: Vec<u32>,
+    /// Weight of each term, parallel to `indices`
+    pub values: Vec<f32>,
+}
+
+impl SparseEmbedding {
+    /// Build a sparse embedding from `(index, weight)` pairs
+    ///
+    /// Pairs may come in any order; weights of repeated indices are summed
+    /// and zero weights are dropped.
+    pub fn from_pairs(pairs: impl IntoIterator<Item = (u32, f32)>) -> Self {
+        let mut pairs: Vec<(u32, f32)> = pairs.into_iter().collect();
+        pairs.sort_by_key(|&(index, _)| index);
+
+        let mut embedding = Self::default();
+        for (index, value) in pairs {
+            match embedding.indices.last() {
+                Some(&last) if last == index => {
+                    if let Some(v) = embedding.values.last_mut() {
+                        *v += value;
+                    }
+                }
+                _ => {
+                    embedding.indices.push(index);
+                    embedding.values.push(value);
+                }
+            }
+        }
+
+        let (indices, values) = embedding
+            .indices
+            .into_iter()
+            .zip(embedding.values)
+            .filter(|&(_, v)| v != 0.0)
+            .unzip();
+        Self { indices, values }
+    }
+
+    /// Number of non-zero terms
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Check if there are no terms
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Iterate over `(index, weight)` pairs in index order
+    pub fn iter(&self) -> impl Iterator<Item = (u32, f32)> + '_ {
+        self.indices.iter().copied().zip(self.values.iter().copied())
+    }
+
+    /// Dot product with another sparse embedding
+    pub fn dot(&self, other: &SparseEmbedding) -> f32 {
+        let (mut i, mut j) = (0, 0);
+        let mut sum = 0.0;
+        while i < self.indices.len() && j < other.indices.len() {
+            match self.indices[i].cmp(&other.indices[j]) {
+                std::cmp::Ordering::Less => i += 1,
+                std::cmp::Ordering::Greater => j += 1,
+                std::cmp::Ordering::Equal => {
+                    sum += self.values[i] * other.values[j];
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        sum
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_pairs_sorts_and_merges() {
+        let sparse = SparseEmbedding::from_pairs([(7, 1.0), (2, 0.5), (7, 0.5), (4, 0.0)]);
+        assert_eq!(sparse.indices, vec![2, 7]);
+        assert_eq!(sparse.values, vec![0.5, 1.5]);
+        assert_eq!(sparse.len(), 2);
+    }
+
+    #[test]
+    fn test_dot() {
+        let a = SparseEmbedding::from_pairs([(1, 2.0), (3, 1.0), (9, 4.0)]);
+        let b = SparseEmbedding::from_pairs([(3, 3.0), (5, 1.0), (9, 0.5)]);
+        assert_eq!(a.dot(&b), 5.0);
+        assert_eq!(a.dot(&SparseEmbedding::default()), 0.0);
+    }
+}
@@ -8,8 +8,16 @@ use axum::{
 };
 use serde::{Deserialize, Serialize};
 use std::sync::Arc;
-use neuro_inference::{GenerateOptions, SamplerConfig};
-use neuro_inference::translation::{detect_language, build_translation_prompt, Language};
+use neuro_core::QueryClassifier;
+use neuro_inference::{
+    CancellationToken, ChatSession, GenerateOptions, InferenceError, InferenceModel,
+    ModelTranslator, SamplerConfig, Translator,
+};
+use neuro_inference::translation::{
+    build_multilingual_prompt, detect_language, detect_language_with_confidence, ensure_language,
+    Language,
+};
+use tracing::debug;
 
 use crate::AppState;
 
@@ -24,6 +32,8 @@ pub struct GenerateRequest {
     pub temperature: Option<f32>,
     /// Whether to translate non-English queries (optional, uses server default)
     pub translate: Option<bool>,
+    /// Whether to answer in the prompt's language (optional, uses server default)
+    pub match_language: Option<bool>,
 }
 
 /// Response from text generation
@@ -37,8 +47,12 @@ pub struct GenerateResponse {
     pub was_translated: bool,
     /// The translated prompt (if applicable)
     pub translated_prompt: Option<String>,
+    /// Whether the answer was translated into the prompt's language
+    pub answer_translated: bool,
     /// Detected language
     pub detected_language: String,
+    /// Confidence of the language detection, from 0.0 to 1.0
+    pub language_confidence: f64,
     /// Time taken in milliseconds
     pub time_ms: u64,
 }
@@ -74,41 +88,35 @@ pub async fn generate(
     Json(request): Json<GenerateRequest>,
 ) -> Result<Json<GenerateResponse>, (StatusCode, Json<ErrorResponse>)> {
     let start = std::time::Instant::now();
-    
-    // Get model
-    let model_guard = state.model.read().await;
-    let model = model_guard.as_ref().ok_or_else(|| {
-        (
-            StatusCode::SERVICE_UNAVAILABLE,
-            Json(ErrorResponse {
-                error: "Model not loaded yet".to_string(),
-            }),
-        )
-    })?;
+    let detection = detect_language_with_confidence(&request.prompt);
 
-    // Detect language
-    let detected_lang = detect_language(&request.prompt);
+    // Sensitive prompts get a fixed reply without touching the model
+    if let Some(reply) = state.classifier.classify(&request.prompt).safe_reply() {
+        return Ok(Json(GenerateResponse {
+            response: reply.to_string(),
+            detected_language: format!("{:?}", detection.language),
+            language_confidence: detection.confidence,
+            prompt: request.prompt,
+            was_translated: false,
+            translated_prompt: None,
+            answer_translated: false,
+            time_ms: start.elapsed().as_millis() as u64,
+        }));
+    }
+
+    let detected_lang = detection.language;
+    debug!(
+        "Detected {:?} (confidence {:.2})",
+        detected_lang, detection.confidence
+    );
     let should_translate = request.translate.unwrap_or(state.auto_translate) 
         && !matches!(detected_lang, Language::English);
+    let match_language = request.match_language.unwrap_or(state.match_language)
+        && !matches!(detected_lang, Language::English | Language::Other);
 
     // Translate if needed
     let (effective_prompt, was_translated, translated_prompt) = if should_translate {
-        let translate_prompt = build_translation_prompt(&request.prompt);
-        
-        let translate_options = GenerateOptions::new(100)
-            .with_sampler(SamplerConfig::default().with_temperature(0.1));
-        
-        let translation = model.generate(&translate_prompt, &translate_options)
-            .map_err(|e| {
-                (
-                    StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ErrorResponse {
-                        error: format!("Translation failed: {}", e),
-                    }),
-                )
-            })?;
-        
-        let english = translation.trim().to_string();
+        let english = translate(&state, &request.prompt, detected_lang).await?;
         (english.clone(), true, Some(english))
     } else {
         (request.prompt.clone(), false, None)
@@ -118,20 +126,24 @@ pub async fn generate(
     let max_tokens = request.max_tokens.unwrap_or(state.max_tokens);
     let temperature = request.temperature.unwrap_or(state.temperature);
     
-    let prompt = format!("Q: {}\nA:", effective_prompt);
+    // An untranslated prompt asks for an answer in its own language
+    let prompt = if match_language && !was_translated {
+        format!("Q: {}\nA:", build_multilingual_prompt(&effective_prompt, detected_lang))
+    } else {
+        format!("Q: {}\nA:", effective_prompt)
+    };
     
     let gen_options = GenerateOptions::new(max_tokens)
         .with_sampler(SamplerConfig::default().with_temperature(temperature));
     
-    let response = model.generate(&prompt, &gen_options)
-        .map_err(|e| {
-            (
-                StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ErrorResponse {
-                    error: format!("Generation failed: {}", e),
-                }),
-            )
-        })?;
+    let response = run_generation(&state, &prompt, &gen_options, "Generation").await?;
+
+    // The model may still answer in English; translate the answer back
+    let (response, answer_translated) = if match_language {
+        answer_in(&state, response.trim(), detected_lang).await?
+    } else {
+        (response, false)
+    };
 
     let time_ms = start.elapsed().as_millis() as u64;
 
@@ -140,27 +152,173 @@ pub async fn generate(
         prompt: request.prompt,
         was_translated,
         translated_prompt,
+        answer_translated,
         detected_language: format!("{:?}", detected_lang),
+        language_confidence: detection.confidence,
         time_ms,
     }))
 }
 
+/// Translate `text` to English with the configured translator
+async fn translate(
+    state: &AppState,
+    text: &str,
+    from: Language,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    let text = text.to_string();
+    run_translator(state, Language::English, move |translator| {
+        translator.translate(&text, from)
+    })
+    .await
+}
+
+/// Make sure `answer` is in `language`, translating it if not
+///
+/// Returns the answer and whether it was translated.
+async fn answer_in(
+    state: &AppState,
+    answer: &str,
+    language: Language,
+) -> Result<(String, bool), (StatusCode, Json<ErrorResponse>)> {
+    let answer = answer.to_string();
+    run_translator(state, language, move |translator| {
+        ensure_language(&answer, language, translator)
+    })
+    .await
+}
+
+/// Run `f` with a translator into `to`, without blocking the runtime
+///
+/// Uses the configured translator when it supports `to`, otherwise the
+/// loaded model.
+async fn run_translator<T: Send + 'static>(
+    state: &AppState,
+    to: Language,
+    f: impl FnOnce(&dyn Translator) -> Result<T, InferenceError> + Send + 'static,
+) -> Result<T, (StatusCode, Json<ErrorResponse>)> {
+    // Cancel a model translation if the client disconnects
+    let cancel = CancellationToken::new();
+    let translator: Arc<dyn Translator> = match &state.translator {
+        Some(translator) if translator.can_translate_to(to) => translator.clone(),
+        _ => Arc::new(
+            ModelTranslator::new(loaded_model(state).await?).with_cancellation(cancel.clone()),
+        ),
+    };
+
+    let guard = cancel.drop_guard();
+    let result = tokio::task::spawn_blocking(move || f(translator.as_ref())).await;
+    guard.disarm();
+
+    match result {
+        Ok(translation) => translation.map_err(|e| generation_error("Translation", e)),
+        Err(e) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ErrorResponse {
+                error: format!("Translation failed: {}", e),
+            }),
+        )),
+    }
+}
+
+/// Run a generation without blocking the runtime
+///
+/// When the client disconnects, axum drops the handler future, which
+/// cancels the generation instead of letting it run to completion.
+async fn run_generation(
+    state: &AppState,
+    prompt: &str,
+    options: &GenerateOptions,
+    what: &str,
+) -> Result<String, (StatusCode, Json<ErrorResponse>)> {
+    let model = loaded_model(state).await?;
+
+    model
+        .generate_async(prompt, options)
+        .await
+        .map_err(|e| generation_error(what, e))
+}
+
+/// Clone the loaded model out of the state
+async fn loaded_model(
+    state: &AppState,
+) -> Result<InferenceModel, (StatusCode, Json<ErrorResponse>)> {
+    state.model.read().await.clone().ok_or_else(|| {
+        (
+            StatusCode::SERVICE_UNAVAILABLE,
+            Json(ErrorResponse {
+                error: "Model not loaded yet".to_string(),
+            }),
+        )
+    })
+}
+
+fn generation_error(what: &str, e: InferenceError) -> (StatusCode, Json<ErrorResponse>) {
+    (
+        StatusCode::INTERNAL_SERVER_ERROR,
+        Json(ErrorResponse {
+            error: format!("{} failed: {}", what, e),
+        }),
+    )
+}
+
 /// Chat endpoint (for compatibility)
+///
+/// The whole conversation is rendered with the model's chat template; the
+/// oldest turns are dropped when it does not fit in the context.
 pub async fn chat(
     State(state): State<Arc<AppState>>,
     Json(request): Json<ChatRequest>,
 ) -> Result<Json<ChatResponse>, (StatusCode, Json<ErrorResponse>)> {
-    let gen_request = GenerateRequest {
-        prompt: request.messages.last()
-            .map(|m| m.content.clone())
-            .unwrap_or_default(),
-        max_tokens: request.max_tokens,
-        temperature: request.temperature,
-        translate: Some(true),
+    let mut messages = request.messages;
+    let user_message = match messages.pop() {
+        Some(message) if message.role == "user" => message.content,
+        _ => {
+            return Err((
+                StatusCode::BAD_REQUEST,
+                Json(ErrorResponse {
+                    error: "The last message must come from the user".to_string(),
+                }),
+            ))
+        }
+    };
+
+    // Sensitive prompts get a fixed reply without touching the model
+    let reply = if let Some(reply) = state.classifier.classify(&user_message).safe_reply() {
+        reply.to_string()
+    } else {
+        let language = detect_language(&user_message);
+        let user_message = if language != Language::English {
+            translate(&state, &user_message, language).await?
+        } else {
+            user_message
+        };
+
+        let max_tokens = request.max_tokens.unwrap_or(state.max_tokens);
+        let temperature = request.temperature.unwrap_or(state.temperature);
+        let options = GenerateOptions::new(max_tokens)
+            .with_sampler(SamplerConfig::default().with_temperature(temperature));
+
+        let model = loaded_model(&state).await?;
+        let mut session = ChatSession::new().with_messages(messages.into_iter().map(|m| {
+            match m.role.as_str() {
+                "system" => neuro_inference::ChatMessage::system(m.content),
+                "assistant" => neuro_inference::ChatMessage::assistant(m.content),
+                _ => neuro_inference::ChatMessage::user(m.content),
+            }
+        }));
+
+        let reply = session
+            .send_async(&model, user_message, &options)
+            .await
+            .map_err(|e| generation_error("Generation", e))?;
+
+        if state.match_language && !matches!(language, Language::English | Language::Other) {
+            answer_in(&state, reply.trim(), language).await?.0
+        } else {
+            reply
+        }
     };
 
-    let result = generate(State(state), Json(gen_request)).await?;
-    
     Ok(Json(ChatResponse {
         id: format!("chatcmpl-{}", uuid_simple()),
         object: "chat.completion".to_string(),
@@ -173,7 +331,7 @@ pub async fn chat(
             index: 0,
             message: ChatMessage {
                 role: "assistant".to_string(),
-                content: result.response.clone(),
+                content: reply.trim().to_string(),
             },
             finish_reason: "stop".to_string(),
         }],
@@ -8,6 +8,7 @@ use tokio::signal;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
 use neuro_daemon::{DaemonConfig, DaemonServer};
+use neuro_inference::TranslatorKind;
 
 #[derive(Parser, Debug)]
 #[command(name = "neuro-daemon")]
@@ -29,6 +30,22 @@ struct Args {
     #[arg(short = 't', long, default_value = "true")]
     auto_translate: bool,
 
+    /// Answer in the query's language, translating English answers back
+    #[arg(long, default_value = "true")]
+    match_language: bool,
+
+    /// How to translate: dictionary, model or api
+    #[arg(long, default_value = "model")]
+    translator: TranslatorKind,
+
+    /// Base URL of a LibreTranslate-compatible API, for --translator api
+    #[arg(long, env = "NEURO_TRANSLATE_API_URL")]
+    translate_api_url: Option<String>,
+
+    /// Key for the translation API
+    #[arg(long, env = "NEURO_TRANSLATE_API_KEY", hide_env_values = true)]
+    translate_api_key: Option<String>,
+
     /// Maximum tokens to generate
     #[arg(long, default_value = "512")]
     max_tokens: u32,
@@ -37,6 +54,11 @@ struct Args {
     #[arg(long, default_value = "0.7")]
     temperature: f32,
 
+    /// Warm the model up before reporting healthy, so the first request
+    /// is fast
+    #[arg(long)]
+    warm_up: bool,
+
     /// Run in foreground (don't daemonize)
     #[arg(short, long)]
     foreground: bool,
@@ -93,13 +115,22 @@ async fn main() -> anyhow::Result<()> {
         anyhow::bail!("Model not found: {}", model_path.display());
     }
 
+    if args.translator == TranslatorKind::Api && args.translate_api_url.is_none() {
+        anyhow::bail!("--translator api needs --translate-api-url");
+    }
+
     let config = DaemonConfig {
         host: args.host,
         port: args.port,
         model_path: model_path.to_string_lossy().to_string(),
         auto_translate: args.auto_translate,
+        match_language: args.match_language,
+        translator: args.translator,
+        translate_api_url: args.translate_api_url,
+        translate_api_key: args.translate_api_key,
         max_tokens: args.max_tokens,
         temperature: args.temperature,
+        warm_up: args.warm_up,
     };
 
     if args.foreground {
@@ -4,11 +4,13 @@ use axum::{
     routing::{get, post},
     Router,
 };
+use neuro_classifier::{Classifier, QueryClassifier};
+use neuro_inference::{ApiTranslator, DictionaryTranslator, Translator, TranslatorKind};
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tower_http::cors::{Any, CorsLayer};
 use tower_http::trace::TraceLayer;
-use tracing::info;
+use tracing::{info, warn};
 
 use crate::{handlers, AppState};
 
@@ -22,10 +24,20 @@ pub struct DaemonConfig {
     pub model_path: String,
     /// Auto-translate non-English queries
     pub auto_translate: bool,
+    /// Answer in the query's language, translating English answers back
+    pub match_language: bool,
+    /// How to translate non-English queries
+    pub translator: TranslatorKind,
+    /// Base URL of the translation API, for [`TranslatorKind::Api`]
+    pub translate_api_url: Option<String>,
+    /// Key for the translation API
+    pub translate_api_key: Option<String>,
     /// Maximum tokens
     pub max_tokens: u32,
     /// Temperature
     pub temperature: f32,
+    /// Warm the model up on startup, before reporting healthy
+    pub warm_up: bool,
 }
 
 impl Default for DaemonConfig {
@@ -35,8 +47,13 @@ impl Default for DaemonConfig {
             port: 11435,
             model_path: String::new(),
             auto_translate: true,
+            match_language: true,
+            translator: TranslatorKind::Model,
+            translate_api_url: None,
+            translate_api_key: None,
             max_tokens: 512,
             temperature: 0.7,
+            warm_up: false,
         }
     }
 }
@@ -50,12 +67,21 @@ pub struct DaemonServer {
 impl DaemonServer {
     /// Create a new daemon server
     pub fn new(config: DaemonConfig) -> Self {
+        Self::with_classifier(config, Arc::new(Classifier::new()))
+    }
+
+    /// Create a daemon server that screens prompts with a custom classifier
+    pub fn with_classifier(config: DaemonConfig, classifier: Arc<dyn QueryClassifier>) -> Self {
         let state = Arc::new(AppState {
             model: Arc::new(tokio::sync::RwLock::new(None)),
             model_path: config.model_path.clone(),
             auto_translate: config.auto_translate,
+            match_language: config.match_language,
+            translator: build_translator(&config),
             max_tokens: config.max_tokens,
             temperature: config.temperature,
+            classifier,
+            warm_up: config.warm_up,
         });
 
         Self { config, state }
@@ -103,7 +129,8 @@ impl DaemonServer {
             .parse()?;
 
         info!("Starting daemon on {}", addr);
-        info!("Auto-translate: {}", self.config.auto_translate);
+        info!("Auto-translate: {} ({})", self.config.auto_translate, self.config.translator);
+        info!("Match answer language: {}", self.config.match_language);
 
         let listener = tokio::net::TcpListener::bind(addr).await?;
         axum::serve(listener, self.router()).await?;
@@ -142,3 +169,23 @@ impl DaemonServer {
         Ok(())
     }
 }
+
+/// Build the configured translator; the model translator needs the loaded
+/// model, so it is created per request
+fn build_translator(config: &DaemonConfig) -> Option<Arc<dyn Translator>> {
+    match (config.translator, &config.translate_api_url) {
+        (TranslatorKind::Model, _) => None,
+        (TranslatorKind::Dictionary, _) => Some(Arc::new(DictionaryTranslator::new())),
+        (TranslatorKind::Api, Some(url)) => {
+            let mut translator = ApiTranslator::new(url);
+            if let Some(key) = &config.translate_api_key {
+                translator = translator.with_api_key(key);
+            }
+            Some(Arc::new(translator))
+        }
+        (TranslatorKind::Api, None) => {
+            warn!("No translation API URL set, using the dictionary translator");
+            Some(Arc::new(DictionaryTranslator::new()))
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Application state for the daemon
 
-use neuro_inference::{InferenceModel, InferenceConfig};
+use neuro_classifier::{Classifier, QueryClassifier};
+use neuro_inference::{InferenceModel, InferenceConfig, Translator};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
@@ -12,10 +13,19 @@ pub struct AppState {
     pub model_path: String,
     /// Whether to auto-translate non-English queries
     pub auto_translate: bool,
+    /// Whether to translate answers back into the query's language
+    pub match_language: bool,
+    /// Translator for non-English queries; `None` uses the loaded model,
+    /// as do answers the translator cannot translate into
+    pub translator: Option<Arc<dyn Translator>>,
     /// Maximum tokens for generation
     pub max_tokens: u32,
     /// Temperature for sampling
     pub temperature: f32,
+    /// Classifier used to screen prompts before generation
+    pub classifier: Arc<dyn QueryClassifier>,
+    /// Warm the model up after loading, before reporting healthy
+    pub warm_up: bool,
 }
 
 impl AppState {
@@ -24,15 +34,30 @@ impl AppState {
             model: Arc::new(RwLock::new(None)),
             model_path,
             auto_translate,
+            match_language: true,
+            translator: None,
             max_tokens: 512,
             temperature: 0.7,
+            classifier: Arc::new(Classifier::new()),
+            warm_up: false,
         }
     }
 
     /// Load the model
+    ///
+    /// With `warm_up` set, the model is only stored, and health reported,
+    /// once it has been warmed up.
     pub async fn load_model(&self) -> anyhow::Result<()> {
         let config = InferenceConfig::new(&self.model_path);
-        let model = tokio::task::spawn_blocking(move || InferenceModel::load(config)).await??;
+        let warm_up = self.warm_up;
+        let model = tokio::task::spawn_blocking(move || {
+            let model = InferenceModel::load(config)?;
+            if warm_up {
+                model.warm_up()?;
+            }
+            Ok::<_, neuro_inference::InferenceError>(model)
+        })
+        .await??;
         
         let mut guard = self.model.write().await;
         *guard = Some(model);
@@ -0,0 +1,165 @@
+//! Async embedding trait and a blocking-pool adapter
+
+use crate::embedder::Embedder;
+use crate::error::{EmbeddingError, Result};
+use crate::models::EmbeddingModel;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// Trait for embedding generation from async code
+///
+/// Mirrors [`Embedder`], but the embedding methods can be awaited without
+/// blocking the runtime.
+#[async_trait]
+pub trait AsyncEmbedder: Send + Sync {
+    /// Get the model being used
+    fn model(&self) -> EmbeddingModel;
+
+    /// Get the identifier recorded alongside embeddings
+    fn model_name(&self) -> String {
+        self.model().to_string()
+    }
+
+    /// Get the embedding dimension
+    fn dimension(&self) -> usize;
+
+    /// Generate embedding for a single text
+    async fn embed_single(&self, text: &str) -> Result<Vec<f32>>;
+
+    /// Generate embeddings for multiple texts (more efficient)
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a search query, adding the model's query instruction
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        self.embed_single(&self.model().format_query(query)).await
+    }
+
+    /// Embed a document, adding the model's passage instruction
+    async fn embed_passage(&self, passage: &str) -> Result<Vec<f32>> {
+        self.embed_single(&self.model().format_passage(passage))
+            .await
+    }
+
+    /// Embed multiple documents, adding the model's passage instruction
+    async fn embed_passages(&self, passages: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let model = self.model();
+        let formatted: Vec<String> = passages.iter().map(|p| model.format_passage(p)).collect();
+        let formatted: Vec<&str> = formatted.iter().map(String::as_str).collect();
+        self.embed_batch(&formatted).await
+    }
+}
+
+/// Runs a blocking [`Embedder`] on tokio's blocking thread pool
+///
+/// Each call copies its input and runs on a `spawn_blocking` worker, so
+/// CPU-bound models and blocking HTTP clients never stall async tasks.
+#[derive(Clone)]
+pub struct SpawnBlockingEmbedder {
+    inner: Arc<dyn Embedder>,
+}
+
+impl SpawnBlockingEmbedder {
+    /// Wrap a blocking embedder
+    pub fn new(inner: Arc<dyn Embedder>) -> Self {
+        Self { inner }
+    }
+
+    /// Get the wrapped blocking embedder
+    pub fn inner(&self) -> &Arc<dyn Embedder> {
+        &self.inner
+    }
+}
+
+#[async_trait]
+impl AsyncEmbedder for SpawnBlockingEmbedder {
+    fn model(&self) -> EmbeddingModel {
+        self.inner.model()
+    }
+
+    fn model_name(&self) -> String {
+        self.inner.model_name()
+    }
+
+    fn dimension(&self) -> usize {
+        self.inner.dimension()
+    }
+
+    async fn embed_single(&self, text: &str) -> Result<Vec<f32>> {
+        let inner = self.inner.clone();
+        let text = text.to_string();
+
+        tokio::task::spawn_blocking(move || inner.embed_single(&text))
+            .await
+            .map_err(|e| EmbeddingError::Generation(format!("Embedding task failed: {}", e)))?
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let inner = self.inner.clone();
+        let texts: Vec<String> = texts.iter().map(|t| t.to_string()).collect();
+
+        tokio::task::spawn_blocking(move || {
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            inner.embed_batch(&texts)
+        })
+        .await
+        .map_err(|e| EmbeddingError::Generation(format!("Embedding task failed: {}", e)))?
+    }
+
+    async fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        let inner = self.inner.clone();
+        let query = query.to_string();
+
+        tokio::task::spawn_blocking(move || inner.embed_query(&query))
+            .await
+            .map_err(|e| EmbeddingError::Generation(format!("Embedding task failed: {}", e)))?
+    }
+
+    async fn embed_passage(&self, passage: &str) -> Result<Vec<f32>> {
+        let inner = self.inner.clone();
+        let passage = passage.to_string();
+
+        tokio::task::spawn_blocking(move || inner.embed_passage(&passage))
+            .await
+            .map_err(|e| EmbeddingError::Generation(format!("Embedding task failed: {}", e)))?
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedder::MockEmbedder;
+
+    #[tokio::test]
+    async fn test_spawn_blocking_matches_blocking() {
+        let blocking = Arc::new(MockEmbedder::new(EmbeddingModel::AllMiniLmL6V2));
+        let embedder = SpawnBlockingEmbedder::new(blocking.clone());
+
+        assert_eq!(embedder.dimension(), 384);
+        assert_eq!(embedder.model_name(), "all-MiniLM-L6-v2");
+
+        let single = embedder.embed_single("hello").await.unwrap();
+        assert_eq!(single, blocking.embed_single("hello").unwrap());
+
+        let batch = embedder.embed_batch(&["hello", "world"]).await.unwrap();
+        assert_eq!(batch.len(), 2);
+        assert_eq!(batch[0], single);
+        assert!(embedder.embed_batch(&[]).await.unwrap().is_empty());
+
+        let query = embedder.embed_query("hello").await.unwrap();
+        assert_eq!(query, blocking.embed_query("hello").unwrap());
+    }
+
+    #[tokio::test]
+    async fn test_spawn_blocking_propagates_errors() {
+        let embedder =
+            SpawnBlockingEmbedder::new(Arc::new(MockEmbedder::new(EmbeddingModel::default())));
+        assert!(matches!(
+            embedder.embed_single("").await,
+            Err(EmbeddingError::InvalidInput(_))
+        ));
+    }
+}
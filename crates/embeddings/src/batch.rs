@@ -0,0 +1,220 @@
+//! Chunked batch embedding with progress reporting and cancellation
+
+use crate::embedder::Embedder;
+use crate::error::{EmbeddingError, Result};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::debug;
+
+/// Default number of texts embedded per chunk
+pub const DEFAULT_BATCH_SIZE: usize = 32;
+
+/// Cooperative cancellation flag shared between threads
+///
+/// Cancellation takes effect between chunks; a chunk already being
+/// embedded always finishes.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
+
+/// Progress of a batch embedding job
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BatchProgress {
+    /// Number of texts embedded so far
+    pub completed: usize,
+    /// Total number of texts
+    pub total: usize,
+}
+
+impl BatchProgress {
+    /// Completed fraction in `[0, 1]`
+    pub fn fraction(&self) -> f32 {
+        if self.total == 0 {
+            1.0
+        } else {
+            self.completed as f32 / self.total as f32
+        }
+    }
+
+    /// Check whether every text has been embedded
+    pub fn is_done(&self) -> bool {
+        self.completed >= self.total
+    }
+}
+
+/// Embeds large inputs in chunks through any [`Embedder`]
+///
+/// ```no_run
+/// use neuro_embeddings::{BatchEmbedder, FastEmbedder};
+///
+/// let embedder = FastEmbedder::default_model().unwrap();
+/// let embeddings = BatchEmbedder::new(&embedder)
+///     .with_batch_size(16)
+///     .embed(&["first", "second"], |p| println!("{}/{}", p.completed, p.total))
+///     .unwrap();
+/// ```
+pub struct BatchEmbedder<'a> {
+    embedder: &'a dyn Embedder,
+    batch_size: usize,
+    cancellation: Option<CancellationToken>,
+}
+
+impl<'a> BatchEmbedder<'a> {
+    /// Create a batch embedder with the default chunk size
+    pub fn new(embedder: &'a dyn Embedder) -> Self {
+        Self {
+            embedder,
+            batch_size: DEFAULT_BATCH_SIZE,
+            cancellation: None,
+        }
+    }
+
+    /// Set the number of texts embedded per chunk
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Stop between chunks once `token` is cancelled
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Embed `texts` chunk by chunk, handing each chunk's embeddings to
+    /// `on_chunk` together with the progress after it
+    ///
+    /// Chunks arrive in input order, so callers can store results as they
+    /// come and keep them if a later chunk fails or the job is cancelled.
+    /// Returns [`EmbeddingError::Cancelled`] when stopped early.
+    pub fn for_each_chunk<F>(&self, texts: &[&str], mut on_chunk: F) -> Result<BatchProgress>
+    where
+        F: FnMut(BatchProgress, Vec<Vec<f32>>),
+    {
+        let mut progress = BatchProgress {
+            completed: 0,
+            total: texts.len(),
+        };
+
+        for chunk in texts.chunks(self.batch_size) {
+            if self.cancellation.as_ref().is_some_and(|t| t.is_cancelled()) {
+                debug!(
+                    "Batch embedding cancelled after {}/{}",
+                    progress.completed, progress.total
+                );
+                return Err(EmbeddingError::Cancelled {
+                    completed: progress.completed,
+                    total: progress.total,
+                });
+            }
+
+            let embeddings = self.embedder.embed_batch(chunk)?;
+            if embeddings.len() != chunk.len() {
+                return Err(EmbeddingError::Generation(format!(
+                    "Embedder returned {} embeddings for {} texts",
+                    embeddings.len(),
+                    chunk.len()
+                )));
+            }
+
+            progress.completed += chunk.len();
+            on_chunk(progress, embeddings);
+        }
+
+        Ok(progress)
+    }
+
+    /// Embed all `texts`, calling `on_progress` after each chunk
+    pub fn embed<F>(&self, texts: &[&str], mut on_progress: F) -> Result<Vec<Vec<f32>>>
+    where
+        F: FnMut(BatchProgress),
+    {
+        let mut all = Vec::with_capacity(texts.len());
+        self.for_each_chunk(texts, |progress, embeddings| {
+            all.extend(embeddings);
+            on_progress(progress);
+        })?;
+        Ok(all)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::embedder::MockEmbedder;
+    use crate::models::EmbeddingModel;
+
+    #[test]
+    fn test_embed_reports_progress() {
+        let embedder = MockEmbedder::new(EmbeddingModel::AllMiniLmL6V2);
+        let texts = ["a", "b", "c", "d", "e"];
+
+        let mut reports = Vec::new();
+        let embeddings = BatchEmbedder::new(&embedder)
+            .with_batch_size(2)
+            .embed(&texts, |p| reports.push(p.completed))
+            .unwrap();
+
+        assert_eq!(embeddings.len(), 5);
+        assert_eq!(embeddings[4], embedder.embed_single("e").unwrap());
+        assert_eq!(reports, vec![2, 4, 5]);
+    }
+
+    #[test]
+    fn test_cancellation_keeps_completed_chunks() {
+        let embedder = MockEmbedder::new(EmbeddingModel::AllMiniLmL6V2);
+        let token = CancellationToken::new();
+        let texts = ["a", "b", "c", "d"];
+
+        let mut received = 0;
+        let result = BatchEmbedder::new(&embedder)
+            .with_batch_size(1)
+            .with_cancellation(token.clone())
+            .for_each_chunk(&texts, |progress, embeddings| {
+                received += embeddings.len();
+                if progress.completed == 2 {
+                    token.cancel();
+                }
+            });
+
+        assert!(matches!(
+            result,
+            Err(EmbeddingError::Cancelled {
+                completed: 2,
+                total: 4
+            })
+        ));
+        assert_eq!(received, 2);
+    }
+
+    #[test]
+    fn test_progress_fraction() {
+        let progress = BatchProgress {
+            completed: 1,
+            total: 4,
+        };
+        assert_eq!(progress.fraction(), 0.25);
+        assert!(!progress.is_done());
+        assert!(BatchProgress {
+            completed: 0,
+            total: 0
+        }
+        .is_done());
+    }
+}
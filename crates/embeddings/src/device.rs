@@ -0,0 +1,177 @@
+//! Runtime device selection for local embedders
+
+use crate::error::Result;
+use fastembed::ExecutionProviderDispatch;
+use serde::{Deserialize, Serialize};
+
+#[cfg(not(feature = "cuda"))]
+use crate::error::EmbeddingError;
+#[cfg(not(feature = "cuda"))]
+use tracing::warn;
+
+/// Hardware a local embedder runs on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Device {
+    /// Run on the CPU
+    #[default]
+    Cpu,
+
+    /// Run on the CUDA GPU with the given device id
+    ///
+    /// Requires the `cuda` feature.
+    Cuda(u32),
+}
+
+impl Device {
+    /// Check if this is a GPU device
+    pub fn is_gpu(&self) -> bool {
+        !matches!(self, Self::Cpu)
+    }
+}
+
+impl std::fmt::Display for Device {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Cpu => write!(f, "cpu"),
+            Self::Cuda(id) => write!(f, "cuda:{}", id),
+        }
+    }
+}
+
+impl std::str::FromStr for Device {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim().to_lowercase();
+        match s.as_str() {
+            "cpu" => Ok(Self::Cpu),
+            "cuda" | "gpu" => Ok(Self::Cuda(0)),
+            _ => s
+                .strip_prefix("cuda:")
+                .or_else(|| s.strip_prefix("gpu:"))
+                .and_then(|id| id.parse().ok())
+                .map(Self::Cuda)
+                .ok_or_else(|| format!("Unknown device: {} (expected cpu or cuda:<id>)", s)),
+        }
+    }
+}
+
+/// Runtime options for [`FastEmbedder`](crate::FastEmbedder)
+///
+/// ```no_run
+/// use neuro_embeddings::{Device, EmbedderOptions, EmbeddingModel, FastEmbedder};
+///
+/// // Pin to the second GPU, failing instead of silently using the CPU
+/// let options = EmbedderOptions::new()
+///     .with_device(Device::Cuda(1))
+///     .with_fallback_to_cpu(false)
+///     .with_max_batch_size(64);
+/// let embedder = FastEmbedder::new_with_options(EmbeddingModel::BgeBaseEnV15, options).unwrap();
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EmbedderOptions {
+    /// Device to run the model on
+    pub device: Device,
+    /// Use the CPU when the device cannot be initialized
+    pub fallback_to_cpu: bool,
+    /// Largest number of texts sent to the device at once
+    /// (`None` uses fastembed's default)
+    pub max_batch_size: Option<usize>,
+}
+
+impl Default for EmbedderOptions {
+    fn default() -> Self {
+        Self {
+            device: Device::Cpu,
+            fallback_to_cpu: true,
+            max_batch_size: None,
+        }
+    }
+}
+
+impl EmbedderOptions {
+    /// Create options for the CPU
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the device
+    pub fn with_device(mut self, device: Device) -> Self {
+        self.device = device;
+        self
+    }
+
+    /// Set whether to fall back to the CPU when the device is unavailable
+    pub fn with_fallback_to_cpu(mut self, fallback: bool) -> Self {
+        self.fallback_to_cpu = fallback;
+        self
+    }
+
+    /// Set the largest batch sent to the device at once
+    pub fn with_max_batch_size(mut self, max_batch_size: usize) -> Self {
+        self.max_batch_size = Some(max_batch_size.max(1));
+        self
+    }
+
+    /// Build the ONNX Runtime execution providers for these options
+    ///
+    /// An empty list means the CPU.
+    pub(crate) fn execution_providers(&self) -> Result<Vec<ExecutionProviderDispatch>> {
+        match self.device {
+            Device::Cpu => Ok(Vec::new()),
+            #[cfg(feature = "cuda")]
+            Device::Cuda(id) => {
+                let provider = ort::ep::CUDA::default()
+                    .with_device_id(id as i32)
+                    .build();
+                Ok(vec![if self.fallback_to_cpu {
+                    provider
+                } else {
+                    provider.error_on_failure()
+                }])
+            }
+            #[cfg(not(feature = "cuda"))]
+            Device::Cuda(_) if self.fallback_to_cpu => {
+                warn!(
+                    "{} requested but neuro-embeddings was built without the `cuda` feature, using cpu",
+                    self.device
+                );
+                Ok(Vec::new())
+            }
+            #[cfg(not(feature = "cuda"))]
+            Device::Cuda(_) => Err(EmbeddingError::ModelInit(format!(
+                "{} requested but neuro-embeddings was built without the `cuda` feature",
+                self.device
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_device_parsing() {
+        assert_eq!("cpu".parse::<Device>().unwrap(), Device::Cpu);
+        assert_eq!("cuda".parse::<Device>().unwrap(), Device::Cuda(0));
+        assert_eq!("CUDA:2".parse::<Device>().unwrap(), Device::Cuda(2));
+        assert!("cuda:x".parse::<Device>().is_err());
+        assert!("tpu".parse::<Device>().is_err());
+        assert_eq!(Device::Cuda(1).to_string(), "cuda:1");
+    }
+
+    #[cfg(not(feature = "cuda"))]
+    #[test]
+    fn test_cuda_without_feature() {
+        let options = EmbedderOptions::new().with_device(Device::Cuda(1));
+        assert!(options.execution_providers().unwrap().is_empty());
+
+        let strict = options.with_fallback_to_cpu(false);
+        assert!(matches!(
+            strict.execution_providers(),
+            Err(EmbeddingError::ModelInit(_))
+        ));
+    }
+}
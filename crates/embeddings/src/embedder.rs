@@ -1,16 +1,31 @@
 //! Embedding generation trait and implementations
 
+use crate::device::{Device, EmbedderOptions};
 use crate::error::{EmbeddingError, Result};
 use crate::models::EmbeddingModel;
+use crate::truncation::{truncate_to_tokens, TruncationStrategy};
 use fastembed::{InitOptions, TextEmbedding};
+use std::borrow::Cow;
 use std::sync::Mutex;
-use tracing::{debug, info};
+use tokenizers::Tokenizer;
+use tracing::{debug, info, warn};
+
+/// Token limit assumed when the tokenizer does not declare one
+const DEFAULT_MAX_TOKENS: usize = 512;
 
 /// Trait for text embedding generation
 pub trait Embedder: Send + Sync {
     /// Get the model being used
     fn model(&self) -> EmbeddingModel;
 
+    /// Get the identifier recorded alongside embeddings
+    ///
+    /// Defaults to the model's name; remote embedders return the name the
+    /// server uses, which may not correspond to an [`EmbeddingModel`].
+    fn model_name(&self) -> String {
+        self.model().to_string()
+    }
+
     /// Get the embedding dimension
     fn dimension(&self) -> usize;
 
@@ -19,21 +34,59 @@ pub trait Embedder: Send + Sync {
 
     /// Generate embeddings for multiple texts (more efficient)
     fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>>;
+
+    /// Embed a search query, adding the model's query instruction
+    ///
+    /// Use this for text that is searched with, and [`Self::embed_passage`]
+    /// for text that is searched over, so asymmetric models such as BGE and
+    /// E5 retrieve at full quality.
+    fn embed_query(&self, query: &str) -> Result<Vec<f32>> {
+        self.embed_single(&self.model().format_query(query))
+    }
+
+    /// Embed a document, adding the model's passage instruction
+    fn embed_passage(&self, passage: &str) -> Result<Vec<f32>> {
+        self.embed_single(&self.model().format_passage(passage))
+    }
+
+    /// Embed multiple documents, adding the model's passage instruction
+    fn embed_passages(&self, passages: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let model = self.model();
+        let formatted: Vec<String> = passages.iter().map(|p| model.format_passage(p)).collect();
+        let formatted: Vec<&str> = formatted.iter().map(String::as_str).collect();
+        self.embed_batch(&formatted)
+    }
 }
 
 /// FastEmbed-based embedder implementation
+///
+/// Inputs longer than the model's token limit are truncated on word
+/// boundaries according to a [`TruncationStrategy`] (keeping the head by
+/// default), with a warning, instead of being cut mid-word by the model.
 pub struct FastEmbedder {
     model: Mutex<TextEmbedding>,
     model_type: EmbeddingModel,
+    /// Copy of the model's tokenizer without truncation or padding
+    tokenizer: Tokenizer,
+    max_tokens: usize,
+    special_tokens: usize,
+    truncation: TruncationStrategy,
+    options: EmbedderOptions,
 }
 
 impl FastEmbedder {
-    /// Create a new FastEmbedder with the specified model
+    /// Create a new FastEmbedder with the specified model on the CPU
     pub fn new(model_type: EmbeddingModel) -> Result<Self> {
+        Self::new_with_options(model_type, EmbedderOptions::default())
+    }
+
+    /// Create a new FastEmbedder with runtime options such as the device
+    pub fn new_with_options(model_type: EmbeddingModel, options: EmbedderOptions) -> Result<Self> {
         info!(
-            "Initializing FastEmbedder with model: {} ({}D)",
+            "Initializing FastEmbedder with model: {} ({}D) on {}",
             model_type.model_name(),
-            model_type.dimension()
+            model_type.dimension(),
+            options.device
         );
 
         let fastembed_model = match model_type {
@@ -46,18 +99,49 @@ impl FastEmbedder {
             EmbeddingModel::MultilingualE5Small => fastembed::EmbeddingModel::MultilingualE5Small,
             EmbeddingModel::MultilingualE5Base => fastembed::EmbeddingModel::MultilingualE5Base,
             EmbeddingModel::MultilingualE5Large => fastembed::EmbeddingModel::MultilingualE5Large,
+            EmbeddingModel::ParaphraseMultilingualMiniLmL12V2 => {
+                fastembed::EmbeddingModel::ParaphraseMLMiniLML12V2
+            }
+            EmbeddingModel::ParaphraseMultilingualMpnetBaseV2 => {
+                fastembed::EmbeddingModel::ParaphraseMLMpnetBaseV2
+            }
         };
 
         let model = TextEmbedding::try_new(
-            InitOptions::new(fastembed_model).with_show_download_progress(true),
+            InitOptions::new(fastembed_model)
+                .with_execution_providers(options.execution_providers()?)
+                .with_show_download_progress(true),
         )
         .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?;
 
-        info!("FastEmbedder initialized successfully");
+        let max_tokens = model
+            .tokenizer
+            .get_truncation()
+            .map(|t| t.max_length)
+            .unwrap_or(DEFAULT_MAX_TOKENS);
+
+        let mut tokenizer = model.tokenizer.clone();
+        tokenizer
+            .with_truncation(None)
+            .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?;
+        tokenizer.with_padding(None);
+
+        let special_tokens = tokenizer
+            .encode("", true)
+            .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?
+            .get_ids()
+            .len();
+
+        info!("FastEmbedder initialized successfully ({} token limit)", max_tokens);
 
         Ok(Self {
             model: Mutex::new(model),
             model_type,
+            tokenizer,
+            max_tokens,
+            special_tokens,
+            truncation: TruncationStrategy::default(),
+            options,
         })
     }
 
@@ -73,6 +157,71 @@ impl FastEmbedder {
             .map_err(|e: String| EmbeddingError::ModelNotFound(e))?;
         Self::new(model_type)
     }
+
+    /// Set how over-long inputs are truncated
+    pub fn with_truncation(mut self, truncation: TruncationStrategy) -> Self {
+        self.truncation = truncation;
+        self
+    }
+
+    /// Get the truncation strategy
+    pub fn truncation(&self) -> TruncationStrategy {
+        self.truncation
+    }
+
+    /// Get the device the embedder was configured for
+    ///
+    /// With CPU fallback enabled the model may be running on the CPU
+    /// instead if the device could not be initialized.
+    pub fn device(&self) -> Device {
+        self.options.device
+    }
+
+    /// Get the model's token limit, including special tokens
+    pub fn max_tokens(&self) -> usize {
+        self.max_tokens
+    }
+
+    /// Count the tokens the model would see for `text`, including special
+    /// tokens and ignoring the limit
+    pub fn count_tokens(&self, text: &str) -> Result<usize> {
+        let encoding = self
+            .tokenizer
+            .encode(text, true)
+            .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
+        Ok(encoding.get_ids().len())
+    }
+
+    /// Truncate `text` to fit the model's token limit
+    ///
+    /// Returns the text unchanged when it already fits.
+    pub fn truncate<'a>(&self, text: &'a str) -> Result<Cow<'a, str>> {
+        let encoding = self
+            .tokenizer
+            .encode(text, false)
+            .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
+        let budget = self.max_tokens.saturating_sub(self.special_tokens);
+        let tokens = encoding.get_ids().len();
+
+        if tokens <= budget {
+            return Ok(Cow::Borrowed(text));
+        }
+
+        warn!(
+            "Input of {} tokens exceeds the {}-token limit of {}, truncating ({})",
+            tokens + self.special_tokens,
+            self.max_tokens,
+            self.model_type,
+            self.truncation
+        );
+
+        Ok(truncate_to_tokens(
+            text,
+            encoding.get_offsets(),
+            budget,
+            self.truncation,
+        ))
+    }
 }
 
 impl Embedder for FastEmbedder {
@@ -91,13 +240,15 @@ impl Embedder for FastEmbedder {
 
         debug!("Embedding single text ({} chars)", text.len());
 
+        let text = self.truncate(text)?;
+
         let mut model = self
             .model
             .lock()
             .map_err(|_| EmbeddingError::Generation("Lock poisoned".to_string()))?;
         
         let embeddings = model
-            .embed(vec![text], None)
+            .embed(vec![text.as_ref()], self.options.max_batch_size)
             .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
 
         embeddings
@@ -123,13 +274,19 @@ impl Embedder for FastEmbedder {
 
         debug!("Embedding batch of {} texts", texts.len());
 
+        let truncated = texts
+            .iter()
+            .map(|t| self.truncate(t))
+            .collect::<Result<Vec<_>>>()?;
+        let texts: Vec<&str> = truncated.iter().map(|t| t.as_ref()).collect();
+
         let mut model = self
             .model
             .lock()
             .map_err(|_| EmbeddingError::Generation("Lock poisoned".to_string()))?;
 
         model
-            .embed(texts.to_vec(), None)
+            .embed(texts, self.options.max_batch_size)
             .map_err(|e| EmbeddingError::Generation(e.to_string()))
     }
 }
@@ -207,6 +364,28 @@ mod tests {
         assert!(result.is_err());
     }
 
+    #[test]
+    fn test_query_and_passage_prefixes() {
+        let embedder = MockEmbedder::new(EmbeddingModel::MultilingualE5Small);
+
+        assert_eq!(
+            embedder.embed_query("rust").unwrap(),
+            embedder.embed_single("query: rust").unwrap()
+        );
+        assert_eq!(
+            embedder.embed_passages(&["rust"]).unwrap()[0],
+            embedder.embed_single("passage: rust").unwrap()
+        );
+        assert!(embedder.embed_query("").is_err());
+
+        // Symmetric models embed queries unchanged
+        let embedder = MockEmbedder::new(EmbeddingModel::AllMiniLmL6V2);
+        assert_eq!(
+            embedder.embed_query("rust").unwrap(),
+            embedder.embed_passage("rust").unwrap()
+        );
+    }
+
     // Integration test - only runs when fastembed can download models
     #[test]
     #[ignore = "Requires model download"]
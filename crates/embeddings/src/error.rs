@@ -24,6 +24,10 @@ pub enum EmbeddingError {
     /// Dimension mismatch
     #[error("Embedding dimension mismatch: expected {expected}, got {actual}")]
     DimensionMismatch { expected: usize, actual: usize },
+
+    /// Batch embedding was cancelled
+    #[error("Embedding cancelled after {completed} of {total} texts")]
+    Cancelled { completed: usize, total: usize },
 }
 
 /// Result type for embedding operations
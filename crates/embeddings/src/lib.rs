@@ -4,11 +4,21 @@
 //!
 //! This crate provides text embedding functionality using fastembed,
 //! supporting various pre-trained models optimized for semantic search.
+//! Embeddings can also be requested from any OpenAI-compatible
+//! `/v1/embeddings` endpoint (OpenAI, Ollama, LM Studio) with
+//! [`RemoteEmbedder`].
 //!
 //! ## Features
 //!
 //! - `cuda` - Enable CUDA GPU acceleration (requires NVIDIA GPU)
 //!
+//! ## Devices
+//!
+//! [`FastEmbedder::new_with_options`] takes [`EmbedderOptions`] to pick the
+//! [`Device`] (e.g. `Device::Cuda(1)` on a multi-GPU machine), whether to
+//! fall back to the CPU when it is unavailable, and the largest batch sent
+//! to the device at once.
+//!
 //! ## Example
 //!
 //! ```no_run
@@ -22,16 +32,94 @@
 //! // Multiple texts (more efficient)
 //! let embeddings = embedder.embed_batch(&["Text 1", "Text 2"]).unwrap();
 //! ```
+//!
+//! ## Large batches
+//!
+//! [`BatchEmbedder`] splits large inputs into chunks, reports
+//! [`BatchProgress`] after each one and stops between chunks once its
+//! [`CancellationToken`] is cancelled.
+//!
+//! ## Long inputs
+//!
+//! [`FastEmbedder`] truncates inputs that exceed the model's token limit on
+//! word boundaries, keeping the head, the tail or both ends
+//! ([`TruncationStrategy`]), and logs a warning when it does.
+//! [`FastEmbedder::count_tokens`] reports the token count up front.
+//!
+//! ## Sparse embeddings
+//!
+//! [`SparseEmbedder`] produces term-weight vectors
+//! ([`neuro_core::SparseEmbedding`]) that capture exact keyword matches.
+//! [`SpladeEmbedder`] uses a learned SPLADE model; [`Bm25Embedder`] needs
+//! no model at all. Stored alongside dense embeddings they enable hybrid
+//! retrieval.
+//!
+//! ## Reranking
+//!
+//! [`Reranker`] re-scores candidate documents against a query with a
+//! cross-encoder, which is more accurate than embedding similarity for
+//! ordering the top results of a search.
+//!
+//! ```no_run
+//! use neuro_embeddings::{Reranker, RerankerModel};
+//!
+//! let reranker = Reranker::new(RerankerModel::BgeRerankerBase).unwrap();
+//! let ranked = reranker
+//!     .rerank("what is rust?", &["Rust is a language", "Rust on iron"])
+//!     .unwrap();
+//! println!("best match: document {}", ranked[0].index);
+//! ```
+//!
+//! ## Async usage
+//!
+//! [`SpawnBlockingEmbedder`] adapts any [`Embedder`] to [`AsyncEmbedder`],
+//! running it on tokio's blocking pool so async handlers can `await`
+//! embeddings without stalling the runtime.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use neuro_embeddings::{AsyncEmbedder, FastEmbedder, SpawnBlockingEmbedder};
+//!
+//! # async fn run() {
+//! let embedder = SpawnBlockingEmbedder::new(Arc::new(FastEmbedder::default_model().unwrap()));
+//! let embedding = embedder.embed_single("Hello, world!").await.unwrap();
+//! # }
+//! ```
+//!
+//! ## Remote embeddings
+//!
+//! ```no_run
+//! use neuro_embeddings::{Embedder, RemoteEmbedder, RemoteEmbedderConfig};
+//!
+//! let config = RemoteEmbedderConfig::new("http://localhost:11434", "nomic-embed-text");
+//! let embedder = RemoteEmbedder::new(config).unwrap();
+//!
+//! let embedding = embedder.embed_single("Hello, world!").unwrap();
+//! ```
 
+mod async_embedder;
+mod batch;
+mod device;
 mod embedder;
 mod models;
 mod error;
+mod remote;
+mod sparse;
+mod reranker;
+mod truncation;
 
+pub use async_embedder::{AsyncEmbedder, SpawnBlockingEmbedder};
+pub use batch::{BatchEmbedder, BatchProgress, CancellationToken, DEFAULT_BATCH_SIZE};
+pub use device::{Device, EmbedderOptions};
 pub use embedder::{Embedder, FastEmbedder};
+pub use remote::{RemoteEmbedder, RemoteEmbedderConfig};
+pub use sparse::{Bm25Embedder, SparseEmbedder, SpladeEmbedder};
+pub use reranker::{RerankScore, Reranker, RerankerModel};
+pub use truncation::{truncate_to_tokens, TruncationStrategy};
 pub use models::EmbeddingModel;
 pub use error::{EmbeddingError, Result};
 
 /// Re-export commonly used types
 pub mod prelude {
-    pub use crate::{Embedder, FastEmbedder, EmbeddingModel, EmbeddingError, Result};
+    pub use crate::{Embedder, FastEmbedder, RemoteEmbedder, EmbeddingModel, EmbeddingError, Result};
 }
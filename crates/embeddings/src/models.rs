@@ -32,6 +32,12 @@ pub enum EmbeddingModel {
     
     /// Multilingual-e5-large (1024 dimensions, multilingual, highest quality)
     MultilingualE5Large,
+
+    /// paraphrase-multilingual-MiniLM-L12-v2 (384 dimensions, multilingual, fast)
+    ParaphraseMultilingualMiniLmL12V2,
+
+    /// paraphrase-multilingual-mpnet-base-v2 (768 dimensions, multilingual)
+    ParaphraseMultilingualMpnetBaseV2,
 }
 
 impl EmbeddingModel {
@@ -47,6 +53,8 @@ impl EmbeddingModel {
             Self::MultilingualE5Small => 384,
             Self::MultilingualE5Base => 768,
             Self::MultilingualE5Large => 1024,
+            Self::ParaphraseMultilingualMiniLmL12V2 => 384,
+            Self::ParaphraseMultilingualMpnetBaseV2 => 768,
         }
     }
 
@@ -62,6 +70,8 @@ impl EmbeddingModel {
             Self::MultilingualE5Small => "multilingual-e5-small",
             Self::MultilingualE5Base => "multilingual-e5-base",
             Self::MultilingualE5Large => "multilingual-e5-large",
+            Self::ParaphraseMultilingualMiniLmL12V2 => "paraphrase-multilingual-MiniLM-L12-v2",
+            Self::ParaphraseMultilingualMpnetBaseV2 => "paraphrase-multilingual-mpnet-base-v2",
         }
     }
 
@@ -69,10 +79,69 @@ impl EmbeddingModel {
     pub fn is_multilingual(&self) -> bool {
         matches!(
             self,
-            Self::MultilingualE5Small | Self::MultilingualE5Base | Self::MultilingualE5Large
+            Self::MultilingualE5Small
+                | Self::MultilingualE5Base
+                | Self::MultilingualE5Large
+                | Self::ParaphraseMultilingualMiniLmL12V2
+                | Self::ParaphraseMultilingualMpnetBaseV2
         )
     }
 
+    /// Pick a model for a corpus
+    ///
+    /// Returns the default model when `is_english` accepts every sample,
+    /// and the multilingual MiniLM preset (same dimension) as soon as one
+    /// sample is not English.
+    pub fn auto_select<'a, I, F>(samples: I, is_english: F) -> Self
+    where
+        I: IntoIterator<Item = &'a str>,
+        F: Fn(&str) -> bool,
+    {
+        if samples.into_iter().all(is_english) {
+            Self::default()
+        } else {
+            Self::ParaphraseMultilingualMiniLmL12V2
+        }
+    }
+
+    /// Instruction prepended to search queries
+    ///
+    /// BGE models expect a retrieval instruction on queries only; E5 models
+    /// were trained with `query: ` and `passage: ` markers on both sides.
+    pub fn query_prefix(&self) -> &'static str {
+        match self {
+            Self::BgeSmallEnV15 | Self::BgeBaseEnV15 | Self::BgeLargeEnV15 => {
+                "Represent this sentence for searching relevant passages: "
+            }
+            Self::MultilingualE5Small | Self::MultilingualE5Base | Self::MultilingualE5Large => {
+                "query: "
+            }
+            _ => "",
+        }
+    }
+
+    /// Instruction prepended to documents being indexed
+    pub fn passage_prefix(&self) -> &'static str {
+        match self {
+            Self::MultilingualE5Small | Self::MultilingualE5Base | Self::MultilingualE5Large => {
+                "passage: "
+            }
+            _ => "",
+        }
+    }
+
+    /// Format a search query the way this model expects
+    ///
+    /// Empty text is returned as is so embedders still reject it.
+    pub fn format_query(&self, text: &str) -> String {
+        with_prefix(self.query_prefix(), text)
+    }
+
+    /// Format a document the way this model expects
+    pub fn format_passage(&self, text: &str) -> String {
+        with_prefix(self.passage_prefix(), text)
+    }
+
     /// Get relative speed (1-5, higher is faster)
     pub fn speed_rating(&self) -> u8 {
         match self {
@@ -80,9 +149,11 @@ impl EmbeddingModel {
             Self::AllMiniLmL12V2 => 4,
             Self::BgeSmallEnV15 => 5,
             Self::MultilingualE5Small => 4,
+            Self::ParaphraseMultilingualMiniLmL12V2 => 4,
             Self::AllMpnetBaseV2 => 3,
             Self::BgeBaseEnV15 => 3,
             Self::MultilingualE5Base => 3,
+            Self::ParaphraseMultilingualMpnetBaseV2 => 3,
             Self::BgeLargeEnV15 => 2,
             Self::MultilingualE5Large => 1,
         }
@@ -95,15 +166,25 @@ impl EmbeddingModel {
             Self::AllMiniLmL12V2 => 3,
             Self::BgeSmallEnV15 => 3,
             Self::MultilingualE5Small => 3,
+            Self::ParaphraseMultilingualMiniLmL12V2 => 3,
             Self::AllMpnetBaseV2 => 4,
             Self::BgeBaseEnV15 => 4,
             Self::MultilingualE5Base => 4,
+            Self::ParaphraseMultilingualMpnetBaseV2 => 4,
             Self::BgeLargeEnV15 => 5,
             Self::MultilingualE5Large => 5,
         }
     }
 }
 
+fn with_prefix(prefix: &str, text: &str) -> String {
+    if text.is_empty() {
+        String::new()
+    } else {
+        format!("{}{}", prefix, text)
+    }
+}
+
 impl Default for EmbeddingModel {
     fn default() -> Self {
         Self::AllMiniLmL6V2
@@ -130,6 +211,12 @@ impl std::str::FromStr for EmbeddingModel {
             "multilingual-e5-small" | "e5-small" => Ok(Self::MultilingualE5Small),
             "multilingual-e5-base" | "e5-base" => Ok(Self::MultilingualE5Base),
             "multilingual-e5-large" | "e5-large" | "e5" => Ok(Self::MultilingualE5Large),
+            "paraphrase-multilingual-minilm-l12-v2" | "multilingual-minilm" | "multilingual" => {
+                Ok(Self::ParaphraseMultilingualMiniLmL12V2)
+            }
+            "paraphrase-multilingual-mpnet-base-v2" | "multilingual-mpnet" => {
+                Ok(Self::ParaphraseMultilingualMpnetBaseV2)
+            }
             _ => Err(format!("Unknown model: {}", s)),
         }
     }
@@ -157,5 +244,37 @@ mod tests {
     fn test_multilingual() {
         assert!(EmbeddingModel::MultilingualE5Large.is_multilingual());
         assert!(!EmbeddingModel::AllMiniLmL6V2.is_multilingual());
+        assert!(EmbeddingModel::ParaphraseMultilingualMiniLmL12V2.is_multilingual());
+        assert_eq!(
+            "multilingual-mpnet".parse::<EmbeddingModel>().unwrap(),
+            EmbeddingModel::ParaphraseMultilingualMpnetBaseV2
+        );
+    }
+
+    #[test]
+    fn test_auto_select() {
+        let is_english = |text: &str| !text.contains('ñ');
+
+        let model = EmbeddingModel::auto_select(["hello", "world"], is_english);
+        assert_eq!(model, EmbeddingModel::AllMiniLmL6V2);
+
+        let model = EmbeddingModel::auto_select(["hello", "mañana"], is_english);
+        assert!(model.is_multilingual());
+        assert_eq!(model.dimension(), EmbeddingModel::default().dimension());
+    }
+
+    #[test]
+    fn test_instruction_prefixes() {
+        let e5 = EmbeddingModel::MultilingualE5Base;
+        assert_eq!(e5.format_query("rust"), "query: rust");
+        assert_eq!(e5.format_passage("rust"), "passage: rust");
+
+        let bge = EmbeddingModel::BgeSmallEnV15;
+        assert!(bge.format_query("rust").ends_with(": rust"));
+        assert_eq!(bge.format_passage("rust"), "rust");
+
+        let minilm = EmbeddingModel::AllMiniLmL6V2;
+        assert_eq!(minilm.format_query("rust"), "rust");
+        assert_eq!(e5.format_query(""), "");
     }
 }
@@ -0,0 +1,407 @@
+//! Remote embedder for OpenAI-compatible embedding endpoints
+//!
+//! Works with any server exposing `POST /v1/embeddings` in the OpenAI
+//! format, including OpenAI itself, Ollama and LM Studio.
+
+use crate::embedder::Embedder;
+use crate::error::{EmbeddingError, Result};
+use crate::models::EmbeddingModel;
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Default timeout for embedding requests in seconds
+const DEFAULT_TIMEOUT_SECS: u64 = 60;
+
+/// Default number of texts sent per request
+const DEFAULT_BATCH_SIZE: usize = 64;
+
+/// Text embedded once to discover the dimension when none is configured
+const DIMENSION_PROBE: &str = "dimension probe";
+
+/// Configuration for a [`RemoteEmbedder`]
+#[derive(Debug, Clone)]
+pub struct RemoteEmbedderConfig {
+    /// Base URL of the server (e.g. `http://localhost:11434` for Ollama)
+    pub base_url: String,
+    /// Model name as the server knows it
+    pub model: String,
+    /// API key sent as a bearer token, if the server requires one
+    pub api_key: Option<String>,
+    /// Embedding dimension; probed from the server when `None`
+    pub dimension: Option<usize>,
+    /// Request timeout in seconds
+    pub timeout_secs: u64,
+    /// Maximum number of texts sent in a single request
+    pub batch_size: usize,
+}
+
+impl RemoteEmbedderConfig {
+    /// Create a config for the given server and model
+    pub fn new(base_url: impl Into<String>, model: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            model: model.into(),
+            api_key: None,
+            dimension: None,
+            timeout_secs: DEFAULT_TIMEOUT_SECS,
+            batch_size: DEFAULT_BATCH_SIZE,
+        }
+    }
+
+    /// Set the API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Set the embedding dimension, skipping the probe request
+    pub fn with_dimension(mut self, dimension: usize) -> Self {
+        self.dimension = Some(dimension);
+        self
+    }
+
+    /// Set the request timeout
+    pub fn with_timeout_secs(mut self, timeout_secs: u64) -> Self {
+        self.timeout_secs = timeout_secs;
+        self
+    }
+
+    /// Set the maximum number of texts per request
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Full URL of the embeddings endpoint
+    ///
+    /// Accepts base URLs with or without a trailing `/v1`.
+    pub fn endpoint(&self) -> String {
+        let base = self.base_url.trim_end_matches('/');
+        if base.ends_with("/v1") {
+            format!("{}/embeddings", base)
+        } else {
+            format!("{}/v1/embeddings", base)
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct EmbeddingsRequest<'a> {
+    model: &'a str,
+    input: &'a [&'a str],
+}
+
+#[derive(Deserialize)]
+struct EmbeddingsResponse {
+    data: Vec<EmbeddingData>,
+}
+
+#[derive(Deserialize)]
+struct EmbeddingData {
+    embedding: Vec<f32>,
+    #[serde(default)]
+    index: usize,
+}
+
+/// Embedder backed by an OpenAI-compatible `/v1/embeddings` endpoint
+pub struct RemoteEmbedder {
+    agent: ureq::Agent,
+    config: RemoteEmbedderConfig,
+    dimension: usize,
+}
+
+impl RemoteEmbedder {
+    /// Create a remote embedder
+    ///
+    /// When no dimension is configured, one text is embedded to discover
+    /// it, so this fails early if the server is unreachable.
+    pub fn new(config: RemoteEmbedderConfig) -> Result<Self> {
+        info!(
+            "Initializing RemoteEmbedder with model {} at {}",
+            config.model,
+            config.endpoint()
+        );
+
+        let agent_config = ureq::Agent::config_builder()
+            .timeout_global(Some(Duration::from_secs(config.timeout_secs)))
+            .http_status_as_error(false)
+            .build();
+        let agent = ureq::Agent::new_with_config(agent_config);
+
+        let mut embedder = Self {
+            agent,
+            config,
+            dimension: 0,
+        };
+
+        embedder.dimension = match embedder.config.dimension {
+            Some(dimension) => dimension,
+            None => embedder
+                .request(&[DIMENSION_PROBE])
+                .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?
+                .into_iter()
+                .next()
+                .map(|e| e.len())
+                .ok_or_else(|| EmbeddingError::ModelInit("No embedding returned".into()))?,
+        };
+
+        info!("RemoteEmbedder initialized ({}D)", embedder.dimension);
+
+        Ok(embedder)
+    }
+
+    /// Get the configuration
+    pub fn config(&self) -> &RemoteEmbedderConfig {
+        &self.config
+    }
+
+    /// Send one request and return embeddings in input order
+    fn request(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        let mut request = self.agent.post(&self.config.endpoint());
+        if let Some(ref api_key) = self.config.api_key {
+            request = request.header("Authorization", &format!("Bearer {}", api_key));
+        }
+
+        let mut response = request
+            .send_json(EmbeddingsRequest {
+                model: &self.config.model,
+                input: texts,
+            })
+            .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            return Err(EmbeddingError::Generation(format!(
+                "Embedding server returned {}: {}",
+                status, body
+            )));
+        }
+
+        let parsed: EmbeddingsResponse = response
+            .body_mut()
+            .read_json()
+            .map_err(|e| EmbeddingError::Generation(format!("Invalid response: {}", e)))?;
+
+        if parsed.data.len() != texts.len() {
+            return Err(EmbeddingError::Generation(format!(
+                "Embedding server returned {} embeddings for {} texts",
+                parsed.data.len(),
+                texts.len()
+            )));
+        }
+
+        let mut data = parsed.data;
+        data.sort_by_key(|d| d.index);
+        Ok(data.into_iter().map(|d| d.embedding).collect())
+    }
+}
+
+impl Embedder for RemoteEmbedder {
+    /// The matching local model if the remote name is a known one,
+    /// otherwise the default model; see [`Embedder::model_name`]
+    fn model(&self) -> EmbeddingModel {
+        self.config.model.parse().unwrap_or_default()
+    }
+
+    fn model_name(&self) -> String {
+        self.config.model.clone()
+    }
+
+    fn dimension(&self) -> usize {
+        self.dimension
+    }
+
+    fn embed_single(&self, text: &str) -> Result<Vec<f32>> {
+        if text.is_empty() {
+            return Err(EmbeddingError::InvalidInput("Empty text provided".into()));
+        }
+
+        self.embed_batch(&[text])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| EmbeddingError::Generation("No embedding returned".into()))
+    }
+
+    fn embed_batch(&self, texts: &[&str]) -> Result<Vec<Vec<f32>>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        for (i, text) in texts.iter().enumerate() {
+            if text.is_empty() {
+                return Err(EmbeddingError::InvalidInput(format!(
+                    "Empty text at index {}",
+                    i
+                )));
+            }
+        }
+
+        debug!("Embedding batch of {} texts remotely", texts.len());
+
+        let mut embeddings = Vec::with_capacity(texts.len());
+        for chunk in texts.chunks(self.config.batch_size) {
+            for embedding in self.request(chunk)? {
+                if embedding.len() != self.dimension {
+                    return Err(EmbeddingError::DimensionMismatch {
+                        expected: self.dimension,
+                        actual: embedding.len(),
+                    });
+                }
+                embeddings.push(embedding);
+            }
+        }
+
+        Ok(embeddings)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::net::TcpListener;
+    use std::thread;
+
+    /// Serve each canned JSON body to one request, returning the base URL
+    /// and a handle yielding the raw requests received
+    fn serve(responses: Vec<(u16, String)>) -> (String, thread::JoinHandle<Vec<String>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+
+        let handle = thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream);
+
+                let mut head = String::new();
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if let Some(value) = line.to_lowercase().strip_prefix("content-length:") {
+                        content_length = value.trim().parse().unwrap();
+                    }
+                    head.push_str(&line);
+                    if line == "\r\n" {
+                        break;
+                    }
+                }
+                let mut payload = vec![0; content_length];
+                reader.read_exact(&mut payload).unwrap();
+                requests.push(head + &String::from_utf8(payload).unwrap());
+
+                let mut stream = reader.into_inner();
+                write!(
+                    stream,
+                    "HTTP/1.1 {} OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+            requests
+        });
+
+        (url, handle)
+    }
+
+    #[test]
+    fn test_endpoint() {
+        let config = RemoteEmbedderConfig::new("http://localhost:11434", "nomic-embed-text");
+        assert_eq!(config.endpoint(), "http://localhost:11434/v1/embeddings");
+
+        let config = RemoteEmbedderConfig::new("https://api.openai.com/v1/", "text-embedding-3-small");
+        assert_eq!(config.endpoint(), "https://api.openai.com/v1/embeddings");
+    }
+
+    #[test]
+    fn test_embed_batch_orders_by_index() {
+        let body = r#"{"object":"list","data":[
+            {"object":"embedding","index":1,"embedding":[0.0,1.0]},
+            {"object":"embedding","index":0,"embedding":[1.0,0.0]}
+        ],"model":"test"}"#;
+        let (url, handle) = serve(vec![(200, body.to_string())]);
+
+        let embedder = RemoteEmbedder::new(
+            RemoteEmbedderConfig::new(url, "test-model")
+                .with_api_key("secret")
+                .with_dimension(2),
+        )
+        .unwrap();
+
+        let embeddings = embedder.embed_batch(&["first", "second"]).unwrap();
+        assert_eq!(embeddings, vec![vec![1.0, 0.0], vec![0.0, 1.0]]);
+        assert_eq!(embedder.model_name(), "test-model");
+
+        let requests = handle.join().unwrap();
+        assert!(requests[0].starts_with("POST /v1/embeddings"));
+        assert!(requests[0].to_lowercase().contains("authorization: bearer secret"));
+        assert!(requests[0].contains(r#""test-model""#));
+        assert!(requests[0].contains(r#""first""#) && requests[0].contains(r#""second""#));
+    }
+
+    #[test]
+    fn test_dimension_probe() {
+        let body = r#"{"data":[{"index":0,"embedding":[0.1,0.2,0.3]}]}"#;
+        let (url, handle) = serve(vec![(200, body.to_string())]);
+
+        let embedder = RemoteEmbedder::new(RemoteEmbedderConfig::new(url, "all-minilm-l6-v2")).unwrap();
+        assert_eq!(embedder.dimension(), 3);
+        assert_eq!(embedder.model(), EmbeddingModel::AllMiniLmL6V2);
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_dimension_mismatch() {
+        let body = r#"{"data":[{"index":0,"embedding":[0.1,0.2,0.3]}]}"#;
+        let (url, handle) = serve(vec![(200, body.to_string())]);
+
+        let embedder =
+            RemoteEmbedder::new(RemoteEmbedderConfig::new(url, "test").with_dimension(2)).unwrap();
+        let result = embedder.embed_single("hello");
+        assert!(matches!(
+            result,
+            Err(EmbeddingError::DimensionMismatch { expected: 2, actual: 3 })
+        ));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_server_error() {
+        let (url, handle) = serve(vec![(401, r#"{"error":"bad key"}"#.to_string())]);
+
+        let embedder =
+            RemoteEmbedder::new(RemoteEmbedderConfig::new(url, "test").with_dimension(2)).unwrap();
+        let err = embedder.embed_single("hello").unwrap_err();
+        assert!(err.to_string().contains("401"));
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_batching_and_empty_input() {
+        let first = r#"{"data":[{"index":0,"embedding":[1.0]},{"index":1,"embedding":[2.0]}]}"#;
+        let second = r#"{"data":[{"index":0,"embedding":[3.0]}]}"#;
+        let (url, handle) = serve(vec![(200, first.to_string()), (200, second.to_string())]);
+
+        let embedder = RemoteEmbedder::new(
+            RemoteEmbedderConfig::new(url, "test")
+                .with_dimension(1)
+                .with_batch_size(2),
+        )
+        .unwrap();
+
+        assert!(embedder.embed_batch(&[]).unwrap().is_empty());
+        assert!(matches!(
+            embedder.embed_batch(&["a", ""]),
+            Err(EmbeddingError::InvalidInput(_))
+        ));
+
+        let embeddings = embedder.embed_batch(&["a", "b", "c"]).unwrap();
+        assert_eq!(embeddings, vec![vec![1.0], vec![2.0], vec![3.0]]);
+        assert_eq!(handle.join().unwrap().len(), 2);
+    }
+}
@@ -0,0 +1,202 @@
+//! Cross-encoder reranking of search results
+
+use crate::error::{EmbeddingError, Result};
+use fastembed::{RerankInitOptions, TextRerank};
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+/// Available cross-encoder reranker models
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RerankerModel {
+    /// bge-reranker-base (English and Chinese)
+    #[default]
+    BgeRerankerBase,
+
+    /// bge-reranker-v2-m3 (multilingual, higher quality)
+    BgeRerankerV2M3,
+
+    /// jina-reranker-v1-turbo-en (English, fast)
+    JinaRerankerV1TurboEn,
+
+    /// jina-reranker-v2-base-multilingual (multilingual)
+    JinaRerankerV2BaseMultilingual,
+}
+
+impl RerankerModel {
+    /// Get the model name
+    pub fn model_name(&self) -> &'static str {
+        match self {
+            Self::BgeRerankerBase => "bge-reranker-base",
+            Self::BgeRerankerV2M3 => "bge-reranker-v2-m3",
+            Self::JinaRerankerV1TurboEn => "jina-reranker-v1-turbo-en",
+            Self::JinaRerankerV2BaseMultilingual => "jina-reranker-v2-base-multilingual",
+        }
+    }
+
+    /// Check if this model supports multiple languages
+    pub fn is_multilingual(&self) -> bool {
+        matches!(self, Self::BgeRerankerV2M3 | Self::JinaRerankerV2BaseMultilingual)
+    }
+
+    fn to_fastembed(self) -> fastembed::RerankerModel {
+        match self {
+            Self::BgeRerankerBase => fastembed::RerankerModel::BGERerankerBase,
+            Self::BgeRerankerV2M3 => fastembed::RerankerModel::BGERerankerV2M3,
+            Self::JinaRerankerV1TurboEn => fastembed::RerankerModel::JINARerankerV1TurboEn,
+            Self::JinaRerankerV2BaseMultilingual => {
+                fastembed::RerankerModel::JINARerankerV2BaseMultiligual
+            }
+        }
+    }
+}
+
+impl std::fmt::Display for RerankerModel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.model_name())
+    }
+}
+
+impl std::str::FromStr for RerankerModel {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bge-reranker-base" | "bge-reranker" | "bge" => Ok(Self::BgeRerankerBase),
+            "bge-reranker-v2-m3" | "bge-m3" => Ok(Self::BgeRerankerV2M3),
+            "jina-reranker-v1-turbo-en" | "jina-turbo" => Ok(Self::JinaRerankerV1TurboEn),
+            "jina-reranker-v2-base-multilingual" | "jina" | "jina-multilingual" => {
+                Ok(Self::JinaRerankerV2BaseMultilingual)
+            }
+            _ => Err(format!("Unknown reranker model: {}", s)),
+        }
+    }
+}
+
+/// Relevance score of one document against a query
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RerankScore {
+    /// Position of the document in the input
+    pub index: usize,
+    /// Cross-encoder relevance score (higher is better, not normalized)
+    pub score: f32,
+}
+
+/// Cross-encoder reranker backed by fastembed
+///
+/// Unlike bi-encoder embeddings, a cross-encoder reads the query and each
+/// document together, which is slower but ranks candidates more
+/// accurately. Use it to re-score the top results of a vector search.
+pub struct Reranker {
+    model: Mutex<TextRerank>,
+    model_type: RerankerModel,
+}
+
+impl Reranker {
+    /// Create a reranker with the specified model
+    pub fn new(model_type: RerankerModel) -> Result<Self> {
+        info!("Initializing Reranker with model: {}", model_type);
+
+        let model = TextRerank::try_new(
+            RerankInitOptions::new(model_type.to_fastembed()).with_show_download_progress(true),
+        )
+        .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?;
+
+        info!("Reranker initialized successfully");
+
+        Ok(Self {
+            model: Mutex::new(model),
+            model_type,
+        })
+    }
+
+    /// Create with the default model (bge-reranker-base)
+    pub fn default_model() -> Result<Self> {
+        Self::new(RerankerModel::default())
+    }
+
+    /// Create with a model specified by name
+    pub fn from_model_name(name: &str) -> Result<Self> {
+        let model_type: RerankerModel = name
+            .parse()
+            .map_err(|e: String| EmbeddingError::ModelNotFound(e))?;
+        Self::new(model_type)
+    }
+
+    /// Get the model being used
+    pub fn model(&self) -> RerankerModel {
+        self.model_type
+    }
+
+    /// Score documents against a query, best first
+    pub fn rerank(&self, query: &str, documents: &[&str]) -> Result<Vec<RerankScore>> {
+        if query.is_empty() {
+            return Err(EmbeddingError::InvalidInput("Empty query provided".into()));
+        }
+        if documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        debug!("Reranking {} documents", documents.len());
+
+        let mut model = self
+            .model
+            .lock()
+            .map_err(|_| EmbeddingError::Generation("Lock poisoned".to_string()))?;
+
+        let results = model
+            .rerank(query, documents, false, None)
+            .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
+
+        Ok(sort_scores(
+            results
+                .into_iter()
+                .map(|r| RerankScore {
+                    index: r.index,
+                    score: r.score,
+                })
+                .collect(),
+        ))
+    }
+}
+
+/// Sort scores best first, keeping input order among ties
+fn sort_scores(mut scores: Vec<RerankScore>) -> Vec<RerankScore> {
+    scores.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then(a.index.cmp(&b.index))
+    });
+    scores
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_model_parsing() {
+        assert_eq!("bge".parse::<RerankerModel>().unwrap(), RerankerModel::BgeRerankerBase);
+        assert_eq!(
+            "jina".parse::<RerankerModel>().unwrap(),
+            RerankerModel::JinaRerankerV2BaseMultilingual
+        );
+        assert!("cohere".parse::<RerankerModel>().is_err());
+        assert_eq!(RerankerModel::BgeRerankerV2M3.to_string(), "bge-reranker-v2-m3");
+        assert!(RerankerModel::BgeRerankerV2M3.is_multilingual());
+    }
+
+    #[test]
+    fn test_sort_scores() {
+        let scores = vec![
+            RerankScore { index: 0, score: -1.5 },
+            RerankScore { index: 1, score: 3.0 },
+            RerankScore { index: 2, score: 0.5 },
+            RerankScore { index: 3, score: 3.0 },
+        ];
+        let indices: Vec<usize> = sort_scores(scores).iter().map(|s| s.index).collect();
+        assert_eq!(indices, vec![1, 3, 2, 0]);
+    }
+}
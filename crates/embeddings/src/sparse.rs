@@ -0,0 +1,245 @@
+//! Sparse (term-weight) embedding generation
+
+use crate::error::{EmbeddingError, Result};
+use fastembed::{SparseInitOptions, SparseModel, SparseTextEmbedding};
+use neuro_core::SparseEmbedding;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tracing::{debug, info};
+
+/// Trait for sparse embedding generation
+///
+/// Sparse embeddings weight individual vocabulary terms, so they capture
+/// exact keyword matches (identifiers, names, rare words) that dense
+/// embeddings tend to blur. They complement rather than replace dense
+/// embeddings.
+pub trait SparseEmbedder: Send + Sync {
+    /// Get the identifier recorded alongside embeddings
+    fn model_name(&self) -> String;
+
+    /// Generate a sparse embedding for a single text
+    fn embed_sparse(&self, text: &str) -> Result<SparseEmbedding>;
+
+    /// Generate sparse embeddings for multiple texts
+    fn embed_sparse_batch(&self, texts: &[&str]) -> Result<Vec<SparseEmbedding>> {
+        texts.iter().map(|t| self.embed_sparse(t)).collect()
+    }
+}
+
+/// SPLADE sparse embedder backed by fastembed (SPLADE++ v1)
+///
+/// Learns term weights with a transformer, including expansion terms that
+/// do not appear in the text itself. English only.
+pub struct SpladeEmbedder {
+    model: Mutex<SparseTextEmbedding>,
+}
+
+impl SpladeEmbedder {
+    /// Create a new SPLADE embedder
+    pub fn new() -> Result<Self> {
+        info!("Initializing SpladeEmbedder");
+
+        let model = SparseTextEmbedding::try_new(
+            SparseInitOptions::new(SparseModel::SPLADEPPV1).with_show_download_progress(true),
+        )
+        .map_err(|e| EmbeddingError::ModelInit(e.to_string()))?;
+
+        info!("SpladeEmbedder initialized successfully");
+
+        Ok(Self {
+            model: Mutex::new(model),
+        })
+    }
+}
+
+impl SparseEmbedder for SpladeEmbedder {
+    fn model_name(&self) -> String {
+        "splade-pp-en-v1".to_string()
+    }
+
+    fn embed_sparse(&self, text: &str) -> Result<SparseEmbedding> {
+        self.embed_sparse_batch(&[text])?
+            .into_iter()
+            .next()
+            .ok_or_else(|| EmbeddingError::Generation("No embedding returned".into()))
+    }
+
+    fn embed_sparse_batch(&self, texts: &[&str]) -> Result<Vec<SparseEmbedding>> {
+        if texts.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        for (i, text) in texts.iter().enumerate() {
+            if text.is_empty() {
+                return Err(EmbeddingError::InvalidInput(format!(
+                    "Empty text at index {}",
+                    i
+                )));
+            }
+        }
+
+        debug!("Sparse-embedding batch of {} texts", texts.len());
+
+        let mut model = self
+            .model
+            .lock()
+            .map_err(|_| EmbeddingError::Generation("Lock poisoned".to_string()))?;
+
+        let embeddings = model
+            .embed(texts, None)
+            .map_err(|e| EmbeddingError::Generation(e.to_string()))?;
+
+        Ok(embeddings
+            .into_iter()
+            .map(|e| {
+                SparseEmbedding::from_pairs(
+                    e.indices.into_iter().map(|i| i as u32).zip(e.values),
+                )
+            })
+            .collect())
+    }
+}
+
+/// BM25-style sparse embedder that needs no model
+///
+/// Terms are lowercased alphanumeric words, hashed into a 32-bit space.
+/// Each term gets BM25's saturated term-frequency weight, normalized by
+/// document length. IDF is not applied because it needs corpus-wide
+/// statistics, so very common words carry weight too.
+#[derive(Debug, Clone)]
+pub struct Bm25Embedder {
+    k1: f32,
+    b: f32,
+    avg_doc_len: f32,
+}
+
+impl Default for Bm25Embedder {
+    fn default() -> Self {
+        Self {
+            k1: 1.2,
+            b: 0.75,
+            avg_doc_len: 256.0,
+        }
+    }
+}
+
+impl Bm25Embedder {
+    /// Create with the standard BM25 parameters (k1 = 1.2, b = 0.75)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the term-frequency saturation parameter
+    pub fn with_k1(mut self, k1: f32) -> Self {
+        self.k1 = k1.max(0.0);
+        self
+    }
+
+    /// Set the length-normalization strength (0 disables it)
+    pub fn with_b(mut self, b: f32) -> Self {
+        self.b = b.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Set the expected document length in words
+    pub fn with_avg_doc_len(mut self, avg_doc_len: f32) -> Self {
+        self.avg_doc_len = avg_doc_len.max(1.0);
+        self
+    }
+
+    /// Stable 32-bit FNV-1a hash of a term
+    ///
+    /// Stored embeddings depend on it, so it must never change.
+    pub fn term_index(term: &str) -> u32 {
+        term.bytes().fold(0x811c_9dc5u32, |hash, byte| {
+            (hash ^ byte as u32).wrapping_mul(0x0100_0193)
+        })
+    }
+}
+
+impl SparseEmbedder for Bm25Embedder {
+    fn model_name(&self) -> String {
+        "bm25".to_string()
+    }
+
+    fn embed_sparse(&self, text: &str) -> Result<SparseEmbedding> {
+        if text.is_empty() {
+            return Err(EmbeddingError::InvalidInput("Empty text provided".into()));
+        }
+
+        let mut term_counts: HashMap<String, u32> = HashMap::new();
+        let mut length = 0usize;
+        for term in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|t| !t.is_empty())
+        {
+            *term_counts.entry(term.to_lowercase()).or_insert(0) += 1;
+            length += 1;
+        }
+
+        let norm = self.k1 * (1.0 - self.b + self.b * length as f32 / self.avg_doc_len);
+        Ok(SparseEmbedding::from_pairs(term_counts.into_iter().map(
+            |(term, count)| {
+                let tf = count as f32;
+                (Self::term_index(&term), tf * (self.k1 + 1.0) / (tf + norm))
+            },
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bm25_term_weights() {
+        let embedder = Bm25Embedder::new();
+        let sparse = embedder.embed_sparse("Rust rust, RUST and cargo").unwrap();
+
+        assert_eq!(sparse.len(), 3);
+        let weight = |term: &str| {
+            sparse
+                .iter()
+                .find(|&(i, _)| i == Bm25Embedder::term_index(term))
+                .map(|(_, w)| w)
+                .unwrap()
+        };
+        assert!(weight("rust") > weight("cargo"));
+        // Saturation: three occurrences weigh less than three times one
+        assert!(weight("rust") < 3.0 * weight("cargo"));
+    }
+
+    #[test]
+    fn test_bm25_keyword_match() {
+        let embedder = Bm25Embedder::new();
+        let query = embedder.embed_sparse("parse_config function").unwrap();
+        let hit = embedder
+            .embed_sparse("The parse_config function reads TOML files")
+            .unwrap();
+        let miss = embedder
+            .embed_sparse("Configuration is loaded at startup")
+            .unwrap();
+
+        assert!(query.dot(&hit) > 0.0);
+        assert_eq!(query.dot(&miss), 0.0);
+    }
+
+    #[test]
+    fn test_bm25_length_normalization() {
+        let embedder = Bm25Embedder::new().with_avg_doc_len(4.0);
+        let short = embedder.embed_sparse("tokio runtime").unwrap();
+        let long = embedder
+            .embed_sparse("tokio runtime with many other unrelated words around it")
+            .unwrap();
+        let query = embedder.embed_sparse("tokio").unwrap();
+
+        assert!(query.dot(&short) > query.dot(&long));
+    }
+
+    #[test]
+    fn test_bm25_stable_hash() {
+        assert_eq!(Bm25Embedder::term_index(""), 0x811c_9dc5);
+        assert_eq!(Bm25Embedder::term_index("a"), 0xe40c_292c);
+        assert!(Bm25Embedder::new().embed_sparse("").is_err());
+    }
+}
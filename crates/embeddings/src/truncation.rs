@@ -0,0 +1,213 @@
+//! Token-aware truncation of embedding inputs
+
+use serde::{Deserialize, Serialize};
+use std::borrow::Cow;
+
+/// Which part of an over-long input to keep
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TruncationStrategy {
+    /// Keep the beginning of the text
+    #[default]
+    Head,
+
+    /// Keep the end of the text
+    Tail,
+
+    /// Keep the beginning and the end, dropping the middle
+    HeadTail,
+}
+
+impl TruncationStrategy {
+    /// Get the strategy name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Head => "head",
+            Self::Tail => "tail",
+            Self::HeadTail => "head_tail",
+        }
+    }
+}
+
+impl std::fmt::Display for TruncationStrategy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for TruncationStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().replace('-', "_").as_str() {
+            "head" => Ok(Self::Head),
+            "tail" => Ok(Self::Tail),
+            "head_tail" | "head+tail" | "middle" => Ok(Self::HeadTail),
+            _ => Err(format!("Unknown truncation strategy: {}", s)),
+        }
+    }
+}
+
+/// Truncate `text` to at most `budget` tokens
+///
+/// `offsets` are the byte spans of the text's tokens, in order and
+/// without special tokens. Cuts are moved to the nearest whitespace so no
+/// word is split, unless one word alone exceeds the budget, in which case
+/// it is cut rather than dropped. [`TruncationStrategy::HeadTail`] joins
+/// the two halves with a newline, which tokenizes to nothing.
+pub fn truncate_to_tokens<'a>(
+    text: &'a str,
+    offsets: &[(usize, usize)],
+    budget: usize,
+    strategy: TruncationStrategy,
+) -> Cow<'a, str> {
+    if offsets.len() <= budget {
+        return Cow::Borrowed(text);
+    }
+
+    match strategy {
+        TruncationStrategy::Head => Cow::Borrowed(head(text, offsets, budget)),
+        TruncationStrategy::Tail => Cow::Borrowed(tail(text, offsets, budget)),
+        TruncationStrategy::HeadTail => {
+            let head_budget = budget.div_ceil(2);
+            let tail_budget = budget - head_budget;
+            let start = head(text, offsets, head_budget);
+            let end = tail(text, offsets, tail_budget);
+            if end.is_empty() {
+                Cow::Borrowed(start)
+            } else {
+                Cow::Owned(format!("{}\n{}", start.trim_end(), end.trim_start()))
+            }
+        }
+    }
+}
+
+/// Keep the first `budget` tokens, ending on a word boundary
+fn head<'a>(text: &'a str, offsets: &[(usize, usize)], budget: usize) -> &'a str {
+    if budget == 0 {
+        return "";
+    }
+
+    let end = offsets[budget - 1].1;
+    let splits_word = offsets
+        .get(budget)
+        .is_some_and(|next| next.0 == end);
+
+    let end = if splits_word {
+        text[..end]
+            .rfind(char::is_whitespace)
+            .filter(|&i| i > offsets[0].0)
+            .unwrap_or(end)
+    } else {
+        end
+    };
+
+    text[..end].trim_end()
+}
+
+/// Keep the last `budget` tokens, starting on a word boundary
+fn tail<'a>(text: &'a str, offsets: &[(usize, usize)], budget: usize) -> &'a str {
+    if budget == 0 {
+        return "";
+    }
+
+    let first = offsets.len() - budget;
+    let start = offsets[first].0;
+    let splits_word = first > 0 && offsets[first - 1].1 == start;
+
+    let start = if splits_word {
+        let last_end = offsets[offsets.len() - 1].1;
+        text[start..]
+            .find(char::is_whitespace)
+            .map(|i| start + i)
+            .filter(|&i| i < last_end)
+            .unwrap_or(start)
+    } else {
+        start
+    };
+
+    text[start..].trim_start()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Byte spans of a naive tokenizer: whitespace-separated words, with
+    /// words longer than four bytes split into four-byte pieces
+    fn offsets(text: &str) -> Vec<(usize, usize)> {
+        let mut spans = Vec::new();
+        let mut pos = 0;
+        for word in text.split_whitespace() {
+            let start = pos + text[pos..].find(word).unwrap();
+            let mut piece = start;
+            while piece < start + word.len() {
+                let end = (piece + 4).min(start + word.len());
+                spans.push((piece, end));
+                piece = end;
+            }
+            pos = start + word.len();
+        }
+        spans
+    }
+
+    #[test]
+    fn test_short_text_untouched() {
+        let text = "one two three";
+        let result = truncate_to_tokens(text, &offsets(text), 10, TruncationStrategy::Head);
+        assert!(matches!(result, Cow::Borrowed(t) if t == text));
+    }
+
+    #[test]
+    fn test_head() {
+        let text = "one two three four";
+        let result = truncate_to_tokens(text, &offsets(text), 2, TruncationStrategy::Head);
+        assert_eq!(result, "one two");
+    }
+
+    #[test]
+    fn test_head_does_not_split_words() {
+        // "truncated" is three tokens: "trun", "cate", "d"
+        let text = "text gets truncated here";
+        let result = truncate_to_tokens(text, &offsets(text), 3, TruncationStrategy::Head);
+        assert_eq!(result, "text gets");
+    }
+
+    #[test]
+    fn test_tail_does_not_split_words() {
+        let text = "start truncated end";
+        let result = truncate_to_tokens(text, &offsets(text), 2, TruncationStrategy::Tail);
+        assert_eq!(result, "end");
+
+        let result = truncate_to_tokens(text, &offsets(text), 1, TruncationStrategy::Tail);
+        assert_eq!(result, "end");
+    }
+
+    #[test]
+    fn test_head_tail() {
+        let text = "alpha beta gamma delta epsilon zeta";
+        let text_offsets = offsets(text);
+        // Words longer than four letters are two tokens each
+        let result = truncate_to_tokens(text, &text_offsets, 6, TruncationStrategy::HeadTail);
+        assert_eq!(result, "alpha beta\nepsilon zeta");
+    }
+
+    #[test]
+    fn test_long_single_word_cut() {
+        let text = "supercalifragilistic word";
+        let result = truncate_to_tokens(text, &offsets(text), 2, TruncationStrategy::Head);
+        assert_eq!(result, "supercal");
+    }
+
+    #[test]
+    fn test_parse_strategy() {
+        assert_eq!("head".parse::<TruncationStrategy>().unwrap(), TruncationStrategy::Head);
+        assert_eq!("TAIL".parse::<TruncationStrategy>().unwrap(), TruncationStrategy::Tail);
+        assert_eq!(
+            "head-tail".parse::<TruncationStrategy>().unwrap(),
+            TruncationStrategy::HeadTail
+        );
+        assert!("middle-out".parse::<TruncationStrategy>().is_err());
+        assert_eq!(TruncationStrategy::HeadTail.to_string(), "head_tail");
+    }
+}
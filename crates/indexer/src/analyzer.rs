@@ -30,6 +30,20 @@ impl TreeSitterAnalyzer {
             Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
             Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
             Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::C => tree_sitter_c::LANGUAGE.into(),
+            Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+            Language::Php => tree_sitter_php::LANGUAGE_PHP.into(),
+            Language::Markdown
+            | Language::Notebook
+            | Language::Text
+            | Language::Pdf
+            | Language::Docx => {
+                return Err(IndexerError::UnsupportedLanguage(format!(
+                    "{} has no tree-sitter analyzer",
+                    language
+                )))
+            }
         };
 
         parser
@@ -97,6 +111,12 @@ impl TreeSitterAnalyzer {
                 chunk = chunk.with_signature(sig);
             }
 
+            if symbol_type == SymbolType::Function {
+                let mut calls = Vec::new();
+                self.collect_calls(node, source, &mut calls);
+                chunk = chunk.with_calls(calls);
+            }
+
             // For classes/structs, visit children with this as parent
             let new_parent = if matches!(symbol_type, SymbolType::Class | SymbolType::Struct | SymbolType::Impl) {
                 Some(name.as_str())
@@ -127,6 +147,14 @@ impl TreeSitterAnalyzer {
             Language::Python => self.classify_python_node(node, kind, source),
             Language::JavaScript | Language::TypeScript => self.classify_js_node(node, kind, source),
             Language::Rust => self.classify_rust_node(node, kind, source),
+            Language::C | Language::Cpp => self.classify_c_node(node, kind, source),
+            Language::Ruby => self.classify_ruby_node(node, kind, source),
+            Language::Php => self.classify_php_node(node, kind, source),
+            Language::Markdown
+            | Language::Notebook
+            | Language::Text
+            | Language::Pdf
+            | Language::Docx => None,
         }
     }
 
@@ -202,6 +230,145 @@ impl TreeSitterAnalyzer {
         }
     }
 
+    fn classify_c_node(&self, node: &tree_sitter::Node, kind: &str, source: &str) -> Option<(SymbolType, String)> {
+        // Templates are chunked whole, so skip the declaration they wrap
+        if node.parent().is_some_and(|p| p.kind() == "template_declaration") {
+            return None;
+        }
+
+        match kind {
+            "function_definition" => {
+                let declarator = node.child_by_field_name("declarator")?;
+                let name = self.declarator_name(declarator, source)?;
+                Some((SymbolType::Function, name))
+            }
+            // Only definitions with a body; `struct foo *p` is just a use
+            "struct_specifier" | "union_specifier" | "class_specifier" | "enum_specifier" => {
+                node.child_by_field_name("body")?;
+                let name = self.get_child_by_field(node, "name", source)?;
+                let symbol_type = match kind {
+                    "class_specifier" => SymbolType::Class,
+                    "enum_specifier" => SymbolType::Enum,
+                    _ => SymbolType::Struct,
+                };
+                Some((symbol_type, name))
+            }
+            "namespace_definition" => {
+                let name = self
+                    .get_child_by_field(node, "name", source)
+                    .unwrap_or_else(|| "(anonymous)".to_string());
+                Some((SymbolType::Module, name))
+            }
+            "template_declaration" => {
+                let mut cursor = node.walk();
+                let inner = node
+                    .named_children(&mut cursor)
+                    .filter(|child| child.kind() != "template_parameter_list")
+                    .last()?;
+                // Forward declarations (`template <class T> T f();`) aren't chunked
+                let (symbol_type, name) = match inner.kind() {
+                    "function_definition" => {
+                        let declarator = inner.child_by_field_name("declarator")?;
+                        (SymbolType::Function, self.declarator_name(declarator, source)?)
+                    }
+                    "class_specifier" | "struct_specifier" | "union_specifier" => {
+                        inner.child_by_field_name("body")?;
+                        let symbol_type = if inner.kind() == "class_specifier" {
+                            SymbolType::Class
+                        } else {
+                            SymbolType::Struct
+                        };
+                        (symbol_type, self.get_child_by_field(&inner, "name", source)?)
+                    }
+                    "alias_declaration" => {
+                        (SymbolType::TypeAlias, self.get_child_by_field(&inner, "name", source)?)
+                    }
+                    _ => return None,
+                };
+                Some((symbol_type, name))
+            }
+            "type_definition" => {
+                let declarator = node.child_by_field_name("declarator")?;
+                let name = self.declarator_name(declarator, source)?;
+                Some((SymbolType::TypeAlias, name))
+            }
+            "alias_declaration" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::TypeAlias, name))
+            }
+            "preproc_def" | "preproc_function_def" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Macro, name))
+            }
+            _ => None,
+        }
+    }
+
+    fn classify_ruby_node(&self, node: &tree_sitter::Node, kind: &str, source: &str) -> Option<(SymbolType, String)> {
+        match kind {
+            // `def self.name` is a singleton_method; both are plain functions here
+            "method" | "singleton_method" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Function, name))
+            }
+            "class" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Class, name))
+            }
+            "module" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Module, name))
+            }
+            _ => None,
+        }
+    }
+
+    fn classify_php_node(&self, node: &tree_sitter::Node, kind: &str, source: &str) -> Option<(SymbolType, String)> {
+        match kind {
+            "function_definition" | "method_declaration" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Function, name))
+            }
+            "class_declaration" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Class, name))
+            }
+            "trait_declaration" | "interface_declaration" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Trait, name))
+            }
+            "enum_declaration" => {
+                let name = self.get_child_by_field(node, "name", source)?;
+                Some((SymbolType::Enum, name))
+            }
+            _ => None,
+        }
+    }
+
+    /// Name declared by a C/C++ declarator, looking through pointer,
+    /// reference and function declarators (`*(&foo)(int)` names `foo`)
+    fn declarator_name(&self, mut node: tree_sitter::Node, source: &str) -> Option<String> {
+        loop {
+            match node.kind() {
+                "function_declarator" | "pointer_declarator" | "array_declarator"
+                | "parenthesized_declarator" | "attributed_declarator" => {
+                    node = match node.child_by_field_name("declarator") {
+                        Some(inner) => inner,
+                        None => node.named_child(0)?,
+                    };
+                }
+                // The reference declarator's target isn't a named field
+                "reference_declarator" => {
+                    let count = node.named_child_count();
+                    node = node.named_child(count.checked_sub(1)?)?;
+                }
+                _ => break,
+            }
+        }
+
+        node.utf8_text(source.as_bytes()).ok().map(|s| s.to_string())
+    }
+
     fn get_child_by_field(&self, node: &tree_sitter::Node, field: &str, source: &str) -> Option<String> {
         node.child_by_field_name(field)?
             .utf8_text(source.as_bytes())
@@ -209,24 +376,166 @@ impl TreeSitterAnalyzer {
             .map(|s| s.to_string())
     }
 
+    /// Documentation for `node`, with comment markers and docstring quotes
+    /// stripped
+    ///
+    /// A Python docstring wins over comments; otherwise it's the run of
+    /// comments directly above the definition (or its decorators,
+    /// attributes or `export`), ending at the first blank line.
     fn extract_documentation(&self, node: &tree_sitter::Node, source: &str) -> Option<String> {
-        // Look for preceding comment/docstring
-        let prev = node.prev_sibling()?;
-        let kind = prev.kind();
-
-        let is_doc = match self.language {
-            Language::Python => kind == "expression_statement" || kind == "comment",
-            Language::JavaScript | Language::TypeScript => kind == "comment",
-            Language::Rust => kind == "line_comment" || kind == "block_comment",
-        };
+        if self.language == Language::Python {
+            if let Some(doc) = python_docstring(node, source) {
+                return Some(doc);
+            }
+        }
 
-        if is_doc {
-            prev.utf8_text(source.as_bytes()).ok().map(|s| s.to_string())
-        } else {
-            None
+        // Comments go before whatever wraps the definition
+        let mut current = *node;
+        while let Some(parent) = current
+            .parent()
+            .filter(|p| matches!(p.kind(), "decorated_definition" | "export_statement"))
+        {
+            current = parent;
+        }
+
+        let mut comments = Vec::new();
+        let mut top_row = current.start_position().row;
+        loop {
+            let prev = match current.prev_sibling() {
+                Some(prev) => prev,
+                // Ruby puts a comment before a body's first statement outside
+                // the body, next to the class/module name
+                None if comments.is_empty()
+                    && current.parent().is_some_and(|p| p.kind() == "body_statement") =>
+                {
+                    match current.parent().and_then(|p| p.prev_sibling()) {
+                        Some(prev) => prev,
+                        None => break,
+                    }
+                }
+                None => break,
+            };
+
+            // A blank line separates unrelated comments from the definition
+            if last_row(&prev) + 1 < top_row {
+                break;
+            }
+            if comments.is_empty() && matches!(prev.kind(), "attribute_item" | "decorator") {
+                top_row = prev.start_position().row;
+                current = prev;
+                continue;
+            }
+
+            let text = prev.utf8_text(source.as_bytes()).ok()?;
+            if !self.is_doc_comment(prev.kind(), text) {
+                break;
+            }
+            comments.push(text);
+            top_row = prev.start_position().row;
+            current = prev;
+        }
+
+        let doc = comments
+            .iter()
+            .rev()
+            .map(|comment| strip_comment_markers(comment))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let doc = doc.trim_matches('\n');
+        (!doc.trim().is_empty()).then(|| doc.to_string())
+    }
+
+    /// Whether a comment node of `kind` documents the code after it
+    fn is_doc_comment(&self, kind: &str, text: &str) -> bool {
+        match self.language {
+            // Only `///` and `/** */`; `//` is a plain comment and `//!`
+            // documents the enclosing item
+            Language::Rust => {
+                matches!(kind, "line_comment" | "block_comment")
+                    && ((text.starts_with("///") && !text.starts_with("////"))
+                        || (text.starts_with("/**") && !text.starts_with("/**/")))
+            }
+            Language::Markdown
+            | Language::Notebook
+            | Language::Text
+            | Language::Pdf
+            | Language::Docx => false,
+            _ => kind == "comment",
         }
     }
 
+    /// Names of the functions called in `node`'s body, in order of first
+    /// call; nested functions are left to record their own calls
+    fn collect_calls(&self, node: tree_sitter::Node, source: &str, calls: &mut Vec<String>) {
+        let mut cursor = node.walk();
+        for child in node.children(&mut cursor) {
+            if matches!(self.classify_node(&child, source), Some((SymbolType::Function, _))) {
+                continue;
+            }
+            if let Some(name) = self
+                .call_target(&child)
+                .and_then(|target| self.callee_name(target, source))
+            {
+                if !calls.contains(&name) {
+                    calls.push(name);
+                }
+            }
+            self.collect_calls(child, source, calls);
+        }
+    }
+
+    /// The expression being called, if `node` is a call
+    fn call_target<'a>(&self, node: &tree_sitter::Node<'a>) -> Option<tree_sitter::Node<'a>> {
+        match (self.language, node.kind()) {
+            (Language::Python, "call") => node.child_by_field_name("function"),
+            (Language::JavaScript | Language::TypeScript, "call_expression") => {
+                node.child_by_field_name("function")
+            }
+            (Language::JavaScript | Language::TypeScript, "new_expression") => {
+                node.child_by_field_name("constructor")
+            }
+            (Language::Rust | Language::C | Language::Cpp, "call_expression") => {
+                node.child_by_field_name("function")
+            }
+            (Language::Ruby, "call") => node.child_by_field_name("method"),
+            (Language::Php, "function_call_expression") => node.child_by_field_name("function"),
+            (
+                Language::Php,
+                "member_call_expression"
+                | "nullsafe_member_call_expression"
+                | "scoped_call_expression",
+            ) => node.child_by_field_name("name"),
+            _ => None,
+        }
+    }
+
+    /// Bare name of a called expression: `greet` for `self.greet`,
+    /// `Vec::new`, `obj->greet` or `greet::<T>`
+    ///
+    /// `None` for calls through anything but a (qualified) name, such as
+    /// a closure returned by another call.
+    fn callee_name(&self, mut node: tree_sitter::Node, source: &str) -> Option<String> {
+        while let Some(inner) = ["name", "field", "property", "attribute", "function"]
+            .iter()
+            .find_map(|field| node.child_by_field_name(field))
+        {
+            node = inner;
+        }
+
+        let text = node.utf8_text(source.as_bytes()).ok()?;
+        let is_ident = |c: char| c.is_alphanumeric() || c == '_' || c == '$';
+        if !text
+            .chars()
+            .all(|c| is_ident(c) || matches!(c, ':' | '.' | '\\'))
+        {
+            return None;
+        }
+        text.rsplit(|c: char| !is_ident(c))
+            .next()
+            .filter(|name| !name.is_empty())
+            .map(|name| name.to_string())
+    }
+
     fn extract_signature(&self, node: &tree_sitter::Node, source: &str) -> Option<String> {
         // Get first line of the node as signature
         let text = node.utf8_text(source.as_bytes()).ok()?;
@@ -235,6 +544,86 @@ impl TreeSitterAnalyzer {
     }
 }
 
+/// Last row `node` has text on; line comments can end at column 0 of
+/// the next row, after their newline
+fn last_row(node: &tree_sitter::Node) -> usize {
+    let (start, end) = (node.start_position(), node.end_position());
+    if end.column == 0 && end.row > start.row {
+        end.row - 1
+    } else {
+        end.row
+    }
+}
+
+/// Docstring of a Python function or class: a string as the first
+/// statement of its body, dedented and without its quotes
+fn python_docstring(node: &tree_sitter::Node, source: &str) -> Option<String> {
+    let body = node.child_by_field_name("body")?;
+    let statement = body.named_child(0)?;
+    let string = statement.named_child(0)?;
+    if statement.kind() != "expression_statement" || string.kind() != "string" {
+        return None;
+    }
+
+    let text = string.utf8_text(source.as_bytes()).ok()?;
+    let text = text.trim_start_matches(|c: char| c.is_ascii_alphabetic());
+    let quote = ["\"\"\"", "'''", "\"", "'"]
+        .into_iter()
+        .find(|q| text.starts_with(q))?;
+    let inner = text.strip_prefix(quote)?.strip_suffix(quote)?;
+
+    // Like `inspect.cleandoc`: the first line is trimmed, the rest lose
+    // their common indentation
+    let mut lines = inner.lines();
+    let first = lines.next().unwrap_or("").trim();
+    let rest: Vec<&str> = lines.collect();
+    let indent = rest
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let doc = std::iter::once(first)
+        .chain(rest.iter().map(|line| line.get(indent..).unwrap_or("").trim_end()))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    let doc = doc.trim_matches('\n');
+    (!doc.trim().is_empty()).then(|| doc.to_string())
+}
+
+/// Comment text without `//`, `#`, `/* */` or leading `*` markers
+fn strip_comment_markers(comment: &str) -> String {
+    let comment = comment.trim();
+    let block = comment.starts_with("/*");
+    let comment = if block {
+        comment
+            .trim_start_matches('/')
+            .trim_start_matches('*')
+            .trim_start_matches('!')
+            .strip_suffix("*/")
+            .unwrap_or(comment)
+    } else {
+        comment
+    };
+
+    comment
+        .lines()
+        .map(|line| {
+            let line = line.trim();
+            let line = if block {
+                line.strip_prefix('*').unwrap_or(line)
+            } else {
+                line.trim_start_matches('/')
+                    .trim_start_matches('!')
+                    .trim_start_matches('#')
+            };
+            line.strip_prefix(' ').unwrap_or(line).trim_end()
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
 impl CodeAnalyzer for TreeSitterAnalyzer {
     fn language(&self) -> Language {
         self.language
@@ -249,6 +638,20 @@ impl CodeAnalyzer for TreeSitterAnalyzer {
             Language::JavaScript => tree_sitter_javascript::LANGUAGE.into(),
             Language::TypeScript => tree_sitter_typescript::LANGUAGE_TYPESCRIPT.into(),
             Language::Rust => tree_sitter_rust::LANGUAGE.into(),
+            Language::C => tree_sitter_c::LANGUAGE.into(),
+            Language::Cpp => tree_sitter_cpp::LANGUAGE.into(),
+            Language::Ruby => tree_sitter_ruby::LANGUAGE.into(),
+            Language::Php => tree_sitter_php::LANGUAGE_PHP.into(),
+            Language::Markdown
+            | Language::Notebook
+            | Language::Text
+            | Language::Pdf
+            | Language::Docx => {
+                return Err(IndexerError::UnsupportedLanguage(format!(
+                    "{} has no tree-sitter analyzer",
+                    self.language
+                )))
+            }
         };
 
         parser
@@ -342,4 +745,319 @@ class Person {
         let chunks = analyzer.analyze(source, "app.js").unwrap();
         assert!(!chunks.is_empty());
     }
+
+    #[test]
+    fn test_c_analyzer() {
+        let analyzer = TreeSitterAnalyzer::new(Language::C).unwrap();
+        let source = r#"
+#define MAX_TOKENS 4096
+#define MIN(a, b) ((a) < (b) ? (a) : (b))
+
+typedef struct {
+    int rows;
+    int cols;
+} matrix_t;
+
+struct tensor {
+    float *data;
+    size_t len;
+};
+
+/* Quantize weights to ternary values */
+static int8_t *quantize(const float *weights, size_t n) {
+    struct tensor *t = NULL;
+    return NULL;
+}
+"#;
+
+        let chunks = analyzer.analyze(source, "ggml.c").unwrap();
+        let find = |name: &str| chunks.iter().find(|c| c.name == name);
+
+        assert_eq!(find("MAX_TOKENS").unwrap().symbol_type, SymbolType::Macro);
+        assert_eq!(find("MIN").unwrap().symbol_type, SymbolType::Macro);
+        assert_eq!(find("matrix_t").unwrap().symbol_type, SymbolType::TypeAlias);
+        assert_eq!(find("tensor").unwrap().symbol_type, SymbolType::Struct);
+
+        let quantize = find("quantize").unwrap();
+        assert_eq!(quantize.symbol_type, SymbolType::Function);
+        assert!(quantize.documentation.as_deref().unwrap().contains("ternary"));
+
+        // The `struct tensor *t` use inside the function isn't a definition
+        assert_eq!(chunks.iter().filter(|c| c.name == "tensor").count(), 1);
+    }
+
+    #[test]
+    fn test_cpp_analyzer() {
+        let analyzer = TreeSitterAnalyzer::new(Language::Cpp).unwrap();
+        let source = r#"
+namespace bitnet {
+
+class Model {
+public:
+    int layers() const { return n_layers; }
+    ~Model();
+private:
+    int n_layers;
+};
+
+template <typename T>
+T clamp(T value, T lo, T hi) {
+    return value < lo ? lo : (value > hi ? hi : value);
+}
+
+template <typename T>
+struct Buffer {
+    T *data;
+};
+
+using Logits = std::vector<float>;
+
+Model::~Model() {}
+
+}
+"#;
+
+        let chunks = analyzer.analyze(source, "model.cpp").unwrap();
+        let find = |name: &str| chunks.iter().find(|c| c.name == name);
+
+        assert_eq!(find("bitnet").unwrap().symbol_type, SymbolType::Module);
+        assert_eq!(find("Model").unwrap().symbol_type, SymbolType::Class);
+
+        let layers = find("layers").unwrap();
+        assert_eq!(layers.symbol_type, SymbolType::Function);
+        assert_eq!(layers.parent.as_deref(), Some("Model"));
+
+        let clamp = find("clamp").unwrap();
+        assert_eq!(clamp.symbol_type, SymbolType::Function);
+        assert!(clamp.content.starts_with("template <typename T>"));
+        assert_eq!(chunks.iter().filter(|c| c.name == "clamp").count(), 1);
+
+        assert_eq!(find("Buffer").unwrap().symbol_type, SymbolType::Struct);
+        assert_eq!(chunks.iter().filter(|c| c.name == "Buffer").count(), 1);
+        assert_eq!(find("Logits").unwrap().symbol_type, SymbolType::TypeAlias);
+        assert_eq!(find("Model::~Model").unwrap().symbol_type, SymbolType::Function);
+    }
+
+    #[test]
+    fn test_ruby_analyzer() {
+        let analyzer = TreeSitterAnalyzer::new(Language::Ruby).unwrap();
+        let source = r#"
+module Billing
+  # Charges a customer's card
+  class Invoice < ApplicationRecord
+    def total
+      items.sum(&:price)
+    end
+
+    def self.overdue
+      where("due_at < ?", Time.now)
+    end
+  end
+end
+"#;
+
+        let chunks = analyzer.analyze(source, "invoice.rb").unwrap();
+        let find = |name: &str| chunks.iter().find(|c| c.name == name);
+
+        assert_eq!(find("Billing").unwrap().symbol_type, SymbolType::Module);
+
+        let invoice = find("Invoice").unwrap();
+        assert_eq!(invoice.symbol_type, SymbolType::Class);
+        assert!(invoice.documentation.as_deref().unwrap().contains("Charges"));
+
+        for method in ["total", "overdue"] {
+            let chunk = find(method).unwrap();
+            assert_eq!(chunk.symbol_type, SymbolType::Function);
+            assert_eq!(chunk.parent.as_deref(), Some("Invoice"));
+        }
+    }
+
+    #[test]
+    fn test_php_analyzer() {
+        let analyzer = TreeSitterAnalyzer::new(Language::Php).unwrap();
+        let source = r#"<?php
+namespace App\Models;
+
+trait HasSlug
+{
+    public function slug(): string
+    {
+        return strtolower($this->title);
+    }
+}
+
+/** A blog post */
+class Post extends Model
+{
+    use HasSlug;
+
+    public function publish(): void
+    {
+        $this->published = true;
+    }
+}
+
+function render_post(Post $post): string
+{
+    return $post->title;
+}
+"#;
+
+        let chunks = analyzer.analyze(source, "Post.php").unwrap();
+        let find = |name: &str| chunks.iter().find(|c| c.name == name);
+
+        assert_eq!(find("HasSlug").unwrap().symbol_type, SymbolType::Trait);
+
+        let post = find("Post").unwrap();
+        assert_eq!(post.symbol_type, SymbolType::Class);
+        assert!(post.documentation.as_deref().unwrap().contains("blog post"));
+
+        assert_eq!(find("publish").unwrap().parent.as_deref(), Some("Post"));
+        assert_eq!(find("render_post").unwrap().symbol_type, SymbolType::Function);
+        assert!(find("slug").is_some());
+    }
+
+    fn calls_in(language: Language, source: &str, function: &str) -> Vec<String> {
+        let chunks = TreeSitterAnalyzer::new(language)
+            .unwrap()
+            .analyze(source, "calls")
+            .unwrap();
+        chunks
+            .into_iter()
+            .find(|c| c.name == function)
+            .unwrap()
+            .calls
+    }
+
+    #[test]
+    fn test_call_extraction() {
+        let python = r#"
+def handle(request):
+    user = load_user(request.id)
+    self.audit(user)
+    def inner():
+        return hidden()
+    return render(load_user(user))
+"#;
+        assert_eq!(
+            calls_in(Language::Python, python, "handle"),
+            ["load_user", "audit", "render"]
+        );
+
+        let rust = r#"
+fn run(config: &Config) {
+    let items = Vec::new();
+    let parsed = parse::<u8>(config.raw());
+    println!("{}", parsed);
+    (make_closure())(items);
 }
+"#;
+        assert_eq!(
+            calls_in(Language::Rust, rust, "run"),
+            ["new", "parse", "raw", "make_closure"]
+        );
+
+        let javascript = "function start() { const app = new App(); app.listen(port()); }";
+        assert_eq!(
+            calls_in(Language::JavaScript, javascript, "start"),
+            ["App", "listen", "port"]
+        );
+
+        let cpp = "int main() { std::sort(v.begin(), v.end()); return run(); }";
+        assert_eq!(
+            calls_in(Language::Cpp, cpp, "main"),
+            ["sort", "begin", "end", "run"]
+        );
+
+        let ruby = "def save\n  validate!\n  store.write(to_h)\nend\n";
+        assert_eq!(calls_in(Language::Ruby, ruby, "save"), ["write"]);
+
+        let php = "<?php\nfunction show($id) { $post = Post::find($id); return view($post->render()); }\n";
+        assert_eq!(
+            calls_in(Language::Php, php, "show"),
+            ["find", "view", "render"]
+        );
+    }
+    #[test]
+    fn test_documentation_extraction() {
+        let doc_of = |language: Language, source: &str, name: &str| {
+            TreeSitterAnalyzer::new(language)
+                .unwrap()
+                .analyze(source, "docs")
+                .unwrap()
+                .into_iter()
+                .find(|c| c.name == name)
+                .unwrap()
+                .documentation
+        };
+
+        let rust = r#"
+// License header
+
+// Not documentation
+/// Runs the model.
+///
+/// Returns the logits.
+#[inline]
+#[must_use]
+fn run() {}
+
+/**
+ * Block docs
+ * over two lines
+ */
+fn block() {}
+
+//! Crate docs
+fn inner() {}
+"#;
+        assert_eq!(
+            doc_of(Language::Rust, rust, "run").as_deref(),
+            Some("Runs the model.\n\nReturns the logits.")
+        );
+        assert_eq!(
+            doc_of(Language::Rust, rust, "block").as_deref(),
+            Some("Block docs\nover two lines")
+        );
+        assert_eq!(doc_of(Language::Rust, rust, "inner"), None);
+
+        let python = r#"
+# Helpers
+
+# Loads a config file.
+@cache
+def load(path):
+    pass
+
+class Config:
+    r"""Parsed configuration.
+
+    Keys are case-insensitive.
+    """
+
+    # Comments above a function with a docstring lose to it
+    def get(self, key):
+        '''Look up a key.'''
+        return self.values[key]
+"#;
+        assert_eq!(
+            doc_of(Language::Python, python, "load").as_deref(),
+            Some("Loads a config file.")
+        );
+        assert_eq!(
+            doc_of(Language::Python, python, "Config").as_deref(),
+            Some("Parsed configuration.\n\nKeys are case-insensitive.")
+        );
+        assert_eq!(
+            doc_of(Language::Python, python, "get").as_deref(),
+            Some("Look up a key.")
+        );
+
+        let javascript =
+            "// Starts the server\n// on the given port\nexport function listen(port) {}\n";
+        assert_eq!(
+            doc_of(Language::JavaScript, javascript, "listen").as_deref(),
+            Some("Starts the server\non the given port")
+        );
+    }
+}
\ No newline at end of file
@@ -26,6 +26,11 @@ pub enum SymbolType {
     TypeAlias,
     /// Import statement
     Import,
+    /// Preprocessor macro (C/C++)
+    Macro,
+    /// Document section under a heading (Markdown), or a run of
+    /// paragraphs (plain text, PDF, Word)
+    Section,
     /// Other code block
     Other,
 }
@@ -43,11 +48,36 @@ impl std::fmt::Display for SymbolType {
             Self::Constant => write!(f, "constant"),
             Self::TypeAlias => write!(f, "type_alias"),
             Self::Import => write!(f, "import"),
+            Self::Macro => write!(f, "macro"),
+            Self::Section => write!(f, "section"),
             Self::Other => write!(f, "other"),
         }
     }
 }
 
+impl std::str::FromStr for SymbolType {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "function" | "fn" | "func" | "def" | "method" => Ok(Self::Function),
+            "class" => Ok(Self::Class),
+            "struct" => Ok(Self::Struct),
+            "enum" => Ok(Self::Enum),
+            "trait" | "interface" => Ok(Self::Trait),
+            "impl" => Ok(Self::Impl),
+            "module" | "mod" | "namespace" => Ok(Self::Module),
+            "constant" | "const" | "static" => Ok(Self::Constant),
+            "type_alias" | "type" | "typedef" => Ok(Self::TypeAlias),
+            "import" | "use" => Ok(Self::Import),
+            "macro" => Ok(Self::Macro),
+            "section" => Ok(Self::Section),
+            "other" => Ok(Self::Other),
+            _ => Err(format!("Unknown symbol type: {}", s)),
+        }
+    }
+}
+
 /// A chunk of code extracted from a source file
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CodeChunk {
@@ -77,6 +107,29 @@ pub struct CodeChunk {
 
     /// Function/method signature (if applicable)
     pub signature: Option<String>,
+
+    /// Notebook cell index (0-based) the chunk came from
+    pub cell: Option<usize>,
+
+    /// Page number (1-based) in a PDF or Word document
+    #[serde(default)]
+    pub page: Option<usize>,
+
+    /// Git commit the source was indexed at
+    pub commit: Option<String>,
+
+    /// Names of the functions this chunk calls
+    #[serde(default)]
+    pub calls: Vec<String>,
+
+    /// Display names of the indexed functions that call this chunk
+    #[serde(default)]
+    pub called_by: Vec<String>,
+
+    /// Part number and part count (1-based) for a window of an oversized
+    /// symbol
+    #[serde(default)]
+    pub part: Option<(usize, usize)>,
 }
 
 impl CodeChunk {
@@ -99,6 +152,12 @@ impl CodeChunk {
             parent: None,
             documentation: None,
             signature: None,
+            cell: None,
+            page: None,
+            commit: None,
+            calls: Vec::new(),
+            called_by: Vec::new(),
+            part: None,
         }
     }
 
@@ -120,6 +179,30 @@ impl CodeChunk {
         self
     }
 
+    /// Set the notebook cell index
+    pub fn with_cell(mut self, cell: usize) -> Self {
+        self.cell = Some(cell);
+        self
+    }
+
+    /// Set the document page number
+    pub fn with_page(mut self, page: usize) -> Self {
+        self.page = Some(page);
+        self
+    }
+
+    /// Set the git commit the source was indexed at
+    pub fn with_commit(mut self, commit: impl Into<String>) -> Self {
+        self.commit = Some(commit.into());
+        self
+    }
+
+    /// Set the names of the functions this chunk calls
+    pub fn with_calls(mut self, calls: Vec<String>) -> Self {
+        self.calls = calls;
+        self
+    }
+
     /// Get the number of lines
     pub fn line_count(&self) -> usize {
         self.end_line.saturating_sub(self.start_line) + 1
@@ -128,6 +211,9 @@ impl CodeChunk {
     /// Get a display string for this chunk
     pub fn display_name(&self) -> String {
         match &self.parent {
+            Some(parent) if self.symbol_type == SymbolType::Section => {
+                format!("{}{}{}", parent, crate::markdown::HEADING_PATH_SEPARATOR, self.name)
+            }
             Some(parent) => format!("{}::{}", parent, self.name),
             None => self.name.clone(),
         }
@@ -139,10 +225,24 @@ impl CodeChunk {
 
         // Add metadata header
         content.push_str(&format!(
-            "# {} `{}`\n",
+            "# {} `{}`",
             self.symbol_type, self.display_name()
         ));
-        content.push_str(&format!("File: {}:{}-{}\n", self.file_path, self.start_line, self.end_line));
+        match self.part {
+            Some((part, parts)) => content.push_str(&format!(" (part {} of {})\n", part, parts)),
+            None => content.push('\n'),
+        }
+        match (self.cell, self.page) {
+            (Some(cell), _) => content.push_str(&format!(
+                "File: {} (cell {}):{}-{}\n",
+                self.file_path, cell, self.start_line, self.end_line
+            )),
+            (None, Some(page)) => content.push_str(&format!(
+                "File: {} (page {}):{}-{}\n",
+                self.file_path, page, self.start_line, self.end_line
+            )),
+            (None, None) => content.push_str(&format!("File: {}:{}-{}\n", self.file_path, self.start_line, self.end_line)),
+        }
 
         if let Some(ref doc) = self.documentation {
             content.push_str(&format!("\n{}\n", doc));
@@ -152,6 +252,19 @@ impl CodeChunk {
             content.push_str(&format!("\nSignature: `{}`\n", sig));
         }
 
+        if !self.calls.is_empty() {
+            content.push_str(&format!("Calls: {}\n", self.calls.join(", ")));
+        }
+        if !self.called_by.is_empty() {
+            content.push_str(&format!("Called by: {}\n", self.called_by.join(", ")));
+        }
+
+        // Sections are prose, not code
+        if self.symbol_type == SymbolType::Section {
+            content.push_str(&format!("\n{}\n", self.content.trim_end()));
+            return content;
+        }
+
         content.push_str("\n```\n");
         content.push_str(&self.content);
         if !self.content.ends_with('\n') {
@@ -0,0 +1,510 @@
+//! Plain text, PDF and Word (`.docx`) documents
+//!
+//! Documents have no symbols to chunk by, so they are chunked by
+//! paragraph: text is split at blank lines and consecutive paragraphs are
+//! packed into chunks of up to [`DEFAULT_DOCUMENT_CHUNK_SIZE`] bytes. A
+//! paragraph is only cut when it is longer than that on its own. PDFs are
+//! chunked page by page and every chunk records its page; Word documents
+//! do the same when they contain page breaks.
+
+use std::path::Path;
+
+use crate::analyzer::CodeAnalyzer;
+use crate::chunk::{CodeChunk, SymbolType};
+use crate::error::{IndexerError, Result};
+use crate::languages::Language;
+
+/// Default chunk length for documents, in bytes
+pub const DEFAULT_DOCUMENT_CHUNK_SIZE: usize = 1000;
+
+/// Paragraph-based analyzer for plain text, PDF and Word documents
+#[derive(Debug)]
+pub struct DocumentAnalyzer {
+    language: Language,
+    chunk_size: usize,
+}
+
+/// Text of a chunk and its first and last line (0-based) on the page
+struct Block {
+    start: usize,
+    end: usize,
+    text: String,
+}
+
+impl DocumentAnalyzer {
+    /// Create an analyzer for `language`, which must be plain text, PDF or
+    /// Word
+    pub fn new(language: Language) -> Result<Self> {
+        match language {
+            Language::Text | Language::Pdf | Language::Docx => Ok(Self {
+                language,
+                chunk_size: DEFAULT_DOCUMENT_CHUNK_SIZE,
+            }),
+            _ => Err(IndexerError::UnsupportedLanguage(format!(
+                "{} is not a document format",
+                language
+            ))),
+        }
+    }
+
+    /// Set the chunk length, in bytes
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Chunks of the document in `bytes`, read from `file_path`
+    ///
+    /// Chunks are named after the file; their lines count from the start
+    /// of their page.
+    pub fn analyze_bytes(&self, bytes: &[u8], file_path: &str) -> Result<Vec<CodeChunk>> {
+        let pages = match self.language {
+            Language::Pdf => pdf_pages(bytes)?,
+            Language::Docx => docx_pages(bytes)?,
+            // Plain text often isn't UTF-8; a few replaced characters
+            // beat skipping the file
+            _ => vec![String::from_utf8_lossy(bytes).into_owned()],
+        };
+        let paged = self.language == Language::Pdf || pages.len() > 1;
+        // PDF text comes one printed line at a time
+        let reflow = self.language == Language::Pdf;
+
+        let name = Path::new(file_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+
+        let mut chunks = Vec::new();
+        for (i, page) in pages.iter().enumerate() {
+            for block in self.pack(paragraphs(page, reflow)) {
+                let chunk = CodeChunk::new(
+                    name.clone(),
+                    SymbolType::Section,
+                    block.text,
+                    file_path,
+                    block.start + 1,
+                    block.end + 1,
+                );
+                chunks.push(if paged { chunk.with_page(i + 1) } else { chunk });
+            }
+        }
+
+        Ok(chunks)
+    }
+
+    /// Pack consecutive paragraphs into blocks of up to `chunk_size`
+    /// bytes, splitting longer paragraphs between words
+    fn pack(&self, paragraphs: Vec<Block>) -> Vec<Block> {
+        let mut blocks = Vec::new();
+        let mut current: Option<Block> = None;
+
+        for paragraph in paragraphs {
+            if current
+                .as_ref()
+                .is_some_and(|c| c.text.len() + paragraph.text.len() + 2 > self.chunk_size)
+            {
+                blocks.extend(current.take());
+            }
+
+            if paragraph.text.len() > self.chunk_size {
+                blocks.extend(
+                    split_words(&paragraph.text, self.chunk_size)
+                        .into_iter()
+                        .map(|text| Block { text, ..paragraph }),
+                );
+                continue;
+            }
+
+            match current {
+                Some(ref mut block) => {
+                    block.text.push_str("\n\n");
+                    block.text.push_str(&paragraph.text);
+                    block.end = paragraph.end;
+                }
+                None => current = Some(paragraph),
+            }
+        }
+
+        blocks.extend(current);
+        blocks
+    }
+}
+
+impl CodeAnalyzer for DocumentAnalyzer {
+    fn language(&self) -> Language {
+        self.language
+    }
+
+    fn analyze(&self, source: &str, file_path: &str) -> Result<Vec<CodeChunk>> {
+        self.analyze_bytes(source.as_bytes(), file_path)
+    }
+}
+
+/// Runs of non-blank lines in `text`
+///
+/// With `reflow`, a paragraph's lines are joined into one, rejoining
+/// words hyphenated across the line break.
+fn paragraphs(text: &str, reflow: bool) -> Vec<Block> {
+    let mut paragraphs = Vec::new();
+    let mut lines: Vec<&str> = Vec::new();
+    let mut start = 0;
+
+    let mut finish = |lines: &mut Vec<&str>, start: usize| {
+        if lines.is_empty() {
+            return;
+        }
+        let text = if reflow {
+            reflow_lines(lines)
+        } else {
+            lines
+                .iter()
+                .map(|l| l.trim_end())
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+        paragraphs.push(Block {
+            start,
+            end: start + lines.len() - 1,
+            text,
+        });
+        lines.clear();
+    };
+
+    for (i, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            finish(&mut lines, start);
+        } else {
+            if lines.is_empty() {
+                start = i;
+            }
+            lines.push(line);
+        }
+    }
+    finish(&mut lines, start);
+
+    paragraphs
+}
+
+/// Join printed lines into one, turning "exam-" + "ple" into "example"
+fn reflow_lines(lines: &[&str]) -> String {
+    let mut text = String::new();
+    for line in lines {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        let hyphenated = text.ends_with('-')
+            && text[..text.len() - 1]
+                .chars()
+                .last()
+                .is_some_and(char::is_alphabetic)
+            && line.chars().next().is_some_and(char::is_lowercase);
+        if hyphenated {
+            text.pop();
+        } else if !text.is_empty() {
+            text.push(' ');
+        }
+        text.push_str(&line);
+    }
+    text
+}
+
+/// Split `text` between words into pieces of up to `size` bytes
+fn split_words(text: &str, size: usize) -> Vec<String> {
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+    for word in text.split_whitespace() {
+        if !current.is_empty() && current.len() + word.len() + 1 > size {
+            pieces.push(std::mem::take(&mut current));
+        }
+        if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(word);
+    }
+    if !current.is_empty() {
+        pieces.push(current);
+    }
+    pieces
+}
+
+/// Text of each page of the PDF in `bytes`
+#[cfg(feature = "pdf")]
+fn pdf_pages(bytes: &[u8]) -> Result<Vec<String>> {
+    if !bytes.starts_with(b"%PDF-") {
+        return Err(IndexerError::ParseError("Not a PDF document".to_string()));
+    }
+    pdf_extract::extract_text_from_mem_by_pages(bytes)
+        .map_err(|e| IndexerError::ParseError(format!("Invalid PDF: {}", e)))
+}
+
+#[cfg(not(feature = "pdf"))]
+fn pdf_pages(_bytes: &[u8]) -> Result<Vec<String>> {
+    Err(IndexerError::UnsupportedLanguage(
+        "PDF (built without the `pdf` feature)".to_string(),
+    ))
+}
+
+/// Text of each page of the Word document in `bytes`, split at explicit
+/// page breaks, with a blank line after every paragraph
+#[cfg(feature = "docx")]
+fn docx_pages(bytes: &[u8]) -> Result<Vec<String>> {
+    use quick_xml::events::Event;
+    use std::io::Read;
+
+    let invalid = |e: &dyn std::fmt::Display| {
+        IndexerError::ParseError(format!("Invalid Word document: {}", e))
+    };
+
+    let mut archive = zip::ZipArchive::new(std::io::Cursor::new(bytes)).map_err(|e| invalid(&e))?;
+    let mut xml = String::new();
+    archive
+        .by_name("word/document.xml")
+        .map_err(|e| invalid(&e))?
+        .read_to_string(&mut xml)?;
+
+    let mut reader = quick_xml::Reader::from_str(&xml);
+    let mut pages = vec![String::new()];
+    // Tabs and breaks only count inside runs (`w:r`); paragraph
+    // properties have `w:tab` stops too
+    let mut in_run = false;
+    let mut in_text = false;
+
+    loop {
+        let page = pages.last_mut().expect("at least one page");
+        match reader.read_event().map_err(|e| invalid(&e))? {
+            Event::Start(e) => match e.local_name().as_ref() {
+                b"r" => in_run = true,
+                b"t" => in_text = true,
+                _ => {}
+            },
+            Event::End(e) => match e.local_name().as_ref() {
+                b"r" => in_run = false,
+                b"t" => in_text = false,
+                b"p" => page.push_str("\n\n"),
+                _ => {}
+            },
+            Event::Empty(e) if in_run => match e.local_name().as_ref() {
+                b"tab" => page.push('\t'),
+                b"br" | b"cr" => {
+                    let page_break = e
+                        .try_get_attribute("w:type")
+                        .ok()
+                        .flatten()
+                        .is_some_and(|a| a.value.as_ref() == b"page");
+                    if page_break {
+                        pages.push(String::new());
+                    } else {
+                        page.push('\n');
+                    }
+                }
+                _ => {}
+            },
+            Event::Text(e) if in_text => page.push_str(&e.unescape().map_err(|e| invalid(&e))?),
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    Ok(pages)
+}
+
+#[cfg(not(feature = "docx"))]
+fn docx_pages(_bytes: &[u8]) -> Result<Vec<String>> {
+    Err(IndexerError::UnsupportedLanguage(
+        "Word (built without the `docx` feature)".to_string(),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn analyze(language: Language, bytes: &[u8], chunk_size: usize) -> Vec<CodeChunk> {
+        DocumentAnalyzer::new(language)
+            .unwrap()
+            .with_chunk_size(chunk_size)
+            .analyze_bytes(bytes, "docs/handbook.txt")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_text_paragraphs() {
+        let text = "Welcome to the team.\nRead this first.\n\n\nVacation is booked\nthrough the portal.\n\nExpenses need receipts.\n";
+        let chunks = analyze(Language::Text, text.as_bytes(), 80);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[0].name, "handbook");
+        assert_eq!(chunks[0].symbol_type, SymbolType::Section);
+        assert_eq!(
+            chunks[0].content,
+            "Welcome to the team.\nRead this first.\n\nVacation is booked\nthrough the portal."
+        );
+        assert_eq!((chunks[0].start_line, chunks[0].end_line), (1, 6));
+        assert_eq!(chunks[1].content, "Expenses need receipts.");
+        assert_eq!((chunks[1].start_line, chunks[1].end_line), (8, 8));
+        assert!(chunks.iter().all(|c| c.page.is_none()));
+    }
+
+    #[test]
+    fn test_long_paragraphs_are_split_between_words() {
+        let text = "one two three four five six seven eight nine ten";
+        let chunks = analyze(Language::Text, text.as_bytes(), 20);
+
+        assert!(chunks.len() > 1);
+        assert!(chunks.iter().all(|c| c.content.len() <= 20));
+        assert_eq!(
+            chunks
+                .iter()
+                .map(|c| c.content.as_str())
+                .collect::<Vec<_>>()
+                .join(" "),
+            text
+        );
+    }
+
+    #[test]
+    fn test_reflow_lines() {
+        assert_eq!(
+            reflow_lines(&["Retrieval  augmented gen-", "eration, well-", "Known."]),
+            "Retrieval augmented generation, well- Known."
+        );
+    }
+
+    #[test]
+    fn test_rejects_other_languages() {
+        assert!(DocumentAnalyzer::new(Language::Rust).is_err());
+    }
+
+    /// A PDF with one page per entry of `pages`, each showing its lines in
+    /// Helvetica
+    #[cfg(feature = "pdf")]
+    fn pdf(pages: &[&[&str]]) -> Vec<u8> {
+        // Catalog, page tree and font come first; each page is then a
+        // page object followed by its content stream
+        let kids: Vec<String> = (0..pages.len())
+            .map(|i| format!("{} 0 R", 4 + 2 * i))
+            .collect();
+        let mut objects = vec![
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            format!(
+                "<< /Type /Pages /Kids [{}] /Count {} >>",
+                kids.join(" "),
+                pages.len()
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+        for (i, lines) in pages.iter().enumerate() {
+            let mut stream = String::from("BT /F1 12 Tf 72 720 Td 14 TL\n");
+            for line in *lines {
+                stream.push_str(&format!("({}) Tj T*\n", line));
+            }
+            stream.push_str("ET");
+
+            objects.push(format!(
+                "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+                 /Resources << /Font << /F1 3 0 R >> >> /Contents {} 0 R >>",
+                5 + 2 * i
+            ));
+            objects.push(format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ));
+        }
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref = out.len();
+        out.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            out.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        out.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        out.into_bytes()
+    }
+
+    #[cfg(feature = "pdf")]
+    #[test]
+    fn test_pdf_pages() {
+        let bytes = pdf(&[&["Quarterly report"], &["Revenue grew", "in every region"]]);
+        let chunks = analyze(Language::Pdf, &bytes, DEFAULT_DOCUMENT_CHUNK_SIZE);
+
+        let pages: Vec<_> = chunks.iter().map(|c| c.page).collect();
+        assert_eq!(pages, [Some(1), Some(2)]);
+        assert!(chunks[0].content.contains("Quarterly report"));
+        assert!(
+            chunks[1].content.contains("Revenue grew in every region"),
+            "{:?}",
+            chunks[1].content
+        );
+        assert!(chunks[1].to_document_content().contains("(page 2)"));
+
+        assert!(DocumentAnalyzer::new(Language::Pdf)
+            .unwrap()
+            .analyze_bytes(b"%PDF-1.4 truncated", "broken.pdf")
+            .is_err());
+    }
+
+    /// A Word document whose body is `body` (WordprocessingML)
+    #[cfg(feature = "docx")]
+    fn docx(body: &str) -> Vec<u8> {
+        use std::io::Write;
+
+        let mut zip = zip::ZipWriter::new(std::io::Cursor::new(Vec::new()));
+        zip.start_file(
+            "word/document.xml",
+            zip::write::SimpleFileOptions::default(),
+        )
+        .unwrap();
+        write!(
+            zip,
+            r#"<?xml version="1.0" encoding="UTF-8" standalone="yes"?>
+<w:document xmlns:w="http://schemas.openxmlformats.org/wordprocessingml/2006/main"><w:body>{}</w:body></w:document>"#,
+            body
+        )
+        .unwrap();
+        zip.finish().unwrap().into_inner()
+    }
+
+    #[cfg(feature = "docx")]
+    #[test]
+    fn test_docx_paragraphs_and_page_breaks() {
+        let bytes = docx(concat!(
+            r#"<w:p><w:pPr><w:tabs><w:tab w:val="left" w:pos="720"/></w:tabs></w:pPr>"#,
+            r#"<w:r><w:t>Onboarding</w:t></w:r><w:r><w:tab/><w:t xml:space="preserve">guide &amp; checklist</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:t>Badges are issued on day one.</w:t></w:r></w:p>"#,
+            r#"<w:p><w:r><w:br w:type="page"/><w:t>Appendix</w:t></w:r></w:p>"#,
+        ));
+        let chunks = analyze(Language::Docx, &bytes, DEFAULT_DOCUMENT_CHUNK_SIZE);
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            chunks[0].content,
+            "Onboarding\tguide & checklist\n\nBadges are issued on day one."
+        );
+        assert_eq!(chunks[0].page, Some(1));
+        assert_eq!(chunks[1].content, "Appendix");
+        assert_eq!(chunks[1].page, Some(2));
+
+        // Without page breaks there are no pages to report
+        let bytes = docx("<w:p><w:r><w:t>Just one page</w:t></w:r></w:p>");
+        let chunks = analyze(Language::Docx, &bytes, DEFAULT_DOCUMENT_CHUNK_SIZE);
+        assert_eq!(chunks[0].page, None);
+
+        assert!(DocumentAnalyzer::new(Language::Docx)
+            .unwrap()
+            .analyze_bytes(b"not a zip", "broken.docx")
+            .is_err());
+    }
+}
@@ -24,6 +24,22 @@ pub enum IndexerError {
     /// Tree-sitter error
     #[error("Tree-sitter error: {0}")]
     TreeSitter(String),
+
+    /// Git command failed
+    #[error("Git error: {0}")]
+    Git(String),
+
+    /// Filesystem watcher error
+    #[error("Watch error: {0}")]
+    Watch(String),
+
+    /// Embedding failed while indexing into storage
+    #[error("Embedding error: {0}")]
+    Embedding(#[from] neuro_embeddings::EmbeddingError),
+
+    /// Storage failed while indexing into storage
+    #[error("Storage error: {0}")]
+    Storage(#[from] neuro_storage::StorageError),
 }
 
 /// Result type for indexer operations
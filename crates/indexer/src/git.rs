@@ -0,0 +1,88 @@
+//! Changed-file discovery through the `git` command line
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use crate::error::{IndexerError, Result};
+
+/// Files that differ between a base revision and the working tree
+#[derive(Debug, Default)]
+pub(crate) struct ChangedFiles {
+    /// Added, modified or untracked files that exist now
+    pub changed: Vec<PathBuf>,
+    /// Files removed since the base revision
+    pub deleted: Vec<PathBuf>,
+}
+
+/// Run `git` in `repo` and return its stdout
+fn git(repo: &Path, args: &[&str]) -> Result<String> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(repo)
+        .args(args)
+        .output()
+        .map_err(|e| IndexerError::Git(format!("failed to run git: {}", e)))?;
+
+    if !output.status.success() {
+        return Err(IndexerError::Git(format!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+/// Root of the working tree containing `repo`
+pub(crate) fn toplevel(repo: &Path) -> Result<PathBuf> {
+    Ok(PathBuf::from(
+        git(repo, &["rev-parse", "--show-toplevel"])?.trim(),
+    ))
+}
+
+/// Full hash of the commit `rev` points at
+pub(crate) fn resolve(repo: &Path, rev: &str) -> Result<String> {
+    let commit = format!("{}^{{commit}}", rev);
+    Ok(git(repo, &["rev-parse", "--verify", &commit])?
+        .trim()
+        .to_string())
+}
+
+/// Files changed in the working tree since `since_ref`
+///
+/// Diffs against the merge base of `since_ref` and `HEAD`, so a branch
+/// name gives only this branch's changes, not what landed on it since.
+/// Uncommitted and untracked (but not ignored) files count as changed.
+pub(crate) fn changed_files(repo: &Path, since_ref: &str) -> Result<ChangedFiles> {
+    let root = toplevel(repo)?;
+    let base = resolve(repo, since_ref)?;
+    let base = git(&root, &["merge-base", &base, "HEAD"])?;
+
+    let mut files = ChangedFiles::default();
+
+    // -z output: status and path as separate NUL-terminated fields
+    let diff = git(
+        &root,
+        &["diff", "--name-status", "--no-renames", "-z", base.trim()],
+    )?;
+    let mut fields = diff.split('\0').filter(|f| !f.is_empty());
+    while let (Some(status), Some(path)) = (fields.next(), fields.next()) {
+        let path = root.join(path);
+        if status.starts_with('D') {
+            files.deleted.push(path);
+        } else {
+            files.changed.push(path);
+        }
+    }
+
+    let untracked = git(&root, &["ls-files", "--others", "--exclude-standard", "-z"])?;
+    files.changed.extend(
+        untracked
+            .split('\0')
+            .filter(|f| !f.is_empty())
+            .map(|f| root.join(f)),
+    );
+
+    Ok(files)
+}
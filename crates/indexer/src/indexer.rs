@@ -1,13 +1,20 @@
 //! Code indexer for processing files and directories
 
-use std::path::Path;
+use ignore::WalkBuilder;
+use std::path::{Path, PathBuf};
 use tracing::{debug, info, warn};
-use walkdir::WalkDir;
 
 use crate::analyzer::{CodeAnalyzer, TreeSitterAnalyzer};
-use crate::chunk::CodeChunk;
+use crate::chunk::{CodeChunk, SymbolType};
+use crate::document::{DocumentAnalyzer, DEFAULT_DOCUMENT_CHUNK_SIZE};
 use crate::error::{IndexerError, Result};
+use crate::git;
 use crate::languages::Language;
+use crate::markdown::MarkdownAnalyzer;
+use crate::notebook::NotebookAnalyzer;
+use crate::split::{split_chunk, ChunkSize};
+use crate::symbols::SymbolQuery;
+use crate::watch::IndexWatcher;
 
 /// Configuration for the code indexer
 #[derive(Debug, Clone)]
@@ -18,6 +25,20 @@ pub struct IndexerConfig {
     pub skip_dirs: Vec<String>,
     /// File patterns to skip
     pub skip_patterns: Vec<String>,
+    /// Skip files excluded by `.gitignore`, `.git/info/exclude` and the
+    /// global git excludes file, even outside a git repository
+    pub respect_gitignore: bool,
+    /// Names of extra gitignore-style files honoured in every directory
+    pub ignore_files: Vec<String>,
+    /// Largest chunk to keep whole; bigger symbols are split into
+    /// overlapping windows. `None` never splits.
+    pub max_chunk_size: Option<ChunkSize>,
+    /// How much consecutive windows of a split chunk share, in the unit of
+    /// `max_chunk_size`
+    pub chunk_overlap: usize,
+    /// Length of the paragraph-packed chunks of plain text, PDF and Word
+    /// documents, in bytes
+    pub document_chunk_size: usize,
 }
 
 impl Default for IndexerConfig {
@@ -40,6 +61,11 @@ impl Default for IndexerConfig {
                 ".bundle.js".to_string(),
                 ".lock".to_string(),
             ],
+            respect_gitignore: true,
+            ignore_files: vec![".neuroignore".to_string()],
+            max_chunk_size: Some(ChunkSize::Lines(200)),
+            chunk_overlap: 20,
+            document_chunk_size: DEFAULT_DOCUMENT_CHUNK_SIZE,
         }
     }
 }
@@ -74,13 +100,42 @@ impl CodeIndexer {
             return Ok(Vec::new());
         }
 
-        let source = std::fs::read_to_string(path)?;
+        let bytes = std::fs::read(path)?;
         let file_path = path.display().to_string();
 
         debug!("Indexing file: {} ({})", file_path, language);
 
-        let analyzer = TreeSitterAnalyzer::new(language)?;
-        analyzer.analyze(&source, &file_path)
+        self.index_bytes(&bytes, &file_path, language)
+    }
+
+    /// Index a file's contents, already read into `bytes`
+    ///
+    /// Nothing is read from disk; `file_path` is only recorded in the
+    /// chunks. Useful for uploads. Source files must be UTF-8, while PDF
+    /// and Word documents are binary.
+    pub fn index_bytes(
+        &self,
+        bytes: &[u8],
+        file_path: &str,
+        language: Language,
+    ) -> Result<Vec<CodeChunk>> {
+        let mut chunks = match language {
+            Language::Text | Language::Pdf | Language::Docx => DocumentAnalyzer::new(language)?
+                .with_chunk_size(self.config.document_chunk_size)
+                .analyze_bytes(bytes, file_path)?,
+            _ => {
+                let source = std::str::from_utf8(bytes).map_err(|e| {
+                    IndexerError::ParseError(format!("{} is not UTF-8: {}", file_path, e))
+                })?;
+                match language {
+                    Language::Markdown => MarkdownAnalyzer::new().analyze(source, file_path)?,
+                    Language::Notebook => NotebookAnalyzer::new().analyze(source, file_path)?,
+                    _ => TreeSitterAnalyzer::new(language)?.analyze(source, file_path)?,
+                }
+            }
+        };
+        Self::link_calls(&mut chunks);
+        Ok(self.split_oversized(chunks))
     }
 
     /// Index a single file, auto-detecting language
@@ -103,25 +158,9 @@ impl CodeIndexer {
         let mut file_count = 0;
         let mut error_count = 0;
 
-        for entry in WalkDir::new(path)
-            .follow_links(false)
-            .into_iter()
-            .filter_entry(|e| !self.should_skip(e))
-        {
-            let entry = match entry {
-                Ok(e) => e,
-                Err(e) => {
-                    warn!("Error walking directory: {}", e);
-                    continue;
-                }
-            };
-
-            if !entry.file_type().is_file() {
-                continue;
-            }
+        for file_path in self.files(path, true) {
+            let file_path = file_path.as_path();
 
-            let file_path = entry.path();
-            
             // Check if we support this file type
             if Language::from_path(file_path).is_none() {
                 continue;
@@ -145,6 +184,9 @@ impl CodeIndexer {
             }
         }
 
+        // Resolve calls across files, not just within each one
+        Self::link_calls(&mut all_chunks);
+
         info!(
             "Indexed {} files, {} chunks, {} errors",
             file_count,
@@ -155,20 +197,187 @@ impl CodeIndexer {
         Ok(all_chunks)
     }
 
-    fn should_skip(&self, entry: &walkdir::DirEntry) -> bool {
-        let file_name = entry.file_name().to_string_lossy();
-        
-        // Skip hidden files/directories (but not the root we're indexing)
-        if entry.depth() > 0 && file_name.starts_with('.') && file_name != "." {
-            return true;
+    /// Index only the files changed in `repo`'s working tree since
+    /// `since_ref` (a commit, tag or branch)
+    ///
+    /// Uncommitted and untracked files are included. Every chunk records
+    /// the current `HEAD` commit; chunks previously indexed for the changed
+    /// and deleted files are stale and should be replaced.
+    pub fn index_git_diff(&self, repo: &Path, since_ref: &str) -> Result<GitDiffIndex> {
+        let commit = git::resolve(repo, "HEAD")?;
+        let files = git::changed_files(repo, since_ref)?;
+
+        info!(
+            "Reindexing {} changed files since {} ({} deleted)",
+            files.changed.len(),
+            since_ref,
+            files.deleted.len()
+        );
+
+        let mut chunks = Vec::new();
+        let mut changed = Vec::new();
+        for file_path in files.changed {
+            if Language::from_path(&file_path).is_none() || self.is_skipped(&file_path) {
+                continue;
+            }
+
+            match self.index_file_auto(&file_path) {
+                Ok(file_chunks) => {
+                    chunks.extend(file_chunks.into_iter().map(|c| c.with_commit(&commit)));
+                    changed.push(file_path);
+                }
+                Err(e) => warn!("Failed to index {:?}: {}", file_path, e),
+            }
+        }
+
+        Ok(GitDiffIndex {
+            commit,
+            chunks,
+            changed,
+            deleted: files.deleted,
+        })
+    }
+
+    /// Watch `root` for file changes, re-analyzing changed files once
+    /// events have been quiet for `debounce`
+    pub fn watch(&self, root: &Path, debounce: std::time::Duration) -> Result<IndexWatcher> {
+        IndexWatcher::new(root, self.config.clone(), debounce)
+    }
+
+    /// Whether `path` is in a skipped directory or matches a skip pattern
+    fn is_skipped(&self, path: &Path) -> bool {
+        let path_str = path.display().to_string();
+        self.config.skip_patterns.iter().any(|p| path_str.contains(p))
+            || path.components().any(|c| {
+                self.config
+                    .skip_dirs
+                    .iter()
+                    .any(|d| c.as_os_str() == d.as_str())
+            })
+    }
+
+    /// Files under `root` that aren't skipped or ignored, descending into
+    /// subdirectories only if `recursive`
+    ///
+    /// Hidden entries, `skip_dirs` and anything matched by the configured
+    /// ignore files are left out; `root` itself is always walked.
+    pub fn files(&self, root: &Path, recursive: bool) -> Vec<PathBuf> {
+        let mut builder = WalkBuilder::new(root);
+        builder
+            .follow_links(false)
+            .hidden(true)
+            .ignore(false)
+            .git_ignore(self.config.respect_gitignore)
+            .git_exclude(self.config.respect_gitignore)
+            .git_global(self.config.respect_gitignore)
+            .require_git(false)
+            .max_depth(if recursive { None } else { Some(1) });
+        for name in &self.config.ignore_files {
+            builder.add_custom_ignore_filename(name);
+        }
+
+        let skip_dirs = self.config.skip_dirs.clone();
+        builder.filter_entry(move |entry| {
+            let is_dir = entry.file_type().is_some_and(|t| t.is_dir());
+            entry.depth() == 0
+                || !is_dir
+                || !skip_dirs.iter().any(|d| entry.file_name() == d.as_str())
+        });
+
+        builder
+            .build()
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!("Error walking directory: {}", e);
+                    None
+                }
+            })
+            .filter(|entry| entry.file_type().is_some_and(|t| t.is_file()))
+            .map(|entry| entry.into_path())
+            .collect()
+    }
+
+    /// Split chunks larger than `max_chunk_size` into overlapping windows
+    ///
+    /// Each window keeps the symbol's name, parent and metadata, covers its
+    /// own line range and records its `part`.
+    pub fn split_oversized(&self, chunks: Vec<CodeChunk>) -> Vec<CodeChunk> {
+        let Some(max) = self.config.max_chunk_size else {
+            return chunks;
+        };
+        chunks
+            .into_iter()
+            .flat_map(|chunk| split_chunk(chunk, max, self.config.chunk_overlap))
+            .collect()
+    }
+
+    /// Fill in `called_by` for `chunks` from the other chunks' `calls`
+    ///
+    /// Calls are resolved by name only. A name defined in the caller's own
+    /// file resolves there; otherwise every function, class or struct of
+    /// that name counts as called. Existing `called_by` entries are
+    /// replaced.
+    pub fn link_calls(chunks: &mut [CodeChunk]) {
+        use std::collections::HashMap;
+
+        let mut definitions: HashMap<&str, Vec<usize>> = HashMap::new();
+        for (i, chunk) in chunks.iter().enumerate() {
+            if matches!(
+                chunk.symbol_type,
+                SymbolType::Function | SymbolType::Class | SymbolType::Struct
+            ) {
+                definitions.entry(chunk.name.as_str()).or_default().push(i);
+            }
         }
 
-        // Skip configured directories
-        if entry.file_type().is_dir() {
-            return self.config.skip_dirs.iter().any(|d| file_name == *d);
+        let mut callers: Vec<Vec<String>> = vec![Vec::new(); chunks.len()];
+        for caller in chunks.iter() {
+            for name in &caller.calls {
+                let Some(targets) = definitions.get(name.as_str()) else {
+                    continue;
+                };
+                let local: Vec<usize> = targets
+                    .iter()
+                    .copied()
+                    .filter(|&t| chunks[t].file_path == caller.file_path)
+                    .collect();
+                let targets = if local.is_empty() { targets } else { &local };
+
+                let caller_name = caller.display_name();
+                for &target in targets {
+                    if !callers[target].contains(&caller_name) {
+                        callers[target].push(caller_name.clone());
+                    }
+                }
+            }
         }
 
-        false
+        for (chunk, called_by) in chunks.iter_mut().zip(callers) {
+            chunk.called_by = called_by;
+        }
+    }
+
+    /// Chunks matching `query`, best match first
+    ///
+    /// Symbols split into several windows are reported once, by their
+    /// first part. Equally good matches are ordered by name length, then
+    /// location.
+    pub fn find_symbols<'a>(chunks: &'a [CodeChunk], query: &SymbolQuery) -> Vec<&'a CodeChunk> {
+        let mut matches: Vec<(u32, &CodeChunk)> = chunks
+            .iter()
+            .filter(|chunk| !matches!(chunk.part, Some((part, _)) if part > 1))
+            .filter_map(|chunk| query.score(chunk).map(|score| (score, chunk)))
+            .collect();
+
+        matches.sort_by(|(a_score, a), (b_score, b)| {
+            b_score
+                .cmp(a_score)
+                .then(a.name.len().cmp(&b.name.len()))
+                .then_with(|| a.file_path.cmp(&b.file_path))
+                .then(a.start_line.cmp(&b.start_line))
+        });
+        matches.into_iter().map(|(_, chunk)| chunk).collect()
     }
 
     /// Get statistics about indexed chunks
@@ -200,6 +409,19 @@ impl Default for CodeIndexer {
     }
 }
 
+/// Result of [`CodeIndexer::index_git_diff`]
+#[derive(Debug)]
+pub struct GitDiffIndex {
+    /// `HEAD` commit the chunks were indexed at
+    pub commit: String,
+    /// Chunks from the changed files
+    pub chunks: Vec<CodeChunk>,
+    /// Changed files that were indexed
+    pub changed: Vec<PathBuf>,
+    /// Files deleted since the base revision
+    pub deleted: Vec<PathBuf>,
+}
+
 /// Statistics about indexed code chunks
 #[derive(Debug)]
 pub struct ChunkStats {
@@ -234,6 +456,20 @@ class World:
         assert!(chunks.len() >= 2);
     }
 
+    #[test]
+    fn test_index_markdown_file() {
+        let dir = tempdir().unwrap();
+        let file_path = dir.path().join("README.md");
+
+        fs::write(&file_path, "# Project\n\nOverview.\n\n## Setup\n\nRun it.\n").unwrap();
+
+        let indexer = CodeIndexer::new();
+        let chunks = indexer.index_file_auto(&file_path).unwrap();
+
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(chunks[1].display_name(), "Project > Setup");
+    }
+
     #[test]
     fn test_index_directory() {
         let dir = tempdir().unwrap();
@@ -289,6 +525,65 @@ def module_func():
         assert!(!chunks.is_empty(), "Should have at least some chunks from the test files");
     }
 
+    #[test]
+    fn test_link_calls_across_files() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("app.py"),
+            "def main():\n    return load()\n\nclass Loader:\n    def run(self):\n        return load() + parse()\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("io.py"), "def load():\n    return parse()\n").unwrap();
+        fs::write(dir.path().join("text.py"), "def parse():\n    pass\n").unwrap();
+        fs::write(
+            dir.path().join("other.py"),
+            "def parse():\n    pass\n\ndef go():\n    parse()\n",
+        )
+        .unwrap();
+
+        let chunks = CodeIndexer::new().index_directory(dir.path()).unwrap();
+        let called_by = |name: &str, file: &str| {
+            let chunk = chunks
+                .iter()
+                .find(|c| c.name == name && c.file_path.ends_with(file))
+                .unwrap();
+            let mut callers = chunk.called_by.clone();
+            callers.sort();
+            callers
+        };
+
+        assert_eq!(called_by("load", "io.py"), ["Loader::run", "main"]);
+        // A same-file definition wins, so go() doesn't call text.py's parse
+        assert_eq!(called_by("parse", "other.py"), ["Loader::run", "go", "load"]);
+        assert_eq!(called_by("parse", "text.py"), ["Loader::run", "load"]);
+        assert!(called_by("main", "app.py").is_empty());
+    }
+
+    #[test]
+    fn test_find_symbols() {
+        let dir = tempdir().unwrap();
+        fs::write(
+            dir.path().join("parser.rs"),
+            "fn parse() {}\nfn parse_config() {}\nstruct Parser;\nfn reparse() {}\n",
+        )
+        .unwrap();
+        fs::write(dir.path().join("cli.py"), "def parse_args():\n    pass\n").unwrap();
+
+        let chunks = CodeIndexer::new().index_directory(dir.path()).unwrap();
+        let names = |query: &str| -> Vec<String> {
+            let query: SymbolQuery = query.parse().unwrap();
+            CodeIndexer::find_symbols(&chunks, &query)
+                .iter()
+                .map(|c| c.name.clone())
+                .collect()
+        };
+
+        assert_eq!(names("parse"), ["parse"]);
+        assert_eq!(names("fn parse*"), ["parse", "parse_args", "parse_config"]);
+        assert_eq!(names("struct Pars*"), ["Parser"]);
+        assert!(names("class parse*").is_empty());
+    }
+
     #[test]
     fn test_skip_directories() {
         let dir = tempdir().unwrap();
@@ -308,6 +603,85 @@ def module_func():
         assert!(chunks.iter().all(|c| !c.file_path.contains("node_modules")));
     }
 
+    #[test]
+    fn test_respects_ignore_files() {
+        let dir = tempdir().unwrap();
+
+        fs::write(dir.path().join(".gitignore"), "generated/\n*.gen.py\n").unwrap();
+        fs::write(dir.path().join(".neuroignore"), "secrets.py\n").unwrap();
+        fs::write(dir.path().join("main.py"), "def main(): pass").unwrap();
+        fs::write(dir.path().join("schema.gen.py"), "def schema(): pass").unwrap();
+        fs::write(dir.path().join("secrets.py"), "def token(): pass").unwrap();
+        let generated = dir.path().join("generated");
+        fs::create_dir(&generated).unwrap();
+        fs::write(generated.join("api.py"), "def api(): pass").unwrap();
+
+        let indexer = CodeIndexer::new();
+        let names: Vec<_> = indexer
+            .files(dir.path(), true)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["main.py"]);
+
+        // Without gitignore support only the custom ignore file applies
+        let indexer = CodeIndexer::with_config(IndexerConfig {
+            respect_gitignore: false,
+            ..Default::default()
+        });
+        let mut names: Vec<_> = indexer
+            .files(dir.path(), true)
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        names.sort();
+        assert_eq!(names, ["api.py", "main.py", "schema.gen.py"]);
+    }
+
+    #[test]
+    fn test_index_git_diff() {
+        let dir = tempdir().unwrap();
+        let git = |args: &[&str]| {
+            let status = std::process::Command::new("git")
+                .arg("-C")
+                .arg(dir.path())
+                .args(["-c", "user.name=test", "-c", "user.email=test@example.com"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(status.status.success(), "git {:?} failed", args);
+        };
+
+        git(&["init", "-q"]);
+        fs::write(dir.path().join("kept.py"), "def kept(): pass").unwrap();
+        fs::write(dir.path().join("edited.py"), "def old(): pass").unwrap();
+        fs::write(dir.path().join("removed.py"), "def removed(): pass").unwrap();
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "base"]);
+        git(&["tag", "base"]);
+
+        fs::write(dir.path().join("edited.py"), "def new(): pass").unwrap();
+        git(&["rm", "-q", "removed.py"]);
+        git(&["commit", "-q", "-am", "change"]);
+        fs::write(dir.path().join("untracked.rs"), "fn fresh() {}").unwrap();
+
+        let indexer = CodeIndexer::new();
+        let diff = indexer.index_git_diff(dir.path(), "base").unwrap();
+
+        let mut names: Vec<_> = diff.chunks.iter().map(|c| c.name.as_str()).collect();
+        names.sort();
+        assert_eq!(names, ["fresh", "new"]);
+        assert_eq!(diff.commit.len(), 40);
+        assert!(diff.chunks.iter().all(|c| c.commit.as_deref() == Some(&*diff.commit)));
+        assert_eq!(diff.deleted.len(), 1);
+        assert!(diff.deleted[0].ends_with("removed.py"));
+
+        assert!(matches!(
+            indexer.index_git_diff(dir.path(), "no-such-ref"),
+            Err(IndexerError::Git(_))
+        ));
+    }
+
     #[test]
     fn test_auto_language_detection() {
         let dir = tempdir().unwrap();
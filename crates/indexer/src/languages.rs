@@ -9,6 +9,15 @@ pub enum Language {
     JavaScript,
     TypeScript,
     Rust,
+    C,
+    Cpp,
+    Ruby,
+    Php,
+    Markdown,
+    Notebook,
+    Text,
+    Pdf,
+    Docx,
 }
 
 impl Language {
@@ -19,6 +28,15 @@ impl Language {
             "js" | "mjs" | "cjs" => Some(Self::JavaScript),
             "ts" | "tsx" => Some(Self::TypeScript),
             "rs" => Some(Self::Rust),
+            "c" | "h" => Some(Self::C),
+            "cpp" | "cc" | "cxx" | "c++" | "hpp" | "hh" | "hxx" | "h++" => Some(Self::Cpp),
+            "rb" | "rake" => Some(Self::Ruby),
+            "php" => Some(Self::Php),
+            "md" | "markdown" => Some(Self::Markdown),
+            "ipynb" => Some(Self::Notebook),
+            "txt" | "text" => Some(Self::Text),
+            "pdf" => Some(Self::Pdf),
+            "docx" => Some(Self::Docx),
             _ => None,
         }
     }
@@ -37,6 +55,15 @@ impl Language {
             Self::JavaScript => "JavaScript",
             Self::TypeScript => "TypeScript",
             Self::Rust => "Rust",
+            Self::C => "C",
+            Self::Cpp => "C++",
+            Self::Ruby => "Ruby",
+            Self::Php => "PHP",
+            Self::Markdown => "Markdown",
+            Self::Notebook => "Jupyter Notebook",
+            Self::Text => "Plain Text",
+            Self::Pdf => "PDF",
+            Self::Docx => "Word",
         }
     }
 
@@ -47,6 +74,15 @@ impl Language {
             Self::JavaScript => &["js", "mjs", "cjs"],
             Self::TypeScript => &["ts", "tsx"],
             Self::Rust => &["rs"],
+            Self::C => &["c", "h"],
+            Self::Cpp => &["cpp", "cc", "cxx", "c++", "hpp", "hh", "hxx", "h++"],
+            Self::Ruby => &["rb", "rake"],
+            Self::Php => &["php"],
+            Self::Markdown => &["md", "markdown"],
+            Self::Notebook => &["ipynb"],
+            Self::Text => &["txt", "text"],
+            Self::Pdf => &["pdf"],
+            Self::Docx => &["docx"],
         }
     }
 }
@@ -66,6 +102,15 @@ impl std::str::FromStr for Language {
             "javascript" | "js" => Ok(Self::JavaScript),
             "typescript" | "ts" => Ok(Self::TypeScript),
             "rust" | "rs" => Ok(Self::Rust),
+            "c" => Ok(Self::C),
+            "c++" | "cpp" | "cxx" => Ok(Self::Cpp),
+            "ruby" | "rb" => Ok(Self::Ruby),
+            "php" => Ok(Self::Php),
+            "markdown" | "md" => Ok(Self::Markdown),
+            "notebook" | "jupyter" | "ipynb" => Ok(Self::Notebook),
+            "text" | "txt" | "plain text" => Ok(Self::Text),
+            "pdf" => Ok(Self::Pdf),
+            "word" | "docx" => Ok(Self::Docx),
             _ => Err(format!("Unknown language: {}", s)),
         }
     }
@@ -81,6 +126,17 @@ mod tests {
         assert_eq!(Language::from_extension("js"), Some(Language::JavaScript));
         assert_eq!(Language::from_extension("ts"), Some(Language::TypeScript));
         assert_eq!(Language::from_extension("rs"), Some(Language::Rust));
+        assert_eq!(Language::from_extension("c"), Some(Language::C));
+        assert_eq!(Language::from_extension("h"), Some(Language::C));
+        assert_eq!(Language::from_extension("cpp"), Some(Language::Cpp));
+        assert_eq!(Language::from_extension("HPP"), Some(Language::Cpp));
+        assert_eq!(Language::from_extension("rb"), Some(Language::Ruby));
+        assert_eq!(Language::from_extension("php"), Some(Language::Php));
+        assert_eq!(Language::from_extension("md"), Some(Language::Markdown));
+        assert_eq!(Language::from_extension("ipynb"), Some(Language::Notebook));
+        assert_eq!(Language::from_extension("txt"), Some(Language::Text));
+        assert_eq!(Language::from_extension("PDF"), Some(Language::Pdf));
+        assert_eq!(Language::from_extension("docx"), Some(Language::Docx));
         assert_eq!(Language::from_extension("unknown"), None);
     }
 
@@ -100,5 +156,9 @@ mod tests {
     fn test_from_str() {
         assert_eq!("python".parse::<Language>().unwrap(), Language::Python);
         assert_eq!("js".parse::<Language>().unwrap(), Language::JavaScript);
+        assert_eq!("c".parse::<Language>().unwrap(), Language::C);
+        assert_eq!("c++".parse::<Language>().unwrap(), Language::Cpp);
+        assert_eq!("ruby".parse::<Language>().unwrap(), Language::Ruby);
+        assert_eq!("PHP".parse::<Language>().unwrap(), Language::Php);
     }
 }
@@ -9,6 +9,14 @@
 //! - JavaScript
 //! - TypeScript
 //! - Rust
+//! - C
+//! - C++
+//! - Ruby
+//! - PHP
+//! - Markdown (chunked by heading)
+//! - Jupyter notebooks (code and markdown cells)
+//! - Plain text, PDF and Word documents (chunked by paragraph, PDFs page
+//!   by page; `pdf` and `docx` features)
 //!
 //! ## Example
 //!
@@ -23,20 +31,69 @@
 //!     println!("{}: {}", chunk.symbol_type, chunk.name);
 //! }
 //! ```
+//!
+//! ## Indexing into storage
+//!
+//! [`index_to_storage`] analyzes a directory, embeds its chunks in batches
+//! and stores them as code documents. Re-running it only embeds chunks
+//! whose code changed and removes the ones that are gone.
+//!
+//! ```no_run
+//! use std::path::Path;
+//! use std::sync::Arc;
+//! use neuro_embeddings::{FastEmbedder, SpawnBlockingEmbedder};
+//! use neuro_indexer::{index_to_storage, PipelineOptions};
+//! use neuro_storage::MemoryStorage;
+//!
+//! # async fn run() -> neuro_indexer::Result<()> {
+//! let embedder = SpawnBlockingEmbedder::new(Arc::new(FastEmbedder::default_model()?));
+//! let mut storage = MemoryStorage::new();
+//! let report = index_to_storage(
+//!     Path::new("src"),
+//!     &embedder,
+//!     &mut storage,
+//!     &PipelineOptions::default(),
+//!     |p| println!("{}/{}", p.completed, p.total),
+//! )
+//! .await?;
+//! println!("{} chunks stored", report.stored);
+//! # Ok(())
+//! # }
+//! ```
 
 mod analyzer;
 mod chunk;
+mod document;
 mod error;
+mod git;
 mod indexer;
 mod languages;
+mod markdown;
+mod notebook;
+mod pipeline;
+mod split;
+mod symbols;
+mod watch;
 
 pub use analyzer::CodeAnalyzer;
 pub use chunk::{CodeChunk, SymbolType};
+pub use document::{DocumentAnalyzer, DEFAULT_DOCUMENT_CHUNK_SIZE};
 pub use error::{IndexerError, Result};
-pub use indexer::CodeIndexer;
+pub use indexer::{CodeIndexer, GitDiffIndex, IndexerConfig};
 pub use languages::Language;
+pub use markdown::MarkdownAnalyzer;
+pub use notebook::NotebookAnalyzer;
+pub use pipeline::{
+    index_to_storage, PipelineOptions, PipelineReport, DEFAULT_PIPELINE_BATCH_SIZE,
+};
+pub use split::ChunkSize;
+pub use symbols::{NameMatch, SymbolQuery};
+pub use watch::{IndexWatcher, WatchUpdate};
 
 /// Re-export commonly used types
 pub mod prelude {
-    pub use crate::{CodeAnalyzer, CodeChunk, CodeIndexer, IndexerError, Language, Result, SymbolType};
+    pub use crate::{
+        CodeAnalyzer, CodeChunk, CodeIndexer, DocumentAnalyzer, IndexerError, Language,
+        MarkdownAnalyzer, NotebookAnalyzer, Result, SymbolQuery, SymbolType,
+    };
 }
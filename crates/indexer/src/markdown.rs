@@ -0,0 +1,342 @@
+//! Markdown analyzer that chunks documents by heading
+//!
+//! Each heading starts a section that runs until the next heading of any
+//! level, so nested sections don't repeat their children's text. The
+//! chunk is named after the heading, with the enclosing headings as its
+//! parent (`Install > Linux`). Text before the first heading becomes a
+//! chunk named after the file.
+
+use std::path::Path;
+
+use crate::analyzer::CodeAnalyzer;
+use crate::chunk::{CodeChunk, SymbolType};
+use crate::error::Result;
+use crate::languages::Language;
+
+/// Separator between headings in a section's parent path
+pub const HEADING_PATH_SEPARATOR: &str = " > ";
+
+/// Heading-based analyzer for Markdown documents
+#[derive(Debug, Default)]
+pub struct MarkdownAnalyzer;
+
+/// A heading found in the document
+struct Heading {
+    /// First line of the heading (the text line for setext headings)
+    line: usize,
+    level: usize,
+    text: String,
+}
+
+impl MarkdownAnalyzer {
+    /// Create a new Markdown analyzer
+    pub fn new() -> Self {
+        Self
+    }
+
+    /// Headings outside code blocks, skipping YAML front matter
+    ///
+    /// Returns the headings and the first line after the front matter.
+    fn headings(lines: &[&str]) -> (Vec<Heading>, usize) {
+        let body_start = front_matter_end(lines);
+        let mut headings = Vec::new();
+        let mut fence: Option<(char, usize)> = None;
+
+        for i in body_start..lines.len() {
+            let line = lines[i];
+
+            if let Some((marker, len)) = code_fence(line) {
+                match fence {
+                    None => fence = Some((marker, len)),
+                    Some((open, open_len)) if open == marker && len >= open_len => fence = None,
+                    Some(_) => {}
+                }
+                continue;
+            }
+            if fence.is_some() {
+                continue;
+            }
+
+            if let Some((level, text)) = atx_heading(line) {
+                headings.push(Heading {
+                    line: i,
+                    level,
+                    text,
+                });
+            } else if let Some(level) = setext_underline(line) {
+                // The underline turns the line above into a heading, unless
+                // that line is blank or already a heading
+                let Some(prev) = i.checked_sub(1).filter(|&p| p >= body_start) else {
+                    continue;
+                };
+                let text = lines[prev].trim();
+                let prev_is_heading = headings.last().is_some_and(|h| h.line == prev);
+                if !text.is_empty() && !prev_is_heading && code_fence(lines[prev]).is_none() {
+                    headings.push(Heading {
+                        line: prev,
+                        level,
+                        text: text.to_string(),
+                    });
+                }
+            }
+        }
+
+        (headings, body_start)
+    }
+
+    /// Sections of `source`, naming the text before the first heading
+    /// `preamble_name`
+    pub(crate) fn sections(
+        &self,
+        source: &str,
+        file_path: &str,
+        preamble_name: &str,
+    ) -> Vec<CodeChunk> {
+        let lines: Vec<&str> = source.lines().collect();
+        let (headings, body_start) = Self::headings(&lines);
+        let mut chunks = Vec::new();
+
+        // Text before the first heading
+        let preamble_end = headings.first().map_or(lines.len(), |h| h.line);
+        if let Some(chunk) = section_chunk(&lines, body_start, preamble_end, file_path, || {
+            preamble_name.to_string()
+        }) {
+            chunks.push(chunk);
+        }
+
+        let mut path: Vec<(usize, &str)> = Vec::new();
+        for (i, heading) in headings.iter().enumerate() {
+            while path
+                .last()
+                .is_some_and(|&(level, _)| level >= heading.level)
+            {
+                path.pop();
+            }
+            let parent = path
+                .iter()
+                .map(|&(_, text)| text)
+                .collect::<Vec<_>>()
+                .join(HEADING_PATH_SEPARATOR);
+            path.push((heading.level, &heading.text));
+
+            let end = headings.get(i + 1).map_or(lines.len(), |next| next.line);
+            // Headings with nothing under them before the next heading add
+            // nothing to search
+            let has_body = lines[heading.line..end]
+                .iter()
+                .skip(1)
+                .any(|line| !line.trim().is_empty() && setext_underline(line).is_none());
+            if !has_body {
+                continue;
+            }
+
+            if let Some(mut chunk) = section_chunk(&lines, heading.line, end, file_path, || {
+                heading.text.clone()
+            }) {
+                if !parent.is_empty() {
+                    chunk = chunk.with_parent(parent);
+                }
+                chunks.push(chunk);
+            }
+        }
+
+        chunks
+    }
+}
+
+impl CodeAnalyzer for MarkdownAnalyzer {
+    fn language(&self) -> Language {
+        Language::Markdown
+    }
+
+    fn analyze(&self, source: &str, file_path: &str) -> Result<Vec<CodeChunk>> {
+        let preamble_name = Path::new(file_path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| file_path.to_string());
+
+        Ok(self.sections(source, file_path, &preamble_name))
+    }
+}
+
+/// Chunk for `lines[start..end]` with trailing blank lines dropped, or
+/// `None` if it's all blank
+fn section_chunk(
+    lines: &[&str],
+    start: usize,
+    end: usize,
+    file_path: &str,
+    name: impl FnOnce() -> String,
+) -> Option<CodeChunk> {
+    let section = lines.get(start..end)?;
+    let first = section.iter().position(|l| !l.trim().is_empty())?;
+    let last = section.iter().rposition(|l| !l.trim().is_empty())?;
+
+    Some(CodeChunk::new(
+        name(),
+        SymbolType::Section,
+        section[first..=last].join("\n"),
+        file_path,
+        start + first + 1,
+        start + last + 1,
+    ))
+}
+
+/// Index of the first line after a leading `---` ... `---` block
+fn front_matter_end(lines: &[&str]) -> usize {
+    if lines.first().map(|l| l.trim_end()) != Some("---") {
+        return 0;
+    }
+    lines
+        .iter()
+        .skip(1)
+        .position(|l| matches!(l.trim_end(), "---" | "..."))
+        .map_or(0, |i| i + 2)
+}
+
+/// Indentation of up to three spaces, which Markdown ignores
+fn strip_indent(line: &str) -> Option<&str> {
+    let trimmed = line.trim_start_matches(' ');
+    (line.len() - trimmed.len() <= 3).then_some(trimmed)
+}
+
+/// Marker character and length of a ``` or ~~~ fence line
+fn code_fence(line: &str) -> Option<(char, usize)> {
+    let trimmed = strip_indent(line)?;
+    let marker = trimmed.chars().next().filter(|c| *c == '`' || *c == '~')?;
+    let len = trimmed.chars().take_while(|c| *c == marker).count();
+    (len >= 3).then_some((marker, len))
+}
+
+/// Level and text of a `# Heading` line
+fn atx_heading(line: &str) -> Option<(usize, String)> {
+    let trimmed = strip_indent(line)?;
+    let level = trimmed.chars().take_while(|c| *c == '#').count();
+    if !(1..=6).contains(&level) {
+        return None;
+    }
+
+    let rest = &trimmed[level..];
+    if !rest.is_empty() && !rest.starts_with([' ', '\t']) {
+        return None;
+    }
+
+    // Drop an optional closing sequence (`## Title ##`)
+    let mut text = rest.trim();
+    let without_closing = text.trim_end_matches('#');
+    if without_closing.is_empty() || without_closing.ends_with([' ', '\t']) {
+        text = without_closing.trim_end();
+    }
+
+    (!text.is_empty()).then(|| (level, text.to_string()))
+}
+
+/// Level of a setext underline (`===` for 1, `---` for 2)
+fn setext_underline(line: &str) -> Option<usize> {
+    let trimmed = strip_indent(line)?.trim_end();
+    let marker = trimmed.chars().next()?;
+    if !trimmed.chars().all(|c| c == marker) {
+        return None;
+    }
+    match marker {
+        '=' => Some(1),
+        '-' => Some(2),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DOC: &str = r#"---
+title: Guide
+---
+Intro paragraph.
+
+# Installation
+
+Download a release.
+
+## Linux
+
+```bash
+# not a heading
+./install.sh
+```
+
+## macOS ##
+
+Use Homebrew.
+
+Usage
+=====
+
+Run `neuro --help`.
+
+### Empty
+
+#### Nested
+
+Deep text.
+"#;
+
+    fn analyze(source: &str) -> Vec<CodeChunk> {
+        MarkdownAnalyzer::new()
+            .analyze(source, "docs/guide.md")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_sections_follow_heading_hierarchy() {
+        let chunks = analyze(DOC);
+        let names: Vec<_> = chunks.iter().map(|c| c.name.as_str()).collect();
+        assert_eq!(
+            names,
+            ["guide", "Installation", "Linux", "macOS", "Usage", "Nested"]
+        );
+
+        let linux = &chunks[2];
+        assert_eq!(linux.parent.as_deref(), Some("Installation"));
+        assert!(linux.content.contains("# not a heading"));
+        assert_eq!(linux.display_name(), "Installation > Linux");
+
+        let usage = &chunks[4];
+        assert_eq!(usage.parent, None);
+        assert!(usage.content.starts_with("Usage\n====="));
+
+        let nested = &chunks[5];
+        assert_eq!(nested.parent.as_deref(), Some("Usage > Empty"));
+        assert!(chunks.iter().all(|c| c.symbol_type == SymbolType::Section));
+    }
+
+    #[test]
+    fn test_sections_do_not_overlap() {
+        let chunks = analyze(DOC);
+
+        let preamble = &chunks[0];
+        assert_eq!(preamble.content, "Intro paragraph.");
+        assert_eq!((preamble.start_line, preamble.end_line), (4, 4));
+
+        let installation = &chunks[1];
+        assert_eq!(
+            installation.content,
+            "# Installation\n\nDownload a release."
+        );
+        assert_eq!((installation.start_line, installation.end_line), (6, 8));
+    }
+
+    #[test]
+    fn test_heading_syntax() {
+        assert_eq!(atx_heading("## Title ##"), Some((2, "Title".to_string())));
+        assert_eq!(atx_heading("# C#"), Some((1, "C#".to_string())));
+        assert_eq!(atx_heading("#hashtag"), None);
+        assert_eq!(atx_heading("####### seven"), None);
+        assert_eq!(atx_heading("    # indented code"), None);
+
+        // A rule after a blank line is not a heading
+        let chunks = analyze("Text\n\n---\n\nMore text\n");
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].name, "guide");
+    }
+}
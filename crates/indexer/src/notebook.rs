@@ -0,0 +1,267 @@
+//! Jupyter notebook (`.ipynb`) analyzer
+//!
+//! Code cells go through the tree-sitter analyzer for the notebook's
+//! kernel language (Python unless the metadata says otherwise), markdown
+//! cells through [`MarkdownAnalyzer`]. Every chunk records the index of
+//! the cell it came from, and its lines count from the start of that cell.
+
+use serde::Deserialize;
+
+use crate::analyzer::{CodeAnalyzer, TreeSitterAnalyzer};
+use crate::chunk::{CodeChunk, SymbolType};
+use crate::error::{IndexerError, Result};
+use crate::languages::Language;
+use crate::markdown::MarkdownAnalyzer;
+
+/// Analyzer for Jupyter notebooks
+#[derive(Debug, Default)]
+pub struct NotebookAnalyzer;
+
+#[derive(Deserialize)]
+struct Notebook {
+    cells: Vec<Cell>,
+    #[serde(default)]
+    metadata: NotebookMetadata,
+}
+
+#[derive(Deserialize, Default)]
+struct NotebookMetadata {
+    kernelspec: Option<NameOrLanguage>,
+    language_info: Option<NameOrLanguage>,
+}
+
+#[derive(Deserialize)]
+struct NameOrLanguage {
+    language: Option<String>,
+    name: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Cell {
+    cell_type: String,
+    #[serde(default)]
+    source: Source,
+}
+
+/// Cell source: a single string or a list of lines (nbformat allows both)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Source {
+    Text(String),
+    Lines(Vec<String>),
+}
+
+impl Default for Source {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl Source {
+    fn text(self) -> String {
+        match self {
+            Self::Text(text) => text,
+            Self::Lines(lines) => lines.concat(),
+        }
+    }
+}
+
+impl NotebookMetadata {
+    /// Kernel language, Python if the notebook doesn't say, or `None`
+    /// when we have no tree-sitter grammar for it
+    fn language(&self) -> Option<Language> {
+        let info = self.language_info.as_ref().and_then(|i| i.name.as_deref());
+        let kernel = self.kernelspec.as_ref().and_then(|k| k.language.as_deref());
+        match info.or(kernel) {
+            None => Some(Language::Python),
+            Some(name) => name.parse().ok().filter(|l| {
+                !matches!(
+                    l,
+                    Language::Markdown
+                        | Language::Notebook
+                        | Language::Text
+                        | Language::Pdf
+                        | Language::Docx
+                )
+            }),
+        }
+    }
+}
+
+impl NotebookAnalyzer {
+    /// Create a new notebook analyzer
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl CodeAnalyzer for NotebookAnalyzer {
+    fn language(&self) -> Language {
+        Language::Notebook
+    }
+
+    fn analyze(&self, source: &str, file_path: &str) -> Result<Vec<CodeChunk>> {
+        let notebook: Notebook = serde_json::from_str(source)
+            .map_err(|e| IndexerError::ParseError(format!("Invalid notebook: {}", e)))?;
+
+        let code_analyzer = notebook
+            .metadata
+            .language()
+            .map(TreeSitterAnalyzer::new)
+            .transpose()?;
+        let markdown_analyzer = MarkdownAnalyzer::new();
+        let mut chunks = Vec::new();
+
+        for (index, cell) in notebook.cells.into_iter().enumerate() {
+            let text = cell.source.text();
+            if text.trim().is_empty() {
+                continue;
+            }
+            let cell_name = format!("cell {}", index);
+
+            let cell_chunks = match cell.cell_type.as_str() {
+                "code" => {
+                    // Most notebook code is top-level script, so the whole cell
+                    // is a chunk, with any functions and classes in it after
+                    let whole = CodeChunk::new(
+                        cell_name.as_str(),
+                        SymbolType::Other,
+                        text.trim_end(),
+                        file_path,
+                        1,
+                        text.trim_end().lines().count().max(1),
+                    );
+                    let symbols = match &code_analyzer {
+                        Some(analyzer) => analyzer.analyze(&text, file_path)?,
+                        None => Vec::new(),
+                    };
+                    std::iter::once(whole)
+                        .chain(symbols.into_iter().map(|chunk| match chunk.parent {
+                            Some(_) => chunk,
+                            None => chunk.with_parent(cell_name.as_str()),
+                        }))
+                        .collect()
+                }
+                "markdown" => markdown_analyzer.sections(&text, file_path, &cell_name),
+                // Raw cells hold unrendered output, nothing to search
+                _ => Vec::new(),
+            };
+
+            chunks.extend(cell_chunks.into_iter().map(|chunk| chunk.with_cell(index)));
+        }
+
+        Ok(chunks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOTEBOOK: &str = r##"{
+        "cells": [
+            {
+                "cell_type": "markdown",
+                "metadata": {},
+                "source": ["# Churn analysis\n", "\n", "Load the data first."]
+            },
+            {
+                "cell_type": "code",
+                "execution_count": 1,
+                "metadata": {},
+                "outputs": [{"output_type": "stream", "text": ["ok\n"]}],
+                "source": [
+                    "import pandas as pd\n",
+                    "\n",
+                    "def load(path):\n",
+                    "    return pd.read_csv(path)\n",
+                    "\n",
+                    "df = load('churn.csv')"
+                ]
+            },
+            {
+                "cell_type": "code",
+                "execution_count": null,
+                "metadata": {},
+                "outputs": [],
+                "source": ""
+            },
+            {
+                "cell_type": "code",
+                "execution_count": 2,
+                "metadata": {},
+                "outputs": [],
+                "source": "df.groupby('plan').churned.mean()"
+            }
+        ],
+        "metadata": {
+            "kernelspec": {"name": "python3", "language": "python", "display_name": "Python 3"},
+            "language_info": {"name": "python"}
+        },
+        "nbformat": 4,
+        "nbformat_minor": 5
+    }"##;
+
+    fn analyze(source: &str) -> Vec<CodeChunk> {
+        NotebookAnalyzer::new()
+            .analyze(source, "churn.ipynb")
+            .unwrap()
+    }
+
+    #[test]
+    fn test_cells_are_chunked_by_type() {
+        let chunks = analyze(NOTEBOOK);
+
+        let heading = &chunks[0];
+        assert_eq!(heading.name, "Churn analysis");
+        assert_eq!(heading.symbol_type, SymbolType::Section);
+        assert_eq!(heading.cell, Some(0));
+
+        let cell = &chunks[1];
+        assert_eq!(cell.name, "cell 1");
+        assert_eq!(cell.symbol_type, SymbolType::Other);
+        assert!(cell.content.starts_with("import pandas"));
+        assert_eq!((cell.start_line, cell.end_line), (1, 6));
+
+        let load = &chunks[2];
+        assert_eq!(load.name, "load");
+        assert_eq!(load.symbol_type, SymbolType::Function);
+        assert_eq!(load.display_name(), "cell 1::load");
+        assert_eq!((load.start_line, load.cell), (3, Some(1)));
+
+        // The empty cell is skipped, but indices keep counting it
+        let last = &chunks[3];
+        assert_eq!(last.name, "cell 3");
+        assert_eq!(last.cell, Some(3));
+        assert_eq!(chunks.len(), 4);
+    }
+
+    #[test]
+    fn test_kernel_language() {
+        let notebook = r#"{
+            "cells": [{"cell_type": "code", "source": "fn main() {}"}],
+            "metadata": {"language_info": {"name": "rust"}}
+        }"#;
+        let chunks = analyze(notebook);
+        assert!(chunks
+            .iter()
+            .any(|c| c.name == "main" && c.symbol_type == SymbolType::Function));
+    }
+
+    #[test]
+    fn test_unsupported_kernel_keeps_whole_cells() {
+        let notebook = r#"{
+            "cells": [{"cell_type": "code", "source": "f <- function(x) x + 1"}],
+            "metadata": {"kernelspec": {"name": "ir", "language": "R"}}
+        }"#;
+        let chunks = analyze(notebook);
+        assert_eq!(chunks.len(), 1);
+        assert_eq!(chunks[0].name, "cell 0");
+    }
+
+    #[test]
+    fn test_invalid_notebook() {
+        let result = NotebookAnalyzer::new().analyze("not json", "broken.ipynb");
+        assert!(matches!(result, Err(IndexerError::ParseError(_))));
+    }
+}
@@ -0,0 +1,420 @@
+//! Indexing straight into storage
+//!
+//! [`index_to_storage`] runs the whole path from source files to stored
+//! documents: analysis, call linking and splitting, embedding in batches,
+//! and writing documents with their symbol metadata. Document IDs are
+//! derived from the chunk's file and content, so re-running it only embeds
+//! what changed and drops chunks that no longer exist.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use futures::StreamExt;
+use neuro_core::{Document, DocumentSource};
+use neuro_embeddings::{AsyncEmbedder, BatchProgress, CancellationToken, EmbeddingError};
+use neuro_storage::Storage;
+use tracing::{debug, info, warn};
+
+use crate::chunk::CodeChunk;
+use crate::error::{IndexerError, Result};
+use crate::indexer::{CodeIndexer, IndexerConfig};
+use crate::languages::Language;
+
+/// Default number of chunks embedded per batch
+pub const DEFAULT_PIPELINE_BATCH_SIZE: usize = 32;
+
+/// Options for [`index_to_storage`]
+#[derive(Debug, Clone)]
+pub struct PipelineOptions {
+    /// Indexer settings: skipped paths, ignore files, chunk splitting
+    pub indexer: IndexerConfig,
+    /// Descend into subdirectories
+    pub recursive: bool,
+    /// Only index files whose name contains one of these; empty indexes
+    /// every supported file
+    pub include: Vec<String>,
+    /// Skip files whose name contains one of these
+    pub exclude: Vec<String>,
+    /// Number of chunks embedded per call
+    pub batch_size: usize,
+    /// Tags added to every stored document
+    pub tags: Vec<String>,
+    /// Stop between batches once cancelled
+    pub cancellation: Option<CancellationToken>,
+}
+
+impl Default for PipelineOptions {
+    fn default() -> Self {
+        Self {
+            indexer: IndexerConfig::default(),
+            recursive: true,
+            include: Vec::new(),
+            exclude: Vec::new(),
+            batch_size: DEFAULT_PIPELINE_BATCH_SIZE,
+            tags: Vec::new(),
+            cancellation: None,
+        }
+    }
+}
+
+/// Summary of an [`index_to_storage`] run
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PipelineReport {
+    /// Files analyzed
+    pub files: usize,
+    /// Chunks found in those files
+    pub chunks: usize,
+    /// Chunks embedded and stored
+    pub stored: usize,
+    /// Chunks already stored with the same content, left as they were
+    pub unchanged: usize,
+    /// Stored chunks deleted because their code changed or disappeared
+    pub removed: usize,
+    /// Files that couldn't be read or parsed
+    pub errors: usize,
+}
+
+/// Index `path` (a file or directory) and store every chunk as a
+/// [`DocumentSource::Code`] document in `storage`
+///
+/// Chunks whose document is already stored are not embedded again, and
+/// stored chunks of the indexed files (or of files deleted from under
+/// `path`) that no longer exist are removed. `on_progress` is called after
+/// each embedded batch.
+///
+/// When cancelled, batches stored so far are kept, stale chunks are not
+/// yet removed, and [`EmbeddingError::Cancelled`] is returned.
+pub async fn index_to_storage<F>(
+    path: &Path,
+    embedder: &dyn AsyncEmbedder,
+    storage: &mut dyn Storage,
+    options: &PipelineOptions,
+    mut on_progress: F,
+) -> Result<PipelineReport>
+where
+    F: FnMut(BatchProgress),
+{
+    if !path.exists() {
+        return Err(IndexerError::FileNotFound(path.display().to_string()));
+    }
+
+    // Parsing is blocking work
+    let (files, chunks, errors) = {
+        let path = path.to_path_buf();
+        let options = options.clone();
+        tokio::task::spawn_blocking(move || analyze(&path, &options))
+            .await
+            .map_err(|e| IndexerError::ParseError(e.to_string()))?
+    };
+
+    let mut report = PipelineReport {
+        files: files.len(),
+        chunks: chunks.len(),
+        errors,
+        ..Default::default()
+    };
+
+    // Pair chunks with their documents' IDs, dropping repeats
+    let mut ids = HashSet::new();
+    let mut pending = Vec::new();
+    for chunk in chunks {
+        let content = chunk.to_document_content();
+        let id = document_id(&chunk.file_path, &content);
+        if !ids.insert(id.clone()) {
+            continue;
+        }
+        if storage.exists(&id).await {
+            report.unchanged += 1;
+        } else {
+            pending.push((id, content, chunk));
+        }
+    }
+
+    // Stale chunks: gone from an indexed file, or from a deleted file
+    let indexed: HashSet<String> = files.iter().map(|f| f.display().to_string()).collect();
+    let mut stale = Vec::new();
+    let mut documents = storage.iter();
+    while let Some(doc) = documents.next().await {
+        if doc.source != DocumentSource::Code || ids.contains(&doc.id) {
+            continue;
+        }
+        let Some(file) = doc.metadata.get("file_path").and_then(|v| v.as_str()) else {
+            continue;
+        };
+        let deleted = Path::new(file).starts_with(path) && !Path::new(file).exists();
+        if indexed.contains(file) || deleted {
+            stale.push(doc.id);
+        }
+    }
+    drop(documents);
+
+    // Embed and store the rest, batch by batch
+    let model = embedder.model_name();
+    let mut progress = BatchProgress {
+        completed: 0,
+        total: pending.len(),
+    };
+    for batch in pending.chunks(options.batch_size.max(1)) {
+        if options
+            .cancellation
+            .as_ref()
+            .is_some_and(|t| t.is_cancelled())
+        {
+            return Err(EmbeddingError::Cancelled {
+                completed: progress.completed,
+                total: progress.total,
+            }
+            .into());
+        }
+
+        let texts: Vec<&str> = batch
+            .iter()
+            .map(|(_, content, _)| content.as_str())
+            .collect();
+        let embeddings = embedder.embed_passages(&texts).await?;
+
+        let documents = batch
+            .iter()
+            .zip(embeddings)
+            .map(|((id, content, chunk), embedding)| {
+                chunk_document(id, content, chunk, embedding, &model, &options.tags)
+            })
+            .collect();
+        storage.add_batch(documents).await?;
+
+        report.stored += batch.len();
+        progress.completed += batch.len();
+        on_progress(progress);
+    }
+
+    // Only now, so a failed run leaves the old chunks searchable
+    for id in stale {
+        storage.delete(&id).await?;
+        report.removed += 1;
+    }
+
+    info!(
+        "Indexed {} files into storage: {} stored, {} unchanged, {} removed",
+        report.files, report.stored, report.unchanged, report.removed
+    );
+    Ok(report)
+}
+
+/// Files analyzed, their chunks, and the number that failed
+fn analyze(path: &Path, options: &PipelineOptions) -> (Vec<PathBuf>, Vec<CodeChunk>, usize) {
+    let indexer = CodeIndexer::with_config(options.indexer.clone());
+    let candidates = if path.is_file() {
+        vec![path.to_path_buf()]
+    } else {
+        indexer.files(path, options.recursive)
+    };
+
+    let mut files = Vec::new();
+    let mut chunks = Vec::new();
+    let mut errors = 0;
+    for file in candidates {
+        if Language::from_path(&file).is_none() || !is_included(&file, options) {
+            continue;
+        }
+        match indexer.index_file_auto(&file) {
+            Ok(file_chunks) => {
+                chunks.extend(file_chunks);
+                files.push(file);
+            }
+            Err(e) => {
+                warn!("Failed to index {:?}: {}", file, e);
+                errors += 1;
+            }
+        }
+    }
+
+    CodeIndexer::link_calls(&mut chunks);
+    debug!(
+        "Analyzed {} files into {} chunks",
+        files.len(),
+        chunks.len()
+    );
+    (files, chunks, errors)
+}
+
+fn is_included(file: &Path, options: &PipelineOptions) -> bool {
+    let name = file
+        .file_name()
+        .map(|n| n.to_string_lossy())
+        .unwrap_or_default();
+    let path_str = file.display().to_string();
+
+    !options
+        .indexer
+        .skip_patterns
+        .iter()
+        .any(|p| path_str.contains(p))
+        && !options.exclude.iter().any(|p| name.contains(p.as_str()))
+        && (options.include.is_empty() || options.include.iter().any(|p| name.contains(p.as_str())))
+}
+
+/// Stable ID for a chunk's document, from its file and content
+///
+/// FNV-1a rather than `DefaultHasher`, whose output may change between
+/// Rust releases.
+fn document_id(file_path: &str, content: &str) -> String {
+    let mut hash: u64 = 0xcbf2_9ce4_8422_2325;
+    for byte in file_path.bytes().chain([0]).chain(content.bytes()) {
+        hash ^= u64::from(byte);
+        hash = hash.wrapping_mul(0x0100_0000_01b3);
+    }
+    format!("code-{:016x}", hash)
+}
+
+/// Document for `chunk` with its location and symbol as metadata
+fn chunk_document(
+    id: &str,
+    content: &str,
+    chunk: &CodeChunk,
+    embedding: Vec<f32>,
+    model: &str,
+    tags: &[String],
+) -> Document {
+    let mut doc = Document::with_id(id, content)
+        .with_embedding(embedding)
+        .with_embedding_model(model)
+        .with_source(DocumentSource::Code)
+        .with_tags(tags.iter().cloned())
+        .with_metadata("file_path", chunk.file_path.clone().into())
+        .with_metadata("symbol", chunk.display_name().into())
+        .with_metadata("symbol_type", chunk.symbol_type.to_string().into())
+        .with_metadata("start_line", chunk.start_line.into())
+        .with_metadata("end_line", chunk.end_line.into());
+
+    if let Some(name) = Path::new(&chunk.file_path).file_name() {
+        doc = doc.with_metadata("file_name", name.to_string_lossy().to_string().into());
+    }
+    if let Some(language) = Language::from_path(Path::new(&chunk.file_path)) {
+        doc = doc.with_metadata("language", language.name().to_lowercase().into());
+    }
+    if let Some(cell) = chunk.cell {
+        doc = doc.with_metadata("cell", cell.into());
+    }
+    if let Some(page) = chunk.page {
+        doc = doc.with_metadata("page", page.into());
+    }
+    if let Some((part, parts)) = chunk.part {
+        doc = doc
+            .with_metadata("part", part.into())
+            .with_metadata("parts", parts.into());
+    }
+    if !chunk.calls.is_empty() {
+        doc = doc.with_metadata("calls", chunk.calls.clone().into());
+    }
+    if !chunk.called_by.is_empty() {
+        doc = doc.with_metadata("called_by", chunk.called_by.clone().into());
+    }
+    doc
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neuro_embeddings::{Embedder, EmbeddingModel, SpawnBlockingEmbedder};
+    use neuro_storage::{DocumentFilter, MemoryStorage};
+    use std::fs;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tempfile::tempdir;
+
+    /// Counts the texts it embeds
+    #[derive(Default)]
+    struct CountingEmbedder {
+        embedded: AtomicUsize,
+    }
+
+    impl Embedder for CountingEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::AllMiniLmL6V2
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            self.embedded.fetch_add(1, Ordering::SeqCst);
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_index_to_storage_is_incremental() {
+        let dir = tempdir().unwrap();
+        let lib = dir.path().join("lib.rs");
+        fs::write(&lib, "fn load() {}\n\nfn save() { load(); }\n").unwrap();
+        fs::write(dir.path().join("util.py"), "def helper():\n    pass\n").unwrap();
+
+        let counter = Arc::new(CountingEmbedder::default());
+        let embedder = SpawnBlockingEmbedder::new(counter.clone());
+        let mut storage = MemoryStorage::new();
+        let options = PipelineOptions {
+            batch_size: 2,
+            ..Default::default()
+        };
+
+        let mut batches = Vec::new();
+        let report = index_to_storage(dir.path(), &embedder, &mut storage, &options, |p| {
+            batches.push(p.completed)
+        })
+        .await
+        .unwrap();
+        assert_eq!((report.files, report.stored), (2, 3));
+        assert_eq!(batches, [2, 3]);
+
+        let save = storage
+            .list_filtered(
+                &DocumentFilter::new().with_metadata("symbol", serde_json::json!("save")),
+            )
+            .await
+            .unwrap();
+        assert_eq!(save.len(), 1);
+        assert_eq!(save[0].source, DocumentSource::Code);
+        assert_eq!(save[0].metadata["calls"], serde_json::json!(["load"]));
+
+        // Change one function, delete a file: only the change is embedded
+        fs::write(&lib, "fn load() {}\n\nfn save() { load(); load(); }\n").unwrap();
+        fs::remove_file(dir.path().join("util.py")).unwrap();
+        let before = counter.embedded.load(Ordering::SeqCst);
+
+        let report = index_to_storage(dir.path(), &embedder, &mut storage, &options, |_| {})
+            .await
+            .unwrap();
+        assert_eq!((report.stored, report.unchanged, report.removed), (1, 1, 2));
+        assert_eq!(counter.embedded.load(Ordering::SeqCst) - before, 1);
+        assert_eq!(storage.count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_cancelled_before_embedding() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}\n").unwrap();
+
+        let embedder = SpawnBlockingEmbedder::new(Arc::new(CountingEmbedder::default()));
+        let mut storage = MemoryStorage::new();
+        let cancellation = CancellationToken::new();
+        cancellation.cancel();
+        let options = PipelineOptions {
+            cancellation: Some(cancellation),
+            ..Default::default()
+        };
+
+        let result = index_to_storage(dir.path(), &embedder, &mut storage, &options, |_| {}).await;
+        assert!(matches!(
+            result,
+            Err(IndexerError::Embedding(EmbeddingError::Cancelled {
+                completed: 0,
+                ..
+            }))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+//! Splitting of oversized chunks into overlapping windows
+//!
+//! A symbol too large to embed in one piece is cut into consecutive line
+//! windows that each stay under the size limit, with the last few lines of
+//! one window repeated at the start of the next so no statement loses its
+//! context. Every window keeps the symbol's name, parent and metadata.
+
+use crate::chunk::CodeChunk;
+
+/// Rough bytes per token for source code, used for token limits
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Size of a chunk, in lines or estimated tokens
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChunkSize {
+    /// Number of lines
+    Lines(usize),
+    /// Estimated number of tokens (about four bytes each)
+    Tokens(usize),
+}
+
+impl ChunkSize {
+    fn limit(self) -> usize {
+        match self {
+            Self::Lines(n) | Self::Tokens(n) => n,
+        }
+    }
+
+    /// Cost of one line in this unit
+    fn cost(self, line: &str) -> usize {
+        match self {
+            Self::Lines(_) => 1,
+            // +1 for the newline
+            Self::Tokens(_) => (line.len() + 1).div_ceil(BYTES_PER_TOKEN),
+        }
+    }
+}
+
+/// Split `chunk` into windows of at most `max` with about `overlap` (in
+/// the same unit) repeated between neighbours
+///
+/// Chunks that already fit are returned unchanged. A single line larger
+/// than the limit becomes a window of its own.
+pub(crate) fn split_chunk(chunk: CodeChunk, max: ChunkSize, overlap: usize) -> Vec<CodeChunk> {
+    let lines: Vec<&str> = chunk.content.lines().collect();
+    let costs: Vec<usize> = lines.iter().map(|line| max.cost(line)).collect();
+    let limit = max.limit().max(1);
+    if costs.iter().sum::<usize>() <= limit {
+        return vec![chunk];
+    }
+
+    let mut windows = Vec::new();
+    let mut start = 0;
+    loop {
+        let mut end = start;
+        let mut size = 0;
+        while end < lines.len() && (end == start || size + costs[end] <= limit) {
+            size += costs[end];
+            end += 1;
+        }
+        windows.push((start, end));
+        if end == lines.len() {
+            break;
+        }
+
+        // Back up from the end by up to `overlap`, always moving forward
+        let mut next = end;
+        let mut repeated = 0;
+        while next > start + 1 && repeated + costs[next - 1] <= overlap {
+            next -= 1;
+            repeated += costs[next];
+        }
+        start = next;
+    }
+
+    let total = windows.len();
+    windows
+        .into_iter()
+        .enumerate()
+        .map(|(i, (start, end))| {
+            let mut part = chunk.clone();
+            part.content = lines[start..end].join("\n");
+            part.start_line = chunk.start_line + start;
+            part.end_line = chunk.start_line + end - 1;
+            part.part = Some((i + 1, total));
+            part
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::chunk::SymbolType;
+
+    fn function(lines: usize) -> CodeChunk {
+        let content: Vec<String> = (0..lines).map(|i| format!("    step_{}();", i)).collect();
+        CodeChunk::new(
+            "process",
+            SymbolType::Function,
+            content.join("\n"),
+            "big.rs",
+            101,
+            100 + lines,
+        )
+        .with_parent("Pipeline")
+    }
+
+    #[test]
+    fn test_small_chunks_are_unchanged() {
+        let parts = split_chunk(function(10), ChunkSize::Lines(10), 2);
+        assert_eq!(parts.len(), 1);
+        assert_eq!(parts[0].part, None);
+    }
+
+    #[test]
+    fn test_split_by_lines_with_overlap() {
+        let parts = split_chunk(function(25), ChunkSize::Lines(10), 2);
+
+        let ranges: Vec<_> = parts.iter().map(|p| (p.start_line, p.end_line)).collect();
+        assert_eq!(ranges, [(101, 110), (109, 118), (117, 125)]);
+        assert_eq!(parts[1].part, Some((2, 3)));
+        assert!(parts[1].content.starts_with("    step_8();"));
+        assert_eq!(parts[2].content.lines().count(), 9);
+        assert!(parts
+            .iter()
+            .all(|p| p.display_name() == "Pipeline::process" && p.line_count() <= 10));
+    }
+
+    #[test]
+    fn test_split_by_tokens() {
+        // Each line is 14 or 15 bytes with its newline, so 4 tokens
+        let parts = split_chunk(function(20), ChunkSize::Tokens(20), 0);
+        assert_eq!(parts.len(), 4);
+        assert!(parts.iter().all(|p| p.line_count() == 5));
+        assert_eq!(parts[3].end_line, 120);
+    }
+
+    #[test]
+    fn test_overlap_always_makes_progress() {
+        let parts = split_chunk(function(6), ChunkSize::Lines(2), 5);
+        let starts: Vec<_> = parts.iter().map(|p| p.start_line).collect();
+        assert_eq!(starts, [101, 102, 103, 104, 105]);
+    }
+}
@@ -0,0 +1,206 @@
+//! Symbol lookup by name and kind
+//!
+//! Embedding search is good at "code that validates tokens" but poor at
+//! "where is `validate_token`". [`SymbolQuery`] matches chunk names
+//! directly, exactly, by prefix or as a fuzzy subsequence.
+
+use std::str::FromStr;
+
+use crate::chunk::{CodeChunk, SymbolType};
+
+/// How a query's name is compared with symbol names
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NameMatch {
+    /// The whole name, case-sensitive
+    #[default]
+    Exact,
+    /// The start of the name, case-sensitive
+    Prefix,
+    /// The query's characters appear in order, ignoring case
+    /// (`pcfg` finds `parse_config`)
+    Fuzzy,
+}
+
+/// A symbol lookup: a name, how to match it, and optionally a kind
+///
+/// Parses from queries like `parse_config`, `fn parse_*` or
+/// `class Model`: a leading kind keyword restricts the symbol type and a
+/// trailing `*` makes it a prefix match. Names containing `::` or ` > `
+/// are matched against the qualified name (`Model::layers`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SymbolQuery {
+    /// Name, prefix or fuzzy pattern to look for
+    pub name: String,
+    /// How `name` is matched
+    pub matching: NameMatch,
+    /// Only symbols of this kind
+    pub symbol_type: Option<SymbolType>,
+}
+
+impl SymbolQuery {
+    /// Query for symbols named exactly `name`
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            matching: NameMatch::Exact,
+            symbol_type: None,
+        }
+    }
+
+    /// Set how the name is matched
+    pub fn with_matching(mut self, matching: NameMatch) -> Self {
+        self.matching = matching;
+        self
+    }
+
+    /// Only match symbols of `symbol_type`
+    pub fn with_symbol_type(mut self, symbol_type: SymbolType) -> Self {
+        self.symbol_type = Some(symbol_type);
+        self
+    }
+
+    /// How well `chunk` matches, higher is better, or `None` if it doesn't
+    pub fn score(&self, chunk: &CodeChunk) -> Option<u32> {
+        if self
+            .symbol_type
+            .is_some_and(|symbol_type| symbol_type != chunk.symbol_type)
+        {
+            return None;
+        }
+
+        let qualified = self.name.contains("::") || self.name.contains(" > ");
+        let display_name;
+        let name = if qualified {
+            display_name = chunk.display_name();
+            display_name.as_str()
+        } else {
+            chunk.name.as_str()
+        };
+
+        match self.matching {
+            NameMatch::Exact => (name == self.name).then_some(1),
+            NameMatch::Prefix if name == self.name => Some(2),
+            NameMatch::Prefix => name.starts_with(&self.name).then_some(1),
+            NameMatch::Fuzzy => fuzzy_score(&self.name, name),
+        }
+    }
+}
+
+impl FromStr for SymbolQuery {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        let s = s.trim();
+        let (symbol_type, name) = match s.split_once(char::is_whitespace) {
+            Some((kind, rest)) if !rest.trim().is_empty() => match kind.parse::<SymbolType>() {
+                Ok(symbol_type) => (Some(symbol_type), rest.trim()),
+                Err(_) => (None, s),
+            },
+            _ => (None, s),
+        };
+
+        let (name, matching) = match name.strip_suffix('*') {
+            Some(prefix) => (prefix, NameMatch::Prefix),
+            None => (name, NameMatch::Exact),
+        };
+        if name.is_empty() {
+            return Err(format!("Empty symbol name in query: {:?}", s));
+        }
+
+        Ok(Self {
+            name: name.to_string(),
+            matching,
+            symbol_type,
+        })
+    }
+}
+
+/// Score for `pattern` as a case-insensitive subsequence of `name`
+///
+/// Each matched character scores a point, with bonuses for runs of
+/// consecutive characters and for characters that start a word
+/// (`parse_config`, `parseConfig`).
+fn fuzzy_score(pattern: &str, name: &str) -> Option<u32> {
+    let chars: Vec<char> = name.chars().collect();
+    let same = |a: char, b: char| a.to_lowercase().eq(b.to_lowercase());
+
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+    for p in pattern.chars().filter(|c| !c.is_whitespace()) {
+        let i = (next..chars.len()).find(|&i| same(chars[i], p))?;
+        score += 1;
+        if previous.is_some_and(|prev| prev + 1 == i) {
+            score += 3;
+        }
+        let word_start = i == 0
+            || matches!(chars[i - 1], '_' | '-' | ':' | '.' | ' ')
+            || (chars[i - 1].is_lowercase() && chars[i].is_uppercase());
+        if word_start {
+            score += 2;
+        }
+        previous = Some(i);
+        next = i + 1;
+    }
+
+    Some(score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(name: &str, symbol_type: SymbolType) -> CodeChunk {
+        CodeChunk::new(name, symbol_type, "", "lib.rs", 1, 1)
+    }
+
+    #[test]
+    fn test_parse_query() {
+        let query: SymbolQuery = "fn parse_*".parse().unwrap();
+        assert_eq!(
+            query,
+            SymbolQuery::new("parse_")
+                .with_matching(NameMatch::Prefix)
+                .with_symbol_type(SymbolType::Function)
+        );
+
+        // A lone word is a name, even if it's also a kind
+        let query: SymbolQuery = "impl".parse().unwrap();
+        assert_eq!(query, SymbolQuery::new("impl"));
+
+        assert!("class *".parse::<SymbolQuery>().is_err());
+    }
+
+    #[test]
+    fn test_match_modes() {
+        let parse_config = chunk("parse_config", SymbolType::Function);
+        let config = chunk("Config", SymbolType::Struct);
+
+        let exact = SymbolQuery::new("parse_config");
+        assert!(exact.score(&parse_config).is_some());
+        assert!(exact.score(&config).is_none());
+
+        let prefix = SymbolQuery::new("parse").with_matching(NameMatch::Prefix);
+        assert!(prefix.score(&parse_config).is_some());
+        assert!(prefix
+            .clone()
+            .with_symbol_type(SymbolType::Struct)
+            .score(&parse_config)
+            .is_none());
+
+        let fuzzy = SymbolQuery::new("pcfg").with_matching(NameMatch::Fuzzy);
+        assert!(fuzzy.score(&parse_config).is_some());
+        assert!(fuzzy.score(&config).is_none());
+
+        let method = chunk("layers", SymbolType::Function).with_parent("Model");
+        assert!(SymbolQuery::new("Model::layers").score(&method).is_some());
+    }
+
+    #[test]
+    fn test_fuzzy_prefers_word_starts() {
+        let query = SymbolQuery::new("pc").with_matching(NameMatch::Fuzzy);
+        let word_starts = query.score(&chunk("parse_config", SymbolType::Function));
+        let scattered = query.score(&chunk("spec", SymbolType::Function));
+        assert!(word_starts > scattered);
+    }
+}
@@ -0,0 +1,304 @@
+//! Filesystem watching with debounced re-analysis
+//!
+//! [`IndexWatcher`] collects filesystem events for a directory tree, waits
+//! until they settle for the debounce interval, drops paths the indexer
+//! would skip, and re-analyzes what changed.
+
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, Receiver, RecvTimeoutError};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::chunk::CodeChunk;
+use crate::error::{IndexerError, Result};
+use crate::indexer::{CodeIndexer, IndexerConfig};
+use crate::languages::Language;
+
+/// Files that changed during one debounce window
+#[derive(Debug, Default)]
+pub struct WatchUpdate {
+    /// Chunks from the changed files in a supported language
+    pub chunks: Vec<CodeChunk>,
+    /// Files created or modified, in any language
+    pub changed: Vec<PathBuf>,
+    /// Files that no longer exist
+    pub removed: Vec<PathBuf>,
+}
+
+impl WatchUpdate {
+    /// Whether nothing changed
+    pub fn is_empty(&self) -> bool {
+        self.changed.is_empty() && self.removed.is_empty()
+    }
+}
+
+/// Watches a directory tree and re-analyzes files as they change
+///
+/// Iterating blocks until the next non-empty update. Hidden paths,
+/// `skip_dirs`, `skip_patterns` and paths matched by `.gitignore` or the
+/// configured ignore files are left out.
+pub struct IndexWatcher {
+    root: PathBuf,
+    indexer: CodeIndexer,
+    config: IndexerConfig,
+    events: Receiver<DebounceEventResult>,
+    /// Ignore rules per directory, loaded on first use
+    ignores: HashMap<PathBuf, Gitignore>,
+    _debouncer: Debouncer<RecommendedWatcher>,
+}
+
+impl IndexWatcher {
+    pub(crate) fn new(root: &Path, config: IndexerConfig, debounce: Duration) -> Result<Self> {
+        let root = root
+            .canonicalize()
+            .map_err(|_| IndexerError::FileNotFound(root.display().to_string()))?;
+
+        let (tx, events) = channel();
+        let mut debouncer =
+            new_debouncer(debounce, tx).map_err(|e| IndexerError::Watch(e.to_string()))?;
+        debouncer
+            .watcher()
+            .watch(&root, RecursiveMode::Recursive)
+            .map_err(|e| IndexerError::Watch(e.to_string()))?;
+
+        Ok(Self {
+            root,
+            indexer: CodeIndexer::with_config(config.clone()),
+            config,
+            events,
+            ignores: HashMap::new(),
+            _debouncer: debouncer,
+        })
+    }
+
+    /// Root directory being watched
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Wait up to `timeout` for the next update
+    ///
+    /// Returns `None` if nothing changed in time or the watcher stopped.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<Result<WatchUpdate>> {
+        let deadline = std::time::Instant::now() + timeout;
+        loop {
+            let remaining = deadline.saturating_duration_since(std::time::Instant::now());
+            match self.events.recv_timeout(remaining) {
+                Ok(events) => {
+                    if let Some(update) = self.process(events) {
+                        return Some(update);
+                    }
+                }
+                Err(RecvTimeoutError::Timeout | RecvTimeoutError::Disconnected) => return None,
+            }
+        }
+    }
+
+    /// Turn a batch of events into an update, or `None` if every path
+    /// was filtered out
+    fn process(&mut self, events: DebounceEventResult) -> Option<Result<WatchUpdate>> {
+        let events = match events {
+            Ok(events) => events,
+            Err(e) => return Some(Err(IndexerError::Watch(e.to_string()))),
+        };
+
+        let paths: BTreeSet<PathBuf> = events.into_iter().map(|e| e.path).collect();
+
+        // Edited ignore files change what's skipped from now on
+        if paths.iter().any(|p| self.is_ignore_file(p)) {
+            self.ignores.clear();
+        }
+
+        let mut update = WatchUpdate::default();
+        for path in paths {
+            if path.is_dir() {
+                // A new or moved-in directory; its files may predate the watch
+                if !self.is_excluded(&path, true) {
+                    update.changed.extend(self.indexer.files(&path, true));
+                }
+            } else if self.is_excluded(&path, false) {
+                continue;
+            } else if path.is_file() {
+                update.changed.push(path);
+            } else {
+                update.removed.push(path);
+            }
+        }
+        update.changed.sort();
+        update.changed.dedup();
+
+        if update.is_empty() {
+            return None;
+        }
+
+        for file in &update.changed {
+            if Language::from_path(file).is_none() {
+                continue;
+            }
+            match self.indexer.index_file_auto(file) {
+                Ok(chunks) => update.chunks.extend(chunks),
+                Err(e) => warn!("Failed to index {:?}: {}", file, e),
+            }
+        }
+
+        debug!(
+            "Watch update: {} changed, {} removed",
+            update.changed.len(),
+            update.removed.len()
+        );
+        Some(Ok(update))
+    }
+
+    fn is_ignore_file(&self, path: &Path) -> bool {
+        path.file_name().is_some_and(|name| {
+            name == ".gitignore" || self.config.ignore_files.iter().any(|f| name == f.as_str())
+        })
+    }
+
+    /// Whether the indexer would skip `path`
+    fn is_excluded(&mut self, path: &Path, is_dir: bool) -> bool {
+        let Ok(relative) = path.strip_prefix(&self.root) else {
+            return true;
+        };
+
+        let hidden_or_skipped = relative.components().any(|c| {
+            let name = c.as_os_str().to_string_lossy();
+            name.starts_with('.') || self.config.skip_dirs.iter().any(|d| name == d.as_str())
+        });
+        let path_str = path.display().to_string();
+        if hidden_or_skipped
+            || self
+                .config
+                .skip_patterns
+                .iter()
+                .any(|p| path_str.contains(p))
+        {
+            return true;
+        }
+
+        // The deepest ignore file with an opinion wins, as in git
+        let dirs: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|dir| dir.starts_with(&self.root))
+            .collect();
+        for dir in dirs {
+            let matched = self
+                .ignore_rules(dir)
+                .matched_path_or_any_parents(path, is_dir);
+            if !matched.is_none() {
+                return matched.is_ignore();
+            }
+        }
+        false
+    }
+
+    /// Ignore rules declared in `dir`
+    fn ignore_rules(&mut self, dir: &Path) -> &Gitignore {
+        if !self.ignores.contains_key(dir) {
+            let rules = self.load_ignore_rules(dir);
+            self.ignores.insert(dir.to_path_buf(), rules);
+        }
+        &self.ignores[dir]
+    }
+
+    fn load_ignore_rules(&self, dir: &Path) -> Gitignore {
+        let mut builder = GitignoreBuilder::new(dir);
+        let gitignore = self.config.respect_gitignore.then_some(".gitignore");
+        for name in gitignore
+            .into_iter()
+            .chain(self.config.ignore_files.iter().map(String::as_str))
+        {
+            let file = dir.join(name);
+            if file.is_file() {
+                if let Some(e) = builder.add(&file) {
+                    warn!("Invalid ignore file {:?}: {}", file, e);
+                }
+            }
+        }
+        builder.build().unwrap_or_else(|_| Gitignore::empty())
+    }
+}
+
+impl Iterator for IndexWatcher {
+    type Item = Result<WatchUpdate>;
+
+    /// Block until the next update; `None` once the watcher stops
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let events = self.events.recv().ok()?;
+            if let Some(update) = self.process(events) {
+                return Some(update);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::tempdir;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    fn watcher(root: &Path) -> IndexWatcher {
+        CodeIndexer::new()
+            .watch(root, Duration::from_millis(100))
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reports_changed_and_removed_files() {
+        let dir = tempdir().unwrap();
+        let doomed = dir.path().join("doomed.py");
+        fs::write(&doomed, "def doomed(): pass").unwrap();
+
+        let mut watcher = watcher(dir.path());
+        fs::write(dir.path().join("app.py"), "def handler(): pass").unwrap();
+        fs::write(dir.path().join("notes.txt"), "todo").unwrap();
+        fs::remove_file(&doomed).unwrap();
+
+        let mut changed = Vec::new();
+        let mut removed = Vec::new();
+        let mut chunks = Vec::new();
+        while changed.len() < 2 || removed.is_empty() {
+            let update = watcher.next_timeout(TIMEOUT).expect("no update").unwrap();
+            changed.extend(update.changed);
+            removed.extend(update.removed);
+            chunks.extend(update.chunks);
+        }
+
+        changed.sort();
+        changed.dedup();
+        let names: Vec<_> = changed
+            .iter()
+            .map(|p| p.file_name().unwrap().to_string_lossy().to_string())
+            .collect();
+        assert_eq!(names, ["app.py", "notes.txt"]);
+        assert!(removed.iter().all(|p| p.ends_with("doomed.py")));
+        assert!(chunks.iter().any(|c| c.name == "handler"));
+    }
+
+    #[test]
+    fn test_skips_ignored_paths() {
+        let dir = tempdir().unwrap();
+        fs::write(dir.path().join(".gitignore"), "*.log\n").unwrap();
+        fs::create_dir(dir.path().join("node_modules")).unwrap();
+
+        let mut watcher = watcher(dir.path());
+        fs::write(dir.path().join("debug.log"), "noise").unwrap();
+        fs::write(dir.path().join("node_modules").join("dep.js"), "x").unwrap();
+        fs::write(dir.path().join(".env"), "SECRET=1").unwrap();
+        fs::write(dir.path().join("main.rs"), "fn main() {}").unwrap();
+
+        let update = watcher.next_timeout(TIMEOUT).expect("no update").unwrap();
+        assert_eq!(update.changed.len(), 1);
+        assert!(update.changed[0].ends_with("main.rs"));
+        assert!(update.removed.is_empty());
+    }
+}
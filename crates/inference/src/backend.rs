@@ -2,9 +2,13 @@
 //!
 //! Defines a unified interface for different inference backends:
 //! - `NativeBackend` - Direct FFI bindings to bitnet.cpp (fastest)
+//! - `ServerBackend` - Talks to a persistent llama-server process
 //! - `SubprocessBackend` - Calls llama-cli binary (fallback)
 
-use crate::error::Result;
+use crate::cancel::CancellationToken;
+use crate::chat::{ChatMessage, ChatTemplate};
+use crate::error::{InferenceError, Result};
+use crate::logprobs::GenerationOutput;
 use crate::sampler::SamplerConfig;
 
 /// Token callback type for streaming
@@ -21,10 +25,18 @@ pub trait InferenceBackend: Send + Sync {
     /// * `prompt` - The input prompt text
     /// * `max_tokens` - Maximum number of tokens to generate
     /// * `sampler` - Sampling configuration (temperature, top_k, etc.)
+    /// * `cancel` - Stops generation with [`InferenceError::Interrupted`]
+    ///   once cancelled
     ///
     /// # Returns
     /// Generated text string
-    fn generate(&self, prompt: &str, max_tokens: u32, sampler: &SamplerConfig) -> Result<String>;
+    fn generate(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        cancel: &CancellationToken,
+    ) -> Result<String>;
 
     /// Generate text with streaming callback
     ///
@@ -35,24 +47,88 @@ pub trait InferenceBackend: Send + Sync {
     /// * `max_tokens` - Maximum number of tokens to generate
     /// * `sampler` - Sampling configuration
     /// * `on_token` - Callback invoked for each token
+    /// * `cancel` - Stops generation once cancelled
     fn generate_streaming(
         &self,
         prompt: &str,
         max_tokens: u32,
         sampler: &SamplerConfig,
         on_token: TokenCallback<'_>,
+        cancel: &CancellationToken,
     ) -> Result<String>;
 
-    /// Chat-style generation with system and user prompts
+    /// Generate text and report each token's log-probability with up to
+    /// `top_n` alternatives
+    ///
+    /// Backends that cannot observe token probabilities return an error.
+    fn generate_with_logprobs(
+        &self,
+        _prompt: &str,
+        _max_tokens: u32,
+        _sampler: &SamplerConfig,
+        _top_n: usize,
+        _cancel: &CancellationToken,
+    ) -> Result<GenerationOutput> {
+        Err(InferenceError::InvalidConfig(format!(
+            "{} does not report token log-probabilities",
+            self.name()
+        )))
+    }
+
+    /// Convert text to the model's token ids
     ///
-    /// Formats the input as a chat conversation and generates a response.
+    /// Special tokens such as `<|eot_id|>` are parsed as single tokens;
+    /// no BOS token is added.
+    fn tokenize(&self, _text: &str) -> Result<Vec<i32>> {
+        Err(InferenceError::InvalidConfig(format!(
+            "{} does not expose the tokenizer",
+            self.name()
+        )))
+    }
+
+    /// Convert token ids back to text
+    fn detokenize(&self, _tokens: &[i32]) -> Result<String> {
+        Err(InferenceError::InvalidConfig(format!(
+            "{} does not expose the tokenizer",
+            self.name()
+        )))
+    }
+
+    /// Chat template used to format conversations for the loaded model
+    fn chat_template(&self) -> ChatTemplate;
+
+    /// Generate the assistant's reply to a conversation
+    ///
+    /// Renders `messages` with [`Self::chat_template`] and cuts the output
+    /// at the end of the assistant's turn.
     fn chat(
         &self,
-        system_prompt: &str,
-        user_message: &str,
+        messages: &[ChatMessage],
         max_tokens: u32,
         sampler: &SamplerConfig,
-    ) -> Result<String>;
+        cancel: &CancellationToken,
+    ) -> Result<String> {
+        let template = self.chat_template();
+        let output = self.generate(&template.render(messages), max_tokens, sampler, cancel)?;
+        Ok(template.extract_reply(&output))
+    }
+
+    /// How many generations this backend can run at once
+    ///
+    /// Used by [`InferenceModel::generate_many`](crate::InferenceModel::generate_many)
+    /// to bound its parallelism.
+    fn max_parallel(&self) -> usize {
+        1
+    }
+
+    /// Get the backend ready to serve its first request quickly
+    ///
+    /// Runs one tiny generation, which pages the model weights into
+    /// memory. Backends override this to also prepare per-request state.
+    fn warm_up(&self, cancel: &CancellationToken) -> Result<()> {
+        self.generate("Hello", 1, &SamplerConfig::greedy(), cancel)
+            .map(|_| ())
+    }
 
     /// Get the backend type name
     fn name(&self) -> &'static str;
@@ -71,9 +147,11 @@ pub trait InferenceBackend: Send + Sync {
 pub enum BackendType {
     /// Native FFI bindings to bitnet.cpp (preferred, fastest)
     Native,
+    /// Persistent llama-server process (model loaded once)
+    Server,
     /// Subprocess backend calling llama-cli binary (fallback)
     Subprocess,
-    /// Auto-detect: try native first, fall back to subprocess
+    /// Auto-detect: try native, then server, then subprocess
     #[default]
     Auto,
 }
@@ -82,6 +160,7 @@ impl std::fmt::Display for BackendType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             BackendType::Native => write!(f, "native"),
+            BackendType::Server => write!(f, "server"),
             BackendType::Subprocess => write!(f, "subprocess"),
             BackendType::Auto => write!(f, "auto"),
         }
@@ -94,6 +173,7 @@ impl std::str::FromStr for BackendType {
     fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
         match s.to_lowercase().as_str() {
             "native" | "ffi" => Ok(BackendType::Native),
+            "server" | "llama-server" => Ok(BackendType::Server),
             "subprocess" | "cli" | "process" => Ok(BackendType::Subprocess),
             "auto" | "default" => Ok(BackendType::Auto),
             _ => Err(format!("Unknown backend type: {}", s)),
@@ -0,0 +1,274 @@
+//! Inference benchmarks
+//!
+//! Loads a model once per thread count and measures load time, prompt
+//! processing and generation speed, and peak memory.
+//!
+//! ```no_run
+//! use neuro_inference::bench::{self, BenchConfig};
+//!
+//! let config = BenchConfig::new("model.gguf").with_thread_counts(vec![2, 4, 8]);
+//! let results = bench::run(&config).unwrap();
+//! println!("{}", bench::format_table(&results));
+//! ```
+
+use crate::backend::BackendType;
+use crate::error::Result;
+use crate::model::{GenerateOptions, InferenceConfig, InferenceModel};
+use crate::sampler::SamplerConfig;
+use std::time::{Duration, Instant};
+use tracing::{debug, info};
+
+/// Filler repeated to build a prompt of the requested length
+const PROMPT_FILLER: &str = "The quick brown fox jumps over the lazy dog. ";
+
+/// Rough bytes per token, used when the backend has no tokenizer
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Benchmark settings
+#[derive(Debug, Clone)]
+pub struct BenchConfig {
+    /// Path to the GGUF model file
+    pub model_path: String,
+    /// Backend to benchmark
+    pub backend: BackendType,
+    /// Context size
+    pub n_ctx: u32,
+    /// Thread counts to compare, one model load each
+    pub thread_counts: Vec<i32>,
+    /// Approximate prompt length in tokens
+    pub prompt_tokens: usize,
+    /// Tokens to generate per run
+    pub gen_tokens: u32,
+    /// Runs per thread count, averaged
+    pub repetitions: usize,
+}
+
+impl BenchConfig {
+    /// Create with default settings
+    pub fn new(model_path: impl Into<String>) -> Self {
+        let cpus = std::thread::available_parallelism().map_or(4, |n| n.get()) as i32;
+        Self {
+            model_path: model_path.into(),
+            backend: BackendType::Auto,
+            n_ctx: 2048,
+            thread_counts: vec![cpus],
+            prompt_tokens: 512,
+            gen_tokens: 128,
+            repetitions: 3,
+        }
+    }
+
+    /// Set backend
+    pub fn with_backend(mut self, backend: BackendType) -> Self {
+        self.backend = backend;
+        self
+    }
+
+    /// Set thread counts to compare
+    pub fn with_thread_counts(mut self, thread_counts: Vec<i32>) -> Self {
+        self.thread_counts = thread_counts;
+        self
+    }
+
+    /// Set approximate prompt length in tokens
+    pub fn with_prompt_tokens(mut self, prompt_tokens: usize) -> Self {
+        self.prompt_tokens = prompt_tokens;
+        self
+    }
+
+    /// Set tokens to generate per run
+    pub fn with_gen_tokens(mut self, gen_tokens: u32) -> Self {
+        self.gen_tokens = gen_tokens;
+        self
+    }
+
+    /// Set runs per thread count
+    pub fn with_repetitions(mut self, repetitions: usize) -> Self {
+        self.repetitions = repetitions.max(1);
+        self
+    }
+}
+
+/// Measurements for one thread count
+#[derive(Debug, Clone)]
+pub struct BenchResult {
+    /// Backend that ran the benchmark
+    pub backend: &'static str,
+    /// CPU threads used
+    pub threads: i32,
+    /// Time to load the model
+    pub load_time: Duration,
+    /// Prompt length in tokens
+    pub prompt_tokens: usize,
+    /// Prompt processing speed (tokens per second)
+    pub prompt_tokens_per_sec: f64,
+    /// Tokens generated per run, averaged
+    pub gen_tokens: usize,
+    /// Generation speed (tokens per second)
+    pub gen_tokens_per_sec: f64,
+    /// Peak resident memory of this process, when known
+    ///
+    /// Backends that run the model in a child process (server,
+    /// subprocess) are not included.
+    pub peak_rss_bytes: Option<u64>,
+}
+
+/// Run the benchmark for every thread count in `config`
+pub fn run(config: &BenchConfig) -> Result<Vec<BenchResult>> {
+    config
+        .thread_counts
+        .iter()
+        .map(|&threads| run_with_threads(config, threads))
+        .collect()
+}
+
+fn run_with_threads(config: &BenchConfig, threads: i32) -> Result<BenchResult> {
+    info!("Benchmarking with {} threads", threads);
+    reset_peak_rss();
+
+    let load_start = Instant::now();
+    let model = InferenceModel::load(
+        InferenceConfig::new(&config.model_path)
+            .with_backend(config.backend)
+            .with_context_size(config.n_ctx)
+            .with_threads(threads),
+    )?;
+    let load_time = load_start.elapsed();
+
+    let prompt = PROMPT_FILLER.repeat(
+        (config.prompt_tokens * BYTES_PER_TOKEN).div_ceil(PROMPT_FILLER.len()),
+    );
+    let prompt_tokens = count_tokens(&model, &prompt);
+    let options = GenerateOptions::new(config.gen_tokens)
+        .with_sampler(SamplerConfig::greedy().with_seed(42));
+
+    // Warm up caches before timing
+    model.generate(&prompt, &GenerateOptions::new(1))?;
+
+    let mut prompt_time = Duration::ZERO;
+    let mut gen_time = Duration::ZERO;
+    let mut gen_tokens = 0;
+    for run in 0..config.repetitions {
+        let start = Instant::now();
+        let mut first_token = None;
+        let mut callbacks = 0;
+        let output = model.generate_streaming(&prompt, &options, &mut |_| {
+            first_token.get_or_insert_with(Instant::now);
+            callbacks += 1;
+        })?;
+        let end = Instant::now();
+
+        // Time to first token is prompt processing plus one decode step
+        let first_token = first_token.unwrap_or(end);
+        prompt_time += first_token - start;
+        gen_time += end - first_token;
+        // Streaming callbacks are not always one per token
+        let tokens = model.count_tokens(&output).unwrap_or(callbacks);
+        gen_tokens += tokens.saturating_sub(1);
+        debug!("Run {}: {} tokens in {:?}", run + 1, tokens, end - start);
+    }
+
+    let runs = config.repetitions as f64;
+    Ok(BenchResult {
+        backend: model.backend_name(),
+        threads,
+        load_time,
+        prompt_tokens,
+        prompt_tokens_per_sec: per_sec(prompt_tokens as f64 * runs, prompt_time),
+        gen_tokens: (gen_tokens as f64 / runs).round() as usize,
+        gen_tokens_per_sec: per_sec(gen_tokens as f64, gen_time),
+        peak_rss_bytes: peak_rss_bytes(),
+    })
+}
+
+/// Format results as a plain-text table, one row per thread count
+pub fn format_table(results: &[BenchResult]) -> String {
+    let mut table = format!(
+        "{:<22} {:>7} {:>9} {:>10} {:>10} {:>10} {:>10}\n",
+        "backend", "threads", "load (s)", "pp tok", "pp tok/s", "tg tok/s", "peak RSS"
+    );
+    for r in results {
+        let rss = r
+            .peak_rss_bytes
+            .map_or_else(|| "-".to_string(), |b| format!("{:.0} MB", b as f64 / 1_048_576.0));
+        table.push_str(&format!(
+            "{:<22} {:>7} {:>9.2} {:>10} {:>10.1} {:>10.1} {:>10}\n",
+            r.backend,
+            r.threads,
+            r.load_time.as_secs_f64(),
+            r.prompt_tokens,
+            r.prompt_tokens_per_sec,
+            r.gen_tokens_per_sec,
+            rss
+        ));
+    }
+    table
+}
+
+fn count_tokens(model: &InferenceModel, text: &str) -> usize {
+    model
+        .count_tokens(text)
+        .unwrap_or(text.len() / BYTES_PER_TOKEN)
+}
+
+fn per_sec(count: f64, elapsed: Duration) -> f64 {
+    if elapsed.is_zero() {
+        0.0
+    } else {
+        count / elapsed.as_secs_f64()
+    }
+}
+
+/// Reset the kernel's peak RSS counter so each thread count is measured
+/// on its own
+fn reset_peak_rss() {
+    #[cfg(target_os = "linux")]
+    let _ = std::fs::write("/proc/self/clear_refs", "5");
+}
+
+/// Peak resident set size of this process
+fn peak_rss_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    parse_vm_hwm(&status)
+}
+
+/// Parse the `VmHWM:   1234 kB` line of `/proc/self/status`
+fn parse_vm_hwm(status: &str) -> Option<u64> {
+    let line = status.lines().find(|l| l.starts_with("VmHWM:"))?;
+    let kb: u64 = line.split_whitespace().nth(1)?.parse().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_vm_hwm() {
+        let status = "Name:\tneuro\nVmPeak:\t  200 kB\nVmHWM:\t   1536 kB\nVmRSS:\t 1024 kB\n";
+        assert_eq!(parse_vm_hwm(status), Some(1536 * 1024));
+        assert_eq!(parse_vm_hwm("Name:\tneuro\n"), None);
+    }
+
+    #[test]
+    fn test_format_table() {
+        let result = BenchResult {
+            backend: "bitnet.cpp (native)",
+            threads: 4,
+            load_time: Duration::from_millis(1500),
+            prompt_tokens: 512,
+            prompt_tokens_per_sec: 250.0,
+            gen_tokens: 128,
+            gen_tokens_per_sec: 20.5,
+            peak_rss_bytes: Some(1024 * 1_048_576),
+        };
+        let table = format_table(&[result]);
+        let lines: Vec<&str> = table.lines().collect();
+
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].contains("tg tok/s"));
+        assert!(lines[1].contains("1.50"));
+        assert!(lines[1].contains("20.5"));
+        assert!(lines[1].contains("1024 MB"));
+    }
+}
@@ -0,0 +1,195 @@
+//! Dedicated worker threads for the async API
+//!
+//! Generation blocks a thread for seconds at a time, so async methods hand
+//! it to these workers instead of tokio's shared blocking pool. The pool
+//! starts on first use and does not need a tokio runtime.
+
+use crate::cancel::DropGuard;
+use crate::error::{InferenceError, Result};
+use futures_core::Stream;
+use once_cell::sync::Lazy;
+use std::panic::{self, AssertUnwindSafe};
+use std::pin::Pin;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+use std::thread;
+use tokio::sync::{mpsc as async_mpsc, oneshot};
+use tracing::error;
+
+type Job = Box<dyn FnOnce() + Send>;
+
+static POOL: Lazy<WorkerPool> = Lazy::new(WorkerPool::new);
+
+struct WorkerPool {
+    jobs: Sender<Job>,
+}
+
+impl WorkerPool {
+    fn new() -> Self {
+        let (jobs, queue) = mpsc::channel::<Job>();
+        let queue = Arc::new(Mutex::new(queue));
+        let n_workers = thread::available_parallelism().map_or(4, |n| n.get());
+
+        for i in 0..n_workers {
+            let queue = Arc::clone(&queue);
+            thread::Builder::new()
+                .name(format!("neuro-inference-{}", i))
+                .spawn(move || worker(&queue))
+                .expect("failed to spawn inference worker thread");
+        }
+
+        Self { jobs }
+    }
+}
+
+fn worker(queue: &Mutex<Receiver<Job>>) {
+    loop {
+        // Hold the lock only while waiting, not while running the job
+        let job = match queue.lock() {
+            Ok(queue) => queue.recv(),
+            Err(_) => return,
+        };
+        let Ok(job) = job else {
+            return;
+        };
+
+        if panic::catch_unwind(AssertUnwindSafe(job)).is_err() {
+            error!("Inference worker job panicked");
+        }
+    }
+}
+
+/// Run `f` on a worker thread and wait for its result
+pub(crate) async fn run<T, F>(f: F) -> Result<T>
+where
+    F: FnOnce() -> Result<T> + Send + 'static,
+    T: Send + 'static,
+{
+    let (tx, rx) = oneshot::channel();
+    spawn(move || {
+        let _ = tx.send(f());
+    });
+    rx.await.map_err(|_| task_failed())?
+}
+
+/// Queue `f` on a worker thread
+pub(crate) fn spawn(f: impl FnOnce() + Send + 'static) {
+    POOL.jobs
+        .send(Box::new(f))
+        .expect("inference workers never stop");
+}
+
+/// Error for a job that panicked before sending its result
+pub(crate) fn task_failed() -> InferenceError {
+    InferenceError::Decode("Generation task panicked".to_string())
+}
+
+/// Tokens of a generation running on a worker thread
+///
+/// Yields tokens as they are produced, either through [`Self::next`] or as
+/// a [`Stream`]. [`Self::finish`] waits for the complete text. Dropping the
+/// stream before the generation ends cancels it.
+pub struct TokenStream {
+    tokens: async_mpsc::UnboundedReceiver<String>,
+    result: oneshot::Receiver<Result<String>>,
+    cancel: Option<DropGuard>,
+}
+
+impl TokenStream {
+    pub(crate) fn new(
+        tokens: async_mpsc::UnboundedReceiver<String>,
+        result: oneshot::Receiver<Result<String>>,
+        cancel: DropGuard,
+    ) -> Self {
+        Self {
+            tokens,
+            result,
+            cancel: Some(cancel),
+        }
+    }
+
+    /// Wait for the next token, or `None` once generation has ended
+    pub async fn next(&mut self) -> Option<String> {
+        self.tokens.recv().await
+    }
+
+    /// Wait for generation to end and return the whole text
+    ///
+    /// Tokens not yet read are skipped; the text is the same as
+    /// [`InferenceModel::generate_streaming`](crate::InferenceModel::generate_streaming)
+    /// returns.
+    pub async fn finish(mut self) -> Result<String> {
+        let result = (&mut self.result).await;
+        if let Some(guard) = self.cancel.take() {
+            guard.disarm();
+        }
+        result.map_err(|_| task_failed())?
+    }
+}
+
+impl Stream for TokenStream {
+    type Item = String;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<String>> {
+        self.tokens.poll_recv(cx)
+    }
+}
+
+impl Drop for TokenStream {
+    fn drop(&mut self) {
+        // Only cancel a generation that is still running; the token may be
+        // shared and reused by the caller
+        if let Some(guard) = self.cancel.take() {
+            if !matches!(
+                self.result.try_recv(),
+                Err(oneshot::error::TryRecvError::Empty)
+            ) {
+                guard.disarm();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_returns_result() {
+        assert_eq!(run(|| Ok(2 + 2)).await.unwrap(), 4);
+        assert!(matches!(
+            run::<(), _>(|| Err(InferenceError::Interrupted)).await,
+            Err(InferenceError::Interrupted)
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_run_survives_panic() {
+        assert!(run::<(), _>(|| panic!("boom")).await.is_err());
+        assert_eq!(run(|| Ok("still running")).await.unwrap(), "still running");
+    }
+
+    #[tokio::test]
+    async fn test_token_stream_cancels_only_unfinished() {
+        use crate::cancel::CancellationToken;
+
+        let token = CancellationToken::new();
+        let (token_tx, tokens) = async_mpsc::unbounded_channel();
+        let (result_tx, result) = oneshot::channel();
+        let mut stream = TokenStream::new(tokens, result, token.clone().drop_guard());
+
+        token_tx.send("Hi".to_string()).unwrap();
+        drop(token_tx);
+        result_tx.send(Ok("Hi".to_string())).unwrap();
+        assert_eq!(stream.next().await.as_deref(), Some("Hi"));
+        assert_eq!(stream.next().await, None);
+        drop(stream);
+        assert!(!token.is_cancelled());
+
+        let (_token_tx, tokens) = async_mpsc::unbounded_channel();
+        let (_result_tx, result) = oneshot::channel();
+        drop(TokenStream::new(tokens, result, token.clone().drop_guard()));
+        assert!(token.is_cancelled());
+    }
+}
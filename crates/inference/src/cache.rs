@@ -3,7 +3,9 @@
 //! Handles model storage in `NEURO_BITNET_MODELS_DIR` or `~/.cache/neuro-bitnet/models/`
 
 use crate::error::{InferenceError, Result};
+use crate::hub::HubFile;
 use crate::models::BitNetModel;
+use crate::registry::{CustomModel, ModelRef, ModelRegistry, REGISTRY_FILE};
 use std::path::{Path, PathBuf};
 use tracing::{info, warn, debug};
 
@@ -128,6 +130,133 @@ impl ModelCache {
             .map(|m| m.len())
             .sum()
     }
+
+    /// Load the user model registry (`models.toml` in the cache directory)
+    pub fn registry(&self) -> Result<ModelRegistry> {
+        ModelRegistry::load(self.cache_dir.join(REGISTRY_FILE))
+    }
+
+    /// Resolve a built-in model alias, a registered model name or a
+    /// `hf:<org>/<repo>[@<revision>]:<file>` Hub reference
+    pub fn resolve(&self, name: &str) -> Result<ModelRef> {
+        if HubFile::is_hub_ref(name) {
+            return Ok(ModelRef::Hub(HubFile::parse(name)?));
+        }
+        if let Some(model) = BitNetModel::from_str(name) {
+            return Ok(ModelRef::BitNet(model));
+        }
+
+        let registry = self.registry()?;
+        if let Some(model) = registry.get(name) {
+            return Ok(ModelRef::Custom(model.clone()));
+        }
+
+        let mut available = vec!["2b", "large", "3b", "8b"];
+        available.extend(registry.models().map(|m| m.name.as_str()));
+        Err(InferenceError::InvalidConfig(format!(
+            "Unknown model '{}'. Available: {}, or hf:<org>/<repo>:<file>",
+            name,
+            available.join(", ")
+        )))
+    }
+
+    /// Get the path of a built-in or registered model
+    ///
+    /// Registered local files are used in place; downloaded ones live in
+    /// `custom/<name>/` in the cache, and Hub files in
+    /// `hf/<org>/<repo>/<revision>/`.
+    pub fn resolved_path(&self, model: &ModelRef) -> PathBuf {
+        match model {
+            ModelRef::BitNet(model) => self.model_path(*model),
+            ModelRef::Custom(CustomModel {
+                path: Some(path), ..
+            }) => path.clone(),
+            ModelRef::Custom(model) => self
+                .cache_dir
+                .join("custom")
+                .join(&model.name)
+                .join(model.filename()),
+            ModelRef::Hub(file) => self.cache_dir.join(file.cache_path()),
+        }
+    }
+
+    /// Check if a built-in or registered model is ready to load
+    pub fn is_available(&self, model: &ModelRef) -> bool {
+        match model {
+            ModelRef::BitNet(model) => self.is_downloaded(*model),
+            ModelRef::Custom(_) | ModelRef::Hub(_) => std::fs::metadata(self.resolved_path(model))
+                .map(|m| m.is_file() && m.len() > 0)
+                .unwrap_or(false),
+        }
+    }
+
+    /// Delete the cached download of a registered model
+    ///
+    /// Registered local files are never deleted.
+    pub fn delete_custom(&self, model: &CustomModel) -> Result<bool> {
+        if model.path.is_some() {
+            return Ok(false);
+        }
+        let model_dir = self.cache_dir.join("custom").join(&model.name);
+        if model_dir.exists() {
+            std::fs::remove_dir_all(&model_dir)?;
+            info!("Deleted model: {}", model.name);
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// Delete a downloaded Hub file
+    pub fn delete_hub(&self, file: &HubFile) -> Result<bool> {
+        let path = self.cache_dir.join(file.cache_path());
+        if path.exists() {
+            std::fs::remove_file(&path)?;
+            info!("Deleted model: {}", file.spec());
+            Ok(true)
+        } else {
+            Ok(false)
+        }
+    }
+
+    /// List downloaded Hub files
+    pub fn list_hub(&self) -> Vec<(HubFile, PathBuf)> {
+        let mut found = Vec::new();
+        let mut dirs = vec![self.cache_dir.join("hf")];
+        while let Some(dir) = dirs.pop() {
+            let Ok(entries) = std::fs::read_dir(&dir) else {
+                continue;
+            };
+            for path in entries.filter_map(|entry| entry.ok()).map(|entry| entry.path()) {
+                if path.is_dir() {
+                    dirs.push(path);
+                } else if path.extension().is_some_and(|ext| ext == "gguf") {
+                    if let Some(file) = self.hub_file_at(&path) {
+                        found.push((file, path));
+                    }
+                }
+            }
+        }
+        found.sort_by(|a, b| a.1.cmp(&b.1));
+        found
+    }
+
+    /// Rebuild the Hub reference from `hf/<org>/<repo>/<revision>/<file>`
+    fn hub_file_at(&self, path: &Path) -> Option<HubFile> {
+        let relative = path.strip_prefix(self.cache_dir.join("hf")).ok()?;
+        let parts: Vec<_> = relative.iter().map(|part| part.to_str()).collect::<Option<_>>()?;
+        if parts.len() < 4 {
+            return None;
+        }
+        let spec = format!(
+            "hf:{}/{}@{}:{}",
+            parts[0],
+            parts[1],
+            parts[2],
+            parts[3..].join("/")
+        );
+        HubFile::parse(&spec).ok()
+    }
 }
 
 impl Default for ModelCache {
@@ -155,6 +284,8 @@ pub mod download {
         pub verify: bool,
         /// Force re-download even if exists
         pub force: bool,
+        /// Hugging Face access token for gated or private repos
+        pub hf_token: Option<String>,
     }
 
     impl Default for DownloadOptions {
@@ -163,6 +294,7 @@ pub mod download {
                 yes: false,
                 verify: true,
                 force: false,
+                hf_token: HubFile::token_from_env(),
             }
         }
     }
@@ -181,21 +313,103 @@ pub mod download {
             return Ok(target_path);
         }
 
+        info!("Downloading {} ({})...", model.name(), model.size_human());
+        download_file(
+            model.download_url(),
+            target_path,
+            Some(model.size_bytes()),
+            model.sha256(),
+            None,
+            options,
+        )
+        .await
+    }
+
+    /// Download a built-in, registered or Hub model to cache
+    ///
+    /// Registered local files are not downloaded; they only need to exist.
+    pub async fn download_ref(
+        cache: &ModelCache,
+        model: &ModelRef,
+        options: &DownloadOptions,
+    ) -> Result<PathBuf> {
+        let custom = match model {
+            ModelRef::BitNet(model) => return download_model(cache, *model, options).await,
+            ModelRef::Hub(file) => return download_hub(cache, file, options).await,
+            ModelRef::Custom(custom) => custom,
+        };
+
+        let target_path = cache.resolved_path(model);
+        let Some(url) = &custom.url else {
+            return if target_path.exists() {
+                Ok(target_path)
+            } else {
+                Err(InferenceError::ModelLoad {
+                    path: target_path.display().to_string(),
+                    message: format!("File registered as model '{}' does not exist", custom.name),
+                })
+            };
+        };
+
+        if !options.force && cache.is_available(model) {
+            info!("Model {} already downloaded at {}", custom.name, target_path.display());
+            return Ok(target_path);
+        }
+
+        info!("Downloading {}...", custom.name);
+        download_file(url, target_path, None, custom.sha256.as_deref(), None, options).await
+    }
+
+    /// Download a file from the Hugging Face Hub to cache
+    async fn download_hub(
+        cache: &ModelCache,
+        file: &HubFile,
+        options: &DownloadOptions,
+    ) -> Result<PathBuf> {
+        let model = ModelRef::Hub(file.clone());
+        let target_path = cache.resolved_path(&model);
+        if !options.force && cache.is_available(&model) {
+            info!("Model {} already downloaded at {}", file.spec(), target_path.display());
+            return Ok(target_path);
+        }
+
+        info!("Downloading {} from {} at {}...", file.filename, file.repo, file.revision);
+        download_file(
+            &file.url(),
+            target_path,
+            None,
+            None,
+            options.hf_token.as_deref(),
+            options,
+        )
+        .await
+    }
+
+    /// Download `url` to `target_path`, verifying `sha256` if given
+    ///
+    /// `token` is sent as a bearer token, for gated Hub repos.
+    async fn download_file(
+        url: &str,
+        target_path: PathBuf,
+        expected_size: Option<u64>,
+        sha256: Option<&str>,
+        token: Option<&str>,
+        options: &DownloadOptions,
+    ) -> Result<PathBuf> {
         // Create directory
         if let Some(parent) = target_path.parent() {
             std::fs::create_dir_all(parent)?;
         }
 
-        let url = model.download_url();
-        let expected_size = model.size_bytes();
-
-        info!("Downloading {} ({})...", model.name(), model.size_human());
         info!("URL: {}", url);
 
         // Create HTTP client
         let client = reqwest::Client::new();
-        let response = client
-            .get(url)
+        let mut request = client.get(url);
+        if let Some(token) = token {
+            request = request.bearer_auth(token);
+        }
+        let response = request
             .send()
             .await
             .map_err(|e| InferenceError::ModelLoad {
@@ -203,14 +417,29 @@ pub mod download {
                 message: format!("HTTP request failed: {}", e),
             })?;
 
-        if !response.status().is_success() {
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN {
+            let hint = if token.is_some() {
+                "the token has no access to this repo; accept its license on huggingface.co"
+            } else {
+                "the repo may be gated or private; set HF_TOKEN or pass --hf-token"
+            };
+            return Err(InferenceError::ModelLoad {
+                path: url.to_string(),
+                message: format!("HTTP error: {} ({})", status, hint),
+            });
+        }
+        if !status.is_success() {
             return Err(InferenceError::ModelLoad {
                 path: url.to_string(),
                 message: format!("HTTP error: {}", response.status()),
             });
         }
 
-        let total_size = response.content_length().unwrap_or(expected_size);
+        let total_size = response
+            .content_length()
+            .or(expected_size)
+            .unwrap_or(0);
 
         // Create progress bar
         let pb = ProgressBar::new(total_size);
@@ -257,7 +486,7 @@ pub mod download {
 
         // Verify checksum if available
         if options.verify {
-            if let Some(expected_hash) = model.sha256() {
+            if let Some(expected_hash) = sha256 {
                 let actual_hash = format!("{:x}", hasher.finalize());
                 if actual_hash != expected_hash {
                     // Clean up failed download
@@ -290,8 +519,11 @@ pub mod download {
         println!("   Description: {}", model.description());
         println!("   Repository: {}", model.hf_repo());
         println!();
-        
-        print!("Download this model? [y/N] ");
+        confirm("Download this model?")
+    }
+
+    fn confirm(question: &str) -> bool {
+        print!("{} [y/N] ", question);
         std::io::stdout().flush().ok();
 
         let mut input = String::new();
@@ -303,6 +535,95 @@ pub mod download {
         }
     }
 
+    /// Get a built-in or registered model, downloading if necessary
+    pub async fn get_or_download_ref(
+        cache: &ModelCache,
+        model: &ModelRef,
+        options: &DownloadOptions,
+    ) -> Result<PathBuf> {
+        let url = match model {
+            ModelRef::BitNet(model) => return get_or_download(cache, *model, options).await,
+            ModelRef::Custom(custom) => custom.url.clone(),
+            ModelRef::Hub(file) => Some(file.url()),
+        };
+        if cache.is_available(model) {
+            return Ok(cache.resolved_path(model));
+        }
+
+        if let Some(url) = url.filter(|_| !options.yes) {
+            println!("\n📦 Model: {}", model.name());
+            println!("   URL: {}", url);
+            println!();
+            if !confirm("Download this model?") {
+                return Err(InferenceError::ModelLoad {
+                    path: cache.resolved_path(model).display().to_string(),
+                    message: "Download cancelled by user".to_string(),
+                });
+            }
+        }
+
+        download_ref(cache, model, options).await
+    }
+
+    /// Result of checking a model file on disk
+    #[derive(Debug, Clone, PartialEq, Eq)]
+    pub enum VerifyStatus {
+        /// The file matches its known SHA256 checksum
+        Verified,
+        /// The file is a complete GGUF file; no checksum is known
+        Complete,
+        /// The file does not exist
+        Missing,
+        /// The file is truncated or does not match its checksum
+        Corrupt(String),
+    }
+
+    /// Check a built-in or registered model file for corruption
+    ///
+    /// Checks that the GGUF file is not truncated, then re-hashes it when
+    /// the checksum is known. Hashing reads the whole file, so call this
+    /// off the async runtime.
+    pub fn verify_ref(cache: &ModelCache, model: &ModelRef) -> VerifyStatus {
+        let path = cache.resolved_path(model);
+        let len = match std::fs::metadata(&path) {
+            Ok(metadata) if metadata.is_file() => metadata.len(),
+            _ => return VerifyStatus::Missing,
+        };
+
+        if let Err(e) = crate::gguf::check_complete(&path) {
+            return VerifyStatus::Corrupt(e.to_string());
+        }
+
+        if let ModelRef::BitNet(model) = model {
+            if model.is_verified() && len != model.size_bytes() {
+                return VerifyStatus::Corrupt(format!(
+                    "Size mismatch: expected {} bytes, got {}",
+                    model.size_bytes(),
+                    len
+                ));
+            }
+        }
+
+        let Some(expected_hash) = model.sha256() else {
+            return VerifyStatus::Complete;
+        };
+        match hash_file(&path) {
+            Ok(actual_hash) if actual_hash == expected_hash => VerifyStatus::Verified,
+            Ok(actual_hash) => VerifyStatus::Corrupt(format!(
+                "Checksum mismatch: expected {}, got {}",
+                expected_hash, actual_hash
+            )),
+            Err(e) => VerifyStatus::Corrupt(e.to_string()),
+        }
+    }
+
+    fn hash_file(path: &Path) -> Result<String> {
+        let mut file = std::fs::File::open(path)?;
+        let mut hasher = Sha256::new();
+        std::io::copy(&mut file, &mut hasher)?;
+        Ok(format!("{:x}", hasher.finalize()))
+    }
+
     /// Get model, downloading if necessary
     pub async fn get_or_download(
         cache: &ModelCache,
@@ -335,6 +656,80 @@ mod tests {
         assert!(cache.cache_dir().to_string_lossy().contains("neuro-bitnet"));
     }
 
+    #[test]
+    fn test_resolve() {
+        let dir = std::env::temp_dir().join(format!("neuro-cache-{}", std::process::id()));
+        let cache = ModelCache::with_dir(dir.clone());
+        let mut registry = cache.registry().unwrap();
+        registry
+            .add(CustomModel::remote("tiny", "https://example.com/tiny.gguf"))
+            .unwrap();
+        registry.save().unwrap();
+
+        assert_eq!(
+            cache.resolve("2b").unwrap(),
+            ModelRef::BitNet(BitNetModel::B1_58_2B_4T)
+        );
+        let tiny = cache.resolve("tiny").unwrap();
+        assert_eq!(cache.resolved_path(&tiny), dir.join("custom/tiny/tiny.gguf"));
+        assert!(!cache.is_available(&tiny));
+
+        let err = cache.resolve("missing").unwrap_err().to_string();
+        assert!(err.contains("2b, large, 3b, 8b, tiny"));
+
+        let hub = cache.resolve("hf:org/repo@v1:model.gguf").unwrap();
+        let hub_path = cache.resolved_path(&hub);
+        assert_eq!(hub_path, dir.join("hf/org/repo/v1/model.gguf"));
+        std::fs::create_dir_all(hub_path.parent().unwrap()).unwrap();
+        std::fs::write(&hub_path, b"GGUF").unwrap();
+        assert!(cache.is_available(&hub));
+        assert_eq!(
+            cache.list_hub(),
+            vec![(HubFile::parse("hf:org/repo@v1:model.gguf").unwrap(), hub_path)]
+        );
+        assert!(cache.resolve("hf:org:model.gguf").is_err());
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[cfg(feature = "download")]
+    #[test]
+    fn test_verify_ref() {
+        use super::download::{verify_ref, VerifyStatus};
+        use sha2::{Digest, Sha256};
+
+        let dir = std::env::temp_dir().join(format!("neuro-verify-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let cache = ModelCache::with_dir(dir.clone());
+
+        // Empty GGUF v3 file: magic, version, no tensors, no metadata
+        let mut gguf = b"GGUF".to_vec();
+        gguf.extend(3u32.to_le_bytes());
+        gguf.extend([0u8; 16]);
+        let path = dir.join("tiny.gguf");
+        std::fs::write(&path, &gguf).unwrap();
+
+        let local = CustomModel::local("tiny", &path);
+        let hash = format!("{:x}", Sha256::digest(&gguf));
+        let status = |model: &CustomModel| verify_ref(&cache, &ModelRef::Custom(model.clone()));
+
+        assert_eq!(status(&local), VerifyStatus::Complete);
+        assert_eq!(status(&local.clone().with_sha256(hash)), VerifyStatus::Verified);
+        assert!(matches!(
+            status(&local.clone().with_sha256("0".repeat(64))),
+            VerifyStatus::Corrupt(_)
+        ));
+        assert_eq!(
+            status(&CustomModel::local("gone", dir.join("gone.gguf"))),
+            VerifyStatus::Missing
+        );
+
+        std::fs::write(&path, &gguf[..12]).unwrap();
+        assert!(matches!(status(&local), VerifyStatus::Corrupt(_)));
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
     #[test]
     fn test_model_path() {
         let cache = ModelCache::with_dir(PathBuf::from("/tmp/models"));
@@ -0,0 +1,119 @@
+//! Cooperative cancellation of in-flight generation
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// How often the subprocess backend checks for cancellation
+#[cfg_attr(not(feature = "subprocess"), allow(dead_code))]
+pub(crate) const CANCEL_POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Flag shared between a generation and whoever may abort it
+///
+/// Backends check the token while generating: the native backend between
+/// tokens, the subprocess backend by killing llama-cli, and the server
+/// backend by closing the connection. A cancelled generation returns
+/// [`InferenceError::Interrupted`](crate::InferenceError::Interrupted).
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    cancelled: AtomicBool,
+    parent: Option<CancellationToken>,
+}
+
+impl CancellationToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a token that is cancelled along with this one
+    ///
+    /// Cancelling the child does not cancel this token.
+    pub fn child_token(&self) -> Self {
+        Self(Arc::new(Inner {
+            cancelled: AtomicBool::new(false),
+            parent: Some(self.clone()),
+        }))
+    }
+
+    /// Request cancellation
+    pub fn cancel(&self) {
+        self.0.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// Check whether cancellation was requested
+    pub fn is_cancelled(&self) -> bool {
+        self.0.cancelled.load(Ordering::SeqCst)
+            || self.0.parent.as_ref().is_some_and(|p| p.is_cancelled())
+    }
+
+    /// Cancel this token when the returned guard is dropped
+    ///
+    /// Useful in async handlers: when the client disconnects the handler
+    /// future is dropped, and with it the guard.
+    pub fn drop_guard(self) -> DropGuard {
+        DropGuard(Some(self))
+    }
+}
+
+/// Cancels its token on drop unless disarmed
+#[derive(Debug)]
+pub struct DropGuard(Option<CancellationToken>);
+
+impl DropGuard {
+    /// Keep the token running after the guard is dropped
+    pub fn disarm(mut self) -> CancellationToken {
+        self.0.take().expect("guard holds a token until disarmed")
+    }
+}
+
+impl Drop for DropGuard {
+    fn drop(&mut self) {
+        if let Some(token) = self.0.take() {
+            token.cancel();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_cancel_is_shared() {
+        let token = CancellationToken::new();
+        let clone = token.clone();
+        assert!(!clone.is_cancelled());
+
+        token.cancel();
+        assert!(clone.is_cancelled());
+    }
+
+    #[test]
+    fn test_child_token() {
+        let parent = CancellationToken::new();
+        let child = parent.child_token();
+
+        child.cancel();
+        assert!(child.is_cancelled());
+        assert!(!parent.is_cancelled());
+
+        let child = parent.child_token();
+        parent.cancel();
+        assert!(child.is_cancelled());
+    }
+
+    #[test]
+    fn test_drop_guard() {
+        let token = CancellationToken::new();
+        drop(token.clone().drop_guard());
+        assert!(token.is_cancelled());
+
+        let token = CancellationToken::new();
+        token.clone().drop_guard().disarm();
+        assert!(!token.is_cancelled());
+    }
+}
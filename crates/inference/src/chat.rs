@@ -0,0 +1,277 @@
+//! Chat messages and prompt templates
+//!
+//! Chat-tuned models expect conversations in the exact format they were
+//! trained on. The format is taken from the model's GGUF metadata when it
+//! names a known template, otherwise from the preset for the
+//! [`BitNetModel`].
+
+use crate::gguf::{read_metadata_string, CHAT_TEMPLATE_KEY};
+use crate::models::BitNetModel;
+use std::fmt;
+use std::path::Path;
+use tracing::{debug, warn};
+
+/// Author of a chat message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChatRole {
+    /// Instructions for the assistant
+    System,
+    /// Message from the user
+    User,
+    /// Earlier reply from the assistant
+    Assistant,
+}
+
+/// A single message in a conversation
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatMessage {
+    pub role: ChatRole,
+    pub content: String,
+}
+
+impl ChatMessage {
+    /// Create a system message
+    pub fn system(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::System,
+            content: content.into(),
+        }
+    }
+
+    /// Create a user message
+    pub fn user(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::User,
+            content: content.into(),
+        }
+    }
+
+    /// Create an assistant message
+    pub fn assistant(content: impl Into<String>) -> Self {
+        Self {
+            role: ChatRole::Assistant,
+            content: content.into(),
+        }
+    }
+}
+
+/// Prompt format for chat conversations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ChatTemplate {
+    /// BitNet b1.58 2B-4T: `User: ...<|eot_id|>Assistant: `
+    #[default]
+    BitNet,
+    /// Llama 3: `<|start_header_id|>user<|end_header_id|>\n\n...<|eot_id|>`
+    Llama3,
+    /// ChatML: `<|im_start|>user\n...<|im_end|>`
+    ChatMl,
+    /// Zephyr: `<|user|>\n...</s>`
+    Zephyr,
+}
+
+impl ChatTemplate {
+    /// Template name
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::BitNet => "bitnet",
+            Self::Llama3 => "llama3",
+            Self::ChatMl => "chatml",
+            Self::Zephyr => "zephyr",
+        }
+    }
+
+    /// Preset for a known model
+    pub fn for_model(model: BitNetModel) -> Self {
+        match model {
+            BitNetModel::Llama3_8B_1_58 => Self::Llama3,
+            _ => Self::BitNet,
+        }
+    }
+
+    /// Recognize a Jinja chat template (as stored in GGUF metadata)
+    ///
+    /// Only the format is detected from the template's markers; the Jinja
+    /// itself is not evaluated.
+    pub fn from_jinja(template: &str) -> Option<Self> {
+        if template.contains("<|start_header_id|>") {
+            Some(Self::Llama3)
+        } else if template.contains("<|im_start|>") {
+            Some(Self::ChatMl)
+        } else if template.contains("<|user|>") {
+            Some(Self::Zephyr)
+        } else if template.contains("Assistant: ") && template.contains("<|eot_id|>") {
+            Some(Self::BitNet)
+        } else {
+            None
+        }
+    }
+
+    /// Pick the template for a model file
+    ///
+    /// Uses the GGUF chat template when it is recognized, then the preset
+    /// for the model named by the file path, then the default.
+    pub fn resolve<P: AsRef<Path>>(model_path: P) -> Self {
+        let path = model_path.as_ref();
+
+        match read_metadata_string(path, CHAT_TEMPLATE_KEY) {
+            Ok(Some(jinja)) => match Self::from_jinja(&jinja) {
+                Some(template) => {
+                    debug!("Using {} chat template from GGUF metadata", template);
+                    return template;
+                }
+                None => warn!("Unrecognized chat template in {}, using preset", path.display()),
+            },
+            Ok(None) => debug!("No chat template in {}", path.display()),
+            Err(e) => debug!("Could not read GGUF metadata from {}: {}", path.display(), e),
+        }
+
+        BitNetModel::from_path(&path.to_string_lossy())
+            .map(Self::for_model)
+            .unwrap_or_default()
+    }
+
+    /// Render a conversation, ending with the cue for the assistant's reply
+    pub fn render(&self, messages: &[ChatMessage]) -> String {
+        let mut prompt = String::new();
+        for message in messages {
+            let role = role_name(message.role);
+            match self {
+                Self::BitNet => {
+                    let role = match message.role {
+                        ChatRole::System => "System",
+                        ChatRole::User => "User",
+                        ChatRole::Assistant => "Assistant",
+                    };
+                    prompt.push_str(&format!("{}: {}<|eot_id|>", role, message.content));
+                }
+                Self::Llama3 => prompt.push_str(&format!(
+                    "<|start_header_id|>{}<|end_header_id|>\n\n{}<|eot_id|>",
+                    role, message.content
+                )),
+                Self::ChatMl => prompt.push_str(&format!(
+                    "<|im_start|>{}\n{}<|im_end|>\n",
+                    role, message.content
+                )),
+                Self::Zephyr => {
+                    prompt.push_str(&format!("<|{}|>\n{}</s>\n", role, message.content))
+                }
+            }
+        }
+
+        prompt.push_str(match self {
+            Self::BitNet => "Assistant: ",
+            Self::Llama3 => "<|start_header_id|>assistant<|end_header_id|>\n\n",
+            Self::ChatMl => "<|im_start|>assistant\n",
+            Self::Zephyr => "<|assistant|>\n",
+        });
+        prompt
+    }
+
+    /// Markers that end the assistant's turn
+    pub fn stop_sequences(&self) -> &'static [&'static str] {
+        match self {
+            Self::BitNet => &["<|eot_id|>", "User:"],
+            Self::Llama3 => &["<|eot_id|>", "<|start_header_id|>"],
+            Self::ChatMl => &["<|im_end|>", "<|im_start|>"],
+            Self::Zephyr => &["</s>", "<|user|>"],
+        }
+    }
+
+    /// Cut a raw completion at the end of the assistant's turn
+    pub fn extract_reply(&self, output: &str) -> String {
+        let end = self
+            .stop_sequences()
+            .iter()
+            .filter_map(|stop| output.find(stop))
+            .min()
+            .unwrap_or(output.len());
+        output[..end].trim().to_string()
+    }
+}
+
+fn role_name(role: ChatRole) -> &'static str {
+    match role {
+        ChatRole::System => "system",
+        ChatRole::User => "user",
+        ChatRole::Assistant => "assistant",
+    }
+}
+
+impl fmt::Display for ChatTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl std::str::FromStr for ChatTemplate {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "bitnet" | "bitnet-b1.58" => Ok(Self::BitNet),
+            "llama3" | "llama-3" => Ok(Self::Llama3),
+            "chatml" => Ok(Self::ChatMl),
+            "zephyr" => Ok(Self::Zephyr),
+            _ => Err(format!("Unknown chat template: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conversation() -> Vec<ChatMessage> {
+        vec![
+            ChatMessage::system("Be brief."),
+            ChatMessage::user("Hi"),
+            ChatMessage::assistant("Hello!"),
+            ChatMessage::user("2+2?"),
+        ]
+    }
+
+    #[test]
+    fn test_render_bitnet() {
+        let prompt = ChatTemplate::BitNet.render(&conversation());
+        assert_eq!(
+            prompt,
+            "System: Be brief.<|eot_id|>User: Hi<|eot_id|>Assistant: Hello!<|eot_id|>\
+             User: 2+2?<|eot_id|>Assistant: "
+        );
+    }
+
+    #[test]
+    fn test_render_llama3() {
+        let prompt = ChatTemplate::Llama3.render(&[ChatMessage::user("Hi")]);
+        assert_eq!(
+            prompt,
+            "<|start_header_id|>user<|end_header_id|>\n\nHi<|eot_id|>\
+             <|start_header_id|>assistant<|end_header_id|>\n\n"
+        );
+    }
+
+    #[test]
+    fn test_from_jinja() {
+        let llama3 = "{% for message in messages %}<|start_header_id|>{{ message['role'] }}";
+        assert_eq!(ChatTemplate::from_jinja(llama3), Some(ChatTemplate::Llama3));
+
+        let bitnet = "{{ 'User: ' + content + '<|eot_id|>' }}{{ 'Assistant: ' }}";
+        assert_eq!(ChatTemplate::from_jinja(bitnet), Some(ChatTemplate::BitNet));
+
+        assert_eq!(ChatTemplate::from_jinja("{{ messages }}"), None);
+    }
+
+    #[test]
+    fn test_resolve_falls_back_to_preset() {
+        let template = ChatTemplate::resolve("/nonexistent/llama3-8b-1.58-i2_s.gguf");
+        assert_eq!(template, ChatTemplate::Llama3);
+        assert_eq!(ChatTemplate::resolve("/nonexistent/model.gguf"), ChatTemplate::BitNet);
+    }
+
+    #[test]
+    fn test_extract_reply() {
+        let reply = ChatTemplate::BitNet.extract_reply(" 4.<|eot_id|>User: thanks");
+        assert_eq!(reply, "4.");
+        assert_eq!("chatml".parse::<ChatTemplate>().unwrap(), ChatTemplate::ChatMl);
+    }
+}
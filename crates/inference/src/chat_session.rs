@@ -0,0 +1,296 @@
+//! Multi-turn chat state
+//!
+//! A [`ChatSession`] keeps the system prompt and the conversation so far.
+//! Each turn renders the whole conversation with the model's chat template
+//! and drops the oldest turns once the prompt and reply no longer fit in
+//! the context.
+
+use crate::blocking;
+use crate::chat::{ChatMessage, ChatRole};
+use crate::error::Result;
+use crate::model::{GenerateOptions, InferenceModel};
+use tracing::debug;
+
+/// Rough bytes per token, used when the backend has no tokenizer
+const BYTES_PER_TOKEN: usize = 4;
+
+/// System prompt and message history of one conversation
+///
+/// ```no_run
+/// use neuro_inference::{ChatSession, GenerateOptions, InferenceConfig, InferenceModel};
+///
+/// # fn run() -> Result<(), neuro_inference::InferenceError> {
+/// let model = InferenceModel::load(InferenceConfig::new("model.gguf"))?;
+/// let mut session = ChatSession::new().with_system_prompt("You are a helpful assistant.");
+///
+/// session.send(&model, "What is Rust?", &GenerateOptions::new(256))?;
+/// let reply = session.send(&model, "And who maintains it?", &GenerateOptions::new(256))?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ChatSession {
+    system_prompt: Option<String>,
+    history: Vec<ChatMessage>,
+    context_budget: Option<usize>,
+}
+
+impl ChatSession {
+    /// Create an empty session
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the system prompt, which is never trimmed
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Limit prompt plus reply to `tokens`
+    ///
+    /// Defaults to the model's context size.
+    pub fn with_context_budget(mut self, tokens: usize) -> Self {
+        self.context_budget = Some(tokens);
+        self
+    }
+
+    /// Start from an existing conversation
+    ///
+    /// System messages replace the system prompt; the rest are appended to
+    /// the history.
+    pub fn with_messages(mut self, messages: impl IntoIterator<Item = ChatMessage>) -> Self {
+        for message in messages {
+            self.push(message);
+        }
+        self
+    }
+
+    /// Append a message without generating a reply
+    pub fn push(&mut self, message: ChatMessage) {
+        match message.role {
+            ChatRole::System => self.system_prompt = Some(message.content),
+            _ => self.history.push(message),
+        }
+    }
+
+    /// The system prompt, if any
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    /// Messages exchanged so far, oldest first
+    pub fn history(&self) -> &[ChatMessage] {
+        &self.history
+    }
+
+    /// The whole conversation as sent to the model
+    pub fn messages(&self) -> Vec<ChatMessage> {
+        self.system_prompt
+            .iter()
+            .map(|prompt| ChatMessage::system(prompt.as_str()))
+            .chain(self.history.iter().cloned())
+            .collect()
+    }
+
+    /// Forget the history, keeping the system prompt
+    pub fn clear(&mut self) {
+        self.history.clear();
+    }
+
+    /// Send a user message and return the assistant's reply
+    ///
+    /// Oldest turns are dropped until the rendered prompt leaves room for
+    /// `options.max_tokens`. The newest message is always kept. If
+    /// generation fails the message is not added to the history.
+    pub fn send(
+        &mut self,
+        model: &InferenceModel,
+        user_message: impl Into<String>,
+        options: &GenerateOptions,
+    ) -> Result<String> {
+        self.history.push(ChatMessage::user(user_message));
+        self.trim(model, options.max_tokens as usize);
+
+        match model.chat_messages(&self.messages(), options) {
+            Ok(reply) => {
+                self.history.push(ChatMessage::assistant(reply.as_str()));
+                Ok(reply)
+            }
+            Err(e) => {
+                self.history.pop();
+                Err(e)
+            }
+        }
+    }
+
+    /// Async version of [`Self::send`]
+    ///
+    /// Dropping the future before it completes cancels the generation and
+    /// leaves the session unchanged.
+    pub async fn send_async(
+        &mut self,
+        model: &InferenceModel,
+        user_message: impl Into<String>,
+        options: &GenerateOptions,
+    ) -> Result<String> {
+        let mut session = self.clone();
+        let model = model.clone();
+        let user_message = user_message.into();
+        let options = options.clone();
+        let guard = options.cancellation.clone().drop_guard();
+
+        let result = blocking::run(move || {
+            let reply = session.send(&model, user_message, &options)?;
+            Ok((session, reply))
+        })
+        .await;
+        guard.disarm();
+
+        let (session, reply) = result?;
+        *self = session;
+        Ok(reply)
+    }
+
+    /// Tokens the rendered conversation takes up
+    pub fn prompt_tokens(&self, model: &InferenceModel) -> usize {
+        let prompt = model.chat_template().render(&self.messages());
+        model
+            .count_tokens(&prompt)
+            .unwrap_or(prompt.len() / BYTES_PER_TOKEN)
+    }
+
+    /// Drop the oldest turns until the prompt leaves `reply_tokens` free
+    fn trim(&mut self, model: &InferenceModel, reply_tokens: usize) {
+        let budget = self
+            .context_budget
+            .unwrap_or(model.context_size() as usize)
+            .saturating_sub(reply_tokens);
+
+        let mut dropped = 0;
+        while self.history.len() > 1 && self.prompt_tokens(model) > budget {
+            // A turn is a user message and the replies that follow it
+            let end = self.history[1..]
+                .iter()
+                .position(|m| m.role == ChatRole::User)
+                .map_or(self.history.len(), |i| i + 1);
+            self.history.drain(..end);
+            dropped += 1;
+        }
+
+        if dropped > 0 {
+            debug!("Dropped {} old turns to fit {} tokens", dropped, budget);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backend::{InferenceBackend, TokenCallback};
+    use crate::cancel::CancellationToken;
+    use crate::chat::ChatTemplate;
+    use crate::error::InferenceError;
+    use crate::model::InferenceConfig;
+    use crate::sampler::SamplerConfig;
+    use std::sync::Arc;
+
+    /// Replies "ok", or fails when the last message asks it to
+    struct ReplyBackend;
+
+    impl InferenceBackend for ReplyBackend {
+        fn generate(
+            &self,
+            prompt: &str,
+            _max_tokens: u32,
+            _sampler: &SamplerConfig,
+            _cancel: &CancellationToken,
+        ) -> Result<String> {
+            if prompt.contains("fail<|eot_id|>Assistant:") {
+                return Err(InferenceError::Decode("bad prompt".to_string()));
+            }
+            Ok("ok".to_string())
+        }
+
+        fn generate_streaming(
+            &self,
+            prompt: &str,
+            max_tokens: u32,
+            sampler: &SamplerConfig,
+            _on_token: TokenCallback<'_>,
+            cancel: &CancellationToken,
+        ) -> Result<String> {
+            self.generate(prompt, max_tokens, sampler, cancel)
+        }
+
+        /// One token per character
+        fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+            Ok(text.chars().map(|c| c as i32).collect())
+        }
+
+        fn chat_template(&self) -> ChatTemplate {
+            ChatTemplate::default()
+        }
+
+        fn name(&self) -> &'static str {
+            "reply"
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+    }
+
+    fn model() -> InferenceModel {
+        InferenceModel::from_backend(Arc::new(ReplyBackend), InferenceConfig::default())
+    }
+
+    #[test]
+    fn test_send_records_turns() {
+        let model = model();
+        let mut session = ChatSession::new().with_system_prompt("Be brief.");
+
+        assert_eq!(
+            session
+                .send(&model, "Hi", &GenerateOptions::new(16))
+                .unwrap(),
+            "ok"
+        );
+        assert!(session
+            .send(&model, "fail", &GenerateOptions::new(16))
+            .is_err());
+        assert_eq!(
+            session.history(),
+            &[ChatMessage::user("Hi"), ChatMessage::assistant("ok")]
+        );
+        assert_eq!(session.messages()[0], ChatMessage::system("Be brief."));
+    }
+
+    #[test]
+    fn test_send_trims_oldest_turns() {
+        let model = model();
+        let options = GenerateOptions::new(16);
+        let mut session = ChatSession::new()
+            .with_system_prompt("Be brief.")
+            .with_context_budget(120);
+
+        for question in ["first question", "second question", "third question"] {
+            session.send(&model, question, &options).unwrap();
+        }
+
+        assert!(session.prompt_tokens(&model) + 16 <= 120);
+        assert_eq!(session.system_prompt(), Some("Be brief."));
+        assert!(!session
+            .history()
+            .contains(&ChatMessage::user("first question")));
+        assert_eq!(
+            session.history().last(),
+            Some(&ChatMessage::assistant("ok"))
+        );
+
+        // The newest message is kept even when it alone is over budget
+        let long = "x".repeat(200);
+        session.send(&model, long.as_str(), &options).unwrap();
+        assert_eq!(session.history()[0], ChatMessage::user(long));
+    }
+}
@@ -29,6 +29,12 @@ pub enum InferenceError {
     #[error("Generation interrupted")]
     Interrupted,
 
+    #[error("Model did not produce valid JSON: {0}")]
+    InvalidJson(String),
+
+    #[error("Translation failed: {0}")]
+    Translation(String),
+
     #[error("Invalid configuration: {0}")]
     InvalidConfig(String),
 
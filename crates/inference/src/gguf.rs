@@ -0,0 +1,309 @@
+//! Minimal GGUF metadata reader
+//!
+//! Reads string values from the key-value header of a GGUF file without
+//! loading tensors, e.g. `tokenizer.chat_template`, and checks that a file
+//! is not truncated.
+
+use crate::error::{InferenceError, Result};
+use std::fs::File;
+use std::io::{BufReader, Read, Seek, SeekFrom};
+use std::path::Path;
+
+/// GGUF magic number ("GGUF" in little endian)
+const GGUF_MAGIC: u32 = 0x4655_4747;
+
+/// Metadata key holding the Jinja chat template
+pub const CHAT_TEMPLATE_KEY: &str = "tokenizer.chat_template";
+
+/// Metadata key holding the tensor data alignment
+const ALIGNMENT_KEY: &str = "general.alignment";
+
+/// Alignment of tensor data when the file does not set one
+const DEFAULT_ALIGNMENT: u64 = 32;
+
+/// Most dimensions a GGML tensor can have
+const MAX_DIMS: u32 = 4;
+
+// GGUF value types
+const TYPE_UINT8: u32 = 0;
+const TYPE_INT8: u32 = 1;
+const TYPE_UINT16: u32 = 2;
+const TYPE_INT16: u32 = 3;
+const TYPE_UINT32: u32 = 4;
+const TYPE_INT32: u32 = 5;
+const TYPE_FLOAT32: u32 = 6;
+const TYPE_BOOL: u32 = 7;
+const TYPE_STRING: u32 = 8;
+const TYPE_ARRAY: u32 = 9;
+const TYPE_UINT64: u32 = 10;
+const TYPE_INT64: u32 = 11;
+const TYPE_FLOAT64: u32 = 12;
+
+/// Read a string metadata value from a GGUF file
+///
+/// Returns `None` when the key is absent or not a string.
+pub fn read_metadata_string<P: AsRef<Path>>(path: P, key: &str) -> Result<Option<String>> {
+    let file = File::open(path.as_ref()).map_err(InferenceError::Io)?;
+    read_string_from(&mut BufReader::new(file), key)
+}
+
+fn read_string_from<R: Read + Seek>(reader: &mut R, key: &str) -> Result<Option<String>> {
+    if read_u32(reader)? != GGUF_MAGIC {
+        return Err(invalid("not a GGUF file"));
+    }
+
+    let version = read_u32(reader)?;
+    // Version 1 used 32-bit counts
+    let (_tensor_count, kv_count) = if version == 1 {
+        (read_u32(reader)? as u64, read_u32(reader)? as u64)
+    } else {
+        (read_u64(reader)?, read_u64(reader)?)
+    };
+
+    for _ in 0..kv_count {
+        let name = read_string(reader, version)?;
+        let value_type = read_u32(reader)?;
+
+        if name == key && value_type == TYPE_STRING {
+            return Ok(Some(read_string(reader, version)?));
+        }
+        skip_value(reader, value_type, version)?;
+    }
+
+    Ok(None)
+}
+
+/// Check that a GGUF file is complete
+///
+/// Reads the whole header, then checks that the file is long enough to
+/// hold the tensor data at one bit per weight. That is less than any
+/// quantization uses, so it catches truncated files without knowing every
+/// tensor format.
+#[cfg_attr(not(feature = "download"), allow(dead_code))]
+pub fn check_complete<P: AsRef<Path>>(path: P) -> Result<()> {
+    let file = File::open(path.as_ref()).map_err(InferenceError::Io)?;
+    let file_len = file.metadata().map_err(InferenceError::Io)?.len();
+    check_complete_from(&mut BufReader::new(file), file_len)
+}
+
+fn check_complete_from<R: Read + Seek>(reader: &mut R, file_len: u64) -> Result<()> {
+    let truncated = |_| invalid("file ends inside the header");
+
+    if read_u32(reader)? != GGUF_MAGIC {
+        return Err(invalid("not a GGUF file"));
+    }
+
+    let version = read_u32(reader).map_err(truncated)?;
+    let (tensor_count, kv_count) = if version == 1 {
+        (read_u32(reader)? as u64, read_u32(reader)? as u64)
+    } else {
+        (read_u64(reader)?, read_u64(reader)?)
+    };
+
+    let mut alignment = DEFAULT_ALIGNMENT;
+    for _ in 0..kv_count {
+        let name = read_string(reader, version).map_err(truncated)?;
+        let value_type = read_u32(reader).map_err(truncated)?;
+
+        if name == ALIGNMENT_KEY && value_type == TYPE_UINT32 {
+            alignment = read_u32(reader).map_err(truncated)?.max(1) as u64;
+        } else {
+            skip_value(reader, value_type, version)?;
+        }
+    }
+
+    // Smallest end of tensor data the header allows
+    let mut min_data_len = 0u64;
+    for _ in 0..tensor_count {
+        read_string(reader, version).map_err(truncated)?;
+        let n_dims = read_u32(reader).map_err(truncated)?;
+        if n_dims > MAX_DIMS {
+            return Err(invalid(&format!("tensor has {} dimensions", n_dims)));
+        }
+        let mut n_elements = 1u64;
+        for _ in 0..n_dims {
+            n_elements = n_elements.saturating_mul(read_len(reader, version).map_err(truncated)?);
+        }
+        let _tensor_type = read_u32(reader).map_err(truncated)?;
+        let offset = read_u64(reader).map_err(truncated)?;
+        min_data_len = min_data_len.max(offset.saturating_add(n_elements.div_ceil(8)));
+    }
+
+    let header_len = reader.stream_position().map_err(InferenceError::Io)?;
+    if header_len > file_len {
+        return Err(invalid("file ends inside the header"));
+    }
+    let data_start = header_len.div_ceil(alignment) * alignment;
+    let min_len = data_start.saturating_add(min_data_len);
+    if tensor_count > 0 && min_len > file_len {
+        return Err(invalid(&format!(
+            "truncated: tensor data needs at least {} bytes, file has {}",
+            min_len, file_len
+        )));
+    }
+
+    Ok(())
+}
+
+fn skip_value<R: Read + Seek>(reader: &mut R, value_type: u32, version: u32) -> Result<()> {
+    match value_type {
+        TYPE_STRING => {
+            let len = read_len(reader, version)?;
+            skip(reader, len)
+        }
+        TYPE_ARRAY => {
+            let item_type = read_u32(reader)?;
+            let count = read_len(reader, version)?;
+            match scalar_size(item_type) {
+                Some(size) => skip(reader, count.saturating_mul(size)),
+                None => {
+                    for _ in 0..count {
+                        skip_value(reader, item_type, version)?;
+                    }
+                    Ok(())
+                }
+            }
+        }
+        other => match scalar_size(other) {
+            Some(size) => skip(reader, size),
+            None => Err(invalid(&format!("unknown value type {}", other))),
+        },
+    }
+}
+
+fn scalar_size(value_type: u32) -> Option<u64> {
+    match value_type {
+        TYPE_UINT8 | TYPE_INT8 | TYPE_BOOL => Some(1),
+        TYPE_UINT16 | TYPE_INT16 => Some(2),
+        TYPE_UINT32 | TYPE_INT32 | TYPE_FLOAT32 => Some(4),
+        TYPE_UINT64 | TYPE_INT64 | TYPE_FLOAT64 => Some(8),
+        _ => None,
+    }
+}
+
+fn read_string<R: Read>(reader: &mut R, version: u32) -> Result<String> {
+    let len = read_len(reader, version)?;
+    let mut buf = Vec::new();
+    reader
+        .take(len)
+        .read_to_end(&mut buf)
+        .map_err(InferenceError::Io)?;
+    if buf.len() as u64 != len {
+        return Err(invalid("truncated string"));
+    }
+    String::from_utf8(buf).map_err(|_| invalid("string is not valid UTF-8"))
+}
+
+fn read_len<R: Read>(reader: &mut R, version: u32) -> Result<u64> {
+    if version == 1 {
+        Ok(read_u32(reader)? as u64)
+    } else {
+        read_u64(reader)
+    }
+}
+
+fn skip<R: Seek>(reader: &mut R, len: u64) -> Result<()> {
+    let offset = i64::try_from(len).map_err(|_| invalid("value too large"))?;
+    reader
+        .seek(SeekFrom::Current(offset))
+        .map_err(InferenceError::Io)?;
+    Ok(())
+}
+
+fn read_u32<R: Read>(reader: &mut R) -> Result<u32> {
+    let mut buf = [0u8; 4];
+    reader.read_exact(&mut buf).map_err(InferenceError::Io)?;
+    Ok(u32::from_le_bytes(buf))
+}
+
+fn read_u64<R: Read>(reader: &mut R) -> Result<u64> {
+    let mut buf = [0u8; 8];
+    reader.read_exact(&mut buf).map_err(InferenceError::Io)?;
+    Ok(u64::from_le_bytes(buf))
+}
+
+fn invalid(reason: &str) -> InferenceError {
+    InferenceError::Io(std::io::Error::new(
+        std::io::ErrorKind::InvalidData,
+        format!("Invalid GGUF metadata: {}", reason),
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    fn gguf_string(buf: &mut Vec<u8>, s: &str) {
+        buf.extend((s.len() as u64).to_le_bytes());
+        buf.extend(s.as_bytes());
+    }
+
+    fn sample_gguf() -> Vec<u8> {
+        let mut buf = Vec::new();
+        buf.extend(GGUF_MAGIC.to_le_bytes());
+        buf.extend(3u32.to_le_bytes());
+        buf.extend(0u64.to_le_bytes());
+        buf.extend(3u64.to_le_bytes());
+
+        gguf_string(&mut buf, "general.architecture");
+        buf.extend(TYPE_STRING.to_le_bytes());
+        gguf_string(&mut buf, "bitnet");
+
+        gguf_string(&mut buf, "tokenizer.ggml.tokens");
+        buf.extend(TYPE_ARRAY.to_le_bytes());
+        buf.extend(TYPE_STRING.to_le_bytes());
+        buf.extend(2u64.to_le_bytes());
+        gguf_string(&mut buf, "<s>");
+        gguf_string(&mut buf, "</s>");
+
+        gguf_string(&mut buf, CHAT_TEMPLATE_KEY);
+        buf.extend(TYPE_STRING.to_le_bytes());
+        gguf_string(&mut buf, "{{ 'User: ' + content }}");
+        buf
+    }
+
+    #[test]
+    fn test_read_metadata_string() {
+        let data = sample_gguf();
+        let template = read_string_from(&mut Cursor::new(&data), CHAT_TEMPLATE_KEY).unwrap();
+        assert_eq!(template.as_deref(), Some("{{ 'User: ' + content }}"));
+
+        let missing = read_string_from(&mut Cursor::new(&data), "general.name").unwrap();
+        assert!(missing.is_none());
+    }
+
+    #[test]
+    fn test_check_complete() {
+        let mut data = sample_gguf();
+        data[16..24].copy_from_slice(&4u64.to_le_bytes());
+        gguf_string(&mut data, ALIGNMENT_KEY);
+        data.extend(TYPE_UINT32.to_le_bytes());
+        data.extend(64u32.to_le_bytes());
+
+        // One tensor of 1024 weights at offset 0
+        data[8..16].copy_from_slice(&1u64.to_le_bytes());
+        gguf_string(&mut data, "token_embd.weight");
+        data.extend(2u32.to_le_bytes());
+        data.extend(32u64.to_le_bytes());
+        data.extend(32u64.to_le_bytes());
+        data.extend(0u32.to_le_bytes());
+        data.extend(0u64.to_le_bytes());
+
+        let data_start = data.len().div_ceil(64) * 64;
+        data.resize(data_start + 1024 * 2, 0);
+        let len = data.len() as u64;
+        assert!(check_complete_from(&mut Cursor::new(&data), len).is_ok());
+
+        // Cut inside the tensor data, then inside the header
+        let cut = (data_start + 100) as u64;
+        assert!(check_complete_from(&mut Cursor::new(&data[..cut as usize]), cut).is_err());
+        assert!(check_complete_from(&mut Cursor::new(&data[..40]), 40).is_err());
+    }
+
+    #[test]
+    fn test_rejects_non_gguf() {
+        let result = read_string_from(&mut Cursor::new(b"not a model file"), CHAT_TEMPLATE_KEY);
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,152 @@
+//! Hugging Face Hub model references
+//!
+//! Any GGUF file on the Hub can be used by name with
+//! `hf:<org>/<repo>[@<revision>]:<file>`, e.g.
+//! `hf:microsoft/bitnet-b1.58-2B-4T-gguf@main:ggml-model-i2_s.gguf`.
+//! Gated and private repos need a token in `HF_TOKEN`.
+
+use crate::error::{InferenceError, Result};
+use std::path::PathBuf;
+
+/// Prefix of a Hub model reference
+pub const HUB_PREFIX: &str = "hf:";
+
+/// Revision used when none is given
+pub const DEFAULT_REVISION: &str = "main";
+
+/// Environment variables checked for a Hub access token, in order
+pub const TOKEN_ENV_VARS: &[&str] = &["HF_TOKEN", "HUGGING_FACE_HUB_TOKEN"];
+
+/// A GGUF file in a Hugging Face Hub repository
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HubFile {
+    spec: String,
+    /// Repository, as `org/repo`
+    pub repo: String,
+    /// Branch, tag or commit hash
+    pub revision: String,
+    /// Path of the file in the repository
+    pub filename: String,
+}
+
+impl HubFile {
+    /// Parse `hf:<org>/<repo>[@<revision>]:<file>`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = |reason: &str| {
+            InferenceError::InvalidConfig(format!(
+                "Invalid Hub model '{}': {}. Expected hf:<org>/<repo>[@<revision>]:<file>",
+                spec, reason
+            ))
+        };
+
+        let rest = spec
+            .strip_prefix(HUB_PREFIX)
+            .ok_or_else(|| invalid("missing hf: prefix"))?;
+        let (repo, filename) = rest
+            .split_once(':')
+            .ok_or_else(|| invalid("missing file name"))?;
+        let (repo, revision) = match repo.split_once('@') {
+            Some((repo, revision)) => (repo, revision),
+            None => (repo, DEFAULT_REVISION),
+        };
+
+        let mut parts = repo.split('/');
+        let valid_repo = matches!(
+            (parts.next(), parts.next(), parts.next()),
+            (Some(org), Some(name), None) if is_name(org) && is_name(name)
+        );
+        if !valid_repo {
+            return Err(invalid("repository must be <org>/<repo>"));
+        }
+        if !is_name(revision) {
+            return Err(invalid("invalid revision"));
+        }
+        if !filename.split('/').all(is_name) {
+            return Err(invalid("invalid file name"));
+        }
+
+        Ok(Self {
+            spec: spec.to_string(),
+            repo: repo.to_string(),
+            revision: revision.to_string(),
+            filename: filename.to_string(),
+        })
+    }
+
+    /// Whether `name` is a Hub model reference rather than a model name
+    pub fn is_hub_ref(name: &str) -> bool {
+        name.starts_with(HUB_PREFIX)
+    }
+
+    /// The reference as given, e.g. `hf:org/repo:file.gguf`
+    pub fn spec(&self) -> &str {
+        &self.spec
+    }
+
+    /// Download URL of the file at the pinned revision
+    pub fn url(&self) -> String {
+        format!(
+            "https://huggingface.co/{}/resolve/{}/{}",
+            self.repo, self.revision, self.filename
+        )
+    }
+
+    /// Path of the file relative to the cache directory
+    pub fn cache_path(&self) -> PathBuf {
+        PathBuf::from("hf")
+            .join(&self.repo)
+            .join(&self.revision)
+            .join(&self.filename)
+    }
+
+    /// Access token from `HF_TOKEN` or `HUGGING_FACE_HUB_TOKEN`
+    pub fn token_from_env() -> Option<String> {
+        TOKEN_ENV_VARS
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|token| !token.is_empty())
+    }
+}
+
+/// Repository, revision and path segments: no separators, no `..`
+fn is_name(s: &str) -> bool {
+    !s.is_empty()
+        && !s.starts_with('.')
+        && s.chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_hub_file() {
+        let file = HubFile::parse("hf:org/repo:model-q4.gguf").unwrap();
+        assert_eq!(file.repo, "org/repo");
+        assert_eq!(file.revision, DEFAULT_REVISION);
+        assert_eq!(
+            file.url(),
+            "https://huggingface.co/org/repo/resolve/main/model-q4.gguf"
+        );
+
+        let pinned = HubFile::parse("hf:org/repo@3f2a9c1:gguf/model.gguf").unwrap();
+        assert_eq!(pinned.revision, "3f2a9c1");
+        assert_eq!(
+            pinned.cache_path(),
+            PathBuf::from("hf/org/repo/3f2a9c1/gguf/model.gguf")
+        );
+        assert_eq!(pinned.spec(), "hf:org/repo@3f2a9c1:gguf/model.gguf");
+
+        for bad in [
+            "org/repo:model.gguf",
+            "hf:repo:model.gguf",
+            "hf:org/repo",
+            "hf:org/repo:../model.gguf",
+            "hf:org/repo@:model.gguf",
+            "hf:a/b/c:model.gguf",
+        ] {
+            assert!(HubFile::parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! JSON-mode generation helpers
+//!
+//! Structured output is produced by constraining sampling with a JSON
+//! grammar and then deserializing the first JSON value in the reply.
+
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// GBNF grammar accepting a JSON object or array
+pub const JSON_GRAMMAR: &str = r#"root   ::= object | array
+value  ::= object | array | string | number | ("true" | "false" | "null") ws
+object ::= "{" ws ( string ":" ws value ("," ws string ":" ws value)* )? "}" ws
+array  ::= "[" ws ( value ("," ws value)* )? "]" ws
+string ::= "\"" ( [^"\\] | "\\" (["\\/bfnrt] | "u" [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F] [0-9a-fA-F]) )* "\"" ws
+number ::= "-"? ([0-9] | [1-9] [0-9]*) ("." [0-9]+)? ([eE] [-+]? [0-9]+)? ws
+ws     ::= ([ \t\n] ws)?
+"#;
+
+/// Number of generations tried before giving up on unparseable output
+pub(crate) const MAX_ATTEMPTS: u32 = 3;
+
+/// Append instructions asking for JSON that matches `schema`
+pub(crate) fn build_prompt(prompt: &str, schema: &Value) -> String {
+    let schema = serde_json::to_string_pretty(schema).unwrap_or_else(|_| schema.to_string());
+    format!(
+        "{}\n\nRespond only with JSON matching this schema:\n{}\n",
+        prompt.trim_end(),
+        schema
+    )
+}
+
+/// Deserialize the first JSON object or array in `output`
+///
+/// Text before the value (e.g. a code fence) and after it is ignored.
+pub(crate) fn parse<T: DeserializeOwned>(output: &str) -> std::result::Result<T, String> {
+    let start = output
+        .find(['{', '['])
+        .ok_or_else(|| "no JSON object or array in output".to_string())?;
+
+    serde_json::Deserializer::from_str(&output[start..])
+        .into_iter::<T>()
+        .next()
+        .unwrap_or_else(|| Err(serde::de::Error::custom("empty output")))
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct Answer {
+        city: String,
+        population: u64,
+    }
+
+    #[test]
+    fn test_parse_ignores_surrounding_text() {
+        let output = "```json\n{\"city\": \"Lima\", \"population\": 10000000}\n```\nDone.";
+        let answer: Answer = parse(output).unwrap();
+        assert_eq!(
+            answer,
+            Answer {
+                city: "Lima".to_string(),
+                population: 10_000_000
+            }
+        );
+
+        let list: Vec<u32> = parse("[1, 2, 3]").unwrap();
+        assert_eq!(list, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_output() {
+        assert!(parse::<Answer>("no json here").is_err());
+        assert!(parse::<Answer>("{\"city\": \"Lima\"}").is_err());
+        assert!(parse::<Answer>("{\"city\": ").is_err());
+    }
+
+    #[test]
+    fn test_build_prompt_includes_schema() {
+        let schema = serde_json::json!({"type": "object"});
+        let prompt = build_prompt("Name a city. ", &schema);
+        assert!(prompt.starts_with("Name a city.\n\n"));
+        assert!(prompt.contains("\"type\": \"object\""));
+    }
+}
@@ -6,18 +6,24 @@
 //! ## Features
 //!
 //! - `subprocess` - Use subprocess backend (calls llama-cli binary)
+//! - `server` - Use a persistent llama-server process
 //! - `native` - Use native FFI bindings to bitnet.cpp (fastest)
 //! - `cuda` - Enable CUDA GPU acceleration (requires `native`)
 //! - `download` - Enable model downloading with progress bars
+//! - `translate-api` - Translate queries through a LibreTranslate-compatible API
 //!
 //! ## Backends
 //!
-//! Two inference backends are available:
+//! Three inference backends are available, tried in this order by
+//! [`BackendType::Auto`]:
 //!
 //! 1. **Native FFI** (`native` feature) - Direct bindings to bitnet.cpp for
 //!    maximum performance. Requires bitnet.cpp to be compiled from source.
 //!
-//! 2. **Subprocess** (`subprocess` feature, default) - Calls the llama-cli
+//! 2. **Server** (`server` feature, default) - Starts bitnet.cpp's
+//!    llama-server once and keeps the model loaded between calls.
+//!
+//! 3. **Subprocess** (`subprocess` feature, default) - Calls the llama-cli
 //!    binary from bitnet.cpp. Works out of the box if binary is installed.
 //!
 //! ## Example
@@ -33,9 +39,20 @@
 //! ```
 
 mod backend;
+mod blocking;
+mod cancel;
+mod chat;
+mod chat_session;
 mod error;
+mod gguf;
+mod hub;
+mod json;
+mod logprobs;
 mod model;
+mod registry;
 mod sampler;
+mod stop;
+pub mod bench;
 pub mod models;
 pub mod cache;
 pub mod translation;
@@ -43,25 +60,46 @@ pub mod translation;
 #[cfg(feature = "subprocess")]
 pub mod subprocess;
 
+#[cfg(feature = "server")]
+pub mod server;
+
 #[cfg(feature = "native")]
 pub mod native;
 
-pub use backend::{InferenceBackend, BackendType};
+pub use backend::{InferenceBackend, BackendType, TokenCallback};
+pub use blocking::TokenStream;
+pub use cancel::{CancellationToken, DropGuard};
+pub use chat::{ChatMessage, ChatRole, ChatTemplate};
+pub use chat_session::ChatSession;
 pub use error::InferenceError;
+pub use json::JSON_GRAMMAR;
+pub use logprobs::{GenerationOutput, TokenLogprob};
 pub use model::{InferenceModel, InferenceConfig, GenerateOptions};
-pub use sampler::SamplerConfig;
-pub use translation::{Language, detect_language, build_translation_prompt, build_multilingual_prompt, translate_to_english};
+pub use sampler::{BiasTarget, Mirostat, SamplerConfig};
+pub use translation::{
+    Language, Translator, TranslatorKind, DictionaryTranslator, ModelTranslator, detect_language,
+    detect_language_with_confidence, LanguageDetection, build_translation_prompt,
+    build_multilingual_prompt, ensure_language, translate_to_english,
+};
+#[cfg(feature = "translate-api")]
+pub use translation::ApiTranslator;
 pub use models::BitNetModel;
+pub use hub::HubFile;
+pub use registry::{CustomModel, ModelRef, ModelRegistry};
 pub use cache::ModelCache;
 
 #[cfg(feature = "native")]
 pub use native::{
-    NativeBackend, LlamaModel, LlamaContext, LlamaSampler, LlamaBatch,
-    ContextPool, PooledContext, PoolConfig, ModelParams, ContextParams,
+    NativeBackend, NativeSession, LlamaModel, LlamaContext, LlamaSampler, LlamaBatch,
+    ContextPool, PooledContext, PoolConfig, ModelParams, ContextParams, PrefixCacheConfig,
+    PrefixCacheStats,
 };
 
 #[cfg(feature = "download")]
-pub use cache::download::{download_model, get_or_download, DownloadOptions};
+pub use cache::download::{
+    download_model, download_ref, get_or_download, get_or_download_ref, verify_ref, DownloadOptions,
+    VerifyStatus,
+};
 
 /// Check if native bindings are available
 pub fn native_available() -> bool {
@@ -0,0 +1,138 @@
+//! Per-token log-probabilities
+//!
+//! Used to estimate how confident the model was in an answer, e.g. to
+//! flag low-confidence RAG responses.
+
+use serde::Serialize;
+
+/// Log-probability of one generated token
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct TokenLogprob {
+    /// Token text
+    pub token: String,
+    /// Natural log of the token's probability
+    pub logprob: f32,
+    /// Most likely tokens at this position with their log-probabilities,
+    /// most likely first
+    pub top: Vec<(String, f32)>,
+}
+
+/// Generated text with per-token log-probabilities
+///
+/// `text` is the concatenation of the tokens and is not trimmed.
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct GenerationOutput {
+    /// Generated text
+    pub text: String,
+    /// Generated tokens in order
+    pub tokens: Vec<TokenLogprob>,
+}
+
+impl GenerationOutput {
+    /// Average log-probability per token
+    pub fn mean_logprob(&self) -> Option<f32> {
+        if self.tokens.is_empty() {
+            return None;
+        }
+        let sum: f32 = self.tokens.iter().map(|t| t.logprob).sum();
+        Some(sum / self.tokens.len() as f32)
+    }
+
+    /// Perplexity of the generated tokens (lower is more confident)
+    pub fn perplexity(&self) -> Option<f32> {
+        self.mean_logprob().map(|mean| (-mean).exp())
+    }
+
+    /// Cut the text at byte `len`, dropping tokens that end after it
+    pub(crate) fn truncate(&mut self, len: usize) {
+        if len >= self.text.len() {
+            return;
+        }
+        self.text.truncate(len);
+
+        let mut end = 0;
+        let keep = self
+            .tokens
+            .iter()
+            .take_while(|t| {
+                end += t.token.len();
+                end <= len
+            })
+            .count();
+        self.tokens.truncate(keep);
+    }
+}
+
+/// Log-probability of `chosen` under `logits`, and the `top_n` most likely
+/// token ids with their log-probabilities
+#[cfg_attr(not(feature = "native"), allow(dead_code))]
+pub(crate) fn log_softmax_top(
+    logits: &[f32],
+    chosen: usize,
+    top_n: usize,
+) -> (f32, Vec<(usize, f32)>) {
+    let max = logits.iter().copied().fold(f32::NEG_INFINITY, f32::max);
+    let log_sum = logits.iter().map(|l| (l - max).exp()).sum::<f32>().ln() + max;
+    let logprob = |i: usize| logits[i] - log_sum;
+
+    let mut ids: Vec<usize> = (0..logits.len()).collect();
+    let top_n = top_n.min(ids.len());
+    if top_n > 0 && top_n < ids.len() {
+        ids.select_nth_unstable_by(top_n - 1, |a, b| logits[*b].total_cmp(&logits[*a]));
+    }
+    ids.truncate(top_n);
+    ids.sort_by(|a, b| logits[*b].total_cmp(&logits[*a]));
+
+    let chosen_logprob = logits
+        .get(chosen)
+        .map_or(f32::NEG_INFINITY, |_| logprob(chosen));
+    (
+        chosen_logprob,
+        ids.into_iter().map(|i| (i, logprob(i))).collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn token(text: &str, logprob: f32) -> TokenLogprob {
+        TokenLogprob {
+            token: text.to_string(),
+            logprob,
+            top: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn test_log_softmax_top() {
+        let logits = [1.0f32, 3.0, 2.0, 0.0];
+        let (chosen, top) = log_softmax_top(&logits, 2, 2);
+
+        let total: f32 = logits.iter().map(|l| l.exp()).sum();
+        assert!((chosen - (2.0f32.exp() / total).ln()).abs() < 1e-5);
+        assert_eq!(top.iter().map(|(i, _)| *i).collect::<Vec<_>>(), vec![1, 2]);
+        assert!((top[1].1 - chosen).abs() < 1e-6);
+
+        assert!(log_softmax_top(&logits, 0, 0).1.is_empty());
+    }
+
+    #[test]
+    fn test_truncate_drops_tokens_past_stop() {
+        let mut output = GenerationOutput {
+            text: "Yes.\n\nQ:".to_string(),
+            tokens: vec![
+                token("Yes", -0.1),
+                token(".", -0.2),
+                token("\n\n", -0.5),
+                token("Q:", -1.0),
+            ],
+        };
+        output.truncate(4);
+
+        assert_eq!(output.text, "Yes.");
+        assert_eq!(output.tokens.len(), 2);
+        assert!((output.mean_logprob().unwrap() + 0.15).abs() < 1e-6);
+        assert!(GenerationOutput::default().perplexity().is_none());
+    }
+}
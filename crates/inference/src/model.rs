@@ -2,18 +2,29 @@
 //!
 //! Supports multiple backends: native FFI (fastest) and subprocess (fallback).
 
-use crate::backend::{BackendType, InferenceBackend};
+use crate::backend::{BackendType, InferenceBackend, TokenCallback};
+use crate::blocking::{self, TokenStream};
+use crate::cancel::CancellationToken;
+use crate::chat::{ChatMessage, ChatTemplate};
 use crate::error::{InferenceError, Result};
-use crate::sampler::SamplerConfig;
-use crate::translation::{detect_language, build_translation_prompt, Language};
-use std::io::{self, Write};
+use crate::json::{self, JSON_GRAMMAR};
+use crate::logprobs::GenerationOutput;
+use crate::sampler::{BiasTarget, SamplerConfig};
+use crate::stop::{StopConditions, StopFilter};
+use crate::translation::{detect_language, ensure_language, Language, ModelTranslator, Translator};
+use serde::de::DeserializeOwned;
 use std::path::Path;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::{mpsc, oneshot};
 use tracing::{info, warn, debug};
 
 #[cfg(feature = "subprocess")]
 use crate::subprocess::SubprocessBackend;
 
+#[cfg(feature = "server")]
+use crate::server::ServerBackend;
+
 #[cfg(feature = "native")]
 use crate::native::{NativeBackend, ModelParams, PoolConfig, ContextParams};
 
@@ -34,8 +45,17 @@ pub struct InferenceConfig {
     pub use_mlock: bool,
     /// Backend type to use
     pub backend: BackendType,
-    /// Context pool size (for native backend)
+    /// Context pool size for the native backend, or the number of
+    /// parallel llama-cli processes for the subprocess backend
     pub pool_size: Option<usize>,
+    /// Chat template (detected from the model when unset)
+    pub chat_template: Option<ChatTemplate>,
+    /// Layers to offload to the GPU (0 = CPU only, needs the `cuda` feature)
+    pub n_gpu_layers: i32,
+    /// GPU that holds the model when it is not split across GPUs
+    pub main_gpu: i32,
+    /// Share of the model per GPU, e.g. `[3.0, 1.0]` (empty = automatic)
+    pub tensor_split: Vec<f32>,
 }
 
 impl Default for InferenceConfig {
@@ -49,6 +69,10 @@ impl Default for InferenceConfig {
             use_mlock: false,
             backend: BackendType::Auto,
             pool_size: None,
+            chat_template: None,
+            n_gpu_layers: 0,
+            main_gpu: 0,
+            tensor_split: Vec::new(),
         }
     }
 }
@@ -86,6 +110,46 @@ impl InferenceConfig {
         self.pool_size = Some(size);
         self
     }
+
+    /// Set the chat template instead of detecting it from the model
+    pub fn with_chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = Some(template);
+        self
+    }
+
+    /// Offload layers to the GPU (`i32::MAX` offloads all of them)
+    pub fn with_gpu_layers(mut self, n_gpu_layers: i32) -> Self {
+        self.n_gpu_layers = n_gpu_layers;
+        self
+    }
+
+    /// Select the GPU that holds the model
+    pub fn with_main_gpu(mut self, main_gpu: i32) -> Self {
+        self.main_gpu = main_gpu;
+        self
+    }
+
+    /// Split the model across GPUs in these proportions
+    pub fn with_tensor_split(mut self, tensor_split: Vec<f32>) -> Self {
+        self.tensor_split = tensor_split;
+        self
+    }
+
+    /// GPU layers to actually offload
+    ///
+    /// Without the `cuda` feature the crate cannot use a GPU, so a request
+    /// for offload is ignored with a warning.
+    #[cfg_attr(not(any(feature = "native", feature = "subprocess")), allow(dead_code))]
+    fn gpu_layers(&self) -> i32 {
+        if cfg!(feature = "cuda") {
+            self.n_gpu_layers
+        } else {
+            if self.n_gpu_layers > 0 {
+                warn!("GPU offload requested but neuro-inference was built without the `cuda` feature");
+            }
+            0
+        }
+    }
 }
 
 /// Options for text generation
@@ -97,8 +161,13 @@ pub struct GenerateOptions {
     pub sampler: SamplerConfig,
     /// Stop sequences
     pub stop_sequences: Vec<String>,
-    /// Whether to stream output
-    pub stream: bool,
+    /// Regular expressions that end generation, matched one line at a time
+    pub stop_patterns: Vec<String>,
+    /// Alternatives reported per token by
+    /// [`InferenceModel::generate_with_logprobs`]
+    pub top_logprobs: usize,
+    /// Aborts generation once cancelled
+    pub cancellation: CancellationToken,
 }
 
 impl Default for GenerateOptions {
@@ -107,7 +176,9 @@ impl Default for GenerateOptions {
             max_tokens: 512,
             sampler: SamplerConfig::default(),
             stop_sequences: vec![],
-            stream: false,
+            stop_patterns: vec![],
+            top_logprobs: 0,
+            cancellation: CancellationToken::default(),
         }
     }
 }
@@ -121,12 +192,6 @@ impl GenerateOptions {
         }
     }
 
-    /// Enable streaming
-    pub fn with_stream(mut self, stream: bool) -> Self {
-        self.stream = stream;
-        self
-    }
-
     /// Set sampler config
     pub fn with_sampler(mut self, sampler: SamplerConfig) -> Self {
         self.sampler = sampler;
@@ -144,93 +209,106 @@ impl GenerateOptions {
         self.stop_sequences.push(stop.into());
         self
     }
+
+    /// Add a stop pattern (regular expression)
+    ///
+    /// Output is cut at the start of the first match. Patterns are matched
+    /// within a line, and an invalid pattern fails generation with
+    /// [`InferenceError::InvalidConfig`].
+    pub fn with_stop_pattern(mut self, pattern: impl Into<String>) -> Self {
+        self.stop_patterns.push(pattern.into());
+        self
+    }
+
+    /// Constrain output to a GBNF grammar (see [`SamplerConfig::with_grammar`])
+    pub fn with_grammar(mut self, grammar: impl Into<String>) -> Self {
+        self.sampler.grammar = Some(grammar.into());
+        self
+    }
+
+    /// Abort generation when `token` is cancelled
+    ///
+    /// Generation then fails with [`InferenceError::Interrupted`].
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = token;
+        self
+    }
+
+    /// Set how many alternatives to report per token
+    pub fn with_top_logprobs(mut self, n: usize) -> Self {
+        self.top_logprobs = n;
+        self
+    }
+
+    /// Boost or suppress a token or text (see [`SamplerConfig::with_logit_bias`])
+    pub fn with_logit_bias(mut self, target: impl Into<BiasTarget>, bias: f32) -> Self {
+        self.sampler.logit_bias.insert(target.into(), bias);
+        self
+    }
 }
 
 /// High-level inference model wrapper for BitNet
 /// 
 /// Supports multiple backends: native FFI (fastest) and subprocess (fallback).
+/// Cloning is cheap and shares the loaded backend.
+#[derive(Clone)]
 pub struct InferenceModel {
     backend: Arc<dyn InferenceBackend>,
-    #[allow(dead_code)]
     config: InferenceConfig,
 }
 
 impl InferenceModel {
     /// Load a model from a GGUF file
     /// 
-    /// Automatically selects the best available backend:
+    /// With [`BackendType::Auto`], selects the best available backend:
     /// 1. Native FFI (if compiled with `native` feature)
-    /// 2. Subprocess (fallback, requires llama-cli binary)
+    /// 2. Persistent llama-server (if compiled with `server` feature)
+    /// 3. Subprocess (fallback, requires llama-cli binary)
+    ///
+    /// The choice is reported by [`Self::backend_name`].
     pub fn load(config: InferenceConfig) -> Result<Self> {
         Self::load_with_backend(config.clone(), config.backend)
     }
 
     /// Load with a specific backend type
     pub fn load_with_backend(config: InferenceConfig, backend_type: BackendType) -> Result<Self> {
-        let backend: Arc<dyn InferenceBackend> = match backend_type {
-            BackendType::Native => {
-                #[cfg(feature = "native")]
-                {
-                    Self::create_native_backend(&config)?
-                }
-                #[cfg(not(feature = "native"))]
-                {
-                    return Err(InferenceError::InvalidConfig(
-                        "Native backend not available (compile with --features native)".to_string()
-                    ));
-                }
-            }
-            BackendType::Subprocess => {
-                #[cfg(feature = "subprocess")]
-                {
-                    Self::create_subprocess_backend(&config)?
-                }
-                #[cfg(not(feature = "subprocess"))]
-                {
-                    return Err(InferenceError::InvalidConfig(
-                        "Subprocess backend not available (compile with --features subprocess)".to_string()
-                    ));
-                }
-            }
-            BackendType::Auto => {
-                // Try native first, fall back to subprocess
-                #[cfg(feature = "native")]
-                {
-                    match Self::create_native_backend(&config) {
-                        Ok(backend) => backend,
-                        Err(e) => {
-                            warn!("Native backend failed: {}, trying subprocess...", e);
-                            #[cfg(feature = "subprocess")]
-                            {
-                                Self::create_subprocess_backend(&config)?
-                            }
-                            #[cfg(not(feature = "subprocess"))]
-                            {
-                                return Err(e);
-                            }
-                        }
-                    }
-                }
-                #[cfg(not(feature = "native"))]
-                {
-                    #[cfg(feature = "subprocess")]
-                    {
-                        Self::create_subprocess_backend(&config)?
-                    }
-                    #[cfg(not(feature = "subprocess"))]
-                    {
-                        return Err(InferenceError::InvalidConfig(
-                            "No backend available (compile with --features subprocess or --features native)".to_string()
-                        ));
-                    }
-                }
-            }
+        let backend = match backend_type {
+            BackendType::Native => Self::create_native_backend(&config)?,
+            BackendType::Server => Self::create_server_backend(&config)?,
+            BackendType::Subprocess => Self::create_subprocess_backend(&config)?,
+            BackendType::Auto => Self::resolve_backend(&config)?,
         };
 
         info!("Loaded model with backend: {}", backend.name());
         Ok(Self { backend, config })
     }
 
+    /// Create the first backend that works, in order of preference
+    fn resolve_backend(config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
+        type Create = fn(&InferenceConfig) -> Result<Arc<dyn InferenceBackend>>;
+        let candidates: [(BackendType, Create); 3] = [
+            (BackendType::Native, Self::create_native_backend),
+            (BackendType::Server, Self::create_server_backend),
+            (BackendType::Subprocess, Self::create_subprocess_backend),
+        ];
+
+        let mut errors = Vec::new();
+        for (backend_type, create) in candidates {
+            match create(config) {
+                Ok(backend) => return Ok(backend),
+                Err(e) => {
+                    debug!("{} backend unavailable: {}", backend_type, e);
+                    errors.push(format!("{}: {}", backend_type, e));
+                }
+            }
+        }
+
+        Err(InferenceError::BackendInit(format!(
+            "No inference backend available ({})",
+            errors.join("; ")
+        )))
+    }
+
     /// Create native backend
     #[cfg(feature = "native")]
     fn create_native_backend(config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
@@ -243,6 +321,9 @@ impl InferenceModel {
         }
 
         let model_params = ModelParams {
+            n_gpu_layers: config.gpu_layers(),
+            main_gpu: config.main_gpu,
+            tensor_split: config.tensor_split.clone(),
             use_mmap: config.use_mmap,
             use_mlock: config.use_mlock,
             ..Default::default()
@@ -259,7 +340,10 @@ impl InferenceModel {
             PoolConfig::default().with_context_params(ctx_params)
         };
 
-        let backend = NativeBackend::new(&config.model_path, model_params, pool_config)?;
+        let mut backend = NativeBackend::new(&config.model_path, model_params, pool_config)?;
+        if let Some(template) = config.chat_template {
+            backend = backend.with_chat_template(template);
+        }
         Ok(Arc::new(backend))
     }
 
@@ -274,11 +358,64 @@ impl InferenceModel {
         if let Some(threads) = config.n_threads {
             backend = backend.with_threads(threads);
         }
+        if let Some(pool_size) = config.pool_size {
+            backend = backend.with_max_parallel(pool_size);
+        }
+        let n_gpu_layers = config.gpu_layers();
+        if n_gpu_layers > 0 {
+            backend = backend.with_gpu_offload(
+                n_gpu_layers,
+                config.main_gpu,
+                config.tensor_split.clone(),
+            );
+        }
+        if let Some(template) = config.chat_template {
+            backend = backend.with_chat_template(template);
+        }
 
         info!("BitNet model ready: {}", config.model_path);
         Ok(Arc::new(backend))
     }
 
+    /// Create server backend
+    #[cfg(feature = "server")]
+    fn create_server_backend(config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
+        info!("Starting BitNet server backend...");
+
+        let mut backend = ServerBackend::start(&config.model_path, config.n_ctx, config.n_threads)?;
+        if let Some(template) = config.chat_template {
+            backend = backend.with_chat_template(template);
+        }
+        Ok(Arc::new(backend))
+    }
+
+    #[cfg(not(feature = "native"))]
+    fn create_native_backend(_config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
+        Err(InferenceError::InvalidConfig(
+            "Native backend not available (compile with --features native)".to_string()
+        ))
+    }
+
+    #[cfg(not(feature = "server"))]
+    fn create_server_backend(_config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
+        Err(InferenceError::InvalidConfig(
+            "Server backend not available (compile with --features server)".to_string()
+        ))
+    }
+
+    #[cfg(not(feature = "subprocess"))]
+    fn create_subprocess_backend(_config: &InferenceConfig) -> Result<Arc<dyn InferenceBackend>> {
+        Err(InferenceError::InvalidConfig(
+            "Subprocess backend not available (compile with --features subprocess)".to_string()
+        ))
+    }
+
+    /// Wrap an already created backend
+    #[cfg(test)]
+    pub(crate) fn from_backend(backend: Arc<dyn InferenceBackend>, config: InferenceConfig) -> Self {
+        Self { backend, config }
+    }
+
     /// Load with a specific binary path (subprocess only)
     #[cfg(feature = "subprocess")]
     pub fn load_with_binary<P1: AsRef<Path>, P2: AsRef<Path>>(
@@ -294,6 +431,9 @@ impl InferenceModel {
         if let Some(threads) = config.n_threads {
             backend = backend.with_threads(threads);
         }
+        if let Some(template) = config.chat_template {
+            backend = backend.with_chat_template(template);
+        }
 
         Ok(Self { 
             backend: Arc::new(backend), 
@@ -303,29 +443,204 @@ impl InferenceModel {
 
     /// Generate text from a prompt
     pub fn generate(&self, prompt: &str, options: &GenerateOptions) -> Result<String> {
-        if options.stream {
-            let mut output = String::new();
-            let mut callback = |token: &str| {
-                print!("{}", token);
-                io::stdout().flush().ok();
+        let stops = stop_conditions(options)?;
+        let output = self.backend.generate(prompt, options.max_tokens, &options.sampler, &options.cancellation)?;
+        Ok(stops.apply(&output))
+    }
+
+    /// Get the model ready to answer its first request quickly
+    ///
+    /// Pages the weights into memory with one tiny decode and, for the
+    /// native backend, prepares every pre-allocated pooled context. Worth
+    /// calling right after [`Self::load`] in servers, so the first request
+    /// does not pay for it.
+    pub fn warm_up(&self) -> Result<()> {
+        let start = std::time::Instant::now();
+        self.backend.warm_up(&CancellationToken::new())?;
+        info!("Warmed up {} in {:?}", self.backend.name(), start.elapsed());
+        Ok(())
+    }
+
+    /// Generate text for several prompts concurrently
+    ///
+    /// Runs up to [`InferenceBackend::max_parallel`] generations at once:
+    /// one per pooled context for the native backend, or that many
+    /// llama-cli processes for the subprocess backend. Results are in the
+    /// order of `prompts`, and one failing prompt does not affect the rest.
+    pub fn generate_many<S: AsRef<str> + Sync>(
+        &self,
+        prompts: &[S],
+        options: &GenerateOptions,
+    ) -> Vec<Result<String>> {
+        let n_workers = self.backend.max_parallel().clamp(1, prompts.len().max(1));
+        let next = AtomicUsize::new(0);
+        let results: Mutex<Vec<Option<Result<String>>>> =
+            Mutex::new(prompts.iter().map(|_| None).collect());
+        debug!("Generating {} prompts with {} workers", prompts.len(), n_workers);
+
+        std::thread::scope(|scope| {
+            for _ in 0..n_workers {
+                scope.spawn(|| loop {
+                    let i = next.fetch_add(1, Ordering::SeqCst);
+                    let Some(prompt) = prompts.get(i) else {
+                        break;
+                    };
+                    let result = self.generate(prompt.as_ref(), options);
+                    results.lock().unwrap_or_else(|e| e.into_inner())[i] = Some(result);
+                });
+            }
+        });
+
+        results
+            .into_inner()
+            .unwrap_or_else(|e| e.into_inner())
+            .into_iter()
+            .map(|result| result.expect("every prompt is generated"))
+            .collect()
+    }
+
+    /// Generate text from a prompt, passing each token to `on_token` as it
+    /// is produced
+    ///
+    /// Generation ends at the first stop sequence or stop pattern match,
+    /// which is never passed on; text that could be the start of a stop is
+    /// held back until it is known not to be. With stop patterns, text is
+    /// passed on a line at a time. Returns the same text as
+    /// [`Self::generate`].
+    ///
+    /// ```no_run
+    /// use std::io::Write;
+    /// use neuro_inference::{GenerateOptions, InferenceConfig, InferenceModel};
+    ///
+    /// let model = InferenceModel::load(InferenceConfig::new("model.gguf")).unwrap();
+    /// let options = GenerateOptions::new(128).with_stop_sequence("\n\n");
+    /// model
+    ///     .generate_streaming("Q: What is Rust?\nA:", &options, &mut |token| {
+    ///         print!("{}", token);
+    ///         std::io::stdout().flush().ok();
+    ///     })
+    ///     .unwrap();
+    /// ```
+    pub fn generate_streaming(
+        &self,
+        prompt: &str,
+        options: &GenerateOptions,
+        on_token: TokenCallback<'_>,
+    ) -> Result<String> {
+        let stops = stop_conditions(options)?;
+        let mut output = String::new();
+        let mut filter = StopFilter::new(&stops);
+        // Cancelled once a stop is reached, so the backend stops generating
+        let stop = options.cancellation.child_token();
+
+        let result = self.backend.generate_streaming(
+            prompt,
+            options.max_tokens,
+            &options.sampler,
+            &mut |token: &str| {
                 output.push_str(token);
-            };
-            self.backend.generate_streaming(
-                prompt,
-                options.max_tokens,
-                &options.sampler,
-                &mut callback,
-            )?;
-            println!();
-            
-            // Apply stop sequences
-            let final_output = self.apply_stop_sequences(&output, &options.stop_sequences);
-            Ok(final_output)
-        } else {
-            let output = self.backend.generate(prompt, options.max_tokens, &options.sampler)?;
-            let final_output = self.apply_stop_sequences(&output, &options.stop_sequences);
-            Ok(final_output)
+                if let Some(text) = filter.push(token) {
+                    on_token(&text);
+                }
+                if filter.is_stopped() {
+                    stop.cancel();
+                }
+            },
+            &stop,
+        );
+        match result {
+            Err(InferenceError::Interrupted) if filter.is_stopped() && !options.cancellation.is_cancelled() => {
+                debug!("Generation ended at a stop sequence");
+            }
+            result => {
+                result?;
+            }
+        }
+        if let Some(text) = filter.finish() {
+            on_token(&text);
+        }
+
+        Ok(stops.apply(&output))
+    }
+
+    /// Generate text with per-token log-probabilities
+    ///
+    /// Each token comes with [`GenerateOptions::top_logprobs`] alternatives.
+    /// Stop sequences cut the text and drop the tokens after the cut.
+    /// Only the native backend reports log-probabilities; others return
+    /// an error.
+    pub fn generate_with_logprobs(&self, prompt: &str, options: &GenerateOptions) -> Result<GenerationOutput> {
+        let stops = stop_conditions(options)?;
+        let mut output = self.backend.generate_with_logprobs(
+            prompt,
+            options.max_tokens,
+            &options.sampler,
+            options.top_logprobs,
+            &options.cancellation,
+        )?;
+        if let Some(pos) = stops.find(&output.text) {
+            output.truncate(pos);
         }
+        Ok(output)
+    }
+
+    /// Generate a JSON value matching `schema` and deserialize it
+    ///
+    /// The schema is shown to the model and, unless `options` already sets
+    /// a grammar, output is constrained by [`JSON_GRAMMAR`]. The reply is
+    /// deserialized into `T`, which is what enforces the schema; output
+    /// that fails to parse is regenerated a few times before giving up
+    /// with [`InferenceError::InvalidJson`].
+    ///
+    /// ```no_run
+    /// use neuro_inference::{GenerateOptions, InferenceConfig, InferenceModel};
+    /// use serde::Deserialize;
+    ///
+    /// #[derive(Deserialize)]
+    /// struct Sentiment {
+    ///     label: String,
+    ///     score: f32,
+    /// }
+    ///
+    /// let model = InferenceModel::load(InferenceConfig::new("model.gguf")).unwrap();
+    /// let schema = serde_json::json!({
+    ///     "type": "object",
+    ///     "properties": {"label": {"type": "string"}, "score": {"type": "number"}},
+    ///     "required": ["label", "score"]
+    /// });
+    /// let sentiment: Sentiment = model
+    ///     .generate_json("Classify: I love it!", &schema, &GenerateOptions::new(64))
+    ///     .unwrap();
+    /// ```
+    pub fn generate_json<T: DeserializeOwned>(
+        &self,
+        prompt: &str,
+        schema: &serde_json::Value,
+        options: &GenerateOptions,
+    ) -> Result<T> {
+        let prompt = json::build_prompt(prompt, schema);
+        let mut options = options.clone();
+        if options.sampler.grammar.is_none() {
+            options.sampler.grammar = Some(JSON_GRAMMAR.to_string());
+        }
+
+        let mut last_error = String::new();
+        for attempt in 1..=json::MAX_ATTEMPTS {
+            let output = self.generate(&prompt, &options)?;
+            match json::parse(&output) {
+                Ok(value) => return Ok(value),
+                Err(e) => {
+                    warn!("JSON output attempt {}/{} failed: {}", attempt, json::MAX_ATTEMPTS, e);
+                    last_error = e;
+                }
+            }
+            // A fixed seed would reproduce the same output
+            if options.sampler.seed != 0 {
+                options.sampler.seed = options.sampler.seed.wrapping_add(1);
+            }
+        }
+
+        Err(InferenceError::InvalidJson(last_error))
     }
 
     /// Generate with a system prompt and user message
@@ -335,7 +650,151 @@ impl InferenceModel {
         user_message: &str,
         options: &GenerateOptions,
     ) -> Result<String> {
-        self.backend.chat(system_prompt, user_message, options.max_tokens, &options.sampler)
+        self.chat_messages(
+            &[
+                ChatMessage::system(system_prompt),
+                ChatMessage::user(user_message),
+            ],
+            options,
+        )
+    }
+
+    /// Generate the assistant's reply to a multi-turn conversation
+    pub fn chat_messages(&self, messages: &[ChatMessage], options: &GenerateOptions) -> Result<String> {
+        let stops = stop_conditions(options)?;
+        let output = self.backend.chat(
+            messages,
+            options.max_tokens,
+            &options.sampler,
+            &options.cancellation,
+        )?;
+        Ok(stops.apply(&output))
+    }
+
+    /// Async version of [`Self::generate`]
+    ///
+    /// Runs on a dedicated worker thread, so it never blocks the async
+    /// runtime. Dropping the future before it completes cancels the
+    /// generation through [`GenerateOptions::with_cancellation`]'s token.
+    pub async fn generate_async(&self, prompt: &str, options: &GenerateOptions) -> Result<String> {
+        let prompt = prompt.to_string();
+        self.run_async(options, move |model, options| model.generate(&prompt, options))
+            .await
+    }
+
+    /// Async version of [`Self::generate_streaming`], yielding tokens as a
+    /// [`TokenStream`]
+    ///
+    /// ```no_run
+    /// use neuro_inference::{GenerateOptions, InferenceConfig, InferenceModel};
+    ///
+    /// # async fn run() -> Result<(), neuro_inference::InferenceError> {
+    /// let model = InferenceModel::load(InferenceConfig::new("model.gguf"))?;
+    /// let mut stream = model.generate_stream("Q: What is Rust?\nA:", &GenerateOptions::new(128));
+    /// while let Some(token) = stream.next().await {
+    ///     print!("{}", token);
+    /// }
+    /// let answer = stream.finish().await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn generate_stream(&self, prompt: &str, options: &GenerateOptions) -> TokenStream {
+        let model = self.clone();
+        let prompt = prompt.to_string();
+        let options = options.clone();
+        let guard = options.cancellation.clone().drop_guard();
+        let (token_tx, token_rx) = mpsc::unbounded_channel();
+        let (result_tx, result_rx) = oneshot::channel();
+
+        blocking::spawn(move || {
+            let result = model.generate_streaming(&prompt, &options, &mut |token| {
+                let _ = token_tx.send(token.to_string());
+            });
+            let _ = result_tx.send(result);
+        });
+
+        TokenStream::new(token_rx, result_rx, guard)
+    }
+
+    /// Async version of [`Self::chat`]
+    pub async fn chat_async(
+        &self,
+        system_prompt: &str,
+        user_message: &str,
+        options: &GenerateOptions,
+    ) -> Result<String> {
+        self.chat_messages_async(
+            &[
+                ChatMessage::system(system_prompt),
+                ChatMessage::user(user_message),
+            ],
+            options,
+        )
+        .await
+    }
+
+    /// Async version of [`Self::chat_messages`]
+    pub async fn chat_messages_async(
+        &self,
+        messages: &[ChatMessage],
+        options: &GenerateOptions,
+    ) -> Result<String> {
+        let messages = messages.to_vec();
+        self.run_async(options, move |model, options| model.chat_messages(&messages, options))
+            .await
+    }
+
+    /// Run `f` on a worker thread, cancelling it if the future is dropped
+    async fn run_async<T, F>(&self, options: &GenerateOptions, f: F) -> Result<T>
+    where
+        F: FnOnce(&Self, &GenerateOptions) -> Result<T> + Send + 'static,
+        T: Send + 'static,
+    {
+        let model = self.clone();
+        let options = options.clone();
+        let guard = options.cancellation.clone().drop_guard();
+        let result = blocking::run(move || f(&model, &options)).await;
+        guard.disarm();
+        result
+    }
+
+    /// Convert text to the model's token ids
+    ///
+    /// Supported by the native and server backends, and by the subprocess
+    /// backend when `llama-tokenize` sits next to `llama-cli`.
+    pub fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        self.backend.tokenize(text)
+    }
+
+    /// Convert token ids back to text (native and server backends)
+    pub fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+        self.backend.detokenize(tokens)
+    }
+
+    /// Count the tokens `text` takes up in the model's context
+    pub fn count_tokens(&self, text: &str) -> Result<usize> {
+        Ok(self.tokenize(text)?.len())
+    }
+
+    /// Cut `text` to at most `max_tokens` tokens
+    ///
+    /// Text that already fits is returned unchanged.
+    pub fn truncate_to_tokens(&self, text: &str, max_tokens: usize) -> Result<String> {
+        let tokens = self.tokenize(text)?;
+        if tokens.len() <= max_tokens {
+            return Ok(text.to_string());
+        }
+        self.detokenize(&tokens[..max_tokens])
+    }
+
+    /// Context size in tokens the model was loaded with
+    pub fn context_size(&self) -> u32 {
+        self.config.n_ctx
+    }
+
+    /// Get the chat template used by the backend
+    pub fn chat_template(&self) -> ChatTemplate {
+        self.backend.chat_template()
     }
 
     /// Get the backend type being used
@@ -367,25 +826,13 @@ impl InferenceModel {
         self.backend.version()
     }
 
-    /// Apply stop sequences to output
-    fn apply_stop_sequences(&self, output: &str, stop_sequences: &[String]) -> String {
-        let mut result = output.to_string();
-        for stop in stop_sequences {
-            if let Some(pos) = result.find(stop) {
-                result.truncate(pos);
-                break;
-            }
-        }
-        result.trim().to_string()
-    }
-
     /// Generate text with automatic translation for non-English queries
     /// 
     /// Flow:
     /// 1. Detect language
     /// 2. If not English, translate to English using BitNet
     /// 3. Get answer in English
-    /// 4. Return answer (optionally translate back)
+    /// 4. Translate the answer back to the query's language
     /// 
     /// Returns (response, original_language, was_translated)
     pub fn generate_translated(&self, prompt: &str, options: &GenerateOptions) -> Result<(String, Language, bool)> {
@@ -397,17 +844,17 @@ impl InferenceModel {
         }
 
         // Step 1: Translate question to English using BitNet
-        let translate_prompt = build_translation_prompt(prompt);
-        let translate_options = GenerateOptions::new(100)
-            .with_temperature(0.1);  // Low temp for accurate translation
-        
-        let english_question = self.generate(&translate_prompt, &translate_options)?;
-        let english_question = english_question.trim().to_string();
+        let translator =
+            ModelTranslator::new(self.clone()).with_cancellation(options.cancellation.clone());
+        let english_question = translator.translate(prompt, lang)?;
         
         debug!("Translated '{}' -> '{}'", prompt, english_question);
 
         // Step 2: Get answer in English (more accurate for factual questions)
         let response = self.generate(&english_question, options)?;
+
+        // Step 3: Answer in the user's language
+        let (response, _) = ensure_language(&response, lang, &translator)?;
         
         Ok((response, lang, true))
     }
@@ -427,17 +874,15 @@ impl InferenceModel {
         }
 
         // Translate user message to English
-        let translate_prompt = build_translation_prompt(user_message);
-        let translate_options = GenerateOptions::new(100)
-            .with_temperature(0.1);
-        
-        let english_message = self.generate(&translate_prompt, &translate_options)?;
-        let english_message = english_message.trim().to_string();
+        let translator =
+            ModelTranslator::new(self.clone()).with_cancellation(options.cancellation.clone());
+        let english_message = translator.translate(user_message, lang)?;
         
         debug!("Translated '{}' -> '{}'", user_message, english_message);
 
-        // Get response with translated message
+        // Get response with translated message, then answer in the user's language
         let response = self.chat(system_prompt, &english_message, options)?;
+        let (response, _) = ensure_language(&response, lang, &translator)?;
         
         Ok((response, lang, true))
     }
@@ -448,10 +893,153 @@ impl InferenceModel {
     }
 }
 
+fn stop_conditions(options: &GenerateOptions) -> Result<StopConditions> {
+    StopConditions::new(&options.stop_sequences, &options.stop_patterns)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    /// Echoes the prompt back and records how many calls overlap
+    struct EchoBackend {
+        max_parallel: usize,
+        active: AtomicUsize,
+        peak: AtomicUsize,
+        streamed: AtomicUsize,
+    }
+
+    impl InferenceBackend for EchoBackend {
+        fn generate(
+            &self,
+            prompt: &str,
+            _max_tokens: u32,
+            _sampler: &SamplerConfig,
+            _cancel: &CancellationToken,
+        ) -> Result<String> {
+            let active = self.active.fetch_add(1, Ordering::SeqCst) + 1;
+            self.peak.fetch_max(active, Ordering::SeqCst);
+            std::thread::sleep(std::time::Duration::from_millis(20));
+            self.active.fetch_sub(1, Ordering::SeqCst);
+
+            if prompt == "fail" {
+                return Err(InferenceError::Decode("bad prompt".to_string()));
+            }
+            Ok(prompt.to_uppercase())
+        }
+
+        fn generate_streaming(
+            &self,
+            prompt: &str,
+            max_tokens: u32,
+            sampler: &SamplerConfig,
+            on_token: TokenCallback<'_>,
+            cancel: &CancellationToken,
+        ) -> Result<String> {
+            // One token per character, checking for cancellation in between
+            let output = self.generate(prompt, max_tokens, sampler, cancel)?;
+            for c in output.chars() {
+                if cancel.is_cancelled() {
+                    return Err(InferenceError::Interrupted);
+                }
+                self.streamed.fetch_add(1, Ordering::SeqCst);
+                on_token(&c.to_string());
+            }
+            Ok(output)
+        }
+
+        /// One token per character
+        fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+            Ok(text.chars().map(|c| c as i32).collect())
+        }
+
+        fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+            Ok(tokens.iter().filter_map(|&t| char::from_u32(t as u32)).collect())
+        }
+
+        fn chat_template(&self) -> ChatTemplate {
+            ChatTemplate::default()
+        }
+
+        fn max_parallel(&self) -> usize {
+            self.max_parallel
+        }
+
+        fn name(&self) -> &'static str {
+            "echo"
+        }
+
+        fn is_ready(&self) -> bool {
+            true
+        }
+    }
+
+    fn echo_model(max_parallel: usize) -> (InferenceModel, Arc<EchoBackend>) {
+        let backend = Arc::new(EchoBackend {
+            max_parallel,
+            active: AtomicUsize::new(0),
+            peak: AtomicUsize::new(0),
+            streamed: AtomicUsize::new(0),
+        });
+        let model = InferenceModel {
+            backend: backend.clone(),
+            config: InferenceConfig::default(),
+        };
+        (model, backend)
+    }
+
+    #[test]
+    fn test_generate_many() {
+        let (model, backend) = echo_model(2);
+
+        let results = model.generate_many(&["a", "fail", "c", "d", "e"], &GenerateOptions::new(8));
+        assert_eq!(results.len(), 5);
+        assert_eq!(results[0].as_deref().unwrap(), "A");
+        assert!(results[1].is_err());
+        assert_eq!(results[4].as_deref().unwrap(), "E");
+        assert_eq!(backend.peak.load(Ordering::SeqCst), 2);
+
+        assert!(model.generate_many::<&str>(&[], &GenerateOptions::new(8)).is_empty());
+    }
+
+    #[test]
+    fn test_streaming_ends_at_stop() {
+        let (model, backend) = echo_model(1);
+        let options = GenerateOptions::new(64).with_stop_sequence("\n\n");
+
+        let mut streamed = String::new();
+        let output = model
+            .generate_streaming("ab\n\ncdefgh", &options, &mut |t| streamed.push_str(t))
+            .unwrap();
+        assert_eq!((output.as_str(), streamed.as_str()), ("AB", "AB"));
+        // Generation stopped at the stop sequence instead of running on
+        assert_eq!(backend.streamed.load(Ordering::SeqCst), 4);
+        assert!(!options.cancellation.is_cancelled());
+
+        let options = GenerateOptions::new(64).with_stop_pattern(r"D\d");
+        assert_eq!(model.generate("abc d1 e", &options).unwrap(), "ABC");
+        let invalid = GenerateOptions::new(64).with_stop_pattern("(");
+        assert!(matches!(
+            model.generate("abc", &invalid),
+            Err(InferenceError::InvalidConfig(_))
+        ));
+    }
+
+    #[test]
+    fn test_warm_up() {
+        let (model, backend) = echo_model(1);
+        model.warm_up().unwrap();
+        assert_eq!(backend.peak.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_token_budget() {
+        let (model, _) = echo_model(1);
+        assert_eq!(model.count_tokens("hello").unwrap(), 5);
+        assert_eq!(model.truncate_to_tokens("hello", 3).unwrap(), "hel");
+        assert_eq!(model.truncate_to_tokens("hi", 3).unwrap(), "hi");
+    }
+
     #[test]
     fn test_inference_config_builder() {
         let config = InferenceConfig::new("/path/to/model.gguf")
@@ -474,6 +1062,14 @@ mod tests {
         assert!(options.stop_sequences.contains(&"</s>".to_string()));
     }
 
+    #[test]
+    fn test_generate_options_grammar() {
+        assert!(GenerateOptions::default().sampler.grammar.is_none());
+
+        let options = GenerateOptions::new(64).with_grammar(r#"root ::= "yes" | "no""#);
+        assert_eq!(options.sampler.grammar.as_deref(), Some(r#"root ::= "yes" | "no""#));
+    }
+
     #[test]
     fn test_apply_stop_sequences() {
         // Direct test of stop sequence logic
@@ -3,14 +3,19 @@
 //! High-performance inference using direct FFI bindings to bitnet.cpp.
 
 use crate::backend::{InferenceBackend, TokenCallback};
+use crate::cancel::CancellationToken;
+use crate::chat::ChatTemplate;
 use crate::error::{InferenceError, Result};
+use crate::logprobs::{log_softmax_top, GenerationOutput, TokenLogprob};
+use crate::native::prefix_cache::PrefixCache;
 use crate::native::{
-    ContextPool, LlamaBatch, LlamaModel, LlamaSampler, ModelParams, PoolConfig, ContextParams,
+    ContextPool, LlamaBatch, LlamaContext, LlamaModel, LlamaSampler, ModelParams, NativeSession,
+    PoolConfig, ContextParams, PrefixCacheConfig, PrefixCacheStats,
 };
 use crate::sampler::SamplerConfig;
 use std::path::Path;
 use std::sync::Arc;
-use tracing::{debug, info};
+use tracing::{debug, info, warn};
 
 /// Native FFI backend for bitnet.cpp
 ///
@@ -21,6 +26,10 @@ pub struct NativeBackend {
     model: Arc<LlamaModel>,
     /// Pool of contexts for concurrent requests
     pool: Arc<ContextPool>,
+    /// Prompt format for chat
+    chat_template: ChatTemplate,
+    /// Saved KV states of shared prompt prefixes
+    prefix_cache: Option<PrefixCache>,
 }
 
 impl NativeBackend {
@@ -43,6 +52,7 @@ impl NativeBackend {
         }
 
         // Load model
+        let chat_template = ChatTemplate::resolve(&model_path);
         let model = LlamaModel::load(model_path, &model_params)?;
         info!(
             "Model loaded: vocab_size={}, n_ctx_train={}, n_embd={}",
@@ -55,7 +65,12 @@ impl NativeBackend {
         let pool = ContextPool::new(Arc::clone(&model), pool_config)?;
         info!("Context pool initialized: {} contexts", pool.size());
 
-        Ok(Self { model, pool })
+        Ok(Self {
+            model,
+            pool,
+            chat_template,
+            prefix_cache: Some(PrefixCache::new(PrefixCacheConfig::default())),
+        })
     }
 
     /// Create with default parameters
@@ -74,81 +89,222 @@ impl NativeBackend {
         &self.pool
     }
 
+    /// Override the chat template detected from the model
+    pub fn with_chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = template;
+        self
+    }
+
+    /// Set prefix cache limits, or disable the cache with `None`
+    ///
+    /// When a prompt shares a long prefix with a recent one, the KV state
+    /// after that prefix is saved so later prompts starting with it skip
+    /// decoding it. Enabled with default limits.
+    pub fn with_prefix_cache(mut self, config: Option<PrefixCacheConfig>) -> Self {
+        self.prefix_cache = config.map(PrefixCache::new);
+        self
+    }
+
+    /// Get prefix cache hit/miss counters and size
+    pub fn prefix_cache_stats(&self) -> Option<PrefixCacheStats> {
+        self.prefix_cache.as_ref().map(PrefixCache::stats)
+    }
+
+    /// Start a session whose KV cache persists between calls
+    ///
+    /// Sessions get their own context rather than one from the pool, so
+    /// they do not reduce the number of concurrent requests.
+    pub fn new_session(&self) -> Result<NativeSession> {
+        NativeSession::new(Arc::clone(&self.model), self.pool.context_params())
+    }
+
+    /// Restore a session saved with [`NativeSession::save`]
+    pub fn load_session<P: AsRef<Path>>(&self, path: P) -> Result<NativeSession> {
+        let mut session = self.new_session()?;
+        session.load(path)?;
+        Ok(session)
+    }
+
     /// Generate tokens with full control
     fn generate_tokens(
         &self,
         tokens: &[i32],
         max_new_tokens: u32,
         sampler_config: &SamplerConfig,
-        mut on_token: Option<&mut dyn FnMut(&str)>,
+        on_token: Option<&mut dyn FnMut(&str)>,
+        logprobs: Option<(usize, &mut Vec<TokenLogprob>)>,
+        cancel: &CancellationToken,
     ) -> Result<String> {
         // Acquire context from pool
         let mut ctx = self.pool.acquire()?;
+        // Contexts come back from the pool holding the previous prompt
+        ctx.kv_cache_clear();
         
         // Create batch for prompt
         let n_ctx = ctx.n_ctx() as usize;
         let mut batch = LlamaBatch::new(n_ctx, 1)?;
         
-        // Add prompt tokens
-        batch.add_sequence(tokens, 0, 0, true)?;
+        // Skip the part of the prompt already in the prefix cache
+        let n_cached = self.restore_prefix(&mut ctx, &mut batch, tokens)?;
+        
+        // Add remaining prompt tokens
+        batch.clear();
+        batch.add_sequence(&tokens[n_cached..], n_cached as i32, 0, true)?;
         
         // Process prompt
         ctx.decode(&mut batch)?;
         
         // Create sampler
-        let mut sampler = LlamaSampler::from_config(sampler_config, self.model.vocab_size())?;
+        let mut sampler = LlamaSampler::for_model(sampler_config, &self.model)?;
         
         // Generate tokens
-        let mut output = String::with_capacity(max_new_tokens as usize * 4); // Estimate 4 chars per token
-        let mut n_decoded = tokens.len();
-        
-        for _ in 0..max_new_tokens {
-            // Sample next token
-            let new_token = sampler.sample(&ctx, -1);
-            sampler.accept(new_token);
-            
-            // Check for end of generation
-            if self.model.is_eog_token(new_token) {
-                debug!("End of generation token");
-                break;
-            }
-            
-            // Decode token to string
-            let piece = self.model.token_to_str(new_token)?;
-            
-            // Stream callback
-            if let Some(ref mut callback) = on_token {
-                callback(&piece);
-            }
-            
-            output.push_str(&piece);
-            
-            // Prepare next batch
-            batch.clear();
-            batch.add(new_token, n_decoded as i32, &[0], true)?;
-            ctx.decode(&mut batch)?;
-            
-            n_decoded += 1;
-            
-            // Check context limit
-            if n_decoded >= n_ctx - 4 {
-                debug!("Approaching context limit, stopping");
-                break;
+        let mut generated = Vec::with_capacity(max_new_tokens as usize);
+        sample_loop(
+            &self.model,
+            &mut ctx,
+            &mut sampler,
+            tokens.len(),
+            max_new_tokens,
+            on_token,
+            logprobs,
+            cancel,
+            &mut generated,
+        )
+    }
+
+    /// Put a cached prompt prefix into the KV cache
+    ///
+    /// Restores the longest cached prefix of `tokens`. Otherwise, if the
+    /// prompt shares a long prefix with a recent one, decodes and caches
+    /// that prefix. Returns how many leading tokens are already decoded.
+    fn restore_prefix(
+        &self,
+        ctx: &mut LlamaContext,
+        batch: &mut LlamaBatch,
+        tokens: &[i32],
+    ) -> Result<usize> {
+        let Some(cache) = &self.prefix_cache else {
+            return Ok(0);
+        };
+
+        if let Some((n_cached, state)) = cache.lookup(tokens) {
+            match ctx.set_seq_state(&state, 0) {
+                Ok(()) => {
+                    debug!("Restored {} cached prompt tokens", n_cached);
+                    return Ok(n_cached);
+                }
+                Err(e) => {
+                    warn!("Ignoring cached prompt prefix: {}", e);
+                    ctx.kv_cache_clear();
+                }
             }
         }
-        
-        Ok(output)
+
+        let Some(n_shared) = cache.shared_prefix(tokens) else {
+            return Ok(0);
+        };
+        batch.add_sequence(&tokens[..n_shared], 0, 0, true)?;
+        ctx.decode(batch)?;
+        cache.insert(&tokens[..n_shared], ctx.seq_state(0));
+        debug!("Cached prompt prefix of {} tokens", n_shared);
+        Ok(n_shared)
+    }
+}
+
+/// Sample up to `max_new_tokens` after the prompt has been decoded
+///
+/// Each generated token is decoded into the context's KV cache and pushed
+/// onto `generated`, so the cache always holds the prompt plus `generated`.
+/// When `logprobs` is given, each token's log-probability and its top
+/// alternatives are recorded from the raw logits. Stops with
+/// [`InferenceError::Interrupted`] once `cancel` is cancelled.
+#[allow(clippy::too_many_arguments)]
+pub(super) fn sample_loop(
+    model: &LlamaModel,
+    ctx: &mut LlamaContext,
+    sampler: &mut LlamaSampler,
+    mut n_past: usize,
+    max_new_tokens: u32,
+    mut on_token: Option<&mut dyn FnMut(&str)>,
+    mut logprobs: Option<(usize, &mut Vec<TokenLogprob>)>,
+    cancel: &CancellationToken,
+    generated: &mut Vec<i32>,
+) -> Result<String> {
+    let n_ctx = ctx.n_ctx() as usize;
+    let mut batch = LlamaBatch::new(1, 1)?;
+    let mut output = String::with_capacity(max_new_tokens as usize * 4); // Estimate 4 chars per token
+
+    for _ in 0..max_new_tokens {
+        if cancel.is_cancelled() {
+            debug!("Generation cancelled after {} tokens", generated.len());
+            return Err(InferenceError::Interrupted);
+        }
+
+        // Sample next token
+        let new_token = sampler.sample(ctx, -1);
+        sampler.accept(new_token);
+
+        // Check for end of generation
+        if model.is_eog_token(new_token) {
+            debug!("End of generation token");
+            break;
+        }
+
+        // Decode token to string
+        let piece = model.token_to_str(new_token)?;
+
+        if let Some((top_n, ref mut out)) = logprobs {
+            let (logprob, top) = log_softmax_top(ctx.get_logits_ith(-1), new_token as usize, top_n);
+            let top = top
+                .into_iter()
+                .map(|(id, lp)| Ok((model.token_to_str(id as i32)?, lp)))
+                .collect::<Result<Vec<_>>>()?;
+            out.push(TokenLogprob {
+                token: piece.clone(),
+                logprob,
+                top,
+            });
+        }
+
+        // Stream callback
+        if let Some(ref mut callback) = on_token {
+            callback(&piece);
+        }
+
+        output.push_str(&piece);
+
+        // Prepare next batch
+        batch.clear();
+        batch.add(new_token, n_past as i32, &[0], true)?;
+        ctx.decode(&mut batch)?;
+
+        generated.push(new_token);
+        n_past += 1;
+
+        // Check context limit
+        if n_past >= n_ctx - 4 {
+            debug!("Approaching context limit, stopping");
+            break;
+        }
     }
+
+    Ok(output)
 }
 
 impl InferenceBackend for NativeBackend {
-    fn generate(&self, prompt: &str, max_tokens: u32, sampler: &SamplerConfig) -> Result<String> {
+    fn generate(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
         // Tokenize prompt
         let tokens = self.model.tokenize(prompt, true, true)?;
         debug!("Tokenized {} chars -> {} tokens", prompt.len(), tokens.len());
         
         // Generate
-        self.generate_tokens(&tokens, max_tokens, sampler, None)
+        self.generate_tokens(&tokens, max_tokens, sampler, None, None, cancel)
     }
 
     fn generate_streaming(
@@ -157,6 +313,7 @@ impl InferenceBackend for NativeBackend {
         max_tokens: u32,
         sampler: &SamplerConfig,
         on_token: TokenCallback<'_>,
+        cancel: &CancellationToken,
     ) -> Result<String> {
         // Tokenize prompt
         let tokens = self.model.tokenize(prompt, true, true)?;
@@ -164,23 +321,74 @@ impl InferenceBackend for NativeBackend {
         
         // We need to convert the reference to a mutable one
         let mut callback = on_token;
-        self.generate_tokens(&tokens, max_tokens, sampler, Some(&mut callback))
+        self.generate_tokens(&tokens, max_tokens, sampler, Some(&mut callback), None, cancel)
     }
 
-    fn chat(
+    fn generate_with_logprobs(
         &self,
-        system_prompt: &str,
-        user_message: &str,
+        prompt: &str,
         max_tokens: u32,
         sampler: &SamplerConfig,
-    ) -> Result<String> {
-        // Format as chat prompt
-        let prompt = format!(
-            "<|system|>\n{}</s>\n<|user|>\n{}</s>\n<|assistant|>\n",
-            system_prompt, user_message
-        );
-        
-        self.generate(&prompt, max_tokens, sampler)
+        top_n: usize,
+        cancel: &CancellationToken,
+    ) -> Result<GenerationOutput> {
+        let tokens = self.model.tokenize(prompt, true, true)?;
+        let mut logprobs = Vec::with_capacity(max_tokens as usize);
+        let text = self.generate_tokens(
+            &tokens,
+            max_tokens,
+            sampler,
+            None,
+            Some((top_n, &mut logprobs)),
+            cancel,
+        )?;
+        Ok(GenerationOutput {
+            text,
+            tokens: logprobs,
+        })
+    }
+
+    fn chat_template(&self) -> ChatTemplate {
+        self.chat_template
+    }
+
+    fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        self.model.tokenize(text, false, true)
+    }
+
+    fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+        tokens.iter().map(|&token| self.model.token_to_str(token)).collect()
+    }
+
+    fn max_parallel(&self) -> usize {
+        self.pool.max_size()
+    }
+
+    /// Decode one token in every idle pooled context, so the first
+    /// requests do not pay for allocating compute buffers
+    fn warm_up(&self, cancel: &CancellationToken) -> Result<()> {
+        let tokens = self.model.tokenize("Hello", true, false)?;
+        let Some(&first) = tokens.first() else {
+            return Ok(());
+        };
+
+        // Hold every context at once so each one is warmed, not the same
+        // one over and over
+        let contexts: Vec<_> = (0..self.pool.available().max(1))
+            .filter_map(|_| self.pool.try_acquire())
+            .collect();
+        let mut batch = LlamaBatch::new(1, 1)?;
+        for mut ctx in contexts {
+            if cancel.is_cancelled() {
+                return Err(InferenceError::Interrupted);
+            }
+            ctx.kv_cache_clear();
+            batch.clear();
+            batch.add_sequence(&[first], 0, 0, true)?;
+            ctx.decode(&mut batch)?;
+        }
+        debug!("Warmed up {} pooled contexts", self.pool.size());
+        Ok(())
     }
 
     fn name(&self) -> &'static str {
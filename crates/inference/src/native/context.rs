@@ -202,6 +202,29 @@ impl LlamaContext {
         }
     }
 
+    /// Copy the KV cache of one sequence
+    pub fn seq_state(&self, seq_id: i32) -> Vec<u8> {
+        let size = unsafe { llama_state_seq_get_size(self.ptr.as_ptr(), seq_id) };
+        let mut state = vec![0u8; size];
+        let written =
+            unsafe { llama_state_seq_get_data(self.ptr.as_ptr(), state.as_mut_ptr(), seq_id) };
+        state.truncate(written);
+        state
+    }
+
+    /// Restore a sequence copied with [`Self::seq_state`]
+    pub fn set_seq_state(&mut self, state: &[u8], seq_id: i32) -> Result<()> {
+        let read = unsafe {
+            llama_state_seq_set_data(self.ptr.as_ptr(), state.as_ptr(), state.len(), seq_id)
+        };
+        if read == 0 {
+            return Err(InferenceError::Decode(
+                "Failed to restore sequence state".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
     /// Get embeddings for the last token (if embeddings mode is enabled)
     pub fn get_embeddings(&self) -> Option<&[f32]> {
         unsafe {
@@ -15,6 +15,10 @@ mod batch;
 mod pool;
 #[cfg(feature = "native")]
 mod backend;
+#[cfg(feature = "native")]
+mod session;
+#[cfg(feature = "native")]
+mod prefix_cache;
 
 #[cfg(feature = "native")]
 pub use self::model::{LlamaModel, ModelParams};
@@ -28,6 +32,10 @@ pub use self::batch::LlamaBatch;
 pub use self::pool::{ContextPool, PooledContext, PoolConfig};
 #[cfg(feature = "native")]
 pub use self::backend::NativeBackend;
+#[cfg(feature = "native")]
+pub use self::session::NativeSession;
+#[cfg(feature = "native")]
+pub use self::prefix_cache::{PrefixCacheConfig, PrefixCacheStats};
 
 /// Check if native bindings are available and functional
 pub fn is_available() -> bool {
@@ -27,6 +27,10 @@ unsafe impl Sync for LlamaModel {}
 pub struct ModelParams {
     /// Number of GPU layers to offload (0 = CPU only)
     pub n_gpu_layers: i32,
+    /// GPU that holds the model when it is not split
+    pub main_gpu: i32,
+    /// Share of the model per GPU, e.g. `[3.0, 1.0]` (empty = automatic)
+    pub tensor_split: Vec<f32>,
     /// Use memory mapping for model file
     pub use_mmap: bool,
     /// Lock model in memory (prevent swapping)
@@ -39,6 +43,8 @@ impl Default for ModelParams {
     fn default() -> Self {
         Self {
             n_gpu_layers: 0, // CPU only by default for BitNet
+            main_gpu: 0,
+            tensor_split: Vec::new(),
             use_mmap: true,
             use_mlock: false,
             check_tensors: false,
@@ -63,6 +69,11 @@ impl LlamaModel {
         // Initialize model params
         let mut model_params = unsafe { llama_model_default_params() };
         model_params.n_gpu_layers = params.n_gpu_layers;
+        model_params.main_gpu = params.main_gpu;
+        if !params.tensor_split.is_empty() {
+            // Read during loading only; `params` outlives the call
+            model_params.tensor_split = params.tensor_split.as_ptr();
+        }
         model_params.use_mmap = params.use_mmap;
         model_params.use_mlock = params.use_mlock;
         model_params.check_tensors = params.check_tensors;
@@ -213,6 +213,16 @@ impl ContextPool {
         &self.model
     }
 
+    /// Get the parameters new contexts are created with
+    pub fn context_params(&self) -> &ContextParams {
+        &self.config.context_params
+    }
+
+    /// Get the most contexts the pool will create
+    pub fn max_size(&self) -> usize {
+        self.config.max_size
+    }
+
     /// Get current pool size
     pub fn size(&self) -> usize {
         self.current_size.load(std::sync::atomic::Ordering::SeqCst)
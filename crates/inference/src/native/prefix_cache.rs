@@ -0,0 +1,272 @@
+//! Prompt prefix caching for the native backend
+//!
+//! RAG prompts often start with the same system prompt and retrieved
+//! context. When a prompt shares a long prefix with a recent one, the KV
+//! state after that prefix is saved, and later prompts starting with it
+//! restore the state instead of decoding the prefix again.
+
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// Number of recent prompts compared when looking for a shared prefix
+const RECENT_PROMPTS: usize = 4;
+
+/// Limits for the prefix cache
+#[derive(Debug, Clone)]
+pub struct PrefixCacheConfig {
+    /// Most prefixes kept
+    pub max_entries: usize,
+    /// Most bytes of saved KV state kept
+    pub max_bytes: usize,
+    /// Shortest prefix worth caching, in tokens
+    pub min_prefix_tokens: usize,
+}
+
+impl Default for PrefixCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 8,
+            max_bytes: 512 * 1024 * 1024,
+            min_prefix_tokens: 128,
+        }
+    }
+}
+
+impl PrefixCacheConfig {
+    /// Set the most prefixes kept
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Set the most bytes of KV state kept
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// Set the shortest prefix worth caching
+    pub fn with_min_prefix_tokens(mut self, min_prefix_tokens: usize) -> Self {
+        self.min_prefix_tokens = min_prefix_tokens;
+        self
+    }
+}
+
+/// Prefix cache counters
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PrefixCacheStats {
+    /// Prompts that restored a cached prefix
+    pub hits: u64,
+    /// Prompts with no cached prefix
+    pub misses: u64,
+    /// Prompt tokens restored instead of decoded
+    pub tokens_reused: u64,
+    /// Prefixes currently cached
+    pub entries: usize,
+    /// Bytes of KV state currently cached
+    pub bytes: usize,
+}
+
+struct Entry {
+    hash: u64,
+    tokens: Vec<i32>,
+    state: Arc<Vec<u8>>,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: Vec<Entry>,
+    recent: VecDeque<Vec<i32>>,
+    clock: u64,
+    stats: PrefixCacheStats,
+}
+
+/// Saved KV states keyed by a hash of their prompt prefix
+pub(crate) struct PrefixCache {
+    config: PrefixCacheConfig,
+    inner: Mutex<Inner>,
+}
+
+impl PrefixCache {
+    pub(crate) fn new(config: PrefixCacheConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// Find the longest cached prefix of `tokens`, leaving at least one
+    /// token to decode
+    pub(crate) fn lookup(&self, tokens: &[i32]) -> Option<(usize, Arc<Vec<u8>>)> {
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let found = inner
+            .entries
+            .iter_mut()
+            .filter(|e| e.tokens.len() < tokens.len())
+            .filter(|e| e.hash == hash_tokens(&tokens[..e.tokens.len()]))
+            .filter(|e| e.tokens == tokens[..e.tokens.len()])
+            .max_by_key(|e| e.tokens.len())
+            .map(|e| {
+                e.last_used = clock;
+                (e.tokens.len(), Arc::clone(&e.state))
+            });
+
+        match found {
+            Some((len, _)) => {
+                inner.stats.hits += 1;
+                inner.stats.tokens_reused += len as u64;
+            }
+            None => inner.stats.misses += 1,
+        }
+        found
+    }
+
+    /// Length of the prefix `tokens` shares with a recent prompt, if it is
+    /// long enough to cache
+    ///
+    /// Remembers `tokens` for later calls.
+    pub(crate) fn shared_prefix(&self, tokens: &[i32]) -> Option<usize> {
+        let mut inner = self.lock();
+        let shared = inner
+            .recent
+            .iter()
+            .map(|recent| common_prefix_len(recent, tokens))
+            .max()
+            .unwrap_or(0)
+            .min(tokens.len().saturating_sub(1));
+
+        if inner.recent.len() == RECENT_PROMPTS {
+            inner.recent.pop_front();
+        }
+        inner.recent.push_back(tokens.to_vec());
+
+        (shared >= self.config.min_prefix_tokens).then_some(shared)
+    }
+
+    /// Save the KV state after `tokens`, evicting the least recently used
+    /// prefixes to stay within the limits
+    pub(crate) fn insert(&self, tokens: &[i32], state: Vec<u8>) {
+        if state.len() > self.config.max_bytes || self.config.max_entries == 0 {
+            return;
+        }
+
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let hash = hash_tokens(tokens);
+        inner
+            .entries
+            .retain(|e| e.hash != hash || e.tokens != tokens);
+
+        while inner.entries.len() >= self.config.max_entries
+            || bytes(&inner.entries) + state.len() > self.config.max_bytes
+        {
+            let Some(oldest) = (0..inner.entries.len()).min_by_key(|&i| inner.entries[i].last_used)
+            else {
+                break;
+            };
+            inner.entries.swap_remove(oldest);
+        }
+
+        let last_used = inner.clock;
+        inner.entries.push(Entry {
+            hash,
+            tokens: tokens.to_vec(),
+            state: Arc::new(state),
+            last_used,
+        });
+    }
+
+    /// Current counters
+    pub(crate) fn stats(&self) -> PrefixCacheStats {
+        let inner = self.lock();
+        PrefixCacheStats {
+            entries: inner.entries.len(),
+            bytes: bytes(&inner.entries),
+            ..inner.stats
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn bytes(entries: &[Entry]) -> usize {
+    entries.iter().map(|e| e.state.len()).sum()
+}
+
+fn hash_tokens(tokens: &[i32]) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    tokens.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn common_prefix_len(a: &[i32], b: &[i32]) -> usize {
+    a.iter().zip(b).take_while(|(x, y)| x == y).count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn cache(max_entries: usize, max_bytes: usize) -> PrefixCache {
+        PrefixCache::new(
+            PrefixCacheConfig::default()
+                .with_max_entries(max_entries)
+                .with_max_bytes(max_bytes)
+                .with_min_prefix_tokens(2),
+        )
+    }
+
+    #[test]
+    fn test_shared_prefix() {
+        let cache = cache(4, 1024);
+        assert_eq!(cache.shared_prefix(&[1, 2, 3, 4]), None);
+        assert_eq!(cache.shared_prefix(&[1, 2, 3, 9]), Some(3));
+        assert_eq!(cache.shared_prefix(&[1, 7]), None);
+        // Always leaves a token to decode
+        assert_eq!(cache.shared_prefix(&[1, 2, 3]), Some(2));
+    }
+
+    #[test]
+    fn test_lookup_longest_prefix() {
+        let cache = cache(4, 1024);
+        cache.insert(&[1, 2], vec![0; 10]);
+        cache.insert(&[1, 2, 3], vec![0; 20]);
+
+        let (len, state) = cache.lookup(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(len, 3);
+        assert_eq!(state.len(), 20);
+        assert_eq!(cache.lookup(&[1, 2, 3]).unwrap().0, 2);
+        assert!(cache.lookup(&[5, 6, 7]).is_none());
+
+        let stats = cache.stats();
+        assert_eq!((stats.hits, stats.misses, stats.tokens_reused), (2, 1, 5));
+        assert_eq!((stats.entries, stats.bytes), (2, 30));
+    }
+
+    #[test]
+    fn test_evicts_least_recently_used() {
+        let cache = cache(2, 100);
+        cache.insert(&[1, 1], vec![0; 10]);
+        cache.insert(&[2, 2], vec![0; 10]);
+        cache.lookup(&[1, 1, 5]);
+        cache.insert(&[3, 3], vec![0; 10]);
+
+        assert!(cache.lookup(&[1, 1, 5]).is_some());
+        assert!(cache.lookup(&[2, 2, 5]).is_none());
+        assert!(cache.lookup(&[3, 3, 5]).is_some());
+
+        // Byte limit evicts too, and oversized states are not cached
+        cache.insert(&[4, 4], vec![0; 95]);
+        assert_eq!(cache.stats().entries, 1);
+        cache.insert(&[5, 5], vec![0; 101]);
+        assert!(cache.lookup(&[5, 5, 5]).is_none());
+    }
+}
@@ -3,8 +3,10 @@
 //! Safe Rust wrapper around the llama_sampler FFI type.
 
 use crate::error::{InferenceError, Result};
-use crate::sampler::SamplerConfig;
+use crate::sampler::{BiasTarget, Mirostat, SamplerConfig};
+use super::LlamaModel;
 use bitnet_sys::*;
+use std::ffi::CString;
 use std::ptr::NonNull;
 
 /// Safe wrapper around llama_sampler
@@ -18,16 +20,70 @@ pub struct LlamaSampler {
 unsafe impl Send for LlamaSampler {}
 
 impl LlamaSampler {
+    /// Create a sampler chain for a model, including its grammar and
+    /// text logit biases
+    ///
+    /// When `config.grammar` is set, a grammar sampler runs first so that
+    /// only tokens the grammar allows reach the rest of the chain. Text
+    /// bias targets apply to every token of the tokenized text.
+    pub fn for_model(config: &SamplerConfig, model: &LlamaModel) -> Result<Self> {
+        let mut biases = token_biases(config);
+        for (target, &bias) in &config.logit_bias {
+            if let BiasTarget::Text(text) = target {
+                for token in model.tokenize(text, false, false)? {
+                    biases.push(llama_logit_bias { token, bias });
+                }
+            }
+        }
+
+        let grammar = match config.grammar {
+            Some(ref grammar) => {
+                let c_grammar = CString::new(grammar.as_str()).map_err(|_| {
+                    InferenceError::Sampling("Grammar contains a null byte".to_string())
+                })?;
+                let c_root = CString::new("root").unwrap();
+
+                let grammar_sampler = unsafe {
+                    llama_sampler_init_grammar(model.as_ptr(), c_grammar.as_ptr(), c_root.as_ptr())
+                };
+                if grammar_sampler.is_null() {
+                    return Err(InferenceError::Sampling("Failed to parse GBNF grammar".to_string()));
+                }
+                Some(grammar_sampler)
+            }
+            None => None,
+        };
+
+        Self::build(config, model.vocab_size(), grammar, &biases)
+    }
+
     /// Create a new sampler chain from configuration
     ///
+    /// The grammar and text logit biases are not applied here since they
+    /// need the model's vocabulary; use [`Self::for_model`] for those.
+    ///
     /// Sets up a chain of samplers in the recommended order:
-    /// 1. Repetition penalty
-    /// 2. Top-K
-    /// 3. Top-P (nucleus)
-    /// 4. Min-P
-    /// 5. Temperature
-    /// 6. Distribution sampling
+    /// 1. Logit bias
+    /// 2. Repetition, presence and frequency penalties
+    /// 3. Top-K
+    /// 4. Top-P (nucleus)
+    /// 5. Min-P
+    /// 6. Typical-P
+    /// 7. Temperature
+    /// 8. Distribution sampling
+    ///
+    /// With mirostat enabled, steps 3-6 are skipped and mirostat replaces
+    /// distribution sampling.
     pub fn from_config(config: &SamplerConfig, vocab_size: i32) -> Result<Self> {
+        Self::build(config, vocab_size, None, &token_biases(config))
+    }
+
+    fn build(
+        config: &SamplerConfig,
+        vocab_size: i32,
+        grammar: Option<*mut llama_sampler>,
+        biases: &[llama_logit_bias],
+    ) -> Result<Self> {
         // Initialize the sampler chain
         let params = llama_sampler_chain_params {
             no_perf: false,
@@ -36,11 +92,32 @@ impl LlamaSampler {
         let chain = unsafe { llama_sampler_chain_init(params) };
         
         if chain.is_null() {
+            if let Some(grammar) = grammar {
+                unsafe { llama_sampler_free(grammar) };
+            }
             return Err(InferenceError::Sampling("Failed to create sampler chain".to_string()));
         }
 
+        // Grammar goes first so the other samplers only see allowed tokens
+        if let Some(grammar) = grammar {
+            unsafe { llama_sampler_chain_add(chain, grammar) };
+        }
+
+        // Add logit bias sampler
+        if !biases.is_empty() {
+            let bias_sampler = unsafe {
+                llama_sampler_init_logit_bias(vocab_size, biases.len() as i32, biases.as_ptr())
+            };
+            if !bias_sampler.is_null() {
+                unsafe { llama_sampler_chain_add(chain, bias_sampler) };
+            }
+        }
+
         // Add repetition penalty sampler
-        if config.repeat_penalty != 1.0 {
+        if config.repeat_penalty != 1.0
+            || config.presence_penalty != 0.0
+            || config.frequency_penalty != 0.0
+        {
             let repeat_sampler = unsafe {
                 llama_sampler_init_penalties(
                     vocab_size,
@@ -48,8 +125,8 @@ impl LlamaSampler {
                     llama_token_nl(std::ptr::null_mut()),
                     config.repeat_last_n,
                     config.repeat_penalty,
-                    0.0,  // frequency_penalty
-                    0.0,  // presence_penalty
+                    config.frequency_penalty,
+                    config.presence_penalty,
                     false, // penalize_nl
                     false, // ignore_eos
                 )
@@ -59,8 +136,11 @@ impl LlamaSampler {
             }
         }
 
+        // Mirostat does its own truncation
+        let truncate = config.mirostat == Mirostat::Disabled;
+
         // Add top-k sampler
-        if config.top_k > 0 {
+        if config.top_k > 0 && truncate {
             let top_k_sampler = unsafe { llama_sampler_init_top_k(config.top_k) };
             if !top_k_sampler.is_null() {
                 unsafe { llama_sampler_chain_add(chain, top_k_sampler) };
@@ -68,7 +148,7 @@ impl LlamaSampler {
         }
 
         // Add top-p (nucleus) sampler
-        if config.top_p < 1.0 {
+        if config.top_p < 1.0 && truncate {
             let top_p_sampler = unsafe { llama_sampler_init_top_p(config.top_p, 1) };
             if !top_p_sampler.is_null() {
                 unsafe { llama_sampler_chain_add(chain, top_p_sampler) };
@@ -76,13 +156,21 @@ impl LlamaSampler {
         }
 
         // Add min-p sampler
-        if config.min_p > 0.0 {
+        if config.min_p > 0.0 && truncate {
             let min_p_sampler = unsafe { llama_sampler_init_min_p(config.min_p, 1) };
             if !min_p_sampler.is_null() {
                 unsafe { llama_sampler_chain_add(chain, min_p_sampler) };
             }
         }
 
+        // Add locally typical sampler
+        if config.typical_p < 1.0 && truncate {
+            let typical_sampler = unsafe { llama_sampler_init_typical(config.typical_p, 1) };
+            if !typical_sampler.is_null() {
+                unsafe { llama_sampler_chain_add(chain, typical_sampler) };
+            }
+        }
+
         // Add temperature sampler
         if config.temperature > 0.0 {
             let temp_sampler = unsafe { llama_sampler_init_temp(config.temperature) };
@@ -91,16 +179,22 @@ impl LlamaSampler {
             }
         }
 
-        // Add distribution sampler (final step)
+        // Add distribution or mirostat sampler (final step)
         let seed = if config.seed == 0 {
             rand::random::<u32>()
         } else {
             config.seed as u32
         };
         
-        let dist_sampler = unsafe { llama_sampler_init_dist(seed) };
-        if !dist_sampler.is_null() {
-            unsafe { llama_sampler_chain_add(chain, dist_sampler) };
+        let final_sampler = match config.mirostat {
+            Mirostat::Disabled => unsafe { llama_sampler_init_dist(seed) },
+            Mirostat::V1 { tau, eta } => unsafe {
+                llama_sampler_init_mirostat(vocab_size, seed, tau, eta, 100)
+            },
+            Mirostat::V2 { tau, eta } => unsafe { llama_sampler_init_mirostat_v2(seed, tau, eta) },
+        };
+        if !final_sampler.is_null() {
+            unsafe { llama_sampler_chain_add(chain, final_sampler) };
         }
 
         let ptr = NonNull::new(chain).ok_or_else(|| {
@@ -155,6 +249,18 @@ impl LlamaSampler {
     }
 }
 
+/// Biases for targets given as token ids
+fn token_biases(config: &SamplerConfig) -> Vec<llama_logit_bias> {
+    config
+        .logit_bias
+        .iter()
+        .filter_map(|(target, &bias)| match target {
+            BiasTarget::Token(token) => Some(llama_logit_bias { token: *token, bias }),
+            BiasTarget::Text(_) => None,
+        })
+        .collect()
+}
+
 impl Drop for LlamaSampler {
     fn drop(&mut self) {
         unsafe {
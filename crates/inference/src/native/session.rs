@@ -0,0 +1,238 @@
+//! Persistent sessions for the native backend
+//!
+//! A session owns a context whose KV cache survives between calls. Each
+//! prompt is compared with the tokens already in the cache and only the
+//! new suffix is decoded, so a conversation that grows turn by turn does
+//! not re-process its history. The cache can be saved to disk and
+//! restored later.
+
+use crate::backend::TokenCallback;
+use crate::cancel::CancellationToken;
+use crate::error::{InferenceError, Result};
+use crate::native::backend::sample_loop;
+use crate::native::{ContextParams, LlamaBatch, LlamaContext, LlamaModel, LlamaSampler};
+use crate::sampler::SamplerConfig;
+use bitnet_sys::*;
+use std::ffi::CString;
+use std::path::Path;
+use std::sync::Arc;
+use tracing::debug;
+
+/// Conversation state kept across generate calls
+///
+/// ```ignore
+/// let backend = NativeBackend::with_defaults("model.gguf", 4096, 4)?;
+/// let mut session = backend.new_session()?;
+///
+/// let mut prompt = String::from("User: Hi<|eot_id|>Assistant: ");
+/// let reply = session.generate(&prompt, 128, &SamplerConfig::default())?;
+///
+/// // Only the new turn is decoded; the history is already cached
+/// prompt.push_str(&format!("{}<|eot_id|>User: And in French?<|eot_id|>Assistant: ", reply));
+/// session.generate(&prompt, 128, &SamplerConfig::default())?;
+///
+/// session.save("chat.session")?;
+/// ```
+pub struct NativeSession {
+    ctx: LlamaContext,
+    model: Arc<LlamaModel>,
+    /// Maximum tokens decoded in one call
+    n_batch: usize,
+    /// Tokens in the KV cache, in position order
+    tokens: Vec<llama_token>,
+}
+
+// SAFETY: The context is owned by the session and only used through &mut self
+unsafe impl Send for NativeSession {}
+
+impl NativeSession {
+    /// Create a session with its own context
+    pub(crate) fn new(model: Arc<LlamaModel>, params: &ContextParams) -> Result<Self> {
+        let ctx = LlamaContext::new(Arc::clone(&model), params)?;
+        Ok(Self {
+            ctx,
+            model,
+            n_batch: params.n_batch.max(1) as usize,
+            tokens: Vec::new(),
+        })
+    }
+
+    /// Number of tokens held in the KV cache
+    pub fn n_cached(&self) -> usize {
+        self.tokens.len()
+    }
+
+    /// Forget the cached conversation
+    pub fn reset(&mut self) {
+        self.ctx.kv_cache_clear();
+        self.tokens.clear();
+    }
+
+    /// Generate a continuation of `prompt`
+    ///
+    /// `prompt` is the whole conversation so far; the part that matches
+    /// the cache is reused and only the rest is decoded.
+    pub fn generate(
+        &mut self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+    ) -> Result<String> {
+        self.generate_tokens(prompt, max_tokens, sampler, None)
+    }
+
+    /// Generate a continuation of `prompt`, passing each token to `on_token`
+    pub fn generate_streaming(
+        &mut self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        on_token: TokenCallback<'_>,
+    ) -> Result<String> {
+        let mut callback = on_token;
+        self.generate_tokens(prompt, max_tokens, sampler, Some(&mut callback))
+    }
+
+    /// Save the KV cache and its tokens to a file
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        let c_path = session_path(path.as_ref())?;
+        let saved = unsafe {
+            llama_state_save_file(
+                self.ctx.as_ptr(),
+                c_path.as_ptr(),
+                self.tokens.as_ptr(),
+                self.tokens.len(),
+            )
+        };
+
+        if !saved {
+            return Err(InferenceError::Io(std::io::Error::other(format!(
+                "Failed to save session to {}",
+                path.as_ref().display()
+            ))));
+        }
+
+        debug!(
+            "Saved session with {} tokens to {}",
+            self.tokens.len(),
+            path.as_ref().display()
+        );
+        Ok(())
+    }
+
+    /// Restore a KV cache saved with [`Self::save`]
+    ///
+    /// The file must come from the same model and fit in this session's
+    /// context.
+    pub fn load<P: AsRef<Path>>(&mut self, path: P) -> Result<()> {
+        let c_path = session_path(path.as_ref())?;
+        let mut tokens = vec![0 as llama_token; self.ctx.n_ctx() as usize];
+        let mut n_tokens = 0usize;
+
+        let loaded = unsafe {
+            llama_state_load_file(
+                self.ctx.as_ptr(),
+                c_path.as_ptr(),
+                tokens.as_mut_ptr(),
+                tokens.len(),
+                &mut n_tokens,
+            )
+        };
+
+        if !loaded {
+            self.reset();
+            return Err(InferenceError::Io(std::io::Error::new(
+                std::io::ErrorKind::InvalidData,
+                format!("Failed to load session from {}", path.as_ref().display()),
+            )));
+        }
+
+        tokens.truncate(n_tokens);
+        self.tokens = tokens;
+        debug!(
+            "Loaded session with {} tokens from {}",
+            n_tokens,
+            path.as_ref().display()
+        );
+        Ok(())
+    }
+
+    fn generate_tokens(
+        &mut self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler_config: &SamplerConfig,
+        on_token: Option<&mut dyn FnMut(&str)>,
+    ) -> Result<String> {
+        let prompt_tokens = self.model.tokenize(prompt, true, true)?;
+        let n_ctx = self.ctx.n_ctx() as usize;
+        if prompt_tokens.is_empty() || prompt_tokens.len() >= n_ctx.saturating_sub(4) {
+            return Err(InferenceError::Decode(format!(
+                "Prompt of {} tokens does not fit in context of {}",
+                prompt_tokens.len(),
+                n_ctx
+            )));
+        }
+
+        // Keep at least one prompt token to decode so there are fresh logits
+        let n_keep = common_prefix_len(&self.tokens, &prompt_tokens).min(prompt_tokens.len() - 1);
+        if n_keep < self.tokens.len() {
+            self.ctx.kv_cache_seq_rm(0, n_keep as i32, -1);
+            self.tokens.truncate(n_keep);
+        }
+        debug!(
+            "Session reuses {} of {} prompt tokens",
+            n_keep,
+            prompt_tokens.len()
+        );
+
+        let mut batch = LlamaBatch::new(self.n_batch, 1)?;
+        for chunk in prompt_tokens[n_keep..].chunks(self.n_batch) {
+            batch.clear();
+            batch.add_sequence(chunk, self.tokens.len() as i32, 0, true)?;
+            self.ctx.decode(&mut batch)?;
+            self.tokens.extend_from_slice(chunk);
+        }
+
+        let mut sampler = LlamaSampler::for_model(sampler_config, &self.model)?;
+        let n_past = self.tokens.len();
+        sample_loop(
+            &self.model,
+            &mut self.ctx,
+            &mut sampler,
+            n_past,
+            max_tokens,
+            on_token,
+            None,
+            &CancellationToken::new(),
+            &mut self.tokens,
+        )
+    }
+}
+
+fn session_path(path: &Path) -> Result<CString> {
+    CString::new(path.to_string_lossy().as_bytes()).map_err(|_| {
+        InferenceError::InvalidConfig(format!("Invalid session path: {}", path.display()))
+    })
+}
+
+/// Number of leading tokens shared by `cached` and `prompt`
+fn common_prefix_len(cached: &[llama_token], prompt: &[llama_token]) -> usize {
+    cached
+        .iter()
+        .zip(prompt)
+        .take_while(|(a, b)| a == b)
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_prefix_len() {
+        assert_eq!(common_prefix_len(&[1, 2, 3], &[1, 2, 4, 5]), 2);
+        assert_eq!(common_prefix_len(&[], &[1, 2]), 0);
+        assert_eq!(common_prefix_len(&[1, 2, 3], &[1, 2]), 2);
+    }
+}
@@ -0,0 +1,289 @@
+//! User model registry
+//!
+//! GGUF files beyond the built-in [`BitNetModel`]s are registered by name
+//! in `models.toml` in the model cache directory:
+//!
+//! ```toml
+//! [models.my-model]
+//! path = "/data/my-model.gguf"
+//!
+//! [models.tiny]
+//! url = "https://huggingface.co/org/repo/resolve/main/tiny.gguf"
+//! sha256 = "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08"
+//! ```
+
+use crate::error::{InferenceError, Result};
+use crate::hub::HubFile;
+use crate::models::BitNetModel;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the registry file in the cache directory
+pub const REGISTRY_FILE: &str = "models.toml";
+
+/// A GGUF model registered by the user
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CustomModel {
+    /// Registry name, used wherever a model name is accepted
+    #[serde(skip)]
+    pub name: String,
+    /// Local GGUF file, used in place
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<PathBuf>,
+    /// Download URL; the file is stored in the cache
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Expected SHA256 checksum of the file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
+}
+
+impl CustomModel {
+    /// Register a GGUF file already on disk
+    pub fn local(name: impl Into<String>, path: impl Into<PathBuf>) -> Self {
+        Self {
+            name: name.into(),
+            path: Some(path.into()),
+            url: None,
+            sha256: None,
+        }
+    }
+
+    /// Register a GGUF file to download from `url`
+    pub fn remote(name: impl Into<String>, url: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            path: None,
+            url: Some(url.into()),
+            sha256: None,
+        }
+    }
+
+    /// Set the expected SHA256 checksum
+    pub fn with_sha256(mut self, sha256: impl Into<String>) -> Self {
+        self.sha256 = Some(sha256.into().to_lowercase());
+        self
+    }
+
+    /// GGUF filename, taken from the path or the last URL segment
+    pub fn filename(&self) -> String {
+        let from_path = self
+            .path
+            .as_deref()
+            .and_then(Path::file_name)
+            .map(|name| name.to_string_lossy().into_owned());
+        let from_url = self.url.as_deref().and_then(|url| {
+            let url = url.split(['?', '#']).next().unwrap_or(url);
+            url.rsplit('/').next().filter(|s| !s.is_empty()).map(str::to_string)
+        });
+
+        from_path
+            .or(from_url)
+            .unwrap_or_else(|| "model.gguf".to_string())
+    }
+
+    fn validate(&self) -> Result<()> {
+        let valid_name = !self.name.is_empty()
+            && self
+                .name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'));
+        if !valid_name || self.name.starts_with('.') {
+            return Err(InferenceError::InvalidConfig(format!(
+                "Invalid model name '{}': use letters, digits, '-', '_' and '.'",
+                self.name
+            )));
+        }
+        if BitNetModel::from_str(&self.name).is_some() {
+            return Err(InferenceError::InvalidConfig(format!(
+                "Model name '{}' is taken by a built-in model",
+                self.name
+            )));
+        }
+        if self.path.is_some() == self.url.is_some() {
+            return Err(InferenceError::InvalidConfig(format!(
+                "Model '{}' needs exactly one of a path or a URL",
+                self.name
+            )));
+        }
+        if let Some(sha256) = &self.sha256 {
+            if sha256.len() != 64 || !sha256.chars().all(|c| c.is_ascii_hexdigit()) {
+                return Err(InferenceError::InvalidConfig(format!(
+                    "Invalid SHA256 checksum for '{}': {}",
+                    self.name, sha256
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A built-in, user-registered or Hugging Face Hub model
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ModelRef {
+    /// One of the built-in BitNet models
+    BitNet(BitNetModel),
+    /// A model from the user registry
+    Custom(CustomModel),
+    /// A GGUF file on the Hugging Face Hub
+    Hub(HubFile),
+}
+
+impl ModelRef {
+    /// Human-readable name
+    pub fn name(&self) -> &str {
+        match self {
+            Self::BitNet(model) => model.name(),
+            Self::Custom(model) => &model.name,
+            Self::Hub(file) => file.spec(),
+        }
+    }
+
+    /// Expected SHA256 checksum, if known
+    pub fn sha256(&self) -> Option<&str> {
+        match self {
+            Self::BitNet(model) => model.sha256(),
+            Self::Custom(model) => model.sha256.as_deref(),
+            Self::Hub(_) => None,
+        }
+    }
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct RegistryFile {
+    #[serde(default)]
+    models: BTreeMap<String, CustomModel>,
+}
+
+/// Models registered in a `models.toml` file
+#[derive(Debug)]
+pub struct ModelRegistry {
+    path: PathBuf,
+    models: BTreeMap<String, CustomModel>,
+}
+
+impl ModelRegistry {
+    /// Load the registry at `path`; a missing file is an empty registry
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self> {
+        let path = path.into();
+        let models = match std::fs::read_to_string(&path) {
+            Ok(text) => {
+                let file: RegistryFile = toml::from_str(&text).map_err(|e| {
+                    InferenceError::InvalidConfig(format!(
+                        "Invalid model registry {}: {}",
+                        path.display(),
+                        e
+                    ))
+                })?;
+                file.models
+                    .into_iter()
+                    .map(|(name, mut model)| {
+                        model.name = name.clone();
+                        (name, model)
+                    })
+                    .collect()
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => return Err(e.into()),
+        };
+
+        debug!("Loaded {} custom models from {}", models.len(), path.display());
+        Ok(Self { path, models })
+    }
+
+    /// Write the registry back to its file
+    pub fn save(&self) -> Result<()> {
+        let file = RegistryFile {
+            models: self.models.clone(),
+        };
+        let text = toml::to_string_pretty(&file).map_err(|e| {
+            InferenceError::InvalidConfig(format!("Failed to write model registry: {}", e))
+        })?;
+
+        if let Some(parent) = self.path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&self.path, text)?;
+        Ok(())
+    }
+
+    /// Path of the registry file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Look up a model by name
+    pub fn get(&self, name: &str) -> Option<&CustomModel> {
+        self.models.get(name)
+    }
+
+    /// Registered models, sorted by name
+    pub fn models(&self) -> impl Iterator<Item = &CustomModel> {
+        self.models.values()
+    }
+
+    /// Add a model, replacing any with the same name
+    ///
+    /// Returns the replaced model. Names of built-in models are rejected.
+    pub fn add(&mut self, model: CustomModel) -> Result<Option<CustomModel>> {
+        model.validate()?;
+        Ok(self.models.insert(model.name.clone(), model))
+    }
+
+    /// Remove a model by name
+    pub fn remove(&mut self, name: &str) -> Option<CustomModel> {
+        self.models.remove(name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_round_trip() {
+        let dir = std::env::temp_dir().join(format!("neuro-registry-{}", std::process::id()));
+        let path = dir.join(REGISTRY_FILE);
+
+        let mut registry = ModelRegistry::load(&path).unwrap();
+        assert_eq!(registry.models().count(), 0);
+        registry
+            .add(CustomModel::local("mine", "/data/mine.gguf"))
+            .unwrap();
+        registry
+            .add(
+                CustomModel::remote("tiny", "https://example.com/org/tiny-q4.gguf?download=1")
+                    .with_sha256("AB".repeat(32)),
+            )
+            .unwrap();
+        registry.save().unwrap();
+
+        let registry = ModelRegistry::load(&path).unwrap();
+        let tiny = registry.get("tiny").unwrap();
+        assert_eq!(tiny.name, "tiny");
+        assert_eq!(tiny.filename(), "tiny-q4.gguf");
+        assert_eq!(tiny.sha256.as_deref(), Some("ab".repeat(32).as_str()));
+        assert_eq!(
+            registry.get("mine").unwrap().path.as_deref(),
+            Some(Path::new("/data/mine.gguf"))
+        );
+
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_add_validates() {
+        let mut registry = ModelRegistry::load("/nonexistent/models.toml").unwrap();
+        assert!(registry.add(CustomModel::local("2b", "/m.gguf")).is_err());
+        assert!(registry.add(CustomModel::local("../up", "/m.gguf")).is_err());
+        assert!(registry
+            .add(CustomModel::remote("x", "https://e.com/x.gguf").with_sha256("abc"))
+            .is_err());
+
+        let mut both = CustomModel::local("both", "/m.gguf");
+        both.url = Some("https://e.com/m.gguf".to_string());
+        assert!(registry.add(both).is_err());
+    }
+}
@@ -1,5 +1,77 @@
 //! Sampler configuration for text generation
 
+use std::collections::HashMap;
+
+/// Token whose logit is adjusted by [`SamplerConfig::logit_bias`]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum BiasTarget {
+    /// A token id in the model's vocabulary
+    Token(i32),
+    /// Every token of this text as tokenized by the model
+    Text(String),
+}
+
+impl From<i32> for BiasTarget {
+    fn from(token: i32) -> Self {
+        Self::Token(token)
+    }
+}
+
+impl From<&str> for BiasTarget {
+    fn from(text: &str) -> Self {
+        Self::Text(text.to_string())
+    }
+}
+
+impl From<String> for BiasTarget {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+/// Mirostat sampling, which adapts to keep output perplexity near a target
+///
+/// When enabled it replaces top-k, top-p, min-p and typical-p.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum Mirostat {
+    /// Use the regular sampler chain
+    #[default]
+    Disabled,
+    /// Mirostat (original algorithm)
+    V1 {
+        /// Target entropy (default 5.0)
+        tau: f32,
+        /// Learning rate (default 0.1)
+        eta: f32,
+    },
+    /// Mirostat 2.0
+    V2 {
+        /// Target entropy (default 5.0)
+        tau: f32,
+        /// Learning rate (default 0.1)
+        eta: f32,
+    },
+}
+
+impl Mirostat {
+    /// Algorithm version as used by llama.cpp (0 = disabled)
+    pub fn version(&self) -> u8 {
+        match self {
+            Mirostat::Disabled => 0,
+            Mirostat::V1 { .. } => 1,
+            Mirostat::V2 { .. } => 2,
+        }
+    }
+
+    /// Target entropy and learning rate, if enabled
+    pub fn params(&self) -> Option<(f32, f32)> {
+        match *self {
+            Mirostat::Disabled => None,
+            Mirostat::V1 { tau, eta } | Mirostat::V2 { tau, eta } => Some((tau, eta)),
+        }
+    }
+}
+
 /// Configuration for token sampling
 #[derive(Debug, Clone)]
 pub struct SamplerConfig {
@@ -11,12 +83,25 @@ pub struct SamplerConfig {
     pub top_p: f32,
     /// Min-P sampling: minimum probability threshold
     pub min_p: f32,
+    /// Locally typical sampling (1.0 = disabled)
+    pub typical_p: f32,
+    /// Mirostat sampling (replaces top-k/top-p/min-p/typical-p when enabled)
+    pub mirostat: Mirostat,
     /// Repetition penalty
     pub repeat_penalty: f32,
     /// Number of tokens to consider for repetition penalty
     pub repeat_last_n: i32,
+    /// Subtracted once from the logit of every token already generated
+    pub presence_penalty: f32,
+    /// Subtracted from a token's logit for each time it was generated
+    pub frequency_penalty: f32,
     /// Random seed (0 = random)
     pub seed: u32,
+    /// GBNF grammar the output must match (`None` = unconstrained)
+    pub grammar: Option<String>,
+    /// Added to the logits of the target tokens
+    /// (`f32::NEG_INFINITY` bans a token)
+    pub logit_bias: HashMap<BiasTarget, f32>,
 }
 
 impl Default for SamplerConfig {
@@ -26,9 +111,15 @@ impl Default for SamplerConfig {
             top_k: 40,
             top_p: 0.95,
             min_p: 0.05,
+            typical_p: 1.0,
+            mirostat: Mirostat::Disabled,
             repeat_penalty: 1.1,
             repeat_last_n: 64,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
             seed: 0,
+            grammar: None,
+            logit_bias: HashMap::new(),
         }
     }
 }
@@ -41,9 +132,15 @@ impl SamplerConfig {
             top_k: 1,
             top_p: 1.0,
             min_p: 0.0,
+            typical_p: 1.0,
+            mirostat: Mirostat::Disabled,
             repeat_penalty: 1.0,
             repeat_last_n: 0,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
             seed: 0,
+            grammar: None,
+            logit_bias: HashMap::new(),
         }
     }
 
@@ -54,9 +151,15 @@ impl SamplerConfig {
             top_k: 50,
             top_p: 0.95,
             min_p: 0.02,
+            typical_p: 1.0,
+            mirostat: Mirostat::Disabled,
             repeat_penalty: 1.15,
             repeat_last_n: 128,
+            presence_penalty: 0.0,
+            frequency_penalty: 0.0,
             seed: 0,
+            grammar: None,
+            logit_bias: HashMap::new(),
         }
     }
 
@@ -71,9 +174,77 @@ impl SamplerConfig {
         self
     }
 
+    /// Set min-p threshold (0.0 = disabled)
+    pub fn with_min_p(mut self, min_p: f32) -> Self {
+        self.min_p = min_p;
+        self
+    }
+
+    /// Set locally typical sampling (1.0 = disabled)
+    pub fn with_typical_p(mut self, typical_p: f32) -> Self {
+        self.typical_p = typical_p;
+        self
+    }
+
+    /// Use mirostat sampling
+    pub fn with_mirostat(mut self, mirostat: Mirostat) -> Self {
+        self.mirostat = mirostat;
+        self
+    }
+
+    /// Set presence and frequency penalties
+    pub fn with_penalties(mut self, presence: f32, frequency: f32) -> Self {
+        self.presence_penalty = presence;
+        self.frequency_penalty = frequency;
+        self
+    }
+
     /// Set random seed
     pub fn with_seed(mut self, seed: u32) -> Self {
         self.seed = seed;
         self
     }
+
+    /// Constrain output to a GBNF grammar
+    ///
+    /// The grammar's start rule must be named `root`.
+    pub fn with_grammar(mut self, grammar: impl Into<String>) -> Self {
+        self.grammar = Some(grammar.into());
+        self
+    }
+
+    /// Boost (positive) or suppress (negative) a token or text
+    pub fn with_logit_bias(mut self, target: impl Into<BiasTarget>, bias: f32) -> Self {
+        self.logit_bias.insert(target.into(), bias);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_logit_bias() {
+        let config = SamplerConfig::default()
+            .with_logit_bias(42, 2.0)
+            .with_logit_bias("As an AI", f32::NEG_INFINITY);
+
+        assert_eq!(config.logit_bias.get(&BiasTarget::Token(42)), Some(&2.0));
+        assert_eq!(
+            config.logit_bias.get(&BiasTarget::Text("As an AI".to_string())),
+            Some(&f32::NEG_INFINITY)
+        );
+        assert!(SamplerConfig::greedy().logit_bias.is_empty());
+    }
+
+    #[test]
+    fn test_mirostat() {
+        assert_eq!(Mirostat::default().version(), 0);
+        assert_eq!(Mirostat::default().params(), None);
+
+        let config = SamplerConfig::default().with_mirostat(Mirostat::V2 { tau: 5.0, eta: 0.1 });
+        assert_eq!(config.mirostat.version(), 2);
+        assert_eq!(config.mirostat.params(), Some((5.0, 0.1)));
+    }
 }
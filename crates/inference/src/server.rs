@@ -0,0 +1,440 @@
+//! Persistent server backend for bitnet.cpp
+//!
+//! Starts the llama-server binary once and sends every request to its
+//! `/completion` endpoint, so the model is loaded a single time instead of
+//! on each call like the subprocess backend.
+
+use crate::backend::{InferenceBackend, TokenCallback};
+use crate::cancel::CancellationToken;
+use crate::chat::ChatTemplate;
+use crate::error::{InferenceError, Result};
+use crate::sampler::{BiasTarget, SamplerConfig};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::io::{BufRead, BufReader};
+use std::net::TcpListener;
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Stdio};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How long to wait for the server to load the model
+const STARTUP_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// Interval between health checks while starting
+const HEALTH_POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+#[derive(Serialize)]
+struct CompletionRequest<'a> {
+    prompt: &'a str,
+    n_predict: u32,
+    temperature: f32,
+    top_k: i32,
+    top_p: f32,
+    min_p: f32,
+    typical_p: f32,
+    repeat_penalty: f32,
+    repeat_last_n: i32,
+    presence_penalty: f32,
+    frequency_penalty: f32,
+    /// 0 = disabled, 1 = Mirostat, 2 = Mirostat 2.0
+    mirostat: u8,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mirostat_tau: Option<f32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mirostat_eta: Option<f32>,
+    /// -1 asks the server for a random seed
+    seed: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    grammar: Option<&'a str>,
+    /// `[token id or text, bias]` pairs; a bias of `false` bans the token
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    logit_bias: Vec<(Value, Value)>,
+    stream: bool,
+}
+
+impl<'a> CompletionRequest<'a> {
+    fn new(prompt: &'a str, max_tokens: u32, sampler: &'a SamplerConfig, stream: bool) -> Self {
+        Self {
+            prompt,
+            n_predict: max_tokens,
+            temperature: sampler.temperature,
+            top_k: sampler.top_k,
+            top_p: sampler.top_p,
+            min_p: sampler.min_p,
+            typical_p: sampler.typical_p,
+            repeat_penalty: sampler.repeat_penalty,
+            repeat_last_n: sampler.repeat_last_n,
+            presence_penalty: sampler.presence_penalty,
+            frequency_penalty: sampler.frequency_penalty,
+            mirostat: sampler.mirostat.version(),
+            mirostat_tau: sampler.mirostat.params().map(|(tau, _)| tau),
+            mirostat_eta: sampler.mirostat.params().map(|(_, eta)| eta),
+            seed: if sampler.seed == 0 { -1 } else { sampler.seed as i64 },
+            grammar: sampler.grammar.as_deref(),
+            logit_bias: sampler
+                .logit_bias
+                .iter()
+                .map(|(target, &bias)| {
+                    let target = match target {
+                        BiasTarget::Token(token) => Value::from(*token),
+                        BiasTarget::Text(text) => Value::from(text.as_str()),
+                    };
+                    // JSON has no infinity
+                    let bias = if bias.is_finite() {
+                        Value::from(bias)
+                    } else {
+                        Value::Bool(false)
+                    };
+                    (target, bias)
+                })
+                .collect(),
+            stream,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct TokenizeRequest<'a> {
+    content: &'a str,
+    add_special: bool,
+}
+
+#[derive(Deserialize)]
+struct TokenizeResponse {
+    tokens: Vec<i32>,
+}
+
+#[derive(Serialize)]
+struct DetokenizeRequest<'a> {
+    tokens: &'a [i32],
+}
+
+#[derive(Deserialize)]
+struct DetokenizeResponse {
+    content: String,
+}
+
+#[derive(Deserialize)]
+struct CompletionChunk {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    stop: bool,
+}
+
+/// Server-based inference backend
+///
+/// Keeps a llama-server process from bitnet.cpp running for the lifetime
+/// of the backend; the process is killed on drop.
+pub struct ServerBackend {
+    /// Running llama-server process
+    process: Mutex<Child>,
+    /// Base URL of the server, e.g. `http://127.0.0.1:8080`
+    base_url: String,
+    agent: ureq::Agent,
+    /// Prompt format for chat
+    chat_template: ChatTemplate,
+}
+
+impl ServerBackend {
+    /// Start llama-server for a model and wait until it is ready
+    pub fn start<P: AsRef<Path>>(model_path: P, n_ctx: u32, n_threads: Option<i32>) -> Result<Self> {
+        let binary_path = Self::find_binary()?;
+        Self::start_with_binary(binary_path, model_path, n_ctx, n_threads)
+    }
+
+    /// Start a specific llama-server binary
+    pub fn start_with_binary<P1: AsRef<Path>, P2: AsRef<Path>>(
+        binary_path: P1,
+        model_path: P2,
+        n_ctx: u32,
+        n_threads: Option<i32>,
+    ) -> Result<Self> {
+        let model_path = model_path.as_ref();
+        let port = free_port()?;
+
+        let mut cmd = Command::new(binary_path.as_ref());
+        cmd.arg("-m").arg(model_path)
+            .arg("-c").arg(n_ctx.to_string())
+            .arg("--host").arg("127.0.0.1")
+            .arg("--port").arg(port.to_string())
+            .arg("--log-disable");
+
+        if let Some(threads) = n_threads {
+            cmd.arg("-t").arg(threads.to_string());
+        }
+
+        debug!("Running: {:?}", cmd);
+
+        let process = cmd
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(InferenceError::Io)?;
+
+        let agent = ureq::Agent::new_with_config(
+            ureq::Agent::config_builder().http_status_as_error(false).build(),
+        );
+
+        let backend = Self {
+            process: Mutex::new(process),
+            base_url: format!("http://127.0.0.1:{}", port),
+            agent,
+            chat_template: ChatTemplate::resolve(model_path),
+        };
+        backend.wait_until_ready()?;
+
+        info!("llama-server ready at {}", backend.base_url);
+        Ok(backend)
+    }
+
+    /// Override the chat template detected from the model
+    pub fn with_chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = template;
+        self
+    }
+
+    /// Check if the backend is available
+    pub fn is_available() -> bool {
+        Self::find_binary().is_ok()
+    }
+
+    /// Find the llama-server binary
+    fn find_binary() -> Result<PathBuf> {
+        // Check environment variable
+        if let Ok(path) = std::env::var("BITNET_SERVER_PATH") {
+            let path = PathBuf::from(path);
+            if path.exists() {
+                return Ok(path);
+            }
+        }
+
+        let candidates = [
+            "~/.local/bin/llama-server-bitnet",
+            "~/.local/bin/llama-server",
+            "~/.local/share/bitnet.cpp/build/bin/llama-server",
+            "./bitnet.cpp/build/bin/llama-server",
+            "./BitNet/build/bin/llama-server",
+            "/usr/local/bin/llama-server-bitnet",
+        ];
+
+        for candidate in candidates {
+            let path = expand_home(candidate);
+            if path.exists() {
+                info!("Found bitnet.cpp server at: {}", path.display());
+                return Ok(path);
+            }
+        }
+
+        Err(InferenceError::BackendInit(
+            "Could not find llama-server binary from bitnet.cpp. \
+             Set BITNET_SERVER_PATH or run scripts/setup_bitnet.sh".to_string()
+        ))
+    }
+
+    /// Poll `/health` until the model is loaded
+    fn wait_until_ready(&self) -> Result<()> {
+        let started = Instant::now();
+        let url = format!("{}/health", self.base_url);
+
+        while started.elapsed() < STARTUP_TIMEOUT {
+            if let Some(status) = self.process_exit_status() {
+                return Err(InferenceError::BackendInit(format!(
+                    "llama-server exited during startup: {}",
+                    status
+                )));
+            }
+
+            // 503 while the model is loading, connection refused before that
+            if let Ok(response) = self.agent.get(&url).call() {
+                if response.status().is_success() {
+                    return Ok(());
+                }
+            }
+            std::thread::sleep(HEALTH_POLL_INTERVAL);
+        }
+
+        Err(InferenceError::BackendInit(format!(
+            "llama-server did not become ready within {}s",
+            STARTUP_TIMEOUT.as_secs()
+        )))
+    }
+
+    fn process_exit_status(&self) -> Option<std::process::ExitStatus> {
+        let mut process = self.process.lock().ok()?;
+        process.try_wait().ok().flatten()
+    }
+
+    /// POST a JSON body to an endpoint and read a JSON reply
+    fn post_json<T: Serialize, R: serde::de::DeserializeOwned>(&self, endpoint: &str, body: &T) -> Result<R> {
+        self.post(endpoint, body)?
+            .body_mut()
+            .read_json()
+            .map_err(|e| InferenceError::Decode(format!("Invalid llama-server response: {}", e)))
+    }
+
+    /// POST a JSON body to an endpoint, failing on non-2xx replies
+    fn post<T: Serialize>(&self, endpoint: &str, body: &T) -> Result<ureq::http::Response<ureq::Body>> {
+        let mut response = self
+            .agent
+            .post(&format!("{}/{}", self.base_url, endpoint))
+            .send_json(body)
+            .map_err(|e| InferenceError::Decode(format!("llama-server request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            return Err(InferenceError::Decode(format!(
+                "llama-server returned {}: {}",
+                status, body
+            )));
+        }
+        Ok(response)
+    }
+}
+
+impl InferenceBackend for ServerBackend {
+    fn generate(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
+        // Streaming lets a cancelled request stop between tokens
+        self.generate_streaming(prompt, max_tokens, sampler, &mut |_| {}, cancel)
+    }
+
+    fn generate_streaming(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        on_token: TokenCallback<'_>,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
+        let request = CompletionRequest::new(prompt, max_tokens, sampler, true);
+        let response = self.post("completion", &request)?;
+        let reader = BufReader::new(response.into_body().into_reader());
+        let mut output = String::new();
+
+        // Server-sent events: one `data: {...}` line per token
+        for line in reader.lines() {
+            // Dropping the response closes the connection, which makes
+            // llama-server stop generating
+            if cancel.is_cancelled() {
+                return Err(InferenceError::Interrupted);
+            }
+
+            let line = line.map_err(InferenceError::Io)?;
+            let Some(data) = line.strip_prefix("data: ") else {
+                continue;
+            };
+
+            let chunk: CompletionChunk = serde_json::from_str(data)
+                .map_err(|e| InferenceError::Decode(format!("Invalid llama-server event: {}", e)))?;
+            if !chunk.content.is_empty() {
+                on_token(&chunk.content);
+                output.push_str(&chunk.content);
+            }
+            if chunk.stop {
+                break;
+            }
+        }
+
+        Ok(output.trim().to_string())
+    }
+
+    fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        let request = TokenizeRequest {
+            content: text,
+            add_special: false,
+        };
+        let response: TokenizeResponse = self.post_json("tokenize", &request)?;
+        Ok(response.tokens)
+    }
+
+    fn detokenize(&self, tokens: &[i32]) -> Result<String> {
+        let response: DetokenizeResponse = self.post_json("detokenize", &DetokenizeRequest { tokens })?;
+        Ok(response.content)
+    }
+
+    fn chat_template(&self) -> ChatTemplate {
+        self.chat_template
+    }
+
+    fn name(&self) -> &'static str {
+        "bitnet.cpp (server)"
+    }
+
+    fn is_ready(&self) -> bool {
+        self.process_exit_status().is_none()
+    }
+}
+
+impl Drop for ServerBackend {
+    fn drop(&mut self) {
+        if let Ok(process) = self.process.get_mut() {
+            if let Err(e) = process.kill() {
+                warn!("Failed to stop llama-server: {}", e);
+            }
+            let _ = process.wait();
+        }
+    }
+}
+
+/// Ask the OS for an unused local port
+fn free_port() -> Result<u16> {
+    let listener = TcpListener::bind("127.0.0.1:0").map_err(InferenceError::Io)?;
+    Ok(listener.local_addr().map_err(InferenceError::Io)?.port())
+}
+
+fn expand_home(path: &str) -> PathBuf {
+    match (path.strip_prefix("~/"), dirs::home_dir()) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => PathBuf::from(path),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_completion_request() {
+        let sampler = SamplerConfig::default().with_grammar("root ::= \"ok\"");
+        let json = serde_json::to_value(CompletionRequest::new("Hi", 16, &sampler, false)).unwrap();
+        assert_eq!(json["n_predict"], 16);
+        assert_eq!(json["seed"], -1);
+        assert_eq!(json["grammar"], "root ::= \"ok\"");
+
+        let json = serde_json::to_value(CompletionRequest::new("Hi", 16, &SamplerConfig::greedy(), true))
+            .unwrap();
+        assert!(json.get("grammar").is_none());
+        assert!(json.get("logit_bias").is_none());
+        assert_eq!(json["stream"], true);
+    }
+
+    #[test]
+    fn test_completion_request_logit_bias() {
+        let sampler = SamplerConfig::default().with_logit_bias("As an AI", f32::NEG_INFINITY);
+        let json = serde_json::to_value(CompletionRequest::new("Hi", 16, &sampler, false)).unwrap();
+        assert_eq!(json["logit_bias"], serde_json::json!([["As an AI", false]]));
+    }
+
+    #[test]
+    fn test_parse_stream_event() {
+        let chunk: CompletionChunk =
+            serde_json::from_str(r#"{"content":" world","stop":false,"id_slot":0}"#).unwrap();
+        assert_eq!(chunk.content, " world");
+        assert!(!chunk.stop);
+    }
+
+    #[test]
+    fn test_expand_home() {
+        assert!(!expand_home("~/bin/llama-server").starts_with("~"));
+        assert_eq!(expand_home("/usr/bin/x"), PathBuf::from("/usr/bin/x"));
+    }
+}
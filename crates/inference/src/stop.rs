@@ -0,0 +1,227 @@
+//! Stop sequences and stop patterns
+//!
+//! Generation ends at the first stop sequence or at the first match of a
+//! stop pattern (a regular expression). While streaming, text is held back
+//! until it is known not to be part of a stop, so callers never see text
+//! past the cut.
+
+use crate::error::{InferenceError, Result};
+use regex::Regex;
+
+/// Compiled stop sequences and patterns of one generation
+#[derive(Debug, Default)]
+pub(crate) struct StopConditions {
+    sequences: Vec<String>,
+    patterns: Vec<Regex>,
+}
+
+impl StopConditions {
+    /// Compile `patterns`, failing with [`InferenceError::InvalidConfig`]
+    /// on an invalid regular expression
+    pub(crate) fn new(sequences: &[String], patterns: &[String]) -> Result<Self> {
+        let patterns = patterns
+            .iter()
+            .map(|pattern| {
+                Regex::new(pattern).map_err(|e| {
+                    InferenceError::InvalidConfig(format!(
+                        "Invalid stop pattern '{}': {}",
+                        pattern, e
+                    ))
+                })
+            })
+            .collect::<Result<_>>()?;
+
+        Ok(Self {
+            sequences: sequences
+                .iter()
+                .filter(|s| !s.is_empty())
+                .cloned()
+                .collect(),
+            patterns,
+        })
+    }
+
+    /// Byte offset of the earliest stop in `text`
+    ///
+    /// Patterns are matched one line at a time.
+    pub(crate) fn find(&self, text: &str) -> Option<usize> {
+        let sequence = self
+            .sequences
+            .iter()
+            .filter_map(|stop| text.find(stop.as_str()))
+            .min();
+
+        let pattern = if self.patterns.is_empty() {
+            None
+        } else {
+            let mut offset = 0;
+            text.split_inclusive('\n').find_map(|line| {
+                let line_start = offset;
+                offset += line.len();
+                let line = line.strip_suffix('\n').unwrap_or(line);
+                self.patterns
+                    .iter()
+                    .filter_map(|pattern| pattern.find(line))
+                    .map(|m| line_start + m.start())
+                    .min()
+            })
+        };
+
+        match (sequence, pattern) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            (a, b) => a.or(b),
+        }
+    }
+
+    /// `text` cut at the earliest stop and trimmed
+    pub(crate) fn apply(&self, text: &str) -> String {
+        let end = self.find(text).unwrap_or(text.len());
+        text[..end].trim().to_string()
+    }
+
+    /// Bytes at the end of `pending` that could still turn into a stop
+    fn held_back(&self, pending: &str) -> usize {
+        // Longest tail that is the start of a stop sequence
+        let sequence = self
+            .sequences
+            .iter()
+            .flat_map(|stop| {
+                (1..stop.len())
+                    .filter(|&len| stop.is_char_boundary(len))
+                    .filter(|&len| pending.ends_with(&stop[..len]))
+            })
+            .max()
+            .unwrap_or(0);
+
+        // A pattern may match anywhere in the unfinished line
+        let line = if self.patterns.is_empty() {
+            0
+        } else {
+            pending.len() - pending.rfind('\n').map_or(0, |i| i + 1)
+        };
+
+        sequence.max(line)
+    }
+}
+
+/// Holds back streamed text until it is known not to contain a stop
+pub(crate) struct StopFilter<'a> {
+    conditions: &'a StopConditions,
+    pending: String,
+    stopped: bool,
+}
+
+impl<'a> StopFilter<'a> {
+    pub(crate) fn new(conditions: &'a StopConditions) -> Self {
+        Self {
+            conditions,
+            pending: String::new(),
+            stopped: false,
+        }
+    }
+
+    /// Whether a stop has been reached; later tokens are dropped
+    pub(crate) fn is_stopped(&self) -> bool {
+        self.stopped
+    }
+
+    /// Add a token, returning the text that is now safe to emit
+    pub(crate) fn push(&mut self, token: &str) -> Option<String> {
+        if self.stopped {
+            return None;
+        }
+        self.pending.push_str(token);
+
+        if let Some(pos) = self.conditions.find(&self.pending) {
+            self.stopped = true;
+            self.pending.truncate(pos);
+            return self.take(pos);
+        }
+
+        let held = self.conditions.held_back(&self.pending);
+        self.take(self.pending.len() - held)
+    }
+
+    /// Flush held-back text once generation has ended
+    pub(crate) fn finish(&mut self) -> Option<String> {
+        if self.stopped {
+            return None;
+        }
+        self.take(self.pending.len())
+    }
+
+    fn take(&mut self, len: usize) -> Option<String> {
+        if len == 0 {
+            return None;
+        }
+        let rest = self.pending.split_off(len);
+        Some(std::mem::replace(&mut self.pending, rest))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn conditions(stops: &[&str], patterns: &[&str]) -> StopConditions {
+        let stops: Vec<String> = stops.iter().map(|s| s.to_string()).collect();
+        let patterns: Vec<String> = patterns.iter().map(|s| s.to_string()).collect();
+        StopConditions::new(&stops, &patterns).unwrap()
+    }
+
+    fn stream(tokens: &[&str], stops: &[&str], patterns: &[&str]) -> String {
+        let conditions = conditions(stops, patterns);
+        let mut filter = StopFilter::new(&conditions);
+        let mut emitted = String::new();
+        for token in tokens {
+            emitted.extend(filter.push(token));
+        }
+        emitted.extend(filter.finish());
+        emitted
+    }
+
+    #[test]
+    fn test_stop_sequence_filter() {
+        // Stop sequence split across tokens is never emitted
+        assert_eq!(
+            stream(&["Hello", " world\n", "\nQ:", " next"], &["\n\n"], &[]),
+            "Hello world"
+        );
+        // Held-back prefix is released when it does not complete a stop
+        assert_eq!(stream(&["a\n", "b"], &["\n\n"], &[]), "a\nb");
+        // Earliest stop sequence wins
+        assert_eq!(stream(&["x END y STOP"], &["STOP", "END"], &[]), "x ");
+        // No stop sequences: everything passes through
+        assert_eq!(stream(&["a", "b"], &[], &[]), "ab");
+    }
+
+    #[test]
+    fn test_stop_pattern_filter() {
+        // Match split across tokens is never emitted
+        assert_eq!(
+            stream(
+                &["Answer: 4\n", "Question ", "1", "2: next"],
+                &[],
+                &[r"Question \d+:"]
+            ),
+            "Answer: 4\n"
+        );
+        // Unmatched lines are released whole
+        assert_eq!(stream(&["one\ntw", "o"], &[], &[r"^\d"]), "one\ntwo");
+        // Sequences and patterns combine, earliest wins
+        assert_eq!(stream(&["a END b 42"], &["END"], &[r"\d+"]), "a ");
+    }
+
+    #[test]
+    fn test_apply_and_invalid_pattern() {
+        let conditions = conditions(&["</s>"], &[r"(?i)^user:"]);
+        assert_eq!(conditions.apply("Hi there\nUSER: more"), "Hi there");
+        assert_eq!(conditions.apply("Hello</s>rest"), "Hello");
+        assert_eq!(conditions.apply("nothing to cut "), "nothing to cut");
+
+        assert!(matches!(
+            StopConditions::new(&[], &["(".to_string()]),
+            Err(InferenceError::InvalidConfig(_))
+        ));
+    }
+}
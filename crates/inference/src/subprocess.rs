@@ -3,13 +3,20 @@
 //! Fallback that calls the llama-cli binary directly when native bindings fail.
 
 use crate::backend::{InferenceBackend, TokenCallback};
+use crate::chat::ChatTemplate;
 use crate::error::{InferenceError, Result};
-use crate::sampler::SamplerConfig;
+use crate::sampler::{BiasTarget, SamplerConfig};
+use crate::cancel::{CancellationToken, CANCEL_POLL_INTERVAL};
 use std::path::{Path, PathBuf};
-use std::process::{Command, Stdio};
-use std::io::{BufRead, BufReader};
+use std::process::{Child, ChildStdout, Command, ExitStatus, Stdio};
+use std::io::{BufRead, BufReader, Read};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
 use tracing::{debug, info, warn};
 
+/// Default number of llama-cli processes run in parallel
+const DEFAULT_MAX_PARALLEL: usize = 2;
+
 /// Subprocess-based inference backend
 /// 
 /// Uses the llama-cli binary from bitnet.cpp for inference.
@@ -23,6 +30,12 @@ pub struct SubprocessBackend {
     n_ctx: u32,
     /// Number of threads
     n_threads: Option<i32>,
+    /// Prompt format for chat
+    chat_template: ChatTemplate,
+    /// Most llama-cli processes to run at once
+    max_parallel: usize,
+    /// `-ngl`, `-mg` and `-ts` flags for GPU offload
+    gpu_args: Vec<String>,
 }
 
 impl SubprocessBackend {
@@ -35,6 +48,9 @@ impl SubprocessBackend {
             model_path: model_path.as_ref().to_path_buf(),
             n_ctx: 2048,
             n_threads: None,
+            chat_template: ChatTemplate::resolve(model_path),
+            max_parallel: DEFAULT_MAX_PARALLEL,
+            gpu_args: Vec::new(),
         })
     }
 
@@ -56,6 +72,9 @@ impl SubprocessBackend {
             model_path: model_path.as_ref().to_path_buf(),
             n_ctx: 2048,
             n_threads: None,
+            chat_template: ChatTemplate::resolve(model_path),
+            max_parallel: DEFAULT_MAX_PARALLEL,
+            gpu_args: Vec::new(),
         })
     }
 
@@ -71,6 +90,30 @@ impl SubprocessBackend {
         self
     }
 
+    /// Override the chat template detected from the model
+    pub fn with_chat_template(mut self, template: ChatTemplate) -> Self {
+        self.chat_template = template;
+        self
+    }
+
+    /// Offload layers to the GPU
+    ///
+    /// Needs a llama-cli built with GPU support. `tensor_split` gives each
+    /// GPU's share of the model; leave it empty to split automatically.
+    pub fn with_gpu_offload(mut self, n_gpu_layers: i32, main_gpu: i32, tensor_split: Vec<f32>) -> Self {
+        self.gpu_args = gpu_args(n_gpu_layers, main_gpu, &tensor_split);
+        self
+    }
+
+    /// Set how many llama-cli processes may run at once
+    ///
+    /// Each process loads its own copy of the model, so this is bounded
+    /// by memory as much as by CPU.
+    pub fn with_max_parallel(mut self, max_parallel: usize) -> Self {
+        self.max_parallel = max_parallel.max(1);
+        self
+    }
+
     /// Find the llama-cli binary
     fn find_binary() -> Result<PathBuf> {
         // Check environment variable
@@ -128,7 +171,13 @@ impl SubprocessBackend {
 }
 
 impl InferenceBackend for SubprocessBackend {
-    fn generate(&self, prompt: &str, max_tokens: u32, sampler: &SamplerConfig) -> Result<String> {
+    fn generate(
+        &self,
+        prompt: &str,
+        max_tokens: u32,
+        sampler: &SamplerConfig,
+        cancel: &CancellationToken,
+    ) -> Result<String> {
         let mut cmd = Command::new(&self.binary_path);
         
         cmd.arg("-m").arg(&self.model_path)
@@ -139,12 +188,23 @@ impl InferenceBackend for SubprocessBackend {
             .arg("--top-k").arg(sampler.top_k.to_string())
             .arg("--top-p").arg(sampler.top_p.to_string())
             .arg("--repeat-penalty").arg(sampler.repeat_penalty.to_string())
+            .args(sampling_args(sampler))
             .arg("--no-display-prompt");
 
         if let Some(threads) = self.n_threads {
             cmd.arg("-t").arg(threads.to_string());
         }
 
+        cmd.args(&self.gpu_args);
+
+        if let Some(grammar) = &sampler.grammar {
+            cmd.arg("--grammar").arg(grammar);
+        }
+
+        for bias in logit_bias_args(sampler) {
+            cmd.arg("--logit-bias").arg(bias);
+        }
+
         if sampler.seed != 0 {
             cmd.arg("-s").arg(sampler.seed.to_string());
         }
@@ -153,21 +213,26 @@ impl InferenceBackend for SubprocessBackend {
 
         debug!("Running: {:?}", cmd);
 
-        let output = cmd
+        let child = cmd
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
-            .output()
+            .spawn()
             .map_err(InferenceError::Io)?;
 
-        if !output.status.success() {
-            let stderr = String::from_utf8_lossy(&output.stderr);
+        let (stdout, status, stderr) = run_cancellable(child, cancel, |mut stdout| {
+            let mut buf = Vec::new();
+            stdout.read_to_end(&mut buf).map_err(InferenceError::Io)?;
+            Ok(buf)
+        })?;
+
+        if !status.success() {
             return Err(InferenceError::Decode(format!(
                 "llama-cli failed: {}",
                 stderr
             )));
         }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
+        let stdout = String::from_utf8_lossy(&stdout);
         Ok(stdout.trim().to_string())
     }
 
@@ -177,6 +242,7 @@ impl InferenceBackend for SubprocessBackend {
         max_tokens: u32,
         sampler: &SamplerConfig,
         on_token: TokenCallback<'_>,
+        cancel: &CancellationToken,
     ) -> Result<String> {
         let mut cmd = Command::new(&self.binary_path);
         
@@ -188,48 +254,58 @@ impl InferenceBackend for SubprocessBackend {
             .arg("--top-k").arg(sampler.top_k.to_string())
             .arg("--top-p").arg(sampler.top_p.to_string())
             .arg("--repeat-penalty").arg(sampler.repeat_penalty.to_string())
+            .args(sampling_args(sampler))
             .arg("--log-disable");
 
         if let Some(threads) = self.n_threads {
             cmd.arg("-t").arg(threads.to_string());
         }
 
-        let mut child = cmd
+        cmd.args(&self.gpu_args);
+
+        if let Some(grammar) = &sampler.grammar {
+            cmd.arg("--grammar").arg(grammar);
+        }
+
+        for bias in logit_bias_args(sampler) {
+            cmd.arg("--logit-bias").arg(bias);
+        }
+
+        let child = cmd
             .stdout(Stdio::piped())
             .stderr(Stdio::null())
             .spawn()
             .map_err(InferenceError::Io)?;
 
-        let stdout = child.stdout.take()
-            .ok_or_else(|| InferenceError::Decode("Failed to capture stdout".to_string()))?;
-
-        let reader = BufReader::new(stdout);
-        let mut output = String::new();
-        let mut past_prompt = false;
-
-        for line in reader.lines() {
-            let line = line.map_err(InferenceError::Io)?;
-            
-            if !past_prompt {
-                if line.contains(prompt) || output.len() < prompt.len() {
-                    output.push_str(&line);
-                    output.push('\n');
-                    if output.len() >= prompt.len() {
-                        past_prompt = true;
-                        output.clear();
+        let (output, status, _) = run_cancellable(child, cancel, |stdout| {
+            let reader = BufReader::new(stdout);
+            let mut output = String::new();
+            let mut past_prompt = false;
+
+            for line in reader.lines() {
+                let line = line.map_err(InferenceError::Io)?;
+
+                if !past_prompt {
+                    if line.contains(prompt) || output.len() < prompt.len() {
+                        output.push_str(&line);
+                        output.push('\n');
+                        if output.len() >= prompt.len() {
+                            past_prompt = true;
+                            output.clear();
+                        }
+                        continue;
                     }
-                    continue;
+                    past_prompt = true;
                 }
-                past_prompt = true;
-            }
 
-            on_token(&line);
-            on_token("\n");
-            output.push_str(&line);
-            output.push('\n');
-        }
+                on_token(&line);
+                on_token("\n");
+                output.push_str(&line);
+                output.push('\n');
+            }
+            Ok(output)
+        })?;
 
-        let status = child.wait().map_err(InferenceError::Io)?;
         if !status.success() {
             warn!("llama-cli exited with status: {}", status);
         }
@@ -237,18 +313,41 @@ impl InferenceBackend for SubprocessBackend {
         Ok(output.trim().to_string())
     }
 
-    fn chat(
-        &self,
-        system_prompt: &str,
-        user_message: &str,
-        max_tokens: u32,
-        sampler: &SamplerConfig,
-    ) -> Result<String> {
-        let prompt = format!(
-            "<|system|>\n{}</s>\n<|user|>\n{}</s>\n<|assistant|>\n",
-            system_prompt, user_message
-        );
-        self.generate(&prompt, max_tokens, sampler)
+    fn chat_template(&self) -> ChatTemplate {
+        self.chat_template
+    }
+
+    fn tokenize(&self, text: &str) -> Result<Vec<i32>> {
+        // llama-tokenize is built alongside llama-cli
+        let binary = self.binary_path.with_file_name("llama-tokenize");
+        if !binary.exists() {
+            return Err(InferenceError::InvalidConfig(format!(
+                "Tokenizing needs {}",
+                binary.display()
+            )));
+        }
+
+        let output = Command::new(&binary)
+            .arg("-m").arg(&self.model_path)
+            .arg("-p").arg(text)
+            .arg("--ids")
+            .arg("--no-bos")
+            .arg("--log-disable")
+            .stderr(Stdio::piped())
+            .output()
+            .map_err(InferenceError::Io)?;
+
+        if !output.status.success() {
+            return Err(InferenceError::Tokenization(format!(
+                "llama-tokenize failed: {}",
+                String::from_utf8_lossy(&output.stderr)
+            )));
+        }
+        parse_token_ids(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn max_parallel(&self) -> usize {
+        self.max_parallel
     }
 
     fn name(&self) -> &'static str {
@@ -264,6 +363,154 @@ impl InferenceBackend for SubprocessBackend {
     }
 }
 
+/// Read the child's stdout with `read`, killing the child as soon as
+/// `cancel` is cancelled
+///
+/// Returns what `read` produced, the exit status and the child's stderr
+/// (empty unless piped).
+fn run_cancellable<T>(
+    mut child: Child,
+    cancel: &CancellationToken,
+    read: impl FnOnce(ChildStdout) -> Result<T>,
+) -> Result<(T, ExitStatus, String)> {
+    let stdout = child.stdout.take()
+        .ok_or_else(|| InferenceError::Decode("Failed to capture stdout".to_string()))?;
+    let stderr = child.stderr.take();
+    let child = Mutex::new(child);
+    let finished = AtomicBool::new(false);
+
+    let (result, stderr) = std::thread::scope(|scope| {
+        scope.spawn(|| {
+            while !finished.load(Ordering::SeqCst) {
+                if cancel.is_cancelled() {
+                    debug!("Generation cancelled, stopping llama-cli");
+                    if let Ok(mut child) = child.lock() {
+                        let _ = child.kill();
+                    }
+                    return;
+                }
+                std::thread::sleep(CANCEL_POLL_INTERVAL);
+            }
+        });
+        let stderr_reader = stderr.map(|mut stderr| {
+            scope.spawn(move || {
+                let mut buf = String::new();
+                let _ = stderr.read_to_string(&mut buf);
+                buf
+            })
+        });
+
+        let result = read(stdout);
+        if result.is_err() {
+            // Nobody reads stdout anymore, so the child could block forever
+            if let Ok(mut child) = child.lock() {
+                let _ = child.kill();
+            }
+        }
+        finished.store(true, Ordering::SeqCst);
+
+        let stderr = stderr_reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default();
+        (result, stderr)
+    });
+
+    let mut child = child.into_inner().unwrap_or_else(|e| e.into_inner());
+    let status = child.wait().map_err(InferenceError::Io)?;
+    if cancel.is_cancelled() {
+        return Err(InferenceError::Interrupted);
+    }
+
+    Ok((result?, status, stderr))
+}
+
+/// llama-cli flags for the sampling options that are off by default
+fn sampling_args(sampler: &SamplerConfig) -> Vec<String> {
+    let mut args = vec![
+        "--min-p".to_string(),
+        sampler.min_p.to_string(),
+        "--repeat-last-n".to_string(),
+        sampler.repeat_last_n.to_string(),
+    ];
+
+    if sampler.typical_p < 1.0 {
+        args.extend(["--typical".to_string(), sampler.typical_p.to_string()]);
+    }
+    if sampler.presence_penalty != 0.0 {
+        args.extend(["--presence-penalty".to_string(), sampler.presence_penalty.to_string()]);
+    }
+    if sampler.frequency_penalty != 0.0 {
+        args.extend(["--frequency-penalty".to_string(), sampler.frequency_penalty.to_string()]);
+    }
+    if let Some((tau, eta)) = sampler.mirostat.params() {
+        args.extend([
+            "--mirostat".to_string(),
+            sampler.mirostat.version().to_string(),
+            "--mirostat-ent".to_string(),
+            tau.to_string(),
+            "--mirostat-lr".to_string(),
+            eta.to_string(),
+        ]);
+    }
+
+    args
+}
+
+/// llama-cli flags for GPU offload
+fn gpu_args(n_gpu_layers: i32, main_gpu: i32, tensor_split: &[f32]) -> Vec<String> {
+    let mut args = vec![
+        "-ngl".to_string(),
+        n_gpu_layers.to_string(),
+        "-mg".to_string(),
+        main_gpu.to_string(),
+    ];
+    if !tensor_split.is_empty() {
+        let split: Vec<String> = tensor_split.iter().map(|s| s.to_string()).collect();
+        args.extend(["-ts".to_string(), split.join(",")]);
+    }
+    args
+}
+
+/// Parse the `[1, 2, 3]` list printed by `llama-tokenize --ids`
+fn parse_token_ids(output: &str) -> Result<Vec<i32>> {
+    let list = output
+        .lines()
+        .rev()
+        .find_map(|line| line.trim().strip_prefix('[')?.strip_suffix(']'))
+        .ok_or_else(|| {
+            InferenceError::Tokenization(format!("Unexpected llama-tokenize output: {}", output))
+        })?;
+
+    list.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            id.parse()
+                .map_err(|_| InferenceError::Tokenization(format!("Invalid token id: {}", id)))
+        })
+        .collect()
+}
+
+/// Format token biases as llama-cli `--logit-bias TOKEN_ID(+/-)BIAS` values
+///
+/// llama-cli only accepts token ids, so text targets are skipped.
+fn logit_bias_args(sampler: &SamplerConfig) -> Vec<String> {
+    sampler
+        .logit_bias
+        .iter()
+        .filter_map(|(target, bias)| match target {
+            BiasTarget::Token(token) => {
+                let sign = if *bias < 0.0 { '-' } else { '+' };
+                Some(format!("{}{}{}", token, sign, bias.abs()))
+            }
+            BiasTarget::Text(text) => {
+                warn!("Subprocess backend cannot bias text {:?}, use token ids", text);
+                None
+            }
+        })
+        .collect()
+}
+
 // Add shellexpand for tilde expansion
 mod shellexpand {
     pub fn tilde(path: &str) -> std::borrow::Cow<'_, str> {
@@ -281,6 +528,7 @@ mod shellexpand {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::sampler::Mirostat;
 
     #[test]
     fn test_shellexpand_tilde() {
@@ -288,6 +536,85 @@ mod tests {
         assert!(!expanded.starts_with("~/"));
     }
 
+    #[test]
+    fn test_logit_bias_args() {
+        let sampler = SamplerConfig::default()
+            .with_logit_bias(15043, 1.5)
+            .with_logit_bias("As an AI", -5.0);
+        assert_eq!(logit_bias_args(&sampler), vec!["15043+1.5"]);
+
+        let banned = SamplerConfig::default().with_logit_bias(2, f32::NEG_INFINITY);
+        assert_eq!(logit_bias_args(&banned), vec!["2-inf"]);
+    }
+
+    #[test]
+    fn test_gpu_args() {
+        assert_eq!(gpu_args(99, 0, &[]), vec!["-ngl", "99", "-mg", "0"]);
+        assert_eq!(gpu_args(20, 1, &[3.0, 1.0])[4..], ["-ts", "3,1"]);
+    }
+
+    #[test]
+    fn test_parse_token_ids() {
+        assert_eq!(parse_token_ids("[128000, 9906, 1917]\n").unwrap(), vec![128000, 9906, 1917]);
+        assert!(parse_token_ids("[]").unwrap().is_empty());
+        assert!(parse_token_ids("error: no model").is_err());
+    }
+
+    #[test]
+    fn test_sampling_args() {
+        let defaults = sampling_args(&SamplerConfig::default());
+        assert_eq!(defaults, vec!["--min-p", "0.05", "--repeat-last-n", "64"]);
+
+        let sampler = SamplerConfig::default()
+            .with_typical_p(0.9)
+            .with_penalties(0.5, 0.0)
+            .with_mirostat(Mirostat::V2 { tau: 5.0, eta: 0.1 });
+        let args = sampling_args(&sampler).join(" ");
+        assert!(args.contains("--typical 0.9"));
+        assert!(args.contains("--presence-penalty 0.5"));
+        assert!(!args.contains("--frequency-penalty"));
+        assert!(args.contains("--mirostat 2 --mirostat-ent 5 --mirostat-lr 0.1"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_cancellable() {
+        let child = Command::new("echo").arg("hi").stdout(Stdio::piped()).spawn().unwrap();
+        let (output, status, _) = run_cancellable(child, &CancellationToken::new(), |mut stdout| {
+            let mut buf = String::new();
+            stdout.read_to_string(&mut buf).map_err(InferenceError::Io)?;
+            Ok(buf)
+        })
+        .unwrap();
+        assert_eq!(output.trim(), "hi");
+        assert!(status.success());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_run_cancellable_kills_child() {
+        let child = Command::new("sleep").arg("30").stdout(Stdio::piped()).spawn().unwrap();
+        let cancel = CancellationToken::new();
+        let canceller = {
+            let cancel = cancel.clone();
+            std::thread::spawn(move || {
+                std::thread::sleep(std::time::Duration::from_millis(100));
+                cancel.cancel();
+            })
+        };
+
+        let started = std::time::Instant::now();
+        let result = run_cancellable(child, &cancel, |mut stdout| {
+            let mut buf = Vec::new();
+            stdout.read_to_end(&mut buf).map_err(InferenceError::Io)?;
+            Ok(buf)
+        });
+        canceller.join().unwrap();
+
+        assert!(matches!(result, Err(InferenceError::Interrupted)));
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+    }
+
     #[test]
     fn test_subprocess_not_found() {
         // Should fail gracefully when binary not found
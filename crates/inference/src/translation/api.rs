@@ -0,0 +1,102 @@
+//! Translation through an external HTTP API
+
+use super::{Language, Translator};
+use crate::error::{InferenceError, Result};
+use serde::{Deserialize, Serialize};
+
+#[derive(Serialize)]
+struct TranslateRequest<'a> {
+    q: &'a str,
+    source: &'a str,
+    target: &'a str,
+    format: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    api_key: Option<&'a str>,
+}
+
+#[derive(Deserialize)]
+struct TranslateResponse {
+    #[serde(rename = "translatedText")]
+    translated_text: String,
+}
+
+/// Translates with a LibreTranslate-compatible API
+///
+/// Sends `POST <url>/translate` and reads `translatedText` from the reply.
+pub struct ApiTranslator {
+    url: String,
+    api_key: Option<String>,
+    agent: ureq::Agent,
+}
+
+impl ApiTranslator {
+    /// Use the API at `url`, e.g. `http://localhost:5000`
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into().trim_end_matches('/').to_string(),
+            api_key: None,
+            agent: ureq::Agent::new_with_config(
+                ureq::Agent::config_builder()
+                    .http_status_as_error(false)
+                    .build(),
+            ),
+        }
+    }
+
+    /// Set the API key
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+}
+
+impl Translator for ApiTranslator {
+    fn translate(&self, text: &str, from: Language) -> Result<String> {
+        self.translate_to(text, from, Language::English)
+    }
+
+    fn translate_to(&self, text: &str, from: Language, to: Language) -> Result<String> {
+        if from == to {
+            return Ok(text.to_string());
+        }
+        let target = to.code().ok_or_else(|| {
+            InferenceError::Translation("no target language to translate to".to_string())
+        })?;
+
+        let request = TranslateRequest {
+            q: text,
+            source: from.code().unwrap_or("auto"),
+            target,
+            format: "text",
+            api_key: self.api_key.as_deref(),
+        };
+        let mut response = self
+            .agent
+            .post(&format!("{}/translate", self.url))
+            .send_json(&request)
+            .map_err(|e| InferenceError::Translation(format!("request failed: {}", e)))?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.body_mut().read_to_string().unwrap_or_default();
+            return Err(InferenceError::Translation(format!(
+                "API returned {}: {}",
+                status, body
+            )));
+        }
+
+        let reply: TranslateResponse = response
+            .body_mut()
+            .read_json()
+            .map_err(|e| InferenceError::Translation(format!("invalid API response: {}", e)))?;
+        Ok(reply.translated_text.trim().to_string())
+    }
+
+    fn can_translate_to(&self, to: Language) -> bool {
+        to.code().is_some()
+    }
+
+    fn name(&self) -> &'static str {
+        "api"
+    }
+}
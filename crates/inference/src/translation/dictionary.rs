@@ -0,0 +1,630 @@
+//! Dictionary-based translation
+//!
+//! Phrase and word dictionaries for fast translation of short questions
+//! to English. This is faster and more reliable than using the model.
+
+use super::{Language, Translator};
+use crate::error::Result;
+use once_cell::sync::Lazy;
+use std::collections::HashMap;
+
+/// Phrases and words of one language
+struct Dictionary {
+    /// Sorted by length, longest first
+    phrases: Vec<(&'static str, &'static str)>,
+    words: HashMap<&'static str, &'static str>,
+}
+
+impl Dictionary {
+    fn new(
+        mut phrases: Vec<(&'static str, &'static str)>,
+        words: &[(&'static str, &'static str)],
+    ) -> Self {
+        // Sort by length descending to match longer phrases first
+        phrases.sort_by_key(|(phrase, _)| std::cmp::Reverse(phrase.len()));
+        Self {
+            phrases,
+            words: words.iter().copied().collect(),
+        }
+    }
+}
+
+/// Spanish to English
+static SPANISH: Lazy<Dictionary> = Lazy::new(|| {
+    Dictionary::new(
+        vec![
+            // Questions - longest first to avoid partial matches
+            ("cuál es la capital de", "what is the capital of"),
+            (
+                "cuál es el planeta más grande",
+                "what is the largest planet",
+            ),
+            ("quién escribió", "who wrote"),
+            ("quién pintó", "who painted"),
+            ("cuántos continentes hay", "how many continents are there"),
+            ("cuántos continentes", "how many continents"),
+            ("cuál es la", "what is the"),
+            ("cuál es el", "what is the"),
+            ("cuál es", "what is"),
+            ("qué es", "what is"),
+            ("quién es", "who is"),
+            ("cómo se llama", "what is the name of"),
+            ("dónde está", "where is"),
+            ("dónde queda", "where is"),
+            ("en qué año", "in what year"),
+            ("por qué", "why"),
+            // Common proper nouns
+            ("don quijote", "Don Quixote"),
+            ("mona lisa", "Mona Lisa"),
+            // Countries
+            ("estados unidos", "United States"),
+            ("reino unido", "United Kingdom"),
+        ],
+        &[
+            // Question words
+            ("qué", "what"),
+            ("cuál", "which"),
+            ("quién", "who"),
+            ("cómo", "how"),
+            ("dónde", "where"),
+            ("cuándo", "when"),
+            ("cuánto", "how much"),
+            ("cuántos", "how many"),
+            ("cuántas", "how many"),
+            ("por", "for"),
+            // Verbs
+            ("es", "is"),
+            ("son", "are"),
+            ("está", "is"),
+            ("están", "are"),
+            ("hay", "are there"),
+            ("tiene", "has"),
+            ("tienen", "have"),
+            ("fue", "was"),
+            ("fueron", "were"),
+            ("escribió", "wrote"),
+            ("pintó", "painted"),
+            ("descubrió", "discovered"),
+            ("inventó", "invented"),
+            ("fundó", "founded"),
+            ("nació", "was born"),
+            ("murió", "died"),
+            ("ganó", "won"),
+            // Articles
+            ("el", "the"),
+            ("la", "the"),
+            ("los", "the"),
+            ("las", "the"),
+            ("un", "a"),
+            ("una", "a"),
+            ("unos", "some"),
+            ("unas", "some"),
+            ("del", "of the"),
+            ("al", "to the"),
+            // Prepositions
+            ("de", "of"),
+            ("en", "in"),
+            ("con", "with"),
+            ("para", "for"),
+            ("sobre", "about"),
+            ("entre", "between"),
+            ("hacia", "towards"),
+            ("desde", "from"),
+            ("hasta", "until"),
+            // Adjectives
+            ("más", "most"),
+            ("grande", "large"),
+            ("pequeño", "small"),
+            ("primer", "first"),
+            ("primero", "first"),
+            ("primera", "first"),
+            ("segundo", "second"),
+            ("última", "last"),
+            ("último", "last"),
+            // Nouns
+            ("capital", "capital"),
+            ("país", "country"),
+            ("países", "countries"),
+            ("planeta", "planet"),
+            ("planetas", "planets"),
+            ("continente", "continent"),
+            ("continentes", "continents"),
+            ("mundo", "world"),
+            ("año", "year"),
+            ("años", "years"),
+            ("persona", "person"),
+            ("personas", "people"),
+            ("libro", "book"),
+            ("obra", "work"),
+            ("pintura", "painting"),
+            ("autor", "author"),
+            ("escritor", "writer"),
+            ("presidente", "president"),
+            ("rey", "king"),
+            ("reina", "queen"),
+            // Countries
+            ("francia", "France"),
+            ("españa", "Spain"),
+            ("alemania", "Germany"),
+            ("italia", "Italy"),
+            ("japón", "Japan"),
+            ("china", "China"),
+            ("brasil", "Brazil"),
+            ("méxico", "Mexico"),
+            ("argentina", "Argentina"),
+            ("chile", "Chile"),
+            ("perú", "Peru"),
+            ("colombia", "Colombia"),
+            ("rusia", "Russia"),
+            ("india", "India"),
+        ],
+    )
+});
+
+/// French to English
+static FRENCH: Lazy<Dictionary> = Lazy::new(|| {
+    Dictionary::new(
+        vec![
+            // Questions
+            ("quelle est la capitale de la", "what is the capital of"),
+            ("quelle est la capitale du", "what is the capital of"),
+            ("quelle est la capitale de", "what is the capital of"),
+            (
+                "quelle est la plus grande planète",
+                "what is the largest planet",
+            ),
+            (
+                "combien de continents y a-t-il",
+                "how many continents are there",
+            ),
+            ("qu'est-ce que", "what is"),
+            ("qui a écrit", "who wrote"),
+            ("qui a peint", "who painted"),
+            ("qui a découvert", "who discovered"),
+            ("qui a inventé", "who invented"),
+            ("quelle est la", "what is the"),
+            ("quel est le", "what is the"),
+            ("quelle est", "what is"),
+            ("quel est", "what is"),
+            ("qui est", "who is"),
+            ("où est", "where is"),
+            ("où se trouve", "where is"),
+            ("en quelle année", "in what year"),
+            ("combien de", "how many"),
+            ("y a-t-il", "are there"),
+            ("pourquoi", "why"),
+            // Common proper nouns
+            ("la joconde", "the Mona Lisa"),
+            ("les misérables", "Les Misérables"),
+            // Countries
+            ("états-unis", "United States"),
+            ("royaume-uni", "United Kingdom"),
+            // Elision
+            ("l'", "the "),
+        ],
+        &[
+            // Question words
+            ("que", "what"),
+            ("quel", "which"),
+            ("quelle", "which"),
+            ("qui", "who"),
+            ("comment", "how"),
+            ("où", "where"),
+            ("quand", "when"),
+            ("combien", "how many"),
+            // Verbs
+            ("est", "is"),
+            ("sont", "are"),
+            ("a", "has"),
+            ("ont", "have"),
+            ("était", "was"),
+            ("étaient", "were"),
+            ("écrit", "wrote"),
+            ("peint", "painted"),
+            ("découvert", "discovered"),
+            ("inventé", "invented"),
+            ("fondé", "founded"),
+            ("né", "born"),
+            ("mort", "died"),
+            ("gagné", "won"),
+            // Articles
+            ("le", "the"),
+            ("la", "the"),
+            ("les", "the"),
+            ("un", "a"),
+            ("une", "a"),
+            ("des", "some"),
+            ("du", "of the"),
+            ("au", "to the"),
+            ("aux", "to the"),
+            // Prepositions
+            ("de", "of"),
+            ("en", "in"),
+            ("dans", "in"),
+            ("avec", "with"),
+            ("pour", "for"),
+            ("sur", "on"),
+            ("entre", "between"),
+            ("depuis", "since"),
+            ("et", "and"),
+            // Adjectives
+            ("plus", "most"),
+            ("grand", "large"),
+            ("grande", "large"),
+            ("petit", "small"),
+            ("premier", "first"),
+            ("première", "first"),
+            ("dernier", "last"),
+            ("dernière", "last"),
+            // Nouns
+            ("capitale", "capital"),
+            ("pays", "country"),
+            ("planète", "planet"),
+            ("planètes", "planets"),
+            ("continent", "continent"),
+            ("continents", "continents"),
+            ("monde", "world"),
+            ("année", "year"),
+            ("ans", "years"),
+            ("personne", "person"),
+            ("personnes", "people"),
+            ("livre", "book"),
+            ("œuvre", "work"),
+            ("tableau", "painting"),
+            ("auteur", "author"),
+            ("écrivain", "writer"),
+            ("président", "president"),
+            ("roi", "king"),
+            ("reine", "queen"),
+            // Countries
+            ("france", "France"),
+            ("espagne", "Spain"),
+            ("allemagne", "Germany"),
+            ("italie", "Italy"),
+            ("japon", "Japan"),
+            ("chine", "China"),
+            ("brésil", "Brazil"),
+            ("mexique", "Mexico"),
+            ("canada", "Canada"),
+            ("russie", "Russia"),
+            ("inde", "India"),
+            ("portugal", "Portugal"),
+        ],
+    )
+});
+
+/// Portuguese to English
+static PORTUGUESE: Lazy<Dictionary> = Lazy::new(|| {
+    Dictionary::new(
+        vec![
+            // Questions
+            ("qual é a capital da", "what is the capital of"),
+            ("qual é a capital do", "what is the capital of"),
+            ("qual é a capital de", "what is the capital of"),
+            ("qual é o maior planeta", "what is the largest planet"),
+            (
+                "quantos continentes existem",
+                "how many continents are there",
+            ),
+            ("quantos continentes há", "how many continents are there"),
+            ("quem escreveu", "who wrote"),
+            ("quem pintou", "who painted"),
+            ("quem descobriu", "who discovered"),
+            ("quem inventou", "who invented"),
+            ("qual é a", "what is the"),
+            ("qual é o", "what is the"),
+            ("qual é", "what is"),
+            ("o que é", "what is"),
+            ("quem é", "who is"),
+            ("como se chama", "what is the name of"),
+            ("onde fica", "where is"),
+            ("onde está", "where is"),
+            ("em que ano", "in what year"),
+            ("por que", "why"),
+            // Common proper nouns
+            ("dom casmurro", "Dom Casmurro"),
+            ("os lusíadas", "Os Lusíadas"),
+            // Countries
+            ("estados unidos", "United States"),
+            ("reino unido", "United Kingdom"),
+        ],
+        &[
+            // Question words
+            ("que", "what"),
+            ("qual", "which"),
+            ("quem", "who"),
+            ("como", "how"),
+            ("onde", "where"),
+            ("quando", "when"),
+            ("quanto", "how much"),
+            ("quantos", "how many"),
+            ("quantas", "how many"),
+            // Verbs
+            ("é", "is"),
+            ("são", "are"),
+            ("está", "is"),
+            ("estão", "are"),
+            ("há", "there is"),
+            ("tem", "has"),
+            ("têm", "have"),
+            ("foi", "was"),
+            ("foram", "were"),
+            ("escreveu", "wrote"),
+            ("pintou", "painted"),
+            ("descobriu", "discovered"),
+            ("inventou", "invented"),
+            ("fundou", "founded"),
+            ("nasceu", "was born"),
+            ("morreu", "died"),
+            ("ganhou", "won"),
+            // Articles
+            ("o", "the"),
+            ("a", "the"),
+            ("os", "the"),
+            ("as", "the"),
+            ("um", "a"),
+            ("uma", "a"),
+            ("do", "of the"),
+            ("da", "of the"),
+            ("dos", "of the"),
+            ("das", "of the"),
+            ("no", "in the"),
+            ("na", "in the"),
+            // Prepositions
+            ("de", "of"),
+            ("em", "in"),
+            ("com", "with"),
+            ("para", "for"),
+            ("sobre", "about"),
+            ("entre", "between"),
+            ("desde", "since"),
+            ("até", "until"),
+            // Adjectives
+            ("mais", "most"),
+            ("maior", "largest"),
+            ("grande", "large"),
+            ("pequeno", "small"),
+            ("primeiro", "first"),
+            ("primeira", "first"),
+            ("último", "last"),
+            ("última", "last"),
+            // Nouns
+            ("capital", "capital"),
+            ("país", "country"),
+            ("países", "countries"),
+            ("planeta", "planet"),
+            ("planetas", "planets"),
+            ("continente", "continent"),
+            ("continentes", "continents"),
+            ("mundo", "world"),
+            ("ano", "year"),
+            ("anos", "years"),
+            ("pessoa", "person"),
+            ("pessoas", "people"),
+            ("livro", "book"),
+            ("obra", "work"),
+            ("pintura", "painting"),
+            ("autor", "author"),
+            ("escritor", "writer"),
+            ("presidente", "president"),
+            ("rei", "king"),
+            ("rainha", "queen"),
+            // Countries
+            ("frança", "France"),
+            ("espanha", "Spain"),
+            ("alemanha", "Germany"),
+            ("itália", "Italy"),
+            ("japão", "Japan"),
+            ("china", "China"),
+            ("brasil", "Brazil"),
+            ("méxico", "Mexico"),
+            ("argentina", "Argentina"),
+            ("portugal", "Portugal"),
+            ("rússia", "Russia"),
+            ("índia", "India"),
+        ],
+    )
+});
+
+/// German to English
+static GERMAN: Lazy<Dictionary> = Lazy::new(|| {
+    Dictionary::new(
+        vec![
+            // Questions
+            ("was ist die hauptstadt von", "what is the capital of"),
+            ("was ist der größte planet", "what is the largest planet"),
+            (
+                "wie viele kontinente gibt es",
+                "how many continents are there",
+            ),
+            ("wer schrieb", "who wrote"),
+            ("wer hat geschrieben", "who wrote"),
+            ("wer malte", "who painted"),
+            ("wer entdeckte", "who discovered"),
+            ("wer erfand", "who invented"),
+            ("was ist die", "what is the"),
+            ("was ist der", "what is the"),
+            ("was ist das", "what is the"),
+            ("was ist", "what is"),
+            ("wer ist", "who is"),
+            ("wie heißt", "what is the name of"),
+            ("wo liegt", "where is"),
+            ("wo ist", "where is"),
+            ("in welchem jahr", "in what year"),
+            ("wie viele", "how many"),
+            ("gibt es", "are there"),
+            // Countries
+            ("vereinigte staaten", "United States"),
+            ("vereinigtes königreich", "United Kingdom"),
+        ],
+        &[
+            // Question words
+            ("was", "what"),
+            ("welche", "which"),
+            ("welcher", "which"),
+            ("welches", "which"),
+            ("wer", "who"),
+            ("wie", "how"),
+            ("wo", "where"),
+            ("wann", "when"),
+            ("warum", "why"),
+            // Verbs
+            ("ist", "is"),
+            ("sind", "are"),
+            ("hat", "has"),
+            ("haben", "have"),
+            ("war", "was"),
+            ("waren", "were"),
+            ("schrieb", "wrote"),
+            ("malte", "painted"),
+            ("entdeckte", "discovered"),
+            ("erfand", "invented"),
+            ("gründete", "founded"),
+            ("geboren", "born"),
+            ("starb", "died"),
+            ("gewann", "won"),
+            // Articles
+            ("der", "the"),
+            ("die", "the"),
+            ("das", "the"),
+            ("den", "the"),
+            ("dem", "the"),
+            ("ein", "a"),
+            ("eine", "a"),
+            ("einen", "a"),
+            // Prepositions
+            ("von", "of"),
+            ("in", "in"),
+            ("im", "in the"),
+            ("mit", "with"),
+            ("für", "for"),
+            ("über", "about"),
+            ("zwischen", "between"),
+            ("seit", "since"),
+            ("bis", "until"),
+            ("und", "and"),
+            // Adjectives
+            ("größte", "largest"),
+            ("groß", "large"),
+            ("klein", "small"),
+            ("erste", "first"),
+            ("erster", "first"),
+            ("letzte", "last"),
+            ("letzter", "last"),
+            // Nouns
+            ("hauptstadt", "capital"),
+            ("land", "country"),
+            ("länder", "countries"),
+            ("planet", "planet"),
+            ("planeten", "planets"),
+            ("kontinent", "continent"),
+            ("kontinente", "continents"),
+            ("welt", "world"),
+            ("jahr", "year"),
+            ("jahre", "years"),
+            ("person", "person"),
+            ("menschen", "people"),
+            ("buch", "book"),
+            ("werk", "work"),
+            ("gemälde", "painting"),
+            ("autor", "author"),
+            ("schriftsteller", "writer"),
+            ("präsident", "president"),
+            ("könig", "king"),
+            ("königin", "queen"),
+            // Countries
+            ("deutschland", "Germany"),
+            ("frankreich", "France"),
+            ("spanien", "Spain"),
+            ("italien", "Italy"),
+            ("japan", "Japan"),
+            ("china", "China"),
+            ("brasilien", "Brazil"),
+            ("mexiko", "Mexico"),
+            ("österreich", "Austria"),
+            ("schweiz", "Switzerland"),
+            ("russland", "Russia"),
+            ("indien", "India"),
+        ],
+    )
+});
+
+/// Translates with built-in phrase and word dictionaries
+///
+/// Covers Spanish, French, Portuguese and German. Words it does not know
+/// are kept as they are, so it works best on short factual questions.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DictionaryTranslator;
+
+impl DictionaryTranslator {
+    /// Create a dictionary translator
+    pub fn new() -> Self {
+        Self
+    }
+
+    fn dictionary(language: Language) -> Option<&'static Dictionary> {
+        match language {
+            Language::Spanish => Some(&SPANISH),
+            Language::French => Some(&FRENCH),
+            Language::Portuguese => Some(&PORTUGUESE),
+            Language::German => Some(&GERMAN),
+            Language::English | Language::Other => None,
+        }
+    }
+}
+
+impl Translator for DictionaryTranslator {
+    fn translate(&self, text: &str, from: Language) -> Result<String> {
+        let Some(dictionary) = Self::dictionary(from) else {
+            return Ok(text.to_string());
+        };
+
+        // Remove Spanish punctuation marks
+        let clean = text.replace(['¿', '¡'], "");
+        let mut result = clean.to_lowercase();
+
+        // First, apply phrase translations (longest first)
+        for (phrase, english) in &dictionary.phrases {
+            result = result.replace(phrase, english);
+        }
+
+        // Then, translate remaining words
+        let translated = result
+            .split_whitespace()
+            .map(|word| {
+                // Remove punctuation for lookup but preserve it for output
+                let clean_word = word.trim_matches(|c: char| !c.is_alphanumeric());
+                match dictionary.words.get(clean_word) {
+                    Some(english) => {
+                        let start = word.find(clean_word).unwrap_or(0);
+                        let end = start + clean_word.len();
+                        format!("{}{}{}", &word[..start], english, &word[end..])
+                    }
+                    // Keep original (might be proper noun or already English)
+                    None => word.to_string(),
+                }
+            })
+            .collect::<Vec<_>>()
+            .join(" ")
+            // French puts a space before question marks
+            .replace(" ?", "?");
+
+        // Capitalize first letter
+        let mut chars = translated.chars();
+        let mut final_text: String = match chars.next() {
+            Some(first) => first.to_uppercase().chain(chars).collect(),
+            None => String::new(),
+        };
+
+        // Add question mark if original had one
+        if text.contains('?') && !final_text.ends_with('?') {
+            final_text.push('?');
+        }
+
+        Ok(final_text)
+    }
+
+    fn name(&self) -> &'static str {
+        "dictionary"
+    }
+}
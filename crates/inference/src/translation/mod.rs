@@ -0,0 +1,546 @@
+//! Translation of non-English queries to English
+//!
+//! Queries are answered in English, which small models handle best. A
+//! [`Translator`] turns a query into English first:
+//!
+//! - [`DictionaryTranslator`] - phrase and word dictionaries (ES, FR, PT,
+//!   DE); fast and needs nothing loaded
+//! - [`ModelTranslator`] - asks the loaded model to translate
+//! - [`ApiTranslator`] - calls a LibreTranslate-compatible HTTP API
+//!   (`translate-api` feature)
+
+use crate::error::{InferenceError, Result};
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "translate-api")]
+mod api;
+mod dictionary;
+mod model;
+
+#[cfg(feature = "translate-api")]
+pub use api::ApiTranslator;
+pub use dictionary::DictionaryTranslator;
+pub use model::ModelTranslator;
+
+/// Supported languages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Language {
+    English,
+    Spanish,
+    French,
+    Portuguese,
+    German,
+    Other,
+}
+
+impl Language {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Language::English => "English",
+            Language::Spanish => "Spanish",
+            Language::French => "French",
+            Language::Portuguese => "Portuguese",
+            Language::German => "German",
+            Language::Other => "Other",
+        }
+    }
+
+    /// ISO 639-1 code, if the language is known
+    pub fn code(&self) -> Option<&'static str> {
+        match self {
+            Language::English => Some("en"),
+            Language::Spanish => Some("es"),
+            Language::French => Some("fr"),
+            Language::Portuguese => Some("pt"),
+            Language::German => Some("de"),
+            Language::Other => None,
+        }
+    }
+}
+
+/// Translates text to English
+pub trait Translator: Send + Sync {
+    /// Translate `text`, written in `from`, to English
+    fn translate(&self, text: &str, from: Language) -> Result<String>;
+
+    /// Translate `text` from `from` to `to`
+    ///
+    /// By default only English is supported as a target.
+    fn translate_to(&self, text: &str, from: Language, to: Language) -> Result<String> {
+        if to == Language::English {
+            return self.translate(text, from);
+        }
+        Err(InferenceError::Translation(format!(
+            "the {} translator cannot translate to {}",
+            self.name(),
+            to.name()
+        )))
+    }
+
+    /// Whether [`Self::translate_to`] can translate into `to`
+    fn can_translate_to(&self, to: Language) -> bool {
+        to == Language::English
+    }
+
+    /// Translator name for logging
+    fn name(&self) -> &'static str;
+}
+
+/// Which [`Translator`] to use
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TranslatorKind {
+    /// Built-in dictionaries
+    #[default]
+    Dictionary,
+    /// The loaded model
+    Model,
+    /// An external translation API
+    Api,
+}
+
+impl std::fmt::Display for TranslatorKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TranslatorKind::Dictionary => write!(f, "dictionary"),
+            TranslatorKind::Model => write!(f, "model"),
+            TranslatorKind::Api => write!(f, "api"),
+        }
+    }
+}
+
+impl std::str::FromStr for TranslatorKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "dictionary" | "dict" => Ok(TranslatorKind::Dictionary),
+            "model" | "llm" => Ok(TranslatorKind::Model),
+            "api" | "libretranslate" => Ok(TranslatorKind::Api),
+            _ => Err(format!("Unknown translator: {}", s)),
+        }
+    }
+}
+
+/// Characters and words that point to one language
+struct LanguageMarkers {
+    language: Language,
+    /// Characters only this language uses
+    chars: &'static [char],
+    /// Common words, rare in English and the other languages
+    words: &'static [&'static str],
+}
+
+/// Markers per language; on a tie the earlier language wins
+const MARKERS: &[LanguageMarkers] = &[
+    LanguageMarkers {
+        language: Language::Spanish,
+        chars: &['¿', '¡', 'ñ'],
+        words: &[
+            "qué", "cuál", "cómo", "dónde", "quién", "cuánto", "que", "cual", "como", "donde",
+            "quien", "cuanto", "es", "son", "está", "están", "hay", "tiene", "del", "las", "los",
+            "una", "uno",
+        ],
+    },
+    LanguageMarkers {
+        language: Language::Portuguese,
+        chars: &['ã', 'õ'],
+        words: &[
+            "qual", "quem", "onde", "quando", "quantos", "quantas", "é", "são", "foi", "não",
+            "você", "um", "uma", "da", "dos", "das", "em", "escreveu", "pintou", "existem",
+        ],
+    },
+    LanguageMarkers {
+        language: Language::French,
+        chars: &['è', 'ù', 'œ', 'ë', 'î'],
+        words: &[
+            "quelle",
+            "quel",
+            "quels",
+            "quelles",
+            "qui",
+            "est",
+            "sont",
+            "où",
+            "comment",
+            "pourquoi",
+            "combien",
+            "le",
+            "les",
+            "des",
+            "du",
+            "une",
+            "et",
+            "dans",
+            "avec",
+            "qu'est-ce",
+            "écrit",
+            "peint",
+        ],
+    },
+    LanguageMarkers {
+        language: Language::German,
+        chars: &['ä', 'ö', 'ü', 'ß'],
+        words: &[
+            "ist",
+            "sind",
+            "wer",
+            "wie",
+            "wo",
+            "warum",
+            "welche",
+            "welcher",
+            "welches",
+            "wann",
+            "der",
+            "das",
+            "ein",
+            "eine",
+            "und",
+            "nicht",
+            "mit",
+            "hat",
+            "gibt",
+            "viele",
+            "hauptstadt",
+            "schrieb",
+            "malte",
+        ],
+    },
+];
+
+/// Accents shared by several languages; alone they default to Spanish
+const SHARED_ACCENTS: &[char] = &['á', 'é', 'í', 'ó', 'ú'];
+
+/// Detector confidence needed to trust it without supporting markers
+pub const MIN_CONFIDENCE: f64 = 0.5;
+
+/// Confidence given to a language backed by markers but not the detector
+const MARKER_CONFIDENCE: f64 = 0.5;
+
+/// Languages we translate from, plus English
+const SUPPORTED: &[whatlang::Lang] = &[
+    whatlang::Lang::Eng,
+    whatlang::Lang::Spa,
+    whatlang::Lang::Fra,
+    whatlang::Lang::Por,
+    whatlang::Lang::Deu,
+];
+
+/// A detected language and how sure the detection is
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct LanguageDetection {
+    pub language: Language,
+    /// From 0.0 to 1.0
+    pub confidence: f64,
+}
+
+impl LanguageDetection {
+    fn new(language: Language, confidence: f64) -> Self {
+        Self {
+            language,
+            confidence,
+        }
+    }
+}
+
+fn from_whatlang(lang: whatlang::Lang) -> Language {
+    match lang {
+        whatlang::Lang::Eng => Language::English,
+        whatlang::Lang::Spa => Language::Spanish,
+        whatlang::Lang::Fra => Language::French,
+        whatlang::Lang::Por => Language::Portuguese,
+        whatlang::Lang::Deu => Language::German,
+        _ => Language::Other,
+    }
+}
+
+/// Language the marker tables point to, and whether a marker character
+/// (strong evidence) was found
+fn detect_markers(text: &str) -> Option<(Language, bool)> {
+    let lower = text.to_lowercase();
+    let words: Vec<&str> = lower
+        .split_whitespace()
+        .map(|w| w.trim_matches(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-'))
+        .collect();
+
+    let mut best: Option<(Language, bool)> = None;
+    let mut best_score = 0;
+    for markers in MARKERS {
+        let char_hits = markers.chars.iter().filter(|c| lower.contains(**c)).count();
+        let word_hits = words.iter().filter(|w| markers.words.contains(w)).count();
+
+        let detected = char_hits > 0 || word_hits >= 2 || (words.len() <= 5 && word_hits >= 1);
+        let score = word_hits + 2 * char_hits;
+        if detected && score > best_score {
+            best = Some((markers.language, char_hits > 0));
+            best_score = score;
+        }
+    }
+
+    if best.is_none() && lower.contains(SHARED_ACCENTS) {
+        return Some((Language::Spanish, false));
+    }
+    best
+}
+
+/// Detect the language of `text` with a confidence score
+///
+/// A statistical detector (whatlang) picks among the supported languages.
+/// Short texts rarely give it a reliable answer, so it is then checked
+/// against common words and characters of each language; text nothing
+/// points to confidently is taken as English. Text reliably detected as an
+/// unsupported language is [`Language::Other`].
+pub fn detect_language_with_confidence(text: &str) -> LanguageDetection {
+    if let Some(info) = whatlang::detect(text) {
+        let language = from_whatlang(info.lang());
+        if info.is_reliable() && language == Language::Other {
+            return LanguageDetection::new(language, info.confidence());
+        }
+    }
+
+    let markers = detect_markers(text);
+    let Some(info) = whatlang::Detector::with_allowlist(SUPPORTED.to_vec()).detect(text) else {
+        return match markers {
+            Some((language, true)) => LanguageDetection::new(language, MARKER_CONFIDENCE),
+            _ => LanguageDetection::new(Language::English, 0.0),
+        };
+    };
+
+    let language = from_whatlang(info.lang());
+    let confidence = info.confidence();
+    match markers {
+        _ if info.is_reliable() => LanguageDetection::new(language, confidence),
+        Some((marked, _)) if marked == language => {
+            LanguageDetection::new(language, confidence.max(MARKER_CONFIDENCE))
+        }
+        Some((marked, true)) => LanguageDetection::new(marked, MARKER_CONFIDENCE),
+        _ if confidence >= MIN_CONFIDENCE => LanguageDetection::new(language, confidence),
+        _ if language == Language::English => LanguageDetection::new(language, confidence),
+        _ => LanguageDetection::new(Language::English, 1.0 - confidence),
+    }
+}
+
+/// Detect the language of `text`
+///
+/// See [`detect_language_with_confidence`].
+pub fn detect_language(text: &str) -> Language {
+    detect_language_with_confidence(text).language
+}
+
+/// Make sure `answer` is written in `language`
+///
+/// Models often reply in English whatever they were asked in. An answer
+/// detected in another language is translated with `translator`. Returns
+/// the answer and whether it was translated.
+pub fn ensure_language(
+    answer: &str,
+    language: Language,
+    translator: &dyn Translator,
+) -> Result<(String, bool)> {
+    if matches!(language, Language::English | Language::Other) {
+        return Ok((answer.to_string(), false));
+    }
+
+    let detected = detect_language(answer);
+    if detected == language {
+        return Ok((answer.to_string(), false));
+    }
+    let translation = translator.translate_to(answer, detected, language)?;
+    Ok((translation, true))
+}
+
+/// Translate text to English using the dictionaries
+///
+/// Text not detected as a supported language is treated as Spanish.
+pub fn translate_to_english(text: &str) -> String {
+    let language = match detect_language(text) {
+        Language::English | Language::Other => Language::Spanish,
+        language => language,
+    };
+    DictionaryTranslator::new()
+        .translate(text, language)
+        .unwrap_or_else(|_| text.to_string())
+}
+
+/// Build a translation - now uses dictionary instead of model
+pub fn build_translation_prompt(text: &str) -> String {
+    // For backward compatibility, but we now translate directly
+    translate_to_english(text)
+}
+
+/// Build a prompt that asks for response in specific language
+pub fn build_multilingual_prompt(question: &str, response_language: Language) -> String {
+    match response_language {
+        Language::Spanish => format!("{}\nResponde brevemente en español.", question),
+        Language::French => format!("{}\nRéponds brièvement en français.", question),
+        Language::Portuguese => format!("{}\nResponda brevemente em português.", question),
+        Language::German => format!("{}\nAntworte kurz auf Deutsch.", question),
+        Language::English => question.to_string(),
+        Language::Other => question.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_spanish() {
+        assert_eq!(
+            detect_language("¿Cuál es la capital de Francia?"),
+            Language::Spanish
+        );
+        assert_eq!(
+            detect_language("¿Cuántos continentes hay?"),
+            Language::Spanish
+        );
+        assert_eq!(
+            detect_language("Quién pintó la Mona Lisa"),
+            Language::Spanish
+        );
+    }
+
+    #[test]
+    fn test_detect_english() {
+        assert_eq!(
+            detect_language("What is the capital of France?"),
+            Language::English
+        );
+        assert_eq!(
+            detect_language("How many continents are there?"),
+            Language::English
+        );
+    }
+
+    #[test]
+    fn test_detect_other_languages() {
+        assert_eq!(
+            detect_language("Quelle est la capitale de la France ?"),
+            Language::French
+        );
+        assert_eq!(detect_language("Où est la tour Eiffel ?"), Language::French);
+        assert_eq!(
+            detect_language("Qual é a capital da França?"),
+            Language::Portuguese
+        );
+        assert_eq!(
+            detect_language("Quem escreveu Dom Casmurro?"),
+            Language::Portuguese
+        );
+        assert_eq!(
+            detect_language("Was ist die Hauptstadt von Deutschland?"),
+            Language::German
+        );
+        assert_eq!(
+            detect_language("Wie viele Kontinente gibt es?"),
+            Language::German
+        );
+    }
+
+    #[test]
+    fn test_detect_short_english() {
+        for text in [
+            "La Bamba by Ritchie Valens",
+            "Es Devlin stage design",
+            "La La Land soundtrack",
+        ] {
+            assert_eq!(detect_language(text), Language::English, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_detect_with_confidence() {
+        let detection = detect_language_with_confidence("What is the capital of France?");
+        assert_eq!(detection.language, Language::English);
+        assert!(detection.confidence >= MIN_CONFIDENCE);
+
+        let detection = detect_language_with_confidence("¿Cuántos continentes hay?");
+        assert_eq!(detection.language, Language::Spanish);
+        assert!(detection.confidence >= MARKER_CONFIDENCE);
+
+        let detection = detect_language_with_confidence(
+            "Questa è una frase scritta in italiano per verificare il rilevamento della lingua.",
+        );
+        assert_eq!(detection.language, Language::Other);
+    }
+
+    #[test]
+    fn test_translation() {
+        assert_eq!(
+            translate_to_english("¿Cuál es la capital de Francia?"),
+            "What is the capital of France?"
+        );
+        assert_eq!(
+            translate_to_english("¿Quién escribió Don Quijote?"),
+            "Who wrote Don Quixote?"
+        );
+        assert_eq!(
+            translate_to_english("¿Quién pintó la Mona Lisa?"),
+            "Who painted the Mona Lisa?"
+        );
+    }
+
+    #[test]
+    fn test_dictionary_translator() {
+        let translator = DictionaryTranslator::new();
+        let translate = |text, from| translator.translate(text, from).unwrap();
+
+        assert_eq!(
+            translate("Quelle est la capitale de la France ?", Language::French),
+            "What is the capital of France?"
+        );
+        assert_eq!(
+            translate("Qui a peint la Joconde ?", Language::French),
+            "Who painted the Mona Lisa?"
+        );
+        assert_eq!(
+            translate("Qual é a capital da França?", Language::Portuguese),
+            "What is the capital of France?"
+        );
+        assert_eq!(
+            translate("Quantos continentes existem?", Language::Portuguese),
+            "How many continents are there?"
+        );
+        assert_eq!(
+            translate("Was ist die Hauptstadt von Deutschland?", Language::German),
+            "What is the capital of Germany?"
+        );
+        assert_eq!(
+            translate("Wie viele Kontinente gibt es?", Language::German),
+            "How many continents are there?"
+        );
+        // English and unknown text pass through
+        assert_eq!(translate("Hello there", Language::English), "Hello there");
+    }
+
+    #[test]
+    fn test_ensure_language() {
+        let dictionary = DictionaryTranslator::new();
+        let answer = "La capital de Francia es París, una de las ciudades más visitadas.";
+        assert_eq!(
+            ensure_language(answer, Language::Spanish, &dictionary).unwrap(),
+            (answer.to_string(), false)
+        );
+        assert_eq!(
+            ensure_language("Paris.", Language::English, &dictionary).unwrap(),
+            ("Paris.".to_string(), false)
+        );
+        // The dictionaries only translate to English
+        assert!(!dictionary.can_translate_to(Language::Spanish));
+        assert!(ensure_language(
+            "The capital of France is Paris.",
+            Language::Spanish,
+            &dictionary
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn test_translator_kind() {
+        assert_eq!("dictionary".parse(), Ok(TranslatorKind::Dictionary));
+        assert_eq!("LLM".parse(), Ok(TranslatorKind::Model));
+        assert_eq!("api".parse(), Ok(TranslatorKind::Api));
+        assert!("google".parse::<TranslatorKind>().is_err());
+        assert_eq!(TranslatorKind::Api.to_string(), "api");
+    }
+}
@@ -0,0 +1,104 @@
+//! Translation with the loaded model
+
+use super::{Language, Translator};
+use crate::cancel::CancellationToken;
+use crate::error::{InferenceError, Result};
+use crate::model::{GenerateOptions, InferenceModel};
+
+/// Tokens allowed for a translated line, at least
+const MAX_TRANSLATION_TOKENS: u32 = 100;
+
+/// Rough bytes per token, used when the backend has no tokenizer
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Translates by prompting the model
+///
+/// Handles any language the model knows, at the cost of a generation per
+/// query.
+#[derive(Clone)]
+pub struct ModelTranslator {
+    model: InferenceModel,
+    options: GenerateOptions,
+}
+
+impl ModelTranslator {
+    /// Translate with `model`, greedy-ish and on a single line
+    pub fn new(model: InferenceModel) -> Self {
+        Self {
+            model,
+            options: GenerateOptions::new(MAX_TRANSLATION_TOKENS)
+                .with_temperature(0.1) // Low temp for accurate translation
+                .with_stop_sequence("\n"),
+        }
+    }
+
+    /// Cancel translations through `token`
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.options = self.options.with_cancellation(token);
+        self
+    }
+
+    fn prompt(text: &str, from: Language, to: Language) -> String {
+        let source = match from {
+            Language::Other => "the",
+            language => language.name(),
+        };
+        format!(
+            "Translate {} text below to {}. Reply with the translation only.\n\nText: {}\n{}:",
+            source,
+            to.name(),
+            text,
+            to.name()
+        )
+    }
+
+    /// Translate one line, leaving room for it to grow
+    fn translate_line(&self, line: &str, from: Language, to: Language) -> Result<String> {
+        let tokens = self
+            .model
+            .count_tokens(line)
+            .unwrap_or(line.len() / BYTES_PER_TOKEN) as u32;
+        let mut options = self.options.clone();
+        options.max_tokens = options.max_tokens.max(tokens * 2);
+
+        let translation = self
+            .model
+            .generate(&Self::prompt(line, from, to), &options)?;
+        Ok(translation.trim().to_string())
+    }
+}
+
+impl Translator for ModelTranslator {
+    fn translate(&self, text: &str, from: Language) -> Result<String> {
+        self.translate_to(text, from, Language::English)
+    }
+
+    /// Translates line by line, since a translation stops at a newline
+    fn translate_to(&self, text: &str, from: Language, to: Language) -> Result<String> {
+        if from == to {
+            return Ok(text.to_string());
+        }
+        if !self.can_translate_to(to) {
+            return Err(InferenceError::Translation(
+                "no target language to translate to".to_string(),
+            ));
+        }
+
+        let lines = text
+            .lines()
+            .map(|line| match line.trim() {
+                "" => Ok(String::new()),
+                line => self.translate_line(line, from, to),
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(lines.join("\n").trim().to_string())
+    }
+
+    fn can_translate_to(&self, to: Language) -> bool {
+        to != Language::Other
+    }
+
+    fn name(&self) -> &'static str {
+        "model"
+    }
+}
@@ -0,0 +1,241 @@
+//! Response caching for repeated chat requests.
+//!
+//! Batch evaluations often ask the same question many times. With a cache
+//! configured, a chat completion whose request body (model, messages,
+//! sampling parameters and tools) matches a recent one is answered from
+//! memory instead of the server.
+
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+use crate::types::ChatResponse;
+
+/// Limits for the response cache.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ResponseCacheConfig {
+    /// Most responses kept; the least recently used is evicted first
+    pub max_entries: usize,
+    /// How long a response stays valid; `None` keeps it until evicted
+    pub ttl: Option<Duration>,
+}
+
+impl Default for ResponseCacheConfig {
+    fn default() -> Self {
+        Self {
+            max_entries: 256,
+            ttl: Some(Duration::from_secs(3600)),
+        }
+    }
+}
+
+impl ResponseCacheConfig {
+    /// Create the default limits: 256 responses for an hour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the most responses kept.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = max_entries;
+        self
+    }
+
+    /// Set how long a response stays valid.
+    pub fn with_ttl(mut self, ttl: Option<Duration>) -> Self {
+        self.ttl = ttl;
+        self
+    }
+}
+
+/// Response cache counters.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ResponseCacheStats {
+    /// Requests answered from the cache
+    pub hits: u64,
+    /// Requests sent to the server
+    pub misses: u64,
+    /// Responses dropped because they expired
+    pub expired: u64,
+    /// Responses dropped to make room
+    pub evicted: u64,
+    /// Responses currently cached
+    pub entries: usize,
+}
+
+struct Entry {
+    hash: u64,
+    key: String,
+    response: ChatResponse,
+    inserted: Instant,
+    last_used: u64,
+}
+
+#[derive(Default)]
+struct Inner {
+    entries: Vec<Entry>,
+    clock: u64,
+    stats: ResponseCacheStats,
+}
+
+/// Chat responses keyed by their request body.
+pub(crate) struct ResponseCache {
+    config: ResponseCacheConfig,
+    inner: Mutex<Inner>,
+}
+
+impl std::fmt::Debug for ResponseCache {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseCache")
+            .field("config", &self.config)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl ResponseCache {
+    pub(crate) fn new(config: ResponseCacheConfig) -> Self {
+        Self {
+            config,
+            inner: Mutex::new(Inner::default()),
+        }
+    }
+
+    /// The cached response for the request body `key`, if still valid.
+    pub(crate) fn get(&self, key: &str) -> Option<ChatResponse> {
+        let hash = hash_key(key);
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        let position = inner
+            .entries
+            .iter()
+            .position(|e| e.hash == hash && e.key == key);
+        let found = match position {
+            Some(i) if self.is_expired(&inner.entries[i]) => {
+                inner.entries.swap_remove(i);
+                inner.stats.expired += 1;
+                None
+            }
+            Some(i) => {
+                let entry = &mut inner.entries[i];
+                entry.last_used = clock;
+                Some(entry.response.clone())
+            }
+            None => None,
+        };
+
+        match found {
+            Some(_) => inner.stats.hits += 1,
+            None => inner.stats.misses += 1,
+        }
+        found
+    }
+
+    /// Cache `response` for the request body `key`.
+    pub(crate) fn insert(&self, key: String, response: ChatResponse) {
+        if self.config.max_entries == 0 {
+            return;
+        }
+        let hash = hash_key(&key);
+        let mut inner = self.lock();
+        inner.clock += 1;
+        let clock = inner.clock;
+
+        inner.entries.retain(|e| !(e.hash == hash && e.key == key));
+        let before = inner.entries.len();
+        inner.entries.retain(|e| !self.is_expired(e));
+        inner.stats.expired += (before - inner.entries.len()) as u64;
+
+        while inner.entries.len() >= self.config.max_entries {
+            let oldest = inner
+                .entries
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, e)| e.last_used)
+                .map(|(i, _)| i);
+            if let Some(i) = oldest {
+                inner.entries.swap_remove(i);
+                inner.stats.evicted += 1;
+            }
+        }
+
+        inner.entries.push(Entry {
+            hash,
+            key,
+            response,
+            inserted: Instant::now(),
+            last_used: clock,
+        });
+    }
+
+    pub(crate) fn stats(&self) -> ResponseCacheStats {
+        let inner = self.lock();
+        ResponseCacheStats {
+            entries: inner.entries.len(),
+            ..inner.stats
+        }
+    }
+
+    /// Drop every cached response, keeping the counters.
+    pub(crate) fn clear(&self) {
+        self.lock().entries.clear();
+    }
+
+    fn is_expired(&self, entry: &Entry) -> bool {
+        self.config
+            .ttl
+            .is_some_and(|ttl| entry.inserted.elapsed() >= ttl)
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+fn hash_key(key: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Message;
+
+    fn response(content: &str) -> ChatResponse {
+        ChatResponse::from_message(Message::assistant(content))
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used() {
+        let cache = ResponseCache::new(ResponseCacheConfig::new().with_max_entries(2));
+        cache.insert("a".to_string(), response("1"));
+        cache.insert("b".to_string(), response("2"));
+        assert_eq!(cache.get("a").unwrap().content(), Some("1"));
+
+        cache.insert("c".to_string(), response("3"));
+        assert!(cache.get("b").is_none());
+        assert!(cache.get("a").is_some());
+
+        let stats = cache.stats();
+        assert_eq!(stats.hits, 2);
+        assert_eq!(stats.misses, 1);
+        assert_eq!(stats.evicted, 1);
+        assert_eq!(stats.entries, 2);
+    }
+
+    #[test]
+    fn test_cache_expires_entries() {
+        let cache = ResponseCache::new(ResponseCacheConfig::new().with_ttl(Some(Duration::ZERO)));
+        cache.insert("a".to_string(), response("1"));
+        assert!(cache.get("a").is_none());
+        assert_eq!(cache.stats().expired, 1);
+        assert_eq!(cache.stats().entries, 0);
+    }
+}
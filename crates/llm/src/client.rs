@@ -1,22 +1,39 @@
 //! LLM client implementation.
 
-use std::time::Duration;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use futures::{Stream, StreamExt};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
 use reqwest::Client;
+use serde::de::DeserializeOwned;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
 use tracing::{debug, info, warn};
 
+use crate::cache::{ResponseCache, ResponseCacheConfig, ResponseCacheStats};
 use crate::error::{LlmError, Result};
+use crate::provider::{LlmProvider, ProviderKind, StreamEvent, StreamFormat};
+use crate::retry::RetryPolicy;
+use crate::structured::{extract_json, with_schema_prompt, ResponseFormat};
+use crate::tools::{Tool, ToolChoice};
 use crate::types::{
-    ChatRequest, ChatResponse, GenerateRequest, GenerateResponse, Message,
+    ChatRequest, ChatResponse, GenerateRequest, GenerateResponse, Message, ModelInfo, Usage,
 };
+use crate::usage::{UsageReport, UsageTracker};
 
 /// Default timeout for requests in seconds.
 const DEFAULT_TIMEOUT_SECS: u64 = 120;
 
+/// Tokens of a streamed chat completion, as they arrive.
+pub type ChatStream = Pin<Box<dyn Stream<Item = Result<String>> + Send>>;
+
 /// Configuration for the LLM client.
-#[derive(Debug, Clone)]
+#[derive(Clone)]
 pub struct LlmConfig {
     /// Base URL of the LLM server
     pub base_url: String,
+    /// API the server speaks
+    pub provider: ProviderKind,
     /// Model name to use
     pub model: String,
     /// Request timeout in seconds
@@ -25,16 +42,56 @@ pub struct LlmConfig {
     pub max_tokens: u32,
     /// Default temperature
     pub temperature: f32,
+    /// Retries for failed requests
+    pub retry: RetryPolicy,
+    /// API key, sent as `Authorization: Bearer <key>`
+    pub api_key: Option<String>,
+    /// Headers added to every request
+    pub headers: Vec<(String, String)>,
+    /// Cache for non-streamed chat completions; off when `None`
+    pub cache: Option<ResponseCacheConfig>,
+    /// Most requests in flight at once; unlimited when `None`
+    ///
+    /// Further requests wait in line, first come first served. A stream
+    /// counts until it ends or is dropped.
+    pub max_concurrent_requests: Option<usize>,
+}
+
+impl std::fmt::Debug for LlmConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LlmConfig")
+            .field("base_url", &self.base_url)
+            .field("provider", &self.provider)
+            .field("model", &self.model)
+            .field("timeout_secs", &self.timeout_secs)
+            .field("max_tokens", &self.max_tokens)
+            .field("temperature", &self.temperature)
+            .field("retry", &self.retry)
+            .field("api_key", &self.api_key.as_ref().map(|_| "<redacted>"))
+            .field(
+                "headers",
+                &self.headers.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .field("cache", &self.cache)
+            .field("max_concurrent_requests", &self.max_concurrent_requests)
+            .finish()
+    }
 }
 
 impl Default for LlmConfig {
     fn default() -> Self {
         Self {
             base_url: "http://localhost:11435".to_string(),
+            provider: ProviderKind::default(),
             model: "bitnet".to_string(),
             timeout_secs: DEFAULT_TIMEOUT_SECS,
             max_tokens: 512,
             temperature: 0.7,
+            retry: RetryPolicy::default(),
+            api_key: None,
+            headers: Vec::new(),
+            cache: None,
+            max_concurrent_requests: None,
         }
     }
 }
@@ -47,15 +104,88 @@ impl LlmConfig {
             ..Default::default()
         }
     }
+
+    /// Set the API the server speaks.
+    pub fn with_provider(mut self, provider: ProviderKind) -> Self {
+        self.provider = provider;
+        self
+    }
+
+    /// Set the retry policy.
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// Authenticate with `Authorization: Bearer <api_key>`.
+    pub fn with_api_key(mut self, api_key: impl Into<String>) -> Self {
+        self.api_key = Some(api_key.into());
+        self
+    }
+
+    /// Add a header to every request.
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Answer repeated chat completions from a cache.
+    ///
+    /// Requests match when their model, messages, sampling parameters and
+    /// tools are identical. Streams are never cached.
+    pub fn with_cache(mut self, cache: ResponseCacheConfig) -> Self {
+        self.cache = Some(cache);
+        self
+    }
+
+    /// Limit the requests in flight at once, queueing the rest.
+    ///
+    /// Single-context servers such as BitNet's time out when hammered with
+    /// parallel requests; `1` sends them one at a time.
+    pub fn with_max_concurrent_requests(mut self, max: usize) -> Self {
+        self.max_concurrent_requests = Some(max.max(1));
+        self
+    }
+
+    /// Headers for every request, with the API key marked sensitive.
+    fn default_headers(&self) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        for (name, value) in &self.headers {
+            let name = HeaderName::from_bytes(name.as_bytes()).map_err(|_| {
+                LlmError::InvalidConfig(format!("Invalid header name: {}", name))
+            })?;
+            let value = HeaderValue::from_str(value).map_err(|_| {
+                LlmError::InvalidConfig(format!("Invalid value for header {}", name))
+            })?;
+            headers.insert(name, value);
+        }
+
+        if let Some(api_key) = &self.api_key {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", api_key))
+                .map_err(|_| LlmError::InvalidConfig("Invalid API key".to_string()))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        Ok(headers)
+    }
 }
 
 /// Client for communicating with BitNet/llama.cpp servers.
 ///
-/// Supports both OpenAI-compatible API and native llama.cpp API.
+/// Chats go through the configured [`LlmProvider`]: OpenAI-compatible
+/// (default), native llama.cpp or Ollama.
+///
+/// Requests, tokens and latencies are counted per client (shared with its
+/// clones) and per process; see [`Self::usage_report`].
 #[derive(Debug, Clone)]
 pub struct LlmClient {
     client: Client,
     config: LlmConfig,
+    provider: Arc<dyn LlmProvider>,
+    usage: Arc<UsageTracker>,
+    cache: Option<Arc<ResponseCache>>,
+    limiter: Option<Arc<Semaphore>>,
 }
 
 impl LlmClient {
@@ -66,13 +196,71 @@ impl LlmClient {
     }
 
     /// Create a new LLM client with custom configuration.
+    ///
+    /// # Panics
+    ///
+    /// If a header or the API key is not a valid HTTP header value; use
+    /// [`Self::try_with_config`] to handle that.
     pub fn with_config(config: LlmConfig) -> Self {
+        Self::try_with_config(config).expect("Failed to create HTTP client")
+    }
+
+    /// Create a new LLM client, failing on invalid headers.
+    pub fn try_with_config(config: LlmConfig) -> Result<Self> {
         let client = Client::builder()
             .timeout(Duration::from_secs(config.timeout_secs))
-            .build()
-            .expect("Failed to create HTTP client");
+            .default_headers(config.default_headers()?)
+            .build()?;
+        let provider = config.provider.provider();
+        let cache = config.cache.clone().map(|cache| Arc::new(ResponseCache::new(cache)));
+        let limiter = config
+            .max_concurrent_requests
+            .map(|max| Arc::new(Semaphore::new(max.max(1))));
+
+        Ok(Self {
+            client,
+            config,
+            provider,
+            usage: Arc::new(UsageTracker::new()),
+            cache,
+            limiter,
+        })
+    }
+
+    /// Use a custom provider instead of the configured one.
+    pub fn with_provider(mut self, provider: Arc<dyn LlmProvider>) -> Self {
+        self.provider = provider;
+        self
+    }
 
-        Self { client, config }
+    /// Get the configuration.
+    pub fn config(&self) -> &LlmConfig {
+        &self.config
+    }
+
+    /// Requests, tokens and latencies of this client and of the process.
+    pub fn usage_report(&self) -> UsageReport {
+        UsageReport {
+            session: self.usage.snapshot(),
+            global: UsageTracker::global().snapshot(),
+        }
+    }
+
+    /// Reset this client's counters; the process counters keep running.
+    pub fn reset_usage(&self) {
+        self.usage.reset();
+    }
+
+    /// Response cache counters, if the cache is on.
+    pub fn cache_stats(&self) -> Option<ResponseCacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
+    }
+
+    /// Drop every cached response.
+    pub fn clear_cache(&self) {
+        if let Some(cache) = &self.cache {
+            cache.clear();
+        }
     }
 
     /// Get the base URL.
@@ -80,12 +268,38 @@ impl LlmClient {
         &self.config.base_url
     }
 
+    /// Get the model requests are sent to.
+    pub fn model(&self) -> &str {
+        &self.config.model
+    }
+
+    /// Send later requests to `model`, e.g. one from [`Self::list_models`].
+    pub fn set_model(&mut self, model: impl Into<String>) {
+        self.config.model = model.into();
+    }
+
+    /// List the models the server serves.
+    pub async fn list_models(&self) -> Result<Vec<ModelInfo>> {
+        let url = format!("{}{}", self.config.base_url, self.provider.models_path());
+        debug!("Listing models: {}", url);
+
+        let response = self.send(self.client.get(&url), false).await?;
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let message = response.text().await.unwrap_or_default();
+            return Err(LlmError::ServerError { status, message });
+        }
+
+        self.provider.parse_models(&response.bytes().await?)
+    }
+
     /// Check if the server is available.
     pub async fn health_check(&self) -> Result<bool> {
-        let url = format!("{}/health", self.config.base_url);
+        let url = format!("{}{}", self.config.base_url, self.provider.health_path());
         debug!("Health check: {}", url);
 
-        match self.client.get(&url).timeout(Duration::from_secs(5)).send().await {
+        let request = self.client.get(&url).timeout(Duration::from_secs(5));
+        match self.send(request, false).await {
             Ok(response) => Ok(response.status().is_success()),
             Err(e) => {
                 warn!("Health check failed: {}", e);
@@ -114,8 +328,168 @@ impl LlmClient {
         })
     }
 
-    /// Send a chat completion request (OpenAI-compatible API).
+    /// Send a chat completion request.
     pub async fn chat(&self, messages: &[Message], options: Option<ChatOptions>) -> Result<String> {
+        let chat_response = self.chat_completion(messages, options).await?;
+        
+        chat_response
+            .content()
+            .map(|s| s.to_string())
+            .ok_or(LlmError::EmptyResponse)
+    }
+
+    /// Send a chat completion request and return the full response.
+    ///
+    /// Use this with [`ChatOptions::tools`] to read the
+    /// [`tool_calls`](ChatResponse::tool_calls) the model makes.
+    pub async fn chat_completion(
+        &self,
+        messages: &[Message],
+        options: Option<ChatOptions>,
+    ) -> Result<ChatResponse> {
+        let body = self.chat_body(messages, options, false)?;
+        let cache_key = self.cache.as_ref().map(|_| body.to_string());
+        if let (Some(cache), Some(key)) = (&self.cache, &cache_key) {
+            if let Some(response) = cache.get(key) {
+                debug!("Chat response served from cache");
+                return Ok(response);
+            }
+        }
+
+        let _permit = self.acquire().await;
+        let started = Instant::now();
+        let result = async {
+            let response = self.send_chat(&body, false).await?;
+            let body = response.bytes().await?;
+            self.provider.parse_chat(&body)
+        }
+        .await;
+
+        match &result {
+            Ok(response) => {
+                self.record(started, response.usage.as_ref());
+                if let (Some(cache), Some(key)) = (&self.cache, cache_key) {
+                    cache.insert(key, response.clone());
+                }
+            }
+            Err(_) => self.record_failure(),
+        }
+        result
+    }
+
+    /// Send a chat and parse the reply as JSON matching `schema`.
+    ///
+    /// The schema is added to the system prompt and sent as the
+    /// `response_format`, so it works whether or not the server supports
+    /// constrained output. A reply that does not parse is sent back with the
+    /// error, up to `retries` times.
+    pub async fn chat_json<T: DeserializeOwned>(
+        &self,
+        messages: &[Message],
+        options: Option<ChatOptions>,
+        schema: serde_json::Value,
+        retries: u32,
+    ) -> Result<T> {
+        let mut messages = with_schema_prompt(messages, &schema);
+        let options = options
+            .unwrap_or_default()
+            .response_format(ResponseFormat::json_schema("response", schema));
+
+        let mut attempt = 0;
+        loop {
+            let reply = self.chat(&messages, Some(options.clone())).await?;
+            match serde_json::from_str(extract_json(&reply)) {
+                Ok(value) => return Ok(value),
+                Err(e) if attempt < retries => {
+                    attempt += 1;
+                    warn!("Reply does not match the schema (attempt {}): {}", attempt, e);
+                    messages.push(Message::assistant(reply));
+                    messages.push(Message::user(format!(
+                        "That reply is not valid: {}. Answer again with only JSON \
+                         matching the schema.",
+                        e
+                    )));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+
+    /// Send a chat and parse the reply into `T`.
+    ///
+    /// [`Self::chat_json`] with the schema of `T`, which can be derived with
+    /// `#[derive(schemars::JsonSchema, serde::Deserialize)]`.
+    #[cfg(feature = "schemars")]
+    pub async fn chat_structured<T: schemars::JsonSchema + DeserializeOwned>(
+        &self,
+        messages: &[Message],
+        options: Option<ChatOptions>,
+        retries: u32,
+    ) -> Result<T> {
+        let schema = crate::structured::schema_for::<T>();
+        self.chat_json(messages, options, schema, retries).await
+    }
+
+    /// Stream a chat completion.
+    ///
+    /// Yields content tokens as the server sends them, ending when the
+    /// provider sees the last event or the server closes the connection.
+    pub async fn chat_stream(
+        &self,
+        messages: &[Message],
+        options: Option<ChatOptions>,
+    ) -> Result<ChatStream> {
+        let body = self.chat_body(messages, options, true)?;
+        let permit = self.acquire().await;
+        let started = Instant::now();
+        let response = match self.send_chat(&body, true).await {
+            Ok(response) => response,
+            Err(e) => {
+                self.record_failure();
+                return Err(e);
+            }
+        };
+        self.record(started, None);
+        let events = EventBuffer::new(self.provider.stream_format());
+        // The permit is held until the stream ends or is dropped
+        let state = (
+            response.bytes_stream().boxed(),
+            events,
+            self.provider.clone(),
+            permit,
+        );
+
+        let tokens = futures::stream::try_unfold(
+            state,
+            |(mut bytes, mut events, provider, permit)| async move {
+                loop {
+                    while let Some(data) = events.next_data() {
+                        match provider.parse_stream_event(&data)? {
+                            StreamEvent::Token(token) => {
+                                return Ok(Some((token, (bytes, events, provider, permit))))
+                            }
+                            StreamEvent::Skip => {}
+                            StreamEvent::Done => return Ok(None),
+                        }
+                    }
+                    match bytes.next().await {
+                        Some(chunk) => events.push(&chunk?),
+                        None => return Ok(None),
+                    }
+                }
+            },
+        );
+
+        Ok(Box::pin(tokens))
+    }
+
+    /// Request body for a chat, with defaults from the config.
+    fn chat_body(
+        &self,
+        messages: &[Message],
+        options: Option<ChatOptions>,
+        stream: bool,
+    ) -> Result<serde_json::Value> {
         let options = options.unwrap_or_default();
         
         let request = ChatRequest {
@@ -124,18 +498,26 @@ impl LlmClient {
             max_tokens: Some(options.max_tokens.unwrap_or(self.config.max_tokens)),
             temperature: Some(options.temperature.unwrap_or(self.config.temperature)),
             top_p: options.top_p,
-            stream: Some(false),
+            stream: Some(stream),
             stop: options.stop,
+            tools: options.tools,
+            tool_choice: options.tool_choice,
+            response_format: options.response_format,
         };
 
-        let url = format!("{}/v1/chat/completions", self.config.base_url);
-        debug!("Chat request to {}", url);
+        self.provider.chat_body(request)
+    }
 
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?;
+    async fn send_chat(&self, body: &serde_json::Value, stream: bool) -> Result<reqwest::Response> {
+        let url = format!("{}{}", self.config.base_url, self.provider.chat_path());
+        debug!(
+            "Chat request to {} ({}, stream: {})",
+            url,
+            self.provider.name(),
+            stream
+        );
+
+        let response = self.send(self.client.post(&url).json(body), true).await?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -143,16 +525,37 @@ impl LlmClient {
             return Err(LlmError::ServerError { status, message });
         }
 
-        let chat_response: ChatResponse = response.json().await?;
-        
-        chat_response
-            .content()
-            .map(|s| s.to_string())
-            .ok_or(LlmError::EmptyResponse)
+        Ok(response)
     }
 
     /// Generate text using native llama.cpp API.
     pub async fn generate(&self, prompt: &str, options: Option<GenerateOptions>) -> Result<GenerateResponse> {
+        let _permit = self.acquire().await;
+        let started = Instant::now();
+        let result = self.send_generate(prompt, options).await;
+
+        match &result {
+            Ok(response) => {
+                let usage = match (response.tokens_evaluated, response.tokens_predicted) {
+                    (Some(prompt_tokens), Some(completion_tokens)) => Some(Usage {
+                        prompt_tokens,
+                        completion_tokens,
+                        total_tokens: prompt_tokens + completion_tokens,
+                    }),
+                    _ => None,
+                };
+                self.record(started, usage.as_ref());
+            }
+            Err(_) => self.record_failure(),
+        }
+        result
+    }
+
+    async fn send_generate(
+        &self,
+        prompt: &str,
+        options: Option<GenerateOptions>,
+    ) -> Result<GenerateResponse> {
         let options = options.unwrap_or_default();
 
         let request = GenerateRequest {
@@ -168,11 +571,7 @@ impl LlmClient {
         let url = format!("{}/completion", self.config.base_url);
         debug!("Generate request to {}", url);
 
-        let response = self.client
-            .post(&url)
-            .json(&request)
-            .send()
-            .await?;
+        let response = self.send(self.client.post(&url).json(&request), true).await?;
 
         if !response.status().is_success() {
             let status = response.status().as_u16();
@@ -184,6 +583,83 @@ impl LlmClient {
         Ok(gen_response)
     }
 
+    /// Wait for a free slot if requests are limited.
+    async fn acquire(&self) -> Option<OwnedSemaphorePermit> {
+        let limiter = self.limiter.as_ref()?;
+        if limiter.available_permits() == 0 {
+            debug!("Waiting for a free request slot");
+        }
+        // The semaphore is never closed
+        limiter.clone().acquire_owned().await.ok()
+    }
+
+    /// Count a request that started at `started` in the session and
+    /// process totals.
+    fn record(&self, started: Instant, usage: Option<&Usage>) {
+        let latency = started.elapsed();
+        self.usage.record(latency, usage);
+        UsageTracker::global().record(latency, usage);
+    }
+
+    fn record_failure(&self) {
+        self.usage.record_failure();
+        UsageTracker::global().record_failure();
+    }
+
+    /// Send `request`, retrying as the retry policy allows.
+    ///
+    /// Returns the last response even when its status is an error, so the
+    /// caller can report the server's message. Streams are only retried
+    /// until the response starts, never after tokens have been yielded.
+    async fn send(
+        &self,
+        request: reqwest::RequestBuilder,
+        completion: bool,
+    ) -> Result<reqwest::Response> {
+        let policy = &self.config.retry;
+        let attempts = if completion && !policy.retry_completions {
+            1
+        } else {
+            policy.max_attempts.max(1)
+        };
+
+        let mut attempt = 1;
+        loop {
+            let last = attempt >= attempts;
+            let builder = request.try_clone().ok_or_else(|| {
+                LlmError::InvalidConfig("request body cannot be retried".to_string())
+            })?;
+
+            let wait = match builder.send().await {
+                Ok(response) if last || !policy.is_retryable_status(response.status().as_u16()) => {
+                    return Ok(response)
+                }
+                Ok(response) => {
+                    let wait = retry_after(&response)
+                        .unwrap_or_else(|| policy.backoff_for(attempt))
+                        .min(policy.max_backoff);
+                    warn!(
+                        "Server returned {}, retrying in {:?} ({}/{})",
+                        response.status(),
+                        wait,
+                        attempt,
+                        attempts
+                    );
+                    wait
+                }
+                Err(e) if !last && (e.is_timeout() || e.is_connect()) => {
+                    let wait = policy.backoff_for(attempt);
+                    warn!("{}, retrying in {:?} ({}/{})", e, wait, attempt, attempts);
+                    wait
+                }
+                Err(e) => return Err(e.into()),
+            };
+
+            tokio::time::sleep(wait).await;
+            attempt += 1;
+        }
+    }
+
     /// Simple question-answering with context.
     pub async fn ask_with_context(
         &self,
@@ -191,25 +667,21 @@ impl LlmClient {
         context: &str,
         system_prompt: Option<&str>,
     ) -> Result<String> {
-        let system = system_prompt.unwrap_or(
-            "You are a helpful assistant. Use the provided context to answer questions. \
-             If the information is not in the context, say so. \
-             Respond in the same language as the question."
-        );
-
-        let user_prompt = format!(
-            "Context:\n{}\n\nQuestion: {}\n\nAnswer:",
-            context, question
-        );
-
-        let messages = vec![
-            Message::system(system),
-            Message::user(user_prompt),
-        ];
-
+        let messages = context_messages(question, context, system_prompt);
         self.chat(&messages, None).await
     }
 
+    /// Streaming version of [`Self::ask_with_context`].
+    pub async fn ask_with_context_stream(
+        &self,
+        question: &str,
+        context: &str,
+        system_prompt: Option<&str>,
+    ) -> Result<ChatStream> {
+        let messages = context_messages(question, context, system_prompt);
+        self.chat_stream(&messages, None).await
+    }
+
     /// Simple question-answering without context.
     pub async fn ask(&self, question: &str) -> Result<String> {
         let messages = vec![
@@ -221,6 +693,84 @@ impl LlmClient {
     }
 }
 
+/// Wait requested by a `Retry-After` header, in seconds.
+fn retry_after(response: &reqwest::Response) -> Option<Duration> {
+    response
+        .headers()
+        .get(reqwest::header::RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Messages asking `question` about `context`.
+fn context_messages(question: &str, context: &str, system_prompt: Option<&str>) -> Vec<Message> {
+    let system = system_prompt.unwrap_or(
+        "You are a helpful assistant. Use the provided context to answer questions. \
+         If the information is not in the context, say so. \
+         Respond in the same language as the question."
+    );
+
+    let user_prompt = format!(
+        "Context:\n{}\n\nQuestion: {}\n\nAnswer:",
+        context, question
+    );
+
+    vec![
+        Message::system(system),
+        Message::user(user_prompt),
+    ]
+}
+
+/// Splits a streamed reply into event payloads.
+///
+/// Bytes are buffered until a full line arrives, so events split across
+/// network chunks (or UTF-8 characters split across them) parse correctly.
+#[derive(Debug)]
+struct EventBuffer {
+    format: StreamFormat,
+    buffer: Vec<u8>,
+}
+
+impl EventBuffer {
+    fn new(format: StreamFormat) -> Self {
+        Self {
+            format,
+            buffer: Vec::new(),
+        }
+    }
+
+    /// Add bytes received from the server.
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Take the next complete event: a `data:` field of server-sent
+    /// events, skipping other fields, or a non-empty JSON line.
+    fn next_data(&mut self) -> Option<String> {
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\r', '\n']);
+            match self.format {
+                StreamFormat::Sse => {
+                    if let Some(data) = line.strip_prefix("data:") {
+                        return Some(data.trim_start().to_string());
+                    }
+                }
+                StreamFormat::JsonLines if !line.trim().is_empty() => {
+                    return Some(line.to_string());
+                }
+                StreamFormat::JsonLines => {}
+            }
+        }
+        None
+    }
+}
+
 /// Options for chat completion.
 #[derive(Debug, Clone, Default)]
 pub struct ChatOptions {
@@ -232,6 +782,12 @@ pub struct ChatOptions {
     pub top_p: Option<f32>,
     /// Stop sequences
     pub stop: Option<Vec<String>>,
+    /// Tools the model may call
+    pub tools: Option<Vec<Tool>>,
+    /// Whether and which tools to call
+    pub tool_choice: Option<ToolChoice>,
+    /// Format the reply must take
+    pub response_format: Option<ResponseFormat>,
 }
 
 impl ChatOptions {
@@ -251,6 +807,24 @@ impl ChatOptions {
         self.temperature = Some(temperature);
         self
     }
+
+    /// Offer tools to the model.
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Set whether and which tools to call.
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Set the format the reply must take.
+    pub fn response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
 }
 
 /// Options for text generation.
@@ -271,6 +845,7 @@ pub struct GenerateOptions {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::types::ChatChunk;
 
     #[test]
     fn test_config_default() {
@@ -285,6 +860,216 @@ mod tests {
         assert_eq!(client.base_url(), "http://localhost:8080");
     }
 
+    #[test]
+    fn test_event_buffer() {
+        let mut events = EventBuffer::new(StreamFormat::Sse);
+        events.push(b": keep-alive\n\ndata: {\"a\":1}\n\nda");
+        assert_eq!(events.next_data().as_deref(), Some("{\"a\":1}"));
+        assert_eq!(events.next_data(), None);
+
+        // An event split across chunks, in the middle of a UTF-8 character
+        let rest = "ta: {\"b\":\"ñ\"}\r\n\r\ndata: [DONE]\n\n".as_bytes();
+        events.push(&rest[..11]);
+        assert_eq!(events.next_data(), None);
+        events.push(&rest[11..]);
+        assert_eq!(events.next_data().as_deref(), Some("{\"b\":\"ñ\"}"));
+        assert_eq!(events.next_data().as_deref(), Some("[DONE]"));
+
+        let mut lines = EventBuffer::new(StreamFormat::JsonLines);
+        lines.push(b"{\"done\":false}\n\n{\"done\":");
+        assert_eq!(lines.next_data().as_deref(), Some("{\"done\":false}"));
+        assert_eq!(lines.next_data(), None);
+    }
+
+    #[test]
+    fn test_chat_chunk_content() {
+        let chunk: ChatChunk = serde_json::from_str(
+            r#"{"choices":[{"index":0,"delta":{"content":"Hel"},"finish_reason":null}]}"#,
+        )
+        .unwrap();
+        assert_eq!(chunk.content(), Some("Hel"));
+
+        let first: ChatChunk =
+            serde_json::from_str(r#"{"choices":[{"delta":{"role":"assistant"}}]}"#).unwrap();
+        assert_eq!(first.content(), None);
+    }
+
+    /// Serve one canned HTTP response per connection, in order
+    async fn serve(responses: Vec<String>) -> String {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                // Read the whole request so closing the socket does not reset it
+                let mut request = Vec::new();
+                let mut buf = [0; 4096];
+                loop {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    request.extend_from_slice(&buf[..n]);
+                    let text = String::from_utf8_lossy(&request);
+                    if let Some(end) = text.find("\r\n\r\n") {
+                        let length = text[..end]
+                            .lines()
+                            .find_map(|line| {
+                                let line = line.to_lowercase();
+                                let value = line.strip_prefix("content-length:")?;
+                                value.trim().parse::<usize>().ok()
+                            })
+                            .unwrap_or(0);
+                        if request.len() >= end + 4 + length {
+                            break;
+                        }
+                    }
+                    if n == 0 {
+                        break;
+                    }
+                }
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.ok();
+            }
+        });
+        url
+    }
+
+    fn http_response(status: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            status,
+            body.len(),
+            body
+        )
+    }
+
+    #[tokio::test]
+    async fn test_chat_retries_transient_errors() {
+        let ok = r#"{"choices":[{"message":{"role":"assistant","content":"4"}}]}"#;
+        let responses = vec![
+            http_response("502 Bad Gateway", ""),
+            http_response("503 Service Unavailable", ""),
+            http_response("200 OK", ok),
+        ];
+        let retry = RetryPolicy::new().backoff(Duration::from_millis(1), Duration::from_millis(5));
+
+        let url = serve(responses.clone()).await;
+        let client = LlmClient::with_config(LlmConfig::new(url).with_retry(retry.clone()));
+        assert_eq!(client.ask("2 + 2?").await.unwrap(), "4");
+        let report = client.clone().usage_report();
+        assert_eq!(report.session.requests, 1);
+        assert_eq!(report.session.failures, 0);
+        assert!(report.global.requests >= 1);
+
+        // Completions can opt out of retries
+        let url = serve(responses).await;
+        let client =
+            LlmClient::with_config(LlmConfig::new(url).with_retry(retry.retry_completions(false)));
+        assert!(matches!(
+            client.ask("2 + 2?").await,
+            Err(LlmError::ServerError { status: 502, .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_chat_served_from_cache() {
+        let ok = r#"{"choices":[{"message":{"role":"assistant","content":"4"}}]}"#;
+        let url = serve(vec![http_response("200 OK", ok)]).await;
+        let config = LlmConfig::new(url)
+            .with_retry(RetryPolicy::none())
+            .with_cache(ResponseCacheConfig::new());
+        let client = LlmClient::with_config(config);
+
+        // The server only answers once
+        assert_eq!(client.ask("2 + 2?").await.unwrap(), "4");
+        assert_eq!(client.ask("2 + 2?").await.unwrap(), "4");
+        assert!(client.ask("3 + 3?").await.is_err());
+
+        let stats = client.cache_stats().unwrap();
+        assert_eq!(stats.hits, 1);
+        assert_eq!(stats.misses, 2);
+        assert_eq!(stats.entries, 1);
+    }
+
+    #[cfg(feature = "schemars")]
+    #[tokio::test]
+    async fn test_chat_structured_retries_invalid_json() {
+        #[derive(schemars::JsonSchema, serde::Deserialize)]
+        struct Language {
+            name: String,
+            year: u32,
+        }
+
+        let reply = |content: &str| {
+            let body = serde_json::json!({
+                "choices": [{"message": {"role": "assistant", "content": content}}]
+            });
+            http_response("200 OK", &body.to_string())
+        };
+        let url = serve(vec![
+            reply("Sure! {\"name\": \"Rust\"}"),
+            reply("```json\n{\"name\": \"Rust\", \"year\": 2015}\n```"),
+        ])
+        .await;
+        let client = LlmClient::new(url);
+
+        let messages = [Message::user("When did Rust 1.0 come out?")];
+        let language: Language = client.chat_structured(&messages, None, 1).await.unwrap();
+        assert_eq!(language.name, "Rust");
+        assert_eq!(language.year, 2015);
+    }
+
+    #[tokio::test]
+    async fn test_list_models() {
+        let models = r#"{"object":"list","data":[{"id":"bitnet-2b"},{"id":"bitnet-8b"}]}"#;
+        let url = serve(vec![http_response("200 OK", models)]).await;
+        let mut client = LlmClient::new(url);
+
+        let models = client.list_models().await.unwrap();
+        assert_eq!(models.len(), 2);
+        assert_eq!(client.model(), "bitnet");
+        client.set_model(&models[1].id);
+        assert_eq!(client.model(), "bitnet-8b");
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_limit_queues_requests() {
+        let client = LlmClient::with_config(
+            LlmConfig::new("http://localhost:11435").with_max_concurrent_requests(1),
+        );
+        let first = client.acquire().await;
+        assert!(first.is_some());
+
+        // Clones share the limit
+        let other = client.clone();
+        let waiting = tokio::spawn(async move { other.acquire().await.is_some() });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!waiting.is_finished());
+
+        drop(first);
+        assert!(waiting.await.unwrap());
+
+        assert!(LlmClient::new("http://localhost:11435").acquire().await.is_none());
+    }
+
+    #[test]
+    fn test_api_key_and_headers() {
+        let config = LlmConfig::new("http://localhost:8080")
+            .with_api_key("sk-secret")
+            .with_header("X-Org", "neuro");
+        let headers = config.default_headers().unwrap();
+        assert_eq!(headers[AUTHORIZATION], "Bearer sk-secret");
+        assert!(headers[AUTHORIZATION].is_sensitive());
+        assert_eq!(headers["x-org"], "neuro");
+        assert!(!format!("{:?}", config).contains("sk-secret"));
+
+        let bad = LlmConfig::new("http://localhost:8080").with_header("Bad Name", "x");
+        assert!(matches!(
+            LlmClient::try_with_config(bad),
+            Err(LlmError::InvalidConfig(_))
+        ));
+    }
+
     #[test]
     fn test_message_creation() {
         let msg = Message::user("Hello");
@@ -0,0 +1,310 @@
+//! Multi-turn conversations with a remote server.
+//!
+//! A [`Conversation`] keeps the system prompt and the messages so far, and
+//! before each request trims the oldest turns, or folds them into a running
+//! summary, so the prompt and reply stay within a token budget.
+
+use tracing::{debug, warn};
+
+use crate::client::{ChatOptions, LlmClient};
+use crate::error::Result;
+use crate::types::{Message, Role};
+
+/// Rough bytes per token; the server's tokenizer is not available here
+const BYTES_PER_TOKEN: usize = 4;
+
+/// Tokens the chat template adds around each message
+const MESSAGE_OVERHEAD_TOKENS: usize = 4;
+
+/// Default limit for prompt plus reply
+const DEFAULT_TOKEN_BUDGET: usize = 4096;
+
+/// Maximum tokens for a summary of old turns
+const SUMMARY_MAX_TOKENS: u32 = 256;
+
+/// What to do with turns that no longer fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TrimStrategy {
+    /// Forget them
+    #[default]
+    DropOldest,
+    /// Ask the server to summarize them and keep the summary
+    ///
+    /// Costs an extra request whenever turns are trimmed. If it fails the
+    /// turns are dropped.
+    Summarize,
+}
+
+/// System prompt and message history of one conversation.
+///
+/// ```no_run
+/// use neuro_llm::{Conversation, LlmClient};
+///
+/// # async fn run() -> neuro_llm::Result<()> {
+/// let client = LlmClient::new("http://localhost:11435");
+/// let mut conversation = Conversation::new()
+///     .with_system_prompt("You are a helpful assistant.")
+///     .with_token_budget(2048);
+///
+/// conversation.send(&client, "What is Rust?", None).await?;
+/// let reply = conversation.send(&client, "And who maintains it?", None).await?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Conversation {
+    system_prompt: Option<String>,
+    summary: Option<String>,
+    history: Vec<Message>,
+    token_budget: usize,
+    strategy: TrimStrategy,
+}
+
+impl Default for Conversation {
+    fn default() -> Self {
+        Self {
+            system_prompt: None,
+            summary: None,
+            history: Vec::new(),
+            token_budget: DEFAULT_TOKEN_BUDGET,
+            strategy: TrimStrategy::default(),
+        }
+    }
+}
+
+impl Conversation {
+    /// Create an empty conversation with a 4096 token budget.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the system prompt, which is never trimmed.
+    pub fn with_system_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.system_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Limit prompt plus reply to `tokens`.
+    pub fn with_token_budget(mut self, tokens: usize) -> Self {
+        self.token_budget = tokens;
+        self
+    }
+
+    /// Set what happens to turns that no longer fit.
+    pub fn with_strategy(mut self, strategy: TrimStrategy) -> Self {
+        self.strategy = strategy;
+        self
+    }
+
+    /// Append a message without sending anything.
+    ///
+    /// System messages replace the system prompt.
+    pub fn push(&mut self, message: Message) {
+        match message.role {
+            Role::System => self.system_prompt = Some(message.content),
+            _ => self.history.push(message),
+        }
+    }
+
+    /// The system prompt, if any.
+    pub fn system_prompt(&self) -> Option<&str> {
+        self.system_prompt.as_deref()
+    }
+
+    /// Summary of trimmed turns, if any.
+    pub fn summary(&self) -> Option<&str> {
+        self.summary.as_deref()
+    }
+
+    /// Messages exchanged so far, oldest first.
+    pub fn history(&self) -> &[Message] {
+        &self.history
+    }
+
+    /// The whole conversation as sent to the server.
+    pub fn messages(&self) -> Vec<Message> {
+        let summary = self
+            .summary
+            .as_ref()
+            .map(|summary| format!("Summary of the earlier conversation: {}", summary));
+
+        self.system_prompt
+            .iter()
+            .cloned()
+            .chain(summary)
+            .map(Message::system)
+            .chain(self.history.iter().cloned())
+            .collect()
+    }
+
+    /// Forget the history and summary, keeping the system prompt.
+    pub fn clear(&mut self) {
+        self.history.clear();
+        self.summary = None;
+    }
+
+    /// Estimated tokens the conversation takes up.
+    pub fn estimated_tokens(&self) -> usize {
+        self.messages().iter().map(estimate_tokens).sum()
+    }
+
+    /// Send a user message and return the assistant's reply.
+    ///
+    /// Old turns are trimmed first so the prompt leaves room for the
+    /// reply's `max_tokens`. The newest message is always kept. If the
+    /// request fails the message is not added to the history.
+    pub async fn send(
+        &mut self,
+        client: &LlmClient,
+        user_message: impl Into<String>,
+        options: Option<ChatOptions>,
+    ) -> Result<String> {
+        let reply_tokens = options
+            .as_ref()
+            .and_then(|options| options.max_tokens)
+            .unwrap_or(client.config().max_tokens) as usize;
+
+        self.history.push(Message::user(user_message));
+        self.trim(client, reply_tokens).await;
+
+        match client.chat(&self.messages(), options).await {
+            Ok(reply) => {
+                self.history.push(Message::assistant(reply.as_str()));
+                Ok(reply)
+            }
+            Err(e) => {
+                self.history.pop();
+                Err(e)
+            }
+        }
+    }
+
+    /// Trim or summarize old turns until the prompt leaves `reply_tokens`
+    /// free.
+    async fn trim(&mut self, client: &LlmClient, reply_tokens: usize) {
+        let dropped = self.drop_overflow(reply_tokens);
+        if dropped.is_empty() || self.strategy == TrimStrategy::DropOldest {
+            return;
+        }
+
+        match self.summarize(client, &dropped).await {
+            Ok(summary) => {
+                self.summary = Some(summary);
+                // The summary itself may push the prompt over again
+                self.drop_overflow(reply_tokens);
+            }
+            Err(e) => warn!("Failed to summarize old turns, dropping them: {}", e),
+        }
+    }
+
+    /// Drop the oldest turns until the prompt leaves `reply_tokens` free.
+    fn drop_overflow(&mut self, reply_tokens: usize) -> Vec<Message> {
+        let budget = self.token_budget.saturating_sub(reply_tokens);
+
+        let mut dropped = Vec::new();
+        while self.history.len() > 1 && self.estimated_tokens() > budget {
+            // A turn is a user message and the replies and tool results that
+            // follow it
+            let end = self.history[1..]
+                .iter()
+                .position(|m| m.role == Role::User)
+                .map_or(self.history.len(), |i| i + 1);
+            dropped.extend(self.history.drain(..end));
+        }
+
+        if !dropped.is_empty() {
+            debug!(
+                "Trimmed {} old messages to fit {} tokens",
+                dropped.len(),
+                budget
+            );
+        }
+        dropped
+    }
+
+    /// Summarize `dropped` together with the previous summary.
+    async fn summarize(&self, client: &LlmClient, dropped: &[Message]) -> Result<String> {
+        let mut transcript = String::new();
+        if let Some(summary) = &self.summary {
+            transcript.push_str(&format!("Earlier: {}\n", summary));
+        }
+        for message in dropped.iter().filter(|m| !m.content.is_empty()) {
+            let role = match message.role {
+                Role::System => "System",
+                Role::User => "User",
+                Role::Assistant => "Assistant",
+                Role::Tool => "Tool",
+            };
+            transcript.push_str(&format!("{}: {}\n", role, message.content));
+        }
+
+        let messages = [
+            Message::system(
+                "Summarize the conversation below in a few sentences. Keep names, \
+                 facts and decisions the rest of the conversation may rely on.",
+            ),
+            Message::user(transcript),
+        ];
+        let options = ChatOptions::new()
+            .max_tokens(SUMMARY_MAX_TOKENS)
+            .temperature(0.0);
+        let summary = client.chat(&messages, Some(options)).await?;
+        Ok(summary.trim().to_string())
+    }
+}
+
+fn estimate_tokens(message: &Message) -> usize {
+    let calls: usize = message
+        .tool_calls
+        .iter()
+        .flatten()
+        .map(|call| call.function.name.len() + call.function.arguments.len())
+        .sum();
+    (message.content.len() + calls).div_ceil(BYTES_PER_TOKEN) + MESSAGE_OVERHEAD_TOKENS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_messages_include_summary() {
+        let mut conversation = Conversation::new().with_system_prompt("Be brief.");
+        conversation.push(Message::user("Hi"));
+        conversation.summary = Some("The user said hello.".to_string());
+
+        let messages = conversation.messages();
+        assert_eq!(messages.len(), 3);
+        assert_eq!(messages[0].content, "Be brief.");
+        assert!(messages[1].content.ends_with("The user said hello."));
+        assert_eq!(messages[2].role, Role::User);
+
+        conversation.clear();
+        assert!(conversation.history().is_empty());
+        assert_eq!(conversation.summary(), None);
+        assert_eq!(conversation.system_prompt(), Some("Be brief."));
+    }
+
+    #[test]
+    fn test_drop_overflow_keeps_newest_turns() {
+        let mut conversation = Conversation::new()
+            .with_system_prompt("Be brief.")
+            .with_token_budget(60);
+        for question in ["first question", "second question", "third question"] {
+            conversation.push(Message::user(question));
+            conversation.push(Message::assistant("x".repeat(40)));
+        }
+        conversation.push(Message::user("fourth question"));
+
+        let dropped = conversation.drop_overflow(16);
+        assert_eq!(dropped[0].content, "first question");
+        assert!(conversation.estimated_tokens() + 16 <= 60);
+        assert_eq!(conversation.history()[0].role, Role::User);
+
+        // The newest message is kept even when it alone is over budget
+        conversation.push(Message::user("y".repeat(400)));
+        conversation.drop_overflow(16);
+        assert_eq!(conversation.history().len(), 1);
+        assert_eq!(conversation.system_prompt(), Some("Be brief."));
+    }
+}
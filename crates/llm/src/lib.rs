@@ -1,7 +1,8 @@
 //! LLM client for neuro-bitnet.
 //!
 //! This crate provides a client for communicating with BitNet/llama.cpp servers
-//! that expose an OpenAI-compatible API.
+//! that expose an OpenAI-compatible API, llama.cpp's native API or Ollama's
+//! API (see [`provider`]).
 //!
 //! # Example
 //!
@@ -24,13 +25,27 @@
 //! }
 //! ```
 
+mod cache;
 mod client;
+mod conversation;
 mod error;
+pub mod provider;
+mod retry;
+mod structured;
+mod tools;
 mod types;
+mod usage;
 
-pub use client::{LlmClient, LlmConfig, ChatOptions, GenerateOptions};
+pub use cache::{ResponseCacheConfig, ResponseCacheStats};
+pub use client::{LlmClient, LlmConfig, ChatOptions, ChatStream, GenerateOptions};
+pub use conversation::{Conversation, TrimStrategy};
 pub use error::{LlmError, Result};
+pub use provider::{LlmProvider, ProviderKind};
+pub use retry::RetryPolicy;
+pub use structured::{JsonSchemaFormat, ResponseFormat};
+pub use tools::{FunctionCall, FunctionDefinition, Tool, ToolCall, ToolChoice};
 pub use types::{
-    ChatRequest, ChatResponse, Choice, Message, Role, Usage,
-    GenerateRequest, GenerateResponse,
+    ChatChunk, ChatRequest, ChatResponse, Choice, ChunkChoice, Delta, Message, Role, Usage,
+    GenerateRequest, GenerateResponse, ModelInfo, ModelList,
 };
+pub use usage::{UsageReport, UsageStats};
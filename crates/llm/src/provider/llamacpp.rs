@@ -0,0 +1,96 @@
+//! llama.cpp's native completion API.
+
+use serde::Deserialize;
+use serde_json::{json, Value};
+
+use super::{LlmProvider, StreamEvent};
+use crate::error::Result;
+use crate::structured::ResponseFormat;
+use crate::types::{ChatRequest, ChatResponse, GenerateRequest, GenerateResponse, Message, Role};
+
+/// llama.cpp's native `/completion`.
+///
+/// It takes a plain prompt, so messages are rendered as `Role: content`
+/// lines ending with `Assistant:`. Tools are not supported and are ignored;
+/// JSON response formats become llama.cpp's `json_schema` constraint.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LlamaCppProvider;
+
+/// A streamed `/completion` event
+#[derive(Deserialize)]
+struct CompletionChunk {
+    #[serde(default)]
+    content: String,
+    #[serde(default)]
+    stop: bool,
+}
+
+fn render_prompt(messages: &[Message]) -> String {
+    let mut prompt = String::new();
+    for message in messages {
+        let role = match message.role {
+            Role::System => "System",
+            Role::User => "User",
+            Role::Assistant => "Assistant",
+            Role::Tool => "Tool",
+        };
+        prompt.push_str(&format!("{}: {}\n", role, message.content));
+    }
+    prompt.push_str("Assistant:");
+    prompt
+}
+
+impl LlmProvider for LlamaCppProvider {
+    fn name(&self) -> &'static str {
+        "llamacpp"
+    }
+
+    fn chat_path(&self) -> &'static str {
+        "/completion"
+    }
+
+    fn chat_body(&self, request: ChatRequest) -> Result<Value> {
+        // Stop before the model writes the user's next turn
+        let mut stop = request.stop.unwrap_or_default();
+        stop.push("\nUser:".to_string());
+
+        let body = GenerateRequest {
+            prompt: render_prompt(&request.messages),
+            n_predict: request.max_tokens,
+            temperature: request.temperature,
+            top_p: request.top_p,
+            top_k: None,
+            stop: Some(stop),
+            stream: request.stream,
+        };
+        let mut body = serde_json::to_value(body)?;
+        match &request.response_format {
+            Some(ResponseFormat::JsonObject) => body["json_schema"] = json!({}),
+            Some(format) => {
+                if let Some(schema) = format.schema() {
+                    body["json_schema"] = schema.clone();
+                }
+            }
+            None => {}
+        }
+        Ok(body)
+    }
+
+    fn parse_chat(&self, body: &[u8]) -> Result<ChatResponse> {
+        let response: GenerateResponse = serde_json::from_slice(body)?;
+        Ok(ChatResponse::from_message(Message::assistant(
+            response.content.trim(),
+        )))
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent> {
+        let chunk: CompletionChunk = serde_json::from_str(data)?;
+        Ok(if !chunk.content.is_empty() {
+            StreamEvent::Token(chunk.content)
+        } else if chunk.stop {
+            StreamEvent::Done
+        } else {
+            StreamEvent::Skip
+        })
+    }
+}
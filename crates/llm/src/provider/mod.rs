@@ -0,0 +1,254 @@
+//! Server APIs the client can talk to.
+//!
+//! An [`LlmProvider`] turns a chat into a request body for one kind of
+//! server and reads its replies; [`LlmClient`](crate::LlmClient) does the
+//! HTTP, retries and authentication. Pick one with [`ProviderKind`] in
+//! [`LlmConfig`](crate::LlmConfig):
+//!
+//! - [`OpenAiProvider`] - OpenAI-compatible `/v1/chat/completions`
+//!   (neuro-daemon, llama-server, vLLM, ...)
+//! - [`LlamaCppProvider`] - llama.cpp's native `/completion`
+//! - [`OllamaProvider`] - Ollama's `/api/chat`
+
+use std::sync::Arc;
+
+use serde_json::Value;
+
+use crate::error::Result;
+use crate::types::{ChatRequest, ChatResponse, ModelInfo, ModelList};
+
+mod llamacpp;
+mod ollama;
+mod openai;
+
+pub use llamacpp::LlamaCppProvider;
+pub use ollama::OllamaProvider;
+pub use openai::OpenAiProvider;
+
+/// How a provider frames streamed replies.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StreamFormat {
+    /// Server-sent events; each `data:` field is one event
+    Sse,
+    /// One JSON object per line
+    JsonLines,
+}
+
+/// What one streamed event carries.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StreamEvent {
+    /// New reply text
+    Token(String),
+    /// Nothing to show, e.g. a role-only or keep-alive event
+    Skip,
+    /// The reply is complete
+    Done,
+}
+
+/// A server API for chat completions.
+pub trait LlmProvider: Send + Sync + std::fmt::Debug {
+    /// Provider name for logging.
+    fn name(&self) -> &'static str;
+
+    /// Path of the chat endpoint, relative to the base URL.
+    fn chat_path(&self) -> &'static str;
+
+    /// Path answering `2xx` when the server is up.
+    fn health_path(&self) -> &'static str {
+        "/health"
+    }
+
+    /// Path listing the served models.
+    fn models_path(&self) -> &'static str {
+        "/v1/models"
+    }
+
+    /// Read the served models.
+    fn parse_models(&self, body: &[u8]) -> Result<Vec<ModelInfo>> {
+        let list: ModelList = serde_json::from_slice(body)?;
+        Ok(list.data)
+    }
+
+    /// Request body for `request`, with defaults already applied.
+    fn chat_body(&self, request: ChatRequest) -> Result<Value>;
+
+    /// Read a (non-streamed) reply.
+    fn parse_chat(&self, body: &[u8]) -> Result<ChatResponse>;
+
+    /// How streamed replies are framed.
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::Sse
+    }
+
+    /// Read one streamed event.
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent>;
+}
+
+/// Which [`LlmProvider`] to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProviderKind {
+    /// OpenAI-compatible chat completions
+    #[default]
+    OpenAi,
+    /// llama.cpp's native completion API
+    LlamaCpp,
+    /// Ollama's chat API
+    Ollama,
+}
+
+impl ProviderKind {
+    /// Create the provider.
+    pub fn provider(&self) -> Arc<dyn LlmProvider> {
+        match self {
+            ProviderKind::OpenAi => Arc::new(OpenAiProvider),
+            ProviderKind::LlamaCpp => Arc::new(LlamaCppProvider),
+            ProviderKind::Ollama => Arc::new(OllamaProvider),
+        }
+    }
+}
+
+impl std::fmt::Display for ProviderKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProviderKind::OpenAi => write!(f, "openai"),
+            ProviderKind::LlamaCpp => write!(f, "llamacpp"),
+            ProviderKind::Ollama => write!(f, "ollama"),
+        }
+    }
+}
+
+impl std::str::FromStr for ProviderKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "openai" | "oai" => Ok(ProviderKind::OpenAi),
+            "llamacpp" | "llama.cpp" | "llama-cpp" => Ok(ProviderKind::LlamaCpp),
+            "ollama" => Ok(ProviderKind::Ollama),
+            _ => Err(format!(
+                "Unknown provider: {} (expected openai, llamacpp or ollama)",
+                s
+            )),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Message;
+
+    fn request() -> ChatRequest {
+        ChatRequest::new(vec![Message::system("Be brief."), Message::user("2 + 2?")]).max_tokens(32)
+    }
+
+    #[test]
+    fn test_provider_kind() {
+        assert_eq!("openai".parse(), Ok(ProviderKind::OpenAi));
+        assert_eq!("llama.cpp".parse(), Ok(ProviderKind::LlamaCpp));
+        assert_eq!("Ollama".parse(), Ok(ProviderKind::Ollama));
+        assert!("claude".parse::<ProviderKind>().is_err());
+        assert_eq!(ProviderKind::LlamaCpp.to_string(), "llamacpp");
+        assert_eq!(ProviderKind::default().provider().name(), "openai");
+    }
+
+    #[test]
+    fn test_openai_provider() {
+        let provider = OpenAiProvider;
+        let body = provider.chat_body(request()).unwrap();
+        assert_eq!(body["messages"][1]["content"], "2 + 2?");
+        assert_eq!(body["max_tokens"], 32);
+
+        let reply = provider
+            .parse_chat(br#"{"choices":[{"message":{"role":"assistant","content":"4"}}]}"#)
+            .unwrap();
+        assert_eq!(reply.content(), Some("4"));
+        assert_eq!(
+            provider
+                .parse_stream_event(r#"{"choices":[{"delta":{"content":"4"}}]}"#)
+                .unwrap(),
+            StreamEvent::Token("4".to_string())
+        );
+        assert_eq!(
+            provider.parse_stream_event("[DONE]").unwrap(),
+            StreamEvent::Done
+        );
+
+        let models = provider
+            .parse_models(br#"{"object":"list","data":[{"id":"bitnet","owned_by":"neuro"}]}"#)
+            .unwrap();
+        assert_eq!(models[0].id, "bitnet");
+        assert_eq!(models[0].owned_by.as_deref(), Some("neuro"));
+    }
+
+    #[test]
+    fn test_llamacpp_provider() {
+        let provider = LlamaCppProvider;
+        let body = provider.chat_body(request()).unwrap();
+        assert_eq!(
+            body["prompt"],
+            "System: Be brief.\nUser: 2 + 2?\nAssistant:"
+        );
+        assert_eq!(body["n_predict"], 32);
+        assert!(body.get("json_schema").is_none());
+
+        let schema = serde_json::json!({"type": "object"});
+        let format = crate::ResponseFormat::json_schema("answer", schema.clone());
+        let body = provider
+            .chat_body(request().response_format(format))
+            .unwrap();
+        assert_eq!(body["json_schema"], schema);
+
+        let reply = provider
+            .parse_chat(br#"{"content":" 4","stop":true}"#)
+            .unwrap();
+        assert_eq!(reply.content(), Some("4"));
+        assert_eq!(
+            provider
+                .parse_stream_event(r#"{"content":"","stop":true}"#)
+                .unwrap(),
+            StreamEvent::Done
+        );
+    }
+
+    #[test]
+    fn test_ollama_provider() {
+        let provider = OllamaProvider;
+        let body = provider.chat_body(request().model("llama3")).unwrap();
+        assert_eq!(body["model"], "llama3");
+        assert_eq!(body["options"]["num_predict"], 32);
+        assert_eq!(body["stream"], false);
+        assert_eq!(provider.stream_format(), StreamFormat::JsonLines);
+
+        let body = provider
+            .chat_body(request().response_format(crate::ResponseFormat::JsonObject))
+            .unwrap();
+        assert_eq!(body["format"], "json");
+
+        let reply = provider
+            .parse_chat(
+                br#"{"message":{"role":"assistant","content":"",
+                    "tool_calls":[{"function":{"name":"add","arguments":{"a":2,"b":2}}}]},
+                    "done":true,"prompt_eval_count":10,"eval_count":3}"#,
+            )
+            .unwrap();
+        let models = provider
+            .parse_models(br#"{"models":[{"name":"llama3:latest","size":1}]}"#)
+            .unwrap();
+        assert_eq!(models[0].id, "llama3:latest");
+
+        let call = &reply.tool_calls()[0];
+        assert_eq!(call.function.name, "add");
+        assert_eq!(call.function.arguments, r#"{"a":2,"b":2}"#);
+        assert_eq!(reply.usage.unwrap().total_tokens, 13);
+
+        assert_eq!(
+            provider
+                .parse_stream_event(
+                    r#"{"message":{"role":"assistant","content":"4"},"done":false}"#
+                )
+                .unwrap(),
+            StreamEvent::Token("4".to_string())
+        );
+    }
+}
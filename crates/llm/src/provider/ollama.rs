@@ -0,0 +1,189 @@
+//! Ollama's chat API.
+
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+use super::{LlmProvider, StreamEvent, StreamFormat};
+use crate::error::Result;
+use crate::structured::ResponseFormat;
+use crate::tools::{FunctionCall, ToolCall};
+use crate::types::{ChatRequest, ChatResponse, Message, ModelInfo, Role, Usage};
+
+/// Ollama's `/api/chat`.
+///
+/// Set [`LlmConfig::model`](crate::LlmConfig::model) to a model pulled into
+/// Ollama, e.g. `llama3.2`. Supports tools on models that do.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OllamaProvider;
+
+#[derive(Deserialize)]
+struct OllamaResponse {
+    message: Option<OllamaMessage>,
+    #[serde(default)]
+    done: bool,
+    done_reason: Option<String>,
+    prompt_eval_count: Option<u32>,
+    eval_count: Option<u32>,
+}
+
+#[derive(Deserialize)]
+struct OllamaMessage {
+    role: Role,
+    #[serde(default)]
+    content: String,
+    tool_calls: Option<Vec<OllamaToolCall>>,
+}
+
+#[derive(Deserialize)]
+struct OllamaToolCall {
+    function: OllamaFunction,
+}
+
+#[derive(Deserialize)]
+struct OllamaFunction {
+    name: String,
+    arguments: Value,
+}
+
+#[derive(Deserialize)]
+struct OllamaTags {
+    models: Vec<OllamaModel>,
+}
+
+#[derive(Deserialize)]
+struct OllamaModel {
+    name: String,
+}
+
+/// Ollama wants tool call arguments as objects, not JSON strings
+fn message_json(message: &Message) -> Value {
+    let mut value = json!({ "role": message.role, "content": message.content });
+    if let Some(calls) = &message.tool_calls {
+        value["tool_calls"] = calls
+            .iter()
+            .map(|call| {
+                let arguments = serde_json::from_str(&call.function.arguments)
+                    .unwrap_or_else(|_| Value::Object(Map::new()));
+                json!({ "function": { "name": call.function.name, "arguments": arguments } })
+            })
+            .collect();
+    }
+    value
+}
+
+impl LlmProvider for OllamaProvider {
+    fn name(&self) -> &'static str {
+        "ollama"
+    }
+
+    fn chat_path(&self) -> &'static str {
+        "/api/chat"
+    }
+
+    fn health_path(&self) -> &'static str {
+        "/api/tags"
+    }
+
+    fn models_path(&self) -> &'static str {
+        "/api/tags"
+    }
+
+    fn parse_models(&self, body: &[u8]) -> Result<Vec<ModelInfo>> {
+        let tags: OllamaTags = serde_json::from_slice(body)?;
+        Ok(tags
+            .models
+            .into_iter()
+            .map(|model| ModelInfo {
+                id: model.name,
+                owned_by: None,
+                created: None,
+            })
+            .collect())
+    }
+
+    fn chat_body(&self, request: ChatRequest) -> Result<Value> {
+        let mut options = Map::new();
+        if let Some(max_tokens) = request.max_tokens {
+            options.insert("num_predict".to_string(), json!(max_tokens));
+        }
+        if let Some(temperature) = request.temperature {
+            options.insert("temperature".to_string(), json!(temperature));
+        }
+        if let Some(top_p) = request.top_p {
+            options.insert("top_p".to_string(), json!(top_p));
+        }
+        if let Some(stop) = &request.stop {
+            options.insert("stop".to_string(), json!(stop));
+        }
+
+        let mut body = json!({
+            "model": request.model,
+            "messages": request.messages.iter().map(message_json).collect::<Vec<_>>(),
+            "stream": request.stream.unwrap_or(false),
+            "options": options,
+        });
+        if let Some(tools) = &request.tools {
+            body["tools"] = serde_json::to_value(tools)?;
+        }
+        match &request.response_format {
+            Some(ResponseFormat::JsonObject) => body["format"] = json!("json"),
+            Some(format) => {
+                if let Some(schema) = format.schema() {
+                    body["format"] = schema.clone();
+                }
+            }
+            None => {}
+        }
+        Ok(body)
+    }
+
+    fn parse_chat(&self, body: &[u8]) -> Result<ChatResponse> {
+        let response: OllamaResponse = serde_json::from_slice(body)?;
+        let mut message = Message::assistant("");
+        if let Some(reply) = response.message {
+            message = Message::new(reply.role, reply.content.trim());
+            message.tool_calls = reply.tool_calls.map(|calls| {
+                calls
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, call)| ToolCall {
+                        id: format!("call_{}", i),
+                        kind: "function".to_string(),
+                        function: FunctionCall {
+                            name: call.function.name,
+                            arguments: call.function.arguments.to_string(),
+                        },
+                    })
+                    .collect()
+            });
+        }
+
+        let mut reply = ChatResponse::from_message(message);
+        reply.choices[0].finish_reason = response.done_reason;
+        if let (Some(prompt), Some(completion)) = (response.prompt_eval_count, response.eval_count)
+        {
+            reply.usage = Some(Usage {
+                prompt_tokens: prompt,
+                completion_tokens: completion,
+                total_tokens: prompt + completion,
+            });
+        }
+        Ok(reply)
+    }
+
+    fn stream_format(&self) -> StreamFormat {
+        StreamFormat::JsonLines
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent> {
+        let chunk: OllamaResponse = serde_json::from_str(data)?;
+        let content = chunk.message.map(|m| m.content).unwrap_or_default();
+        Ok(if !content.is_empty() {
+            StreamEvent::Token(content)
+        } else if chunk.done {
+            StreamEvent::Done
+        } else {
+            StreamEvent::Skip
+        })
+    }
+}
@@ -0,0 +1,43 @@
+//! OpenAI-compatible chat completions.
+
+use serde_json::Value;
+
+use super::{LlmProvider, StreamEvent};
+use crate::error::Result;
+use crate::types::{ChatChunk, ChatRequest, ChatResponse};
+
+/// OpenAI-compatible `/v1/chat/completions`.
+///
+/// Served by neuro-daemon, llama-server, vLLM and most hosted APIs.
+/// Supports tools on servers that implement function calling.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct OpenAiProvider;
+
+impl LlmProvider for OpenAiProvider {
+    fn name(&self) -> &'static str {
+        "openai"
+    }
+
+    fn chat_path(&self) -> &'static str {
+        "/v1/chat/completions"
+    }
+
+    fn chat_body(&self, request: ChatRequest) -> Result<Value> {
+        Ok(serde_json::to_value(request)?)
+    }
+
+    fn parse_chat(&self, body: &[u8]) -> Result<ChatResponse> {
+        Ok(serde_json::from_slice(body)?)
+    }
+
+    fn parse_stream_event(&self, data: &str) -> Result<StreamEvent> {
+        if data == "[DONE]" {
+            return Ok(StreamEvent::Done);
+        }
+        let chunk: ChatChunk = serde_json::from_str(data)?;
+        Ok(match chunk.content() {
+            Some(content) if !content.is_empty() => StreamEvent::Token(content.to_string()),
+            _ => StreamEvent::Skip,
+        })
+    }
+}
@@ -0,0 +1,121 @@
+//! Retry policy for LLM requests.
+
+use std::time::Duration;
+
+/// When and how often to retry failed requests.
+///
+/// Connection errors, timeouts and the status codes in
+/// [`retry_statuses`](Self::retry_statuses) are retried with exponential
+/// backoff. A `Retry-After` header from the server is honored, up to
+/// [`max_backoff`](Self::max_backoff).
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    /// Attempts in total, including the first; 1 disables retries
+    pub max_attempts: u32,
+    /// Wait before the first retry
+    pub initial_backoff: Duration,
+    /// Upper bound for any wait
+    pub max_backoff: Duration,
+    /// Factor the wait grows by after each retry
+    pub multiplier: f64,
+    /// HTTP status codes worth retrying
+    pub retry_statuses: Vec<u16>,
+    /// Retry completion requests (POST)
+    ///
+    /// Completions have no side effects on a plain inference server, so
+    /// they are retried by default. Turn this off for servers where each
+    /// request counts, e.g. metered APIs. Health checks are always retried.
+    pub retry_completions: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(8),
+            multiplier: 2.0,
+            retry_statuses: vec![408, 429, 500, 502, 503, 504],
+            retry_completions: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create the default policy: 3 attempts, 0.5s doubling to at most 8s.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Never retry.
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::default()
+        }
+    }
+
+    /// Set the total number of attempts.
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Set the first wait and the upper bound.
+    pub fn backoff(mut self, initial: Duration, max: Duration) -> Self {
+        self.initial_backoff = initial;
+        self.max_backoff = max;
+        self
+    }
+
+    /// Set the status codes worth retrying.
+    pub fn retry_statuses(mut self, statuses: impl Into<Vec<u16>>) -> Self {
+        self.retry_statuses = statuses.into();
+        self
+    }
+
+    /// Set whether completion requests are retried.
+    pub fn retry_completions(mut self, retry: bool) -> Self {
+        self.retry_completions = retry;
+        self
+    }
+
+    /// Whether a response with `status` should be retried.
+    pub fn is_retryable_status(&self, status: u16) -> bool {
+        self.retry_statuses.contains(&status)
+    }
+
+    /// Wait before retry number `retry` (1-based).
+    pub fn backoff_for(&self, retry: u32) -> Duration {
+        let factor = self.multiplier.max(1.0).powi(retry.saturating_sub(1) as i32);
+        let secs = (self.initial_backoff.as_secs_f64() * factor)
+            .min(self.max_backoff.as_secs_f64());
+        Duration::from_secs_f64(secs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_grows_to_max() {
+        let policy = RetryPolicy::new();
+        assert_eq!(policy.backoff_for(1), Duration::from_millis(500));
+        assert_eq!(policy.backoff_for(2), Duration::from_secs(1));
+        assert_eq!(policy.backoff_for(3), Duration::from_secs(2));
+        assert_eq!(policy.backoff_for(10), Duration::from_secs(8));
+    }
+
+    #[test]
+    fn test_retryable_statuses() {
+        let policy = RetryPolicy::new();
+        assert!(policy.is_retryable_status(502));
+        assert!(policy.is_retryable_status(429));
+        assert!(!policy.is_retryable_status(400));
+        assert!(!policy.is_retryable_status(404));
+
+        assert_eq!(RetryPolicy::none().max_attempts, 1);
+        assert_eq!(RetryPolicy::new().max_attempts(0).max_attempts, 1);
+    }
+}
@@ -0,0 +1,152 @@
+//! Structured (JSON) output.
+//!
+//! A [`ResponseFormat`] asks the server to constrain its reply to JSON,
+//! optionally matching a schema. Servers that ignore it still see the
+//! schema in the prompt when using
+//! [`LlmClient::chat_json`](crate::LlmClient::chat_json), which also parses
+//! the reply and asks again when it does not match.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::types::Message;
+
+/// Format the reply must take.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ResponseFormat {
+    /// Plain text
+    Text,
+    /// Any JSON object
+    JsonObject,
+    /// JSON matching a schema
+    JsonSchema {
+        /// Name and schema
+        json_schema: JsonSchemaFormat,
+    },
+}
+
+/// A named JSON schema for [`ResponseFormat::JsonSchema`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct JsonSchemaFormat {
+    /// Name of the schema
+    pub name: String,
+    /// The JSON schema
+    pub schema: Value,
+    /// Whether the server must follow the schema exactly
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub strict: Option<bool>,
+}
+
+impl ResponseFormat {
+    /// Require JSON matching `schema`.
+    pub fn json_schema(name: impl Into<String>, schema: Value) -> Self {
+        ResponseFormat::JsonSchema {
+            json_schema: JsonSchemaFormat {
+                name: name.into(),
+                schema,
+                strict: Some(true),
+            },
+        }
+    }
+
+    /// Require JSON that deserializes to `T`.
+    #[cfg(feature = "schemars")]
+    pub fn for_type<T: schemars::JsonSchema>() -> Self {
+        Self::json_schema(T::schema_name(), schema_for::<T>())
+    }
+
+    /// The schema, if one is required.
+    pub fn schema(&self) -> Option<&Value> {
+        match self {
+            ResponseFormat::JsonSchema { json_schema } => Some(&json_schema.schema),
+            _ => None,
+        }
+    }
+}
+
+/// JSON schema of `T`, without the `$schema` and `title` keys models
+/// don't need.
+#[cfg(feature = "schemars")]
+pub(crate) fn schema_for<T: schemars::JsonSchema>() -> Value {
+    let mut schema = schemars::schema_for!(T).to_value();
+    if let Some(object) = schema.as_object_mut() {
+        object.remove("$schema");
+        object.remove("title");
+    }
+    schema
+}
+
+/// `messages` with an instruction to reply with JSON matching `schema`,
+/// added to the system prompt.
+pub(crate) fn with_schema_prompt(messages: &[Message], schema: &Value) -> Vec<Message> {
+    let instruction = format!(
+        "Reply with only a JSON value matching this JSON schema, without \
+         explanations or code fences:\n{}",
+        schema
+    );
+
+    let mut messages = messages.to_vec();
+    match messages.first_mut() {
+        Some(system) if system.role == crate::types::Role::System => {
+            system.content = format!("{}\n\n{}", system.content, instruction);
+        }
+        _ => messages.insert(0, Message::system(instruction)),
+    }
+    messages
+}
+
+/// The JSON in a reply, skipping code fences and text around it.
+pub(crate) fn extract_json(reply: &str) -> &str {
+    let start = reply.find(['{', '[']);
+    let end = reply.rfind(['}', ']']);
+    match (start, end) {
+        (Some(start), Some(end)) if start < end => &reply[start..=end],
+        _ => reply.trim(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_response_format_serialization() {
+        let format = ResponseFormat::json_schema("answer", json!({"type": "object"}));
+        assert_eq!(
+            serde_json::to_value(&format).unwrap(),
+            json!({
+                "type": "json_schema",
+                "json_schema": {"name": "answer", "schema": {"type": "object"}, "strict": true}
+            })
+        );
+        assert_eq!(
+            serde_json::to_value(ResponseFormat::JsonObject).unwrap(),
+            json!({"type": "json_object"})
+        );
+        assert_eq!(format.schema(), Some(&json!({"type": "object"})));
+    }
+
+    #[test]
+    fn test_extract_json() {
+        assert_eq!(extract_json("```json\n{\"a\": 1}\n```"), "{\"a\": 1}");
+        assert_eq!(extract_json("Sure: [1, 2]."), "[1, 2]");
+        assert_eq!(extract_json(" 42 "), "42");
+    }
+
+    #[test]
+    fn test_with_schema_prompt() {
+        let schema = json!({"type": "object"});
+        let messages = with_schema_prompt(&[Message::user("Hi")], &schema);
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].content.contains(r#"{"type":"object"}"#));
+
+        let messages = with_schema_prompt(
+            &[Message::system("Be brief."), Message::user("Hi")],
+            &schema,
+        );
+        assert_eq!(messages.len(), 2);
+        assert!(messages[0].content.starts_with("Be brief.\n\n"));
+    }
+}
@@ -0,0 +1,214 @@
+//! Function calling (tools) for OpenAI-compatible APIs.
+//!
+//! Tools are described with a JSON schema of their arguments and sent with
+//! a [`ChatRequest`](crate::ChatRequest). Servers that support function
+//! calling answer with [`ToolCall`]s instead of (or besides) text; servers
+//! that do not simply reply with text, so callers should check
+//! [`ChatResponse::tool_calls`](crate::ChatResponse::tool_calls) and fall
+//! back to the content.
+
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize, Serializer};
+use serde_json::Value;
+
+/// A tool the model may call.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Tool {
+    /// Tool type; always `function`
+    #[serde(rename = "type")]
+    pub kind: String,
+    /// The function definition
+    pub function: FunctionDefinition,
+}
+
+impl Tool {
+    /// Define a function tool with a JSON schema for its arguments.
+    pub fn function(
+        name: impl Into<String>,
+        description: impl Into<String>,
+        parameters: Value,
+    ) -> Self {
+        Self {
+            kind: "function".to_string(),
+            function: FunctionDefinition {
+                name: name.into(),
+                description: Some(description.into()),
+                parameters,
+            },
+        }
+    }
+
+    /// Define a function tool whose arguments deserialize to `T`.
+    ///
+    /// The schema is generated from `T`'s `JsonSchema` implementation, which
+    /// can be derived with `#[derive(schemars::JsonSchema)]`.
+    #[cfg(feature = "schemars")]
+    pub fn for_type<T: schemars::JsonSchema>(
+        name: impl Into<String>,
+        description: impl Into<String>,
+    ) -> Self {
+        Self::function(name, description, crate::structured::schema_for::<T>())
+    }
+
+    /// The function name.
+    pub fn name(&self) -> &str {
+        &self.function.name
+    }
+}
+
+/// Name, description and argument schema of a function tool.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionDefinition {
+    /// Function name the model calls
+    pub name: String,
+    /// What the function does, shown to the model
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// JSON schema of the arguments
+    pub parameters: Value,
+}
+
+/// Whether and which tools the model should call.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum ToolChoice {
+    /// Never call tools
+    None,
+    /// Let the model decide
+    #[default]
+    Auto,
+    /// Call at least one tool
+    Required,
+    /// Call the named function
+    Function(String),
+}
+
+impl Serialize for ToolChoice {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            ToolChoice::None => serializer.serialize_str("none"),
+            ToolChoice::Auto => serializer.serialize_str("auto"),
+            ToolChoice::Required => serializer.serialize_str("required"),
+            ToolChoice::Function(name) => serde_json::json!({
+                "type": "function",
+                "function": { "name": name },
+            })
+            .serialize(serializer),
+        }
+    }
+}
+
+/// A call to a tool requested by the model.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolCall {
+    /// Call ID, echoed back in the tool's result message
+    pub id: String,
+    /// Tool type; always `function`
+    #[serde(rename = "type", default = "function_kind")]
+    pub kind: String,
+    /// The function to call
+    pub function: FunctionCall,
+}
+
+fn function_kind() -> String {
+    "function".to_string()
+}
+
+/// Function name and arguments of a [`ToolCall`].
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FunctionCall {
+    /// Function name
+    pub name: String,
+    /// Arguments as a JSON string
+    pub arguments: String,
+}
+
+impl FunctionCall {
+    /// Parse the arguments into `T`.
+    pub fn parse_arguments<T: DeserializeOwned>(&self) -> serde_json::Result<T> {
+        serde_json::from_str(&self.arguments)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{ChatRequest, ChatResponse, Message, Role};
+    use serde_json::json;
+
+    #[test]
+    fn test_request_with_tools() {
+        let weather = Tool::function(
+            "get_weather",
+            "Current weather for a city",
+            json!({
+                "type": "object",
+                "properties": { "city": { "type": "string" } },
+                "required": ["city"],
+            }),
+        );
+        let request = ChatRequest::new(vec![Message::user("Weather in Lima?")])
+            .tools(vec![weather])
+            .tool_choice(ToolChoice::Function("get_weather".to_string()));
+
+        let value = serde_json::to_value(&request).unwrap();
+        assert_eq!(value["tools"][0]["type"], "function");
+        assert_eq!(value["tools"][0]["function"]["name"], "get_weather");
+        assert_eq!(value["tool_choice"]["function"]["name"], "get_weather");
+        assert!(value["messages"][0].get("tool_calls").is_none());
+
+        let auto = ChatRequest::new(vec![]).tool_choice(ToolChoice::Auto);
+        assert_eq!(serde_json::to_value(&auto).unwrap()["tool_choice"], "auto");
+    }
+
+    #[test]
+    fn test_response_with_tool_calls() {
+        let response: ChatResponse = serde_json::from_value(json!({
+            "choices": [{
+                "index": 0,
+                "message": {
+                    "role": "assistant",
+                    "content": null,
+                    "tool_calls": [{
+                        "id": "call_1",
+                        "type": "function",
+                        "function": { "name": "get_weather", "arguments": "{\"city\":\"Lima\"}" },
+                    }],
+                },
+                "finish_reason": "tool_calls",
+            }],
+        }))
+        .unwrap();
+
+        let calls = response.tool_calls();
+        assert_eq!(calls.len(), 1);
+        let args: Value = calls[0].function.parse_arguments().unwrap();
+        assert_eq!(args["city"], "Lima");
+        assert_eq!(response.content(), Some(""));
+
+        let result = Message::tool("call_1", "18°C, cloudy");
+        assert_eq!(result.role, Role::Tool);
+        assert_eq!(
+            serde_json::to_value(&result).unwrap()["tool_call_id"],
+            "call_1"
+        );
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn test_tool_for_type() {
+        #[allow(dead_code)]
+        #[derive(schemars::JsonSchema, Deserialize)]
+        struct WeatherArgs {
+            /// City name
+            city: String,
+            days: Option<u32>,
+        }
+
+        let tool = Tool::for_type::<WeatherArgs>("get_weather", "Weather forecast");
+        let parameters = &tool.function.parameters;
+        assert_eq!(parameters["type"], "object");
+        assert_eq!(parameters["properties"]["city"]["description"], "City name");
+        assert_eq!(parameters["required"], json!(["city"]));
+        assert!(parameters.get("$schema").is_none());
+    }
+}
@@ -1,6 +1,9 @@
 //! Types for OpenAI-compatible API requests and responses.
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::structured::ResponseFormat;
+use crate::tools::{Tool, ToolCall, ToolChoice};
 
 /// Role in a chat conversation.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
@@ -12,6 +15,8 @@ pub enum Role {
     User,
     /// Assistant message (model response)
     Assistant,
+    /// Result of a tool call
+    Tool,
 }
 
 /// A message in a chat conversation.
@@ -19,8 +24,19 @@ pub enum Role {
 pub struct Message {
     /// The role of the message author
     pub role: Role,
-    /// The content of the message
+    /// The content of the message; empty when the server sent `null`
+    #[serde(default, deserialize_with = "null_as_empty")]
     pub content: String,
+    /// Tools the assistant asked to call
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_calls: Option<Vec<ToolCall>>,
+    /// The call a tool message answers
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tool_call_id: Option<String>,
+}
+
+fn null_as_empty<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    Ok(Option::<String>::deserialize(deserializer)?.unwrap_or_default())
 }
 
 impl Message {
@@ -29,6 +45,8 @@ impl Message {
         Self {
             role,
             content: content.into(),
+            tool_calls: None,
+            tool_call_id: None,
         }
     }
 
@@ -46,6 +64,22 @@ impl Message {
     pub fn assistant(content: impl Into<String>) -> Self {
         Self::new(Role::Assistant, content)
     }
+
+    /// Create an assistant message that calls tools.
+    pub fn assistant_tool_calls(tool_calls: Vec<ToolCall>) -> Self {
+        Self {
+            tool_calls: Some(tool_calls),
+            ..Self::new(Role::Assistant, "")
+        }
+    }
+
+    /// Create a message with the result of the tool call `tool_call_id`.
+    pub fn tool(tool_call_id: impl Into<String>, content: impl Into<String>) -> Self {
+        Self {
+            tool_call_id: Some(tool_call_id.into()),
+            ..Self::new(Role::Tool, content)
+        }
+    }
 }
 
 /// Request for chat completion (OpenAI-compatible).
@@ -70,6 +104,15 @@ pub struct ChatRequest {
     /// Stop sequences
     #[serde(skip_serializing_if = "Option::is_none")]
     pub stop: Option<Vec<String>>,
+    /// Tools the model may call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<Tool>>,
+    /// Whether and which tools to call
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool_choice: Option<ToolChoice>,
+    /// Format the reply must take
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub response_format: Option<ResponseFormat>,
 }
 
 impl ChatRequest {
@@ -83,6 +126,9 @@ impl ChatRequest {
             top_p: None,
             stream: Some(false),
             stop: None,
+            tools: None,
+            tool_choice: None,
+            response_format: None,
         }
     }
 
@@ -115,6 +161,24 @@ impl ChatRequest {
         self.stream = Some(stream);
         self
     }
+
+    /// Offer tools to the model.
+    pub fn tools(mut self, tools: Vec<Tool>) -> Self {
+        self.tools = Some(tools);
+        self
+    }
+
+    /// Set whether and which tools to call.
+    pub fn tool_choice(mut self, tool_choice: ToolChoice) -> Self {
+        self.tool_choice = Some(tool_choice);
+        self
+    }
+
+    /// Set the format the reply must take.
+    pub fn response_format(mut self, response_format: ResponseFormat) -> Self {
+        self.response_format = Some(response_format);
+        self
+    }
 }
 
 /// Response from chat completion.
@@ -135,10 +199,36 @@ pub struct ChatResponse {
 }
 
 impl ChatResponse {
+    /// Create a response with a single message.
+    pub fn from_message(message: Message) -> Self {
+        Self {
+            id: None,
+            object: None,
+            created: None,
+            model: None,
+            choices: vec![Choice {
+                index: Some(0),
+                message: Some(message),
+                delta: None,
+                finish_reason: None,
+            }],
+            usage: None,
+        }
+    }
+
     /// Get the content of the first choice.
     pub fn content(&self) -> Option<&str> {
         self.choices.first().and_then(|c| c.message.as_ref()).map(|m| m.content.as_str())
     }
+
+    /// Get the tool calls of the first choice.
+    pub fn tool_calls(&self) -> &[ToolCall] {
+        self.choices
+            .first()
+            .and_then(|c| c.message.as_ref())
+            .and_then(|m| m.tool_calls.as_deref())
+            .unwrap_or_default()
+    }
 }
 
 /// A choice in the response.
@@ -154,6 +244,41 @@ pub struct Choice {
     pub finish_reason: Option<String>,
 }
 
+/// A chunk of a streamed chat completion.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChatChunk {
+    /// Choices updated by this chunk
+    pub choices: Vec<ChunkChoice>,
+}
+
+impl ChatChunk {
+    /// Get the new content of the first choice.
+    pub fn content(&self) -> Option<&str> {
+        self.choices.first().and_then(|c| c.delta.content.as_deref())
+    }
+}
+
+/// A choice in a streamed chunk.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ChunkChoice {
+    /// Index of this choice
+    pub index: Option<u32>,
+    /// What this chunk adds to the message
+    #[serde(default)]
+    pub delta: Delta,
+    /// Reason for finishing, set on the last chunk
+    pub finish_reason: Option<String>,
+}
+
+/// Incremental message content in a streamed chunk.
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Delta {
+    /// The role, sent with the first chunk
+    pub role: Option<Role>,
+    /// Content added by this chunk
+    pub content: Option<String>,
+}
+
 /// Token usage statistics.
 #[derive(Debug, Clone, Deserialize)]
 pub struct Usage {
@@ -165,6 +290,26 @@ pub struct Usage {
     pub total_tokens: u32,
 }
 
+/// A model the server serves.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+pub struct ModelInfo {
+    /// Name to send as the request's `model`
+    pub id: String,
+    /// Organization that owns the model
+    #[serde(default)]
+    pub owned_by: Option<String>,
+    /// Unix timestamp
+    #[serde(default)]
+    pub created: Option<u64>,
+}
+
+/// Response from `/v1/models`.
+#[derive(Debug, Clone, Deserialize)]
+pub struct ModelList {
+    /// Models the server serves
+    pub data: Vec<ModelInfo>,
+}
+
 /// Request for text generation (llama.cpp native).
 #[derive(Debug, Clone, Serialize)]
 pub struct GenerateRequest {
@@ -0,0 +1,152 @@
+//! Token and request accounting.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+use serde::Serialize;
+
+use crate::types::Usage;
+
+/// Counters shared by every client in the process
+static GLOBAL: UsageTracker = UsageTracker::new();
+
+/// Running totals, updated without locks.
+#[derive(Debug, Default)]
+pub(crate) struct UsageTracker {
+    requests: AtomicU64,
+    failures: AtomicU64,
+    prompt_tokens: AtomicU64,
+    completion_tokens: AtomicU64,
+    latency_ms: AtomicU64,
+    max_latency_ms: AtomicU64,
+}
+
+impl UsageTracker {
+    pub(crate) const fn new() -> Self {
+        Self {
+            requests: AtomicU64::new(0),
+            failures: AtomicU64::new(0),
+            prompt_tokens: AtomicU64::new(0),
+            completion_tokens: AtomicU64::new(0),
+            latency_ms: AtomicU64::new(0),
+            max_latency_ms: AtomicU64::new(0),
+        }
+    }
+
+    /// The counters of all clients in the process.
+    pub(crate) fn global() -> &'static Self {
+        &GLOBAL
+    }
+
+    /// Count a request that took `latency`, with the tokens the server
+    /// reported (if any).
+    pub(crate) fn record(&self, latency: Duration, usage: Option<&Usage>) {
+        let ms = latency.as_millis() as u64;
+        self.requests.fetch_add(1, Ordering::Relaxed);
+        self.latency_ms.fetch_add(ms, Ordering::Relaxed);
+        self.max_latency_ms.fetch_max(ms, Ordering::Relaxed);
+        if let Some(usage) = usage {
+            self.prompt_tokens
+                .fetch_add(usage.prompt_tokens as u64, Ordering::Relaxed);
+            self.completion_tokens
+                .fetch_add(usage.completion_tokens as u64, Ordering::Relaxed);
+        }
+    }
+
+    /// Count a failed request.
+    pub(crate) fn record_failure(&self) {
+        self.failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn snapshot(&self) -> UsageStats {
+        let requests = self.requests.load(Ordering::Relaxed);
+        let prompt_tokens = self.prompt_tokens.load(Ordering::Relaxed);
+        let completion_tokens = self.completion_tokens.load(Ordering::Relaxed);
+        let latency_ms = self.latency_ms.load(Ordering::Relaxed);
+        UsageStats {
+            requests,
+            failures: self.failures.load(Ordering::Relaxed),
+            prompt_tokens,
+            completion_tokens,
+            total_tokens: prompt_tokens + completion_tokens,
+            avg_latency_ms: if requests == 0 {
+                0.0
+            } else {
+                latency_ms as f64 / requests as f64
+            },
+            max_latency_ms: self.max_latency_ms.load(Ordering::Relaxed),
+        }
+    }
+
+    pub(crate) fn reset(&self) {
+        for counter in [
+            &self.requests,
+            &self.failures,
+            &self.prompt_tokens,
+            &self.completion_tokens,
+            &self.latency_ms,
+            &self.max_latency_ms,
+        ] {
+            counter.store(0, Ordering::Relaxed);
+        }
+    }
+}
+
+/// Request and token counts at one point in time.
+///
+/// Tokens are only counted when the server reports them; streamed replies
+/// usually don't.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct UsageStats {
+    /// Requests that got a reply
+    pub requests: u64,
+    /// Requests that failed after retries
+    pub failures: u64,
+    /// Prompt tokens
+    pub prompt_tokens: u64,
+    /// Completion tokens
+    pub completion_tokens: u64,
+    /// Prompt plus completion tokens
+    pub total_tokens: u64,
+    /// Mean time until the reply (or, for streams, until it starts)
+    pub avg_latency_ms: f64,
+    /// Slowest reply
+    pub max_latency_ms: u64,
+}
+
+/// Usage of one client and of all clients in the process.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize)]
+pub struct UsageReport {
+    /// This client and its clones
+    pub session: UsageStats,
+    /// Every client in the process
+    pub global: UsageStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tracker_totals() {
+        let tracker = UsageTracker::new();
+        let usage = Usage {
+            prompt_tokens: 10,
+            completion_tokens: 5,
+            total_tokens: 15,
+        };
+        tracker.record(Duration::from_millis(100), Some(&usage));
+        tracker.record(Duration::from_millis(300), None);
+        tracker.record_failure();
+
+        let stats = tracker.snapshot();
+        assert_eq!(stats.requests, 2);
+        assert_eq!(stats.failures, 1);
+        assert_eq!(stats.total_tokens, 15);
+        assert_eq!(stats.avg_latency_ms, 200.0);
+        assert_eq!(stats.max_latency_ms, 300);
+
+        tracker.reset();
+        assert_eq!(tracker.snapshot(), UsageStats::default());
+    }
+}
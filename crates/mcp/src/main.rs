@@ -4,8 +4,10 @@
 
 use clap::Parser;
 use std::path::PathBuf;
+use std::sync::Arc;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
 
+use neuro_inference::{ApiTranslator, DictionaryTranslator, TranslatorKind};
 use neuro_mcp::McpServer;
 
 #[derive(Parser, Debug)]
@@ -16,6 +18,18 @@ struct Args {
     #[arg(short, long, env = "NEURO_MODEL_PATH")]
     model: Option<PathBuf>,
 
+    /// How to translate non-English queries: dictionary, model or api
+    #[arg(long, default_value = "dictionary")]
+    translator: TranslatorKind,
+
+    /// Base URL of a LibreTranslate-compatible API, for --translator api
+    #[arg(long, env = "NEURO_TRANSLATE_API_URL")]
+    translate_api_url: Option<String>,
+
+    /// Key for the translation API
+    #[arg(long, env = "NEURO_TRANSLATE_API_KEY", hide_env_values = true)]
+    translate_api_key: Option<String>,
+
     /// Enable debug logging (writes to stderr)
     #[arg(short, long)]
     debug: bool,
@@ -67,6 +81,20 @@ async fn main() -> anyhow::Result<()> {
     }
 
     let server = McpServer::new(model_path.to_string_lossy().to_string());
+    let server = match args.translator {
+        TranslatorKind::Dictionary => server.with_translator(Arc::new(DictionaryTranslator::new())),
+        TranslatorKind::Model => server.with_model_translator(),
+        TranslatorKind::Api => {
+            let url = args.translate_api_url.ok_or_else(|| {
+                anyhow::anyhow!("--translator api needs --translate-api-url")
+            })?;
+            let mut translator = ApiTranslator::new(url);
+            if let Some(key) = args.translate_api_key {
+                translator = translator.with_api_key(key);
+            }
+            server.with_translator(Arc::new(translator))
+        }
+    };
     server.run().await?;
 
     Ok(())
@@ -3,6 +3,7 @@
 //! Handles JSON-RPC communication over stdio
 
 use std::io::{BufRead, Write};
+use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{debug, error, info};
 
@@ -10,15 +11,34 @@ use crate::{
     get_tools, execute_tool,
     protocol::*,
 };
+use neuro_inference::{DictionaryTranslator, Translator};
 
 /// MCP Server
 pub struct McpServer {
     model_path: String,
+    /// `None` translates with the model
+    translator: Option<Arc<dyn Translator>>,
 }
 
 impl McpServer {
+    /// Create a server that translates queries with the dictionaries
     pub fn new(model_path: String) -> Self {
-        Self { model_path }
+        Self {
+            model_path,
+            translator: Some(Arc::new(DictionaryTranslator::new())),
+        }
+    }
+
+    /// Translate non-English queries with `translator`
+    pub fn with_translator(mut self, translator: Arc<dyn Translator>) -> Self {
+        self.translator = Some(translator);
+        self
+    }
+
+    /// Translate non-English queries with the model
+    pub fn with_model_translator(mut self) -> Self {
+        self.translator = None;
+        self
     }
 
     /// Run the MCP server (stdio transport)
@@ -155,6 +175,12 @@ impl McpServer {
             None => anyhow::bail!("Missing parameters"),
         };
 
-        Ok(execute_tool(&params.name, params.arguments, &self.model_path).await)
+        Ok(execute_tool(
+            &params.name,
+            params.arguments,
+            &self.model_path,
+            self.translator.as_ref(),
+        )
+        .await)
     }
 }
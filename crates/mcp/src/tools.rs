@@ -3,25 +3,27 @@
 //! Available tools for the MCP server
 
 use serde_json::json;
+use std::sync::Arc;
 
 use crate::{CallToolResult, Tool};
 use neuro_inference::{
-    InferenceModel, InferenceConfig, GenerateOptions, SamplerConfig,
-    translation::{build_translation_prompt, detect_language, Language},
+    InferenceModel, InferenceConfig, GenerateOptions, ModelTranslator, SamplerConfig, Translator,
+    translation::{detect_language_with_confidence, Language, MIN_CONFIDENCE},
 };
+use tracing::debug;
 
 /// Get all available tools
 pub fn get_tools() -> Vec<Tool> {
     vec![
         Tool {
             name: "generate".to_string(),
-            description: "Generate text using BitNet model. Supports queries in English and Spanish.".to_string(),
+            description: "Generate text using BitNet model. Supports queries in English, Spanish, French, Portuguese and German.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "prompt": {
                         "type": "string",
-                        "description": "The prompt to generate text from. Can be in English or Spanish."
+                        "description": "The prompt to generate text from. Non-English prompts are translated first."
                     },
                     "max_tokens": {
                         "type": "integer",
@@ -39,7 +41,7 @@ pub fn get_tools() -> Vec<Tool> {
         },
         Tool {
             name: "translate".to_string(),
-            description: "Translate text to English using BitNet model.".to_string(),
+            description: "Translate text to English.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
@@ -53,13 +55,13 @@ pub fn get_tools() -> Vec<Tool> {
         },
         Tool {
             name: "ask".to_string(),
-            description: "Ask a question and get an answer. Automatically handles Spanish queries.".to_string(),
+            description: "Ask a question and get an answer. Automatically handles Spanish, French, Portuguese and German queries.".to_string(),
             input_schema: json!({
                 "type": "object",
                 "properties": {
                     "question": {
                         "type": "string",
-                        "description": "The question to ask. Can be in English or Spanish."
+                        "description": "The question to ask. Non-English questions are translated first."
                     },
                     "context": {
                         "type": "string",
@@ -92,21 +94,29 @@ pub fn get_tools() -> Vec<Tool> {
 }
 
 /// Execute a tool
+///
+/// Non-English queries are translated with `translator`, or with the model
+/// when it is `None`.
 pub async fn execute_tool(
     name: &str,
     arguments: serde_json::Value,
     model_path: &str,
+    translator: Option<&Arc<dyn Translator>>,
 ) -> CallToolResult {
     match name {
-        "generate" => execute_generate(arguments, model_path).await,
-        "translate" => execute_translate(arguments, model_path).await,
-        "ask" => execute_ask(arguments, model_path).await,
+        "generate" => execute_generate(arguments, model_path, translator).await,
+        "translate" => execute_translate(arguments, model_path, translator).await,
+        "ask" => execute_ask(arguments, model_path, translator).await,
         "summarize" => execute_summarize(arguments, model_path).await,
         _ => CallToolResult::error(format!("Unknown tool: {}", name)),
     }
 }
 
-async fn execute_generate(args: serde_json::Value, model_path: &str) -> CallToolResult {
+async fn execute_generate(
+    args: serde_json::Value,
+    model_path: &str,
+    translator: Option<&Arc<dyn Translator>>,
+) -> CallToolResult {
     let prompt = match args.get("prompt").and_then(|v| v.as_str()) {
         Some(p) => p,
         None => return CallToolResult::error("Missing required parameter: prompt".to_string()),
@@ -123,11 +133,10 @@ async fn execute_generate(args: serde_json::Value, model_path: &str) -> CallTool
         .unwrap_or(0.7) as f32;
 
     // Detect language and translate if needed
-    let lang = detect_language(prompt);
-    let english_prompt = if matches!(lang, Language::Spanish) {
-        let translation_prompt = build_translation_prompt(prompt);
-        match run_model(model_path, &translation_prompt, 256, temperature).await {
-            Ok(translated) => translated.trim().to_string(),
+    let lang = detect(prompt);
+    let english_prompt = if lang != Language::English {
+        match translate(prompt, lang, model_path, translator).await {
+            Ok(translated) => translated,
             Err(e) => return CallToolResult::error(format!("Translation failed: {}", e)),
         }
     } else {
@@ -140,20 +149,37 @@ async fn execute_generate(args: serde_json::Value, model_path: &str) -> CallTool
     }
 }
 
-async fn execute_translate(args: serde_json::Value, model_path: &str) -> CallToolResult {
+async fn execute_translate(
+    args: serde_json::Value,
+    model_path: &str,
+    translator: Option<&Arc<dyn Translator>>,
+) -> CallToolResult {
     let text = match args.get("text").and_then(|v| v.as_str()) {
         Some(t) => t,
         None => return CallToolResult::error("Missing required parameter: text".to_string()),
     };
 
-    let prompt = build_translation_prompt(text);
-    match run_model(model_path, &prompt, 256, 0.3).await {
+    // Text that is only weakly English may be in a language we cannot
+    // detect, so it is translated anyway
+    let detection = detect_language_with_confidence(text);
+    let lang = match detection.language {
+        Language::English if detection.confidence >= MIN_CONFIDENCE => {
+            return CallToolResult::text(text.to_string())
+        }
+        Language::English => Language::Other,
+        lang => lang,
+    };
+    match translate(text, lang, model_path, translator).await {
         Ok(result) => CallToolResult::text(result),
         Err(e) => CallToolResult::error(format!("Translation failed: {}", e)),
     }
 }
 
-async fn execute_ask(args: serde_json::Value, model_path: &str) -> CallToolResult {
+async fn execute_ask(
+    args: serde_json::Value,
+    model_path: &str,
+    translator: Option<&Arc<dyn Translator>>,
+) -> CallToolResult {
     let question = match args.get("question").and_then(|v| v.as_str()) {
         Some(q) => q,
         None => return CallToolResult::error("Missing required parameter: question".to_string()),
@@ -162,11 +188,10 @@ async fn execute_ask(args: serde_json::Value, model_path: &str) -> CallToolResul
     let context = args.get("context").and_then(|v| v.as_str());
 
     // Detect language and translate if needed
-    let lang = detect_language(question);
-    let english_question = if matches!(lang, Language::Spanish) {
-        let translation_prompt = build_translation_prompt(question);
-        match run_model(model_path, &translation_prompt, 256, 0.3).await {
-            Ok(translated) => translated.trim().to_string(),
+    let lang = detect(question);
+    let english_question = if lang != Language::English {
+        match translate(question, lang, model_path, translator).await {
+            Ok(translated) => translated,
             Err(e) => return CallToolResult::error(format!("Translation failed: {}", e)),
         }
     } else {
@@ -211,6 +236,40 @@ async fn execute_summarize(args: serde_json::Value, model_path: &str) -> CallToo
     }
 }
 
+/// Detect the language of a query
+fn detect(text: &str) -> Language {
+    let detection = detect_language_with_confidence(text);
+    debug!(
+        "Detected {:?} (confidence {:.2})",
+        detection.language, detection.confidence
+    );
+    detection.language
+}
+
+/// Translate `text` to English, loading the model only when `translator`
+/// is `None`
+async fn translate(
+    text: &str,
+    from: Language,
+    model_path: &str,
+    translator: Option<&Arc<dyn Translator>>,
+) -> anyhow::Result<String> {
+    let text = text.to_string();
+    let model_path = model_path.to_string();
+    let translator = translator.cloned();
+
+    let result = tokio::task::spawn_blocking(move || match translator {
+        Some(translator) => translator.translate(&text, from),
+        None => {
+            let model = InferenceModel::load(InferenceConfig::new(&model_path))?;
+            ModelTranslator::new(model).translate(&text, from)
+        }
+    })
+    .await??;
+
+    Ok(result)
+}
+
 /// Run the BitNet model
 async fn run_model(
     model_path: &str,
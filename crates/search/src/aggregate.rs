@@ -0,0 +1,332 @@
+//! Searching several providers at once
+//!
+//! [`AggregatedSearcher`] sends a query to every configured provider
+//! concurrently, drops duplicates (same page, or near-identical titles)
+//! and merges the lists with weighted reciprocal rank fusion, so results
+//! found by several providers, or ranked high by a trusted one, come first.
+
+use async_trait::async_trait;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+use url::Url;
+
+use crate::error::{Result, SearchError};
+use crate::fetcher::PageFetcher;
+use crate::result::WebSearchResult;
+use crate::searcher::WebSearcher;
+
+/// Rank offset of reciprocal rank fusion; dampens the lead of top ranks
+const RRF_K: f64 = 60.0;
+
+/// Word overlap above which two titles name the same thing
+const TITLE_SIMILARITY: f64 = 0.8;
+
+struct Provider {
+    searcher: Arc<dyn WebSearcher>,
+    weight: f64,
+}
+
+/// A result with its fused score
+struct Ranked {
+    result: WebSearchResult,
+    weight: f64,
+    score: f64,
+    url_key: String,
+    title_words: HashSet<String>,
+}
+
+/// Searches several providers and merges their results
+#[derive(Default)]
+pub struct AggregatedSearcher {
+    providers: Vec<Provider>,
+    fetcher: PageFetcher,
+}
+
+impl AggregatedSearcher {
+    /// Create an aggregator without providers
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a provider; results weigh in proportion to `weight`
+    pub fn with_searcher(mut self, searcher: Arc<dyn WebSearcher>, weight: f64) -> Self {
+        self.providers.push(Provider {
+            searcher,
+            weight: weight.max(0.0),
+        });
+        self
+    }
+
+    /// Fetch content with `fetcher` for results no provider claims
+    pub fn with_fetcher(mut self, fetcher: PageFetcher) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
+    /// Number of configured providers
+    pub fn len(&self) -> usize {
+        self.providers.len()
+    }
+
+    /// Whether no provider is configured
+    pub fn is_empty(&self) -> bool {
+        self.providers.is_empty()
+    }
+}
+
+/// Merge ranked lists, given with their provider weights, best first
+fn merge(lists: Vec<(f64, Vec<WebSearchResult>)>) -> Vec<WebSearchResult> {
+    let mut merged: Vec<Ranked> = Vec::new();
+
+    for (weight, results) in lists {
+        for (rank, result) in results.into_iter().enumerate() {
+            let score = weight / (RRF_K + rank as f64 + 1.0);
+            let url_key = url_key(&result.url);
+            let title_words = words(&result.title);
+
+            let duplicate = merged.iter_mut().find(|existing| {
+                existing.url_key == url_key
+                    || jaccard(&existing.title_words, &title_words) >= TITLE_SIMILARITY
+            });
+            match duplicate {
+                Some(existing) => {
+                    existing.score += score;
+                    // Keep the copy from the more trusted provider, or the one
+                    // that already has content
+                    if weight > existing.weight
+                        || (!existing.result.has_content() && result.has_content())
+                    {
+                        existing.result = result;
+                        existing.weight = weight;
+                    }
+                }
+                None => merged.push(Ranked {
+                    result,
+                    weight,
+                    score,
+                    url_key,
+                    title_words,
+                }),
+            }
+        }
+    }
+
+    merged.sort_by(|a, b| b.score.total_cmp(&a.score));
+    merged
+        .into_iter()
+        .map(|ranked| {
+            ranked
+                .result
+                .with_metadata("aggregate_score", ranked.score.into())
+        })
+        .collect()
+}
+
+/// URL without scheme, `www.`, fragment or trailing slash
+fn url_key(url: &str) -> String {
+    match Url::parse(url) {
+        Ok(url) => {
+            let host = url.host_str().unwrap_or_default();
+            let host = host.strip_prefix("www.").unwrap_or(host);
+            let mut key = format!("{}{}", host, url.path().trim_end_matches('/'));
+            if let Some(query) = url.query() {
+                key.push('?');
+                key.push_str(query);
+            }
+            key.to_lowercase()
+        }
+        Err(_) => url.trim().to_lowercase(),
+    }
+}
+
+fn words(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+#[async_trait]
+impl WebSearcher for AggregatedSearcher {
+    fn name(&self) -> &str {
+        "Aggregated"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        self.search_in_language(query, max_results, "auto").await
+    }
+
+    async fn search_in_language(
+        &self,
+        query: &str,
+        max_results: usize,
+        language: &str,
+    ) -> Result<Vec<WebSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(SearchError::InvalidQuery("Empty query".into()));
+        }
+
+        let mut tasks = JoinSet::new();
+        for (i, provider) in self.providers.iter().enumerate() {
+            let searcher = provider.searcher.clone();
+            let query = query.to_string();
+            let language = language.to_string();
+            tasks.spawn(async move {
+                let results = searcher
+                    .search_in_language(&query, max_results, &language)
+                    .await;
+                (i, results)
+            });
+        }
+
+        let mut lists = Vec::new();
+        let mut last_error = None;
+        while let Some(joined) = tasks.join_next().await {
+            match joined {
+                Ok((i, Ok(results))) => lists.push((i, results)),
+                Ok((i, Err(e))) => {
+                    warn!("{} search failed: {}", self.providers[i].searcher.name(), e);
+                    last_error = Some(e);
+                }
+                Err(e) => warn!("Search task failed: {}", e),
+            }
+        }
+
+        if lists.is_empty() {
+            return Err(last_error.unwrap_or_else(|| SearchError::NoResults(query.to_string())));
+        }
+
+        // Merge in provider order so ties don't depend on response timing
+        lists.sort_by_key(|(i, _)| *i);
+        let lists = lists
+            .into_iter()
+            .map(|(i, results)| (self.providers[i].weight, results))
+            .collect();
+
+        let mut results = merge(lists);
+        results.truncate(max_results);
+        debug!("Aggregated {} results", results.len());
+        Ok(results)
+    }
+
+    async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        let owner = self
+            .providers
+            .iter()
+            .find(|provider| provider.searcher.name() == result.source);
+        match owner {
+            Some(provider) => provider.searcher.fetch_content(result).await,
+            None => self.fetcher.fetch_content(result).await,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Fixed {
+        name: &'static str,
+        results: Vec<(&'static str, &'static str)>,
+    }
+
+    #[async_trait]
+    impl WebSearcher for Fixed {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn search(&self, _query: &str, _max_results: usize) -> Result<Vec<WebSearchResult>> {
+            if self.results.is_empty() {
+                return Err(SearchError::Timeout);
+            }
+            Ok(self
+                .results
+                .iter()
+                .map(|(title, url)| WebSearchResult::new(*title, *url, "", self.name))
+                .collect())
+        }
+
+        async fn fetch_content(&self, _result: &WebSearchResult) -> Result<String> {
+            Ok(self.name.to_string())
+        }
+    }
+
+    fn fixed(
+        name: &'static str,
+        results: Vec<(&'static str, &'static str)>,
+    ) -> Arc<dyn WebSearcher> {
+        Arc::new(Fixed { name, results })
+    }
+
+    #[test]
+    fn test_url_key() {
+        assert_eq!(
+            url_key("https://www.Example.com/docs/#intro"),
+            url_key("http://example.com/docs")
+        );
+        assert_ne!(
+            url_key("https://a.com/?id=1"),
+            url_key("https://a.com/?id=2")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_merges_and_deduplicates() {
+        let searcher = AggregatedSearcher::new()
+            .with_searcher(
+                fixed(
+                    "A",
+                    vec![
+                        ("Only in A", "https://a.com/1"),
+                        ("Rust ownership", "https://rust-lang.org/ownership"),
+                    ],
+                ),
+                1.0,
+            )
+            .with_searcher(
+                fixed(
+                    "B",
+                    vec![("Rust Ownership", "https://mirror.org/rust/ownership")],
+                ),
+                1.0,
+            )
+            .with_searcher(fixed("Down", vec![]), 1.0);
+
+        let results = searcher.search("ownership", 10).await.unwrap();
+        assert_eq!(results.len(), 2);
+        // Found by two providers, so it beats A's top result
+        assert_eq!(results[0].title.to_lowercase(), "rust ownership");
+        assert_eq!(results[1].title, "Only in A");
+    }
+
+    #[tokio::test]
+    async fn test_weights_rank_results() {
+        let searcher = AggregatedSearcher::new()
+            .with_searcher(fixed("A", vec![("From A", "https://a.com")]), 1.0)
+            .with_searcher(fixed("B", vec![("From B", "https://b.com")]), 2.0);
+
+        let results = searcher.search("query", 10).await.unwrap();
+        assert_eq!(results[0].source, "B");
+        assert_eq!(searcher.fetch_content(&results[1]).await.unwrap(), "A");
+    }
+
+    #[tokio::test]
+    async fn test_all_providers_failing() {
+        let searcher = AggregatedSearcher::new().with_searcher(fixed("Down", vec![]), 1.0);
+        assert!(matches!(
+            searcher.search("query", 3).await,
+            Err(SearchError::Timeout)
+        ));
+    }
+}
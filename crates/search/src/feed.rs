@@ -0,0 +1,641 @@
+//! RSS and Atom feeds
+//!
+//! [`FeedReader`] downloads and parses RSS 2.0, RSS 1.0 (RDF) and Atom
+//! feeds into [`FeedEntry`]s. [`FeedSubscriptions`] remembers which
+//! entries of each subscribed feed were already seen, so refreshing only
+//! returns new ones, ready to ingest as [`WebSearchResult`]s.
+
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::Path;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+use crate::error::{Result, SearchError};
+use crate::fetcher::{html_to_text, truncate};
+use crate::http::HttpConfig;
+use crate::result::WebSearchResult;
+
+/// Entry ids remembered per feed
+const MAX_SEEN: usize = 500;
+
+/// Longest entry summary kept as the snippet
+const SNIPPET_LENGTH: usize = 300;
+
+/// Feed reader configuration
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    /// Request timeout
+    pub timeout: Duration,
+    /// Most entries read from one feed
+    pub max_entries: usize,
+    /// Maximum entry content length
+    pub max_content_length: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_entries: 20,
+            max_content_length: 20000,
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+/// A parsed feed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Feed {
+    /// Feed title
+    pub title: Option<String>,
+    /// Entries, in feed order (usually newest first)
+    pub entries: Vec<FeedEntry>,
+}
+
+/// One feed item or entry
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FeedEntry {
+    /// Stable id (guid or id, else the link)
+    pub id: String,
+    /// Entry title
+    pub title: String,
+    /// Link to the full article
+    pub link: Option<String>,
+    /// Summary, as HTML or text
+    pub summary: Option<String>,
+    /// Full content, as HTML or text, if the feed includes it
+    pub content: Option<String>,
+    /// Publication date as written in the feed
+    pub published: Option<String>,
+}
+
+impl FeedEntry {
+    /// Convert to a search result from the feed titled `feed_title`
+    ///
+    /// Content included in the feed becomes the result content; otherwise
+    /// the article behind the link still needs fetching.
+    pub fn to_result(&self, feed_title: &str, max_content_length: usize) -> WebSearchResult {
+        let summary = self.summary.as_deref().or(self.content.as_deref());
+        let mut snippet = summary.map(html_to_text).unwrap_or_default();
+        truncate(&mut snippet, SNIPPET_LENGTH);
+
+        let url = self.link.clone().unwrap_or_else(|| self.id.clone());
+        let mut result = WebSearchResult::new(&self.title, url, snippet, feed_title)
+            .with_metadata("feed_entry_id", self.id.clone().into());
+        if let Some(published) = &self.published {
+            result = result.with_metadata("published", published.clone().into());
+        }
+        if let Some(content) = &self.content {
+            let mut content = html_to_text(content);
+            truncate(&mut content, max_content_length);
+            if !content.is_empty() {
+                result = result.with_content(content);
+            }
+        }
+        result
+    }
+}
+
+/// Downloads and parses feeds
+pub struct FeedReader {
+    client: Client,
+    config: FeedConfig,
+}
+
+impl FeedReader {
+    /// Create a new feed reader with default config
+    pub fn new() -> Self {
+        Self::with_config(FeedConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: FeedConfig) -> Self {
+        let client = config.http.client(config.timeout);
+
+        Self { client, config }
+    }
+
+    /// Download and parse the feed at `url`
+    pub async fn fetch(&self, url: &str) -> Result<Feed> {
+        debug!("Fetching feed: {}", url);
+        let body = self
+            .config
+            .http
+            .send(self.client.get(url))
+            .await?
+            .error_for_status()?
+            .text()
+            .await?;
+
+        let mut feed = parse_feed(&body)?;
+        feed.entries.truncate(self.config.max_entries);
+        Ok(feed)
+    }
+}
+
+impl Default for FeedReader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A subscribed feed and the entries already seen
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Subscription {
+    /// Feed URL
+    pub url: String,
+    /// Feed title, once fetched
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Ids of seen entries, oldest first
+    #[serde(default)]
+    pub seen: Vec<String>,
+}
+
+/// Feeds to follow, saved as JSON between runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FeedSubscriptions {
+    /// Subscribed feeds
+    #[serde(default)]
+    pub feeds: Vec<Subscription>,
+}
+
+impl FeedSubscriptions {
+    /// Load from `path`; a missing file means no subscriptions
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        match std::fs::read_to_string(path) {
+            Ok(json) => serde_json::from_str(&json).map_err(|e| SearchError::Parse(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(SearchError::Parse(e.to_string())),
+        }
+    }
+
+    /// Save to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let json =
+            serde_json::to_string_pretty(self).map_err(|e| SearchError::Parse(e.to_string()))?;
+        std::fs::write(path, json).map_err(|e| SearchError::Parse(e.to_string()))
+    }
+
+    /// Follow the feed at `url`; returns `false` if already subscribed
+    pub fn subscribe(&mut self, url: impl Into<String>) -> bool {
+        let url = url.into();
+        if self.feeds.iter().any(|feed| feed.url == url) {
+            return false;
+        }
+        self.feeds.push(Subscription {
+            url,
+            ..Default::default()
+        });
+        true
+    }
+
+    /// Stop following the feed at `url`; returns `false` if not subscribed
+    pub fn unsubscribe(&mut self, url: &str) -> bool {
+        let before = self.feeds.len();
+        self.feeds.retain(|feed| feed.url != url);
+        self.feeds.len() != before
+    }
+
+    /// Fetch every feed and return the entries not seen before, marking
+    /// them seen
+    ///
+    /// Feeds that fail to download are skipped and returned with their
+    /// error.
+    pub async fn refresh(
+        &mut self,
+        reader: &FeedReader,
+    ) -> (Vec<WebSearchResult>, Vec<(String, SearchError)>) {
+        let mut results = Vec::new();
+        let mut errors = Vec::new();
+
+        for subscription in &mut self.feeds {
+            let feed = match reader.fetch(&subscription.url).await {
+                Ok(feed) => feed,
+                Err(e) => {
+                    warn!("Failed to fetch feed {}: {}", subscription.url, e);
+                    errors.push((subscription.url.clone(), e));
+                    continue;
+                }
+            };
+            if feed.title.is_some() {
+                subscription.title = feed.title.clone();
+            }
+            let entries = subscription.take_new(feed.entries);
+            let feed_title = subscription.title.as_deref().unwrap_or(&subscription.url);
+            results.extend(
+                entries
+                    .iter()
+                    .map(|entry| entry.to_result(feed_title, reader.config.max_content_length)),
+            );
+        }
+
+        (results, errors)
+    }
+}
+
+impl Subscription {
+    /// Entries not seen before, marking them seen
+    fn take_new(&mut self, entries: Vec<FeedEntry>) -> Vec<FeedEntry> {
+        let seen: HashSet<&String> = self.seen.iter().collect();
+        let new: Vec<FeedEntry> = entries
+            .into_iter()
+            .filter(|entry| !seen.contains(&entry.id))
+            .collect();
+
+        // Feeds list newest first; remember oldest first
+        self.seen
+            .extend(new.iter().rev().map(|entry| entry.id.clone()));
+        if self.seen.len() > MAX_SEEN {
+            self.seen.drain(..self.seen.len() - MAX_SEEN);
+        }
+        new
+    }
+}
+
+/// Parse an RSS or Atom document
+pub fn parse_feed(xml: &str) -> Result<Feed> {
+    let mut feed = Feed::default();
+    let mut found_root = false;
+    let mut stack: Vec<String> = Vec::new();
+    let mut entry: Option<FeedEntry> = None;
+    let mut text = String::new();
+
+    let mut reader = XmlReader::new(xml);
+    while let Some(event) = reader.next_event()? {
+        match event {
+            XmlEvent::Start { name, attrs, empty } => {
+                let parent = stack.last().map(String::as_str);
+                match name {
+                    "rss" | "feed" | "RDF" if stack.is_empty() => found_root = true,
+                    "item" | "entry" => entry = Some(FeedEntry::default()),
+                    _ => {}
+                }
+                if matches!(parent, Some("item" | "entry" | "channel" | "feed")) {
+                    text.clear();
+                }
+                // Atom links are attributes
+                if let (Some(entry), "link", Some("entry")) = (entry.as_mut(), name, parent) {
+                    let rel = attr(&attrs, "rel");
+                    if matches!(rel, None | Some("alternate")) {
+                        if let Some(href) = attr(&attrs, "href") {
+                            entry.link.get_or_insert_with(|| href.to_string());
+                        }
+                    }
+                }
+                if !empty {
+                    stack.push(name.to_string());
+                }
+            }
+            XmlEvent::Text(content) => text.push_str(&content),
+            XmlEvent::End(name) => {
+                stack.pop();
+                let parent = stack.last().map(String::as_str);
+                let value = text.trim();
+                match (parent, entry.as_mut()) {
+                    (Some("item" | "entry"), Some(entry)) if !value.is_empty() => {
+                        set_field(entry, name, value)
+                    }
+                    (Some("channel" | "feed"), _) if name == "title" && !value.is_empty() => {
+                        feed.title = Some(value.to_string());
+                    }
+                    _ => {}
+                }
+                if matches!(name, "item" | "entry") {
+                    if let Some(mut entry) = entry.take() {
+                        if entry.id.is_empty() {
+                            entry.id = entry.link.clone().unwrap_or_else(|| entry.title.clone());
+                        }
+                        feed.entries.push(entry);
+                    }
+                }
+            }
+        }
+    }
+
+    if !found_root {
+        return Err(SearchError::Parse("Not an RSS or Atom feed".to_string()));
+    }
+    Ok(feed)
+}
+
+fn set_field(entry: &mut FeedEntry, name: &str, value: &str) {
+    let value = value.to_string();
+    match name {
+        "title" => entry.title = value,
+        "link" => {
+            entry.link.get_or_insert(value);
+        }
+        "guid" | "id" => entry.id = value,
+        "description" | "summary" => entry.summary = Some(value),
+        "encoded" | "content" => entry.content = Some(value),
+        "pubDate" | "published" | "date" => entry.published = Some(value),
+        "updated" => {
+            entry.published.get_or_insert(value);
+        }
+        _ => {}
+    }
+}
+
+fn attr<'a>(attrs: &'a [(&str, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| *key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// An XML event; names are local (without namespace prefix)
+#[derive(Debug, PartialEq)]
+enum XmlEvent<'a> {
+    Start {
+        name: &'a str,
+        attrs: Vec<(&'a str, String)>,
+        empty: bool,
+    },
+    End(&'a str),
+    Text(String),
+}
+
+/// A small pull parser, enough for feeds
+///
+/// Handles elements, attributes, CDATA, comments, processing instructions,
+/// doctypes and the predefined and numeric entities. It does not validate.
+struct XmlReader<'a> {
+    rest: &'a str,
+}
+
+impl<'a> XmlReader<'a> {
+    fn new(xml: &'a str) -> Self {
+        Self {
+            rest: xml.trim_start_matches('\u{feff}'),
+        }
+    }
+
+    fn next_event(&mut self) -> Result<Option<XmlEvent<'a>>> {
+        loop {
+            if self.rest.is_empty() {
+                return Ok(None);
+            }
+
+            if !self.rest.starts_with('<') {
+                let end = self.rest.find('<').unwrap_or(self.rest.len());
+                let text = &self.rest[..end];
+                self.rest = &self.rest[end..];
+                return Ok(Some(XmlEvent::Text(unescape(text))));
+            }
+
+            if let Some(rest) = self.rest.strip_prefix("<![CDATA[") {
+                let end = rest.find("]]>").ok_or_else(|| unterminated("CDATA"))?;
+                self.rest = &rest[end + 3..];
+                return Ok(Some(XmlEvent::Text(rest[..end].to_string())));
+            }
+            if let Some(rest) = self.rest.strip_prefix("<!--") {
+                let end = rest.find("-->").ok_or_else(|| unterminated("comment"))?;
+                self.rest = &rest[end + 3..];
+                continue;
+            }
+            if let Some(rest) = self.rest.strip_prefix("<?") {
+                let end = rest.find("?>").ok_or_else(|| unterminated("declaration"))?;
+                self.rest = &rest[end + 2..];
+                continue;
+            }
+            if self.rest.starts_with("<!") {
+                let end = self.rest.find('>').ok_or_else(|| unterminated("doctype"))?;
+                self.rest = &self.rest[end + 1..];
+                continue;
+            }
+
+            let end = tag_end(self.rest).ok_or_else(|| unterminated("tag"))?;
+            let tag = &self.rest[1..end];
+            self.rest = &self.rest[end + 1..];
+
+            if let Some(name) = tag.strip_prefix('/') {
+                return Ok(Some(XmlEvent::End(local_name(name.trim()))));
+            }
+
+            let (tag, empty) = match tag.strip_suffix('/') {
+                Some(tag) => (tag, true),
+                None => (tag, false),
+            };
+            let name_end = tag.find(char::is_whitespace).unwrap_or(tag.len());
+            return Ok(Some(XmlEvent::Start {
+                name: local_name(&tag[..name_end]),
+                attrs: parse_attrs(&tag[name_end..]),
+                empty,
+            }));
+        }
+    }
+}
+
+/// Index of the `>` closing the tag at the start of `s`, skipping quoted
+/// attribute values
+fn tag_end(s: &str) -> Option<usize> {
+    let mut quote = None;
+    for (i, c) in s.char_indices() {
+        match (quote, c) {
+            (None, '"' | '\'') => quote = Some(c),
+            (Some(q), _) if c == q => quote = None,
+            (None, '>') => return Some(i),
+            _ => {}
+        }
+    }
+    None
+}
+
+fn parse_attrs(mut s: &str) -> Vec<(&str, String)> {
+    let mut attrs = Vec::new();
+    loop {
+        s = s.trim_start();
+        let Some(eq) = s.find('=') else { break };
+        let name = s[..eq].trim();
+        let value = s[eq + 1..].trim_start();
+        let Some(quote) = value.chars().next().filter(|c| matches!(c, '"' | '\'')) else {
+            break;
+        };
+        let Some(close) = value[1..].find(quote) else {
+            break;
+        };
+        attrs.push((local_name(name), unescape(&value[1..close + 1])));
+        s = &value[close + 2..];
+    }
+    attrs
+}
+
+fn local_name(name: &str) -> &str {
+    name.rsplit(':').next().unwrap_or(name)
+}
+
+fn unterminated(what: &str) -> SearchError {
+    SearchError::Parse(format!("Unterminated {} in feed", what))
+}
+
+/// Decode the predefined and numeric entities; unknown ones are kept
+fn unescape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('&') {
+        out.push_str(&rest[..start]);
+        rest = &rest[start..];
+        let decoded = rest.find(';').filter(|&end| end <= 10).and_then(|end| {
+            let entity = &rest[1..end];
+            let c = match entity {
+                "lt" => Some('<'),
+                "gt" => Some('>'),
+                "amp" => Some('&'),
+                "quot" => Some('"'),
+                "apos" => Some('\''),
+                _ => entity
+                    .strip_prefix("#x")
+                    .or_else(|| entity.strip_prefix("#X"))
+                    .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                    .or_else(|| entity.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                    .and_then(char::from_u32),
+            };
+            c.map(|c| (c, end))
+        });
+        match decoded {
+            Some((c, end)) => {
+                out.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                out.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+    out.push_str(rest);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RSS: &str = r#"<?xml version="1.0" encoding="UTF-8"?>
+        <rss version="2.0" xmlns:content="http://purl.org/rss/1.0/modules/content/">
+        <channel>
+            <title>Rust Blog</title>
+            <link>https://blog.rust-lang.org/</link>
+            <item>
+                <title>Announcing Rust 1.80 &amp; more</title>
+                <link>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</link>
+                <guid>https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html</guid>
+                <pubDate>Thu, 25 Jul 2024 00:00:00 +0000</pubDate>
+                <description>&lt;p&gt;The Rust team is happy to announce 1.80.&lt;/p&gt;</description>
+                <content:encoded><![CDATA[<p>Full <code>LazyCell</code> post.</p>]]></content:encoded>
+            </item>
+            <item>
+                <title>Older post</title>
+                <link>https://blog.rust-lang.org/old.html</link>
+            </item>
+        </channel>
+        </rss>"#;
+
+    const ATOM: &str = r#"<?xml version="1.0" encoding="utf-8"?>
+        <feed xmlns="http://www.w3.org/2005/Atom">
+            <title type="text">Example Feed</title>
+            <link href="http://example.org/"/>
+            <entry>
+                <title>Atom-Powered Robots Run Amok</title>
+                <link rel="edit" href="http://example.org/edit/1"/>
+                <link href="http://example.org/2003/12/13/atom03"/>
+                <id>urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a</id>
+                <updated>2003-12-13T18:30:02Z</updated>
+                <summary>Some text.</summary>
+                <author><name>John Doe</name></author>
+            </entry>
+        </feed>"#;
+
+    #[test]
+    fn test_parse_rss() {
+        let feed = parse_feed(RSS).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Rust Blog"));
+        assert_eq!(feed.entries.len(), 2);
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title, "Announcing Rust 1.80 & more");
+        assert_eq!(
+            entry.id,
+            "https://blog.rust-lang.org/2024/07/25/Rust-1.80.0.html"
+        );
+        assert_eq!(
+            entry.summary.as_deref(),
+            Some("<p>The Rust team is happy to announce 1.80.</p>")
+        );
+        assert_eq!(
+            entry.published.as_deref(),
+            Some("Thu, 25 Jul 2024 00:00:00 +0000")
+        );
+
+        let result = entry.to_result("Rust Blog", 1000);
+        assert_eq!(result.snippet, "The Rust team is happy to announce 1.80.");
+        assert_eq!(result.content.as_deref(), Some("Full `LazyCell` post."));
+        assert_eq!(result.source, "Rust Blog");
+
+        // No guid: the link identifies the entry
+        assert_eq!(feed.entries[1].id, "https://blog.rust-lang.org/old.html");
+    }
+
+    #[test]
+    fn test_parse_atom() {
+        let feed = parse_feed(ATOM).unwrap();
+        assert_eq!(feed.title.as_deref(), Some("Example Feed"));
+
+        let entry = &feed.entries[0];
+        assert_eq!(entry.title, "Atom-Powered Robots Run Amok");
+        assert_eq!(
+            entry.link.as_deref(),
+            Some("http://example.org/2003/12/13/atom03")
+        );
+        assert_eq!(entry.id, "urn:uuid:1225c695-cfb8-4ebb-aaaa-80da344efa6a");
+        assert_eq!(entry.published.as_deref(), Some("2003-12-13T18:30:02Z"));
+        assert!(!entry.to_result("Example Feed", 1000).has_content());
+    }
+
+    #[test]
+    fn test_parse_rejects_non_feeds() {
+        assert!(parse_feed("<html><body>Not a feed</body></html>").is_err());
+        assert!(parse_feed("<rss><channel><title>Broken").is_ok());
+        assert!(parse_feed("<rss><![CDATA[never closed").is_err());
+    }
+
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape("a &lt;b&gt; &#65;&#x42; &nbsp; &"),
+            "a <b> AB &nbsp; &"
+        );
+    }
+
+    #[test]
+    fn test_subscription_returns_new_entries_once() {
+        let mut subscriptions = FeedSubscriptions::default();
+        assert!(subscriptions.subscribe("https://example.org/feed"));
+        assert!(!subscriptions.subscribe("https://example.org/feed"));
+
+        let entries = parse_feed(RSS).unwrap().entries;
+        let subscription = &mut subscriptions.feeds[0];
+        assert_eq!(subscription.take_new(entries.clone()).len(), 2);
+        assert!(subscription.take_new(entries).is_empty());
+        assert_eq!(subscription.seen[0], "https://blog.rust-lang.org/old.html");
+
+        assert!(subscriptions.unsubscribe("https://example.org/feed"));
+        assert!(subscriptions.feeds.is_empty());
+    }
+
+    // Integration test - requires network
+    #[tokio::test]
+    #[ignore = "Requires network"]
+    async fn test_fetch_integration() {
+        let feed = FeedReader::new()
+            .fetch("https://blog.rust-lang.org/feed.xml")
+            .await
+            .unwrap();
+        assert!(!feed.entries.is_empty());
+    }
+}
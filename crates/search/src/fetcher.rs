@@ -0,0 +1,538 @@
+//! Page fetching with main-content extraction
+//!
+//! Search providers return short snippets. [`PageFetcher`] downloads the
+//! page behind any result and keeps only its main text: navigation, ads,
+//! sidebars and other boilerplate are dropped, and code blocks keep their
+//! formatting.
+
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Client;
+use scraper::node::Node;
+use scraper::{ElementRef, Html, Selector};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::task::JoinSet;
+use tracing::{debug, warn};
+
+use crate::error::{Result, SearchError};
+use crate::http::HttpConfig;
+use crate::result::WebSearchResult;
+
+/// Elements that never hold article text
+const SKIPPED_TAGS: &[&str] = &[
+    "script", "style", "noscript", "template", "nav", "header", "footer", "aside", "form",
+    "button", "iframe", "svg", "canvas", "select", "head",
+];
+
+/// Class and id prefixes of boilerplate containers
+const BOILERPLATE_PREFIXES: &[&str] = &[
+    "nav",
+    "menu",
+    "sidebar",
+    "footer",
+    "comment",
+    "share",
+    "social",
+    "cookie",
+    "breadcrumb",
+    "related",
+    "advert",
+    "promo",
+    "banner",
+    "popup",
+    "newsletter",
+    "subscribe",
+];
+
+/// Block elements that end a paragraph
+const BLOCK_TAGS: &[&str] = &[
+    "p",
+    "div",
+    "section",
+    "article",
+    "main",
+    "li",
+    "dt",
+    "dd",
+    "blockquote",
+    "figcaption",
+    "table",
+    "tr",
+    "ul",
+    "ol",
+    "dl",
+    "br",
+    "hr",
+];
+
+/// Lists with more link text than this are treated as navigation
+const MAX_LIST_LINK_DENSITY: f64 = 0.8;
+
+/// Page fetcher configuration
+#[derive(Debug, Clone)]
+pub struct PageFetcherConfig {
+    /// Request timeout
+    pub timeout: Duration,
+    /// Maximum extracted text length, in bytes
+    pub max_content_length: usize,
+    /// Pages fetched at once by [`PageFetcher::fetch_results`]
+    pub max_concurrent: usize,
+    /// Largest PDF downloaded for text extraction, in bytes
+    pub max_pdf_size: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
+}
+
+impl Default for PageFetcherConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_content_length: 20000,
+            max_concurrent: 4,
+            max_pdf_size: 20 * 1024 * 1024,
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+/// Main text of a fetched page
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Article {
+    /// Page title, if the page has one
+    pub title: Option<String>,
+    /// Main text; paragraphs are separated by blank lines and code blocks
+    /// are fenced with ```
+    pub text: String,
+}
+
+/// Downloads arbitrary pages and extracts their main text
+#[derive(Clone)]
+pub struct PageFetcher {
+    client: Client,
+    config: PageFetcherConfig,
+}
+
+impl PageFetcher {
+    /// Create a new page fetcher with default config
+    pub fn new() -> Self {
+        Self::with_config(PageFetcherConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: PageFetcherConfig) -> Self {
+        let client = config.http.client(config.timeout);
+
+        Self { client, config }
+    }
+
+    /// Download `url` and extract its main text
+    ///
+    /// HTML pages go through boilerplate removal; plain text is returned
+    /// as is, and PDFs are converted to text (with the `pdf` feature).
+    /// Other content types are rejected.
+    pub async fn fetch(&self, url: &str) -> Result<Article> {
+        debug!("Fetching page: {}", url);
+
+        let response = self
+            .config
+            .http
+            .send(self.client.get(url))
+            .await?
+            .error_for_status()?;
+        let content_type = response
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("text/html")
+            .to_lowercase();
+
+        #[cfg(feature = "pdf")]
+        if crate::pdf::is_pdf(&content_type, url) {
+            let too_large = |size: usize| {
+                SearchError::Parse(format!(
+                    "PDF too large: {} bytes (limit {})",
+                    size, self.config.max_pdf_size
+                ))
+            };
+            if let Some(size) = response.content_length() {
+                if size as usize > self.config.max_pdf_size {
+                    return Err(too_large(size as usize));
+                }
+            }
+            let bytes = response.bytes().await?;
+            if bytes.len() > self.config.max_pdf_size {
+                return Err(too_large(bytes.len()));
+            }
+
+            let mut text = crate::pdf::extract_text(bytes.to_vec()).await?;
+            if text.is_empty() {
+                return Err(SearchError::NoResults(url.to_string()));
+            }
+            truncate(&mut text, self.config.max_content_length);
+            return Ok(Article { title: None, text });
+        }
+
+        let body = response.text().await?;
+
+        let mut article = if content_type.contains("html") {
+            extract_article(&body)
+        } else if content_type.starts_with("text/") {
+            Article {
+                title: None,
+                text: body.trim().to_string(),
+            }
+        } else {
+            return Err(SearchError::Parse(format!(
+                "Unsupported content type: {}",
+                content_type
+            )));
+        };
+
+        if article.text.is_empty() {
+            return Err(SearchError::NoResults(url.to_string()));
+        }
+        truncate(&mut article.text, self.config.max_content_length);
+        Ok(article)
+    }
+
+    /// Fetch the page behind a search result from any provider
+    pub async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        Ok(self.fetch(&result.url).await?.text)
+    }
+
+    /// Fill in the content of every result, fetching pages concurrently
+    ///
+    /// Results whose page cannot be fetched keep their snippet.
+    pub async fn fetch_results(&self, results: Vec<WebSearchResult>) -> Vec<WebSearchResult> {
+        let mut results: Vec<Option<WebSearchResult>> = results.into_iter().map(Some).collect();
+        let mut pending = results
+            .iter()
+            .enumerate()
+            .filter(|(_, result)| result.as_ref().is_some_and(|r| !r.has_content()))
+            .map(|(i, _)| i)
+            .collect::<Vec<_>>()
+            .into_iter();
+        let mut tasks = JoinSet::new();
+
+        loop {
+            while tasks.len() < self.config.max_concurrent.max(1) {
+                let Some(i) = pending.next() else { break };
+                let Some(result) = results[i].take() else {
+                    continue;
+                };
+                let fetcher = self.clone();
+                tasks.spawn(async move {
+                    let content = fetcher.fetch_content(&result).await;
+                    (i, result, content)
+                });
+            }
+
+            let Some(joined) = tasks.join_next().await else {
+                break;
+            };
+            match joined {
+                Ok((i, result, Ok(content))) => results[i] = Some(result.with_content(content)),
+                Ok((i, result, Err(e))) => {
+                    warn!("Failed to fetch {}: {}", result.url, e);
+                    results[i] = Some(result);
+                }
+                Err(e) => warn!("Page fetch task failed: {}", e),
+            }
+        }
+
+        results.into_iter().flatten().collect()
+    }
+}
+
+impl Default for PageFetcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Extract the title and main text of an HTML page
+pub fn extract_article(html: &str) -> Article {
+    let document = Html::parse_document(html);
+
+    let title = Selector::parse("title")
+        .ok()
+        .and_then(|selector| document.select(&selector).next())
+        .map(|title| collapse_whitespace(&title.text().collect::<String>()))
+        .filter(|title| !title.is_empty());
+
+    let mut blocks = Blocks::default();
+    if let Some(root) = main_element(&document) {
+        blocks.render(root);
+    }
+    blocks.flush();
+
+    Article {
+        title,
+        text: blocks.done.join("\n\n"),
+    }
+}
+
+/// Text of an HTML fragment, such as a post body, with code blocks kept
+pub(crate) fn html_to_text(html: &str) -> String {
+    let fragment = Html::parse_fragment(html);
+    let mut blocks = Blocks::default();
+    blocks.render(fragment.root_element());
+    blocks.flush();
+    blocks.done.join("\n\n")
+}
+
+/// The element holding the page's main content
+///
+/// Prefers `<article>` and `<main>`; otherwise picks the element whose
+/// paragraphs hold the most text, as readability-style extractors do.
+fn main_element(document: &Html) -> Option<ElementRef<'_>> {
+    let explicit = Selector::parse(r#"article, main, [role="main"]"#).ok()?;
+    if let Some(element) = document
+        .select(&explicit)
+        .find(|element| text_length(*element) > 0)
+    {
+        return Some(element);
+    }
+
+    let paragraphs = Selector::parse("p, pre").ok()?;
+    let mut scores = HashMap::new();
+    for paragraph in document.select(&paragraphs) {
+        if paragraph
+            .ancestors()
+            .filter_map(ElementRef::wrap)
+            .any(is_boilerplate)
+        {
+            continue;
+        }
+        let length = text_length(paragraph);
+        let mut ancestors = paragraph.ancestors().filter_map(ElementRef::wrap);
+        if let Some(parent) = ancestors.next() {
+            *scores.entry(parent.id()).or_insert(0) += length;
+        }
+        if let Some(grandparent) = ancestors.next() {
+            *scores.entry(grandparent.id()).or_insert(0) += length / 2;
+        }
+    }
+
+    let best = scores
+        .into_iter()
+        .max_by_key(|&(_, score)| score)
+        .and_then(|(id, _)| document.tree.get(id))
+        .and_then(ElementRef::wrap);
+    best.or_else(|| {
+        let body = Selector::parse("body").ok()?;
+        document.select(&body).next()
+    })
+}
+
+/// Whether an element is navigation, ads or other boilerplate
+fn is_boilerplate(element: ElementRef<'_>) -> bool {
+    let value = element.value();
+    if SKIPPED_TAGS.contains(&value.name())
+        || value.attr("hidden").is_some()
+        || value.attr("aria-hidden") == Some("true")
+        || matches!(
+            value.attr("role"),
+            Some("navigation" | "banner" | "complementary")
+        )
+    {
+        return true;
+    }
+
+    let names = [value.attr("class"), value.attr("id")];
+    names
+        .into_iter()
+        .flatten()
+        .flat_map(|name| name.split(|c: char| c.is_whitespace() || c == '-' || c == '_'))
+        .map(str::to_lowercase)
+        .any(|token| {
+            token == "ad"
+                || token == "ads"
+                || BOILERPLATE_PREFIXES
+                    .iter()
+                    .any(|prefix| token.starts_with(prefix))
+        })
+}
+
+/// Whether most of a list's text is links
+fn is_link_list(element: ElementRef<'_>) -> bool {
+    let total = text_length(element);
+    if total == 0 {
+        return false;
+    }
+    let links = Selector::parse("a").expect("valid selector");
+    let linked: usize = element.select(&links).map(text_length).sum();
+    linked as f64 / total as f64 > MAX_LIST_LINK_DENSITY
+}
+
+fn text_length(element: ElementRef<'_>) -> usize {
+    element.text().map(|text| text.trim().len()).sum()
+}
+
+/// Paragraphs of extracted text
+#[derive(Default)]
+struct Blocks {
+    done: Vec<String>,
+    current: String,
+}
+
+impl Blocks {
+    fn render(&mut self, element: ElementRef<'_>) {
+        for child in element.children() {
+            match child.value() {
+                Node::Text(text) => self.current.push_str(text),
+                Node::Element(_) => {
+                    let Some(child) = ElementRef::wrap(child) else {
+                        continue;
+                    };
+                    self.render_element(child);
+                }
+                _ => {}
+            }
+        }
+    }
+
+    fn render_element(&mut self, element: ElementRef<'_>) {
+        let name = element.value().name();
+        if is_boilerplate(element) || (matches!(name, "ul" | "ol") && is_link_list(element)) {
+            return;
+        }
+
+        match name {
+            "pre" => {
+                self.flush();
+                let code: String = element.text().collect();
+                let code = code.trim_matches('\n').trim_end();
+                if !code.is_empty() {
+                    self.done.push(format!("```\n{}\n```", code));
+                }
+            }
+            "code" => {
+                let code: String = element.text().collect();
+                self.current.push('`');
+                self.current.push_str(code.trim());
+                self.current.push('`');
+            }
+            "h1" | "h2" | "h3" | "h4" | "h5" | "h6" => {
+                self.flush();
+                let level = name[1..].parse().unwrap_or(2);
+                let heading = collapse_whitespace(&element.text().collect::<String>());
+                if !heading.is_empty() {
+                    self.done.push(format!("{} {}", "#".repeat(level), heading));
+                }
+            }
+            "li" => {
+                self.flush();
+                self.current.push_str("- ");
+                self.render(element);
+                self.flush();
+            }
+            _ if BLOCK_TAGS.contains(&name) => {
+                self.flush();
+                self.render(element);
+                self.flush();
+            }
+            _ => self.render(element),
+        }
+    }
+
+    /// End the current paragraph
+    fn flush(&mut self) {
+        let text = collapse_whitespace(&self.current);
+        self.current.clear();
+        if !text.is_empty() && text != "-" {
+            self.done.push(text);
+        }
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Cut `text` to at most `max_len` bytes, at a sentence boundary if one is
+/// in the second half, else mid-sentence with `...`
+pub(crate) fn truncate(text: &mut String, max_len: usize) {
+    if text.len() <= max_len {
+        return;
+    }
+    let mut end = max_len;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    match text[..end].rfind(". ").filter(|&pos| pos >= end / 2) {
+        Some(pos) => text.truncate(pos + 1),
+        None => {
+            text.truncate(end);
+            text.push_str("...");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<!DOCTYPE html>
+        <html>
+        <head><title> Ownership in Rust </title><style>body { color: red; }</style></head>
+        <body>
+            <header><a href="/">Home</a></header>
+            <ul class="top-links"><li><a href="/a">Docs</a></li><li><a href="/b">Blog</a></li></ul>
+            <div class="content">
+                <h1>Ownership</h1>
+                <p>Each value in Rust has an <b>owner</b>.
+                   There can only be one owner at a time.</p>
+                <pre><code>let s = String::from("hello");
+let t = s;</code></pre>
+                <p>Use <code>clone</code> to copy the heap data.</p>
+                <div class="share-buttons">Share on social media</div>
+            </div>
+            <div id="sidebar"><p>Related posts you might like and more text here.</p></div>
+            <footer>Copyright 2024</footer>
+            <script>track();</script>
+        </body>
+        </html>"#;
+
+    #[test]
+    fn test_extract_article() {
+        let article = extract_article(PAGE);
+        assert_eq!(article.title.as_deref(), Some("Ownership in Rust"));
+        assert_eq!(
+            article.text,
+            "# Ownership\n\n\
+             Each value in Rust has an owner. There can only be one owner at a time.\n\n\
+             ```\nlet s = String::from(\"hello\");\nlet t = s;\n```\n\n\
+             Use `clone` to copy the heap data."
+        );
+    }
+
+    #[test]
+    fn test_extract_prefers_article_element() {
+        let html = r#"<body>
+            <div><p>Cookie notice that is long enough to look like content.</p></div>
+            <article><p>The story.</p><ul><li>First point</li><li>Second point</li></ul></article>
+        </body>"#;
+        let article = extract_article(html);
+        assert_eq!(
+            article.text,
+            "The story.\n\n- First point\n\n- Second point"
+        );
+        assert_eq!(article.title, None);
+    }
+
+    #[test]
+    fn test_truncate() {
+        let mut text = "A short one. Then another sentence. And a third.".to_string();
+        truncate(&mut text, 40);
+        assert_eq!(text, "A short one. Then another sentence.");
+
+        let mut text = "One long sentence without a break".to_string();
+        truncate(&mut text, 8);
+        assert_eq!(text, "One long...");
+
+        let mut text = "ñññ".to_string();
+        truncate(&mut text, 3);
+        assert_eq!(text, "ñ...");
+    }
+}
@@ -0,0 +1,386 @@
+//! GitHub repository and code search implementation
+
+use async_trait::async_trait;
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION};
+use reqwest::{Client, RequestBuilder, StatusCode};
+use serde::Deserialize;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+use crate::error::{Result, SearchError};
+use crate::fetcher::truncate;
+use crate::http::HttpConfig;
+use crate::result::WebSearchResult;
+use crate::searcher::WebSearcher;
+
+/// Media type for raw file and README contents
+const RAW_MEDIA_TYPE: &str = "application/vnd.github.raw+json";
+
+/// Media type for search results with matching fragments
+const TEXT_MATCH_MEDIA_TYPE: &str = "application/vnd.github.text-match+json";
+
+/// What to search on GitHub
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum GitHubSearchKind {
+    /// Repositories; results fetch to their README
+    #[default]
+    Repositories,
+    /// Code in files; requires a token
+    Code,
+}
+
+/// GitHub search configuration
+#[derive(Debug, Clone)]
+pub struct GitHubConfig {
+    /// Request timeout
+    pub timeout: Duration,
+    /// API base URL (change for GitHub Enterprise)
+    pub api_url: String,
+    /// Personal access token; raises rate limits and enables code search
+    pub token: Option<String>,
+    /// What to search
+    pub kind: GitHubSearchKind,
+    /// Maximum content length to fetch
+    pub max_content_length: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
+}
+
+impl Default for GitHubConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            api_url: "https://api.github.com".to_string(),
+            token: None,
+            kind: GitHubSearchKind::default(),
+            max_content_length: 10000,
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+/// GitHub search provider
+pub struct GitHubSearcher {
+    client: Client,
+    config: GitHubConfig,
+}
+
+impl GitHubSearcher {
+    /// Create a new GitHub searcher with default config
+    pub fn new() -> Self {
+        Self::with_config(GitHubConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: GitHubConfig) -> Self {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            ACCEPT,
+            HeaderValue::from_static("application/vnd.github+json"),
+        );
+        headers.insert(
+            "X-GitHub-Api-Version",
+            HeaderValue::from_static("2022-11-28"),
+        );
+        if let Some(token) = &config.token {
+            if let Ok(mut value) = HeaderValue::from_str(&format!("Bearer {}", token)) {
+                value.set_sensitive(true);
+                headers.insert(AUTHORIZATION, value);
+            }
+        }
+
+        let client = config
+            .http
+            .client_builder(config.timeout)
+            .default_headers(headers)
+            .build()
+            .expect("Failed to build HTTP client");
+
+        Self { client, config }
+    }
+
+    /// Create with a token, searching `kind`
+    pub fn with_token(token: impl Into<String>, kind: GitHubSearchKind) -> Self {
+        Self::with_config(GitHubConfig {
+            token: Some(token.into()),
+            kind,
+            ..Default::default()
+        })
+    }
+
+    fn search_url(&self, query: &str, max_results: usize) -> Result<Url> {
+        let endpoint = match self.config.kind {
+            GitHubSearchKind::Repositories => "repositories",
+            GitHubSearchKind::Code => "code",
+        };
+        Url::parse_with_params(
+            &format!("{}/search/{}", self.config.api_url, endpoint),
+            &[
+                ("q", query),
+                ("per_page", &max_results.min(100).to_string()),
+            ],
+        )
+        .map_err(|e| SearchError::Parse(e.to_string()))
+    }
+
+    /// API URL with the raw contents behind a repository or file URL
+    fn contents_url(&self, url: &str) -> Option<String> {
+        let url = Url::parse(url).ok()?;
+        if url.host_str() != Some("github.com") {
+            return None;
+        }
+        let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+        match segments.as_slice() {
+            [owner, repo] => Some(format!(
+                "{}/repos/{}/{}/readme",
+                self.config.api_url, owner, repo
+            )),
+            [owner, repo, "blob", git_ref, path @ ..] if !path.is_empty() => Some(format!(
+                "{}/repos/{}/{}/contents/{}?ref={}",
+                self.config.api_url,
+                owner,
+                repo,
+                path.join("/"),
+                git_ref
+            )),
+            _ => None,
+        }
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<reqwest::Response> {
+        let response = self.config.http.send(request).await?;
+        match response.status() {
+            StatusCode::TOO_MANY_REQUESTS => Err(SearchError::RateLimited),
+            StatusCode::FORBIDDEN
+                if response
+                    .headers()
+                    .get("x-ratelimit-remaining")
+                    .is_some_and(|remaining| remaining == "0") =>
+            {
+                Err(SearchError::RateLimited)
+            }
+            StatusCode::UNAUTHORIZED if self.config.kind == GitHubSearchKind::Code => Err(
+                SearchError::InvalidQuery("GitHub code search requires a token".into()),
+            ),
+            StatusCode::UNPROCESSABLE_ENTITY => {
+                let message = response.text().await.unwrap_or_default();
+                Err(SearchError::InvalidQuery(message))
+            }
+            _ => Ok(response.error_for_status()?),
+        }
+    }
+}
+
+impl Default for GitHubSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchResponse<T> {
+    items: Vec<T>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RepoItem {
+    full_name: String,
+    html_url: String,
+    description: Option<String>,
+    #[serde(default)]
+    stargazers_count: u64,
+    language: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeItem {
+    path: String,
+    html_url: String,
+    repository: CodeRepository,
+    #[serde(default)]
+    text_matches: Vec<TextMatch>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CodeRepository {
+    full_name: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TextMatch {
+    fragment: String,
+}
+
+fn repo_result(item: RepoItem) -> WebSearchResult {
+    let mut snippet = item.description.unwrap_or_default();
+    let mut details = vec![format!("{} stars", item.stargazers_count)];
+    details.extend(item.language);
+    snippet.push_str(&format!(" ({})", details.join(", ")));
+
+    WebSearchResult::new(item.full_name, item.html_url, snippet.trim(), "GitHub")
+}
+
+fn code_result(item: CodeItem) -> WebSearchResult {
+    let snippet = if item.text_matches.is_empty() {
+        item.path.clone()
+    } else {
+        item.text_matches
+            .iter()
+            .map(|m| m.fragment.trim())
+            .collect::<Vec<_>>()
+            .join("\n...\n")
+    };
+
+    WebSearchResult::new(
+        format!("{}: {}", item.repository.full_name, item.path),
+        item.html_url,
+        snippet,
+        "GitHub",
+    )
+}
+
+#[async_trait]
+impl WebSearcher for GitHubSearcher {
+    fn name(&self) -> &str {
+        "GitHub"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(SearchError::InvalidQuery("Empty query".into()));
+        }
+
+        debug!("Searching GitHub {:?} for: {}", self.config.kind, query);
+
+        let url = self.search_url(query, max_results)?;
+        let request = self.client.get(url).header(ACCEPT, TEXT_MATCH_MEDIA_TYPE);
+        let response = self.send(request).await?;
+
+        let results: Vec<WebSearchResult> = match self.config.kind {
+            GitHubSearchKind::Repositories => response
+                .json::<SearchResponse<RepoItem>>()
+                .await?
+                .items
+                .into_iter()
+                .map(repo_result)
+                .collect(),
+            GitHubSearchKind::Code => response
+                .json::<SearchResponse<CodeItem>>()
+                .await?
+                .items
+                .into_iter()
+                .map(code_result)
+                .collect(),
+        };
+
+        if results.is_empty() {
+            return Err(SearchError::NoResults(query.to_string()));
+        }
+
+        debug!("Found {} GitHub results", results.len());
+        Ok(results)
+    }
+
+    async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        debug!("Fetching GitHub content for: {}", result.url);
+
+        let url = self
+            .contents_url(&result.url)
+            .ok_or_else(|| SearchError::Parse(format!("Not a GitHub URL: {}", result.url)))?;
+        let request = self.client.get(url).header(ACCEPT, RAW_MEDIA_TYPE);
+        let mut content = self.send(request).await?.text().await?;
+
+        truncate(&mut content, self.config.max_content_length);
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_config_default() {
+        let config = GitHubConfig::default();
+        assert_eq!(config.api_url, "https://api.github.com");
+        assert_eq!(config.kind, GitHubSearchKind::Repositories);
+        assert!(config.token.is_none());
+    }
+
+    #[test]
+    fn test_search_url() {
+        let searcher = GitHubSearcher::with_token("token", GitHubSearchKind::Code);
+        let url = searcher.search_url("tokio select", 5).unwrap();
+        assert_eq!(url.path(), "/search/code");
+        assert!(url.query().unwrap().contains("per_page=5"));
+    }
+
+    #[test]
+    fn test_contents_url() {
+        let searcher = GitHubSearcher::new();
+        assert_eq!(
+            searcher
+                .contents_url("https://github.com/tokio-rs/tokio")
+                .as_deref(),
+            Some("https://api.github.com/repos/tokio-rs/tokio/readme")
+        );
+        assert_eq!(
+            searcher
+                .contents_url("https://github.com/tokio-rs/tokio/blob/abc123/tokio/src/lib.rs")
+                .as_deref(),
+            Some(
+                "https://api.github.com/repos/tokio-rs/tokio/contents/tokio/src/lib.rs?ref=abc123"
+            )
+        );
+        assert!(searcher.contents_url("https://example.com/a/b").is_none());
+        assert!(searcher
+            .contents_url("https://github.com/tokio-rs")
+            .is_none());
+    }
+
+    #[test]
+    fn test_parse_results() {
+        let repos: SearchResponse<RepoItem> = serde_json::from_str(
+            r#"{"total_count": 1, "items": [{
+                "full_name": "tokio-rs/tokio",
+                "html_url": "https://github.com/tokio-rs/tokio",
+                "description": "A runtime for async Rust",
+                "stargazers_count": 25000,
+                "language": "Rust"
+            }]}"#,
+        )
+        .unwrap();
+        let result = repo_result(repos.items.into_iter().next().unwrap());
+        assert_eq!(result.title, "tokio-rs/tokio");
+        assert_eq!(
+            result.snippet,
+            "A runtime for async Rust (25000 stars, Rust)"
+        );
+        assert_eq!(result.source, "GitHub");
+
+        let code: SearchResponse<CodeItem> = serde_json::from_str(
+            r#"{"items": [{
+                "path": "src/main.rs",
+                "html_url": "https://github.com/a/b/blob/main/src/main.rs",
+                "repository": {"full_name": "a/b"},
+                "text_matches": [{"fragment": "tokio::select! {"}]
+            }]}"#,
+        )
+        .unwrap();
+        let result = code_result(code.items.into_iter().next().unwrap());
+        assert_eq!(result.title, "a/b: src/main.rs");
+        assert_eq!(result.snippet, "tokio::select! {");
+    }
+
+    // Integration test - requires network
+    #[tokio::test]
+    #[ignore = "Requires network"]
+    async fn test_search_integration() {
+        let searcher = GitHubSearcher::new();
+        let results = searcher.search("tokio runtime", 3).await.unwrap();
+
+        assert!(!results.is_empty());
+        assert_eq!(results[0].source, "GitHub");
+    }
+}
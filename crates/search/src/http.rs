@@ -0,0 +1,202 @@
+//! Connection settings shared by every provider
+//!
+//! Each provider's config embeds an [`HttpConfig`] with connect and read
+//! timeouts, a proxy, and how often requests failing for transient
+//! reasons (connection errors, timeouts, 502/504) are retried. A 503 is
+//! left to [`RateLimitedSearcher`](crate::RateLimitedSearcher), which backs
+//! off every caller instead of retrying against an overloaded provider.
+
+use reqwest::{Client, ClientBuilder, Proxy, RequestBuilder, Response, StatusCode};
+use std::time::Duration;
+use tracing::warn;
+
+use crate::error::Result;
+
+const USER_AGENT: &str = "neuro-bitnet/0.1 (RAG system)";
+
+/// Timeouts, retries and proxy for a provider's requests
+#[derive(Debug, Clone)]
+pub struct HttpConfig {
+    /// Time allowed to establish a connection
+    pub connect_timeout: Duration,
+    /// Longest wait between two reads of a response; `None` leaves only
+    /// the provider's overall timeout
+    pub read_timeout: Option<Duration>,
+    /// Retries after a connection failure, timeout or 502/504 response
+    pub max_retries: u32,
+    /// Wait before the first retry; doubles on each retry
+    pub retry_backoff: Duration,
+    /// Proxy URL for all requests (`http://`, `https://`, `socks5://` or
+    /// `socks5h://`, credentials allowed). `None` uses the `HTTP_PROXY`,
+    /// `HTTPS_PROXY` and `NO_PROXY` environment variables
+    pub proxy: Option<String>,
+}
+
+impl Default for HttpConfig {
+    fn default() -> Self {
+        Self {
+            connect_timeout: Duration::from_secs(5),
+            read_timeout: None,
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(500),
+            proxy: None,
+        }
+    }
+}
+
+impl HttpConfig {
+    /// Set the proxy for all requests
+    pub fn with_proxy(mut self, proxy: impl Into<String>) -> Self {
+        self.proxy = Some(proxy.into());
+        self
+    }
+
+    /// Client with these settings, giving up on requests after `timeout`
+    pub(crate) fn client(&self, timeout: Duration) -> Client {
+        self.client_builder(timeout)
+            .build()
+            .expect("Failed to build HTTP client")
+    }
+
+    /// Client builder with these settings, for providers that add headers
+    pub(crate) fn client_builder(&self, timeout: Duration) -> ClientBuilder {
+        let mut builder = Client::builder()
+            .timeout(timeout)
+            .connect_timeout(self.connect_timeout)
+            .user_agent(USER_AGENT);
+        if let Some(read_timeout) = self.read_timeout {
+            builder = builder.read_timeout(read_timeout);
+        }
+        if let Some(proxy) = &self.proxy {
+            builder = builder.proxy(Proxy::all(proxy).expect("Invalid proxy URL"));
+        }
+        builder
+    }
+
+    /// Send `request`, retrying transient failures
+    ///
+    /// Once retries run out, the last response (whatever its status) or
+    /// error is returned.
+    pub(crate) async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        let mut attempt = 0;
+        loop {
+            // Requests with streaming bodies can't be repeated
+            let Some(retry) = request.try_clone() else {
+                return Ok(request.send().await?);
+            };
+
+            let outcome = retry.send().await;
+            let reason = match &outcome {
+                Ok(response) if is_transient_status(response.status()) => {
+                    response.status().to_string()
+                }
+                Err(e) if e.is_connect() || e.is_timeout() => e.to_string(),
+                _ => return Ok(outcome?),
+            };
+            if attempt >= self.max_retries {
+                return Ok(outcome?);
+            }
+
+            let backoff = self
+                .retry_backoff
+                .saturating_mul(2u32.saturating_pow(attempt));
+            warn!("Request failed ({}), retrying in {:?}", reason, backoff);
+            tokio::time::sleep(backoff).await;
+            attempt += 1;
+        }
+    }
+}
+
+fn is_transient_status(status: StatusCode) -> bool {
+    matches!(status, StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Serve `statuses` in turn, one per connection; returns the URL and
+    /// the number of requests served
+    async fn serve(statuses: &'static [u16]) -> (String, Arc<AtomicU32>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let served = Arc::new(AtomicU32::new(0));
+        let counter = served.clone();
+        tokio::spawn(async move {
+            for status in statuses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut buf = [0; 1024];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {} X\r\ncontent-length: 0\r\nconnection: close\r\n\r\n",
+                    status
+                );
+                socket.write_all(response.as_bytes()).await.unwrap();
+                counter.fetch_add(1, Ordering::SeqCst);
+            }
+        });
+        (url, served)
+    }
+
+    fn config(max_retries: u32) -> HttpConfig {
+        HttpConfig {
+            max_retries,
+            retry_backoff: Duration::from_millis(1),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_retries_transient_statuses() {
+        let (url, served) = serve(&[504, 502, 200]).await;
+        let http = config(2);
+        let client = http.client(Duration::from_secs(5));
+
+        let response = http.send(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(served.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let (url, served) = serve(&[502, 502, 200]).await;
+        let http = config(1);
+        let client = http.client(Duration::from_secs(5));
+
+        let response = http.send(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_GATEWAY);
+        assert_eq!(served.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_does_not_retry_client_errors() {
+        let (url, served) = serve(&[404, 200]).await;
+        let http = config(3);
+        let client = http.client(Duration::from_secs(5));
+
+        let response = http.send(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_leaves_service_unavailable_to_rate_limiter() {
+        let (url, served) = serve(&[503, 200]).await;
+        let http = config(3);
+        let client = http.client(Duration::from_secs(5));
+
+        let response = http.send(client.get(&url)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(served.load(Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn test_proxy() {
+        let http = HttpConfig::default().with_proxy("socks5h://proxy.internal:1080");
+        http.client(Duration::from_secs(5));
+    }
+}
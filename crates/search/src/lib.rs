@@ -8,7 +8,17 @@
 //! ## Features
 //!
 //! - Wikipedia search and content extraction
-//! - Configurable timeouts and result limits
+//! - Wikidata facts (dates, capitals, populations) for factual questions
+//! - GitHub repository and code search, for external code examples
+//! - Stack Overflow answers for programming questions
+//! - RSS/Atom feed subscriptions, returning only new entries
+//! - Main-text extraction from any page, to turn results from any
+//!   provider into full RAG context
+//! - PDF text extraction (`pdf` feature, on by default)
+//! - Aggregated search over several providers, with deduplication
+//! - Reranking of results by embedding similarity to the query
+//! - Configurable timeouts, retries, proxies (HTTP or SOCKS) and result limits
+//! - Per-provider rate limiting with backoff on 429/503 responses
 //! - Clean text extraction from HTML
 //!
 //! ## Example
@@ -27,17 +37,41 @@
 //! }
 //! ```
 
+mod aggregate;
 mod error;
+mod feed;
+mod fetcher;
+mod github;
+mod http;
+#[cfg(feature = "pdf")]
+mod pdf;
+mod ratelimit;
+mod relevance;
 mod searcher;
+mod stackoverflow;
+mod wikidata;
 mod wikipedia;
 mod result;
 
+pub use aggregate::AggregatedSearcher;
 pub use error::{SearchError, Result};
+pub use http::HttpConfig;
+pub use feed::{
+    parse_feed, Feed, FeedConfig, FeedEntry, FeedReader, FeedSubscriptions, Subscription,
+};
+pub use fetcher::{extract_article, Article, PageFetcher, PageFetcherConfig};
+pub use github::{GitHubConfig, GitHubSearchKind, GitHubSearcher};
+pub use ratelimit::{RateLimitConfig, RateLimitedSearcher};
+pub use relevance::{rank_by_similarity, DEFAULT_MIN_SIMILARITY};
 pub use searcher::WebSearcher;
+pub use stackoverflow::{StackOverflowConfig, StackOverflowSearcher};
+pub use wikidata::{WikidataConfig, WikidataSearcher};
 pub use wikipedia::WikipediaSearcher;
 pub use result::WebSearchResult;
 
 /// Re-export commonly used types
 pub mod prelude {
-    pub use crate::{WebSearcher, WikipediaSearcher, WebSearchResult, SearchError, Result};
+    pub use crate::{
+        GitHubSearcher, PageFetcher, StackOverflowSearcher, WebSearcher, WikidataSearcher, WikipediaSearcher, WebSearchResult, SearchError, Result,
+    };
 }
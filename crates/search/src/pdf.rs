@@ -0,0 +1,165 @@
+//! PDF text extraction
+//!
+//! Papers and manuals are often served as PDF. Their text is extracted
+//! with `pdf-extract` on the blocking thread pool, then reflowed: the
+//! extractor emits one line per printed line, so lines are joined into
+//! paragraphs and words hyphenated across line breaks are rejoined.
+
+use tracing::debug;
+
+use crate::error::{Result, SearchError};
+
+/// Whether a response with `content_type` from `url` is a PDF
+///
+/// Servers often send PDFs as `application/octet-stream`, so the URL
+/// extension is checked too.
+pub(crate) fn is_pdf(content_type: &str, url: &str) -> bool {
+    if content_type.contains("pdf") {
+        return true;
+    }
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    content_type.contains("octet-stream") && path.to_lowercase().ends_with(".pdf")
+}
+
+/// Extract the text of the PDF in `bytes`
+pub(crate) async fn extract_text(bytes: Vec<u8>) -> Result<String> {
+    if !bytes.starts_with(b"%PDF-") {
+        return Err(SearchError::Parse("Not a PDF document".to_string()));
+    }
+
+    debug!("Extracting text from {} byte PDF", bytes.len());
+    // The extractor panics on some malformed files; a panic only fails
+    // the task
+    let text = tokio::task::spawn_blocking(move || pdf_extract::extract_text_from_mem(&bytes))
+        .await
+        .map_err(|e| SearchError::Parse(format!("PDF extraction failed: {}", e)))?
+        .map_err(|e| SearchError::Parse(format!("Invalid PDF: {}", e)))?;
+
+    Ok(reflow(&text))
+}
+
+/// Join printed lines into paragraphs separated by blank lines
+fn reflow(text: &str) -> String {
+    let mut paragraphs = Vec::new();
+    let mut current = String::new();
+
+    for line in text.lines() {
+        let line = line.split_whitespace().collect::<Vec<_>>().join(" ");
+        if line.is_empty() {
+            if !current.is_empty() {
+                paragraphs.push(std::mem::take(&mut current));
+            }
+            continue;
+        }
+
+        // "exam-" + "ple" -> "example"; keep real dashes like "well-"
+        // followed by a capital or number
+        let hyphenated = current.ends_with('-')
+            && current[..current.len() - 1]
+                .chars()
+                .last()
+                .is_some_and(char::is_alphabetic)
+            && line.chars().next().is_some_and(char::is_lowercase);
+        if hyphenated {
+            current.pop();
+        } else if !current.is_empty() {
+            current.push(' ');
+        }
+        current.push_str(&line);
+    }
+
+    if !current.is_empty() {
+        paragraphs.push(current);
+    }
+    paragraphs.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A one-page PDF showing `lines` in Helvetica
+    fn pdf(lines: &[&str]) -> Vec<u8> {
+        let mut stream = String::from("BT /F1 12 Tf 72 720 Td 14 TL\n");
+        for line in lines {
+            stream.push_str(&format!("({}) Tj T*\n", line));
+        }
+        stream.push_str("ET");
+
+        let objects = [
+            "<< /Type /Catalog /Pages 2 0 R >>".to_string(),
+            "<< /Type /Pages /Kids [3 0 R] /Count 1 >>".to_string(),
+            "<< /Type /Page /Parent 2 0 R /MediaBox [0 0 612 792] \
+             /Resources << /Font << /F1 5 0 R >> >> /Contents 4 0 R >>"
+                .to_string(),
+            format!(
+                "<< /Length {} >>\nstream\n{}\nendstream",
+                stream.len(),
+                stream
+            ),
+            "<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica \
+             /Encoding /WinAnsiEncoding >>"
+                .to_string(),
+        ];
+
+        let mut out = String::from("%PDF-1.4\n");
+        let mut offsets = Vec::new();
+        for (i, object) in objects.iter().enumerate() {
+            offsets.push(out.len());
+            out.push_str(&format!("{} 0 obj\n{}\nendobj\n", i + 1, object));
+        }
+        let xref = out.len();
+        out.push_str(&format!(
+            "xref\n0 {}\n0000000000 65535 f \n",
+            objects.len() + 1
+        ));
+        for offset in offsets {
+            out.push_str(&format!("{:010} 00000 n \n", offset));
+        }
+        out.push_str(&format!(
+            "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+            objects.len() + 1,
+            xref
+        ));
+        out.into_bytes()
+    }
+
+    #[test]
+    fn test_is_pdf() {
+        assert!(is_pdf("application/pdf", "https://example.com/download"));
+        assert!(is_pdf(
+            "application/octet-stream",
+            "https://example.com/paper.PDF?v=2"
+        ));
+        assert!(!is_pdf(
+            "application/octet-stream",
+            "https://example.com/data.bin"
+        ));
+        assert!(!is_pdf("text/html", "https://example.com/paper.pdf"));
+    }
+
+    #[test]
+    fn test_reflow() {
+        let text = "Retrieval  augmented gen-\neration combines\nsearch and models.\n\n\nSee Section 2-\n3 for well-\nKnown results.\n";
+        assert_eq!(
+            reflow(text),
+            "Retrieval augmented generation combines search and models.\n\n\
+             See Section 2- 3 for well- Known results."
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_text() {
+        let text = extract_text(pdf(&["Hello from a PDF", "second line"]))
+            .await
+            .unwrap();
+        assert!(text.contains("Hello from a PDF"), "{:?}", text);
+        assert!(text.contains("second line"), "{:?}", text);
+    }
+
+    #[tokio::test]
+    async fn test_extract_text_rejects_other_files() {
+        assert!(extract_text(b"<html></html>".to_vec()).await.is_err());
+        assert!(extract_text(b"%PDF-1.4 truncated".to_vec()).await.is_err());
+    }
+}
@@ -0,0 +1,284 @@
+//! Client-side rate limiting for search providers
+//!
+//! [`RateLimitedSearcher`] wraps any [`WebSearcher`] with a token bucket,
+//! so bursts of queries are spread out instead of hammering the provider.
+//! When the provider answers 429 or 503 anyway, every caller backs off
+//! (exponentially, up to a limit) before the request is retried.
+
+use async_trait::async_trait;
+use reqwest::StatusCode;
+use std::future::Future;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+use crate::error::{Result, SearchError};
+use crate::result::WebSearchResult;
+use crate::searcher::WebSearcher;
+
+/// Rate limit and backoff configuration
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Sustained requests per second
+    pub requests_per_second: f64,
+    /// Requests allowed at once after a quiet period
+    pub burst: u32,
+    /// Retries after a rate-limited response
+    pub max_retries: u32,
+    /// Wait after the first rate-limited response; doubles on each retry
+    pub initial_backoff: Duration,
+    /// Longest wait between retries
+    pub max_backoff: Duration,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 1.0,
+            burst: 3,
+            max_retries: 3,
+            initial_backoff: Duration::from_secs(1),
+            max_backoff: Duration::from_secs(60),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    blocked_until: Option<Instant>,
+}
+
+/// A search provider limited to a request rate
+pub struct RateLimitedSearcher<S> {
+    inner: S,
+    config: RateLimitConfig,
+    bucket: Mutex<Bucket>,
+}
+
+impl<S: WebSearcher> RateLimitedSearcher<S> {
+    /// Limit `inner` to the default rate
+    pub fn new(inner: S) -> Self {
+        Self::with_config(inner, RateLimitConfig::default())
+    }
+
+    /// Limit `inner` with custom configuration
+    pub fn with_config(inner: S, config: RateLimitConfig) -> Self {
+        let bucket = Bucket {
+            tokens: config.burst.max(1) as f64,
+            refilled: Instant::now(),
+            blocked_until: None,
+        };
+        Self {
+            inner,
+            config,
+            bucket: Mutex::new(bucket),
+        }
+    }
+
+    /// The wrapped provider
+    pub fn inner(&self) -> &S {
+        &self.inner
+    }
+
+    /// Wait until a request may be sent
+    async fn acquire(&self) {
+        loop {
+            let wait = {
+                let mut bucket = self.lock();
+                let now = Instant::now();
+
+                let rate = self.config.requests_per_second.max(f64::MIN_POSITIVE);
+                let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+                bucket.tokens =
+                    (bucket.tokens + elapsed * rate).min(self.config.burst.max(1) as f64);
+                bucket.refilled = now;
+
+                match bucket.blocked_until {
+                    Some(until) if until > now => until - now,
+                    _ if bucket.tokens >= 1.0 => {
+                        bucket.tokens -= 1.0;
+                        return;
+                    }
+                    _ => Duration::from_secs_f64((1.0 - bucket.tokens) / rate),
+                }
+            };
+            tokio::time::sleep(wait).await;
+        }
+    }
+
+    /// Hold every request back after the `attempt`th rate-limited response
+    fn back_off(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff);
+
+        let mut bucket = self.lock();
+        let until = Instant::now() + backoff;
+        bucket.blocked_until = Some(bucket.blocked_until.map_or(until, |b| b.max(until)));
+        backoff
+    }
+
+    async fn call<T, F, Fut>(&self, request: F) -> Result<T>
+    where
+        F: Fn() -> Fut,
+        Fut: Future<Output = Result<T>>,
+    {
+        let mut attempt = 0;
+        loop {
+            self.acquire().await;
+            match request().await {
+                Err(e) if is_rate_limit(&e) && attempt < self.config.max_retries => {
+                    let backoff = self.back_off(attempt);
+                    warn!(
+                        "{} rate limited, retrying in {:?}",
+                        self.inner.name(),
+                        backoff
+                    );
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Bucket> {
+        self.bucket.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// Whether the provider asked us to slow down
+fn is_rate_limit(error: &SearchError) -> bool {
+    match error {
+        SearchError::RateLimited => true,
+        SearchError::Http(e) => matches!(
+            e.status(),
+            Some(StatusCode::TOO_MANY_REQUESTS | StatusCode::SERVICE_UNAVAILABLE)
+        ),
+        _ => false,
+    }
+}
+
+#[async_trait]
+impl<S: WebSearcher> WebSearcher for RateLimitedSearcher<S> {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        self.call(|| self.inner.search(query, max_results)).await
+    }
+
+    async fn search_in_language(
+        &self,
+        query: &str,
+        max_results: usize,
+        language: &str,
+    ) -> Result<Vec<WebSearchResult>> {
+        self.call(|| self.inner.search_in_language(query, max_results, language))
+            .await
+    }
+
+    async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        self.call(|| self.inner.fetch_content(result)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    /// Rate limited for the first `failures` searches
+    struct Flaky {
+        failures: u32,
+        calls: AtomicU32,
+    }
+
+    #[async_trait]
+    impl WebSearcher for Flaky {
+        fn name(&self) -> &str {
+            "Flaky"
+        }
+
+        async fn search(&self, query: &str, _max_results: usize) -> Result<Vec<WebSearchResult>> {
+            if self.calls.fetch_add(1, Ordering::SeqCst) < self.failures {
+                return Err(SearchError::RateLimited);
+            }
+            Ok(vec![WebSearchResult::new(
+                query,
+                "https://example.com",
+                "",
+                "Flaky",
+            )])
+        }
+
+        async fn fetch_content(&self, _result: &WebSearchResult) -> Result<String> {
+            Ok(String::new())
+        }
+    }
+
+    fn flaky(failures: u32, config: RateLimitConfig) -> RateLimitedSearcher<Flaky> {
+        let inner = Flaky {
+            failures,
+            calls: AtomicU32::new(0),
+        };
+        RateLimitedSearcher::with_config(inner, config)
+    }
+
+    #[tokio::test]
+    async fn test_token_bucket_spaces_requests() {
+        let searcher = flaky(
+            0,
+            RateLimitConfig {
+                requests_per_second: 20.0,
+                burst: 2,
+                ..Default::default()
+            },
+        );
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            searcher.search("rust", 1).await.unwrap();
+        }
+        // Two requests from the burst, then one every 50ms
+        assert!(start.elapsed() >= Duration::from_millis(90));
+    }
+
+    #[tokio::test]
+    async fn test_backs_off_and_retries() {
+        let searcher = flaky(
+            2,
+            RateLimitConfig {
+                initial_backoff: Duration::from_millis(20),
+                ..Default::default()
+            },
+        );
+
+        let start = Instant::now();
+        let results = searcher.search("rust", 1).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(searcher.inner().calls.load(Ordering::SeqCst), 3);
+        // 20ms, then 40ms
+        assert!(start.elapsed() >= Duration::from_millis(60));
+    }
+
+    #[tokio::test]
+    async fn test_gives_up_after_max_retries() {
+        let searcher = flaky(
+            10,
+            RateLimitConfig {
+                max_retries: 1,
+                initial_backoff: Duration::from_millis(1),
+                ..Default::default()
+            },
+        );
+
+        let result = searcher.search("rust", 1).await;
+        assert!(matches!(result, Err(SearchError::RateLimited)));
+        assert_eq!(searcher.inner().calls.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,70 @@
+//! Relevance ranking of web results
+//!
+//! Providers order results by their own notion of relevance, which often
+//! differs from the question asked. Given embeddings of the query and of
+//! each result, [`rank_by_similarity`] sorts results by cosine similarity
+//! and drops the off-topic ones before they reach the context.
+
+use crate::result::WebSearchResult;
+
+/// Similarity below which a result is considered off-topic
+pub const DEFAULT_MIN_SIMILARITY: f32 = 0.25;
+
+/// Sort `results` by similarity between `query` and their `embeddings`,
+/// dropping those below `min_similarity`
+///
+/// `embeddings[i]` belongs to `results[i]`; results without one are
+/// dropped. Each kept result records its score as `similarity` metadata.
+pub fn rank_by_similarity(
+    query: &[f32],
+    results: Vec<WebSearchResult>,
+    embeddings: &[Vec<f32>],
+    min_similarity: f32,
+) -> Vec<WebSearchResult> {
+    let mut scored: Vec<(f32, WebSearchResult)> = results
+        .into_iter()
+        .zip(embeddings)
+        .map(|(result, embedding)| (cosine_similarity(query, embedding), result))
+        .filter(|(similarity, _)| *similarity >= min_similarity)
+        .collect();
+
+    scored.sort_by(|a, b| b.0.total_cmp(&a.0));
+    scored
+        .into_iter()
+        .map(|(similarity, result)| result.with_metadata("similarity", similarity.into()))
+        .collect()
+}
+
+fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
+    if a.len() != b.len() {
+        return 0.0;
+    }
+    let dot: f32 = a.iter().zip(b).map(|(x, y)| x * y).sum();
+    let norm_a = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+    let norm_b = b.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm_a == 0.0 || norm_b == 0.0 {
+        0.0
+    } else {
+        dot / (norm_a * norm_b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(title: &str) -> WebSearchResult {
+        WebSearchResult::new(title, "https://example.com", "", "Test")
+    }
+
+    #[test]
+    fn test_rank_by_similarity() {
+        let results = vec![result("Off topic"), result("Close"), result("Exact")];
+        let embeddings = vec![vec![0.0, 1.0], vec![1.0, 0.5], vec![1.0, 0.0]];
+
+        let ranked = rank_by_similarity(&[1.0, 0.0], results, &embeddings, DEFAULT_MIN_SIMILARITY);
+        let titles: Vec<&str> = ranked.iter().map(|r| r.title.as_str()).collect();
+        assert_eq!(titles, ["Exact", "Close"]);
+        assert_eq!(ranked[0].metadata["similarity"], 1.0);
+    }
+}
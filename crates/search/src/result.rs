@@ -1,6 +1,7 @@
 //! Web search result types
 
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 
 /// Result from a web search
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,6 +20,10 @@ pub struct WebSearchResult {
 
     /// Source name (e.g., "Wikipedia")
     pub source: String,
+
+    /// Provider-specific details (e.g., answer score)
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub metadata: HashMap<String, serde_json::Value>,
 }
 
 impl WebSearchResult {
@@ -35,6 +40,7 @@ impl WebSearchResult {
             snippet: snippet.into(),
             content: None,
             source: source.into(),
+            metadata: HashMap::new(),
         }
     }
 
@@ -44,6 +50,12 @@ impl WebSearchResult {
         self
     }
 
+    /// Add metadata
+    pub fn with_metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
     /// Check if full content is available
     pub fn has_content(&self) -> bool {
         self.content.is_some()
@@ -54,6 +66,11 @@ impl WebSearchResult {
         self.content.as_deref().unwrap_or(&self.snippet)
     }
 
+    /// Title and snippet, the short text to judge relevance by
+    pub fn summary(&self) -> String {
+        format!("{}\n{}", self.title, self.snippet)
+    }
+
     /// Convert to a RAG-friendly string
     pub fn to_rag_context(&self) -> String {
         let mut context = format!("# {}\n", self.title);
@@ -17,6 +17,20 @@ pub trait WebSearcher: Send + Sync {
     /// * `max_results` - Maximum number of results to return
     async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>>;
 
+    /// Search in `language` (an ISO 639-1 code, or `auto` to detect it
+    /// from the query)
+    ///
+    /// Only providers with per-language editions use the language; the
+    /// default ignores it.
+    async fn search_in_language(
+        &self,
+        query: &str,
+        max_results: usize,
+        _language: &str,
+    ) -> Result<Vec<WebSearchResult>> {
+        self.search(query, max_results).await
+    }
+
     /// Fetch full content for a search result
     async fn fetch_content(&self, result: &WebSearchResult) -> Result<String>;
 }
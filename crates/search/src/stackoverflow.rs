@@ -0,0 +1,366 @@
+//! Stack Overflow (StackExchange API) search implementation
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+use crate::error::{Result, SearchError};
+use crate::fetcher::{html_to_text, truncate};
+use crate::http::HttpConfig;
+use crate::result::WebSearchResult;
+use crate::searcher::WebSearcher;
+
+/// StackExchange error id for too many requests
+const THROTTLE_VIOLATION: u32 = 502;
+
+/// Longest answer excerpt kept as the snippet
+const SNIPPET_LENGTH: usize = 300;
+
+/// Stack Overflow search configuration
+#[derive(Debug, Clone)]
+pub struct StackOverflowConfig {
+    /// Request timeout
+    pub timeout: Duration,
+    /// API base URL
+    pub api_url: String,
+    /// StackExchange site (e.g., "stackoverflow", "superuser")
+    pub site: String,
+    /// App key; raises the daily request quota
+    pub api_key: Option<String>,
+    /// Maximum answer length kept as content
+    pub max_content_length: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
+}
+
+impl Default for StackOverflowConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            api_url: "https://api.stackexchange.com/2.3".to_string(),
+            site: "stackoverflow".to_string(),
+            api_key: None,
+            max_content_length: 10000,
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+/// Stack Overflow search provider
+///
+/// Finds answered questions and returns the accepted (or else the highest
+/// voted) answer of each, with the scores in the result metadata.
+pub struct StackOverflowSearcher {
+    client: Client,
+    config: StackOverflowConfig,
+}
+
+impl StackOverflowSearcher {
+    /// Create a new Stack Overflow searcher with default config
+    pub fn new() -> Self {
+        Self::with_config(StackOverflowConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: StackOverflowConfig) -> Self {
+        let client = config.http.client(config.timeout);
+
+        Self { client, config }
+    }
+
+    /// Create for another StackExchange site
+    pub fn with_site(site: impl Into<String>) -> Self {
+        Self::with_config(StackOverflowConfig {
+            site: site.into(),
+            ..Default::default()
+        })
+    }
+
+    fn api_url(&self, path: &str, params: &[(&str, &str)]) -> Result<Url> {
+        let mut url = Url::parse_with_params(&format!("{}{}", self.config.api_url, path), params)
+            .map_err(|e| SearchError::Parse(e.to_string()))?;
+        url.query_pairs_mut().append_pair("site", &self.config.site);
+        if let Some(key) = &self.config.api_key {
+            url.query_pairs_mut().append_pair("key", key);
+        }
+        Ok(url)
+    }
+
+    async fn get<T: DeserializeOwned>(&self, url: Url) -> Result<Vec<T>> {
+        let response: ApiResponse<T> = self
+            .config
+            .http
+            .send(self.client.get(url))
+            .await?
+            .json()
+            .await?;
+        match response.error_id {
+            Some(THROTTLE_VIOLATION) => Err(SearchError::RateLimited),
+            Some(_) => Err(SearchError::InvalidQuery(
+                response.error_message.unwrap_or_default(),
+            )),
+            None => Ok(response.items),
+        }
+    }
+
+    /// Answers to `question_ids`, best first
+    async fn answers(&self, question_ids: &[u64]) -> Result<Vec<Answer>> {
+        let ids = question_ids
+            .iter()
+            .map(u64::to_string)
+            .collect::<Vec<_>>()
+            .join(";");
+        let url = self.api_url(
+            &format!("/questions/{}/answers", ids),
+            &[
+                ("order", "desc"),
+                ("sort", "votes"),
+                ("filter", "withbody"),
+                ("pagesize", "100"),
+            ],
+        )?;
+        self.get(url).await
+    }
+}
+
+impl Default for StackOverflowSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiResponse<T> {
+    #[serde(default = "Vec::new")]
+    items: Vec<T>,
+    error_id: Option<u32>,
+    error_message: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Question {
+    question_id: u64,
+    title: String,
+    link: String,
+    score: i64,
+    #[serde(default)]
+    tags: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Answer {
+    question_id: u64,
+    score: i64,
+    #[serde(default)]
+    is_accepted: bool,
+    #[serde(default)]
+    body: String,
+}
+
+/// The accepted answer to each question, or else its highest voted one
+fn best_answers(answers: Vec<Answer>) -> HashMap<u64, Answer> {
+    let mut best: HashMap<u64, Answer> = HashMap::new();
+    for answer in answers {
+        let better = !best.get(&answer.question_id).is_some_and(|current| {
+            (answer.is_accepted, answer.score) <= (current.is_accepted, current.score)
+        });
+        if better {
+            best.insert(answer.question_id, answer);
+        }
+    }
+    best
+}
+
+fn answer_result(question: Question, answer: &Answer, max_len: usize) -> WebSearchResult {
+    let mut content = html_to_text(&answer.body);
+    let mut snippet = content.clone();
+    truncate(&mut snippet, SNIPPET_LENGTH);
+    truncate(&mut content, max_len);
+
+    WebSearchResult::new(
+        html_to_text(&question.title),
+        question.link,
+        snippet,
+        "Stack Overflow",
+    )
+    .with_content(content)
+    .with_metadata("question_score", question.score.into())
+    .with_metadata("answer_score", answer.score.into())
+    .with_metadata("accepted", answer.is_accepted.into())
+    .with_metadata("tags", question.tags.into())
+}
+
+/// Question id in a `/questions/{id}/...` URL
+fn question_id(url: &str) -> Option<u64> {
+    let url = Url::parse(url).ok()?;
+    let mut segments = url.path_segments()?;
+    segments.find(|segment| *segment == "questions")?;
+    segments.next()?.parse().ok()
+}
+
+#[async_trait]
+impl WebSearcher for StackOverflowSearcher {
+    fn name(&self) -> &str {
+        "Stack Overflow"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(SearchError::InvalidQuery("Empty query".into()));
+        }
+
+        debug!("Searching Stack Overflow for: {}", query);
+
+        let url = self.api_url(
+            "/search/advanced",
+            &[
+                ("q", query),
+                ("order", "desc"),
+                ("sort", "relevance"),
+                ("answers", "1"),
+                ("pagesize", &max_results.min(100).to_string()),
+            ],
+        )?;
+        let questions: Vec<Question> = self.get(url).await?;
+        if questions.is_empty() {
+            return Err(SearchError::NoResults(query.to_string()));
+        }
+
+        let ids: Vec<u64> = questions.iter().map(|q| q.question_id).collect();
+        let answers = best_answers(self.answers(&ids).await?);
+
+        let results: Vec<WebSearchResult> = questions
+            .into_iter()
+            .filter_map(|question| {
+                let answer = answers.get(&question.question_id)?;
+                Some(answer_result(
+                    question,
+                    answer,
+                    self.config.max_content_length,
+                ))
+            })
+            .collect();
+
+        if results.is_empty() {
+            return Err(SearchError::NoResults(query.to_string()));
+        }
+
+        debug!("Found {} Stack Overflow answers", results.len());
+        Ok(results)
+    }
+
+    async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        if let Some(content) = &result.content {
+            return Ok(content.clone());
+        }
+
+        let id = question_id(&result.url)
+            .ok_or_else(|| SearchError::Parse(format!("Not a question URL: {}", result.url)))?;
+        let answers = best_answers(self.answers(&[id]).await?);
+        let answer = answers
+            .get(&id)
+            .ok_or_else(|| SearchError::NoResults(result.title.clone()))?;
+
+        let mut content = html_to_text(&answer.body);
+        truncate(&mut content, self.config.max_content_length);
+        Ok(content)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn answer(question_id: u64, score: i64, is_accepted: bool) -> Answer {
+        Answer {
+            question_id,
+            score,
+            is_accepted,
+            body: format!("<p>Answer scoring {}</p>", score),
+        }
+    }
+
+    #[test]
+    fn test_config_default() {
+        let config = StackOverflowConfig::default();
+        assert_eq!(config.site, "stackoverflow");
+        assert!(config.api_key.is_none());
+    }
+
+    #[test]
+    fn test_best_answers_prefers_accepted() {
+        let best = best_answers(vec![
+            answer(1, 50, false),
+            answer(1, 10, true),
+            answer(2, 3, false),
+            answer(2, 7, false),
+        ]);
+        assert_eq!(best[&1].score, 10);
+        assert_eq!(best[&2].score, 7);
+    }
+
+    #[test]
+    fn test_answer_result() {
+        let question: Question = serde_json::from_str(
+            r#"{
+                "question_id": 42,
+                "title": "How to convert &amp;str to String?",
+                "link": "https://stackoverflow.com/questions/42/how-to-convert",
+                "score": 120,
+                "tags": ["rust", "string"]
+            }"#,
+        )
+        .unwrap();
+        let answer = Answer {
+            question_id: 42,
+            score: 99,
+            is_accepted: true,
+            body: "<p>Use <code>to_string</code>:</p><pre><code>let s = \"a\".to_string();\n</code></pre>"
+                .to_string(),
+        };
+
+        let result = answer_result(question, &answer, 1000);
+        assert_eq!(result.title, "How to convert &str to String?");
+        assert_eq!(
+            result.best_text(),
+            "Use `to_string`:\n\n```\nlet s = \"a\".to_string();\n```"
+        );
+        assert_eq!(result.metadata["answer_score"], 99);
+        assert_eq!(result.metadata["accepted"], true);
+    }
+
+    #[test]
+    fn test_question_id() {
+        assert_eq!(
+            question_id("https://stackoverflow.com/questions/42/how-to-convert"),
+            Some(42)
+        );
+        assert_eq!(question_id("https://stackoverflow.com/users/1"), None);
+    }
+
+    #[test]
+    fn test_api_error() {
+        let response: ApiResponse<Question> = serde_json::from_str(
+            r#"{"error_id": 502, "error_message": "too many requests", "error_name": "throttle_violation"}"#,
+        )
+        .unwrap();
+        assert!(response.items.is_empty());
+        assert_eq!(response.error_id, Some(THROTTLE_VIOLATION));
+    }
+
+    // Integration test - requires network
+    #[tokio::test]
+    #[ignore = "Requires network"]
+    async fn test_search_integration() {
+        let searcher = StackOverflowSearcher::new();
+        let results = searcher.search("rust borrow checker", 3).await.unwrap();
+
+        assert!(!results.is_empty());
+        assert!(results[0].has_content());
+    }
+}
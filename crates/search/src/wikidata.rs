@@ -0,0 +1,703 @@
+//! Wikidata structured facts
+//!
+//! Factual questions ("capital of France", "when was Ada Lovelace born")
+//! are answered best by a few exact statements, not prose. The
+//! [`WikidataSearcher`] resolves the entities a query names and returns
+//! their key statements (dates, places, populations...) as short
+//! `property: value` lines.
+
+use async_trait::async_trait;
+use reqwest::Client;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::time::Duration;
+use tracing::debug;
+use url::Url;
+
+use crate::error::{Result, SearchError};
+use crate::http::HttpConfig;
+use crate::result::WebSearchResult;
+use crate::searcher::WebSearcher;
+use crate::wikipedia::is_language_code;
+
+/// Statements reported, in order, with their English names
+const FACTS: &[(&str, &str)] = &[
+    ("P31", "instance of"),
+    ("P17", "country"),
+    ("P131", "located in"),
+    ("P30", "continent"),
+    ("P36", "capital"),
+    ("P1376", "capital of"),
+    ("P1082", "population"),
+    ("P2046", "area"),
+    ("P2044", "elevation"),
+    ("P37", "official language"),
+    ("P38", "currency"),
+    ("P35", "head of state"),
+    ("P6", "head of government"),
+    ("P569", "date of birth"),
+    ("P19", "place of birth"),
+    ("P570", "date of death"),
+    ("P20", "place of death"),
+    ("P27", "country of citizenship"),
+    ("P106", "occupation"),
+    ("P166", "award received"),
+    ("P571", "inception"),
+    ("P112", "founded by"),
+    ("P159", "headquarters location"),
+    ("P50", "author"),
+    ("P170", "creator"),
+    ("P57", "director"),
+    ("P577", "publication date"),
+    ("P856", "official website"),
+];
+
+/// Values listed per statement
+const MAX_VALUES: usize = 3;
+
+/// Ids per `wbgetentities` request (the API limit)
+const MAX_IDS: usize = 50;
+
+/// Words that never name the entity asked about
+const QUESTION_WORDS: &[&str] = &[
+    "what", "who", "whom", "whose", "when", "where", "which", "how", "many", "much", "is", "was",
+    "are", "were", "did", "does", "do", "the", "a", "an", "of", "in", "tell", "me", "about", "qué",
+    "que", "quién", "quien", "cuándo", "cuando", "dónde", "donde", "cuál", "cual", "cuántos",
+    "cuántas", "es", "fue", "son", "el", "la", "los", "las", "de", "del", "en",
+];
+
+/// Wikidata search configuration
+#[derive(Debug, Clone)]
+pub struct WikidataConfig {
+    /// Request timeout
+    pub timeout: Duration,
+    /// API endpoint
+    pub api_url: String,
+    /// Language of labels and descriptions; English fills the gaps
+    pub language: String,
+    /// Most statements per entity
+    pub max_facts: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
+}
+
+impl Default for WikidataConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            api_url: "https://www.wikidata.org/w/api.php".to_string(),
+            language: "en".to_string(),
+            max_facts: 20,
+            http: HttpConfig::default(),
+        }
+    }
+}
+
+/// Wikidata structured-facts provider
+///
+/// Each result is one entity; its content lists the entity's key facts.
+pub struct WikidataSearcher {
+    client: Client,
+    config: WikidataConfig,
+}
+
+impl WikidataSearcher {
+    /// Create a new Wikidata searcher with default config
+    pub fn new() -> Self {
+        Self::with_config(WikidataConfig::default())
+    }
+
+    /// Create with custom configuration
+    pub fn with_config(config: WikidataConfig) -> Self {
+        let client = config.http.client(config.timeout);
+
+        Self { client, config }
+    }
+
+    /// Create with labels in `language`
+    pub fn with_language(language: impl Into<String>) -> Self {
+        Self::with_config(WikidataConfig {
+            language: language.into(),
+            ..Default::default()
+        })
+    }
+
+    async fn get<T: for<'de> Deserialize<'de>>(&self, params: &[(&str, &str)]) -> Result<T> {
+        let url = Url::parse_with_params(&self.config.api_url, params)
+            .map_err(|e| SearchError::Parse(e.to_string()))?;
+        let response = self.config.http.send(self.client.get(url)).await?;
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            return Err(SearchError::RateLimited);
+        }
+        Ok(response.error_for_status()?.json().await?)
+    }
+
+    /// Ids of entities whose label matches `phrase`
+    async fn search_entities(
+        &self,
+        phrase: &str,
+        language: &str,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let response: SearchEntitiesResponse = self
+            .get(&[
+                ("action", "wbsearchentities"),
+                ("search", phrase),
+                ("language", language),
+                ("uselang", language),
+                ("type", "item"),
+                ("limit", &limit.to_string()),
+                ("format", "json"),
+            ])
+            .await?;
+        if let Some(error) = response.error {
+            return Err(SearchError::InvalidQuery(error.info));
+        }
+        Ok(response.search.into_iter().map(|m| m.id).collect())
+    }
+
+    /// Entities by id, with `props` in `language` (and English)
+    async fn entities(
+        &self,
+        ids: &[String],
+        props: &str,
+        language: &str,
+    ) -> Result<HashMap<String, Entity>> {
+        let languages = if language == "en" {
+            "en".to_string()
+        } else {
+            format!("{}|en", language)
+        };
+
+        let mut entities = HashMap::new();
+        for ids in ids.chunks(MAX_IDS) {
+            let response: EntitiesResponse = self
+                .get(&[
+                    ("action", "wbgetentities"),
+                    ("ids", &ids.join("|")),
+                    ("props", props),
+                    ("languages", &languages),
+                    ("format", "json"),
+                ])
+                .await?;
+            if let Some(error) = response.error {
+                return Err(SearchError::InvalidQuery(error.info));
+            }
+            entities.extend(response.entities);
+        }
+        Ok(entities)
+    }
+
+    /// Results for the entities `ids`, with their facts as content
+    async fn entity_results(&self, ids: &[String], language: &str) -> Result<Vec<WebSearchResult>> {
+        let entities = self
+            .entities(ids, "labels|descriptions|claims", language)
+            .await?;
+
+        // Items and units the statements point to, named in one request
+        let mut referenced: Vec<String> = entities
+            .values()
+            .flat_map(Entity::referenced_ids)
+            .filter(|id| !entities.contains_key(id))
+            .collect();
+        referenced.sort();
+        referenced.dedup();
+        let mut labels: HashMap<String, String> = self
+            .entities(&referenced, "labels", language)
+            .await?
+            .into_iter()
+            .filter_map(|(id, entity)| Some((id, entity.label(language)?.to_string())))
+            .collect();
+        for (id, entity) in &entities {
+            if let Some(label) = entity.label(language) {
+                labels.insert(id.clone(), label.to_string());
+            }
+        }
+
+        Ok(ids
+            .iter()
+            .filter_map(|id| {
+                let entity = entities.get(id)?;
+                let label = entity.label(language)?;
+                let description = entity.description(language);
+                let facts = entity.facts(label, description, &labels, self.config.max_facts);
+                let snippet = description.unwrap_or_default();
+
+                Some(
+                    WebSearchResult::new(label, entity_url(id), snippet, "Wikidata")
+                        .with_content(facts)
+                        .with_metadata("wikidata_id", id.clone().into()),
+                )
+            })
+            .collect())
+    }
+}
+
+impl Default for WikidataSearcher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ApiError {
+    info: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchEntitiesResponse {
+    #[serde(default)]
+    search: Vec<EntityMatch>,
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntityMatch {
+    id: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct EntitiesResponse {
+    #[serde(default)]
+    entities: HashMap<String, Entity>,
+    error: Option<ApiError>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct Entity {
+    #[serde(default)]
+    labels: HashMap<String, Text>,
+    #[serde(default)]
+    descriptions: HashMap<String, Text>,
+    #[serde(default)]
+    claims: HashMap<String, Vec<Claim>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Text {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Claim {
+    mainsnak: Snak,
+    #[serde(default)]
+    rank: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct Snak {
+    #[serde(default, deserialize_with = "known_value")]
+    datavalue: Option<DataValue>,
+}
+
+/// A value of a supported type; others (external ids, math...) are dropped
+fn known_value<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> std::result::Result<Option<DataValue>, D::Error> {
+    let value = serde_json::Value::deserialize(deserializer)?;
+    Ok(serde_json::from_value(value).ok())
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", content = "value")]
+enum DataValue {
+    #[serde(rename = "string")]
+    String(String),
+    #[serde(rename = "wikibase-entityid")]
+    Entity { id: String },
+    #[serde(rename = "time")]
+    Time { time: String, precision: u8 },
+    #[serde(rename = "quantity")]
+    Quantity { amount: String, unit: String },
+    #[serde(rename = "monolingualtext")]
+    Text { text: String },
+    #[serde(rename = "globecoordinate")]
+    Coordinate { latitude: f64, longitude: f64 },
+}
+
+impl Entity {
+    fn label(&self, language: &str) -> Option<&str> {
+        localized(&self.labels, language)
+    }
+
+    fn description(&self, language: &str) -> Option<&str> {
+        localized(&self.descriptions, language)
+    }
+
+    /// Values of `property`: preferred ones if any, never deprecated ones
+    fn values(&self, property: &str) -> Vec<&DataValue> {
+        let claims = self
+            .claims
+            .get(property)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let preferred = claims.iter().any(|claim| claim.rank == "preferred");
+        claims
+            .iter()
+            .filter(|claim| claim.rank != "deprecated")
+            .filter(|claim| !preferred || claim.rank == "preferred")
+            .filter_map(|claim| claim.mainsnak.datavalue.as_ref())
+            .collect()
+    }
+
+    /// Items and units the reported statements point to
+    fn referenced_ids(&self) -> Vec<String> {
+        FACTS
+            .iter()
+            .flat_map(|(property, _)| self.values(property).into_iter().take(MAX_VALUES))
+            .filter_map(|value| match value {
+                DataValue::Entity { id } => Some(id.clone()),
+                DataValue::Quantity { unit, .. } => unit_id(unit).map(str::to_string),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The entity named and described, then its facts, one
+    /// `property: value` line each
+    fn facts(
+        &self,
+        label: &str,
+        description: Option<&str>,
+        labels: &HashMap<String, String>,
+        max_facts: usize,
+    ) -> String {
+        let mut lines = vec![match description {
+            Some(description) => format!("{} ({})", label, description),
+            None => label.to_string(),
+        }];
+        for (property, name) in FACTS {
+            if lines.len() > max_facts {
+                break;
+            }
+            let values: Vec<String> = self
+                .values(property)
+                .into_iter()
+                .filter_map(|value| format_value(value, labels))
+                .take(MAX_VALUES)
+                .collect();
+            if !values.is_empty() {
+                lines.push(format!("{}: {}", name, values.join(", ")));
+            }
+        }
+        lines.join("\n")
+    }
+}
+
+fn localized<'a>(texts: &'a HashMap<String, Text>, language: &str) -> Option<&'a str> {
+    texts
+        .get(language)
+        .or_else(|| texts.get("en"))
+        .map(|text| text.value.as_str())
+}
+
+fn entity_url(id: &str) -> String {
+    format!("https://www.wikidata.org/wiki/{}", id)
+}
+
+/// Entity id in a Wikidata entity URL
+fn entity_id(url: &str) -> Option<&str> {
+    let id = url.rsplit('/').next()?;
+    let valid = id.len() > 1 && id.starts_with('Q') && id[1..].chars().all(|c| c.is_ascii_digit());
+    valid.then_some(id)
+}
+
+/// Item id of a quantity unit; `"1"` means no unit
+fn unit_id(unit: &str) -> Option<&str> {
+    entity_id(unit)
+}
+
+fn format_value(value: &DataValue, labels: &HashMap<String, String>) -> Option<String> {
+    Some(match value {
+        DataValue::String(text) | DataValue::Text { text } => text.clone(),
+        DataValue::Entity { id } => labels.get(id)?.clone(),
+        DataValue::Time { time, precision } => format_time(time, *precision)?,
+        DataValue::Quantity { amount, unit } => {
+            let amount = format_amount(amount);
+            match unit_id(unit).and_then(|id| labels.get(id)) {
+                Some(unit) => format!("{} {}", amount, unit),
+                None => amount,
+            }
+        }
+        DataValue::Coordinate {
+            latitude,
+            longitude,
+        } => format!("{:.4}, {:.4}", latitude, longitude),
+    })
+}
+
+/// Wikidata time (`+1815-12-10T00:00:00Z`) at its precision: 11 is a day,
+/// 10 a month, 9 a year, 8 a decade, 7 a century
+fn format_time(time: &str, precision: u8) -> Option<String> {
+    let bc = time.starts_with('-');
+    let mut parts = time.trim_start_matches(['+', '-']).split(['-', 'T']);
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month = parts.next()?;
+    let day = parts.next()?;
+
+    let date = match precision {
+        11.. => format!("{}-{}-{}", year, month, day),
+        10 => format!("{}-{}", year, month),
+        9 => year.to_string(),
+        8 => format!("{}s", year / 10 * 10),
+        _ => format!("century {}", (year - 1) / 100 + 1),
+    };
+    Some(if bc { format!("{} BC", date) } else { date })
+}
+
+/// Amount with thousands separators: `+68373433` -> `68,373,433`
+fn format_amount(amount: &str) -> String {
+    let amount = amount.trim_start_matches('+');
+    let (sign, amount) = match amount.strip_prefix('-') {
+        Some(rest) => ("-", rest),
+        None => ("", amount),
+    };
+    let (whole, fraction) = match amount.split_once('.') {
+        Some((whole, fraction)) => (whole, Some(fraction)),
+        None => (amount, None),
+    };
+
+    let mut grouped = String::new();
+    for (i, digit) in whole.chars().enumerate() {
+        if i > 0 && (whole.len() - i) % 3 == 0 {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    match fraction {
+        Some(fraction) => format!("{}{}.{}", sign, grouped, fraction),
+        None => format!("{}{}", sign, grouped),
+    }
+}
+
+/// Phrases of `query` likely to name an entity, most specific first:
+/// runs of capitalized words, what follows the last "of", then the query
+/// without question words
+fn entity_phrases(query: &str) -> Vec<String> {
+    let words: Vec<&str> = query
+        .split_whitespace()
+        .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric() && c != '-' && c != '\''))
+        .filter(|word| !word.is_empty())
+        .collect();
+    let is_question_word = |word: &str| QUESTION_WORDS.contains(&word.to_lowercase().as_str());
+
+    let mut phrases: Vec<String> = Vec::new();
+    let mut run: Vec<&str> = Vec::new();
+    for word in words.iter().copied().chain([""]) {
+        let capitalized = word.chars().next().is_some_and(char::is_uppercase);
+        if capitalized && !is_question_word(word) {
+            run.push(word);
+        } else if !run.is_empty() {
+            phrases.push(run.join(" "));
+            run.clear();
+        }
+    }
+
+    if let Some(of) = words
+        .iter()
+        .rposition(|word| matches!(word.to_lowercase().as_str(), "of" | "de" | "del"))
+    {
+        let rest: Vec<&str> = words[of + 1..]
+            .iter()
+            .copied()
+            .filter(|word| !is_question_word(word))
+            .collect();
+        if !rest.is_empty() {
+            phrases.push(rest.join(" "));
+        }
+    }
+
+    let content: Vec<&str> = words
+        .iter()
+        .copied()
+        .filter(|word| !is_question_word(word))
+        .collect();
+    if !content.is_empty() {
+        phrases.push(content.join(" "));
+    }
+
+    let mut unique = Vec::new();
+    for phrase in phrases {
+        if !unique.contains(&phrase) {
+            unique.push(phrase);
+        }
+    }
+    unique
+}
+
+#[async_trait]
+impl WebSearcher for WikidataSearcher {
+    fn name(&self) -> &str {
+        "Wikidata"
+    }
+
+    async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        self.search_in_language(query, max_results, "auto").await
+    }
+
+    async fn search_in_language(
+        &self,
+        query: &str,
+        max_results: usize,
+        language: &str,
+    ) -> Result<Vec<WebSearchResult>> {
+        if query.trim().is_empty() {
+            return Err(SearchError::InvalidQuery("Empty query".into()));
+        }
+        let language = match language {
+            "auto" => self.config.language.as_str(),
+            code if is_language_code(code) => code,
+            code => {
+                return Err(SearchError::InvalidQuery(format!(
+                    "Invalid language code: {}",
+                    code
+                )))
+            }
+        };
+
+        debug!("Searching Wikidata for: {}", query);
+        for phrase in entity_phrases(query) {
+            let ids = self.search_entities(&phrase, language, max_results).await?;
+            if !ids.is_empty() {
+                debug!("Wikidata entities for {:?}: {:?}", phrase, ids);
+                return self.entity_results(&ids, language).await;
+            }
+        }
+
+        Err(SearchError::NoResults(query.to_string()))
+    }
+
+    async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
+        if let Some(content) = &result.content {
+            return Ok(content.clone());
+        }
+
+        let id = entity_id(&result.url)
+            .ok_or_else(|| SearchError::Parse(format!("Not a Wikidata URL: {}", result.url)))?;
+        self.entity_results(&[id.to_string()], &self.config.language)
+            .await?
+            .into_iter()
+            .next()
+            .and_then(|result| result.content)
+            .ok_or_else(|| SearchError::NoResults(result.url.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PARIS: &str = r#"{
+        "labels": {"en": {"language": "en", "value": "Paris"}},
+        "descriptions": {"en": {"language": "en", "value": "capital of France"}},
+        "claims": {
+            "P31": [{"mainsnak": {"datavalue": {"type": "wikibase-entityid",
+                "value": {"entity-type": "item", "numeric-id": 515, "id": "Q515"}}}, "rank": "normal"}],
+            "P1082": [
+                {"mainsnak": {"datavalue": {"type": "quantity",
+                    "value": {"amount": "+2165423", "unit": "1"}}}, "rank": "normal"},
+                {"mainsnak": {"datavalue": {"type": "quantity",
+                    "value": {"amount": "+2102650", "unit": "1"}}}, "rank": "preferred"}
+            ],
+            "P2046": [{"mainsnak": {"datavalue": {"type": "quantity",
+                "value": {"amount": "+105.4", "unit": "http://www.wikidata.org/entity/Q712226"}}}, "rank": "normal"}],
+            "P571": [{"mainsnak": {"datavalue": {"type": "time",
+                "value": {"time": "-0250-00-00T00:00:00Z", "precision": 7}}}, "rank": "normal"}],
+            "P856": [{"mainsnak": {"datavalue": {"type": "string", "value": "https://www.paris.fr/"}}, "rank": "normal"}],
+            "P17": [{"mainsnak": {"snaktype": "somevalue"}, "rank": "normal"}],
+            "P1435": [{"mainsnak": {"datavalue": {"type": "external-id-like", "value": {}}}, "rank": "normal"}]
+        }
+    }"#;
+
+    #[test]
+    fn test_facts() {
+        let entity: Entity = serde_json::from_str(PARIS).unwrap();
+        let mut referenced = entity.referenced_ids();
+        referenced.sort();
+        assert_eq!(referenced, ["Q515", "Q712226"]);
+
+        let labels = HashMap::from([
+            ("Q515".to_string(), "city".to_string()),
+            ("Q712226".to_string(), "square kilometre".to_string()),
+        ]);
+        assert_eq!(
+            entity.facts("Paris", entity.description("en"), &labels, 20),
+            "Paris (capital of France)\n\
+             instance of: city\n\
+             population: 2,102,650\n\
+             area: 105.4 square kilometre\n\
+             inception: century 3 BC\n\
+             official website: https://www.paris.fr/"
+        );
+        assert_eq!(entity.facts("Paris", None, &labels, 2).lines().count(), 3);
+    }
+
+    #[test]
+    fn test_format_time() {
+        assert_eq!(
+            format_time("+1815-12-10T00:00:00Z", 11).as_deref(),
+            Some("1815-12-10")
+        );
+        assert_eq!(
+            format_time("+1815-12-00T00:00:00Z", 10).as_deref(),
+            Some("1815-12")
+        );
+        assert_eq!(
+            format_time("+1815-00-00T00:00:00Z", 9).as_deref(),
+            Some("1815")
+        );
+        assert_eq!(
+            format_time("+1815-00-00T00:00:00Z", 8).as_deref(),
+            Some("1810s")
+        );
+        assert_eq!(
+            format_time("-0044-03-15T00:00:00Z", 11).as_deref(),
+            Some("44-03-15 BC")
+        );
+        assert_eq!(format_time("garbage", 11), None);
+    }
+
+    #[test]
+    fn test_format_amount() {
+        assert_eq!(format_amount("+68373433"), "68,373,433");
+        assert_eq!(format_amount("+999"), "999");
+        assert_eq!(format_amount("-1234.5"), "-1,234.5");
+    }
+
+    #[test]
+    fn test_entity_phrases() {
+        assert_eq!(
+            entity_phrases("When was Ada Lovelace born?"),
+            ["Ada Lovelace", "Ada Lovelace born"]
+        );
+        assert_eq!(
+            entity_phrases("what is the population of new york city"),
+            ["new york city", "population new york city"]
+        );
+        assert_eq!(
+            entity_phrases("¿Cuál es la capital de Francia?"),
+            ["Francia", "capital Francia"]
+        );
+    }
+
+    #[test]
+    fn test_entity_id() {
+        assert_eq!(entity_id("https://www.wikidata.org/wiki/Q90"), Some("Q90"));
+        assert_eq!(
+            entity_id("http://www.wikidata.org/entity/Q712226"),
+            Some("Q712226")
+        );
+        assert_eq!(entity_id("1"), None);
+        assert_eq!(entity_id("https://example.com/Qx"), None);
+    }
+
+    // Integration test - requires network
+    #[tokio::test]
+    #[ignore = "Requires network"]
+    async fn test_search_integration() {
+        let searcher = WikidataSearcher::new();
+        let results = searcher.search("capital of France", 1).await.unwrap();
+        assert!(results[0].content.as_deref().unwrap().contains("Paris"));
+    }
+}
@@ -1,4 +1,9 @@
 //! Wikipedia search implementation
+//!
+//! Queries go to the Wikipedia edition of the query's language, detected
+//! among [`WikipediaConfig::languages`], so a Spanish question gets Spanish
+//! articles; queries whose language is unclear use
+//! [`WikipediaConfig::language`].
 
 use async_trait::async_trait;
 use reqwest::Client;
@@ -9,6 +14,7 @@ use tracing::{debug, warn};
 use url::Url;
 
 use crate::error::{Result, SearchError};
+use crate::http::HttpConfig;
 use crate::result::WebSearchResult;
 use crate::searcher::WebSearcher;
 
@@ -17,10 +23,17 @@ use crate::searcher::WebSearcher;
 pub struct WikipediaConfig {
     /// Request timeout
     pub timeout: Duration,
-    /// Language code (e.g., "en", "es")
+    /// Language code (e.g., "en", "es"); the edition used when the query
+    /// language is not detected, or detection is off
     pub language: String,
+    /// Pick the edition from the query language
+    pub auto_language: bool,
+    /// Editions auto-detection chooses from
+    pub languages: Vec<String>,
     /// Maximum content length to fetch
     pub max_content_length: usize,
+    /// Connect/read timeouts, retries and proxy
+    pub http: HttpConfig,
 }
 
 impl Default for WikipediaConfig {
@@ -28,7 +41,10 @@ impl Default for WikipediaConfig {
         Self {
             timeout: Duration::from_secs(10),
             language: "en".to_string(),
+            auto_language: true,
+            languages: ["en", "es", "fr", "de", "pt"].map(String::from).to_vec(),
             max_content_length: 10000,
+            http: HttpConfig::default(),
         }
     }
 }
@@ -47,38 +63,112 @@ impl WikipediaSearcher {
 
     /// Create with custom configuration
     pub fn with_config(config: WikipediaConfig) -> Self {
-        let client = Client::builder()
-            .timeout(config.timeout)
-            .user_agent("neuro-bitnet/0.1 (RAG system)")
-            .build()
-            .expect("Failed to build HTTP client");
+        let client = config.http.client(config.timeout);
 
         Self { client, config }
     }
 
-    /// Create with specific language
+    /// Create with specific language, whatever the query language
     pub fn with_language(language: impl Into<String>) -> Self {
-        let mut config = WikipediaConfig::default();
-        config.language = language.into();
-        Self::with_config(config)
+        Self::with_config(WikipediaConfig {
+            language: language.into(),
+            auto_language: false,
+            ..WikipediaConfig::default()
+        })
     }
 
-    fn api_url(&self) -> String {
-        format!(
-            "https://{}.wikipedia.org/w/api.php",
-            self.config.language
-        )
+    /// Edition `query` is searched in
+    pub fn query_language<'a>(&'a self, query: &str) -> &'a str {
+        if !self.config.auto_language {
+            return &self.config.language;
+        }
+        detect_language(query, &self.config.languages).unwrap_or(&self.config.language)
     }
 
-    fn article_url(&self, title: &str) -> String {
-        format!(
-            "https://{}.wikipedia.org/wiki/{}",
-            self.config.language,
-            urlencoding::encode(title)
-        )
+    /// Edition a result comes from, from its URL host
+    fn result_language<'a>(&'a self, result: &'a WebSearchResult) -> &'a str {
+        result
+            .url
+            .strip_prefix("https://")
+            .and_then(|rest| rest.split_once(".wikipedia.org/"))
+            .map(|(language, _)| language)
+            .filter(|language| is_language_code(language))
+            .unwrap_or(&self.config.language)
     }
 }
 
+fn api_url(language: &str) -> String {
+    format!("https://{}.wikipedia.org/w/api.php", language)
+}
+
+fn article_url(language: &str, title: &str) -> String {
+    format!(
+        "https://{}.wikipedia.org/wiki/{}",
+        language,
+        urlencoding::encode(title)
+    )
+}
+
+/// Whether `code` can name a Wikipedia edition (and is safe in a host name)
+pub(crate) fn is_language_code(code: &str) -> bool {
+    (2..=12).contains(&code.len())
+        && code.chars().all(|c| c.is_ascii_lowercase() || c == '-')
+        && !code.starts_with('-')
+}
+
+/// Characters that only appear in one of the usual editions
+const MARKERS: &[(char, &str)] = &[
+    ('¿', "es"),
+    ('¡', "es"),
+    ('ñ', "es"),
+    ('ß', "de"),
+    ('ã', "pt"),
+    ('õ', "pt"),
+];
+
+/// Language of `query` among `editions`, if detected reliably
+fn detect_language<'a>(query: &str, editions: &'a [String]) -> Option<&'a str> {
+    let edition = |code: &str| editions.iter().find(|e| *e == code).map(String::as_str);
+
+    let allowlist: Vec<whatlang::Lang> = editions.iter().filter_map(|e| whatlang_lang(e)).collect();
+    if allowlist.len() > 1 {
+        let detected = whatlang::Detector::with_allowlist(allowlist)
+            .detect(query)
+            .filter(|info| info.is_reliable());
+        if let Some(info) = detected {
+            return editions
+                .iter()
+                .find(|e| whatlang_lang(e) == Some(info.lang()))
+                .map(String::as_str);
+        }
+    }
+
+    let lower = query.to_lowercase();
+    MARKERS
+        .iter()
+        .find(|(marker, _)| lower.contains(*marker))
+        .and_then(|(_, code)| edition(code))
+}
+
+fn whatlang_lang(code: &str) -> Option<whatlang::Lang> {
+    use whatlang::Lang;
+    Some(match code {
+        "en" => Lang::Eng,
+        "es" => Lang::Spa,
+        "fr" => Lang::Fra,
+        "de" => Lang::Deu,
+        "pt" => Lang::Por,
+        "it" => Lang::Ita,
+        "nl" => Lang::Nld,
+        "ru" => Lang::Rus,
+        "pl" => Lang::Pol,
+        "uk" => Lang::Ukr,
+        "ja" => Lang::Jpn,
+        "zh" => Lang::Cmn,
+        _ => return None,
+    })
+}
+
 impl Default for WikipediaSearcher {
     fn default() -> Self {
         Self::new()
@@ -116,14 +206,33 @@ impl WebSearcher for WikipediaSearcher {
     }
 
     async fn search(&self, query: &str, max_results: usize) -> Result<Vec<WebSearchResult>> {
+        self.search_in_language(query, max_results, "auto").await
+    }
+
+    async fn search_in_language(
+        &self,
+        query: &str,
+        max_results: usize,
+        language: &str,
+    ) -> Result<Vec<WebSearchResult>> {
         if query.trim().is_empty() {
             return Err(SearchError::InvalidQuery("Empty query".into()));
         }
+        let language = match language {
+            "auto" => self.query_language(query),
+            code if is_language_code(code) => code,
+            code => {
+                return Err(SearchError::InvalidQuery(format!(
+                    "Invalid language code: {}",
+                    code
+                )))
+            }
+        };
 
-        debug!("Searching Wikipedia for: {}", query);
+        debug!("Searching {} Wikipedia for: {}", language, query);
 
         let url = Url::parse_with_params(
-            &self.api_url(),
+            &api_url(language),
             &[
                 ("action", "query"),
                 ("list", "search"),
@@ -136,9 +245,9 @@ impl WebSearcher for WikipediaSearcher {
         .map_err(|e| SearchError::Parse(e.to_string()))?;
 
         let response = self
-            .client
-            .get(url)
-            .send()
+            .config
+            .http
+            .send(self.client.get(url))
             .await?
             .json::<WikiSearchResponse>()
             .await?;
@@ -160,7 +269,7 @@ impl WebSearcher for WikipediaSearcher {
                 
                 WebSearchResult::new(
                     r.title.clone(),
-                    self.article_url(&r.title),
+                    article_url(language, &r.title),
                     snippet,
                     "Wikipedia",
                 )
@@ -174,9 +283,9 @@ impl WebSearcher for WikipediaSearcher {
     async fn fetch_content(&self, result: &WebSearchResult) -> Result<String> {
         debug!("Fetching Wikipedia content for: {}", result.title);
 
-        // Use the extracts API for clean text
+        // Use the extracts API for clean text, from the result's edition
         let url = Url::parse_with_params(
-            &self.api_url(),
+            &api_url(self.result_language(result)),
             &[
                 ("action", "query"),
                 ("titles", &result.title),
@@ -191,9 +300,9 @@ impl WebSearcher for WikipediaSearcher {
         .map_err(|e| SearchError::Parse(e.to_string()))?;
 
         let response = self
-            .client
-            .get(url)
-            .send()
+            .config
+            .http
+            .send(self.client.get(url))
             .await?
             .json::<WikiSearchResponse>()
             .await?;
@@ -263,8 +372,7 @@ mod tests {
 
     #[test]
     fn test_article_url() {
-        let searcher = WikipediaSearcher::new();
-        let url = searcher.article_url("Rust (programming language)");
+        let url = article_url("en", "Rust (programming language)");
         assert!(url.contains("wikipedia.org/wiki/"));
         assert!(url.contains("Rust"));
     }
@@ -272,7 +380,42 @@ mod tests {
     #[test]
     fn test_api_url() {
         let searcher = WikipediaSearcher::with_language("es");
-        assert!(searcher.api_url().contains("es.wikipedia.org"));
+        assert!(api_url(searcher.query_language("Rust")).contains("es.wikipedia.org"));
+    }
+
+    #[test]
+    fn test_query_language() {
+        let searcher = WikipediaSearcher::new();
+        assert_eq!(searcher.query_language("¿Quién pintó la Mona Lisa?"), "es");
+        assert_eq!(
+            searcher.query_language("Quelle est la capitale de la France et pourquoi"),
+            "fr"
+        );
+        assert_eq!(
+            searcher.query_language("What is the capital of France and why"),
+            "en"
+        );
+        // Too short to tell
+        assert_eq!(searcher.query_language("Rust"), "en");
+
+        let pinned = WikipediaSearcher::with_language("de");
+        assert_eq!(pinned.query_language("¿Quién pintó la Mona Lisa?"), "de");
+    }
+
+    #[test]
+    fn test_result_language() {
+        let searcher = WikipediaSearcher::new();
+        let spanish = WebSearchResult::new("Rust", article_url("es", "Rust"), "", "Wikipedia");
+        assert_eq!(searcher.result_language(&spanish), "es");
+        let other = WebSearchResult::new("Rust", "https://evil.example/x", "", "Wikipedia");
+        assert_eq!(searcher.result_language(&other), "en");
+    }
+
+    #[tokio::test]
+    async fn test_rejects_invalid_language() {
+        let searcher = WikipediaSearcher::new();
+        let result = searcher.search_in_language("Rust", 3, "evil.example/").await;
+        assert!(matches!(result, Err(SearchError::InvalidQuery(_))));
     }
 
     // Integration test - requires network
@@ -0,0 +1,367 @@
+//! Answering queries with a model for `/ask`
+//!
+//! The passages a query retrieved become numbered [`Source`]s. The prompt
+//! lists them as `[1]`, `[2]`, ... and asks the model to cite them the same
+//! way, so the response can tell which sources the answer relies on.
+//!
+//! A local GGUF model is used when the server loaded one, otherwise the
+//! configured LLM server.
+
+use futures::StreamExt;
+use serde::Serialize;
+use serde_json::json;
+use tracing::debug;
+
+use neuro_core::SearchResult;
+use neuro_inference::{GenerateOptions, InferenceModel, SamplerConfig};
+use neuro_llm::LlmClient;
+use neuro_search::WebSearchResult;
+
+use crate::config::ServerConfig;
+use crate::error::{Result, ServerError};
+use crate::state::AppState;
+use crate::stream::EventSink;
+
+/// Instructions for answering from numbered sources
+const ASK_INSTRUCTIONS: &str = "You are a helpful assistant. Answer the question using \
+     the numbered sources in the context and cite each source you use as [n]. If the \
+     sources do not contain the answer, say so. Respond in the same language as the \
+     question.";
+
+/// Tokens kept free for the instructions and prompt labels of a local model
+const PROMPT_OVERHEAD_TOKENS: usize = 96;
+
+/// A passage the answer can cite
+#[derive(Debug, Clone, Serialize)]
+pub struct Source {
+    /// Number the answer cites the source by, from 1
+    pub id: usize,
+    /// Title, file name or URL of the passage's document
+    pub title: String,
+    /// Page the passage comes from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+    /// Stored document holding the passage; `None` for live web results
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub document_id: Option<String>,
+    /// Search score of stored documents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub score: Option<f32>,
+    /// The passage
+    pub text: String,
+    /// Whether the answer cites this source
+    pub cited: bool,
+}
+
+/// Time spent on each step of an `/ask` request, in milliseconds
+#[derive(Debug, Clone, Copy, Default, Serialize)]
+pub struct AskTimings {
+    pub classification_ms: u64,
+    /// Storage and web search
+    pub retrieval_ms: u64,
+    pub generation_ms: u64,
+    pub total_ms: u64,
+}
+
+/// Number the passages a query retrieved
+///
+/// Stored documents come first, chosen like
+/// [`QueryResult::build_context`](neuro_core::QueryResult::build_context):
+/// weak matches are skipped and documents stop once `max_length` bytes are
+/// used. All web results follow.
+pub fn collect_sources(
+    search_results: &[SearchResult],
+    web_results: &[WebSearchResult],
+    max_length: usize,
+) -> Vec<Source> {
+    let mut sources = Vec::new();
+    let mut length = 0;
+
+    for result in search_results {
+        if result.is_weak_match() {
+            continue;
+        }
+
+        let doc = &result.document;
+        length += doc.content.len();
+        if length > max_length {
+            break;
+        }
+
+        let metadata = |key: &str| doc.metadata.get(key).and_then(|v| v.as_str());
+        let url = metadata("url").map(str::to_string);
+        let title = metadata("title")
+            .or_else(|| metadata("file_name"))
+            .or_else(|| metadata("file_path"))
+            .or(url.as_deref())
+            .unwrap_or(&doc.id)
+            .to_string();
+
+        sources.push(Source {
+            id: sources.len() + 1,
+            title,
+            url,
+            document_id: Some(doc.id.clone()),
+            score: Some(result.score),
+            text: doc.content.clone(),
+            cited: false,
+        });
+    }
+
+    for result in web_results {
+        sources.push(Source {
+            id: sources.len() + 1,
+            title: result.title.clone(),
+            url: Some(result.url.clone()),
+            document_id: None,
+            score: None,
+            text: result.best_text().to_string(),
+            cited: false,
+        });
+    }
+
+    sources
+}
+
+/// Lay sources out for the prompt, each headed by `[n] title`
+pub fn numbered_context(sources: &[Source]) -> String {
+    sources
+        .iter()
+        .map(|s| format!("[{}] {}\n{}", s.id, s.title, s.text))
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Mark the sources the answer cites as `[n]` or `[n, m]`
+pub fn mark_cited(sources: &mut [Source], answer: &str) {
+    let mut rest = answer;
+    while let Some(open) = rest.find('[') {
+        rest = &rest[open + 1..];
+        let Some(close) = rest.find(']') else {
+            break;
+        };
+
+        let ids: Option<Vec<usize>> = rest[..close]
+            .split(',')
+            .map(|id| id.trim().parse().ok())
+            .collect();
+        for id in ids.unwrap_or_default() {
+            if let Some(source) = id.checked_sub(1).and_then(|i| sources.get_mut(i)) {
+                source.cited = true;
+            }
+        }
+    }
+}
+
+/// Name of the model that answers `/ask`, if the server has one
+pub fn model_name(state: &AppState) -> Option<String> {
+    if state.local_model.is_some() {
+        let path = state.config.model_path.as_ref()?;
+        return Some(path.file_stem()?.to_string_lossy().into_owned());
+    }
+    state.llm.as_ref().map(|llm| llm.model().to_string())
+}
+
+/// Answer `question` from `context`, sending each token to `events`
+pub async fn generate(
+    state: &AppState,
+    question: &str,
+    context: &str,
+    events: &EventSink,
+) -> Result<String> {
+    let answer = if let Some(ref model) = state.local_model {
+        generate_local(model, &state.config, question, context, events).await?
+    } else if let Some(ref llm) = state.llm {
+        generate_remote(llm, question, context, events).await?
+    } else {
+        return Err(ServerError::BadRequest(
+            "No model is configured on this server".to_string(),
+        ));
+    };
+    Ok(answer.trim().to_string())
+}
+
+async fn generate_local(
+    model: &InferenceModel,
+    config: &ServerConfig,
+    question: &str,
+    context: &str,
+    events: &EventSink,
+) -> Result<String> {
+    let context = fit_context(model, question, context, config.max_tokens).await;
+    let prompt = if context.is_empty() {
+        format!("{}\n\nQ: {}\nA:", ASK_INSTRUCTIONS, question)
+    } else {
+        format!(
+            "{}\n\nContext:\n{}\n\nQ: {}\nA:",
+            ASK_INSTRUCTIONS, context, question
+        )
+    };
+
+    let options = GenerateOptions::new(config.max_tokens)
+        .with_sampler(SamplerConfig::default().with_temperature(config.temperature))
+        .with_stop_sequence("\n\n")
+        .with_stop_sequence("\nQ:")
+        .with_stop_sequence("\nQuestion:");
+
+    if !events.is_streaming() {
+        return Ok(model.generate_async(&prompt, &options).await?);
+    }
+
+    let mut tokens = model.generate_stream(&prompt, &options);
+    let mut answer = String::new();
+    while let Some(token) = tokens.next().await {
+        if !events.send("token", &json!({ "token": token })).await {
+            // Dropping the stream cancels the generation
+            debug!("Client went away, stopping generation");
+            return Ok(answer);
+        }
+        answer.push_str(&token);
+    }
+    Ok(tokens.finish().await?)
+}
+
+/// Cut `context` to what fits in the model's window next to the question
+/// and the answer
+async fn fit_context(
+    model: &InferenceModel,
+    question: &str,
+    context: &str,
+    max_tokens: u32,
+) -> String {
+    if context.is_empty() {
+        return String::new();
+    }
+
+    let model = model.clone();
+    let question = question.to_string();
+    let full = context.to_string();
+    let fitted = tokio::task::spawn_blocking(move || {
+        let question_tokens = model.count_tokens(&question)?;
+        let budget = (model.context_size() as usize)
+            .saturating_sub(max_tokens as usize + question_tokens + PROMPT_OVERHEAD_TOKENS);
+        model.truncate_to_tokens(&full, budget)
+    })
+    .await;
+
+    match fitted {
+        Ok(Ok(fitted)) => fitted,
+        Ok(Err(e)) => {
+            debug!("Could not fit context to the model, using it whole: {}", e);
+            context.to_string()
+        }
+        Err(e) => {
+            debug!("Context fitting task failed, using it whole: {}", e);
+            context.to_string()
+        }
+    }
+}
+
+async fn generate_remote(
+    llm: &LlmClient,
+    question: &str,
+    context: &str,
+    events: &EventSink,
+) -> Result<String> {
+    if !events.is_streaming() {
+        return Ok(llm
+            .ask_with_context(question, context, Some(ASK_INSTRUCTIONS))
+            .await?);
+    }
+
+    let mut tokens = llm
+        .ask_with_context_stream(question, context, Some(ASK_INSTRUCTIONS))
+        .await?;
+    let mut answer = String::new();
+    while let Some(token) = tokens.next().await {
+        let token = token?;
+        if !events.send("token", &json!({ "token": token })).await {
+            debug!("Client went away, stopping generation");
+            break;
+        }
+        answer.push_str(&token);
+    }
+    Ok(answer)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neuro_core::Document;
+
+    fn stored(content: &str, score: f32, metadata: &[(&str, &str)]) -> SearchResult {
+        let mut doc = Document::new(content);
+        for (key, value) in metadata {
+            doc = doc.with_metadata(*key, (*value).into());
+        }
+        SearchResult::new(doc, score)
+    }
+
+    #[test]
+    fn test_collect_sources() {
+        let results = vec![
+            stored(
+                "Paris is the capital of France.",
+                0.9,
+                &[("file_name", "france.txt")],
+            ),
+            stored("Unrelated text.", 0.1, &[]),
+            stored(
+                "France is in Europe.",
+                0.8,
+                &[("title", "Europe"), ("url", "https://example.com")],
+            ),
+        ];
+        let web = vec![WebSearchResult::new(
+            "France",
+            "https://en.wikipedia.org/wiki/France",
+            "France is a country.",
+            "Wikipedia",
+        )];
+
+        let sources = collect_sources(&results, &web, 10_000);
+
+        let titles: Vec<&str> = sources.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["france.txt", "Europe", "France"]);
+        assert_eq!(sources.iter().map(|s| s.id).collect::<Vec<_>>(), [1, 2, 3]);
+        assert_eq!(sources[1].url.as_deref(), Some("https://example.com"));
+        assert!(sources[2].document_id.is_none());
+        assert!(sources.iter().all(|s| !s.cited));
+    }
+
+    #[test]
+    fn test_collect_sources_respects_length() {
+        let results = vec![
+            stored("first passage", 0.9, &[]),
+            stored("second passage", 0.9, &[]),
+        ];
+
+        let sources = collect_sources(&results, &[], 20);
+
+        assert_eq!(sources.len(), 1);
+        assert_eq!(sources[0].title, results[0].document.id);
+    }
+
+    #[test]
+    fn test_numbered_context() {
+        let results = vec![stored("Paris.", 0.9, &[("title", "Capital")])];
+        let sources = collect_sources(&results, &[], 1000);
+
+        assert_eq!(numbered_context(&sources), "[1] Capital\nParis.");
+    }
+
+    #[test]
+    fn test_mark_cited() {
+        let results = vec![
+            stored("a", 0.9, &[]),
+            stored("b", 0.9, &[]),
+            stored("c", 0.9, &[]),
+        ];
+        let mut sources = collect_sources(&results, &[], 1000);
+
+        mark_cited(&mut sources, "Yes [1], as [1, 3] say. See [note] and [7].");
+
+        let cited: Vec<bool> = sources.iter().map(|s| s.cited).collect();
+        assert_eq!(cited, [true, false, true]);
+    }
+}
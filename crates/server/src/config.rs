@@ -2,6 +2,11 @@
 
 use std::path::PathBuf;
 
+use neuro_llm::ProviderKind;
+
+use crate::cors::CorsConfig;
+use crate::ratelimit::RateLimitConfig;
+
 /// Server configuration
 #[derive(Debug, Clone)]
 pub struct ServerConfig {
@@ -17,15 +22,63 @@ pub struct ServerConfig {
     /// Embedding model to use
     pub embedding_model: String,
     
+    /// OpenAI-compatible embeddings server; `embedding_model` is then the
+    /// remote model name instead of a local one
+    pub embedding_url: Option<String>,
+    
+    /// API key for the embeddings server
+    pub embedding_api_key: Option<String>,
+    
+    /// Device for the local embedding model (`cpu`, `cuda`, `cuda:1`)
+    pub embedding_device: String,
+    
+    /// Sparse embedder (`bm25` or `splade`) stored alongside dense
+    /// embeddings; queries use hybrid search when set
+    pub sparse_model: Option<String>,
+    
+    /// Cross-encoder model for `rerank: true` queries; reranking is
+    /// unavailable when unset
+    pub reranker_model: Option<String>,
+    
+    /// Local GGUF model that answers `/ask`; preferred over `llm_url`
+    pub model_path: Option<PathBuf>,
+    
+    /// Context window of the local model, in tokens
+    pub context_size: u32,
+    
+    /// LLM server that answers `/ask` when no local model is set; the
+    /// endpoint is unavailable without either
+    pub llm_url: Option<String>,
+    
+    /// API the LLM server speaks
+    pub llm_provider: ProviderKind,
+    
+    /// Model name sent to the LLM server (the client's default when unset)
+    pub llm_model: Option<String>,
+    
+    /// Most tokens generated per `/ask` answer
+    pub max_tokens: u32,
+    
+    /// Sampling temperature for `/ask` answers
+    pub temperature: f32,
+    
     /// Maximum number of search results
     pub max_search_results: usize,
     
-    /// Enable CORS
-    pub enable_cors: bool,
+    /// Origins, methods and headers allowed for browser clients on other
+    /// origins; cross-origin requests are refused when unset
+    pub cors: Option<CorsConfig>,
     
     /// Request timeout in seconds
     pub timeout_secs: u64,
     
+    /// Per-client limit on the endpoints that embed or generate (`/query`,
+    /// `/ask`, `/add`, `/search`, `/ingest`, `/upload`); unlimited when unset
+    pub rate_limit: Option<RateLimitConfig>,
+    
+    /// Largest accepted `/upload` body, in bytes
+    pub max_upload_size: usize,
+    
     /// Log level
     pub log_level: String,
 }
@@ -37,9 +90,23 @@ impl Default for ServerConfig {
             port: 8080,
             storage_path: None,
             embedding_model: "minilm".to_string(),
+            embedding_url: None,
+            embedding_api_key: None,
+            embedding_device: "cpu".to_string(),
+            sparse_model: None,
+            reranker_model: None,
+            model_path: None,
+            context_size: 2048,
+            llm_url: None,
+            llm_provider: ProviderKind::default(),
+            llm_model: None,
+            max_tokens: 512,
+            temperature: 0.7,
             max_search_results: 10,
-            enable_cors: true,
+            cors: None,
             timeout_secs: 30,
+            rate_limit: None,
+            max_upload_size: 32 * 1024 * 1024,
             log_level: "info".to_string(),
         }
     }
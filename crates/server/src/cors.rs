@@ -0,0 +1,156 @@
+//! Cross-origin access for browser clients
+//!
+//! Browsers only let pages call the API from another origin when the
+//! server answers with CORS headers. [`CorsConfig`] lists who may do so;
+//! without one, the server sends no CORS headers and cross-origin calls
+//! are refused by the browser.
+
+use axum::http::header::RETRY_AFTER;
+use axum::http::{HeaderName, HeaderValue, Method};
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::error::{Result, ServerError};
+
+/// Which origins may call the API, and how
+///
+/// Each list may hold `*` to allow anything.
+#[derive(Debug, Clone)]
+pub struct CorsConfig {
+    /// Origins allowed to call the API, e.g. `https://app.example.com`
+    pub allowed_origins: Vec<String>,
+    /// HTTP methods allowed in cross-origin requests
+    pub allowed_methods: Vec<String>,
+    /// Request headers allowed in cross-origin requests
+    pub allowed_headers: Vec<String>,
+}
+
+impl Default for CorsConfig {
+    fn default() -> Self {
+        Self {
+            allowed_origins: vec!["*".to_string()],
+            allowed_methods: ["GET", "POST", "DELETE"].map(String::from).to_vec(),
+            allowed_headers: ["content-type", "authorization", "x-api-key"]
+                .map(String::from)
+                .to_vec(),
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Allow `origins` with the default methods and headers
+    pub fn new<I, S>(origins: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        Self {
+            allowed_origins: origins.into_iter().map(Into::into).collect(),
+            ..Self::default()
+        }
+    }
+
+    /// Set the allowed methods
+    pub fn with_methods<I, S>(mut self, methods: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_methods = methods.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Set the allowed request headers
+    pub fn with_headers<I, S>(mut self, headers: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.allowed_headers = headers.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Build the middleware enforcing this policy
+    ///
+    /// `Retry-After` is exposed so pages can honour rate limits.
+    pub fn layer(&self) -> Result<CorsLayer> {
+        let origins = if is_wildcard(&self.allowed_origins) {
+            AllowOrigin::any()
+        } else {
+            AllowOrigin::list(parse_all(&self.allowed_origins, "origin", |o| {
+                HeaderValue::from_str(o.trim_end_matches('/')).ok()
+            })?)
+        };
+
+        let methods = if is_wildcard(&self.allowed_methods) {
+            AllowMethods::any()
+        } else {
+            AllowMethods::list(parse_all(&self.allowed_methods, "method", |m| {
+                Method::from_bytes(m.to_ascii_uppercase().as_bytes()).ok()
+            })?)
+        };
+
+        let headers = if is_wildcard(&self.allowed_headers) {
+            AllowHeaders::any()
+        } else {
+            AllowHeaders::list(parse_all(&self.allowed_headers, "header", |h| {
+                HeaderName::from_bytes(h.as_bytes()).ok()
+            })?)
+        };
+
+        Ok(CorsLayer::new()
+            .allow_origin(origins)
+            .allow_methods(methods)
+            .allow_headers(headers)
+            .expose_headers([RETRY_AFTER]))
+    }
+}
+
+fn is_wildcard(values: &[String]) -> bool {
+    values.iter().any(|v| v.trim() == "*")
+}
+
+fn parse_all<T>(
+    values: &[String],
+    what: &str,
+    parse: impl Fn(&str) -> Option<T>,
+) -> Result<Vec<T>> {
+    values
+        .iter()
+        .map(|value| {
+            parse(value.trim())
+                .ok_or_else(|| ServerError::Internal(format!("Invalid CORS {}: {:?}", what, value)))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_allows_any_origin() {
+        let config = CorsConfig::default();
+        assert!(is_wildcard(&config.allowed_origins));
+        assert!(config.layer().is_ok());
+    }
+
+    #[test]
+    fn test_origin_list() {
+        let config = CorsConfig::new(["https://app.example.com/", "http://localhost:3000"])
+            .with_methods(["get", "POST"])
+            .with_headers(["Content-Type"]);
+        assert!(config.layer().is_ok());
+    }
+
+    #[test]
+    fn test_invalid_values_are_rejected() {
+        let config = CorsConfig::new(["https://bad\norigin"]);
+        assert!(config.layer().is_err());
+
+        let config = CorsConfig::default().with_methods(["GET POST"]);
+        assert!(config.layer().is_err());
+
+        let config = CorsConfig::default().with_headers(["bad header"]);
+        assert!(config.layer().is_err());
+    }
+}
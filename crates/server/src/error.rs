@@ -1,5 +1,6 @@
 //! Server error types
 
+use axum::http::header::RETRY_AFTER;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use axum::Json;
@@ -13,6 +14,10 @@ pub enum ServerError {
     #[error("Bad request: {0}")]
     BadRequest(String),
 
+    /// Client over its rate limit; may retry after this many seconds
+    #[error("Too many requests, retry in {0} s")]
+    RateLimited(u64),
+
     /// Not found
     #[error("Not found: {0}")]
     NotFound(String),
@@ -33,6 +38,14 @@ pub enum ServerError {
     #[error("Search error: {0}")]
     Search(#[from] neuro_search::SearchError),
 
+    /// LLM error
+    #[error("LLM error: {0}")]
+    Llm(#[from] neuro_llm::LlmError),
+
+    /// Local inference error
+    #[error("Inference error: {0}")]
+    Inference(#[from] neuro_inference::InferenceError),
+
     /// Core error
     #[error("{0}")]
     Core(#[from] neuro_core::Error),
@@ -45,11 +58,14 @@ impl IntoResponse for ServerError {
     fn into_response(self) -> Response {
         let (status, message) = match &self {
             ServerError::BadRequest(msg) => (StatusCode::BAD_REQUEST, msg.clone()),
+            ServerError::RateLimited(_) => (StatusCode::TOO_MANY_REQUESTS, self.to_string()),
             ServerError::NotFound(msg) => (StatusCode::NOT_FOUND, msg.clone()),
             ServerError::Internal(msg) => (StatusCode::INTERNAL_SERVER_ERROR, msg.clone()),
             ServerError::Storage(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             ServerError::Embedding(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             ServerError::Search(e) => (StatusCode::BAD_GATEWAY, e.to_string()),
+            ServerError::Llm(e) => (StatusCode::BAD_GATEWAY, e.to_string()),
+            ServerError::Inference(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
             ServerError::Core(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()),
         };
 
@@ -58,6 +74,11 @@ impl IntoResponse for ServerError {
             "status": status.as_u16()
         }));
 
-        (status, body).into_response()
+        match self {
+            ServerError::RateLimited(secs) => {
+                (status, [(RETRY_AFTER, secs.to_string())], body).into_response()
+            }
+            _ => (status, body).into_response(),
+        }
     }
 }
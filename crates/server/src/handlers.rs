@@ -1,18 +1,29 @@
 //! HTTP request handlers
 
-use axum::extract::{Json, State};
+use axum::extract::{Json, Multipart, Query, State};
 use axum::http::StatusCode;
+use axum::response::{IntoResponse, Response};
 use serde::{Deserialize, Serialize};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
 use tracing::{debug, info};
 
-use neuro_core::{Document, DocumentSource, QueryResult};
-use neuro_search::WebSearcher;
-use neuro_storage::Storage;
-
+use neuro_core::{
+    Document, DocumentSource, QueryCategory, QueryClassifier, QueryResult, SearchResult,
+    SparseEmbedding,
+};
+use neuro_embeddings::Reranker;
+use neuro_indexer::{CodeChunk, CodeIndexer, Language};
+use neuro_llm::UsageReport;
+use neuro_search::{rank_by_similarity, WebSearchResult, WebSearcher, DEFAULT_MIN_SIMILARITY};
+use neuro_storage::{DocumentFilter, Storage, StorageStats, DEFAULT_DENSE_WEIGHT};
+
+use crate::answer::{self, collect_sources, mark_cited, numbered_context, AskTimings, Source};
 use crate::error::{Result, ServerError};
+use crate::ingest::IngestReport;
 use crate::state::AppState;
+use crate::stream::{stream_events, EventSink};
 
 // ============================================================================
 // Request/Response types
@@ -25,12 +36,48 @@ pub struct QueryRequest {
     pub user_id: Option<String>,
     #[serde(default = "default_top_k")]
     pub top_k: usize,
+    /// Only search documents from this source
+    #[serde(default)]
+    pub source: Option<DocumentSource>,
+    /// Re-score the top results with the server's cross-encoder
+    #[serde(default)]
+    pub rerank: bool,
+    /// Language of the web search (ISO 639-1 code); detected from the query
+    /// when unset
+    #[serde(default)]
+    pub web_language: Option<String>,
+    /// Send progress as server-sent events instead of one JSON body
+    #[serde(default)]
+    pub stream: bool,
+}
+
+impl QueryRequest {
+    /// Build the storage filter for this request
+    fn filter(&self) -> DocumentFilter {
+        DocumentFilter {
+            user_id: self.user_id.clone(),
+            source: self.source.clone(),
+            ..DocumentFilter::default()
+        }
+    }
 }
 
 fn default_top_k() -> usize {
     5
 }
 
+/// How many candidates per requested result are fetched for reranking
+const RERANK_CANDIDATE_FACTOR: usize = 4;
+
+/// Web results added to a query's context
+const WEB_RESULTS: usize = 3;
+
+/// Web results fetched to pick the most relevant `WEB_RESULTS` from
+const WEB_CANDIDATES: usize = 6;
+
+/// Entities whose facts are added to a factual query's context
+const FACT_RESULTS: usize = 1;
+
 #[derive(Debug, Deserialize)]
 pub struct AddDocumentRequest {
     pub content: String,
@@ -40,6 +87,8 @@ pub struct AddDocumentRequest {
     pub source: Option<String>,
     #[serde(default)]
     pub metadata: Option<serde_json::Value>,
+    #[serde(default)]
+    pub tags: Vec<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -49,6 +98,72 @@ pub struct SearchRequest {
     pub user_id: Option<String>,
     #[serde(default = "default_top_k")]
     pub top_k: usize,
+    /// Only return documents carrying all of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only return documents from this source
+    #[serde(default)]
+    pub source: Option<DocumentSource>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IngestRequest {
+    /// Pages to ingest
+    #[serde(default)]
+    pub urls: Vec<String>,
+    /// Search the web and ingest the top results instead
+    #[serde(default)]
+    pub query: Option<String>,
+    /// Results ingested for `query`
+    #[serde(default = "default_ingest_results")]
+    pub max_results: usize,
+    #[serde(default)]
+    pub user_id: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn default_ingest_results() -> usize {
+    3
+}
+
+impl SearchRequest {
+    /// Build the storage filter for this request
+    fn filter(&self) -> DocumentFilter {
+        DocumentFilter {
+            user_id: self.user_id.clone(),
+            source: self.source.clone(),
+            tags: self.tags.clone(),
+            ..DocumentFilter::default()
+        }
+    }
+}
+
+/// Query parameters for listing documents
+#[derive(Debug, Default, Deserialize)]
+pub struct ListDocumentsParams {
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// Comma-separated list of required tags
+    #[serde(default)]
+    pub tags: Option<String>,
+}
+
+impl ListDocumentsParams {
+    fn filter(&self) -> DocumentFilter {
+        let mut filter = DocumentFilter::new();
+        if let Some(ref user_id) = self.user_id {
+            filter = filter.with_user_id(user_id);
+        }
+        if let Some(ref tags) = self.tags {
+            filter = filter.with_tags(
+                tags.split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty()),
+            );
+        }
+        filter
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -61,8 +176,11 @@ pub struct HealthResponse {
 pub struct StatsResponse {
     pub uptime_secs: u64,
     pub request_count: u64,
-    pub document_count: usize,
-    pub embedding_dimension: Option<usize>,
+    #[serde(flatten)]
+    pub storage: StorageStats,
+    /// Requests and tokens of the LLM client, if the server has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub llm_usage: Option<UsageReport>,
 }
 
 #[derive(Debug, Serialize)]
@@ -71,6 +189,36 @@ pub struct AddDocumentResponse {
     pub message: String,
 }
 
+#[derive(Debug, Serialize)]
+pub struct DeleteDocumentsResponse {
+    pub deleted: usize,
+}
+
+#[derive(Debug, Serialize)]
+pub struct AskResponse {
+    pub query: String,
+    /// The generated answer, or the safe reply for sensitive queries
+    pub answer: String,
+    pub classification: neuro_core::ClassificationResult,
+    /// Passages given to the model, numbered as the answer cites them
+    pub sources: Vec<Source>,
+    pub used_web_search: bool,
+    /// Model that wrote the answer; `None` for safe replies
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub timings: AskTimings,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct UploadResponse {
+    /// Files stored
+    pub files: usize,
+    /// Chunks stored
+    pub chunks: usize,
+    /// Chunks of earlier uploads of the same files that were replaced
+    pub replaced: usize,
+}
+
 // ============================================================================
 // Handlers
 // ============================================================================
@@ -91,8 +239,8 @@ pub async fn stats(State(state): State<Arc<AppState>>) -> Result<Json<StatsRespo
     Ok(Json(StatsResponse {
         uptime_secs: state.uptime_secs(),
         request_count: state.get_request_count().await,
-        document_count: stats.document_count,
-        embedding_dimension: stats.embedding_dimension,
+        storage: stats,
+        llm_usage: state.llm.as_ref().map(|llm| llm.usage_report()),
     }))
 }
 
@@ -100,41 +248,211 @@ pub async fn stats(State(state): State<Arc<AppState>>) -> Result<Json<StatsRespo
 pub async fn query(
     State(state): State<Arc<AppState>>,
     Json(req): Json<QueryRequest>,
-) -> Result<Json<QueryResult>> {
+) -> Result<Response> {
+    state.increment_requests().await;
+
+    if req.query.trim().is_empty() {
+        return Err(ServerError::BadRequest("Empty query".to_string()));
+    }
+
+    if req.stream {
+        let sse = stream_events(move |events| async move {
+            let retrieval = execute_query(&state, &req, &events).await?;
+            Ok(retrieval.result)
+        });
+        return Ok(sse.into_response());
+    }
+
+    let retrieval = execute_query(&state, &req, &EventSink::none()).await?;
+    Ok(Json(retrieval.result).into_response())
+}
+
+/// Answer a query with the server's model, citing the retrieved sources
+pub async fn ask(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<QueryRequest>,
+) -> Result<Response> {
     state.increment_requests().await;
-    let start = Instant::now();
 
     if req.query.trim().is_empty() {
         return Err(ServerError::BadRequest("Empty query".to_string()));
     }
+    if state.local_model.is_none() && state.llm.is_none() {
+        return Err(ServerError::BadRequest(
+            "No model is configured on this server".to_string(),
+        ));
+    }
+
+    if req.stream {
+        let sse =
+            stream_events(move |events| async move { execute_ask(&state, &req, &events).await });
+        return Ok(sse.into_response());
+    }
+
+    let response = execute_ask(&state, &req, &EventSink::none()).await?;
+    Ok(Json(response).into_response())
+}
+
+/// Retrieve context for a query, then generate an answer from it
+async fn execute_ask(
+    state: &AppState,
+    req: &QueryRequest,
+    events: &EventSink,
+) -> Result<AskResponse> {
+    let start = Instant::now();
+    let Retrieval {
+        result,
+        web_results,
+        classification_ms,
+    } = execute_query(state, req, events).await?;
+    let mut timings = AskTimings {
+        classification_ms,
+        retrieval_ms: result.processing_time_ms.saturating_sub(classification_ms),
+        ..AskTimings::default()
+    };
+
+    // Sensitive queries keep their safe reply; no model is called
+    if let Some(answer) = result.safe_reply {
+        timings.total_ms = start.elapsed().as_millis() as u64;
+        return Ok(AskResponse {
+            query: result.query,
+            answer,
+            classification: result.classification,
+            sources: Vec::new(),
+            used_web_search: false,
+            model: None,
+            timings,
+        });
+    }
+
+    let mut sources = collect_sources(
+        &result.search_results,
+        &web_results,
+        state.config.max_search_results * 1000,
+    );
+    let context = numbered_context(&sources);
+
+    let generation_start = Instant::now();
+    let answer = answer::generate(state, &req.query, &context, events).await?;
+    mark_cited(&mut sources, &answer);
+    timings.generation_ms = generation_start.elapsed().as_millis() as u64;
+    timings.total_ms = start.elapsed().as_millis() as u64;
+
+    Ok(AskResponse {
+        query: result.query,
+        answer,
+        classification: result.classification,
+        sources,
+        used_web_search: result.used_web_search,
+        model: answer::model_name(state),
+        timings,
+    })
+}
+
+/// What a query found
+struct Retrieval {
+    result: QueryResult,
+    /// Web results whose text was added to `result.context`
+    web_results: Vec<WebSearchResult>,
+    /// Time spent classifying the query
+    classification_ms: u64,
+}
+
+/// Classify a query, then search storage and the web for its context
+///
+/// Progress is reported to `events` as it happens.
+async fn execute_query(
+    state: &AppState,
+    req: &QueryRequest,
+    events: &EventSink,
+) -> Result<Retrieval> {
+    let start = Instant::now();
 
     info!("Processing query: {}", req.query);
 
     // Classify the query
     let classification = state.classifier.classify(&req.query);
+    let classification_ms = start.elapsed().as_millis() as u64;
     debug!("Classification: {:?}", classification);
+    events.send("classification", &classification).await;
+
+    // Sensitive queries are answered without searching or calling a model
+    if let Some(reply) = classification.safe_reply() {
+        info!("Short-circuiting sensitive query with a safe reply");
+        let result = QueryResult::new(&req.query, classification)
+            .with_safe_reply(reply)
+            .with_processing_time(start.elapsed().as_millis() as u64);
+        return Ok(Retrieval {
+            result,
+            web_results: Vec::new(),
+            classification_ms,
+        });
+    }
+
+    let reranker = if req.rerank {
+        Some(state.reranker.clone().ok_or_else(|| {
+            ServerError::BadRequest("Reranking is not enabled on this server".to_string())
+        })?)
+    } else {
+        None
+    };
 
-    // Generate embedding for search
+    // Fetch extra candidates when they will be reranked
+    let limit = if reranker.is_some() {
+        req.top_k * RERANK_CANDIDATE_FACTOR
+    } else {
+        req.top_k
+    };
+
+    // Generate embeddings for search
     let embedding = state
-        .embedder
-        .embed_single(&req.query)
+        .async_embedder
+        .embed_query(&req.query)
+        .await
         .map_err(ServerError::Embedding)?;
+    let sparse = embed_sparse(state, &req.query).await?;
 
     // Search storage
     let storage = state.storage.read().await;
-    let search_results = if let Some(ref user_id) = req.user_id {
+    let filter = req.filter();
+
+    // Prefer code indexed in the detected language, if there is any
+    let language_results = match classification.programming_language() {
+        Some(language) => {
+            let language_filter = filter
+                .clone()
+                .with_metadata("language", serde_json::Value::String(language.to_string()));
+            storage
+                .search_filtered(&embedding, &language_filter, limit)
+                .await
+                .map_err(ServerError::Storage)?
+        }
+        None => Vec::new(),
+    };
+
+    let search_results = if !language_results.is_empty() {
+        language_results
+    } else if let Some(ref sparse) = sparse {
         storage
-            .search_by_user(&embedding, user_id, req.top_k)
+            .search_hybrid(&embedding, sparse, &filter, limit, DEFAULT_DENSE_WEIGHT)
             .await
             .map_err(ServerError::Storage)?
+    } else if filter.is_empty() {
+        storage.search(&embedding, limit).await.map_err(ServerError::Storage)?
     } else {
         storage
-            .search(&embedding, req.top_k)
+            .search_filtered(&embedding, &filter, limit)
             .await
             .map_err(ServerError::Storage)?
     };
     drop(storage);
 
+    let search_results = match reranker {
+        Some(reranker) => rerank(reranker, &req.query, search_results, req.top_k).await?,
+        None => search_results,
+    };
+    events.send("sources", &search_results).await;
+
     // Build result
     let mut result = QueryResult::new(&req.query, classification);
     result = result.with_search_results(search_results);
@@ -146,28 +464,56 @@ pub async fn query(
         neuro_core::QueryStrategy::RagThenWeb | neuro_core::QueryStrategy::WebSearch
     ) && !result.has_relevant_results();
 
+    let mut web_results = Vec::new();
     if needs_web {
         debug!("Attempting web search for: {}", req.query);
-        match state.web_searcher.search(&req.query, 3).await {
-            Ok(web_results) => {
-                let mut context = result.context.clone();
-                for web_result in web_results {
-                    if !context.is_empty() {
-                        context.push_str("\n\n---\n\n");
-                    }
-                    context.push_str(&web_result.to_rag_context());
-                }
-                result = result.with_context(context).with_web_search();
+        let language = req.web_language.as_deref().unwrap_or("auto");
+
+        // Exact facts first; prose may not state them
+        if result.classification.category == QueryCategory::Factual {
+            match state
+                .fact_searcher
+                .search_in_language(&req.query, FACT_RESULTS, language)
+                .await
+            {
+                Ok(facts) => web_results.extend(facts),
+                Err(e) => debug!("Fact lookup failed: {}", e),
+            }
+        }
+
+        match state
+            .web_searcher
+            .search_in_language(&req.query, WEB_CANDIDATES, language)
+            .await
+        {
+            Ok(found) => {
+                let found = rank_web_results(state, &embedding, found).await;
+                web_results.extend(found.into_iter().take(WEB_RESULTS));
             }
             Err(e) => {
                 debug!("Web search failed: {}", e);
             }
         }
+
+        if !web_results.is_empty() {
+            let mut context = result.context.clone();
+            for web_result in &web_results {
+                if !context.is_empty() {
+                    context.push_str("\n\n---\n\n");
+                }
+                context.push_str(&web_result.to_rag_context());
+            }
+            result = result.with_context(context).with_web_search();
+        }
     }
 
     result = result.with_processing_time(start.elapsed().as_millis() as u64);
 
-    Ok(Json(result))
+    Ok(Retrieval {
+        result,
+        web_results,
+        classification_ms,
+    })
 }
 
 /// Classify query without execution
@@ -200,27 +546,26 @@ pub async fn add_document(
 
     // Generate embedding
     let embedding = state
-        .embedder
-        .embed_single(&req.content)
+        .async_embedder
+        .embed_passage(&req.content)
+        .await
         .map_err(ServerError::Embedding)?;
 
     // Build document
-    let mut doc = Document::new(&req.content).with_embedding(embedding);
+    let mut doc = Document::new(&req.content)
+        .with_embedding(embedding)
+        .with_embedding_model(state.async_embedder.model_name());
+
+    if let Some(sparse) = embed_sparse(&state, &req.content).await? {
+        doc = doc.with_sparse_embedding(sparse);
+    }
 
     if let Some(user_id) = req.user_id {
         doc = doc.with_user_id(user_id);
     }
 
     if let Some(source) = req.source {
-        let source = match source.as_str() {
-            "manual" => DocumentSource::Manual,
-            "file" => DocumentSource::File,
-            "web" => DocumentSource::Web,
-            "conversation" => DocumentSource::Conversation,
-            "code" => DocumentSource::Code,
-            _ => DocumentSource::Manual,
-        };
-        doc = doc.with_source(source);
+        doc = doc.with_source(source.parse().unwrap_or_default());
     }
 
     if let Some(metadata) = req.metadata {
@@ -231,6 +576,10 @@ pub async fn add_document(
         }
     }
 
+    if !req.tags.is_empty() {
+        doc = doc.with_tags(req.tags);
+    }
+
     let id = doc.id.clone();
 
     // Add to storage
@@ -246,6 +595,72 @@ pub async fn add_document(
     ))
 }
 
+/// Compute the sparse embedding of `text`, if a sparse embedder is configured
+async fn embed_sparse(state: &AppState, text: &str) -> Result<Option<SparseEmbedding>> {
+    let Some(embedder) = state.sparse_embedder.clone() else {
+        return Ok(None);
+    };
+
+    let text = text.to_string();
+    let sparse = tokio::task::spawn_blocking(move || embedder.embed_sparse(&text))
+        .await
+        .map_err(|e| ServerError::Internal(format!("Sparse embedding task failed: {}", e)))?
+        .map_err(ServerError::Embedding)?;
+    Ok(Some(sparse))
+}
+
+/// Order web results by similarity to the query, dropping off-topic ones
+///
+/// If the results cannot be embedded, they are kept in provider order.
+async fn rank_web_results(
+    state: &AppState,
+    query_embedding: &[f32],
+    results: Vec<WebSearchResult>,
+) -> Vec<WebSearchResult> {
+    let summaries: Vec<String> = results.iter().map(WebSearchResult::summary).collect();
+    let summaries: Vec<&str> = summaries.iter().map(String::as_str).collect();
+    match state.async_embedder.embed_passages(&summaries).await {
+        Ok(embeddings) => {
+            rank_by_similarity(query_embedding, results, &embeddings, DEFAULT_MIN_SIMILARITY)
+        }
+        Err(e) => {
+            debug!("Could not embed web results, keeping provider order: {}", e);
+            results
+        }
+    }
+}
+
+/// Re-score search results with a cross-encoder, keeping the best `top_k`
+async fn rerank(
+    reranker: Arc<Reranker>,
+    query: &str,
+    results: Vec<SearchResult>,
+    top_k: usize,
+) -> Result<Vec<SearchResult>> {
+    if results.is_empty() {
+        return Ok(results);
+    }
+
+    let query = query.to_string();
+    let documents: Vec<String> = results.iter().map(|r| r.document.content.clone()).collect();
+    let scores = tokio::task::spawn_blocking(move || {
+        let documents: Vec<&str> = documents.iter().map(String::as_str).collect();
+        reranker.rerank(&query, &documents)
+    })
+    .await
+    .map_err(|e| ServerError::Internal(format!("Reranking task failed: {}", e)))?
+    .map_err(ServerError::Embedding)?;
+
+    let mut results: Vec<Option<SearchResult>> = results.into_iter().map(Some).collect();
+    Ok(scores
+        .into_iter()
+        .filter_map(|s| results.get_mut(s.index)?.take().map(|r| r.with_rerank_score(s.score)))
+        .take(top_k)
+        .enumerate()
+        .map(|(rank, r)| r.with_rank(rank))
+        .collect())
+}
+
 /// Search endpoint
 pub async fn search(
     State(state): State<Arc<AppState>>,
@@ -259,37 +674,264 @@ pub async fn search(
 
     debug!("Searching for: {}", req.query);
 
-    // Generate embedding
+    // Generate embeddings
     let embedding = state
-        .embedder
-        .embed_single(&req.query)
+        .async_embedder
+        .embed_query(&req.query)
+        .await
         .map_err(ServerError::Embedding)?;
+    let sparse = embed_sparse(&state, &req.query).await?;
 
     // Search
     let storage = state.storage.read().await;
-    let results = if let Some(ref user_id) = req.user_id {
+    let filter = req.filter();
+    let results = if let Some(ref sparse) = sparse {
         storage
-            .search_by_user(&embedding, user_id, req.top_k)
+            .search_hybrid(&embedding, sparse, &filter, req.top_k, DEFAULT_DENSE_WEIGHT)
             .await
-            .map_err(ServerError::Storage)?
+    } else if filter.is_empty() {
+        storage.search(&embedding, req.top_k).await
     } else {
+        storage.search_filtered(&embedding, &filter, req.top_k).await
+    }
+    .map_err(ServerError::Storage)?;
+
+    Ok(Json(results))
+}
+
+/// Ingest web pages endpoint
+///
+/// Fetches the given URLs, or the top results of a web search, and stores
+/// their chunks as web documents.
+pub async fn ingest(
+    State(state): State<Arc<AppState>>,
+    Json(req): Json<IngestRequest>,
+) -> Result<(StatusCode, Json<IngestReport>)> {
+    state.increment_requests().await;
+
+    let query = req.query.as_deref().map(str::trim).filter(|q| !q.is_empty());
+    let mut ingestion = match (query, req.urls.is_empty()) {
+        (Some(query), true) => {
+            info!("Ingesting web results for: {}", query);
+            state.ingestor.ingest_query(query, req.max_results).await?
+        }
+        (None, false) => {
+            info!("Ingesting {} URLs", req.urls.len());
+            state.ingestor.ingest_urls(&req.urls).await?
+        }
+        _ => {
+            return Err(ServerError::BadRequest(
+                "Give either urls or a query".to_string(),
+            ))
+        }
+    };
+
+    if let Some(user_id) = req.user_id {
+        ingestion = ingestion.with_user_id(user_id);
+    }
+    if !req.tags.is_empty() {
+        ingestion = ingestion.with_tags(&req.tags);
+    }
+
+    let mut storage = state.storage.write().await;
+    let report = ingestion.store(&mut **storage).await?;
+
+    Ok((StatusCode::CREATED, Json(report)))
+}
+
+/// Upload files endpoint
+///
+/// Takes a multipart form with one or more file parts, plus optional
+/// `user_id` and comma-separated `tags` fields. Files are chunked by
+/// extension the way `neuro index` does it: plain text, PDF and Word
+/// documents by paragraph (PDFs page by page), Markdown by section, code
+/// by symbol. Uploading a file with the same name again replaces its
+/// chunks.
+pub async fn upload(
+    State(state): State<Arc<AppState>>,
+    mut multipart: Multipart,
+) -> Result<(StatusCode, Json<UploadResponse>)> {
+    state.increment_requests().await;
+
+    let bad_request = |e: axum::extract::multipart::MultipartError| {
+        ServerError::BadRequest(format!("Invalid upload: {}", e))
+    };
+    let mut files = Vec::new();
+    let mut user_id = None;
+    let mut tags: Vec<String> = Vec::new();
+    while let Some(field) = multipart.next_field().await.map_err(bad_request)? {
+        match field.name() {
+            Some("user_id") => user_id = Some(field.text().await.map_err(bad_request)?),
+            Some("tags") => {
+                tags = field
+                    .text()
+                    .await
+                    .map_err(bad_request)?
+                    .split(',')
+                    .map(str::trim)
+                    .filter(|t| !t.is_empty())
+                    .map(String::from)
+                    .collect();
+            }
+            _ => {
+                let Some(file_name) = field.file_name().map(str::to_string) else {
+                    continue;
+                };
+                let bytes = field.bytes().await.map_err(bad_request)?;
+                files.push((file_name, bytes));
+            }
+        }
+    }
+    if files.is_empty() {
+        return Err(ServerError::BadRequest("No files uploaded".to_string()));
+    }
+
+    // Chunk every file before storing any, so one bad file rejects the
+    // whole upload
+    let mut uploads = Vec::with_capacity(files.len());
+    for (file_name, bytes) in files {
+        let language = Language::from_path(Path::new(&file_name)).ok_or_else(|| {
+            ServerError::BadRequest(format!("Unsupported file type: {}", file_name))
+        })?;
+        let path = file_name.clone();
+        // PDF extraction and parsing are CPU-bound
+        let chunks = tokio::task::spawn_blocking(move || {
+            CodeIndexer::new().index_bytes(&bytes, &path, language)
+        })
+        .await
+        .map_err(|e| ServerError::Internal(format!("Indexing task failed: {}", e)))?
+        .map_err(|e| ServerError::BadRequest(format!("Could not read {}: {}", file_name, e)))?;
+        uploads.push((file_name, language, chunks));
+    }
+
+    let mut response = UploadResponse::default();
+    for (file_name, language, chunks) in uploads {
+        if chunks.is_empty() {
+            continue;
+        }
+        info!("Uploading {} ({} chunks)", file_name, chunks.len());
+
+        let texts: Vec<String> = chunks.iter().map(CodeChunk::to_document_content).collect();
+        let passages: Vec<&str> = texts.iter().map(String::as_str).collect();
+        let embeddings = state
+            .async_embedder
+            .embed_passages(&passages)
+            .await
+            .map_err(ServerError::Embedding)?;
+
+        let mut documents = Vec::with_capacity(chunks.len());
+        for ((chunk, text), embedding) in chunks.into_iter().zip(texts).zip(embeddings) {
+            let mut doc = upload_document(&file_name, language, chunk, text)
+                .with_embedding(embedding)
+                .with_embedding_model(state.async_embedder.model_name());
+            if let Some(sparse) = embed_sparse(&state, &doc.content).await? {
+                doc = doc.with_sparse_embedding(sparse);
+            }
+            if let Some(ref user_id) = user_id {
+                doc = doc.with_user_id(user_id.clone());
+            }
+            if !tags.is_empty() {
+                doc = doc.with_tags(tags.iter().cloned());
+            }
+            documents.push(doc);
+        }
+
+        let filter = DocumentFilter {
+            user_id: user_id.clone(),
+            source: Some(DocumentSource::File),
+            ..DocumentFilter::default()
+        }
+        .with_metadata("file_name", file_name.clone().into());
+        let mut storage = state.storage.write().await;
+        response.replaced += storage
+            .delete_where(&filter)
+            .await
+            .map_err(ServerError::Storage)?;
+        response.chunks += documents.len();
         storage
-            .search(&embedding, req.top_k)
+            .add_batch(documents)
             .await
-            .map_err(ServerError::Storage)?
-    };
+            .map_err(ServerError::Storage)?;
+        response.files += 1;
+    }
 
-    Ok(Json(results))
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// File document for one chunk of an upload, with the chunk's location
+fn upload_document(
+    file_name: &str,
+    language: Language,
+    chunk: CodeChunk,
+    text: String,
+) -> Document {
+    let mut doc = Document::new(text)
+        .with_source(DocumentSource::File)
+        .with_metadata("file_name", file_name.into())
+        .with_metadata("language", language.name().to_lowercase().into())
+        .with_metadata("section", chunk.display_name().into())
+        .with_metadata("start_line", chunk.start_line.into())
+        .with_metadata("end_line", chunk.end_line.into());
+    if let Some(page) = chunk.page {
+        doc = doc.with_metadata("page", page.into());
+    }
+    if let Some(cell) = chunk.cell {
+        doc = doc.with_metadata("cell", cell.into());
+    }
+    if let Some((part, parts)) = chunk.part {
+        doc = doc
+            .with_metadata("part", part.into())
+            .with_metadata("parts", parts.into());
+    }
+    doc
 }
 
 /// List documents endpoint
+///
+/// Supports optional `user_id` and comma-separated `tags` query parameters.
 pub async fn list_documents(
     State(state): State<Arc<AppState>>,
+    Query(params): Query<ListDocumentsParams>,
 ) -> Result<Json<Vec<Document>>> {
     state.increment_requests().await;
 
+    let filter = params.filter();
     let storage = state.storage.read().await;
-    let documents = storage.list().await.map_err(ServerError::Storage)?;
+    let documents = if filter.is_empty() {
+        storage.list().await.map_err(ServerError::Storage)?
+    } else {
+        storage
+            .list_filtered(&filter)
+            .await
+            .map_err(ServerError::Storage)?
+    };
 
     Ok(Json(documents))
 }
+
+/// Bulk delete documents endpoint
+///
+/// The request body is a [`DocumentFilter`]. An empty filter is rejected
+/// to avoid wiping the whole storage by accident.
+pub async fn delete_documents(
+    State(state): State<Arc<AppState>>,
+    Json(filter): Json<DocumentFilter>,
+) -> Result<Json<DeleteDocumentsResponse>> {
+    state.increment_requests().await;
+
+    if filter.is_empty() {
+        return Err(ServerError::BadRequest(
+            "Filter must contain at least one condition".to_string(),
+        ));
+    }
+
+    let mut storage = state.storage.write().await;
+    let deleted = storage
+        .delete_where(&filter)
+        .await
+        .map_err(ServerError::Storage)?;
+
+    info!("Deleted {} documents", deleted);
+
+    Ok(Json(DeleteDocumentsResponse { deleted }))
+}
@@ -0,0 +1,298 @@
+//! Turning web pages into stored documents
+//!
+//! [`WebIngestor`] fetches pages (given directly, or found with a web
+//! search), splits their main text into chunks, embeds the chunks and
+//! returns them as [`DocumentSource::Web`] documents carrying the page URL
+//! and title. Storing is a separate step, so fetching and embedding don't
+//! hold the storage lock.
+
+use serde::Serialize;
+use std::sync::Arc;
+use tracing::{debug, warn};
+
+use neuro_core::{Document, DocumentSource};
+use neuro_embeddings::{AsyncEmbedder, SparseEmbedder};
+use neuro_search::{PageFetcher, WebSearchResult, WebSearcher};
+use neuro_storage::{DocumentFilter, Storage};
+
+use crate::error::{Result, ServerError};
+
+/// Default chunk length, in bytes
+pub const DEFAULT_CHUNK_SIZE: usize = 1000;
+
+/// Fetches, chunks and embeds web pages
+pub struct WebIngestor {
+    embedder: Arc<dyn AsyncEmbedder>,
+    sparse_embedder: Option<Arc<dyn SparseEmbedder>>,
+    searcher: Option<Arc<dyn WebSearcher>>,
+    fetcher: PageFetcher,
+    chunk_size: usize,
+}
+
+/// Documents built from web pages, ready to store
+#[derive(Debug, Default)]
+pub struct Ingestion {
+    /// One document per chunk
+    pub documents: Vec<Document>,
+    /// URLs of the pages the documents come from
+    pub urls: Vec<String>,
+    /// URLs that could not be fetched
+    pub failed: Vec<String>,
+    /// Owner of the documents
+    pub user_id: Option<String>,
+}
+
+/// Outcome of storing an [`Ingestion`]
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct IngestReport {
+    /// Pages stored
+    pub pages: usize,
+    /// Chunks stored
+    pub chunks: usize,
+    /// Chunks of earlier ingestions of the same pages that were replaced
+    pub replaced: usize,
+    /// URLs that could not be fetched
+    pub failed: Vec<String>,
+}
+
+impl WebIngestor {
+    /// Create an ingestor embedding chunks with `embedder`
+    pub fn new(embedder: Arc<dyn AsyncEmbedder>) -> Self {
+        Self {
+            embedder,
+            sparse_embedder: None,
+            searcher: None,
+            fetcher: PageFetcher::new(),
+            chunk_size: DEFAULT_CHUNK_SIZE,
+        }
+    }
+
+    /// Also compute sparse embeddings, for hybrid search
+    pub fn with_sparse_embedder(mut self, embedder: Arc<dyn SparseEmbedder>) -> Self {
+        self.sparse_embedder = Some(embedder);
+        self
+    }
+
+    /// Search with `searcher` in [`Self::ingest_query`]
+    pub fn with_searcher(mut self, searcher: Arc<dyn WebSearcher>) -> Self {
+        self.searcher = Some(searcher);
+        self
+    }
+
+    /// Download pages with `fetcher`
+    pub fn with_fetcher(mut self, fetcher: PageFetcher) -> Self {
+        self.fetcher = fetcher;
+        self
+    }
+
+    /// Set the chunk length, in bytes
+    pub fn with_chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Fetch and embed the pages at `urls`
+    pub async fn ingest_urls(&self, urls: &[String]) -> Result<Ingestion> {
+        let mut results = Vec::new();
+        let mut failed = Vec::new();
+        for url in urls {
+            match self.fetcher.fetch(url).await {
+                Ok(article) => {
+                    let title = article.title.unwrap_or_else(|| url.clone());
+                    results.push(
+                        WebSearchResult::new(title, url.as_str(), "", "Web")
+                            .with_content(article.text),
+                    );
+                }
+                Err(e) => {
+                    warn!("Could not fetch {}: {}", url, e);
+                    failed.push(url.clone());
+                }
+            }
+        }
+
+        let mut ingestion = self.ingest_results(results).await?;
+        ingestion.failed.extend(failed);
+        Ok(ingestion)
+    }
+
+    /// Search for `query` and embed the pages of the top results
+    pub async fn ingest_query(&self, query: &str, max_results: usize) -> Result<Ingestion> {
+        let searcher = self
+            .searcher
+            .as_ref()
+            .ok_or_else(|| ServerError::Internal("No web searcher configured".to_string()))?;
+        let results = searcher.search(query, max_results).await?;
+        self.ingest_results(results).await
+    }
+
+    /// Fetch the full content of `results` where missing, then embed it
+    ///
+    /// Results whose page cannot be fetched are reported as failed, unless
+    /// they already carry content.
+    pub async fn ingest_results(&self, results: Vec<WebSearchResult>) -> Result<Ingestion> {
+        let mut ingestion = Ingestion::default();
+
+        for result in self.fetcher.fetch_results(results).await {
+            let Some(content) = result.content.as_deref() else {
+                ingestion.failed.push(result.url);
+                continue;
+            };
+
+            let chunks = chunk_text(content, self.chunk_size);
+            if chunks.is_empty() {
+                ingestion.failed.push(result.url);
+                continue;
+            }
+            debug!("Embedding {} chunks of {}", chunks.len(), result.url);
+
+            let texts: Vec<&str> = chunks.iter().map(String::as_str).collect();
+            let embeddings = self
+                .embedder
+                .embed_passages(&texts)
+                .await
+                .map_err(ServerError::Embedding)?;
+
+            let total = chunks.len();
+            for (i, (chunk, embedding)) in chunks.into_iter().zip(embeddings).enumerate() {
+                let mut doc = Document::new(chunk)
+                    .with_source(DocumentSource::Web)
+                    .with_embedding(embedding)
+                    .with_embedding_model(self.embedder.model_name())
+                    .with_metadata("url", result.url.clone().into())
+                    .with_metadata("title", result.title.clone().into())
+                    .with_metadata("provider", result.source.clone().into())
+                    .with_metadata("chunk", i.into())
+                    .with_metadata("chunks", total.into());
+                if let Some(sparse) = &self.sparse_embedder {
+                    let sparse = sparse.clone();
+                    let text = doc.content.clone();
+                    let embedding = tokio::task::spawn_blocking(move || sparse.embed_sparse(&text))
+                        .await
+                        .map_err(|e| {
+                            ServerError::Internal(format!("Sparse embedding task failed: {}", e))
+                        })?
+                        .map_err(ServerError::Embedding)?;
+                    doc = doc.with_sparse_embedding(embedding);
+                }
+                ingestion.documents.push(doc);
+            }
+            ingestion.urls.push(result.url);
+        }
+
+        Ok(ingestion)
+    }
+}
+
+impl Ingestion {
+    /// Give every document to `user_id`
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        let user_id = user_id.into();
+        for doc in &mut self.documents {
+            doc.user_id = Some(user_id.clone());
+        }
+        self.user_id = Some(user_id);
+        self
+    }
+
+    /// Tag every document
+    pub fn with_tags(mut self, tags: &[String]) -> Self {
+        self.documents = self
+            .documents
+            .into_iter()
+            .map(|doc| doc.with_tags(tags.iter().cloned()))
+            .collect();
+        self
+    }
+
+    /// Store the documents, replacing those the same user stored earlier
+    /// from the same pages
+    pub async fn store(self, storage: &mut dyn Storage) -> Result<IngestReport> {
+        let mut replaced = 0;
+        for url in &self.urls {
+            let filter = DocumentFilter {
+                user_id: self.user_id.clone(),
+                source: Some(DocumentSource::Web),
+                ..DocumentFilter::default()
+            }
+            .with_metadata("url", url.clone().into());
+            replaced += storage.delete_where(&filter).await?;
+        }
+
+        let chunks = self.documents.len();
+        storage.add_batch(self.documents).await?;
+
+        Ok(IngestReport {
+            pages: self.urls.len(),
+            chunks,
+            replaced,
+            failed: self.failed,
+        })
+    }
+}
+
+/// Split `text` into chunks of about `size` bytes
+///
+/// Chunks end at paragraph breaks where possible, so code blocks and
+/// paragraphs stay whole; longer paragraphs are split between words.
+pub fn chunk_text(text: &str, size: usize) -> Vec<String> {
+    let mut chunks = Vec::new();
+    let mut current = String::new();
+
+    for paragraph in text.split("\n\n").map(str::trim).filter(|p| !p.is_empty()) {
+        if !current.is_empty() && current.len() + paragraph.len() + 2 > size {
+            chunks.push(std::mem::take(&mut current));
+        }
+        if paragraph.len() <= size {
+            if !current.is_empty() {
+                current.push_str("\n\n");
+            }
+            current.push_str(paragraph);
+            continue;
+        }
+
+        for word in paragraph.split_whitespace() {
+            if !current.is_empty() && current.len() + word.len() + 1 > size {
+                chunks.push(std::mem::take(&mut current));
+            }
+            if !current.is_empty() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+
+    if !current.is_empty() {
+        chunks.push(current);
+    }
+    chunks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_chunk_text_keeps_paragraphs() {
+        let text = "First paragraph.\n\nSecond paragraph.\n\n```\nfn main() {}\n```";
+        assert_eq!(chunk_text(text, 1000), vec![text.to_string()]);
+
+        let chunks = chunk_text(text, 40);
+        assert_eq!(
+            chunks,
+            vec![
+                "First paragraph.\n\nSecond paragraph.".to_string(),
+                "```\nfn main() {}\n```".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_chunk_text_splits_long_paragraphs() {
+        let text = "word ".repeat(50);
+        let chunks = chunk_text(&text, 24);
+        assert!(chunks.iter().all(|chunk| chunk.len() <= 24));
+        assert_eq!(chunks.join(" "), text.trim());
+        assert!(chunk_text("  \n\n ", 10).is_empty());
+    }
+}
@@ -3,20 +3,33 @@
 //! HTTP API server for the neuro-bitnet RAG system.
 //!
 //! This crate provides a REST API using Axum for:
-//! - Query classification and execution
+//! - Query classification and execution, optionally answered by an LLM
+//! - Streaming query progress as server-sent events
 //! - Document management (add, search, list)
+//! - Ingesting web pages and uploaded files as documents
 //! - Health checks and statistics
+//! - Per-client rate limits on the endpoints that embed or generate
+//! - Configurable CORS for browser clients (off by default)
 //!
 //! ## Endpoints
 //!
 //! - `GET /health` - Health check
 //! - `GET /stats` - Server statistics
 //! - `POST /query` - Intelligent query (classify + execute)
+//! - `POST /ask` - Query answered by the local model or LLM server, citing
+//!   its sources
 //! - `POST /classify` - Classify query without execution
 //! - `POST /add` - Add document
 //! - `POST /search` - Similarity search
+//! - `POST /ingest` - Fetch, chunk and store web pages
+//! - `POST /upload` - Chunk and store uploaded files (multipart; text, PDF,
+//!   Word, Markdown and code)
 //! - `GET /documents` - List documents
 //!
+//! `/query` and `/ask` stream server-sent events when the request sets
+//! `"stream": true`: `classification`, `sources`, `token` (`/ask` only),
+//! then `result` with the full response, or `error`.
+//!
 //! ## Example
 //!
 //! ```no_run
@@ -30,15 +43,23 @@
 //! }
 //! ```
 
+mod answer;
 mod config;
+mod cors;
 mod error;
 mod handlers;
+mod ingest;
+mod ratelimit;
 mod routes;
 mod state;
+mod stream;
 mod server;
 
 pub use config::ServerConfig;
+pub use cors::CorsConfig;
 pub use error::{ServerError, Result};
+pub use ingest::{chunk_text, IngestReport, Ingestion, WebIngestor, DEFAULT_CHUNK_SIZE};
+pub use ratelimit::{RateLimitConfig, RateLimiter};
 pub use server::Server;
 pub use state::AppState;
 
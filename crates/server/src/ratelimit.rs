@@ -0,0 +1,251 @@
+//! Per-client rate limiting for expensive endpoints
+//!
+//! Every client gets a token bucket keyed by its IP address, so one client
+//! can't starve the embedder or the model for everyone else. Requests that
+//! carry an API key (`X-API-Key` or `Authorization: Bearer`) must also fit
+//! the key's own bucket, which limits a key shared across addresses. Keys
+//! can't lift the IP limit, since the server doesn't check them.
+//!
+//! A request over the limit is answered with `429 Too Many Requests` and a
+//! `Retry-After` header.
+
+use std::collections::HashMap;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::extract::{ConnectInfo, Request, State};
+use axum::http::header::AUTHORIZATION;
+use axum::http::HeaderMap;
+use axum::middleware::Next;
+use axum::response::Response;
+
+use crate::error::{Result, ServerError};
+use crate::state::AppState;
+
+/// How often buckets that have refilled completely are dropped
+const PRUNE_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Rate limit applied to each client
+#[derive(Debug, Clone)]
+pub struct RateLimitConfig {
+    /// Sustained requests per second
+    pub requests_per_second: f64,
+    /// Requests allowed at once after a quiet period
+    pub burst: u32,
+}
+
+impl Default for RateLimitConfig {
+    fn default() -> Self {
+        Self {
+            requests_per_second: 2.0,
+            burst: 10,
+        }
+    }
+}
+
+/// Who a bucket belongs to
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum ClientKey {
+    Ip(IpAddr),
+    ApiKey(String),
+    /// Requests whose peer address is unknown share one bucket
+    Unknown,
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+}
+
+#[derive(Debug)]
+struct Buckets {
+    clients: HashMap<ClientKey, Bucket>,
+    pruned: Instant,
+}
+
+/// Token buckets for every client seen recently
+#[derive(Debug)]
+pub struct RateLimiter {
+    config: RateLimitConfig,
+    buckets: Mutex<Buckets>,
+}
+
+impl RateLimiter {
+    /// Create a limiter allowing each client `config`'s rate
+    pub fn new(config: RateLimitConfig) -> Self {
+        Self {
+            config,
+            buckets: Mutex::new(Buckets {
+                clients: HashMap::new(),
+                pruned: Instant::now(),
+            }),
+        }
+    }
+
+    /// Take a token from each of `keys`' buckets
+    ///
+    /// Nothing is taken unless every bucket has a token; otherwise returns
+    /// how long until they all do.
+    fn check(&self, keys: &[ClientKey], now: Instant) -> std::result::Result<(), Duration> {
+        let rate = self.config.requests_per_second.max(f64::MIN_POSITIVE);
+        let burst = self.config.burst.max(1) as f64;
+
+        let mut buckets = self.buckets.lock().unwrap_or_else(|e| e.into_inner());
+        if now.duration_since(buckets.pruned) >= PRUNE_INTERVAL {
+            buckets.clients.retain(|_, bucket| {
+                bucket.tokens + now.duration_since(bucket.refilled).as_secs_f64() * rate < burst
+            });
+            buckets.pruned = now;
+        }
+
+        let mut wait = Duration::ZERO;
+        for key in keys {
+            let bucket = buckets.clients.entry(key.clone()).or_insert(Bucket {
+                tokens: burst,
+                refilled: now,
+            });
+            let elapsed = now.duration_since(bucket.refilled).as_secs_f64();
+            bucket.tokens = (bucket.tokens + elapsed * rate).min(burst);
+            bucket.refilled = now;
+
+            if bucket.tokens < 1.0 {
+                wait = wait.max(Duration::from_secs_f64((1.0 - bucket.tokens) / rate));
+            }
+        }
+        if !wait.is_zero() {
+            return Err(wait);
+        }
+
+        for key in keys {
+            if let Some(bucket) = buckets.clients.get_mut(key) {
+                bucket.tokens -= 1.0;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Middleware rejecting requests from clients over the server's rate limit
+pub async fn limit(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Result<Response> {
+    if let Some(ref limiter) = state.rate_limiter {
+        let ip = request
+            .extensions()
+            .get::<ConnectInfo<SocketAddr>>()
+            .map(|ConnectInfo(addr)| addr.ip());
+        let keys = client_keys(ip, request.headers());
+
+        if let Err(wait) = limiter.check(&keys, Instant::now()) {
+            return Err(ServerError::RateLimited(wait.as_secs_f64().ceil() as u64));
+        }
+    }
+
+    Ok(next.run(request).await)
+}
+
+/// The buckets a request draws from
+fn client_keys(ip: Option<IpAddr>, headers: &HeaderMap) -> Vec<ClientKey> {
+    let mut keys = vec![ip.map_or(ClientKey::Unknown, ClientKey::Ip)];
+    if let Some(key) = api_key(headers) {
+        keys.push(ClientKey::ApiKey(key.to_string()));
+    }
+    keys
+}
+
+/// The API key a request carries, if any
+fn api_key(headers: &HeaderMap) -> Option<&str> {
+    if let Some(key) = headers.get("x-api-key") {
+        return key.to_str().ok().filter(|k| !k.is_empty());
+    }
+    headers
+        .get(AUTHORIZATION)?
+        .to_str()
+        .ok()?
+        .strip_prefix("Bearer ")
+        .map(str::trim)
+        .filter(|k| !k.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn limiter(requests_per_second: f64, burst: u32) -> RateLimiter {
+        RateLimiter::new(RateLimitConfig {
+            requests_per_second,
+            burst,
+        })
+    }
+
+    fn ip(last: u8) -> ClientKey {
+        ClientKey::Ip(IpAddr::from([10, 0, 0, last]))
+    }
+
+    #[test]
+    fn test_burst_then_refill() {
+        let limiter = limiter(1.0, 2);
+        let start = Instant::now();
+
+        assert!(limiter.check(&[ip(1)], start).is_ok());
+        assert!(limiter.check(&[ip(1)], start).is_ok());
+        let wait = limiter.check(&[ip(1)], start).unwrap_err();
+        assert_eq!(wait, Duration::from_secs(1));
+
+        // Other clients have their own bucket
+        assert!(limiter.check(&[ip(2)], start).is_ok());
+
+        let later = start + Duration::from_millis(1500);
+        assert!(limiter.check(&[ip(1)], later).is_ok());
+        assert!(limiter.check(&[ip(1)], later).is_err());
+    }
+
+    #[test]
+    fn test_api_key_shared_across_addresses() {
+        let limiter = limiter(1.0, 2);
+        let key = ClientKey::ApiKey("secret".to_string());
+        let now = Instant::now();
+
+        assert!(limiter.check(&[ip(1), key.clone()], now).is_ok());
+        assert!(limiter.check(&[ip(2), key.clone()], now).is_ok());
+        assert!(limiter.check(&[ip(3), key.clone()], now).is_err());
+
+        // A rejected request takes no token from its address
+        assert!(limiter.check(&[ip(3)], now).is_ok());
+        assert!(limiter.check(&[ip(3)], now).is_ok());
+    }
+
+    #[test]
+    fn test_idle_buckets_are_pruned() {
+        let limiter = limiter(1.0, 2);
+        let now = Instant::now();
+        limiter.check(&[ip(1)], now).unwrap();
+
+        limiter.check(&[ip(2)], now + PRUNE_INTERVAL).unwrap();
+
+        let buckets = limiter.buckets.lock().unwrap();
+        assert!(!buckets.clients.contains_key(&ip(1)));
+        assert!(buckets.clients.contains_key(&ip(2)));
+    }
+
+    #[test]
+    fn test_client_keys() {
+        let mut headers = HeaderMap::new();
+        let addr = IpAddr::from([10, 0, 0, 1]);
+        assert_eq!(client_keys(Some(addr), &headers), [ClientKey::Ip(addr)]);
+        assert_eq!(client_keys(None, &headers), [ClientKey::Unknown]);
+
+        headers.insert(AUTHORIZATION, "Bearer secret".parse().unwrap());
+        assert_eq!(
+            client_keys(Some(addr), &headers),
+            [ClientKey::Ip(addr), ClientKey::ApiKey("secret".to_string())]
+        );
+
+        headers.insert("x-api-key", "other".parse().unwrap());
+        assert_eq!(api_key(&headers), Some("other"));
+    }
+}
@@ -1,29 +1,48 @@
 //! Route definitions
 
+use axum::extract::DefaultBodyLimit;
+use axum::middleware;
 use axum::routing::{get, post};
 use axum::Router;
 use std::sync::Arc;
-use tower_http::cors::{Any, CorsLayer};
 use tower_http::timeout::TimeoutLayer;
 use tower_http::trace::TraceLayer;
 use std::time::Duration;
 
 use crate::handlers;
+use crate::ratelimit;
 use crate::state::AppState;
 
 /// Build the application router
 pub fn build_router(state: Arc<AppState>) -> Router {
+    // Endpoints that embed or generate, limited per client
+    let expensive = Router::new()
+        .route("/query", post(handlers::query))
+        .route("/ask", post(handlers::ask))
+        .route("/add", post(handlers::add_document))
+        .route("/search", post(handlers::search))
+        .route("/ingest", post(handlers::ingest))
+        .route(
+            "/upload",
+            post(handlers::upload).layer(DefaultBodyLimit::max(state.config.max_upload_size)),
+        )
+        .route_layer(middleware::from_fn_with_state(
+            state.clone(),
+            ratelimit::limit,
+        ));
+
     let mut app = Router::new()
         // Health and stats
         .route("/health", get(handlers::health))
         .route("/stats", get(handlers::stats))
         // Query endpoints
-        .route("/query", post(handlers::query))
         .route("/classify", post(handlers::classify))
         // Document endpoints
-        .route("/add", post(handlers::add_document))
-        .route("/search", post(handlers::search))
-        .route("/documents", get(handlers::list_documents))
+        .route(
+            "/documents",
+            get(handlers::list_documents).delete(handlers::delete_documents),
+        )
+        .merge(expensive)
         // State
         .with_state(state.clone());
 
@@ -31,13 +50,8 @@ pub fn build_router(state: Arc<AppState>) -> Router {
     app = app.layer(TraceLayer::new_for_http());
     app = app.layer(TimeoutLayer::new(Duration::from_secs(state.config.timeout_secs)));
 
-    if state.config.enable_cors {
-        app = app.layer(
-            CorsLayer::new()
-                .allow_origin(Any)
-                .allow_methods(Any)
-                .allow_headers(Any),
-        );
+    if let Some(ref cors) = state.cors {
+        app = app.layer(cors.clone());
     }
 
     app
@@ -1,5 +1,7 @@
 //! Server implementation
 
+use neuro_core::QueryClassifier;
+use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
 use tracing::info;
@@ -21,6 +23,16 @@ impl Server {
         Ok(Self { state })
     }
 
+    /// Create a server from prepared application state
+    ///
+    /// Use this to inject a custom classifier with
+    /// [`AppState::with_classifier`].
+    pub fn with_state(state: AppState) -> Self {
+        Self {
+            state: Arc::new(state),
+        }
+    }
+
     /// Get a reference to the application state
     pub fn state(&self) -> Arc<AppState> {
         self.state.clone()
@@ -34,8 +46,9 @@ impl Server {
         info!("Starting neuro-bitnet server on {}", addr);
         info!(
             "Embedding model: {}",
-            self.state.embedder.model()
+            self.state.embedder.model_name()
         );
+        info!("Query classifier: {}", self.state.classifier.name());
 
         let listener = TcpListener::bind(&addr)
             .await
@@ -43,9 +56,13 @@ impl Server {
 
         info!("Server listening on http://{}", addr);
 
-        axum::serve(listener, router)
-            .await
-            .map_err(|e| ServerError::Internal(format!("Server error: {}", e)))?;
+        // Peer addresses key the per-client rate limits
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .await
+        .map_err(|e| ServerError::Internal(format!("Server error: {}", e)))?;
 
         Ok(())
     }
@@ -63,10 +80,13 @@ impl Server {
 
         info!("Server listening on http://{}", addr);
 
-        axum::serve(listener, router)
-            .with_graceful_shutdown(shutdown)
-            .await
-            .map_err(|e| ServerError::Internal(format!("Server error: {}", e)))?;
+        axum::serve(
+            listener,
+            router.into_make_service_with_connect_info::<SocketAddr>(),
+        )
+        .with_graceful_shutdown(shutdown)
+        .await
+        .map_err(|e| ServerError::Internal(format!("Server error: {}", e)))?;
 
         info!("Server shutdown complete");
         Ok(())
@@ -76,7 +96,9 @@ impl Server {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use axum_test::multipart::{MultipartForm, Part};
     use axum_test::TestServer;
+    use neuro_core::{ClassificationResult, QueryCategory, QueryClassifier, QueryStrategy};
     use serde_json::json;
 
     async fn test_server() -> TestServer {
@@ -107,6 +129,86 @@ mod tests {
         response.assert_status_ok();
         let body: serde_json::Value = response.json();
         assert!(body["document_count"].is_number());
+        assert!(body.get("llm_usage").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_stats_reports_llm_usage() {
+        let config = ServerConfig {
+            storage_path: None,
+            ..ServerConfig::development()
+        };
+        let state = AppState::new(config)
+            .await
+            .unwrap()
+            .with_llm(neuro_llm::LlmClient::new("http://localhost:11435"));
+        let server = TestServer::new(build_router(Arc::new(state))).unwrap();
+
+        let response = server.get("/stats").await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["llm_usage"]["session"]["requests"], 0);
+        assert!(body["llm_usage"]["global"]["total_tokens"].is_number());
+    }
+
+    #[tokio::test]
+    async fn test_ingest_requires_urls_or_query() {
+        let server = test_server().await;
+
+        let response = server.post("/ingest").json(&json!({})).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        let response = server
+            .post("/ingest")
+            .json(&json!({"urls": ["https://example.com"], "query": "rust"}))
+            .await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_upload_rejects_unsupported_files() {
+        let server = test_server().await;
+
+        let form = MultipartForm::new().add_text("tags", "docs");
+        let response = server.post("/upload").multipart(form).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+
+        let form = MultipartForm::new()
+            .add_part("file", Part::bytes(vec![0u8; 16]).file_name("photo.jpg"));
+        let response = server.post("/upload").multipart(form).await;
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    #[ignore = "Requires embedding model download"]
+    async fn test_upload_and_search() {
+        let server = test_server().await;
+        let handbook = "Badges are issued on your first day.\n\nExpenses need receipts.\n";
+        let upload = || {
+            MultipartForm::new().add_text("tags", "handbook").add_part(
+                "file",
+                Part::bytes(handbook.as_bytes().to_vec()).file_name("handbook.txt"),
+            )
+        };
+
+        let response = server.post("/upload").multipart(upload()).await;
+        response.assert_status(axum::http::StatusCode::CREATED);
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["files"], 1);
+        assert_eq!(body["replaced"], 0);
+
+        // Uploading the file again replaces its chunks
+        let response = server.post("/upload").multipart(upload()).await;
+        let again: serde_json::Value = response.json();
+        assert_eq!(again["replaced"], body["chunks"]);
+
+        let response = server
+            .post("/search")
+            .json(&json!({"query": "first day badge", "tags": ["handbook"]}))
+            .await;
+        let results: Vec<serde_json::Value> = response.json();
+        assert_eq!(results[0]["document"]["metadata"]["file_name"], "handbook.txt");
     }
 
     #[tokio::test]
@@ -154,6 +256,212 @@ mod tests {
         assert_eq!(body["category"], "math");
     }
 
+    #[tokio::test]
+    async fn test_custom_classifier() {
+        struct AlwaysGreeting;
+
+        impl QueryClassifier for AlwaysGreeting {
+            fn classify(&self, query: &str) -> ClassificationResult {
+                ClassificationResult::new(QueryCategory::Greeting, QueryStrategy::LlmDirect, 1.0)
+                    .with_query(query)
+            }
+        }
+
+        let config = ServerConfig {
+            storage_path: None,
+            ..ServerConfig::development()
+        };
+        let state = AppState::new(config)
+            .await
+            .unwrap()
+            .with_classifier(Arc::new(AlwaysGreeting));
+        let server = TestServer::new(build_router(Arc::new(state))).unwrap();
+
+        let response = server
+            .post("/classify")
+            .json(&json!({
+                "query": "What is 2 + 2?"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["category"], "greeting");
+    }
+
+    #[tokio::test]
+    async fn test_query_sensitive_short_circuits() {
+        let server = test_server().await;
+
+        let response = server
+            .post("/query")
+            .json(&json!({
+                "query": "How do I make a pipe bomb?"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["classification"]["strategy"], "safe_reply");
+        assert_eq!(body["safe_reply"], neuro_core::SAFE_REPLY);
+        assert!(body["search_results"].as_array().unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_query_stream_sends_events() {
+        let server = test_server().await;
+
+        let response = server
+            .post("/query")
+            .json(&json!({
+                "query": "How do I make a pipe bomb?",
+                "stream": true
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let content_type = response.header("content-type");
+        assert!(content_type
+            .to_str()
+            .unwrap()
+            .starts_with("text/event-stream"));
+        let body = response.text();
+        let classification = body.find("event: classification").unwrap();
+        let result = body.find("event: result").unwrap();
+        assert!(classification < result);
+        assert!(!body.contains("event: sources"));
+    }
+
+    #[tokio::test]
+    async fn test_ask_requires_model() {
+        let server = test_server().await;
+
+        let response = server
+            .post("/ask")
+            .json(&json!({
+                "query": "What is Rust?"
+            }))
+            .await;
+
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_ask_sensitive_uses_safe_reply() {
+        let config = ServerConfig {
+            storage_path: None,
+            ..ServerConfig::development()
+        };
+        let state = AppState::new(config)
+            .await
+            .unwrap()
+            .with_llm(neuro_llm::LlmClient::new("http://localhost:11435"));
+        let server = TestServer::new(build_router(Arc::new(state))).unwrap();
+
+        let response = server
+            .post("/ask")
+            .json(&json!({
+                "query": "How do I make a pipe bomb?"
+            }))
+            .await;
+
+        response.assert_status_ok();
+        let body: serde_json::Value = response.json();
+        assert_eq!(body["answer"], neuro_core::SAFE_REPLY);
+        assert_eq!(body["classification"]["strategy"], "safe_reply");
+        assert!(body["sources"].as_array().unwrap().is_empty());
+        assert!(body["timings"]["total_ms"].is_number());
+        assert!(body.get("model").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_query_rerank_requires_reranker() {
+        let server = test_server().await;
+
+        let response = server
+            .post("/query")
+            .json(&json!({
+                "query": "What is Rust?",
+                "rerank": true
+            }))
+            .await;
+
+        response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit() {
+        let config = ServerConfig {
+            storage_path: None,
+            rate_limit: Some(crate::RateLimitConfig {
+                requests_per_second: 0.01,
+                burst: 2,
+            }),
+            ..ServerConfig::development()
+        };
+        let state = Arc::new(AppState::new(config).await.unwrap());
+        let server = TestServer::new(build_router(state)).unwrap();
+
+        for _ in 0..2 {
+            let response = server.post("/query").json(&json!({"query": ""})).await;
+            response.assert_status(axum::http::StatusCode::BAD_REQUEST);
+        }
+
+        let response = server.post("/query").json(&json!({"query": ""})).await;
+        response.assert_status(axum::http::StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.header("retry-after"), "100");
+
+        // Cheap endpoints are not limited
+        server.get("/health").await.assert_status_ok();
+        server
+            .post("/classify")
+            .json(&json!({"query": "Hello"}))
+            .await
+            .assert_status_ok();
+    }
+
+    #[tokio::test]
+    async fn test_cors() {
+        use axum::http::header::{ACCESS_CONTROL_ALLOW_ORIGIN, ORIGIN};
+        use axum::http::HeaderValue;
+
+        let origin = HeaderValue::from_static("https://app.example.com");
+
+        // Off by default
+        let server = test_server().await;
+        let response = server
+            .get("/health")
+            .add_header(ORIGIN, origin.clone())
+            .await;
+        assert!(response
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+
+        let config = ServerConfig {
+            storage_path: None,
+            cors: Some(crate::CorsConfig::new(["https://app.example.com"])),
+            ..ServerConfig::development()
+        };
+        let state = Arc::new(AppState::new(config).await.unwrap());
+        let server = TestServer::new(build_router(state)).unwrap();
+
+        let response = server
+            .get("/health")
+            .add_header(ORIGIN, origin.clone())
+            .await;
+        assert_eq!(response.headers()[ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+
+        let response = server
+            .get("/health")
+            .add_header(ORIGIN, HeaderValue::from_static("https://evil.example.com"))
+            .await;
+        assert!(response
+            .headers()
+            .get(ACCESS_CONTROL_ALLOW_ORIGIN)
+            .is_none());
+    }
+
     #[tokio::test]
     #[ignore = "Requires embedding model download"]
     async fn test_query() {
@@ -3,14 +3,25 @@
 use std::sync::Arc;
 use std::time::Instant;
 use tokio::sync::RwLock;
+use tower_http::cors::CorsLayer;
+use tracing::warn;
 
-use neuro_classifier::Classifier;
-use neuro_embeddings::{Embedder, FastEmbedder, EmbeddingModel};
+use neuro_classifier::{Classifier, QueryClassifier, SemanticClassifier};
+use neuro_embeddings::{
+    AsyncEmbedder, Bm25Embedder, Device, EmbedderOptions, Embedder, EmbeddingModel, FastEmbedder,
+    RemoteEmbedder, RemoteEmbedderConfig, Reranker, SparseEmbedder, SpawnBlockingEmbedder,
+    SpladeEmbedder,
+};
+use neuro_inference::{InferenceConfig, InferenceModel};
+use neuro_llm::{LlmClient, LlmConfig};
 use neuro_storage::{Storage, MemoryStorage, FileStorage};
-use neuro_search::{WebSearcher, WikipediaSearcher};
+use neuro_search::{RateLimitedSearcher, WebSearcher, WikidataSearcher, WikipediaSearcher};
 
 use crate::config::ServerConfig;
+use crate::cors::CorsConfig;
 use crate::error::{Result, ServerError};
+use crate::ingest::WebIngestor;
+use crate::ratelimit::RateLimiter;
 
 /// Shared application state
 pub struct AppState {
@@ -20,11 +31,40 @@ pub struct AppState {
     /// Embedding generator
     pub embedder: Arc<dyn Embedder>,
     
+    /// Embedding generator for request handlers, running `embedder` off
+    /// the async runtime
+    pub async_embedder: Arc<dyn AsyncEmbedder>,
+    
+    /// Sparse embedder for hybrid search, if configured
+    pub sparse_embedder: Option<Arc<dyn SparseEmbedder>>,
+    
+    /// Cross-encoder reranker, if configured
+    pub reranker: Option<Arc<Reranker>>,
+    
     /// Query classifier
-    pub classifier: Classifier,
+    pub classifier: Arc<dyn QueryClassifier>,
     
     /// Web searcher
     pub web_searcher: Arc<dyn WebSearcher>,
+
+    /// Structured facts for factual queries
+    pub fact_searcher: Arc<dyn WebSearcher>,
+    
+    /// Turns web pages into documents for `/ingest`
+    pub ingestor: WebIngestor,
+    
+    /// Local model answering `/ask`, if any
+    pub local_model: Option<InferenceModel>,
+    
+    /// LLM client answering `/ask` without a local model; `/stats` reports
+    /// its usage
+    pub llm: Option<LlmClient>,
+    
+    /// CORS middleware built from `config.cors`, if configured
+    pub cors: Option<CorsLayer>,
+    
+    /// Per-client limit on expensive endpoints, if configured
+    pub rate_limiter: Option<RateLimiter>,
     
     /// Server configuration
     pub config: ServerConfig,
@@ -51,33 +91,152 @@ impl AppState {
         };
 
         // Initialize embedder
-        let model: EmbeddingModel = config
-            .embedding_model
-            .parse()
-            .unwrap_or(EmbeddingModel::AllMiniLmL6V2);
-        
-        let embedder = Arc::new(
-            FastEmbedder::new(model)
-                .map_err(|e| ServerError::Internal(e.to_string()))?,
-        );
-
-        // Initialize classifier
-        let classifier = Classifier::new();
-
-        // Initialize web searcher
-        let web_searcher = Arc::new(WikipediaSearcher::new());
+        let embedder: Arc<dyn Embedder> = if let Some(ref url) = config.embedding_url {
+            let mut remote = RemoteEmbedderConfig::new(url, &config.embedding_model);
+            if let Some(ref api_key) = config.embedding_api_key {
+                remote = remote.with_api_key(api_key);
+            }
+            Arc::new(
+                RemoteEmbedder::new(remote)
+                    .map_err(|e| ServerError::Internal(e.to_string()))?,
+            )
+        } else {
+            let model: EmbeddingModel = config
+                .embedding_model
+                .parse()
+                .unwrap_or(EmbeddingModel::AllMiniLmL6V2);
+            let device: Device = config
+                .embedding_device
+                .parse()
+                .map_err(ServerError::Internal)?;
+            Arc::new(
+                FastEmbedder::new_with_options(model, EmbedderOptions::new().with_device(device))
+                    .map_err(|e| ServerError::Internal(e.to_string()))?,
+            )
+        };
+
+        // Initialize sparse embedder
+        let sparse_embedder: Option<Arc<dyn SparseEmbedder>> = match config.sparse_model.as_deref() {
+            Some("bm25") => Some(Arc::new(Bm25Embedder::new())),
+            Some("splade") => Some(Arc::new(
+                SpladeEmbedder::new().map_err(|e| ServerError::Internal(e.to_string()))?,
+            )),
+            Some(other) => {
+                return Err(ServerError::Internal(format!(
+                    "Unknown sparse model: {} (expected bm25 or splade)",
+                    other
+                )))
+            }
+            None => None,
+        };
+
+        // Initialize reranker
+        let reranker = match config.reranker_model {
+            Some(ref name) => Some(Arc::new(
+                Reranker::from_model_name(name)
+                    .map_err(|e| ServerError::Internal(e.to_string()))?,
+            )),
+            None => None,
+        };
+
+        // Initialize classifier (with embedding-based fallback for paraphrases)
+        let classifier = match SemanticClassifier::new(embedder.clone()) {
+            Ok(semantic) => Classifier::new().with_semantic_fallback(semantic),
+            Err(e) => {
+                warn!("Semantic classifier unavailable, using patterns only: {}", e);
+                Classifier::new()
+            }
+        };
+
+        // Initialize web searcher, rate limited so bursts of queries
+        // needing web context don't get the host banned
+        let web_searcher: Arc<dyn WebSearcher> =
+            Arc::new(RateLimitedSearcher::new(WikipediaSearcher::new()));
+        let fact_searcher: Arc<dyn WebSearcher> =
+            Arc::new(RateLimitedSearcher::new(WikidataSearcher::new()));
+
+        let async_embedder: Arc<dyn AsyncEmbedder> =
+            Arc::new(SpawnBlockingEmbedder::new(embedder.clone()));
+
+        // Load the local model for `/ask`, off the async runtime
+        let local_model = match config.model_path {
+            Some(ref path) => {
+                let inference_config =
+                    InferenceConfig::new(path).with_context_size(config.context_size);
+                let model =
+                    tokio::task::spawn_blocking(move || InferenceModel::load(inference_config))
+                        .await
+                        .map_err(|e| {
+                            ServerError::Internal(format!("Model loading task failed: {}", e))
+                        })??;
+                Some(model)
+            }
+            None => None,
+        };
+
+        // Initialize the LLM client for `/ask`
+        let llm = match config.llm_url {
+            Some(ref url) => {
+                let mut llm_config = LlmConfig::new(url).with_provider(config.llm_provider);
+                llm_config.max_tokens = config.max_tokens;
+                llm_config.temperature = config.temperature;
+                if let Some(ref model) = config.llm_model {
+                    llm_config.model = model.clone();
+                }
+                Some(
+                    LlmClient::try_with_config(llm_config)
+                        .map_err(|e| ServerError::Internal(e.to_string()))?,
+                )
+            }
+            None => None,
+        };
+
+        let cors = config.cors.as_ref().map(CorsConfig::layer).transpose()?;
+
+        let mut ingestor =
+            WebIngestor::new(async_embedder.clone()).with_searcher(web_searcher.clone());
+        if let Some(ref sparse) = sparse_embedder {
+            ingestor = ingestor.with_sparse_embedder(sparse.clone());
+        }
 
         Ok(Self {
             storage: RwLock::new(storage),
+            async_embedder,
             embedder,
-            classifier,
+            sparse_embedder,
+            reranker,
+            classifier: Arc::new(classifier),
             web_searcher,
+            fact_searcher,
+            ingestor,
+            local_model,
+            llm,
+            cors,
+            rate_limiter: config.rate_limit.clone().map(RateLimiter::new),
             config,
             start_time: Instant::now(),
             request_count: RwLock::new(0),
         })
     }
 
+    /// Replace the query classifier
+    pub fn with_classifier(mut self, classifier: Arc<dyn QueryClassifier>) -> Self {
+        self.classifier = classifier;
+        self
+    }
+
+    /// Answer `/ask` with a local model
+    pub fn with_local_model(mut self, model: InferenceModel) -> Self {
+        self.local_model = Some(model);
+        self
+    }
+
+    /// Answer `/ask` with an LLM client and report its usage in `/stats`
+    pub fn with_llm(mut self, client: LlmClient) -> Self {
+        self.llm = Some(client);
+        self
+    }
+
     /// Increment request counter
     pub async fn increment_requests(&self) {
         let mut count = self.request_count.write().await;
@@ -0,0 +1,84 @@
+//! Server-sent event streams for `/query` and `/ask`
+//!
+//! A streamed request emits, in order:
+//!
+//! - `classification` - how the query was classified
+//! - `sources` - the documents retrieved for it
+//! - `token` - each piece of the generated answer (`/ask` only)
+//! - `result` - the complete response, as the non-streamed endpoint returns it
+//!
+//! A failure ends the stream with an `error` event carrying `{"error": ...}`
+//! instead of `result`. Events for steps a query skips, such as retrieval
+//! for a sensitive query, are not sent.
+
+use std::convert::Infallible;
+use std::future::Future;
+
+use axum::response::sse::{Event, KeepAlive, Sse};
+use futures::Stream;
+use serde::Serialize;
+use serde_json::json;
+use tokio::sync::mpsc;
+use tokio_stream::wrappers::ReceiverStream;
+use tracing::debug;
+
+use crate::error::Result;
+
+/// Events buffered before the request task waits for the client
+const EVENT_BUFFER: usize = 64;
+
+/// Where a request reports its progress; discards everything for
+/// non-streamed requests
+pub(crate) struct EventSink(Option<mpsc::Sender<std::result::Result<Event, Infallible>>>);
+
+impl EventSink {
+    /// A sink for requests that are not streamed
+    pub(crate) fn none() -> Self {
+        Self(None)
+    }
+
+    /// Whether events reach a client
+    pub(crate) fn is_streaming(&self) -> bool {
+        self.0.is_some()
+    }
+
+    /// Send an event, returning false once the client has gone away
+    pub(crate) async fn send(&self, name: &str, data: &impl Serialize) -> bool {
+        let Some(ref tx) = self.0 else {
+            return true;
+        };
+        let event = match Event::default().event(name).json_data(data) {
+            Ok(event) => event,
+            Err(e) => {
+                debug!("Could not serialize {} event: {}", name, e);
+                return true;
+            }
+        };
+        tx.send(Ok(event)).await.is_ok()
+    }
+}
+
+/// Run `task` in the background, streaming its events followed by its
+/// `result` or `error`
+pub(crate) fn stream_events<F, Fut, T>(
+    task: F,
+) -> Sse<impl Stream<Item = std::result::Result<Event, Infallible>>>
+where
+    F: FnOnce(EventSink) -> Fut,
+    Fut: Future<Output = Result<T>> + Send + 'static,
+    T: Serialize + Send + Sync + 'static,
+{
+    let (tx, rx) = mpsc::channel(EVENT_BUFFER);
+    let sink = EventSink(Some(tx));
+    let done = EventSink(sink.0.clone());
+    let future = task(sink);
+
+    tokio::spawn(async move {
+        match future.await {
+            Ok(result) => done.send("result", &result).await,
+            Err(e) => done.send("error", &json!({ "error": e.to_string() })).await,
+        };
+    });
+
+    Sse::new(ReceiverStream::new(rx)).keep_alive(KeepAlive::default())
+}
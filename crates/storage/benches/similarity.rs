@@ -0,0 +1,68 @@
+//! Benchmarks for cosine similarity kernels
+//!
+//! Run with `cargo bench -p neuro-storage --bench similarity`.
+
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use neuro_storage::{cosine_similarity, cosine_similarity_scalar, simd_backend};
+
+/// Deterministic pseudo-random vectors (no extra dependencies)
+fn make_vectors(count: usize, dim: usize, seed: u32) -> Vec<Vec<f32>> {
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            (0..dim)
+                .map(|_| {
+                    state = state.wrapping_mul(1_664_525).wrapping_add(1_013_904_223);
+                    (state >> 8) as f32 / (1u32 << 24) as f32 - 0.5
+                })
+                .collect()
+        })
+        .collect()
+}
+
+fn bench_pair(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("cosine_pair ({})", simd_backend()));
+
+    for dim in [384, 768, 1024] {
+        let v = make_vectors(2, dim, 42);
+        let (a, b) = (&v[0], &v[1]);
+
+        group.bench_with_input(BenchmarkId::new("simd", dim), &dim, |bench, _| {
+            bench.iter(|| cosine_similarity(black_box(a), black_box(b)))
+        });
+        group.bench_with_input(BenchmarkId::new("scalar", dim), &dim, |bench, _| {
+            bench.iter(|| cosine_similarity_scalar(black_box(a), black_box(b)))
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_scan(c: &mut Criterion) {
+    let mut group = c.benchmark_group(format!("cosine_scan_10k_384 ({})", simd_backend()));
+
+    let query = make_vectors(1, 384, 7).remove(0);
+    let documents = make_vectors(10_000, 384, 1234);
+
+    group.bench_function("simd", |bench| {
+        bench.iter(|| {
+            documents
+                .iter()
+                .map(|d| cosine_similarity(black_box(&query), d))
+                .fold(f32::MIN, f32::max)
+        })
+    });
+    group.bench_function("scalar", |bench| {
+        bench.iter(|| {
+            documents
+                .iter()
+                .map(|d| cosine_similarity_scalar(black_box(&query), d))
+                .fold(f32::MIN, f32::max)
+        })
+    });
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_pair, bench_scan);
+criterion_main!(benches);
@@ -0,0 +1,1180 @@
+//! Disk-resident storage for larger-than-RAM corpora
+//!
+//! Embeddings live in a flat, memory-mapped `vectors.bin` file and document
+//! records in an append-only `documents.jsonl` file, so only a small
+//! per-document index entry is kept in RAM. Search either scans the mapped
+//! vectors directly or, once [`DiskStorage::build_index`] has been called,
+//! probes an inverted-file (IVF) index built with k-means clustering.
+
+use async_trait::async_trait;
+use futures::stream;
+use memmap2::Mmap;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::mem::size_of;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info, warn};
+
+use neuro_core::{Document, SearchResult};
+use neuro_embeddings::Embedder;
+use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
+use crate::similarity::cosine_similarity;
+use crate::storage::{
+    reembed_into, validate_embedding_model, DocumentStream, Storage, StorageStats,
+};
+use crate::swap::{finish_swap, remove_dir_if_exists, sibling, swap_in, STAGING_SUFFIX};
+
+const VECTORS_FILE: &str = "vectors.bin";
+const DOCUMENTS_FILE: &str = "documents.jsonl";
+const META_FILE: &str = "meta.json";
+const INDEX_FILE: &str = "ivf.json";
+
+/// Configuration for the IVF index used by [`DiskStorage`]
+#[derive(Debug, Clone)]
+pub struct DiskIndexConfig {
+    /// Number of clusters (0 = square root of the document count)
+    pub nlist: usize,
+    /// Number of clusters probed per query
+    pub nprobe: usize,
+    /// Maximum number of vectors sampled to train the clusters
+    pub train_sample: usize,
+    /// Number of k-means iterations
+    pub train_iterations: usize,
+}
+
+impl Default for DiskIndexConfig {
+    fn default() -> Self {
+        Self {
+            nlist: 0,
+            nprobe: 8,
+            train_sample: 20_000,
+            train_iterations: 10,
+        }
+    }
+}
+
+impl DiskIndexConfig {
+    /// Set the number of clusters
+    pub fn with_nlist(mut self, nlist: usize) -> Self {
+        self.nlist = nlist;
+        self
+    }
+
+    /// Set the number of clusters probed per query
+    pub fn with_nprobe(mut self, nprobe: usize) -> Self {
+        self.nprobe = nprobe.max(1);
+        self
+    }
+}
+
+/// A record in `documents.jsonl`
+#[derive(Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+enum DiskRecord {
+    /// Document stored at the given vector row (embedding omitted)
+    Put { row: usize, document: Box<Document> },
+    /// Tombstone for a deleted document
+    Delete { id: String },
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct DiskMeta {
+    dimension: Option<usize>,
+    #[serde(default)]
+    embedding_model: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct IvfIndex {
+    centroids: Vec<Vec<f32>>,
+    lists: Vec<Vec<usize>>,
+    /// Rows covered by the persisted lists; later rows are assigned on open
+    indexed_rows: usize,
+}
+
+impl IvfIndex {
+    fn nearest(&self, vector: &[f32]) -> usize {
+        nearest_centroid(&self.centroids, vector)
+    }
+}
+
+/// In-memory index entry pointing at on-disk data
+struct DiskEntry {
+    row: usize,
+    offset: usize,
+    len: usize,
+    user_id: Option<String>,
+}
+
+/// Disk-resident document storage
+///
+/// Keeps embeddings and documents on disk and memory-maps them, so the
+/// operating system pages data in and out as needed. Deletes are recorded
+/// as tombstones.
+pub struct DiskStorage {
+    dir: PathBuf,
+    config: DiskIndexConfig,
+    dimension: Option<usize>,
+    embedding_model: Option<String>,
+    entries: HashMap<String, DiskEntry>,
+    row_ids: Vec<Option<String>>,
+    vectors_file: File,
+    documents_file: File,
+    vectors: Option<Mmap>,
+    documents: Option<Mmap>,
+    documents_len: usize,
+    index: Option<IvfIndex>,
+}
+
+impl DiskStorage {
+    /// Open (or create) a disk storage in the given directory
+    pub async fn open(dir: impl AsRef<Path>) -> Result<Self> {
+        Self::open_with_config(dir, DiskIndexConfig::default()).await
+    }
+
+    /// Open with a custom index configuration
+    pub async fn open_with_config(dir: impl AsRef<Path>, config: DiskIndexConfig) -> Result<Self> {
+        let dir = dir.as_ref().to_path_buf();
+        finish_swap(&dir)?;
+        fs::create_dir_all(&dir)?;
+
+        let meta: DiskMeta = read_json(&dir.join(META_FILE))?.unwrap_or_default();
+
+        let open_append = |name: &str| {
+            OpenOptions::new()
+                .create(true)
+                .read(true)
+                .append(true)
+                .open(dir.join(name))
+        };
+        let vectors_file = open_append(VECTORS_FILE)?;
+        let documents_file = open_append(DOCUMENTS_FILE)?;
+
+        let mut storage = Self {
+            dir,
+            config,
+            dimension: meta.dimension,
+            embedding_model: meta.embedding_model,
+            entries: HashMap::new(),
+            row_ids: Vec::new(),
+            vectors_file,
+            documents_file,
+            vectors: None,
+            documents: None,
+            documents_len: 0,
+            index: None,
+        };
+
+        storage.remap()?;
+        storage.load_entries()?;
+        storage.load_index()?;
+
+        info!(
+            "Opened disk storage at {:?} ({} documents, index: {})",
+            storage.dir,
+            storage.entries.len(),
+            storage.has_index()
+        );
+
+        Ok(storage)
+    }
+
+    /// Get the storage directory
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Get the embedding dimension
+    pub fn dimension(&self) -> Option<usize> {
+        self.dimension
+    }
+
+    /// Get the embedding model (if recorded on any document)
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+
+    /// Check whether an IVF index has been built
+    pub fn has_index(&self) -> bool {
+        self.index.is_some()
+    }
+
+    /// Train the IVF index on the stored vectors and persist it
+    ///
+    /// Documents added afterwards are assigned to their nearest cluster
+    /// automatically. Rebuild periodically if the corpus changes a lot.
+    pub fn build_index(&mut self) -> Result<()> {
+        let live_rows: Vec<usize> = self
+            .row_ids
+            .iter()
+            .enumerate()
+            .filter_map(|(row, id)| id.as_ref().map(|_| row))
+            .collect();
+
+        if live_rows.is_empty() {
+            self.index = None;
+            remove_if_exists(&self.dir.join(INDEX_FILE))?;
+            return Ok(());
+        }
+
+        let n = live_rows.len();
+        let nlist = if self.config.nlist > 0 {
+            self.config.nlist
+        } else {
+            (n as f64).sqrt().ceil() as usize
+        }
+        .clamp(1, n);
+
+        // Train on an evenly spaced sample
+        let step = (n / self.config.train_sample.max(1)).max(1);
+        let sample: Vec<&[f32]> = live_rows
+            .iter()
+            .step_by(step)
+            .filter_map(|&row| self.vector(row))
+            .collect();
+
+        let mut centroids: Vec<Vec<f32>> = (0..nlist)
+            .map(|i| normalized(sample[i * sample.len() / nlist]))
+            .collect();
+
+        let dim = centroids[0].len();
+        for _ in 0..self.config.train_iterations {
+            let mut sums = vec![vec![0.0f32; dim]; nlist];
+            let mut counts = vec![0usize; nlist];
+
+            for v in &sample {
+                let c = nearest_centroid(&centroids, v);
+                counts[c] += 1;
+                for (s, x) in sums[c].iter_mut().zip(v.iter()) {
+                    *s += x;
+                }
+            }
+
+            for (c, sum) in sums.iter().enumerate() {
+                if counts[c] > 0 {
+                    centroids[c] = normalized(sum);
+                }
+            }
+        }
+
+        let mut index = IvfIndex {
+            centroids,
+            lists: vec![Vec::new(); nlist],
+            indexed_rows: self.row_ids.len(),
+        };
+        for row in live_rows {
+            if let Some(v) = self.vector(row) {
+                let c = index.nearest(v);
+                index.lists[c].push(row);
+            }
+        }
+
+        write_json(&self.dir.join(INDEX_FILE), &index)?;
+        info!("Built IVF index with {} clusters over {} documents", nlist, n);
+
+        self.index = Some(index);
+        Ok(())
+    }
+
+    fn remap(&mut self) -> Result<()> {
+        self.vectors = map_file(&self.vectors_file)?;
+        self.documents = map_file(&self.documents_file)?;
+        Ok(())
+    }
+
+    /// Rebuild the in-memory entries by scanning `documents.jsonl`
+    fn load_entries(&mut self) -> Result<()> {
+        let mut good_len = 0;
+
+        if let Some(map) = self.documents.as_ref() {
+            let mut offset = 0;
+            while offset < map.len() {
+                let end = match map[offset..].iter().position(|&b| b == b'\n') {
+                    Some(pos) => offset + pos,
+                    None => break,
+                };
+
+                let line = &map[offset..end];
+                match serde_json::from_slice::<DiskRecord>(line) {
+                    Ok(DiskRecord::Put { row, document }) => {
+                        if self.row_ids.len() <= row {
+                            self.row_ids.resize(row + 1, None);
+                        }
+                        self.row_ids[row] = Some(document.id.clone());
+                        self.entries.insert(
+                            document.id.clone(),
+                            DiskEntry {
+                                row,
+                                offset,
+                                len: line.len(),
+                                user_id: document.user_id,
+                            },
+                        );
+                    }
+                    Ok(DiskRecord::Delete { id }) => {
+                        if let Some(entry) = self.entries.remove(&id) {
+                            self.row_ids[entry.row] = None;
+                        }
+                    }
+                    Err(e) => {
+                        warn!("Corrupt record in {:?} at byte {}: {}", self.dir, offset, e);
+                        break;
+                    }
+                }
+
+                offset = end + 1;
+                good_len = offset;
+            }
+
+            if good_len < map.len() {
+                warn!(
+                    "Truncating {} bytes of incomplete data from {}",
+                    map.len() - good_len,
+                    DOCUMENTS_FILE
+                );
+            }
+        }
+
+        if good_len < self.documents.as_ref().map_or(0, |m| m.len()) {
+            self.documents = None;
+            self.documents_file.set_len(good_len as u64)?;
+            self.documents = map_file(&self.documents_file)?;
+        }
+        self.documents_len = good_len;
+
+        // Account for vector rows without a committed record (crash between writes)
+        if let Some(dim) = self.dimension {
+            let rows = self.vectors_file.metadata()?.len() as usize / (dim * 4);
+            if self.row_ids.len() < rows {
+                self.row_ids.resize(rows, None);
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_index(&mut self) -> Result<()> {
+        let Some(mut index) = read_json::<IvfIndex>(&self.dir.join(INDEX_FILE))? else {
+            return Ok(());
+        };
+
+        // Assign rows added since the index was persisted
+        for row in index.indexed_rows..self.row_ids.len() {
+            if self.row_ids[row].is_some() {
+                if let Some(v) = self.vector(row) {
+                    let c = index.nearest(v);
+                    index.lists[c].push(row);
+                }
+            }
+        }
+
+        self.index = Some(index);
+        Ok(())
+    }
+
+    fn vector(&self, row: usize) -> Option<&[f32]> {
+        let dim = self.dimension?;
+        let map = self.vectors.as_ref()?;
+        let start = row * dim * 4;
+        let bytes = map.get(start..start + dim * 4)?;
+        debug_assert_eq!(bytes.as_ptr() as usize % std::mem::align_of::<f32>(), 0);
+        // SAFETY: the mapping is page-aligned and every row starts at a
+        // multiple of 4 bytes, so the pointer is aligned for f32; any bit
+        // pattern is a valid f32 and the slice lives as long as `&self`.
+        Some(unsafe { std::slice::from_raw_parts(bytes.as_ptr() as *const f32, dim) })
+    }
+
+    fn read_document(&self, entry: &DiskEntry) -> Result<Document> {
+        let map = self
+            .documents
+            .as_ref()
+            .ok_or_else(|| StorageError::InvalidOperation("Documents file is empty".to_string()))?;
+
+        match serde_json::from_slice(&map[entry.offset..entry.offset + entry.len])? {
+            DiskRecord::Put { mut document, .. } => {
+                document.embedding = self.vector(entry.row).map(|v| v.to_vec());
+                Ok(*document)
+            }
+            DiskRecord::Delete { id } => Err(StorageError::InvalidOperation(format!(
+                "Expected document record, found tombstone for {}",
+                id
+            ))),
+        }
+    }
+
+    fn validate_embedding(&self, embedding: &[f32]) -> Result<()> {
+        if let Some(dim) = self.dimension {
+            if embedding.len() != dim {
+                return Err(StorageError::DimensionMismatch {
+                    expected: dim,
+                    actual: embedding.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Append a document to disk without remapping
+    fn append(&mut self, mut document: Document) -> Result<()> {
+        let embedding = document
+            .embedding
+            .take()
+            .ok_or_else(|| StorageError::MissingEmbedding(document.id.clone()))?;
+
+        if self.entries.contains_key(&document.id) {
+            return Err(StorageError::AlreadyExists(document.id.clone()));
+        }
+
+        self.validate_embedding(&embedding)?;
+        validate_embedding_model(self.embedding_model.as_deref(), &document)?;
+
+        let new_model = self.embedding_model.is_none() && document.embedding_model.is_some();
+        if self.dimension.is_none() || new_model {
+            self.dimension = Some(embedding.len());
+            if new_model {
+                self.embedding_model = document.embedding_model.clone();
+            }
+            write_json(
+                &self.dir.join(META_FILE),
+                &DiskMeta {
+                    dimension: self.dimension,
+                    embedding_model: self.embedding_model.clone(),
+                },
+            )?;
+        }
+
+        debug!("Adding document {} ({} chars)", document.id, document.content.len());
+
+        let id = document.id.clone();
+        let user_id = document.user_id.clone();
+
+        let row = self.row_ids.len();
+        let mut line = serde_json::to_vec(&DiskRecord::Put {
+            row,
+            document: Box::new(document),
+        })?;
+        let len = line.len();
+        line.push(b'\n');
+
+        // Vector first: a record is only committed once its line is written
+        let bytes: Vec<u8> = embedding.iter().flat_map(|x| x.to_ne_bytes()).collect();
+        if let Err(e) = self.vectors_file.write_all(&bytes) {
+            truncate_after_failed_write(&self.vectors_file, (row * bytes.len()) as u64);
+            return Err(e.into());
+        }
+
+        // The vector occupies its row whether or not the record follows, so
+        // later records keep pointing at their own vectors
+        self.row_ids.push(None);
+        if let Err(e) = self.documents_file.write_all(&line) {
+            truncate_after_failed_write(&self.documents_file, self.documents_len as u64);
+            return Err(e.into());
+        }
+
+        let offset = self.documents_len;
+        self.documents_len += line.len();
+        self.row_ids[row] = Some(id.clone());
+        self.entries.insert(
+            id,
+            DiskEntry {
+                row,
+                offset,
+                len,
+                user_id,
+            },
+        );
+
+        if let Some(index) = self.index.as_mut() {
+            let c = index.nearest(&embedding);
+            index.lists[c].push(row);
+        }
+
+        Ok(())
+    }
+
+    fn remove(&mut self, id: &str) -> Result<()> {
+        let mut line = serde_json::to_vec(&DiskRecord::Delete { id: id.to_string() })?;
+        line.push(b'\n');
+        if let Err(e) = self.documents_file.write_all(&line) {
+            truncate_after_failed_write(&self.documents_file, self.documents_len as u64);
+            return Err(e.into());
+        }
+        self.documents_len += line.len();
+
+        if let Some(entry) = self.entries.remove(id) {
+            self.row_ids[entry.row] = None;
+        }
+        Ok(())
+    }
+
+    /// Rows to score for a query: probed IVF lists, or every row
+    fn candidate_rows(&self, embedding: &[f32]) -> Vec<usize> {
+        match self.index.as_ref() {
+            Some(index) => {
+                let mut clusters: Vec<(usize, f32)> = index
+                    .centroids
+                    .iter()
+                    .enumerate()
+                    .map(|(i, c)| (i, cosine_similarity(embedding, c)))
+                    .collect();
+                clusters.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+                clusters
+                    .into_iter()
+                    .take(self.config.nprobe)
+                    .flat_map(|(i, _)| index.lists[i].iter().copied())
+                    .collect()
+            }
+            None => (0..self.row_ids.len()).collect(),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for DiskStorage {
+    async fn add(&mut self, document: Document) -> Result<()> {
+        let result = self.append(document);
+        self.remap()?;
+        result
+    }
+
+    async fn add_batch(&mut self, documents: Vec<Document>) -> Result<()> {
+        let mut result = Ok(());
+        for doc in documents {
+            result = self.append(doc);
+            if result.is_err() {
+                break;
+            }
+        }
+        self.remap()?;
+        result
+    }
+
+    async fn get(&self, id: &str) -> Result<Document> {
+        let entry = self
+            .entries
+            .get(id)
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))?;
+        self.read_document(entry)
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<()> {
+        if !self.entries.contains_key(id) {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+
+        debug!("Deleting document {}", id);
+        self.remove(id)?;
+        self.remap()
+    }
+
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize> {
+        let mut ids = Vec::new();
+        for (id, entry) in &self.entries {
+            if filter.matches(&self.read_document(entry)?) {
+                ids.push(id.clone());
+            }
+        }
+
+        debug!("Deleting {} documents matching filter", ids.len());
+
+        for id in &ids {
+            self.remove(id)?;
+        }
+        self.remap()?;
+        Ok(ids.len())
+    }
+
+    async fn exists(&self, id: &str) -> bool {
+        self.entries.contains_key(id)
+    }
+
+    async fn search(&self, embedding: &[f32], top_k: usize) -> Result<Vec<SearchResult>> {
+        self.search_filtered(embedding, &DocumentFilter::new(), top_k)
+            .await
+    }
+
+    async fn search_by_user(
+        &self,
+        embedding: &[f32],
+        user_id: &str,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filter = DocumentFilter::new().with_user_id(user_id);
+        self.search_filtered(embedding, &filter, top_k).await
+    }
+
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        if self.entries.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.validate_embedding(embedding)?;
+
+        let mut scored: Vec<(usize, f32)> = self
+            .candidate_rows(embedding)
+            .into_iter()
+            .filter(|&row| matches!(self.row_ids.get(row), Some(Some(_))))
+            .filter_map(|row| Some((row, cosine_similarity(embedding, self.vector(row)?))))
+            .collect();
+
+        let by_score = |a: &(usize, f32), b: &(usize, f32)| {
+            b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
+        };
+
+        // Without a filter only the top-k rows are ever needed
+        if filter.is_empty() && top_k < scored.len() {
+            scored.select_nth_unstable_by(top_k, by_score);
+            scored.truncate(top_k);
+        }
+        scored.sort_by(by_score);
+
+        let mut results = Vec::new();
+        for (row, score) in scored {
+            if results.len() >= top_k {
+                break;
+            }
+
+            let Some(entry) = self.row_ids[row].as_ref().and_then(|id| self.entries.get(id)) else {
+                continue;
+            };
+            if let Some(ref user_id) = filter.user_id {
+                if entry.user_id.as_deref() != Some(user_id.as_str()) {
+                    continue;
+                }
+            }
+
+            let document = self.read_document(entry)?;
+            if filter.matches(&document) {
+                let rank = results.len();
+                results.push(SearchResult::new(document, score).with_rank(rank));
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn list(&self) -> Result<Vec<Document>> {
+        self.entries
+            .values()
+            .map(|entry| self.read_document(entry))
+            .collect()
+    }
+
+    fn iter(&self) -> DocumentStream<'_> {
+        Box::pin(stream::iter(self.entries.values().filter_map(
+            move |entry| match self.read_document(entry) {
+                Ok(doc) => Some(doc),
+                Err(e) => {
+                    warn!("Skipping unreadable document at byte {}: {}", entry.offset, e);
+                    None
+                }
+            },
+        )))
+    }
+
+    async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        self.entries
+            .values()
+            .filter(|entry| entry.user_id.as_deref() == Some(user_id))
+            .map(|entry| self.read_document(entry))
+            .collect()
+    }
+
+    async fn count(&self) -> usize {
+        self.entries.len()
+    }
+
+    async fn clear(&mut self) -> Result<()> {
+        self.vectors = None;
+        self.documents = None;
+        self.vectors_file.set_len(0)?;
+        self.documents_file.set_len(0)?;
+        remove_if_exists(&self.dir.join(META_FILE))?;
+        remove_if_exists(&self.dir.join(INDEX_FILE))?;
+
+        self.entries.clear();
+        self.row_ids.clear();
+        self.dimension = None;
+        self.embedding_model = None;
+        self.documents_len = 0;
+        self.index = None;
+        Ok(())
+    }
+
+    async fn stats(&self) -> StorageStats {
+        // Documents are read from the mapped files one at a time
+        let documents = self
+            .entries
+            .values()
+            .filter_map(|entry| self.read_document(entry).ok());
+        let mut stats = StorageStats::from_documents(documents, self.dimension);
+
+        // Only the index lives in RAM; vectors and documents are paged in on demand
+        let entries: usize = self
+            .entries
+            .iter()
+            .map(|(id, e)| {
+                id.len() * 2
+                    + size_of::<DiskEntry>()
+                    + e.user_id.as_ref().map_or(0, String::len)
+                    + size_of::<Option<String>>()
+            })
+            .sum();
+        let index = self.index.as_ref().map_or(0, |index| {
+            index.centroids.iter().map(|c| c.len() * size_of::<f32>()).sum::<usize>()
+                + index.lists.iter().map(|l| l.len() * size_of::<usize>()).sum::<usize>()
+        });
+        stats.estimated_memory_bytes = entries + index;
+
+        stats
+    }
+
+    /// Re-embed every document into a sibling directory, then swap it in
+    ///
+    /// Documents are streamed, so memory use stays bounded. Until the swap
+    /// the storage is untouched; a crash during it is finished by
+    /// [`DiskStorage::open`]. An IVF index is rebuilt if one existed.
+    async fn reembed(&mut self, embedder: &dyn Embedder) -> Result<usize> {
+        let dir = fs::canonicalize(&self.dir)?;
+        let staging_dir = sibling(&dir, STAGING_SUFFIX)?;
+        remove_dir_if_exists(&staging_dir)?;
+
+        let mut staging = DiskStorage::open_with_config(&staging_dir, self.config.clone()).await?;
+        let staged = async {
+            let count = reembed_into(&*self, &mut staging, embedder).await?;
+            if self.has_index() {
+                staging.build_index()?;
+            }
+            Ok(count)
+        }
+        .await;
+        drop(staging);
+        let count = match staged {
+            Ok(count) => count,
+            Err(e) => {
+                remove_dir_if_exists(&staging_dir)?;
+                return Err(e);
+            }
+        };
+
+        swap_in(&dir, &staging_dir)?;
+
+        info!("Re-embedded {} documents in {:?}", count, dir);
+        *self = Self::open_with_config(&self.dir, self.config.clone()).await?;
+        Ok(count)
+    }
+}
+
+fn map_file(file: &File) -> Result<Option<Mmap>> {
+    if file.metadata()?.len() == 0 {
+        return Ok(None);
+    }
+    // SAFETY: the files are owned by this storage and only ever appended to
+    // or truncated through it; concurrent external modification is unsupported.
+    Ok(Some(unsafe { Mmap::map(file)? }))
+}
+
+fn read_json<T: serde::de::DeserializeOwned>(path: &Path) -> Result<Option<T>> {
+    if !path.exists() {
+        return Ok(None);
+    }
+    Ok(Some(serde_json::from_slice(&fs::read(path)?)?))
+}
+
+fn write_json<T: Serialize>(path: &Path, value: &T) -> Result<()> {
+    let temp_path = path.with_extension("tmp");
+    fs::write(&temp_path, serde_json::to_vec(value)?)?;
+    fs::rename(&temp_path, path)?;
+    Ok(())
+}
+
+/// Drop the partial data a failed append may have left past `len`
+fn truncate_after_failed_write(file: &File, len: u64) {
+    if let Err(e) = file.set_len(len) {
+        warn!("Could not truncate after a failed write: {}", e);
+    }
+}
+
+fn remove_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn normalized(v: &[f32]) -> Vec<f32> {
+    let norm = v.iter().map(|x| x * x).sum::<f32>().sqrt();
+    if norm == 0.0 {
+        v.to_vec()
+    } else {
+        v.iter().map(|x| x / norm).collect()
+    }
+}
+
+fn nearest_centroid(centroids: &[Vec<f32>], vector: &[f32]) -> usize {
+    centroids
+        .iter()
+        .enumerate()
+        .map(|(i, c)| (i, cosine_similarity(vector, c)))
+        .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neuro_embeddings::EmbeddingModel;
+    use tempfile::tempdir;
+
+    fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
+        Document::with_id(id, content).with_embedding(embedding)
+    }
+
+    struct LengthEmbedder;
+
+    impl Embedder for LengthEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::BgeSmallEnV15
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    /// Unit vector pointing mostly along `axis`
+    fn axis_vector(axis: usize, jitter: f32) -> Vec<f32> {
+        let mut v = vec![jitter; 8];
+        v[axis % 8] = 1.0;
+        v
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_persistence() {
+        let dir = tempdir().unwrap();
+
+        {
+            let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+            storage
+                .add(make_doc("doc1", "Similar", vec![1.0, 0.0, 0.0]).with_user_id("alice"))
+                .await
+                .unwrap();
+            storage
+                .add(make_doc("doc2", "Different", vec![0.0, 1.0, 0.0]))
+                .await
+                .unwrap();
+        }
+
+        let storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.dimension(), Some(3));
+
+        let doc = storage.get("doc1").await.unwrap();
+        assert_eq!(doc.content, "Similar");
+        assert_eq!(doc.embedding, Some(vec![1.0, 0.0, 0.0]));
+
+        let results = storage.search(&[1.0, 0.1, 0.0], 1).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.id, "doc1");
+
+        let stats = storage.stats().await;
+        assert_eq!(stats.unique_users, 1);
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_embedding_model_survives_reopen() {
+        let dir = tempdir().unwrap();
+
+        {
+            let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+            storage
+                .add(make_doc("doc1", "First", vec![1.0, 0.0]).with_embedding_model("model-a"))
+                .await
+                .unwrap();
+        }
+
+        let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.embedding_model(), Some("model-a"));
+
+        let result = storage
+            .add(make_doc("doc2", "Second", vec![0.0, 1.0]).with_embedding_model("model-b"))
+            .await;
+        assert!(matches!(result, Err(StorageError::EmbeddingModelMismatch { .. })));
+        assert_eq!(storage.count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_delete_survives_reopen() {
+        let dir = tempdir().unwrap();
+
+        {
+            let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+            storage
+                .add_batch(vec![
+                    make_doc("doc1", "A", vec![1.0, 0.0]),
+                    make_doc("doc2", "B", vec![0.0, 1.0]),
+                ])
+                .await
+                .unwrap();
+            storage.delete("doc1").await.unwrap();
+            assert!(storage.search(&[1.0, 0.0], 5).await.unwrap().len() == 1);
+        }
+
+        let storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert!(!storage.exists("doc1").await);
+        assert!(storage.exists("doc2").await);
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_ivf_index() {
+        let dir = tempdir().unwrap();
+        let config = DiskIndexConfig::default().with_nlist(4).with_nprobe(1);
+
+        {
+            let mut storage = DiskStorage::open_with_config(dir.path(), config.clone())
+                .await
+                .unwrap();
+            let docs = (0..64)
+                .map(|i| {
+                    let jitter = 0.01 * (i / 8) as f32;
+                    make_doc(&format!("doc{}", i), "text", axis_vector(i, jitter))
+                })
+                .collect();
+            storage.add_batch(docs).await.unwrap();
+            storage.build_index().unwrap();
+            assert!(storage.has_index());
+
+            // Added after the index was built
+            storage
+                .add(make_doc("late", "late", axis_vector(3, 0.5)))
+                .await
+                .unwrap();
+        }
+
+        let storage = DiskStorage::open_with_config(dir.path(), config).await.unwrap();
+        assert!(storage.has_index());
+
+        let results = storage.search(&axis_vector(3, 0.5), 1).await.unwrap();
+        assert_eq!(results[0].document.id, "late");
+
+        let results = storage.search(&axis_vector(5, 0.0), 3).await.unwrap();
+        assert_eq!(results.len(), 3);
+        assert!(results.iter().all(|r| r.score > 0.9));
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_truncates_partial_record() {
+        let dir = tempdir().unwrap();
+
+        {
+            let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+            storage
+                .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+                .await
+                .unwrap();
+        }
+
+        // Simulate a crash in the middle of writing a record
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(dir.path().join(DOCUMENTS_FILE))
+            .unwrap();
+        file.write_all(b"{\"op\":\"put\",\"row\":1,").unwrap();
+
+        let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.count().await, 1);
+
+        storage
+            .add(make_doc("doc2", "B", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+        let storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.get("doc2").await.unwrap().content, "B");
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_failed_record_write_keeps_rows_aligned() {
+        let dir = tempdir().unwrap();
+        let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+
+        // A read-only handle makes the record write fail after the vector
+        let documents_path = dir.path().join(DOCUMENTS_FILE);
+        let writable = std::mem::replace(
+            &mut storage.documents_file,
+            File::open(&documents_path).unwrap(),
+        );
+        assert!(storage
+            .add(make_doc("doc2", "B", vec![0.0, 1.0]))
+            .await
+            .is_err());
+        storage.documents_file = writable;
+
+        storage
+            .add(make_doc("doc3", "C", vec![0.6, 0.8]))
+            .await
+            .unwrap();
+        assert!(!storage.exists("doc2").await);
+
+        let results = storage.search(&[0.6, 0.8], 1).await.unwrap();
+        assert_eq!(results[0].document.id, "doc3");
+        assert_eq!(results[0].document.embedding, Some(vec![0.6, 0.8]));
+
+        let storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        let doc = storage.get("doc3").await.unwrap();
+        assert_eq!(doc.embedding, Some(vec![0.6, 0.8]));
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_failed_delete_keeps_offsets() {
+        let dir = tempdir().unwrap();
+        let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+
+        let documents_path = dir.path().join(DOCUMENTS_FILE);
+        let writable = std::mem::replace(
+            &mut storage.documents_file,
+            File::open(&documents_path).unwrap(),
+        );
+        assert!(storage.delete("doc1").await.is_err());
+        storage.documents_file = writable;
+        assert!(storage.exists("doc1").await);
+
+        storage
+            .add(make_doc("doc2", "B", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+        assert_eq!(storage.get("doc2").await.unwrap().content, "B");
+
+        let storage = DiskStorage::open(dir.path()).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.get("doc1").await.unwrap().content, "A");
+        assert_eq!(storage.get("doc2").await.unwrap().content, "B");
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_reembed() {
+        let dir = tempdir().unwrap();
+        let store = dir.path().join("store");
+        let config = DiskIndexConfig::default().with_nlist(2);
+
+        let mut storage = DiskStorage::open_with_config(&store, config.clone())
+            .await
+            .unwrap();
+        storage
+            .add_batch(vec![
+                make_doc("doc1", "Hi", vec![1.0, 0.0]).with_embedding_model("model-a"),
+                make_doc("doc2", "Hello", vec![0.0, 1.0]).with_embedding_model("model-a"),
+            ])
+            .await
+            .unwrap();
+        storage.build_index().unwrap();
+
+        assert_eq!(storage.reembed(&LengthEmbedder).await.unwrap(), 2);
+        assert_eq!(storage.dimension(), Some(4));
+        assert_eq!(storage.embedding_model(), Some("BGE-small-en-v1.5"));
+        assert!(storage.has_index());
+        assert_eq!(
+            storage.get("doc2").await.unwrap().embedding,
+            Some(vec![5.0, 1.0, 0.0, 0.0])
+        );
+
+        // Only the swapped-in directory is left
+        let names: Vec<_> = fs::read_dir(dir.path())
+            .unwrap()
+            .map(|e| e.unwrap().file_name())
+            .collect();
+        assert_eq!(names, ["store"]);
+
+        let storage = DiskStorage::open_with_config(&store, config).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.dimension(), Some(4));
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_open_finishes_interrupted_swap() {
+        let dir = tempdir().unwrap();
+        let store = dir.path().join("store");
+        let backup = dir.path().join("store.previous");
+
+        {
+            let mut storage = DiskStorage::open(&store).await.unwrap();
+            storage
+                .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+                .await
+                .unwrap();
+        }
+
+        // Crash after the old directory was moved aside
+        fs::rename(&store, &backup).unwrap();
+        let storage = DiskStorage::open(&store).await.unwrap();
+        assert!(storage.exists("doc1").await);
+        assert!(!backup.exists());
+        drop(storage);
+
+        // Crash after the new directory took its place
+        fs::create_dir(&backup).unwrap();
+        let storage = DiskStorage::open(&store).await.unwrap();
+        assert!(storage.exists("doc1").await);
+        assert!(!backup.exists());
+    }
+
+    #[tokio::test]
+    async fn test_disk_storage_filtered_search_and_clear() {
+        let dir = tempdir().unwrap();
+        let mut storage = DiskStorage::open(dir.path()).await.unwrap();
+
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]).with_user_id("alice"))
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("doc2", "B", vec![1.0, 0.1]).with_user_id("bob"))
+            .await
+            .unwrap();
+
+        let results = storage
+            .search_by_user(&[1.0, 0.0], "bob", 5)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.id, "doc2");
+
+        storage.clear().await.unwrap();
+        assert_eq!(storage.count().await, 0);
+        assert_eq!(storage.dimension(), None);
+
+        storage
+            .add(make_doc("doc3", "C", vec![1.0, 0.0, 0.0]))
+            .await
+            .unwrap();
+        assert_eq!(storage.get("doc3").await.unwrap().content, "C");
+    }
+}
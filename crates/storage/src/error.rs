@@ -21,6 +21,14 @@ pub enum StorageError {
     #[error("Embedding dimension mismatch: expected {expected}, got {actual}")]
     DimensionMismatch { expected: usize, actual: usize },
 
+    /// Embedding model mismatch
+    #[error("Embedding model mismatch: expected {expected}, got {actual}")]
+    EmbeddingModelMismatch { expected: String, actual: String },
+
+    /// Embedding generation error
+    #[error("Embedding error: {0}")]
+    Embedding(#[from] neuro_embeddings::EmbeddingError),
+
     /// IO error
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
@@ -36,6 +44,21 @@ pub enum StorageError {
     /// Invalid operation
     #[error("Invalid operation: {0}")]
     InvalidOperation(String),
+
+    /// LanceDB error
+    #[cfg(feature = "lance")]
+    #[error("LanceDB error: {0}")]
+    Lance(#[from] lancedb::Error),
+
+    /// Arrow error
+    #[cfg(feature = "lance")]
+    #[error("Arrow error: {0}")]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    /// SQLite error
+    #[cfg(feature = "sqlite")]
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
 }
 
 /// Result type for storage operations
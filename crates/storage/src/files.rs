@@ -1,27 +1,85 @@
 //! File-based persistent storage implementation
 
 use async_trait::async_trait;
-use std::collections::{HashMap, HashSet};
+use futures::{future, stream, StreamExt};
+use std::collections::HashMap;
+use std::mem::size_of;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, Weak};
+use std::time::Duration;
 use tokio::fs;
+use tokio::sync::Mutex;
+use tokio::task::JoinHandle;
+use tokio::time::MissedTickBehavior;
 use tracing::{debug, info, warn};
 
 use neuro_core::{Document, SearchResult};
+use neuro_embeddings::Embedder;
 use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
 use crate::similarity::top_k_similar;
-use crate::storage::{Storage, StorageStats};
+use crate::storage::{
+    reembed_batch, validate_embedding_model, DocumentStream, Storage, StorageStats,
+    REEMBED_BATCH_SIZE,
+};
+use crate::wal::{WalEntry, WriteAheadLog};
+
+/// When [`FileStorage`] writes its snapshot to disk
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SaveMode {
+    /// Save after every mutation
+    Immediate,
+    /// Only save when [`FileStorage::save`] is called
+    Manual,
+    /// Save at most once per interval from a background task
+    Interval(Duration),
+    /// Save after every `n` mutations
+    EveryNOps(usize),
+}
 
 /// File-based document storage
 ///
 /// Persists documents as JSON files. Each save operation writes
 /// the entire storage to disk for consistency.
+///
+/// When saves are not immediate, every mutation is first appended to a
+/// write-ahead log (`<path>.wal`) so unsaved changes survive a crash. The
+/// log is replayed on startup and truncated after each successful save.
+///
+/// With [`FileStorage::save_every`] or [`FileStorage::save_every_n_ops`]
+/// saves are debounced, and pending changes are flushed when the storage
+/// is dropped.
 pub struct FileStorage {
+    shared: Arc<Shared>,
+    mode: SaveMode,
+    /// Set while an auto-saving batch is in progress (saved once at the end)
+    batching: bool,
+    /// Mutations since the last save (for [`SaveMode::EveryNOps`])
+    pending_ops: usize,
+    /// Background task for [`SaveMode::Interval`]
+    flusher: Option<JoinHandle<()>>,
+}
+
+/// Storage contents shared with the background flush task
+struct Shared {
     path: PathBuf,
+    wal: WriteAheadLog,
+    state: RwLock<FileState>,
+    /// Held while logging a mutation or writing a snapshot, so a snapshot
+    /// never truncates log entries it does not contain
+    io: Mutex<()>,
+    /// Set when the snapshot on disk is behind the in-memory state
+    dirty: AtomicBool,
+}
+
+#[derive(Default)]
+struct FileState {
     documents: HashMap<String, Document>,
     embeddings: Vec<Vec<f32>>,
     id_to_index: HashMap<String, usize>,
     dimension: Option<usize>,
-    auto_save: bool,
+    embedding_model: Option<String>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -37,18 +95,27 @@ impl FileStorage {
     pub async fn new(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
         
+        let wal = WriteAheadLog::for_snapshot(&path);
+
         let mut storage = Self {
-            path,
-            documents: HashMap::new(),
-            embeddings: Vec::new(),
-            id_to_index: HashMap::new(),
-            dimension: None,
-            auto_save: true,
+            shared: Arc::new(Shared {
+                path,
+                wal,
+                state: RwLock::new(FileState::default()),
+                io: Mutex::new(()),
+                dirty: AtomicBool::new(false),
+            }),
+            mode: SaveMode::Immediate,
+            batching: false,
+            pending_ops: 0,
+            flusher: None,
         };
 
-        // Try to load existing data
-        if storage.path.exists() {
-            storage.load().await?;
+        // Load existing snapshot and replay any unsaved mutations
+        let replayed = storage.load().await?;
+        if replayed > 0 {
+            // Compact the log into a fresh snapshot
+            storage.save().await?;
         }
 
         Ok(storage)
@@ -57,74 +124,309 @@ impl FileStorage {
     /// Create with auto-save disabled (must call save() manually)
     pub async fn new_manual_save(path: impl AsRef<Path>) -> Result<Self> {
         let mut storage = Self::new(path).await?;
-        storage.auto_save = false;
+        storage.set_save_mode(SaveMode::Manual);
         Ok(storage)
     }
 
+    /// Save at most once per `interval` from a background task
+    ///
+    /// Must be called from within a Tokio runtime.
+    pub fn save_every(mut self, interval: Duration) -> Self {
+        self.set_save_mode(SaveMode::Interval(interval));
+        self
+    }
+
+    /// Save after every `n` mutations
+    pub fn save_every_n_ops(mut self, n: usize) -> Self {
+        self.set_save_mode(SaveMode::EveryNOps(n));
+        self
+    }
+
     /// Enable or disable auto-save
     pub fn set_auto_save(&mut self, enabled: bool) {
-        self.auto_save = enabled;
+        self.set_save_mode(if enabled {
+            SaveMode::Immediate
+        } else {
+            SaveMode::Manual
+        });
+    }
+
+    /// Change when the snapshot is written to disk
+    ///
+    /// A zero interval or operation count behaves like [`SaveMode::Immediate`].
+    pub fn set_save_mode(&mut self, mode: SaveMode) {
+        let mode = match mode {
+            SaveMode::Interval(interval) if interval.is_zero() => SaveMode::Immediate,
+            SaveMode::EveryNOps(0) => SaveMode::Immediate,
+            mode => mode,
+        };
+
+        if let Some(flusher) = self.flusher.take() {
+            flusher.abort();
+        }
+        if let SaveMode::Interval(interval) = mode {
+            self.flusher = Some(spawn_flusher(Arc::downgrade(&self.shared), interval));
+        }
+
+        self.mode = mode;
+        self.pending_ops = 0;
+    }
+
+    /// Get the current save mode
+    pub fn save_mode(&self) -> SaveMode {
+        self.mode
     }
 
     /// Get the storage file path
     pub fn path(&self) -> &Path {
-        &self.path
+        &self.shared.path
+    }
+
+    /// Get the write-ahead log path
+    pub fn wal_path(&self) -> &Path {
+        self.shared.wal.path()
     }
 
     /// Get the embedding dimension
     pub fn dimension(&self) -> Option<usize> {
-        self.dimension
+        self.shared.read().dimension
+    }
+
+    /// Get the embedding model (if recorded on any document)
+    pub fn embedding_model(&self) -> Option<String> {
+        self.shared.read().embedding_model.clone()
     }
 
     /// Manually save storage to disk
     pub async fn save(&self) -> Result<()> {
-        let data = StorageData {
-            documents: self.documents.values().cloned().collect(),
-            dimension: self.dimension,
+        self.shared.save().await
+    }
+
+    /// Load storage from disk
+    ///
+    /// Reads the snapshot (if any) and replays the write-ahead log on top
+    /// of it. Returns the number of replayed log entries.
+    pub async fn load(&mut self) -> Result<usize> {
+        let path = &self.shared.path;
+
+        let data: Option<StorageData> = if path.exists() {
+            let json = fs::read_to_string(path).await?;
+            Some(serde_json::from_str(&json)?)
+        } else {
+            info!("Storage file does not exist, starting empty: {:?}", path);
+            None
         };
 
-        let json = serde_json::to_string_pretty(&data)?;
+        let entries = self.shared.wal.read_entries().await?;
+        let replayed = entries.len();
 
-        // Write to temp file first, then rename for atomicity
-        let temp_path = self.path.with_extension("tmp");
-        fs::write(&temp_path, &json).await?;
-        fs::rename(&temp_path, &self.path).await?;
+        let mut state = self.shared.write();
+        state.reset();
+
+        if let Some(data) = data {
+            state.dimension = data.dimension;
+
+            for doc in data.documents {
+                state.insert(doc);
+            }
+
+            info!("Loaded {} documents from {:?}", state.documents.len(), path);
+        }
+
+        for entry in entries {
+            state.replay(entry);
+        }
+
+        if replayed > 0 {
+            info!(
+                "Replayed {} write-ahead log entries from {:?}",
+                replayed,
+                self.shared.wal.path()
+            );
+        }
 
-        debug!("Saved {} documents to {:?}", self.documents.len(), self.path);
+        Ok(replayed)
+    }
+
+    /// Whether mutations need to be logged before being applied
+    fn wal_enabled(&self) -> bool {
+        self.mode != SaveMode::Immediate && !self.batching
+    }
+
+    /// Log (if needed) and apply a mutation
+    async fn apply(&self, entry: WalEntry) -> Result<()> {
+        let _io = self.shared.io.lock().await;
+
+        if self.wal_enabled() {
+            self.shared.wal.append(&entry).await?;
+        }
+
+        self.shared.write().replay(entry);
+        self.shared.dirty.store(true, Ordering::SeqCst);
         Ok(())
     }
 
-    /// Load storage from disk
-    pub async fn load(&mut self) -> Result<()> {
-        if !self.path.exists() {
-            info!("Storage file does not exist, starting empty: {:?}", self.path);
+    async fn maybe_save(&mut self) -> Result<()> {
+        if self.batching {
             return Ok(());
         }
 
-        let json = fs::read_to_string(&self.path).await?;
-        let data: StorageData = serde_json::from_str(&json)?;
+        match self.mode {
+            SaveMode::Immediate => self.save().await?,
+            SaveMode::EveryNOps(n) => {
+                self.pending_ops += 1;
+                if self.pending_ops >= n {
+                    self.save().await?;
+                    self.pending_ops = 0;
+                }
+            }
+            SaveMode::Manual | SaveMode::Interval(_) => {}
+        }
+        Ok(())
+    }
+}
 
-        self.documents.clear();
-        self.embeddings.clear();
-        self.id_to_index.clear();
-        self.dimension = data.dimension;
+impl Drop for FileStorage {
+    fn drop(&mut self) {
+        if let Some(flusher) = self.flusher.take() {
+            flusher.abort();
+        }
 
-        for doc in data.documents {
-            if let Some(ref embedding) = doc.embedding {
-                let index = self.embeddings.len();
-                self.embeddings.push(embedding.clone());
-                self.id_to_index.insert(doc.id.clone(), index);
+        let debounced = matches!(self.mode, SaveMode::Interval(_) | SaveMode::EveryNOps(_));
+        if debounced && self.shared.dirty.load(Ordering::SeqCst) {
+            if let Err(e) = self.shared.save_blocking() {
+                // Mutations are still in the write-ahead log
+                warn!("Failed to flush {:?} on drop: {}", self.shared.path, e);
             }
-            self.documents.insert(doc.id.clone(), doc);
         }
+    }
+}
+
+impl Shared {
+    fn read(&self) -> RwLockReadGuard<'_, FileState> {
+        self.state.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, FileState> {
+        self.state.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn snapshot(&self) -> Result<(String, usize)> {
+        let state = self.read();
+        let data = StorageData {
+            documents: state.documents.values().cloned().collect(),
+            dimension: state.dimension,
+        };
+        Ok((serde_json::to_string_pretty(&data)?, data.documents.len()))
+    }
+
+    async fn save(&self) -> Result<()> {
+        let _io = self.io.lock().await;
+        self.dirty.store(false, Ordering::SeqCst);
+
+        let result = async {
+            let (json, count) = self.snapshot()?;
+
+            // Write to temp file first, then rename for atomicity
+            let temp_path = self.path.with_extension("tmp");
+            fs::write(&temp_path, &json).await?;
+            fs::rename(&temp_path, &self.path).await?;
+
+            // Snapshot now contains every logged mutation
+            self.wal.truncate().await?;
+
+            debug!("Saved {} documents to {:?}", count, self.path);
+            Ok(())
+        }
+        .await;
+
+        if result.is_err() {
+            self.dirty.store(true, Ordering::SeqCst);
+        }
+        result
+    }
+
+    /// Synchronous save for use in `Drop`
+    fn save_blocking(&self) -> Result<()> {
+        let Ok(_io) = self.io.try_lock() else {
+            debug!("Save already in progress, leaving changes in the write-ahead log");
+            return Ok(());
+        };
+
+        let (json, count) = self.snapshot()?;
+        let temp_path = self.path.with_extension("tmp");
+        std::fs::write(&temp_path, &json)?;
+        std::fs::rename(&temp_path, &self.path)?;
+
+        self.wal.truncate_blocking()?;
 
-        info!(
-            "Loaded {} documents from {:?}",
-            self.documents.len(),
-            self.path
-        );
+        self.dirty.store(false, Ordering::SeqCst);
+        debug!("Flushed {} documents to {:?}", count, self.path);
         Ok(())
     }
+}
+
+impl FileState {
+    /// Apply a mutation to the in-memory state
+    ///
+    /// Also used to replay logged mutations, so invalid entries are
+    /// skipped rather than rejected.
+    fn replay(&mut self, entry: WalEntry) {
+        match entry {
+            WalEntry::Add { document } => {
+                let Some(embedding) = document.embedding.as_ref() else {
+                    warn!("Skipping WAL entry without embedding: {}", document.id);
+                    return;
+                };
+                if self.documents.contains_key(&document.id) {
+                    return;
+                }
+                if self.validate_embedding(embedding).is_err() {
+                    warn!("Skipping WAL entry with wrong dimension: {}", document.id);
+                    return;
+                }
+                if validate_embedding_model(self.embedding_model.as_deref(), &document).is_err() {
+                    warn!("Skipping WAL entry with wrong embedding model: {}", document.id);
+                    return;
+                }
+                self.insert(*document);
+            }
+            WalEntry::Delete { ids } => {
+                for id in &ids {
+                    self.remove(id);
+                }
+            }
+            WalEntry::Clear => self.reset(),
+        }
+    }
+
+    fn insert(&mut self, document: Document) {
+        if let Some(ref embedding) = document.embedding {
+            if self.dimension.is_none() {
+                self.dimension = Some(embedding.len());
+            }
+            let index = self.embeddings.len();
+            self.embeddings.push(embedding.clone());
+            self.id_to_index.insert(document.id.clone(), index);
+        }
+        if self.embedding_model.is_none() {
+            self.embedding_model = document.embedding_model.clone();
+        }
+        self.documents.insert(document.id.clone(), document);
+    }
+
+    fn remove(&mut self, id: &str) {
+        self.documents.remove(id);
+        self.id_to_index.remove(id);
+    }
+
+    fn reset(&mut self) {
+        self.documents.clear();
+        self.embeddings.clear();
+        self.id_to_index.clear();
+        self.dimension = None;
+        self.embedding_model = None;
+    }
 
     fn validate_embedding(&self, embedding: &[f32]) -> Result<()> {
         if let Some(dim) = self.dimension {
@@ -137,13 +439,29 @@ impl FileStorage {
         }
         Ok(())
     }
+}
 
-    async fn maybe_save(&self) -> Result<()> {
-        if self.auto_save {
-            self.save().await?;
+/// Periodically save the storage while it has unsaved changes
+fn spawn_flusher(shared: Weak<Shared>, interval: Duration) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        // The first tick completes immediately
+        ticker.tick().await;
+
+        loop {
+            ticker.tick().await;
+
+            let Some(shared) = shared.upgrade() else {
+                break;
+            };
+            if shared.dirty.load(Ordering::SeqCst) {
+                if let Err(e) = shared.save().await {
+                    warn!("Background save of {:?} failed: {}", shared.path, e);
+                }
+            }
         }
-        Ok(())
-    }
+    })
 }
 
 #[async_trait]
@@ -154,78 +472,114 @@ impl Storage for FileStorage {
             .as_ref()
             .ok_or_else(|| StorageError::MissingEmbedding(document.id.clone()))?;
 
-        if self.documents.contains_key(&document.id) {
-            return Err(StorageError::AlreadyExists(document.id.clone()));
-        }
+        {
+            let state = self.shared.read();
+            if state.documents.contains_key(&document.id) {
+                return Err(StorageError::AlreadyExists(document.id.clone()));
+            }
 
-        if self.dimension.is_none() {
-            self.dimension = Some(embedding.len());
+            state.validate_embedding(embedding)?;
+            validate_embedding_model(state.embedding_model.as_deref(), &document)?;
         }
-        self.validate_embedding(embedding)?;
 
         debug!("Adding document {} ({} chars)", document.id, document.content.len());
 
-        let index = self.embeddings.len();
-        self.embeddings.push(embedding.clone());
-        self.id_to_index.insert(document.id.clone(), index);
-        self.documents.insert(document.id.clone(), document);
+        self.apply(WalEntry::Add {
+            document: Box::new(document),
+        })
+        .await?;
 
         self.maybe_save().await?;
         Ok(())
     }
 
     async fn add_batch(&mut self, documents: Vec<Document>) -> Result<()> {
-        // Temporarily disable auto-save for batch
-        let was_auto_save = self.auto_save;
-        self.auto_save = false;
+        // Immediate saves are deferred to the end of the batch, so there is
+        // no need to log its entries either
+        let batching = self.mode == SaveMode::Immediate;
+        self.batching = batching;
 
+        let mut result = Ok(());
         for doc in documents {
-            self.add(doc).await?;
+            result = self.add(doc).await;
+            if result.is_err() {
+                break;
+            }
         }
 
-        self.auto_save = was_auto_save;
-        self.maybe_save().await?;
-        Ok(())
+        self.batching = false;
+        if batching {
+            self.save().await?;
+        }
+        result
     }
 
     async fn get(&self, id: &str) -> Result<Document> {
-        self.documents
+        self.shared
+            .read()
+            .documents
             .get(id)
             .cloned()
             .ok_or_else(|| StorageError::NotFound(id.to_string()))
     }
 
     async fn delete(&mut self, id: &str) -> Result<()> {
-        if !self.documents.contains_key(id) {
+        if !self.shared.read().documents.contains_key(id) {
             return Err(StorageError::NotFound(id.to_string()));
         }
 
         debug!("Deleting document {}", id);
 
-        self.documents.remove(id);
-        self.id_to_index.remove(id);
+        self.apply(WalEntry::Delete {
+            ids: vec![id.to_string()],
+        })
+        .await?;
 
         self.maybe_save().await?;
         Ok(())
     }
 
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize> {
+        let ids: Vec<String> = self
+            .shared
+            .read()
+            .documents
+            .values()
+            .filter(|d| filter.matches(d))
+            .map(|d| d.id.clone())
+            .collect();
+
+        debug!("Deleting {} documents matching filter", ids.len());
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        let count = ids.len();
+        self.apply(WalEntry::Delete { ids }).await?;
+
+        self.maybe_save().await?;
+        Ok(count)
+    }
+
     async fn exists(&self, id: &str) -> bool {
-        self.documents.contains_key(id)
+        self.shared.read().documents.contains_key(id)
     }
 
     async fn search(&self, embedding: &[f32], top_k: usize) -> Result<Vec<SearchResult>> {
-        if self.documents.is_empty() {
+        let state = self.shared.read();
+        if state.documents.is_empty() {
             return Ok(Vec::new());
         }
 
-        self.validate_embedding(embedding)?;
+        state.validate_embedding(embedding)?;
 
-        let valid_docs: Vec<(&String, &Vec<f32>)> = self
+        let valid_docs: Vec<(&String, &Vec<f32>)> = state
             .id_to_index
             .iter()
             .filter_map(|(id, &idx)| {
-                if self.documents.contains_key(id) {
-                    Some((id, &self.embeddings[idx]))
+                if state.documents.contains_key(id) {
+                    Some((id, &state.embeddings[idx]))
                 } else {
                     None
                 }
@@ -246,7 +600,7 @@ impl Storage for FileStorage {
             .enumerate()
             .filter_map(|(rank, (idx, score))| {
                 let id = doc_ids.get(idx)?;
-                let document = self.documents.get(*id)?.clone();
+                let document = state.documents.get(*id)?.clone();
                 Some(SearchResult::new(document, score).with_rank(rank))
             })
             .collect();
@@ -260,19 +614,20 @@ impl Storage for FileStorage {
         user_id: &str,
         top_k: usize,
     ) -> Result<Vec<SearchResult>> {
-        if self.documents.is_empty() {
+        let state = self.shared.read();
+        if state.documents.is_empty() {
             return Ok(Vec::new());
         }
 
-        self.validate_embedding(embedding)?;
+        state.validate_embedding(embedding)?;
 
-        let valid_docs: Vec<(&String, &Vec<f32>)> = self
+        let valid_docs: Vec<(&String, &Vec<f32>)> = state
             .id_to_index
             .iter()
             .filter_map(|(id, &idx)| {
-                let doc = self.documents.get(id)?;
+                let doc = state.documents.get(id)?;
                 if doc.user_id.as_deref() == Some(user_id) {
-                    Some((id, &self.embeddings[idx]))
+                    Some((id, &state.embeddings[idx]))
                 } else {
                     None
                 }
@@ -293,7 +648,55 @@ impl Storage for FileStorage {
             .enumerate()
             .filter_map(|(rank, (idx, score))| {
                 let id = doc_ids.get(idx)?;
-                let document = self.documents.get(*id)?.clone();
+                let document = state.documents.get(*id)?.clone();
+                Some(SearchResult::new(document, score).with_rank(rank))
+            })
+            .collect();
+
+        Ok(results)
+    }
+
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let state = self.shared.read();
+        if state.documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        state.validate_embedding(embedding)?;
+
+        let valid_docs: Vec<(&String, &Vec<f32>)> = state
+            .id_to_index
+            .iter()
+            .filter_map(|(id, &idx)| {
+                let doc = state.documents.get(id)?;
+                if filter.matches(doc) {
+                    Some((id, &state.embeddings[idx]))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if valid_docs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let doc_embeddings: Vec<Vec<f32>> = valid_docs.iter().map(|(_, e)| (*e).clone()).collect();
+        let doc_ids: Vec<&String> = valid_docs.iter().map(|(id, _)| *id).collect();
+
+        let top_results = top_k_similar(embedding, &doc_embeddings, top_k);
+
+        let results: Vec<SearchResult> = top_results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(rank, (idx, score))| {
+                let id = doc_ids.get(idx)?;
+                let document = state.documents.get(*id)?.clone();
                 Some(SearchResult::new(document, score).with_rank(rank))
             })
             .collect();
@@ -302,11 +705,21 @@ impl Storage for FileStorage {
     }
 
     async fn list(&self) -> Result<Vec<Document>> {
-        Ok(self.documents.values().cloned().collect())
+        Ok(self.shared.read().documents.values().cloned().collect())
+    }
+
+    fn iter(&self) -> DocumentStream<'_> {
+        // Clone documents lazily without holding the lock across polls
+        let ids: Vec<String> = self.shared.read().documents.keys().cloned().collect();
+        Box::pin(stream::iter(ids).filter_map(move |id| {
+            future::ready(self.shared.read().documents.get(&id).cloned())
+        }))
     }
 
     async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>> {
         Ok(self
+            .shared
+            .read()
             .documents
             .values()
             .filter(|d| d.user_id.as_deref() == Some(user_id))
@@ -315,46 +728,96 @@ impl Storage for FileStorage {
     }
 
     async fn count(&self) -> usize {
-        self.documents.len()
+        self.shared.read().documents.len()
     }
 
     async fn clear(&mut self) -> Result<()> {
-        self.documents.clear();
-        self.embeddings.clear();
-        self.id_to_index.clear();
-        self.dimension = None;
+        self.apply(WalEntry::Clear).await?;
 
         self.maybe_save().await?;
         Ok(())
     }
 
     async fn stats(&self) -> StorageStats {
-        let unique_users: HashSet<&str> = self
-            .documents
-            .values()
-            .filter_map(|d| d.user_id.as_deref())
-            .collect();
+        let state = self.shared.read();
+        let mut stats = StorageStats::from_documents(state.documents.values(), state.dimension);
 
-        let total_content_bytes: usize = self.documents.values().map(|d| d.content.len()).sum();
+        // Search copies of the embeddings plus the id index
+        stats.estimated_memory_bytes += state
+            .embeddings
+            .iter()
+            .map(|e| e.len() * size_of::<f32>() + size_of::<Vec<f32>>())
+            .sum::<usize>()
+            + state
+                .id_to_index
+                .keys()
+                .map(|id| id.len() + size_of::<String>() + size_of::<usize>())
+                .sum::<usize>();
+
+        stats
+    }
 
-        StorageStats {
-            document_count: self.documents.len(),
-            embedding_dimension: self.dimension,
-            total_content_bytes,
-            unique_users: unique_users.len(),
+    /// Re-embed every document into a new state, then save it in one go
+    ///
+    /// The snapshot on disk is replaced atomically, so a failure or crash
+    /// at any point leaves either the old or the new documents.
+    async fn reembed(&mut self, embedder: &dyn Embedder) -> Result<usize> {
+        let mut reembedded = FileState::default();
+        let mut batches = self.iter().chunks(REEMBED_BATCH_SIZE);
+        while let Some(mut batch) = batches.next().await {
+            reembed_batch(&mut batch, embedder)?;
+            for doc in batch {
+                reembedded.insert(doc);
+            }
         }
+        drop(batches);
+
+        let count = reembedded.documents.len();
+        {
+            // Keep logged mutations from interleaving with the swap
+            let _io = self.shared.io.lock().await;
+            *self.shared.write() = reembedded;
+            self.shared.dirty.store(true, Ordering::SeqCst);
+        }
+
+        // Written regardless of the save mode: the log still describes
+        // the old documents
+        self.pending_ops = 0;
+        self.save().await?;
+        Ok(count)
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use neuro_embeddings::EmbeddingModel;
     use tempfile::tempdir;
 
     fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
         Document::with_id(id, content).with_embedding(embedding)
     }
 
+    struct LengthEmbedder;
+
+    impl Embedder for LengthEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::BgeSmallEnV15
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
     #[tokio::test]
     async fn test_file_storage_basic() {
         let dir = tempdir().unwrap();
@@ -413,6 +876,37 @@ mod tests {
         assert_eq!(results[0].document.content, "Similar");
     }
 
+    #[tokio::test]
+    async fn test_file_storage_delete_where_persists() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        {
+            let mut storage = FileStorage::new(&path).await.unwrap();
+            storage
+                .add(
+                    make_doc("doc1", "Keep", vec![1.0, 0.0])
+                        .with_metadata("dir", serde_json::json!("/docs")),
+                )
+                .await
+                .unwrap();
+            storage
+                .add(
+                    make_doc("doc2", "Drop", vec![0.0, 1.0])
+                        .with_metadata("dir", serde_json::json!("/src")),
+                )
+                .await
+                .unwrap();
+
+            let filter = DocumentFilter::new().with_metadata("dir", serde_json::json!("/src"));
+            assert_eq!(storage.delete_where(&filter).await.unwrap(), 1);
+        }
+
+        let storage = FileStorage::new(&path).await.unwrap();
+        assert_eq!(storage.count().await, 1);
+        assert!(storage.exists("doc1").await);
+    }
+
     #[tokio::test]
     async fn test_file_storage_manual_save() {
         let dir = tempdir().unwrap();
@@ -431,4 +925,175 @@ mod tests {
         storage.save().await.unwrap();
         assert!(path.exists());
     }
+
+    #[tokio::test]
+    async fn test_file_storage_wal_replay_after_crash() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        // Simulate a batch workload that is killed before save()
+        {
+            let mut storage = FileStorage::new_manual_save(&path).await.unwrap();
+            storage
+                .add(make_doc("doc1", "First", vec![1.0, 0.0, 0.0]))
+                .await
+                .unwrap();
+            storage
+                .add(make_doc("doc2", "Second", vec![0.0, 1.0, 0.0]))
+                .await
+                .unwrap();
+            storage.delete("doc1").await.unwrap();
+
+            assert!(!path.exists());
+            assert!(storage.wal_path().exists());
+        }
+
+        let storage = FileStorage::new(&path).await.unwrap();
+        assert_eq!(storage.count().await, 1);
+        assert!(storage.exists("doc2").await);
+        assert_eq!(storage.dimension(), Some(3));
+
+        // Replayed entries are compacted into a fresh snapshot
+        assert!(path.exists());
+        assert!(!storage.wal_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_save_truncates_wal() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new_manual_save(&path).await.unwrap();
+        storage
+            .add(make_doc("doc1", "Hello", vec![1.0, 0.0, 0.0]))
+            .await
+            .unwrap();
+        storage.clear().await.unwrap();
+        storage
+            .add(make_doc("doc2", "World", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+        assert!(storage.wal_path().exists());
+
+        storage.save().await.unwrap();
+        assert!(!storage.wal_path().exists());
+
+        let reloaded = FileStorage::new(&path).await.unwrap();
+        assert_eq!(reloaded.count().await, 1);
+        assert_eq!(reloaded.dimension(), Some(2));
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_auto_save_skips_wal() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new(&path).await.unwrap();
+        storage
+            .add_batch(vec![
+                make_doc("doc1", "A", vec![1.0, 0.0]),
+                make_doc("doc2", "B", vec![0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+
+        assert!(path.exists());
+        assert!(!storage.wal_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_save_every_n_ops() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new(&path).await.unwrap().save_every_n_ops(2);
+        assert_eq!(storage.save_mode(), SaveMode::EveryNOps(2));
+
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        assert!(!path.exists());
+        assert!(storage.wal_path().exists());
+
+        storage
+            .add(make_doc("doc2", "B", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+        assert!(path.exists());
+        assert!(!storage.wal_path().exists());
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_save_every_interval() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new(&path)
+            .await
+            .unwrap()
+            .save_every(Duration::from_millis(50));
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        assert!(!path.exists());
+
+        tokio::time::sleep(Duration::from_millis(200)).await;
+        assert!(path.exists());
+        assert!(!storage.wal_path().exists());
+
+        let reloaded = FileStorage::new(&path).await.unwrap();
+        assert_eq!(reloaded.count().await, 1);
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_reembed_replaces_snapshot() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new_manual_save(&path).await.unwrap();
+        storage
+            .add(make_doc("doc1", "Hi", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("doc2", "Hello", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.reembed(&LengthEmbedder).await.unwrap(), 2);
+        assert_eq!(storage.dimension(), Some(4));
+        drop(storage);
+
+        let reloaded = FileStorage::new(&path).await.unwrap();
+        assert_eq!(reloaded.count().await, 2);
+        assert_eq!(reloaded.dimension(), Some(4));
+        let doc = reloaded.get("doc2").await.unwrap();
+        assert_eq!(doc.embedding, Some(vec![5.0, 1.0, 0.0, 0.0]));
+    }
+
+    #[tokio::test]
+    async fn test_file_storage_flush_on_drop() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut storage = FileStorage::new(&path)
+            .await
+            .unwrap()
+            .save_every(Duration::from_secs(3600));
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        let wal_path = storage.wal_path().to_path_buf();
+        assert!(!path.exists());
+
+        drop(storage);
+        assert!(path.exists());
+        assert!(!wal_path.exists());
+
+        let reloaded = FileStorage::new(&path).await.unwrap();
+        assert_eq!(reloaded.count().await, 1);
+    }
 }
@@ -0,0 +1,156 @@
+//! Document filters for search, listing and bulk deletion
+
+use neuro_core::{Document, DocumentSource};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Filter applied to documents during search, listing and deletion
+///
+/// All set conditions must match. An empty filter matches every document.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct DocumentFilter {
+    /// Only match documents owned by this user
+    #[serde(default)]
+    pub user_id: Option<String>,
+    /// Only match documents from this source
+    #[serde(default)]
+    pub source: Option<DocumentSource>,
+    /// Only match documents carrying all of these tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// Only match documents whose metadata contains all of these key/value pairs
+    #[serde(default)]
+    pub metadata: HashMap<String, serde_json::Value>,
+}
+
+impl DocumentFilter {
+    /// Create an empty filter that matches every document
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Restrict to a single user
+    pub fn with_user_id(mut self, user_id: impl Into<String>) -> Self {
+        self.user_id = Some(user_id.into());
+        self
+    }
+
+    /// Restrict to a single document source
+    pub fn with_source(mut self, source: DocumentSource) -> Self {
+        self.source = Some(source);
+        self
+    }
+
+    /// Require a metadata key to equal the given value
+    pub fn with_metadata(mut self, key: impl Into<String>, value: serde_json::Value) -> Self {
+        self.metadata.insert(key.into(), value);
+        self
+    }
+
+    /// Require all of the given tags
+    pub fn with_tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Check whether the filter has no conditions
+    pub fn is_empty(&self) -> bool {
+        self.user_id.is_none()
+            && self.source.is_none()
+            && self.tags.is_empty()
+            && self.metadata.is_empty()
+    }
+
+    /// Check whether a document satisfies the filter
+    pub fn matches(&self, document: &Document) -> bool {
+        if let Some(ref user_id) = self.user_id {
+            if document.user_id.as_deref() != Some(user_id.as_str()) {
+                return false;
+            }
+        }
+
+        if let Some(ref source) = self.source {
+            if &document.source != source {
+                return false;
+            }
+        }
+
+        if !self
+            .metadata
+            .iter()
+            .all(|(key, value)| document.metadata.get(key) == Some(value))
+        {
+            return false;
+        }
+
+        document.has_all_tags(&self.tags)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_empty_filter_matches_all() {
+        let filter = DocumentFilter::new();
+        assert!(filter.is_empty());
+        assert!(filter.matches(&Document::new("anything")));
+    }
+
+    #[test]
+    fn test_filter_by_user_and_tags() {
+        let filter = DocumentFilter::new()
+            .with_user_id("alice")
+            .with_tags(["rust", "docs"]);
+
+        let matching = Document::new("a")
+            .with_user_id("alice")
+            .with_tags(["docs", "rust", "extra"]);
+        let wrong_user = Document::new("b")
+            .with_user_id("bob")
+            .with_tags(["rust", "docs"]);
+        let missing_tag = Document::new("c")
+            .with_user_id("alice")
+            .with_tags(["rust"]);
+
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&wrong_user));
+        assert!(!filter.matches(&missing_tag));
+    }
+
+    #[test]
+    fn test_filter_by_source_and_metadata() {
+        let filter = DocumentFilter::new()
+            .with_source(DocumentSource::File)
+            .with_metadata("dir", serde_json::json!("/src"));
+
+        let matching = Document::new("a")
+            .with_source(DocumentSource::File)
+            .with_metadata("dir", serde_json::json!("/src"));
+        let wrong_source = Document::new("b")
+            .with_source(DocumentSource::Web)
+            .with_metadata("dir", serde_json::json!("/src"));
+        let wrong_value = Document::new("c")
+            .with_source(DocumentSource::File)
+            .with_metadata("dir", serde_json::json!("/docs"));
+
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&wrong_source));
+        assert!(!filter.matches(&wrong_value));
+    }
+
+    #[test]
+    fn test_filter_deserialize() {
+        let json = r#"{"source": "code", "metadata": {"lang": "rust"}}"#;
+        let filter: DocumentFilter = serde_json::from_str(json).unwrap();
+
+        assert_eq!(filter.source, Some(DocumentSource::Code));
+        assert_eq!(filter.metadata.get("lang"), Some(&serde_json::json!("rust")));
+        assert!(filter.user_id.is_none());
+    }
+}
@@ -0,0 +1,158 @@
+//! Sparse scoring and dense + sparse result fusion
+
+use neuro_core::{Document, SearchResult, SparseEmbedding};
+use std::collections::HashMap;
+
+use crate::similarity::cosine_similarity;
+
+/// Default share of the dense score in hybrid search
+pub const DEFAULT_DENSE_WEIGHT: f32 = 0.7;
+
+/// Rank documents by sparse dot product, best first
+///
+/// Documents without a sparse embedding or without any shared term are
+/// skipped.
+pub(crate) fn rank_sparse(
+    query: &SparseEmbedding,
+    documents: impl IntoIterator<Item = Document>,
+    top_k: usize,
+) -> Vec<SearchResult> {
+    let mut scored: Vec<(Document, f32)> = documents
+        .into_iter()
+        .filter_map(|doc| {
+            let score = doc.sparse_embedding.as_ref()?.dot(query);
+            (score > 0.0).then_some((doc, score))
+        })
+        .collect();
+
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored
+        .into_iter()
+        .take(top_k)
+        .enumerate()
+        .map(|(rank, (doc, score))| SearchResult::new(doc, score).with_rank(rank))
+        .collect()
+}
+
+/// Combine dense and sparse results into one ranking
+///
+/// Sparse scores are normalized by the best sparse score so both halves
+/// fall in `[0, 1]`. Documents found only by the sparse search get their
+/// dense score from their stored embedding. The combined score is
+/// `dense_weight * dense + (1 - dense_weight) * sparse`.
+pub(crate) fn fuse(
+    embedding: &[f32],
+    dense: Vec<SearchResult>,
+    sparse: Vec<SearchResult>,
+    dense_weight: f32,
+    top_k: usize,
+) -> Vec<SearchResult> {
+    let dense_weight = dense_weight.clamp(0.0, 1.0);
+    let max_sparse = sparse.iter().map(|r| r.score).fold(0.0f32, f32::max);
+
+    let mut candidates: HashMap<String, (Document, Option<f32>, f32)> = HashMap::new();
+    for result in dense {
+        candidates.insert(
+            result.document.id.clone(),
+            (result.document, Some(result.score), 0.0),
+        );
+    }
+    for result in sparse {
+        let normalized = if max_sparse > 0.0 {
+            result.score / max_sparse
+        } else {
+            0.0
+        };
+        candidates
+            .entry(result.document.id.clone())
+            .or_insert((result.document, None, 0.0))
+            .2 = normalized;
+    }
+
+    let mut scored: Vec<(Document, f32)> = candidates
+        .into_values()
+        .map(|(doc, dense_score, sparse_score)| {
+            let dense_score = dense_score.unwrap_or_else(|| match doc.embedding {
+                Some(ref e) if e.len() == embedding.len() => cosine_similarity(embedding, e),
+                _ => 0.0,
+            });
+            let score = dense_weight * dense_score.max(0.0) + (1.0 - dense_weight) * sparse_score;
+            (doc, score)
+        })
+        .collect();
+
+    scored.sort_by(|a, b| {
+        b.1.partial_cmp(&a.1)
+            .unwrap_or(std::cmp::Ordering::Equal)
+            .then_with(|| a.0.id.cmp(&b.0.id))
+    });
+    scored
+        .into_iter()
+        .take(top_k)
+        .enumerate()
+        .map(|(rank, (doc, score))| SearchResult::new(doc, score).with_rank(rank))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn doc(id: &str, embedding: Vec<f32>, terms: &[(u32, f32)]) -> Document {
+        Document::with_id(id, id)
+            .with_embedding(embedding)
+            .with_sparse_embedding(SparseEmbedding::from_pairs(terms.iter().copied()))
+    }
+
+    #[test]
+    fn test_rank_sparse() {
+        let query = SparseEmbedding::from_pairs([(1, 1.0), (2, 1.0)]);
+        let docs = vec![
+            doc("a", vec![1.0], &[(1, 0.5)]),
+            doc("b", vec![1.0], &[(1, 1.0), (2, 1.0)]),
+            doc("c", vec![1.0], &[(3, 1.0)]),
+            Document::with_id("d", "no sparse"),
+        ];
+
+        let results = rank_sparse(&query, docs, 10);
+        let ids: Vec<&str> = results.iter().map(|r| r.document.id.as_str()).collect();
+        assert_eq!(ids, vec!["b", "a"]);
+        assert_eq!(results[0].score, 2.0);
+        assert_eq!(results[1].rank, 1);
+    }
+
+    #[test]
+    fn test_fuse_promotes_keyword_match() {
+        let embedding = [1.0, 0.0];
+        let semantic = doc("semantic", vec![1.0, 0.0], &[]);
+        let keyword = doc("keyword", vec![0.8, 0.6], &[(7, 1.0)]);
+
+        let dense = vec![SearchResult::new(semantic.clone(), 1.0)];
+        let sparse = vec![SearchResult::new(keyword.clone(), 3.0)];
+
+        // Mostly dense: the exact semantic match wins
+        let results = fuse(&embedding, dense.clone(), sparse.clone(), 0.9, 10);
+        assert_eq!(results[0].document.id, "semantic");
+        // Dense score of the sparse-only hit comes from its embedding
+        assert!((results[1].score - (0.9 * 0.8 + 0.1)).abs() < 1e-5);
+
+        // Balanced: the keyword match (0.5 * 0.8 + 0.5) overtakes (0.5 * 1.0)
+        let results = fuse(&embedding, dense, sparse, 0.5, 10);
+        assert_eq!(results[0].document.id, "keyword");
+        assert_eq!(results[0].rank, 0);
+    }
+
+    #[test]
+    fn test_fuse_merges_duplicates() {
+        let both = doc("both", vec![1.0], &[(1, 1.0)]);
+        let results = fuse(
+            &[1.0],
+            vec![SearchResult::new(both.clone(), 0.6)],
+            vec![SearchResult::new(both, 2.0)],
+            0.5,
+            10,
+        );
+        assert_eq!(results.len(), 1);
+        assert!((results[0].score - 0.8).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,762 @@
+//! LanceDB storage backend
+//!
+//! Stores documents in a Lance table on local disk (or any URI supported by
+//! LanceDB). Lance's columnar format is versioned and memory-mapped, so the
+//! corpus can grow to millions of rows without being loaded into RAM.
+//!
+//! Requires the `lance` feature.
+
+use arrow_array::types::Float32Type;
+use arrow_array::{Array, FixedSizeListArray, Float32Array, RecordBatch, StringArray};
+use arrow_schema::{DataType, Field, Schema, SchemaRef};
+use async_trait::async_trait;
+use futures::{stream, StreamExt, TryStreamExt};
+use lancedb::index::Index;
+use lancedb::query::{ExecutableQuery, QueryBase};
+use lancedb::{Connection, DistanceType, Table};
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tracing::{debug, info, warn};
+
+use neuro_core::{Document, SearchResult};
+use neuro_embeddings::Embedder;
+use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
+use crate::storage::{
+    reembed_into, validate_embedding_model, DocumentStream, Storage, StorageStats,
+};
+use crate::swap::{finish_swap, remove_dir_if_exists, swap_in, STAGING_SUFFIX};
+
+/// Default table name
+pub const DEFAULT_LANCE_TABLE: &str = "documents";
+
+const VECTOR_COLUMN: &str = "vector";
+const DISTANCE_COLUMN: &str = "_distance";
+
+/// LanceDB-backed document storage
+///
+/// The table is created lazily on the first insert, once the embedding
+/// dimension is known. Every write creates a new table version; older
+/// versions can be inspected with [`LanceStorage::checkout`].
+pub struct LanceStorage {
+    uri: String,
+    connection: Connection,
+    table_name: String,
+    table: Option<Table>,
+    dimension: Option<usize>,
+    /// Model recorded on the first stored row
+    embedding_model: Option<String>,
+}
+
+impl LanceStorage {
+    /// Open (or create) a Lance database at the given URI or directory
+    pub async fn open(uri: &str) -> Result<Self> {
+        Self::open_table(uri, DEFAULT_LANCE_TABLE).await
+    }
+
+    /// Open a specific table in a Lance database
+    pub async fn open_table(uri: &str, table_name: &str) -> Result<Self> {
+        if let Some(dir) = local_table_dir(uri, table_name) {
+            finish_swap(&dir)?;
+        }
+        let connection = lancedb::connect(uri).execute().await?;
+
+        let table_names = connection.table_names().execute().await?;
+        let (table, dimension, embedding_model) =
+            if table_names.iter().any(|name| name == table_name) {
+                let table = connection.open_table(table_name).execute().await?;
+                let schema = table.schema().await?;
+                let dimension = vector_dimension(&schema);
+                let embedding_model = first_embedding_model(&table).await?;
+                (Some(table), dimension, embedding_model)
+            } else {
+                (None, None, None)
+            };
+
+        info!(
+            "Opened Lance storage at {} (table: {}, dimension: {:?})",
+            uri, table_name, dimension
+        );
+
+        Ok(Self {
+            uri: uri.to_string(),
+            connection,
+            table_name: table_name.to_string(),
+            table,
+            dimension,
+            embedding_model,
+        })
+    }
+
+    /// Get the embedding dimension
+    pub fn dimension(&self) -> Option<usize> {
+        self.dimension
+    }
+
+    /// Get the embedding model (if recorded)
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+
+    /// Get the current table version
+    pub async fn version(&self) -> Result<Option<u64>> {
+        match self.table {
+            Some(ref table) => Ok(Some(table.version().await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// Check out an older table version (read-only until [`Self::checkout_latest`])
+    pub async fn checkout(&self, version: u64) -> Result<()> {
+        self.table()?.checkout(version).await?;
+        Ok(())
+    }
+
+    /// Return to the latest table version
+    pub async fn checkout_latest(&self) -> Result<()> {
+        self.table()?.checkout_latest().await?;
+        Ok(())
+    }
+
+    /// Build an approximate nearest-neighbour index on the vector column
+    ///
+    /// Small tables are searched exactly without an index; build one once
+    /// the corpus reaches a few hundred thousand rows.
+    pub async fn build_index(&self) -> Result<()> {
+        self.table()?
+            .create_index(&[VECTOR_COLUMN], Index::Auto)
+            .execute()
+            .await?;
+        info!("Built vector index on Lance table {}", self.table_name);
+        Ok(())
+    }
+
+    fn table(&self) -> Result<&Table> {
+        self.table.as_ref().ok_or_else(|| {
+            StorageError::InvalidOperation("Lance table has not been created".to_string())
+        })
+    }
+
+    fn validate_embedding(&self, embedding: &[f32]) -> Result<()> {
+        if let Some(dim) = self.dimension {
+            if embedding.len() != dim {
+                return Err(StorageError::DimensionMismatch {
+                    expected: dim,
+                    actual: embedding.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Run a plain (non-vector) query and decode all matching documents
+    async fn query_documents(&self, predicate: Option<String>) -> Result<Vec<Document>> {
+        let Some(ref table) = self.table else {
+            return Ok(Vec::new());
+        };
+
+        let mut query = table.query();
+        if let Some(predicate) = predicate {
+            query = query.only_if(predicate);
+        }
+
+        let batches: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+        let mut documents = Vec::new();
+        for batch in &batches {
+            documents.extend(decode_batch(batch)?.into_iter().map(|(doc, _)| doc));
+        }
+        Ok(documents)
+    }
+
+    async fn count_where(&self, predicate: Option<String>) -> Result<usize> {
+        match self.table {
+            Some(ref table) => Ok(table.count_rows(predicate).await?),
+            None => Ok(0),
+        }
+    }
+}
+
+#[async_trait]
+impl Storage for LanceStorage {
+    async fn add(&mut self, document: Document) -> Result<()> {
+        self.add_batch(vec![document]).await
+    }
+
+    async fn add_batch(&mut self, documents: Vec<Document>) -> Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        let mut dimension = self.dimension;
+        let mut embedding_model = self.embedding_model.clone();
+        let mut ids = Vec::with_capacity(documents.len());
+        for doc in &documents {
+            let embedding = doc
+                .embedding
+                .as_ref()
+                .ok_or_else(|| StorageError::MissingEmbedding(doc.id.clone()))?;
+
+            let expected = *dimension.get_or_insert(embedding.len());
+            if embedding.len() != expected {
+                return Err(StorageError::DimensionMismatch {
+                    expected,
+                    actual: embedding.len(),
+                });
+            }
+
+            validate_embedding_model(embedding_model.as_deref(), doc)?;
+            if embedding_model.is_none() {
+                embedding_model = doc.embedding_model.clone();
+            }
+
+            if ids.contains(&doc.id) {
+                return Err(StorageError::AlreadyExists(doc.id.clone()));
+            }
+            ids.push(doc.id.clone());
+        }
+
+        let quoted: Vec<String> = ids.iter().map(|id| sql_string(id)).collect();
+        for chunk in quoted.chunks(1000) {
+            let predicate = format!("id IN ({})", chunk.join(", "));
+            if let Some(existing) = self.query_documents(Some(predicate)).await?.first() {
+                return Err(StorageError::AlreadyExists(existing.id.clone()));
+            }
+        }
+
+        let dimension = dimension.unwrap_or_default();
+        let batch = encode_batch(&documents, dimension)?;
+        debug!("Adding {} documents to Lance table {}", documents.len(), self.table_name);
+
+        match self.table {
+            Some(ref table) => {
+                table.add(batch).execute().await?;
+            }
+            None => {
+                let table = self
+                    .connection
+                    .create_table(&self.table_name, batch)
+                    .execute()
+                    .await?;
+                self.table = Some(table);
+            }
+        }
+
+        self.dimension = Some(dimension);
+        self.embedding_model = embedding_model;
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Document> {
+        self.query_documents(Some(format!("id = {}", sql_string(id))))
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| StorageError::NotFound(id.to_string()))
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<()> {
+        if !self.exists(id).await {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+
+        debug!("Deleting document {}", id);
+        self.table()?
+            .delete(format!("id = {}", sql_string(id)).as_str())
+            .await?;
+        Ok(())
+    }
+
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize> {
+        if self.table.is_none() {
+            return Ok(0);
+        }
+
+        // Tags and metadata live in the serialized document, so match those here
+        let ids: Vec<String> = self
+            .query_documents(filter_predicate(filter))
+            .await?
+            .into_iter()
+            .filter(|doc| filter.matches(doc))
+            .map(|doc| sql_string(&doc.id))
+            .collect();
+
+        if ids.is_empty() {
+            return Ok(0);
+        }
+
+        debug!("Deleting {} documents matching filter", ids.len());
+        for chunk in ids.chunks(1000) {
+            self.table()?
+                .delete(format!("id IN ({})", chunk.join(", ")).as_str())
+                .await?;
+        }
+        Ok(ids.len())
+    }
+
+    async fn exists(&self, id: &str) -> bool {
+        self.count_where(Some(format!("id = {}", sql_string(id))))
+            .await
+            .map(|n| n > 0)
+            .unwrap_or(false)
+    }
+
+    async fn search(&self, embedding: &[f32], top_k: usize) -> Result<Vec<SearchResult>> {
+        self.search_filtered(embedding, &DocumentFilter::new(), top_k)
+            .await
+    }
+
+    async fn search_by_user(
+        &self,
+        embedding: &[f32],
+        user_id: &str,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filter = DocumentFilter::new().with_user_id(user_id);
+        self.search_filtered(embedding, &filter, top_k).await
+    }
+
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let Some(ref table) = self.table else {
+            return Ok(Vec::new());
+        };
+        if top_k == 0 {
+            return Ok(Vec::new());
+        }
+
+        self.validate_embedding(embedding)?;
+
+        // Tags and metadata are matched after the vector search, so fetch
+        // every candidate that passes the column predicate in that case
+        let post_filter = !filter.tags.is_empty() || !filter.metadata.is_empty();
+        let predicate = filter_predicate(filter);
+        let limit = if post_filter {
+            self.count_where(predicate.clone()).await?.max(1)
+        } else {
+            top_k
+        };
+
+        let mut query = table
+            .query()
+            .nearest_to(embedding)?
+            .distance_type(DistanceType::Cosine)
+            .limit(limit);
+        if let Some(predicate) = predicate {
+            query = query.only_if(predicate);
+        }
+
+        let batches: Vec<RecordBatch> = query.execute().await?.try_collect().await?;
+        let mut scored = Vec::new();
+        for batch in &batches {
+            scored.extend(decode_batch(batch)?);
+        }
+
+        // Cosine distance is 1 - similarity
+        scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        Ok(scored
+            .into_iter()
+            .filter(|(doc, _)| filter.matches(doc))
+            .take(top_k)
+            .enumerate()
+            .map(|(rank, (doc, distance))| {
+                SearchResult::new(doc, 1.0 - distance.unwrap_or(1.0)).with_rank(rank)
+            })
+            .collect())
+    }
+
+    async fn list(&self) -> Result<Vec<Document>> {
+        self.query_documents(None).await
+    }
+
+    fn iter(&self) -> DocumentStream<'_> {
+        let Some(table) = self.table.clone() else {
+            return Box::pin(stream::empty());
+        };
+
+        let batches = stream::once(async move { table.query().execute().await })
+            .try_flatten()
+            .filter_map(|batch| async move {
+                match batch.map_err(StorageError::from).and_then(|b| decode_batch(&b)) {
+                    Ok(docs) => Some(stream::iter(docs.into_iter().map(|(doc, _)| doc))),
+                    Err(e) => {
+                        warn!("Skipping unreadable Lance batch: {}", e);
+                        None
+                    }
+                }
+            });
+
+        Box::pin(batches.flatten())
+    }
+
+    async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        self.query_documents(Some(format!("user_id = {}", sql_string(user_id))))
+            .await
+    }
+
+    async fn count(&self) -> usize {
+        self.count_where(None).await.unwrap_or(0)
+    }
+
+    async fn clear(&mut self) -> Result<()> {
+        if self.table.take().is_some() {
+            self.connection.drop_table(&self.table_name, &[]).await?;
+        }
+        self.dimension = None;
+        self.embedding_model = None;
+        Ok(())
+    }
+
+    async fn stats(&self) -> StorageStats {
+        let documents = self.iter().collect::<Vec<_>>().await;
+        let mut stats = StorageStats::from_documents(&documents, self.dimension);
+
+        // Lance memory-maps its data files; only query buffers live in RAM
+        stats.estimated_memory_bytes = 0;
+        stats
+    }
+
+    /// Re-embed every row into a staging table, then swap its directory in
+    ///
+    /// Rows are streamed, so memory use stays bounded, and the live table
+    /// is untouched until the staging table is complete. Only databases on
+    /// the local filesystem can be re-embedded this way.
+    async fn reembed(&mut self, embedder: &dyn Embedder) -> Result<usize> {
+        let Some(dir) = local_table_dir(&self.uri, &self.table_name) else {
+            return Err(StorageError::InvalidOperation(format!(
+                "Re-embedding needs a Lance database on the local filesystem, not {}",
+                self.uri
+            )));
+        };
+        let Some(ref table) = self.table else {
+            return Ok(0);
+        };
+        let indexed = !table.list_indices().await?.is_empty();
+
+        let staging_name = format!("{}_{}", self.table_name, STAGING_SUFFIX);
+        let staging_dir = table_dir(&dir, &staging_name);
+        remove_dir_if_exists(&staging_dir)?;
+
+        let mut staging = LanceStorage::open_table(&self.uri, &staging_name).await?;
+        let staged = async {
+            let count = reembed_into(&*self, &mut staging, embedder).await?;
+            if indexed && count > 0 {
+                staging.build_index().await?;
+            }
+            Ok(count)
+        }
+        .await;
+        drop(staging);
+        let count = match staged {
+            Ok(count) => count,
+            Err(e) => {
+                remove_dir_if_exists(&staging_dir)?;
+                return Err(e);
+            }
+        };
+
+        // Release the live table before its directory moves
+        self.table = None;
+        let swapped = swap_in(&dir, &staging_dir);
+        *self = Self::open_table(&self.uri, &self.table_name).await?;
+        swapped?;
+
+        info!(
+            "Re-embedded {} documents in Lance table {}",
+            count, self.table_name
+        );
+        Ok(count)
+    }
+}
+
+/// Directory holding `table_name` of a database on the local filesystem
+fn local_table_dir(uri: &str, table_name: &str) -> Option<PathBuf> {
+    let path = match uri.split_once("://") {
+        Some(("file", path)) => path,
+        Some(_) => return None,
+        None => uri,
+    };
+    Some(Path::new(path).join(format!("{}.lance", table_name)))
+}
+
+/// Directory of `table_name` next to the table stored in `dir`
+fn table_dir(dir: &Path, table_name: &str) -> PathBuf {
+    dir.with_file_name(format!("{}.lance", table_name))
+}
+
+/// Embedding model recorded on the first row of a table
+async fn first_embedding_model(table: &Table) -> Result<Option<String>> {
+    let batches: Vec<RecordBatch> = table.query().limit(1).execute().await?.try_collect().await?;
+    for batch in &batches {
+        if let Some((doc, _)) = decode_batch(batch)?.into_iter().next() {
+            return Ok(doc.embedding_model);
+        }
+    }
+    Ok(None)
+}
+
+/// Arrow schema for a table with the given embedding dimension
+fn schema(dimension: usize) -> SchemaRef {
+    Arc::new(Schema::new(vec![
+        Field::new("id", DataType::Utf8, false),
+        Field::new("user_id", DataType::Utf8, true),
+        Field::new("source", DataType::Utf8, false),
+        Field::new("document", DataType::Utf8, false),
+        Field::new(
+            VECTOR_COLUMN,
+            DataType::FixedSizeList(
+                Arc::new(Field::new("item", DataType::Float32, true)),
+                dimension as i32,
+            ),
+            false,
+        ),
+    ]))
+}
+
+fn vector_dimension(schema: &Schema) -> Option<usize> {
+    match schema.field_with_name(VECTOR_COLUMN).ok()?.data_type() {
+        DataType::FixedSizeList(_, size) => Some(*size as usize),
+        _ => None,
+    }
+}
+
+/// Encode documents into a record batch
+///
+/// Filterable fields get their own columns; the full document (minus the
+/// embedding) is stored as JSON so new fields need no schema migration.
+fn encode_batch(documents: &[Document], dimension: usize) -> Result<RecordBatch> {
+    let mut ids = Vec::with_capacity(documents.len());
+    let mut user_ids = Vec::with_capacity(documents.len());
+    let mut sources = Vec::with_capacity(documents.len());
+    let mut payloads = Vec::with_capacity(documents.len());
+
+    for doc in documents {
+        let mut stripped = doc.clone();
+        stripped.embedding = None;
+
+        ids.push(doc.id.clone());
+        user_ids.push(doc.user_id.clone());
+        sources.push(doc.source.to_string());
+        payloads.push(serde_json::to_string(&stripped)?);
+    }
+
+    let vectors = FixedSizeListArray::from_iter_primitive::<Float32Type, _, _>(
+        documents.iter().map(|doc| {
+            doc.embedding
+                .as_ref()
+                .map(|e| e.iter().copied().map(Some).collect::<Vec<_>>())
+        }),
+        dimension as i32,
+    );
+
+    Ok(RecordBatch::try_new(
+        schema(dimension),
+        vec![
+            Arc::new(StringArray::from(ids)),
+            Arc::new(StringArray::from(user_ids)),
+            Arc::new(StringArray::from(sources)),
+            Arc::new(StringArray::from(payloads)),
+            Arc::new(vectors),
+        ],
+    )?)
+}
+
+/// Decode documents (with embeddings) and distances from a record batch
+fn decode_batch(batch: &RecordBatch) -> Result<Vec<(Document, Option<f32>)>> {
+    let column = |name: &str| {
+        batch
+            .column_by_name(name)
+            .ok_or_else(|| StorageError::InvalidOperation(format!("Missing column {}", name)))
+    };
+
+    let payloads = column("document")?
+        .as_any()
+        .downcast_ref::<StringArray>()
+        .ok_or_else(|| StorageError::InvalidOperation("Invalid document column".into()))?;
+    let vectors = column(VECTOR_COLUMN)?
+        .as_any()
+        .downcast_ref::<FixedSizeListArray>()
+        .ok_or_else(|| StorageError::InvalidOperation("Invalid vector column".into()))?;
+    let distances = batch
+        .column_by_name(DISTANCE_COLUMN)
+        .and_then(|c| c.as_any().downcast_ref::<Float32Array>());
+
+    let mut documents = Vec::with_capacity(batch.num_rows());
+    for row in 0..batch.num_rows() {
+        let mut doc: Document = serde_json::from_str(payloads.value(row))?;
+
+        let vector = vectors.value(row);
+        doc.embedding = vector
+            .as_any()
+            .downcast_ref::<Float32Array>()
+            .map(|v| v.values().to_vec());
+
+        let distance = distances.filter(|d| d.is_valid(row)).map(|d| d.value(row));
+        documents.push((doc, distance));
+    }
+
+    Ok(documents)
+}
+
+/// SQL predicate for the columnar part of a filter
+fn filter_predicate(filter: &DocumentFilter) -> Option<String> {
+    let mut clauses = Vec::new();
+    if let Some(ref user_id) = filter.user_id {
+        clauses.push(format!("user_id = {}", sql_string(user_id)));
+    }
+    if let Some(ref source) = filter.source {
+        clauses.push(format!("source = {}", sql_string(&source.to_string())));
+    }
+
+    if clauses.is_empty() {
+        None
+    } else {
+        Some(clauses.join(" AND "))
+    }
+}
+
+/// Quote a string literal for a Lance SQL predicate
+fn sql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neuro_core::DocumentSource;
+    use neuro_embeddings::EmbeddingModel;
+    use tempfile::tempdir;
+
+    fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
+        Document::with_id(id, content).with_embedding(embedding)
+    }
+
+    #[test]
+    fn test_filter_predicate() {
+        let filter = DocumentFilter::new()
+            .with_user_id("o'brien")
+            .with_source(DocumentSource::Web);
+        assert_eq!(
+            filter_predicate(&filter).unwrap(),
+            "user_id = 'o''brien' AND source = 'web'"
+        );
+        assert!(filter_predicate(&DocumentFilter::new()).is_none());
+    }
+
+    #[test]
+    fn test_encode_decode_roundtrip() {
+        let docs = vec![
+            make_doc("doc1", "Hello", vec![1.0, 0.0]).with_tags(["a"]),
+            make_doc("doc2", "World", vec![0.0, 1.0]).with_user_id("alice"),
+        ];
+
+        let batch = encode_batch(&docs, 2).unwrap();
+        let decoded = decode_batch(&batch).unwrap();
+
+        assert_eq!(decoded.len(), 2);
+        assert_eq!(decoded[0].0.tags, vec!["a".to_string()]);
+        assert_eq!(decoded[1].0.embedding, Some(vec![0.0, 1.0]));
+        assert!(decoded[0].1.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lance_storage_roundtrip() {
+        let dir = tempdir().unwrap();
+        let uri = dir.path().to_str().unwrap();
+
+        {
+            let mut storage = LanceStorage::open(uri).await.unwrap();
+            storage
+                .add(make_doc("doc1", "Similar", vec![1.0, 0.0, 0.0]).with_user_id("alice"))
+                .await
+                .unwrap();
+            storage
+                .add(make_doc("doc2", "Different", vec![0.0, 1.0, 0.0]))
+                .await
+                .unwrap();
+            assert!(storage.add(make_doc("doc1", "Dup", vec![1.0, 0.0, 0.0])).await.is_err());
+        }
+
+        let mut storage = LanceStorage::open(uri).await.unwrap();
+        assert_eq!(storage.dimension(), Some(3));
+        assert_eq!(storage.count().await, 2);
+
+        let results = storage.search(&[1.0, 0.1, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].document.id, "doc1");
+        assert!(results[0].score > 0.9);
+
+        let results = storage
+            .search_by_user(&[0.0, 1.0, 0.0], "alice", 5)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        assert_eq!(storage.delete_by_user("alice").await.unwrap(), 1);
+        assert!(!storage.exists("doc1").await);
+        assert_eq!(storage.get("doc2").await.unwrap().content, "Different");
+    }
+
+    struct LengthEmbedder;
+
+    impl Embedder for LengthEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::BgeSmallEnV15
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    #[test]
+    fn test_local_table_dir() {
+        assert_eq!(
+            local_table_dir("/data/lance", "documents"),
+            Some(PathBuf::from("/data/lance/documents.lance"))
+        );
+        assert_eq!(
+            local_table_dir("file:///data/lance", "documents"),
+            Some(PathBuf::from("/data/lance/documents.lance"))
+        );
+        assert!(local_table_dir("s3://bucket/lance", "documents").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_lance_storage_reembed() {
+        let dir = tempdir().unwrap();
+        let uri = dir.path().to_str().unwrap();
+
+        let mut storage = LanceStorage::open(uri).await.unwrap();
+        storage
+            .add_batch(vec![
+                make_doc("doc1", "Hi", vec![1.0, 0.0]),
+                make_doc("doc2", "Hello", vec![0.0, 1.0]),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(storage.reembed(&LengthEmbedder).await.unwrap(), 2);
+        assert_eq!(storage.dimension(), Some(4));
+        assert_eq!(
+            storage.get("doc2").await.unwrap().embedding,
+            Some(vec![5.0, 1.0, 0.0, 0.0])
+        );
+
+        let storage = LanceStorage::open(uri).await.unwrap();
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.dimension(), Some(4));
+        assert!(!dir.path().join("documents_reembed.lance").exists());
+    }
+}
@@ -5,6 +5,9 @@
 //! This crate provides vector storage with similarity search capabilities:
 //! - [`MemoryStorage`] - In-memory storage (fast, non-persistent)
 //! - [`FileStorage`] - JSON file-based storage (persistent)
+//! - [`DiskStorage`] - Memory-mapped on-disk storage with IVF index (larger-than-RAM)
+//! - `LanceStorage` - LanceDB columnar storage (requires the `lance` feature)
+//! - `SqliteStorage` - Single-file SQLite storage (requires the `sqlite` feature)
 //!
 //! ## Example
 //!
@@ -28,16 +31,34 @@
 mod storage;
 mod memory;
 mod files;
+mod disk;
+#[cfg(feature = "lance")]
+mod lance;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+mod filter;
+mod hybrid;
 mod similarity;
+mod migrate;
+mod swap;
+mod wal;
 mod error;
 
-pub use storage::Storage;
+pub use storage::{DocumentStream, Storage, StorageStats};
 pub use memory::MemoryStorage;
-pub use files::FileStorage;
-pub use similarity::cosine_similarity;
+pub use files::{FileStorage, SaveMode};
+pub use disk::{DiskIndexConfig, DiskStorage};
+#[cfg(feature = "lance")]
+pub use lance::{LanceStorage, DEFAULT_LANCE_TABLE};
+#[cfg(feature = "sqlite")]
+pub use sqlite::SqliteStorage;
+pub use filter::DocumentFilter;
+pub use hybrid::DEFAULT_DENSE_WEIGHT;
+pub use similarity::{cosine_similarity, cosine_similarity_scalar, simd_backend};
+pub use migrate::{migrate, MigrationReport, DEFAULT_MIGRATION_BATCH_SIZE};
 pub use error::{StorageError, Result};
 
 /// Re-export commonly used types
 pub mod prelude {
-    pub use crate::{Storage, MemoryStorage, FileStorage, StorageError, Result};
+    pub use crate::{Storage, MemoryStorage, FileStorage, DocumentFilter, StorageError, Result};
 }
@@ -1,13 +1,16 @@
 //! In-memory storage implementation
 
 use async_trait::async_trait;
-use std::collections::{HashMap, HashSet};
+use futures::stream;
+use std::collections::HashMap;
+use std::mem::size_of;
 use tracing::debug;
 
 use neuro_core::{Document, SearchResult};
 use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
 use crate::similarity::top_k_similar;
-use crate::storage::{Storage, StorageStats};
+use crate::storage::{validate_embedding_model, DocumentStream, Storage, StorageStats};
 
 /// In-memory document storage
 ///
@@ -17,6 +20,7 @@ pub struct MemoryStorage {
     embeddings: Vec<Vec<f32>>,
     id_to_index: HashMap<String, usize>,
     dimension: Option<usize>,
+    embedding_model: Option<String>,
 }
 
 impl MemoryStorage {
@@ -27,6 +31,7 @@ impl MemoryStorage {
             embeddings: Vec::new(),
             id_to_index: HashMap::new(),
             dimension: None,
+            embedding_model: None,
         }
     }
 
@@ -37,6 +42,7 @@ impl MemoryStorage {
             embeddings: Vec::with_capacity(capacity),
             id_to_index: HashMap::with_capacity(capacity),
             dimension: None,
+            embedding_model: None,
         }
     }
 
@@ -45,6 +51,16 @@ impl MemoryStorage {
         self.dimension
     }
 
+    /// Get the embedding model (if recorded on any document)
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+
+    /// Take the stored documents, consuming the storage
+    pub(crate) fn into_documents(self) -> Vec<Document> {
+        self.documents.into_values().collect()
+    }
+
     fn validate_embedding(&self, embedding: &[f32]) -> Result<()> {
         if let Some(dim) = self.dimension {
             if embedding.len() != dim {
@@ -81,6 +97,10 @@ impl Storage for MemoryStorage {
             self.dimension = Some(embedding.len());
         }
         self.validate_embedding(embedding)?;
+        validate_embedding_model(self.embedding_model.as_deref(), &document)?;
+        if self.embedding_model.is_none() {
+            self.embedding_model = document.embedding_model.clone();
+        }
 
         debug!("Adding document {} ({} chars)", document.id, document.content.len());
 
@@ -115,6 +135,23 @@ impl Storage for MemoryStorage {
         Ok(())
     }
 
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize> {
+        let ids: Vec<String> = self
+            .documents
+            .values()
+            .filter(|d| filter.matches(d))
+            .map(|d| d.id.clone())
+            .collect();
+
+        debug!("Deleting {} documents matching filter", ids.len());
+
+        for id in &ids {
+            self.documents.remove(id);
+            self.id_to_index.remove(id);
+        }
+        Ok(ids.len())
+    }
+
     async fn exists(&self, id: &str) -> bool {
         self.documents.contains_key(id)
     }
@@ -209,10 +246,61 @@ impl Storage for MemoryStorage {
         Ok(results)
     }
 
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        if self.documents.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        self.validate_embedding(embedding)?;
+
+        let valid_docs: Vec<(&String, &Vec<f32>)> = self
+            .id_to_index
+            .iter()
+            .filter_map(|(id, &idx)| {
+                let doc = self.documents.get(id)?;
+                if filter.matches(doc) {
+                    Some((id, &self.embeddings[idx]))
+                } else {
+                    None
+                }
+            })
+            .collect();
+
+        if valid_docs.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let doc_embeddings: Vec<Vec<f32>> = valid_docs.iter().map(|(_, e)| (*e).clone()).collect();
+        let doc_ids: Vec<&String> = valid_docs.iter().map(|(id, _)| *id).collect();
+
+        let top_results = top_k_similar(embedding, &doc_embeddings, top_k);
+
+        let results: Vec<SearchResult> = top_results
+            .into_iter()
+            .enumerate()
+            .filter_map(|(rank, (idx, score))| {
+                let id = doc_ids.get(idx)?;
+                let document = self.documents.get(*id)?.clone();
+                Some(SearchResult::new(document, score).with_rank(rank))
+            })
+            .collect();
+
+        Ok(results)
+    }
+
     async fn list(&self) -> Result<Vec<Document>> {
         Ok(self.documents.values().cloned().collect())
     }
 
+    fn iter(&self) -> DocumentStream<'_> {
+        Box::pin(stream::iter(self.documents.values().cloned()))
+    }
+
     async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>> {
         Ok(self
             .documents
@@ -231,30 +319,34 @@ impl Storage for MemoryStorage {
         self.embeddings.clear();
         self.id_to_index.clear();
         self.dimension = None;
+        self.embedding_model = None;
         Ok(())
     }
 
     async fn stats(&self) -> StorageStats {
-        let unique_users: HashSet<&str> = self
-            .documents
-            .values()
-            .filter_map(|d| d.user_id.as_deref())
-            .collect();
-
-        let total_content_bytes: usize = self.documents.values().map(|d| d.content.len()).sum();
+        let mut stats = StorageStats::from_documents(self.documents.values(), self.dimension);
 
-        StorageStats {
-            document_count: self.documents.len(),
-            embedding_dimension: self.dimension,
-            total_content_bytes,
-            unique_users: unique_users.len(),
-        }
+        // Search copies of the embeddings plus the id index
+        stats.estimated_memory_bytes += self
+            .embeddings
+            .iter()
+            .map(|e| e.len() * size_of::<f32>() + size_of::<Vec<f32>>())
+            .sum::<usize>()
+            + self
+                .id_to_index
+                .keys()
+                .map(|id| id.len() + size_of::<String>() + size_of::<usize>())
+                .sum::<usize>();
+
+        stats
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use neuro_core::DocumentSource;
+    use neuro_embeddings::{Embedder, EmbeddingModel};
 
     fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
         Document::with_id(id, content).with_embedding(embedding)
@@ -345,6 +437,121 @@ mod tests {
         assert_eq!(results[0].document.content, "User A doc");
     }
 
+    #[tokio::test]
+    async fn test_search_by_tags() {
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(
+                make_doc("doc1", "Rust async", vec![1.0, 0.0, 0.0])
+                    .with_tags(["rust", "async"]),
+            )
+            .await
+            .unwrap();
+        storage
+            .add(
+                make_doc("doc2", "Rust sync", vec![0.9, 0.1, 0.0])
+                    .with_tags(["rust"]),
+            )
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("doc3", "Untagged", vec![1.0, 0.0, 0.0]))
+            .await
+            .unwrap();
+
+        let tags = vec!["rust".to_string()];
+        let results = storage
+            .search_by_tags(&[1.0, 0.0, 0.0], &tags, 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 2);
+
+        let tags = vec!["rust".to_string(), "async".to_string()];
+        let results = storage
+            .search_by_tags(&[1.0, 0.0, 0.0], &tags, 10)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.id, "doc1");
+
+        let listed = storage.list_by_tags(&tags).await.unwrap();
+        assert_eq!(listed.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_search_by_source() {
+        use neuro_core::DocumentSource;
+
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(make_doc("code", "fn main", vec![1.0, 0.0]).with_source(DocumentSource::Code))
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("web", "Article", vec![1.0, 0.1]).with_source(DocumentSource::Web))
+            .await
+            .unwrap();
+
+        let results = storage
+            .search_by_source(&[1.0, 0.1], DocumentSource::Code, 5)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.id, "code");
+    }
+
+    #[tokio::test]
+    async fn test_search_filtered_by_user_and_tags() {
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(
+                make_doc("doc1", "A", vec![1.0, 0.0, 0.0])
+                    .with_user_id("user_a")
+                    .with_tag("notes"),
+            )
+            .await
+            .unwrap();
+        storage
+            .add(
+                make_doc("doc2", "B", vec![1.0, 0.0, 0.0])
+                    .with_user_id("user_b")
+                    .with_tag("notes"),
+            )
+            .await
+            .unwrap();
+
+        let filter = DocumentFilter::new()
+            .with_user_id("user_b")
+            .with_tags(["notes"]);
+        let results = storage
+            .search_filtered(&[1.0, 0.0, 0.0], &filter, 10)
+            .await
+            .unwrap();
+
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].document.id, "doc2");
+    }
+
+    #[tokio::test]
+    async fn test_iter_streams_all_documents() {
+        use futures::StreamExt;
+
+        let mut storage = MemoryStorage::new();
+        for i in 0..10 {
+            storage
+                .add(make_doc(&format!("doc{}", i), "Hello", vec![i as f32, 1.0]))
+                .await
+                .unwrap();
+        }
+        storage.delete("doc3").await.unwrap();
+
+        let mut ids: Vec<String> = storage.iter().map(|d| d.id).collect().await;
+        ids.sort();
+
+        assert_eq!(ids.len(), 9);
+        assert!(!ids.contains(&"doc3".to_string()));
+    }
+
     #[tokio::test]
     async fn test_delete() {
         let mut storage = MemoryStorage::new();
@@ -358,6 +565,37 @@ mod tests {
         assert!(!storage.exists("doc1").await);
     }
 
+    #[tokio::test]
+    async fn test_bulk_delete() {
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(make_doc("doc1", "A", vec![1.0, 0.0]).with_user_id("user_a"))
+            .await
+            .unwrap();
+        storage
+            .add(
+                make_doc("doc2", "B", vec![0.0, 1.0])
+                    .with_user_id("user_b")
+                    .with_source(DocumentSource::File),
+            )
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("doc3", "C", vec![1.0, 1.0]).with_source(DocumentSource::File))
+            .await
+            .unwrap();
+
+        assert_eq!(storage.delete_by_user("user_a").await.unwrap(), 1);
+        assert!(!storage.exists("doc1").await);
+
+        assert_eq!(storage.delete_by_source(DocumentSource::File).await.unwrap(), 2);
+        assert_eq!(storage.count().await, 0);
+
+        // Deleted documents must not show up in search
+        let results = storage.search(&[1.0, 0.0], 10).await.unwrap();
+        assert!(results.is_empty());
+    }
+
     #[tokio::test]
     async fn test_stats() {
         let mut storage = MemoryStorage::new();
@@ -381,4 +619,146 @@ mod tests {
         assert_eq!(stats.embedding_dimension, Some(3));
         assert_eq!(stats.unique_users, 2);
     }
+
+    #[tokio::test]
+    async fn test_stats_breakdowns() {
+        use neuro_core::DocumentSource;
+
+        let mut storage = MemoryStorage::new();
+        let mut old = make_doc("old", "First", vec![3.0, 4.0])
+            .with_user_id("alice")
+            .with_source(DocumentSource::File);
+        old.created_at = "2024-01-01T00:00:00Z".parse().unwrap();
+
+        storage.add(old).await.unwrap();
+        storage
+            .add(make_doc("new", "Second", vec![1.0, 0.0]).with_user_id("alice"))
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("anon", "Third", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+
+        let stats = storage.stats().await;
+        assert_eq!(stats.documents_by_source.get(&DocumentSource::File), Some(&1));
+        assert_eq!(stats.documents_by_source.get(&DocumentSource::Manual), Some(&2));
+        assert_eq!(stats.documents_by_user.get("alice"), Some(&2));
+        assert_eq!(stats.unique_users, 1);
+        assert!((stats.average_embedding_norm.unwrap() - 7.0 / 3.0).abs() < 1e-5);
+        assert_eq!(
+            stats.oldest_document.unwrap().to_rfc3339(),
+            "2024-01-01T00:00:00+00:00"
+        );
+        assert!(stats.newest_document > stats.oldest_document);
+        assert!(stats.estimated_memory_bytes > stats.total_content_bytes);
+
+        let json = serde_json::to_value(&stats).unwrap();
+        assert_eq!(json["documents_by_source"]["file"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_embedding_model_mismatch() {
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(make_doc("doc1", "First", vec![1.0, 0.0]).with_embedding_model("model-a"))
+            .await
+            .unwrap();
+        // Documents without a recorded model are still accepted
+        storage
+            .add(make_doc("doc2", "Second", vec![0.0, 1.0]))
+            .await
+            .unwrap();
+
+        let result = storage
+            .add(make_doc("doc3", "Third", vec![1.0, 1.0]).with_embedding_model("model-b"))
+            .await;
+        assert!(matches!(result, Err(StorageError::EmbeddingModelMismatch { .. })));
+        assert_eq!(storage.embedding_model(), Some("model-a"));
+        assert_eq!(storage.stats().await.embedding_model.as_deref(), Some("model-a"));
+    }
+
+    /// Embeds text as `[length, 1.0, 0.0, 0.0]`
+    struct LengthEmbedder;
+
+    impl Embedder for LengthEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::BgeSmallEnV15
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_search_hybrid() {
+        use neuro_core::SparseEmbedding;
+
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(make_doc("semantic", "Loading settings", vec![1.0, 0.0]))
+            .await
+            .unwrap();
+        storage
+            .add(
+                make_doc("keyword", "parse_config reads TOML", vec![0.6, 0.8])
+                    .with_sparse_embedding(SparseEmbedding::from_pairs([(42, 1.0)])),
+            )
+            .await
+            .unwrap();
+
+        let query = SparseEmbedding::from_pairs([(42, 1.0)]);
+        let filter = DocumentFilter::new();
+
+        let sparse = storage.search_sparse(&query, &filter, 5).await.unwrap();
+        assert_eq!(sparse.len(), 1);
+        assert_eq!(sparse[0].document.id, "keyword");
+
+        let dense_only = storage
+            .search_hybrid(&[1.0, 0.0], &query, &filter, 2, 1.0)
+            .await
+            .unwrap();
+        assert_eq!(dense_only[0].document.id, "semantic");
+
+        let hybrid = storage
+            .search_hybrid(&[1.0, 0.0], &query, &filter, 2, 0.5)
+            .await
+            .unwrap();
+        assert_eq!(hybrid[0].document.id, "keyword");
+        assert_eq!(hybrid.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_reembed() {
+        let mut storage = MemoryStorage::new();
+        storage
+            .add(make_doc("doc1", "Hi", vec![1.0, 0.0]).with_embedding_model("model-a"))
+            .await
+            .unwrap();
+        storage
+            .add(make_doc("doc2", "Hello", vec![0.0, 1.0]).with_embedding_model("model-a"))
+            .await
+            .unwrap();
+
+        let count = storage.reembed(&LengthEmbedder).await.unwrap();
+        assert_eq!(count, 2);
+        assert_eq!(storage.dimension(), Some(4));
+        assert_eq!(storage.embedding_model(), Some("BGE-small-en-v1.5"));
+
+        let doc = storage.get("doc2").await.unwrap();
+        assert_eq!(doc.embedding, Some(vec![5.0, 1.0, 0.0, 0.0]));
+        assert_eq!(doc.embedding_model.as_deref(), Some("BGE-small-en-v1.5"));
+
+        let results = storage.search(&[2.0, 1.0, 0.0, 0.0], 1).await.unwrap();
+        assert_eq!(results.len(), 1);
+    }
 }
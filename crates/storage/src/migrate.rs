@@ -0,0 +1,175 @@
+//! Migration of documents between storage backends
+
+use futures::StreamExt;
+use tracing::debug;
+
+use crate::error::{Result, StorageError};
+use crate::storage::Storage;
+
+/// Default number of documents written per batch during migration
+pub const DEFAULT_MIGRATION_BATCH_SIZE: usize = 100;
+
+/// Summary of a completed migration
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MigrationReport {
+    /// Documents found in the source backend
+    pub total: usize,
+    /// Documents written to the target backend
+    pub migrated: usize,
+    /// Documents skipped because they already exist in the target
+    pub skipped: usize,
+}
+
+/// Copy every document (including embeddings) from `source` into `target`
+///
+/// Documents are streamed from the source and written in batches of
+/// `batch_size`, so memory use is bounded by the batch size. Before
+/// anything is written, the embedding dimension of every source document
+/// is checked against the target backend so a migration never leaves the
+/// target half-populated with incompatible vectors. Documents whose ID
+/// already exists in the target are skipped.
+///
+/// `on_progress` is called after each batch with the number of documents
+/// processed so far.
+pub async fn migrate<F>(
+    source: &dyn Storage,
+    target: &mut dyn Storage,
+    batch_size: usize,
+    mut on_progress: F,
+) -> Result<MigrationReport>
+where
+    F: FnMut(usize),
+{
+    if batch_size == 0 {
+        return Err(StorageError::InvalidOperation(
+            "Migration batch size must be greater than zero".to_string(),
+        ));
+    }
+
+    // Validate dimensions up front
+    let mut expected = target.stats().await.embedding_dimension;
+    let mut total = 0;
+    let mut documents = source.iter();
+    while let Some(doc) = documents.next().await {
+        total += 1;
+        let dim = doc
+            .embedding_dim()
+            .ok_or_else(|| StorageError::MissingEmbedding(doc.id.clone()))?;
+        match expected {
+            Some(expected) if expected != dim => {
+                return Err(StorageError::DimensionMismatch {
+                    expected,
+                    actual: dim,
+                });
+            }
+            Some(_) => {}
+            None => expected = Some(dim),
+        }
+    }
+
+    let mut report = MigrationReport {
+        total,
+        ..Default::default()
+    };
+    let mut processed = 0;
+    let mut batch = Vec::with_capacity(batch_size);
+
+    let mut documents = source.iter();
+    while let Some(doc) = documents.next().await {
+        processed += 1;
+
+        if target.exists(&doc.id).await {
+            debug!("Skipping existing document {}", doc.id);
+            report.skipped += 1;
+        } else {
+            batch.push(doc);
+        }
+
+        if batch.len() >= batch_size {
+            report.migrated += batch.len();
+            target.add_batch(std::mem::take(&mut batch)).await?;
+            on_progress(processed);
+        }
+    }
+
+    if !batch.is_empty() {
+        report.migrated += batch.len();
+        target.add_batch(batch).await?;
+    }
+    on_progress(processed);
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileStorage, MemoryStorage};
+    use neuro_core::Document;
+    use tempfile::tempdir;
+
+    fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
+        Document::with_id(id, content).with_embedding(embedding)
+    }
+
+    #[tokio::test]
+    async fn test_migrate_memory_to_file() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("storage.json");
+
+        let mut source = MemoryStorage::new();
+        for i in 0..5 {
+            let doc = make_doc(&format!("doc{}", i), "content", vec![i as f32, 1.0, 0.0]);
+            source.add(doc).await.unwrap();
+        }
+
+        let mut target = FileStorage::new(&path).await.unwrap();
+        let mut calls = Vec::new();
+        let report = migrate(&source, &mut target, 2, |n| calls.push(n))
+            .await
+            .unwrap();
+
+        assert_eq!(report.total, 5);
+        assert_eq!(report.migrated, 5);
+        assert_eq!(report.skipped, 0);
+        assert_eq!(calls.last(), Some(&5));
+
+        let reloaded = FileStorage::new(&path).await.unwrap();
+        assert_eq!(reloaded.count().await, 5);
+        let doc = reloaded.get("doc3").await.unwrap();
+        assert_eq!(doc.embedding, Some(vec![3.0, 1.0, 0.0]));
+    }
+
+    #[tokio::test]
+    async fn test_migrate_skips_existing() {
+        let mut source = MemoryStorage::new();
+        source.add(make_doc("doc1", "First", vec![1.0, 0.0])).await.unwrap();
+        source.add(make_doc("doc2", "Second", vec![0.0, 1.0])).await.unwrap();
+
+        let mut target = MemoryStorage::new();
+        target.add(make_doc("doc1", "First", vec![1.0, 0.0])).await.unwrap();
+
+        let report = migrate(&source, &mut target, 10, |_| {}).await.unwrap();
+
+        assert_eq!(report.migrated, 1);
+        assert_eq!(report.skipped, 1);
+        assert_eq!(target.count().await, 2);
+    }
+
+    #[tokio::test]
+    async fn test_migrate_dimension_mismatch() {
+        let mut source = MemoryStorage::new();
+        source.add(make_doc("doc1", "First", vec![1.0, 0.0, 0.0])).await.unwrap();
+
+        let mut target = MemoryStorage::new();
+        target.add(make_doc("other", "Other", vec![1.0, 0.0])).await.unwrap();
+
+        let result = migrate(&source, &mut target, 10, |_| {}).await;
+
+        assert!(matches!(
+            result,
+            Err(StorageError::DimensionMismatch { expected: 2, actual: 3 })
+        ));
+        assert_eq!(target.count().await, 1);
+    }
+}
@@ -1,6 +1,9 @@
-//! Cosine similarity calculations using ndarray for SIMD optimization
-
-use ndarray::{Array1, ArrayView1};
+//! Cosine similarity calculations with SIMD acceleration
+//!
+//! The hot loop computes the dot product and both squared norms in a single
+//! pass. On x86_64 an AVX2+FMA kernel is selected at runtime when the CPU
+//! supports it; on aarch64 a NEON kernel is always used. Other targets fall
+//! back to a portable scalar loop.
 
 /// Calculate cosine similarity between two vectors
 ///
@@ -16,18 +19,41 @@ use ndarray::{Array1, ArrayView1};
 pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
     assert_eq!(a.len(), b.len(), "Vectors must have same length");
 
-    let a = ArrayView1::from(a);
-    let b = ArrayView1::from(b);
+    let (dot, norm_a, norm_b) = dot_and_norms(a, b);
+    finish_cosine(dot, norm_a.sqrt(), norm_b.sqrt())
+}
 
-    let dot = a.dot(&b);
-    let norm_a = a.dot(&a).sqrt();
-    let norm_b = b.dot(&b).sqrt();
+/// Portable scalar cosine similarity
+///
+/// Produces the same result as [`cosine_similarity`] (up to floating point
+/// rounding) without any SIMD. Mainly useful as a reference and benchmark
+/// baseline.
+///
+/// # Panics
+/// Panics if vectors have different lengths
+pub fn cosine_similarity_scalar(a: &[f32], b: &[f32]) -> f32 {
+    assert_eq!(a.len(), b.len(), "Vectors must have same length");
 
-    if norm_a == 0.0 || norm_b == 0.0 {
-        return 0.0;
+    let (dot, norm_a, norm_b) = scalar::dot_and_norms(a, b);
+    finish_cosine(dot, norm_a.sqrt(), norm_b.sqrt())
+}
+
+/// Name of the SIMD kernel selected for this CPU
+pub fn simd_backend() -> &'static str {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86::is_supported() {
+            return "avx2+fma";
+        }
     }
 
-    dot / (norm_a * norm_b)
+    #[cfg(target_arch = "aarch64")]
+    {
+        return "neon";
+    }
+
+    #[allow(unreachable_code)]
+    "scalar"
 }
 
 /// Calculate cosine similarity between a query vector and multiple document vectors
@@ -37,14 +63,14 @@ pub fn cosine_similarity(a: &[f32], b: &[f32]) -> f32 {
 /// * `documents` - Slice of document embedding vectors
 ///
 /// # Returns
-/// Vector of similarity scores in the same order as documents
+/// Vector of similarity scores in the same order as documents. Documents
+/// whose length differs from the query score 0.0.
 pub fn batch_cosine_similarity(query: &[f32], documents: &[Vec<f32>]) -> Vec<f32> {
     if documents.is_empty() {
         return Vec::new();
     }
 
-    let query = Array1::from_vec(query.to_vec());
-    let query_norm = query.dot(&query).sqrt();
+    let query_norm = dot(query, query).sqrt();
 
     if query_norm == 0.0 {
         return vec![0.0; documents.len()];
@@ -53,18 +79,179 @@ pub fn batch_cosine_similarity(query: &[f32], documents: &[Vec<f32>]) -> Vec<f32
     documents
         .iter()
         .map(|doc| {
-            let doc = ArrayView1::from(doc.as_slice());
-            let doc_norm = doc.dot(&doc).sqrt();
-
-            if doc_norm == 0.0 {
-                0.0
-            } else {
-                query.dot(&doc) / (query_norm * doc_norm)
+            if doc.len() != query.len() {
+                return 0.0;
             }
+            let (dot, _, doc_norm) = dot_and_norms(query, doc);
+            finish_cosine(dot, query_norm, doc_norm.sqrt())
         })
         .collect()
 }
 
+#[inline]
+fn finish_cosine(dot: f32, norm_a: f32, norm_b: f32) -> f32 {
+    if norm_a == 0.0 || norm_b == 0.0 {
+        return 0.0;
+    }
+
+    dot / (norm_a * norm_b)
+}
+
+/// Dot product of two vectors
+#[inline]
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    dot_and_norms(a, b).0
+}
+
+/// Compute `(a·b, a·a, b·b)` in a single pass, dispatching to the best kernel
+#[inline]
+fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+    debug_assert_eq!(a.len(), b.len());
+
+    #[cfg(target_arch = "x86_64")]
+    {
+        if x86::is_supported() {
+            // SAFETY: the required CPU features were detected at runtime
+            return unsafe { x86::dot_and_norms(a, b) };
+        }
+    }
+
+    #[cfg(target_arch = "aarch64")]
+    {
+        // SAFETY: NEON is mandatory on aarch64
+        return unsafe { neon::dot_and_norms(a, b) };
+    }
+
+    #[allow(unreachable_code)]
+    scalar::dot_and_norms(a, b)
+}
+
+mod scalar {
+    /// Scalar kernel, unrolled by 4 to give the optimizer independent accumulators
+    pub fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let mut dot = [0.0f32; 4];
+        let mut na = [0.0f32; 4];
+        let mut nb = [0.0f32; 4];
+
+        let chunks = a.len() / 4 * 4;
+        for (ca, cb) in a[..chunks].chunks_exact(4).zip(b[..chunks].chunks_exact(4)) {
+            for i in 0..4 {
+                dot[i] += ca[i] * cb[i];
+                na[i] += ca[i] * ca[i];
+                nb[i] += cb[i] * cb[i];
+            }
+        }
+
+        let mut dot: f32 = dot.iter().sum();
+        let mut na: f32 = na.iter().sum();
+        let mut nb: f32 = nb.iter().sum();
+
+        for (x, y) in a[chunks..].iter().zip(&b[chunks..]) {
+            dot += x * y;
+            na += x * x;
+            nb += y * y;
+        }
+
+        (dot, na, nb)
+    }
+}
+
+#[cfg(target_arch = "x86_64")]
+mod x86 {
+    use std::arch::x86_64::*;
+
+    #[inline]
+    pub fn is_supported() -> bool {
+        is_x86_feature_detected!("avx2") && is_x86_feature_detected!("fma")
+    }
+
+    #[target_feature(enable = "avx2,fma")]
+    unsafe fn hsum(v: __m256) -> f32 {
+        let lo = _mm256_castps256_ps128(v);
+        let hi = _mm256_extractf128_ps(v, 1);
+        let sum = _mm_add_ps(lo, hi);
+        let shuf = _mm_movehdup_ps(sum);
+        let sum = _mm_add_ps(sum, shuf);
+        let shuf = _mm_movehl_ps(shuf, sum);
+        _mm_cvtss_f32(_mm_add_ss(sum, shuf))
+    }
+
+    /// AVX2+FMA kernel processing 8 lanes per iteration
+    ///
+    /// # Safety
+    /// The caller must ensure the CPU supports AVX2 and FMA.
+    #[target_feature(enable = "avx2,fma")]
+    pub unsafe fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let len = a.len().min(b.len());
+        let chunks = len / 8 * 8;
+
+        let mut dot = _mm256_setzero_ps();
+        let mut na = _mm256_setzero_ps();
+        let mut nb = _mm256_setzero_ps();
+
+        let mut i = 0;
+        while i < chunks {
+            let va = _mm256_loadu_ps(a.as_ptr().add(i));
+            let vb = _mm256_loadu_ps(b.as_ptr().add(i));
+            dot = _mm256_fmadd_ps(va, vb, dot);
+            na = _mm256_fmadd_ps(va, va, na);
+            nb = _mm256_fmadd_ps(vb, vb, nb);
+            i += 8;
+        }
+
+        let (mut dot, mut na, mut nb) = (hsum(dot), hsum(na), hsum(nb));
+
+        for j in chunks..len {
+            let (x, y) = (a[j], b[j]);
+            dot += x * y;
+            na += x * x;
+            nb += y * y;
+        }
+
+        (dot, na, nb)
+    }
+}
+
+#[cfg(target_arch = "aarch64")]
+mod neon {
+    use std::arch::aarch64::*;
+
+    /// NEON kernel processing 4 lanes per iteration
+    ///
+    /// # Safety
+    /// NEON is mandatory on aarch64, so this is always safe to call there.
+    #[target_feature(enable = "neon")]
+    pub unsafe fn dot_and_norms(a: &[f32], b: &[f32]) -> (f32, f32, f32) {
+        let len = a.len().min(b.len());
+        let chunks = len / 4 * 4;
+
+        let mut dot = vdupq_n_f32(0.0);
+        let mut na = vdupq_n_f32(0.0);
+        let mut nb = vdupq_n_f32(0.0);
+
+        let mut i = 0;
+        while i < chunks {
+            let va = vld1q_f32(a.as_ptr().add(i));
+            let vb = vld1q_f32(b.as_ptr().add(i));
+            dot = vfmaq_f32(dot, va, vb);
+            na = vfmaq_f32(na, va, va);
+            nb = vfmaq_f32(nb, vb, vb);
+            i += 4;
+        }
+
+        let (mut dot, mut na, mut nb) = (vaddvq_f32(dot), vaddvq_f32(na), vaddvq_f32(nb));
+
+        for j in chunks..len {
+            let (x, y) = (a[j], b[j]);
+            dot += x * y;
+            na += x * x;
+            nb += y * y;
+        }
+
+        (dot, na, nb)
+    }
+}
+
 /// Find top-k most similar documents
 ///
 /// # Arguments
@@ -154,6 +341,22 @@ mod tests {
         assert!((sims[2] + 1.0).abs() < 1e-6);
     }
 
+    #[test]
+    fn test_batch_cosine_similarity_length_mismatch() {
+        let query = vec![1.0, 0.0, 0.0];
+        let documents = vec![
+            vec![1.0, 0.0],           // too short
+            vec![1.0, 0.0, 0.0],      // identical
+            vec![1.0, 0.0, 0.0, 1.0], // too long
+        ];
+
+        let sims = batch_cosine_similarity(&query, &documents);
+        assert_eq!(sims.len(), 3);
+        assert_eq!(sims[0], 0.0);
+        assert!((sims[1] - 1.0).abs() < 1e-6);
+        assert_eq!(sims[2], 0.0);
+    }
+
     #[test]
     fn test_top_k_similar() {
         let query = vec![1.0, 0.0, 0.0];
@@ -170,6 +373,30 @@ mod tests {
         assert_eq!(top[1].0, 3); // Index 3 is second most similar
     }
 
+    #[test]
+    fn test_simd_matches_scalar() {
+        // Odd length exercises the remainder loop of every kernel
+        for len in [1, 3, 7, 8, 9, 17, 384, 385] {
+            let a: Vec<f32> = (0..len).map(|i| ((i * 7 % 13) as f32 - 6.0) / 3.0).collect();
+            let b: Vec<f32> = (0..len).map(|i| ((i * 5 % 11) as f32 - 4.0) / 2.0).collect();
+
+            let fast = cosine_similarity(&a, &b);
+            let reference = cosine_similarity_scalar(&a, &b);
+            assert!(
+                (fast - reference).abs() < 1e-5,
+                "len {}: {} vs {}",
+                len,
+                fast,
+                reference
+            );
+        }
+    }
+
+    #[test]
+    fn test_simd_backend_name() {
+        assert!(["avx2+fma", "neon", "scalar"].contains(&simd_backend()));
+    }
+
     #[test]
     fn test_top_k_similar_empty() {
         let query = vec![1.0, 0.0];
@@ -0,0 +1,629 @@
+//! SQLite storage backend
+//!
+//! Stores every document in a single SQLite database file: the embedding
+//! as a little-endian `f32` blob and the rest of the document as JSON.
+//! Search scans the embeddings without loading the documents, so only the
+//! best matches are decoded.
+//!
+//! Requires the `sqlite` feature.
+
+use async_trait::async_trait;
+use futures::stream::{self, StreamExt};
+use rusqlite::{params, Connection, ErrorCode, OptionalExtension, Transaction};
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard};
+use tracing::{debug, info, warn};
+
+use neuro_core::{Document, SearchResult};
+use neuro_embeddings::Embedder;
+use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
+use crate::similarity::cosine_similarity;
+use crate::storage::{
+    reembed_batch, validate_embedding_model, DocumentStream, Storage, StorageStats,
+    REEMBED_BATCH_SIZE,
+};
+
+/// Rows fetched per query while iterating
+const PAGE_SIZE: usize = 256;
+
+const SCHEMA: &str = "
+    PRAGMA journal_mode = WAL;
+    CREATE TABLE IF NOT EXISTS documents (
+        id TEXT PRIMARY KEY,
+        user_id TEXT,
+        embedding BLOB NOT NULL,
+        document TEXT NOT NULL
+    );
+    CREATE INDEX IF NOT EXISTS documents_user_id ON documents (user_id);
+    CREATE TABLE IF NOT EXISTS meta (
+        key TEXT PRIMARY KEY,
+        value TEXT NOT NULL
+    );
+";
+
+/// SQLite-backed document storage
+///
+/// Every write runs in a transaction, so a batch is stored completely or
+/// not at all.
+pub struct SqliteStorage {
+    path: PathBuf,
+    connection: Mutex<Connection>,
+    dimension: Option<usize>,
+    embedding_model: Option<String>,
+}
+
+impl SqliteStorage {
+    /// Open (or create) a SQLite database at the given path
+    pub async fn open(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            std::fs::create_dir_all(parent)?;
+        }
+
+        let connection = Connection::open(&path)?;
+        connection.execute_batch(SCHEMA)?;
+
+        let meta = |key: &str| -> Result<Option<String>> {
+            Ok(connection
+                .query_row("SELECT value FROM meta WHERE key = ?1", [key], |row| {
+                    row.get(0)
+                })
+                .optional()?)
+        };
+        let dimension = meta("dimension")?.and_then(|d| d.parse().ok());
+        let embedding_model = meta("embedding_model")?;
+
+        let storage = Self {
+            path,
+            connection: Mutex::new(connection),
+            dimension,
+            embedding_model,
+        };
+
+        info!(
+            "Opened SQLite storage at {:?} ({} documents)",
+            storage.path,
+            storage.count_rows()?
+        );
+
+        Ok(storage)
+    }
+
+    /// Get the database path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Get the embedding dimension
+    pub fn dimension(&self) -> Option<usize> {
+        self.dimension
+    }
+
+    /// Get the embedding model (if recorded on any document)
+    pub fn embedding_model(&self) -> Option<&str> {
+        self.embedding_model.as_deref()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Connection> {
+        self.connection.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn validate_embedding(&self, embedding: &[f32]) -> Result<()> {
+        if let Some(dim) = self.dimension {
+            if embedding.len() != dim {
+                return Err(StorageError::DimensionMismatch {
+                    expected: dim,
+                    actual: embedding.len(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn count_rows(&self) -> Result<usize> {
+        let count: i64 = self
+            .lock()
+            .query_row("SELECT COUNT(*) FROM documents", [], |row| row.get(0))?;
+        Ok(count as usize)
+    }
+
+    /// Decode the documents matching `condition` (an SQL `WHERE` clause)
+    fn query_documents(&self, condition: &str, param: Option<&str>) -> Result<Vec<Document>> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(&format!(
+            "SELECT embedding, document FROM documents WHERE {}",
+            condition
+        ))?;
+        let rows = match param {
+            Some(param) => statement.query_map([param], read_row)?,
+            None => statement.query_map([], read_row)?,
+        };
+
+        let mut documents = Vec::new();
+        for row in rows {
+            documents.push(decode_document(row?)?);
+        }
+        Ok(documents)
+    }
+
+    /// The documents after `rowid`, with the rowid of the last one
+    fn page_after(&self, rowid: i64) -> Result<(Vec<Document>, Option<i64>)> {
+        let connection = self.lock();
+        let mut statement = connection.prepare(
+            "SELECT rowid, embedding, document FROM documents
+             WHERE rowid > ?1 ORDER BY rowid LIMIT ?2",
+        )?;
+        let rows = statement.query_map(params![rowid, PAGE_SIZE as i64], |row| {
+            Ok((row.get::<_, i64>(0)?, (row.get(1)?, row.get(2)?)))
+        })?;
+
+        let mut documents = Vec::new();
+        let mut last = None;
+        for row in rows {
+            let (rowid, row) = row?;
+            documents.push(decode_document(row)?);
+            last = Some(rowid);
+        }
+        Ok((documents, last))
+    }
+
+    /// Score every stored embedding (optionally of one user) against `embedding`
+    fn score_all(&self, embedding: &[f32], user_id: Option<&str>) -> Result<Vec<(String, f32)>> {
+        let connection = self.lock();
+        let mut statement = connection
+            .prepare("SELECT id, embedding FROM documents WHERE ?1 IS NULL OR user_id = ?1")?;
+        let rows = statement.query_map([user_id], |row| {
+            Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+        })?;
+
+        let mut scored = Vec::new();
+        for row in rows {
+            let (id, blob) = row?;
+            scored.push((id, cosine_similarity(embedding, &decode_embedding(&blob))));
+        }
+        Ok(scored)
+    }
+}
+
+#[async_trait]
+impl Storage for SqliteStorage {
+    async fn add(&mut self, document: Document) -> Result<()> {
+        self.add_batch(vec![document]).await
+    }
+
+    async fn add_batch(&mut self, documents: Vec<Document>) -> Result<()> {
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        let mut dimension = self.dimension;
+        let mut embedding_model = self.embedding_model.clone();
+        for doc in &documents {
+            let embedding = doc
+                .embedding
+                .as_ref()
+                .ok_or_else(|| StorageError::MissingEmbedding(doc.id.clone()))?;
+
+            let expected = *dimension.get_or_insert(embedding.len());
+            if embedding.len() != expected {
+                return Err(StorageError::DimensionMismatch {
+                    expected,
+                    actual: embedding.len(),
+                });
+            }
+
+            validate_embedding_model(embedding_model.as_deref(), doc)?;
+            if embedding_model.is_none() {
+                embedding_model = doc.embedding_model.clone();
+            }
+        }
+
+        debug!("Adding {} documents to {:?}", documents.len(), self.path);
+
+        {
+            let mut connection = self.lock();
+            let tx = connection.transaction()?;
+            {
+                let mut insert = tx.prepare(
+                    "INSERT INTO documents (id, user_id, embedding, document)
+                     VALUES (?1, ?2, ?3, ?4)",
+                )?;
+                for mut doc in documents {
+                    let embedding = doc.embedding.take().unwrap_or_default();
+                    let inserted = insert.execute(params![
+                        doc.id,
+                        doc.user_id,
+                        encode_embedding(&embedding),
+                        serde_json::to_string(&doc)?,
+                    ]);
+                    match inserted {
+                        Err(e) if e.sqlite_error_code() == Some(ErrorCode::ConstraintViolation) => {
+                            return Err(StorageError::AlreadyExists(doc.id));
+                        }
+                        inserted => {
+                            inserted?;
+                        }
+                    }
+                }
+            }
+            write_meta(&tx, dimension, embedding_model.as_deref())?;
+            tx.commit()?;
+        }
+
+        self.dimension = dimension;
+        self.embedding_model = embedding_model;
+        Ok(())
+    }
+
+    async fn get(&self, id: &str) -> Result<Document> {
+        let row = self
+            .lock()
+            .query_row(
+                "SELECT embedding, document FROM documents WHERE id = ?1",
+                [id],
+                read_row,
+            )
+            .optional()?;
+
+        match row {
+            Some(row) => decode_document(row),
+            None => Err(StorageError::NotFound(id.to_string())),
+        }
+    }
+
+    async fn delete(&mut self, id: &str) -> Result<()> {
+        debug!("Deleting document {}", id);
+
+        let deleted = self
+            .lock()
+            .execute("DELETE FROM documents WHERE id = ?1", [id])?;
+        if deleted == 0 {
+            return Err(StorageError::NotFound(id.to_string()));
+        }
+        Ok(())
+    }
+
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize> {
+        if filter.is_empty() {
+            let count = self.count_rows()?;
+            self.clear().await?;
+            return Ok(count);
+        }
+
+        let ids: Vec<String> = match filter.user_id {
+            Some(ref user_id) => self.list_by_user(user_id).await?,
+            None => self.list().await?,
+        }
+        .into_iter()
+        .filter(|d| filter.matches(d))
+        .map(|d| d.id)
+        .collect();
+
+        debug!("Deleting {} documents matching filter", ids.len());
+
+        let mut connection = self.lock();
+        let tx = connection.transaction()?;
+        {
+            let mut delete = tx.prepare("DELETE FROM documents WHERE id = ?1")?;
+            for id in &ids {
+                delete.execute([id])?;
+            }
+        }
+        tx.commit()?;
+        Ok(ids.len())
+    }
+
+    async fn exists(&self, id: &str) -> bool {
+        let row = self
+            .lock()
+            .query_row("SELECT 1 FROM documents WHERE id = ?1", [id], |_| Ok(()))
+            .optional();
+        matches!(row, Ok(Some(())))
+    }
+
+    async fn search(&self, embedding: &[f32], top_k: usize) -> Result<Vec<SearchResult>> {
+        self.search_filtered(embedding, &DocumentFilter::new(), top_k)
+            .await
+    }
+
+    async fn search_by_user(
+        &self,
+        embedding: &[f32],
+        user_id: &str,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filter = DocumentFilter::new().with_user_id(user_id);
+        self.search_filtered(embedding, &filter, top_k).await
+    }
+
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        if self.dimension.is_none() {
+            return Ok(Vec::new());
+        }
+        self.validate_embedding(embedding)?;
+
+        let mut scored = self.score_all(embedding, filter.user_id.as_deref())?;
+
+        let by_score = |a: &(String, f32), b: &(String, f32)| {
+            b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal)
+        };
+
+        // Without a filter only the top-k rows are ever needed
+        if filter.is_empty() && top_k < scored.len() {
+            scored.select_nth_unstable_by(top_k, by_score);
+            scored.truncate(top_k);
+        }
+        scored.sort_by(by_score);
+
+        let mut results = Vec::new();
+        for (id, score) in scored {
+            if results.len() >= top_k {
+                break;
+            }
+
+            let document = self.get(&id).await?;
+            if filter.matches(&document) {
+                let rank = results.len();
+                results.push(SearchResult::new(document, score).with_rank(rank));
+            }
+        }
+
+        Ok(results)
+    }
+
+    async fn list(&self) -> Result<Vec<Document>> {
+        self.query_documents("1", None)
+    }
+
+    fn iter(&self) -> DocumentStream<'_> {
+        let pages = stream::unfold(Some(0), move |after| async move {
+            match self.page_after(after?) {
+                Ok((documents, last)) if !documents.is_empty() => Some((documents, last)),
+                Ok(_) => None,
+                Err(e) => {
+                    warn!("Stopping iteration over {:?}: {}", self.path, e);
+                    None
+                }
+            }
+        });
+
+        Box::pin(pages.flat_map(stream::iter))
+    }
+
+    async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>> {
+        self.query_documents("user_id = ?1", Some(user_id))
+    }
+
+    async fn count(&self) -> usize {
+        self.count_rows().unwrap_or(0)
+    }
+
+    async fn clear(&mut self) -> Result<()> {
+        self.lock()
+            .execute_batch("DELETE FROM documents; DELETE FROM meta;")?;
+        self.dimension = None;
+        self.embedding_model = None;
+        Ok(())
+    }
+
+    async fn stats(&self) -> StorageStats {
+        let documents = self.iter().collect::<Vec<_>>().await;
+        let mut stats = StorageStats::from_documents(&documents, self.dimension);
+
+        // SQLite keeps only its page cache in RAM
+        stats.estimated_memory_bytes = 0;
+        stats
+    }
+
+    /// Re-embed every row in place within one transaction
+    ///
+    /// Rows are processed a batch at a time, and nothing is visible until
+    /// the transaction commits, so a failure or crash leaves the old
+    /// embeddings.
+    async fn reembed(&mut self, embedder: &dyn Embedder) -> Result<usize> {
+        let model = embedder.model_name();
+        let mut dimension = None;
+        let mut count = 0;
+
+        {
+            let mut connection = self.lock();
+            let tx = connection.transaction()?;
+            let mut after = 0;
+            loop {
+                let mut rowids = Vec::new();
+                let mut documents = Vec::new();
+                {
+                    let mut select = tx.prepare(
+                        "SELECT rowid, embedding, document FROM documents
+                         WHERE rowid > ?1 ORDER BY rowid LIMIT ?2",
+                    )?;
+                    let rows = select
+                        .query_map(params![after, REEMBED_BATCH_SIZE as i64], |row| {
+                            Ok((row.get::<_, i64>(0)?, (row.get(1)?, row.get(2)?)))
+                        })?;
+                    for row in rows {
+                        let (rowid, row) = row?;
+                        rowids.push(rowid);
+                        documents.push(decode_document(row)?);
+                    }
+                }
+                let Some(&last) = rowids.last() else {
+                    break;
+                };
+
+                reembed_batch(&mut documents, embedder)?;
+
+                let mut update = tx.prepare(
+                    "UPDATE documents SET embedding = ?1, document = ?2 WHERE rowid = ?3",
+                )?;
+                for (rowid, mut doc) in rowids.into_iter().zip(documents) {
+                    let embedding = doc.embedding.take().unwrap_or_default();
+                    dimension = Some(embedding.len());
+                    update.execute(params![
+                        encode_embedding(&embedding),
+                        serde_json::to_string(&doc)?,
+                        rowid,
+                    ])?;
+                    count += 1;
+                }
+                after = last;
+            }
+
+            if count > 0 {
+                write_meta(&tx, dimension, Some(model.as_str()))?;
+            }
+            tx.commit()?;
+        }
+
+        if count > 0 {
+            self.dimension = dimension;
+            self.embedding_model = Some(model);
+        }
+        info!("Re-embedded {} documents in {:?}", count, self.path);
+        Ok(count)
+    }
+}
+
+/// Embedding blob and document JSON of a row
+type Row = (Vec<u8>, String);
+
+fn read_row(row: &rusqlite::Row<'_>) -> rusqlite::Result<Row> {
+    Ok((row.get(0)?, row.get(1)?))
+}
+
+fn decode_document((blob, json): Row) -> Result<Document> {
+    let mut document: Document = serde_json::from_str(&json)?;
+    document.embedding = Some(decode_embedding(&blob));
+    Ok(document)
+}
+
+fn encode_embedding(embedding: &[f32]) -> Vec<u8> {
+    embedding.iter().flat_map(|x| x.to_le_bytes()).collect()
+}
+
+fn decode_embedding(blob: &[u8]) -> Vec<f32> {
+    blob.chunks_exact(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect()
+}
+
+fn write_meta(tx: &Transaction<'_>, dimension: Option<usize>, model: Option<&str>) -> Result<()> {
+    let mut upsert = tx.prepare("INSERT OR REPLACE INTO meta (key, value) VALUES (?1, ?2)")?;
+    if let Some(dimension) = dimension {
+        upsert.execute(["dimension", &dimension.to_string()])?;
+    }
+    if let Some(model) = model {
+        upsert.execute(["embedding_model", model])?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use neuro_embeddings::EmbeddingModel;
+    use tempfile::tempdir;
+
+    fn make_doc(id: &str, content: &str, embedding: Vec<f32>) -> Document {
+        Document::with_id(id, content).with_embedding(embedding)
+    }
+
+    struct LengthEmbedder;
+
+    impl Embedder for LengthEmbedder {
+        fn model(&self) -> EmbeddingModel {
+            EmbeddingModel::BgeSmallEnV15
+        }
+
+        fn dimension(&self) -> usize {
+            4
+        }
+
+        fn embed_single(&self, text: &str) -> neuro_embeddings::Result<Vec<f32>> {
+            Ok(vec![text.len() as f32, 1.0, 0.0, 0.0])
+        }
+
+        fn embed_batch(&self, texts: &[&str]) -> neuro_embeddings::Result<Vec<Vec<f32>>> {
+            texts.iter().map(|t| self.embed_single(t)).collect()
+        }
+    }
+
+    #[test]
+    fn test_embedding_roundtrip() {
+        let embedding = vec![1.5, -0.25, 0.0];
+        assert_eq!(decode_embedding(&encode_embedding(&embedding)), embedding);
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_roundtrip() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("docs.db");
+
+        {
+            let mut storage = SqliteStorage::open(&path).await.unwrap();
+            storage
+                .add(make_doc("doc1", "Similar", vec![1.0, 0.0, 0.0]).with_user_id("alice"))
+                .await
+                .unwrap();
+            storage
+                .add(make_doc("doc2", "Different", vec![0.0, 1.0, 0.0]))
+                .await
+                .unwrap();
+            let result = storage
+                .add(make_doc("doc1", "Dup", vec![1.0, 0.0, 0.0]))
+                .await;
+            assert!(matches!(result, Err(StorageError::AlreadyExists(_))));
+        }
+
+        let mut storage = SqliteStorage::open(&path).await.unwrap();
+        assert_eq!(storage.dimension(), Some(3));
+        assert_eq!(storage.count().await, 2);
+        assert_eq!(storage.iter().count().await, 2);
+
+        let results = storage.search(&[1.0, 0.1, 0.0], 1).await.unwrap();
+        assert_eq!(results[0].document.id, "doc1");
+        assert_eq!(results[0].document.embedding, Some(vec![1.0, 0.0, 0.0]));
+
+        let results = storage
+            .search_by_user(&[0.0, 1.0, 0.0], "alice", 5)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 1);
+
+        assert_eq!(storage.delete_by_user("alice").await.unwrap(), 1);
+        assert!(!storage.exists("doc1").await);
+        assert!(storage.delete("doc1").await.is_err());
+        assert_eq!(storage.get("doc2").await.unwrap().content, "Different");
+    }
+
+    #[tokio::test]
+    async fn test_sqlite_storage_reembed() {
+        let dir = tempdir().unwrap();
+        let path = dir.path().join("docs.db");
+
+        let mut storage = SqliteStorage::open(&path).await.unwrap();
+        storage
+            .add_batch(vec![
+                make_doc("doc1", "Hi", vec![1.0, 0.0]).with_embedding_model("model-a"),
+                make_doc("doc2", "Hello", vec![0.0, 1.0]).with_embedding_model("model-a"),
+            ])
+            .await
+            .unwrap();
+
+        assert_eq!(storage.reembed(&LengthEmbedder).await.unwrap(), 2);
+        drop(storage);
+
+        let storage = SqliteStorage::open(&path).await.unwrap();
+        assert_eq!(storage.dimension(), Some(4));
+        assert_eq!(storage.embedding_model(), Some("BGE-small-en-v1.5"));
+        let doc = storage.get("doc2").await.unwrap();
+        assert_eq!(doc.embedding, Some(vec![5.0, 1.0, 0.0, 0.0]));
+        assert_eq!(doc.embedding_model.as_deref(), Some("BGE-small-en-v1.5"));
+    }
+}
@@ -1,22 +1,192 @@
 //! Storage trait definition
 
 use async_trait::async_trait;
-use neuro_core::{Document, SearchResult};
-use crate::error::Result;
+use chrono::{DateTime, Utc};
+use futures::stream::BoxStream;
+use futures::StreamExt;
+use neuro_core::{Document, DocumentSource, SearchResult, SparseEmbedding};
+use serde::Serialize;
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::mem::size_of;
+use neuro_embeddings::Embedder;
+use crate::error::{Result, StorageError};
+use crate::filter::DocumentFilter;
+use crate::hybrid;
+use crate::memory::MemoryStorage;
+
+/// Number of documents embedded per call during [`Storage::reembed`]
+pub(crate) const REEMBED_BATCH_SIZE: usize = 32;
+
+/// Candidates fetched from each side per requested hybrid result
+const HYBRID_CANDIDATE_FACTOR: usize = 4;
 
 /// Statistics about the storage
-#[derive(Debug, Clone, Default)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct StorageStats {
     /// Total number of documents
     pub document_count: usize,
     /// Embedding dimension (if documents exist)
     pub embedding_dimension: Option<usize>,
+    /// Model that produced the stored embeddings (if recorded)
+    pub embedding_model: Option<String>,
     /// Total content size in bytes
     pub total_content_bytes: usize,
     /// Number of unique users
     pub unique_users: usize,
+    /// Number of documents per source
+    pub documents_by_source: HashMap<DocumentSource, usize>,
+    /// Number of documents per user (documents without a user are not counted)
+    pub documents_by_user: HashMap<String, usize>,
+    /// Mean L2 norm of the stored embeddings
+    pub average_embedding_norm: Option<f32>,
+    /// Creation time of the oldest document
+    pub oldest_document: Option<DateTime<Utc>>,
+    /// Creation time of the newest document
+    pub newest_document: Option<DateTime<Utc>>,
+    /// Rough estimate of the memory used by the backend, in bytes
+    pub estimated_memory_bytes: usize,
+}
+
+impl StorageStats {
+    /// Compute statistics over a set of documents
+    ///
+    /// `estimated_memory_bytes` only covers the documents themselves;
+    /// backends add the cost of their own index structures.
+    pub fn from_documents<I, D>(documents: I, embedding_dimension: Option<usize>) -> Self
+    where
+        I: IntoIterator<Item = D>,
+        D: Borrow<Document>,
+    {
+        let mut stats = Self {
+            embedding_dimension,
+            ..Self::default()
+        };
+        let mut norm_sum = 0.0f64;
+        let mut norm_count = 0usize;
+
+        for doc in documents {
+            let doc = doc.borrow();
+
+            stats.document_count += 1;
+            stats.total_content_bytes += doc.content.len();
+            stats.estimated_memory_bytes += estimate_document_bytes(doc);
+            *stats.documents_by_source.entry(doc.source.clone()).or_default() += 1;
+
+            if let Some(ref user_id) = doc.user_id {
+                *stats.documents_by_user.entry(user_id.clone()).or_default() += 1;
+            }
+
+            if stats.embedding_model.is_none() {
+                stats.embedding_model = doc.embedding_model.clone();
+            }
+
+            if let Some(ref embedding) = doc.embedding {
+                norm_sum += embedding.iter().map(|x| x * x).sum::<f32>().sqrt() as f64;
+                norm_count += 1;
+            }
+
+            stats.oldest_document = Some(
+                stats
+                    .oldest_document
+                    .map_or(doc.created_at, |t| t.min(doc.created_at)),
+            );
+            stats.newest_document = Some(
+                stats
+                    .newest_document
+                    .map_or(doc.created_at, |t| t.max(doc.created_at)),
+            );
+        }
+
+        stats.unique_users = stats.documents_by_user.len();
+        if norm_count > 0 {
+            stats.average_embedding_norm = Some((norm_sum / norm_count as f64) as f32);
+        }
+
+        stats
+    }
+}
+
+/// Approximate heap and inline size of a document
+pub(crate) fn estimate_document_bytes(doc: &Document) -> usize {
+    let metadata: usize = doc
+        .metadata
+        .iter()
+        .map(|(key, value)| key.len() + value.to_string().len() + size_of::<serde_json::Value>())
+        .sum();
+    let tags: usize = doc.tags.iter().map(|t| t.len() + size_of::<String>()).sum();
+
+    size_of::<Document>()
+        + doc.id.len()
+        + doc.content.len()
+        + doc.user_id.as_ref().map_or(0, String::len)
+        + doc.embedding_model.as_ref().map_or(0, String::len)
+        + metadata
+        + tags
+        + doc.embedding.as_ref().map_or(0, |e| e.len() * size_of::<f32>())
+        + doc
+            .sparse_embedding
+            .as_ref()
+            .map_or(0, |s| s.len() * (size_of::<u32>() + size_of::<f32>()))
+}
+
+/// Check that a document was embedded with the model already in use
+///
+/// Documents without a recorded model are accepted, so corpora created
+/// before models were tracked keep working.
+pub(crate) fn validate_embedding_model(expected: Option<&str>, document: &Document) -> Result<()> {
+    match (expected, document.embedding_model.as_deref()) {
+        (Some(expected), Some(actual)) if expected != actual => {
+            Err(StorageError::EmbeddingModelMismatch {
+                expected: expected.to_string(),
+                actual: actual.to_string(),
+            })
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Replace the embeddings of `documents` with ones computed by `embedder`
+pub(crate) fn reembed_batch(documents: &mut [Document], embedder: &dyn Embedder) -> Result<()> {
+    let texts: Vec<&str> = documents.iter().map(|d| d.content.as_str()).collect();
+    let embeddings = embedder.embed_passages(&texts)?;
+    if embeddings.len() != documents.len() {
+        return Err(StorageError::InvalidOperation(format!(
+            "Embedder returned {} embeddings for {} documents",
+            embeddings.len(),
+            documents.len()
+        )));
+    }
+
+    let model = embedder.model_name();
+    for (doc, embedding) in documents.iter_mut().zip(embeddings) {
+        doc.embedding = Some(embedding);
+        doc.embedding_model = Some(model.clone());
+    }
+    Ok(())
 }
 
+/// Stream every document of `source` into `target`, re-embedded in batches
+///
+/// Returns the number of documents written.
+pub(crate) async fn reembed_into<S: Storage + ?Sized>(
+    source: &S,
+    target: &mut dyn Storage,
+    embedder: &dyn Embedder,
+) -> Result<usize> {
+    let mut count = 0;
+    let mut batches = source.iter().chunks(REEMBED_BATCH_SIZE);
+    while let Some(mut batch) = batches.next().await {
+        reembed_batch(&mut batch, embedder)?;
+        count += batch.len();
+        target.add_batch(batch).await?;
+    }
+    Ok(count)
+}
+
+/// Stream of documents yielded one at a time by [`Storage::iter`]
+pub type DocumentStream<'a> = BoxStream<'a, Document>;
+
 /// Trait for document storage with vector similarity search
 #[async_trait]
 pub trait Storage: Send + Sync {
@@ -40,6 +210,24 @@ pub trait Storage: Send + Sync {
     /// Delete a document by ID
     async fn delete(&mut self, id: &str) -> Result<()>;
 
+    /// Delete all documents matching a filter
+    ///
+    /// Returns the number of deleted documents. An empty filter matches
+    /// every document.
+    async fn delete_where(&mut self, filter: &DocumentFilter) -> Result<usize>;
+
+    /// Delete all documents owned by a user
+    async fn delete_by_user(&mut self, user_id: &str) -> Result<usize> {
+        self.delete_where(&DocumentFilter::new().with_user_id(user_id))
+            .await
+    }
+
+    /// Delete all documents from a source
+    async fn delete_by_source(&mut self, source: DocumentSource) -> Result<usize> {
+        self.delete_where(&DocumentFilter::new().with_source(source))
+            .await
+    }
+
     /// Check if a document exists
     async fn exists(&self, id: &str) -> bool;
 
@@ -61,12 +249,104 @@ pub trait Storage: Send + Sync {
         top_k: usize,
     ) -> Result<Vec<SearchResult>>;
 
+    /// Search only among documents matching a filter
+    async fn search_filtered(
+        &self,
+        embedding: &[f32],
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>>;
+
+    /// Search only among documents carrying all of the given tags
+    async fn search_by_tags(
+        &self,
+        embedding: &[f32],
+        tags: &[String],
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filter = DocumentFilter::new().with_tags(tags.iter().cloned());
+        self.search_filtered(embedding, &filter, top_k).await
+    }
+
+    /// Search only among documents from a single source
+    async fn search_by_source(
+        &self,
+        embedding: &[f32],
+        source: DocumentSource,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let filter = DocumentFilter::new().with_source(source);
+        self.search_filtered(embedding, &filter, top_k).await
+    }
+
+    /// Search by sparse term weights among documents matching a filter
+    ///
+    /// Scores are raw dot products. Documents without a sparse embedding
+    /// are skipped. The default implementation scans every matching
+    /// document.
+    async fn search_sparse(
+        &self,
+        sparse: &SparseEmbedding,
+        filter: &DocumentFilter,
+        top_k: usize,
+    ) -> Result<Vec<SearchResult>> {
+        let documents = self.list_filtered(filter).await?;
+        Ok(hybrid::rank_sparse(sparse, documents, top_k))
+    }
+
+    /// Search with both dense and sparse embeddings
+    ///
+    /// Candidates from [`Storage::search_filtered`] and
+    /// [`Storage::search_sparse`] are merged; each score is
+    /// `dense_weight` times the cosine similarity plus the remainder times
+    /// the sparse score normalized to the best sparse hit.
+    async fn search_hybrid(
+        &self,
+        embedding: &[f32],
+        sparse: &SparseEmbedding,
+        filter: &DocumentFilter,
+        top_k: usize,
+        dense_weight: f32,
+    ) -> Result<Vec<SearchResult>> {
+        let candidates = top_k * HYBRID_CANDIDATE_FACTOR;
+        let dense = if filter.is_empty() {
+            self.search(embedding, candidates).await?
+        } else {
+            self.search_filtered(embedding, filter, candidates).await?
+        };
+        let sparse = self.search_sparse(sparse, filter, candidates).await?;
+        Ok(hybrid::fuse(embedding, dense, sparse, dense_weight, top_k))
+    }
+
     /// List all documents
     async fn list(&self) -> Result<Vec<Document>>;
 
+    /// Iterate over all documents without collecting them into a `Vec`
+    ///
+    /// Documents are cloned lazily as the stream is polled, so exports,
+    /// migrations and re-embedding jobs can walk the whole storage with
+    /// bounded memory. Ordering is unspecified.
+    fn iter(&self) -> DocumentStream<'_>;
+
     /// List documents for a specific user
     async fn list_by_user(&self, user_id: &str) -> Result<Vec<Document>>;
 
+    /// List documents matching a filter
+    async fn list_filtered(&self, filter: &DocumentFilter) -> Result<Vec<Document>> {
+        Ok(self
+            .list()
+            .await?
+            .into_iter()
+            .filter(|d| filter.matches(d))
+            .collect())
+    }
+
+    /// List documents carrying all of the given tags
+    async fn list_by_tags(&self, tags: &[String]) -> Result<Vec<Document>> {
+        let filter = DocumentFilter::new().with_tags(tags.iter().cloned());
+        self.list_filtered(&filter).await
+    }
+
     /// Get the number of documents
     async fn count(&self) -> usize;
 
@@ -75,4 +355,22 @@ pub trait Storage: Send + Sync {
 
     /// Get storage statistics
     async fn stats(&self) -> StorageStats;
+
+    /// Re-embed every document with a different model
+    ///
+    /// All new embeddings are computed into an in-memory copy before
+    /// anything is written, so an embedding failure leaves the storage
+    /// untouched. The storage is then cleared and repopulated, which resets
+    /// its dimension and model. This suits in-memory backends only: the
+    /// file, disk and Lance backends override it to stream into a staging
+    /// copy on disk and swap that in, never clearing the live data.
+    /// Returns the number of re-embedded documents.
+    async fn reembed(&mut self, embedder: &dyn Embedder) -> Result<usize> {
+        let mut staging = MemoryStorage::new();
+        let count = reembed_into(&*self, &mut staging, embedder).await?;
+
+        self.clear().await?;
+        self.add_batch(staging.into_documents()).await?;
+        Ok(count)
+    }
 }
@@ -0,0 +1,72 @@
+//! Replacing a storage directory with a rebuilt copy
+//!
+//! Re-embedding writes a complete new copy next to the live directory and
+//! then swaps it in with two renames. [`finish_swap`] completes a swap that
+//! a crash interrupted, so opening the storage always finds either the old
+//! or the new contents.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+use crate::error::{Result, StorageError};
+
+/// Suffix of the sibling directory a re-embedded copy is written to
+pub(crate) const STAGING_SUFFIX: &str = "reembed";
+/// Suffix the old directory is renamed to while the copy is swapped in
+const BACKUP_SUFFIX: &str = "previous";
+
+/// `dir` with `suffix` appended to its name
+pub(crate) fn sibling(dir: &Path, suffix: &str) -> Result<PathBuf> {
+    let name = dir.file_name().ok_or_else(|| {
+        StorageError::InvalidOperation(format!("Storage directory {:?} has no name", dir))
+    })?;
+    let mut name = name.to_os_string();
+    name.push(".");
+    name.push(suffix);
+    Ok(dir.with_file_name(name))
+}
+
+/// Replace `dir` with `staging_dir`
+pub(crate) fn swap_in(dir: &Path, staging_dir: &Path) -> Result<()> {
+    let backup_dir = sibling(dir, BACKUP_SUFFIX)?;
+    remove_dir_if_exists(&backup_dir)?;
+
+    fs::rename(dir, &backup_dir)?;
+    if let Err(e) = fs::rename(staging_dir, dir) {
+        fs::rename(&backup_dir, dir)?;
+        return Err(e.into());
+    }
+    remove_dir_if_exists(&backup_dir)
+}
+
+/// Complete a directory swap interrupted by a crash during re-embedding
+///
+/// If the old directory was moved aside but the new one never took its
+/// place, the old one is restored; if both are present, the old one is
+/// dropped.
+pub(crate) fn finish_swap(dir: &Path) -> Result<()> {
+    let dir = fs::canonicalize(dir).unwrap_or_else(|_| dir.to_path_buf());
+    let Ok(backup_dir) = sibling(&dir, BACKUP_SUFFIX) else {
+        return Ok(());
+    };
+    if !backup_dir.exists() {
+        return Ok(());
+    }
+
+    if dir.exists() {
+        remove_dir_if_exists(&backup_dir)
+    } else {
+        warn!("Restoring {:?} after an interrupted re-embedding", dir);
+        fs::rename(&backup_dir, &dir)?;
+        Ok(())
+    }
+}
+
+pub(crate) fn remove_dir_if_exists(path: &Path) -> Result<()> {
+    match fs::remove_dir_all(path) {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
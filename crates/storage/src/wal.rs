@@ -0,0 +1,205 @@
+//! Write-ahead log for file-based storage
+//!
+//! Each mutation is appended as a single JSON line before it is applied
+//! in memory. After a successful snapshot save the log is truncated. On
+//! startup, any entries left in the log are replayed on top of the last
+//! snapshot so unsaved mutations survive a crash.
+
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tokio::fs::{self, File, OpenOptions};
+use tokio::io::AsyncWriteExt;
+use tokio::sync::Mutex;
+use tracing::warn;
+
+use neuro_core::Document;
+use crate::error::Result;
+
+/// A single logged mutation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "op", rename_all = "snake_case")]
+pub(crate) enum WalEntry {
+    /// A document was added
+    Add { document: Box<Document> },
+    /// Documents were deleted
+    Delete { ids: Vec<String> },
+    /// All documents were removed
+    Clear,
+}
+
+/// Append-only log stored next to the snapshot file
+#[derive(Debug)]
+pub(crate) struct WriteAheadLog {
+    path: PathBuf,
+    /// Append handle, opened on first use and dropped when the log is removed
+    file: Mutex<Option<File>>,
+}
+
+impl WriteAheadLog {
+    /// Create a log for the given snapshot path (`<snapshot>.wal`)
+    pub fn for_snapshot(snapshot: &Path) -> Self {
+        let mut path = snapshot.as_os_str().to_owned();
+        path.push(".wal");
+        Self {
+            path: path.into(),
+            file: Mutex::new(None),
+        }
+    }
+
+    /// Get the log file path
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Append an entry and sync it to disk
+    pub async fn append(&self, entry: &WalEntry) -> Result<()> {
+        let mut line = serde_json::to_vec(entry)?;
+        line.push(b'\n');
+
+        let mut handle = self.file.lock().await;
+        let file = match handle.as_mut() {
+            Some(file) => file,
+            None => handle.insert(
+                OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(&self.path)
+                    .await?,
+            ),
+        };
+
+        let written = async {
+            file.write_all(&line).await?;
+            file.sync_data().await
+        }
+        .await;
+        if written.is_err() {
+            // Reopen on the next append rather than reuse a failed handle
+            *handle = None;
+        }
+        Ok(written?)
+    }
+
+    /// Read all complete entries from the log
+    ///
+    /// A trailing partially written line (e.g. from a crash mid-append) is
+    /// ignored with a warning.
+    pub async fn read_entries(&self) -> Result<Vec<WalEntry>> {
+        if !self.path.exists() {
+            return Ok(Vec::new());
+        }
+
+        let content = fs::read_to_string(&self.path).await?;
+        let mut entries = Vec::new();
+
+        for (line_no, line) in content.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match serde_json::from_str(line) {
+                Ok(entry) => entries.push(entry),
+                Err(e) => {
+                    warn!(
+                        "Ignoring corrupt WAL entry at {:?}:{}: {}",
+                        self.path,
+                        line_no + 1,
+                        e
+                    );
+                    break;
+                }
+            }
+        }
+
+        Ok(entries)
+    }
+
+    /// Remove all entries from the log
+    pub async fn truncate(&self) -> Result<()> {
+        *self.file.lock().await = None;
+        remove_log(fs::remove_file(&self.path).await)
+    }
+
+    /// Synchronous [`truncate`](Self::truncate) for use in `Drop`
+    ///
+    /// Callers must not append concurrently; the open handle is only
+    /// released if it is not in use.
+    pub fn truncate_blocking(&self) -> Result<()> {
+        if let Ok(mut file) = self.file.try_lock() {
+            *file = None;
+        }
+        remove_log(std::fs::remove_file(&self.path))
+    }
+}
+
+/// Treat an already missing log as removed
+fn remove_log(result: std::io::Result<()>) -> Result<()> {
+    match result {
+        Ok(()) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::tempdir;
+
+    #[tokio::test]
+    async fn test_append_and_read() {
+        let dir = tempdir().unwrap();
+        let wal = WriteAheadLog::for_snapshot(&dir.path().join("storage.json"));
+        assert!(wal.path().ends_with("storage.json.wal"));
+
+        wal.append(&WalEntry::Add {
+            document: Box::new(Document::with_id("doc1", "Hello")),
+        })
+        .await
+        .unwrap();
+        wal.append(&WalEntry::Delete {
+            ids: vec!["doc1".to_string()],
+        })
+        .await
+        .unwrap();
+
+        let entries = wal.read_entries().await.unwrap();
+        assert_eq!(entries.len(), 2);
+        assert!(matches!(entries[0], WalEntry::Add { .. }));
+
+        wal.truncate().await.unwrap();
+        assert!(wal.read_entries().await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_append_after_truncate_reopens_log() {
+        let dir = tempdir().unwrap();
+        let wal = WriteAheadLog::for_snapshot(&dir.path().join("storage.json"));
+
+        wal.append(&WalEntry::Clear).await.unwrap();
+        wal.truncate().await.unwrap();
+        wal.append(&WalEntry::Clear).await.unwrap();
+        assert_eq!(wal.read_entries().await.unwrap().len(), 1);
+
+        wal.truncate_blocking().unwrap();
+        assert!(!wal.path().exists());
+        wal.append(&WalEntry::Clear).await.unwrap();
+        assert_eq!(wal.read_entries().await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_partial_trailing_entry_ignored() {
+        let dir = tempdir().unwrap();
+        let wal = WriteAheadLog::for_snapshot(&dir.path().join("storage.json"));
+
+        wal.append(&WalEntry::Clear).await.unwrap();
+        let mut file = OpenOptions::new()
+            .append(true)
+            .open(wal.path())
+            .await
+            .unwrap();
+        file.write_all(b"{\"op\":\"add\",\"docu").await.unwrap();
+
+        let entries = wal.read_entries().await.unwrap();
+        assert_eq!(entries.len(), 1);
+    }
+}